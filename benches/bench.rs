@@ -2,8 +2,11 @@
 
 use criterion::{criterion_group, criterion_main, Criterion};
 use icu_locid::locale;
+use spreadsheet_ods::defaultstyles::DefaultFormat;
+use spreadsheet_ods::style::units::WrapOption;
+use spreadsheet_ods::style::CellStyle;
 use spreadsheet_ods::{
-    read_ods, write_ods_buf, write_ods_buf_uncompressed, OdsError, Sheet, WorkBook,
+    read_ods, read_ods_buf, write_ods_buf, write_ods_buf_uncompressed, OdsError, Sheet, WorkBook,
 };
 
 fn read_orders() -> Result<(), OdsError> {
@@ -48,6 +51,40 @@ fn create_wb(rows: u32, cols: u32) -> Result<WorkBook, OdsError> {
     Ok(wb)
 }
 
+fn create_styles_wb(styles: u32) -> Result<WorkBook, OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.locale_settings(locale!("en_US"));
+    let mut sh = Sheet::new("1");
+
+    for i in 0..styles {
+        let mut st = CellStyle::new(format!("s{}", i), &DefaultFormat::number());
+        st.set_wrap_option(WrapOption::Wrap);
+        st.set_print_content(true);
+        let st = wb.add_cellstyle(st);
+        sh.set_styled_value(i, 0, i, &st);
+    }
+
+    wb.push_sheet(sh);
+
+    Ok(wb)
+}
+
+fn create_numbers_wb(rows: u32, cols: u32) -> Result<WorkBook, OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.locale_settings(locale!("en_US"));
+    let mut sh = Sheet::new("1");
+
+    for r in 0..rows {
+        for c in 0..cols {
+            sh.set_value(r, c, (r * cols + c) as f64 * 0.25);
+        }
+    }
+
+    wb.push_sheet(sh);
+
+    Ok(wb)
+}
+
 fn write_wb<'a>(wb: &'a mut WorkBook) -> impl FnMut() -> Result<(), OdsError> + 'a {
     move || {
         let buf = write_ods_buf_uncompressed(wb, Vec::new())?;
@@ -59,6 +96,22 @@ fn criterion_read(c: &mut Criterion) {
     c.bench_function("read", |b| b.iter(|| read_orders()));
 }
 
+fn criterion_read_cells(c: &mut Criterion) {
+    let mut wb = create_wb(100, 400).expect("create_wb");
+    let buf = write_ods_buf(&mut wb, Vec::new()).expect("write_ods_buf");
+    c.bench_function("read_cells", |b| {
+        b.iter(|| read_ods_buf(&buf).expect("read_ods_buf"))
+    });
+}
+
+fn criterion_read_styles(c: &mut Criterion) {
+    let mut wb = create_styles_wb(2000).expect("create_styles_wb");
+    let buf = write_ods_buf(&mut wb, Vec::new()).expect("write_ods_buf");
+    c.bench_function("read_styles", |b| {
+        b.iter(|| read_ods_buf(&buf).expect("read_ods_buf"))
+    });
+}
+
 fn criterion_write(c: &mut Criterion) {
     c.bench_function("write", |b| {
         b.iter(|| {
@@ -68,7 +121,28 @@ fn criterion_write(c: &mut Criterion) {
     });
 }
 
+fn criterion_write_numbers(c: &mut Criterion) {
+    // 1M unstyled numbers.
+    let mut wb = create_numbers_wb(1000, 1000).expect("create_numbers_wb");
+    let mut group = c.benchmark_group("write_numbers");
+    group.sample_size(10);
+    group.bench_function("uncompressed", |b| {
+        b.iter(|| write_ods_buf_uncompressed(&mut wb, Vec::new()).expect("write_ods_buf"))
+    });
+    group.bench_function("compressed", |b| {
+        b.iter(|| write_ods_buf(&mut wb, Vec::new()).expect("write_ods_buf"))
+    });
+    group.finish();
+}
+
 ///
-criterion_group!(benches, criterion_read, criterion_write);
+criterion_group!(
+    benches,
+    criterion_read,
+    criterion_read_cells,
+    criterion_read_styles,
+    criterion_write,
+    criterion_write_numbers
+);
 ///
 criterion_main!(benches);
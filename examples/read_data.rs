@@ -70,6 +70,7 @@ fn read_only() -> OdsResult<()> {
                 spreadsheet_ods::Value::Text(v) => println!("({},{}) = text {}", r, c, v),
                 spreadsheet_ods::Value::TextXml(v) => println!("({},{}) = xml {:?}", r, c, v),
                 spreadsheet_ods::Value::DateTime(v) => println!("({},{}) = date {}", r, c, v),
+                spreadsheet_ods::Value::DateTimeTz(v) => println!("({},{}) = date {}", r, c, v),
                 spreadsheet_ods::Value::TimeDuration(v) => {
                     println!("({},{}) = duration {}", r, c, v)
                 }
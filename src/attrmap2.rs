@@ -74,6 +74,13 @@ impl AttrMap2 {
         self.values.push(value.into().into_boxed_str());
     }
 
+    /// Adds an attribute with an already interned name.
+    #[inline]
+    pub(crate) fn push_attr_atom(&mut self, name: DefaultAtom, value: Box<str>) {
+        self.keys.push(name);
+        self.values.push(value);
+    }
+
     #[inline(always)]
     fn find_idx(&self, test: &DefaultAtom) -> Option<usize> {
         self.keys
@@ -128,6 +135,19 @@ impl AttrMap2 {
     pub fn len(&self) -> usize {
         self.keys.len()
     }
+
+    /// Replaces values in place. The function gets name and value and
+    /// returns the new value, or None to keep the current one.
+    pub(crate) fn map_values<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &str) -> Option<String>,
+    {
+        for (k, v) in self.keys.iter().zip(self.values.iter_mut()) {
+            if let Some(new) = f(k, v) {
+                *v = new.into_boxed_str();
+            }
+        }
+    }
 }
 
 /// Iterator for an AttrMap.
@@ -1,7 +1,7 @@
 use crate::draw::{Annotation, DrawFrame};
 use crate::validation::ValidationRef;
 use crate::value_::Value;
-use crate::CellStyleRef;
+use crate::{CellStyleRef, OdsError};
 use get_size::GetSize;
 use get_size_derive::GetSize;
 use std::fmt::{Display, Formatter};
@@ -325,6 +325,13 @@ impl<'a> CellContentRef<'a> {
         self.formula
     }
 
+    /// Returns the last calculated result of the formula.
+    /// None if there is no formula.
+    #[inline]
+    pub fn cached_value(&self) -> Option<&'a Value> {
+        self.formula.map(|_| self.value)
+    }
+
     /// Returns the cell style.
     #[inline]
     pub fn style(&self) -> Option<&'a CellStyleRef> {
@@ -425,6 +432,12 @@ impl CellContent {
         Default::default()
     }
 
+    /// Starts a builder for a CellContent.
+    #[inline]
+    pub fn builder() -> CellContentBuilder {
+        CellContentBuilder::new()
+    }
+
     /// Transform to CellData
     pub(crate) fn into_celldata(mut self) -> CellData {
         let extra = self.into_celldata_ext();
@@ -476,6 +489,13 @@ impl CellContent {
         self.formula.as_ref()
     }
 
+    /// Returns the last calculated result of the formula.
+    /// None if there is no formula.
+    #[inline]
+    pub fn cached_value(&self) -> Option<&Value> {
+        self.formula.as_ref().map(|_| &self.value)
+    }
+
     /// Sets the formula.
     #[inline]
     pub fn set_formula<V: Into<String>>(&mut self, formula: V) {
@@ -514,6 +534,16 @@ impl CellContent {
         self.repeat = repeat;
     }
 
+    /// Sets the repeat count for the cell. Fails if the repeat is 0.
+    #[inline]
+    pub fn try_set_repeat(&mut self, repeat: u32) -> Result<(), OdsError> {
+        if repeat == 0 {
+            return Err(OdsError::Ods("Cell repeat 0".to_string()));
+        }
+        self.repeat = repeat;
+        Ok(())
+    }
+
     /// Returns the repeat count for the cell.
     #[inline]
     pub fn get_repeat(&mut self) -> u32 {
@@ -546,6 +576,16 @@ impl CellContent {
         self.span.row_span = rows;
     }
 
+    /// Sets the row span of this cell. Fails if the span is 0.
+    #[inline]
+    pub fn try_set_row_span(&mut self, rows: u32) -> Result<(), OdsError> {
+        if rows == 0 {
+            return Err(OdsError::Ods("Row span 0".to_string()));
+        }
+        self.span.row_span = rows;
+        Ok(())
+    }
+
     /// Returns the row span.
     #[inline]
     pub fn row_span(&self) -> u32 {
@@ -560,6 +600,16 @@ impl CellContent {
         self.span.col_span = cols;
     }
 
+    /// Sets the column span of this cell. Fails if the span is 0.
+    #[inline]
+    pub fn try_set_col_span(&mut self, cols: u32) -> Result<(), OdsError> {
+        if cols == 0 {
+            return Err(OdsError::Ods("Col span 0".to_string()));
+        }
+        self.span.col_span = cols;
+        Ok(())
+    }
+
     /// Returns the col span.
     #[inline]
     pub fn col_span(&self) -> u32 {
@@ -624,3 +674,103 @@ impl CellContent {
         &self.draw_frames
     }
 }
+
+/// Assembles a CellContent.
+///
+/// ```
+/// use spreadsheet_ods::{CellContent, CellStyleRef, Sheet};
+///
+/// let cell = CellContent::builder()
+///     .value(42)
+///     .formula("of:=6*7")
+///     .style(&CellStyleRef::from("bold"))
+///     .span(2, 3)
+///     .build();
+///
+/// let mut sheet = Sheet::new("one");
+/// sheet.add_cell(0, 0, cell);
+/// assert_eq!(sheet.col_span(0, 0), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CellContentBuilder {
+    cell: CellContent,
+}
+
+impl Default for CellContentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CellContentBuilder {
+    /// Empty cell with repeat 1.
+    pub fn new() -> Self {
+        Self {
+            cell: CellContent {
+                repeat: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Value.
+    pub fn value<V: Into<Value>>(mut self, value: V) -> Self {
+        self.cell.value = value.into();
+        self
+    }
+
+    /// Formula.
+    pub fn formula<S: Into<String>>(mut self, formula: S) -> Self {
+        self.cell.formula = Some(formula.into());
+        self
+    }
+
+    /// Cell style.
+    pub fn style(mut self, style: &CellStyleRef) -> Self {
+        self.cell.style = Some(style.clone());
+        self
+    }
+
+    /// Repeat count. Must be > 0.
+    pub fn repeat(mut self, repeat: u32) -> Self {
+        self.cell.set_repeat(repeat);
+        self
+    }
+
+    /// Validation.
+    pub fn validation(mut self, validation: &ValidationRef) -> Self {
+        self.cell.validation_name = Some(validation.clone());
+        self
+    }
+
+    /// Row and column span.
+    pub fn span(mut self, rows: u32, cols: u32) -> Self {
+        self.cell.set_row_span(rows);
+        self.cell.set_col_span(cols);
+        self
+    }
+
+    /// Row and column span of a matrix formula.
+    pub fn matrix_span(mut self, rows: u32, cols: u32) -> Self {
+        self.cell.set_matrix_row_span(rows);
+        self.cell.set_matrix_col_span(cols);
+        self
+    }
+
+    /// Annotation.
+    pub fn annotation(mut self, annotation: Annotation) -> Self {
+        self.cell.annotation = Some(Box::new(annotation));
+        self
+    }
+
+    /// Adds a draw frame.
+    pub fn draw_frame(mut self, draw_frame: DrawFrame) -> Self {
+        self.cell.draw_frames.push(draw_frame);
+        self
+    }
+
+    /// Returns the CellContent.
+    pub fn build(self) -> CellContent {
+        self.cell
+    }
+}
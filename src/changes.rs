@@ -0,0 +1,307 @@
+//!
+//! Read-only view of the tracked changes of a spreadsheet.
+//!
+//! The table:tracked-changes element is kept as is for writing, this
+//! module only interprets it. Insertions, deletions and cell content
+//! changes are available, other changes are ignored.
+//!
+//! ```
+//! use spreadsheet_ods::changes::ChangeKind;
+//! use spreadsheet_ods::read_ods;
+//!
+//! let book = read_ods("tests/test_changes.ods").unwrap();
+//! if let Some(changes) = book.tracked_changes().unwrap() {
+//!     for change in changes.changes() {
+//!         if let ChangeKind::CellContent { sheet, row, col, .. } = change.kind() {
+//!             println!("{:?} changed {}:{}/{}", change.author(), sheet, row, col);
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::io::parse::{parse_bool, parse_datetime, parse_u32};
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::OdsError;
+use chrono::NaiveDateTime;
+
+/// Type of an insertion or deletion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeType {
+    /// Rows.
+    Row,
+    /// Columns.
+    Column,
+    /// Sheets.
+    Table,
+}
+
+impl TryFrom<&str> for ChangeType {
+    type Error = OdsError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "row" => Ok(ChangeType::Row),
+            "column" => Ok(ChangeType::Column),
+            "table" => Ok(ChangeType::Table),
+            _ => Err(OdsError::Parse("invalid table:type", Some(value.into()))),
+        }
+    }
+}
+
+/// State of a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AcceptanceState {
+    /// Not yet decided.
+    #[default]
+    Pending,
+    /// Accepted.
+    Accepted,
+    /// Rejected.
+    Rejected,
+}
+
+impl TryFrom<&str> for AcceptanceState {
+    type Error = OdsError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "pending" => Ok(AcceptanceState::Pending),
+            "accepted" => Ok(AcceptanceState::Accepted),
+            "rejected" => Ok(AcceptanceState::Rejected),
+            _ => Err(OdsError::Parse(
+                "invalid table:acceptance-state",
+                Some(value.into()),
+            )),
+        }
+    }
+}
+
+/// What was changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    /// Inserted rows, columns or sheets.
+    Insertion {
+        /// Rows, columns or sheets.
+        change_type: ChangeType,
+        /// First inserted row/column/sheet.
+        position: u32,
+        /// Number of inserted rows/columns/sheets.
+        count: u32,
+        /// Sheet index. Not used for inserted sheets.
+        sheet: u32,
+    },
+    /// Deleted rows, columns or sheets.
+    Deletion {
+        /// Rows, columns or sheets.
+        change_type: ChangeType,
+        /// Position of the deletion.
+        position: u32,
+        /// Sheet index. Not used for deleted sheets.
+        sheet: u32,
+    },
+    /// Changed cell content.
+    CellContent {
+        /// Sheet index.
+        sheet: u32,
+        /// Row.
+        row: u32,
+        /// Column.
+        col: u32,
+        /// Text of the cell before the change.
+        previous: Option<String>,
+        /// Formula of the cell before the change.
+        previous_formula: Option<String>,
+    },
+}
+
+/// One tracked change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    id: String,
+    kind: ChangeKind,
+    acceptance_state: AcceptanceState,
+    author: Option<String>,
+    date: Option<NaiveDateTime>,
+    comment: Option<String>,
+}
+
+impl Change {
+    /// Id of the change.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// What was changed.
+    pub fn kind(&self) -> &ChangeKind {
+        &self.kind
+    }
+
+    /// Accepted or rejected.
+    pub fn acceptance_state(&self) -> AcceptanceState {
+        self.acceptance_state
+    }
+
+    /// Author of the change.
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Timestamp of the change.
+    pub fn date(&self) -> Option<NaiveDateTime> {
+        self.date
+    }
+
+    /// Comment for the change.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+/// All tracked changes of a workbook.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackedChanges {
+    track_changes: bool,
+    changes: Vec<Change>,
+}
+
+impl TrackedChanges {
+    /// Change tracking is active.
+    pub fn track_changes(&self) -> bool {
+        self.track_changes
+    }
+
+    /// The changes in document order.
+    pub fn changes(&self) -> &Vec<Change> {
+        &self.changes
+    }
+
+    /// All changes of one author.
+    pub fn changes_by<'a>(&'a self, author: &'a str) -> impl Iterator<Item = &'a Change> {
+        self.changes
+            .iter()
+            .filter(move |v| v.author.as_deref() == Some(author))
+    }
+}
+
+fn tags(tag: &XmlTag) -> impl Iterator<Item = &XmlTag> {
+    tag.content().iter().filter_map(|v| match v {
+        XmlContent::Tag(t) => Some(t),
+        XmlContent::Text(_) => None,
+    })
+}
+
+fn attr_u32(tag: &XmlTag, name: &str, default: u32) -> Result<u32, OdsError> {
+    match tag.get_attr(name) {
+        Some(v) => parse_u32(v.as_bytes()),
+        None => Ok(default),
+    }
+}
+
+// Reads the table:tracked-changes element.
+pub(crate) fn read_tracked_changes(tag: &XmlTag) -> Result<TrackedChanges, OdsError> {
+    let mut tracked = TrackedChanges {
+        track_changes: match tag.get_attr("table:track-changes") {
+            Some(v) => parse_bool(v.as_bytes())?,
+            None => true,
+        },
+        changes: Vec::new(),
+    };
+
+    for t in tags(tag) {
+        let kind = match t.name() {
+            "table:insertion" => ChangeKind::Insertion {
+                change_type: ChangeType::try_from(t.get_attr("table:type").unwrap_or_default())?,
+                position: attr_u32(t, "table:position", 0)?,
+                count: attr_u32(t, "table:count", 1)?,
+                sheet: attr_u32(t, "table:table", 0)?,
+            },
+            "table:deletion" => ChangeKind::Deletion {
+                change_type: ChangeType::try_from(t.get_attr("table:type").unwrap_or_default())?,
+                position: attr_u32(t, "table:position", 0)?,
+                sheet: attr_u32(t, "table:table", 0)?,
+            },
+            "table:cell-content-change" => read_cell_content_change(t)?,
+            _ => continue,
+        };
+
+        let mut change = Change {
+            id: t.get_attr("table:id").unwrap_or_default().to_string(),
+            kind,
+            acceptance_state: match t.get_attr("table:acceptance-state") {
+                Some(v) => AcceptanceState::try_from(v)?,
+                None => AcceptanceState::Pending,
+            },
+            author: None,
+            date: None,
+            comment: None,
+        };
+        if let Some(info) = tags(t).find(|v| v.name() == "office:change-info") {
+            read_change_info(&mut change, info)?;
+        }
+        tracked.changes.push(change);
+    }
+
+    Ok(tracked)
+}
+
+fn read_cell_content_change(tag: &XmlTag) -> Result<ChangeKind, OdsError> {
+    let mut sheet = 0;
+    let mut row = 0;
+    let mut col = 0;
+    let mut previous = None;
+    let mut previous_formula = None;
+
+    for t in tags(tag) {
+        match t.name() {
+            "table:cell-address" => {
+                sheet = attr_u32(t, "table:table", 0)?;
+                row = attr_u32(t, "table:row", 0)?;
+                col = attr_u32(t, "table:column", 0)?;
+            }
+            "table:previous" => {
+                if let Some(cell) = tags(t).find(|v| v.name() == "table:change-track-table-cell") {
+                    previous_formula = cell.get_attr("table:formula").map(|v| v.to_string());
+                    let mut buf = String::new();
+                    for p in tags(cell).filter(|v| v.name() == "text:p") {
+                        if !buf.is_empty() {
+                            buf.push('\n');
+                        }
+                        p.extract_text(&mut buf);
+                    }
+                    if !buf.is_empty() {
+                        previous = Some(buf);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ChangeKind::CellContent {
+        sheet,
+        row,
+        col,
+        previous,
+        previous_formula,
+    })
+}
+
+fn read_change_info(change: &mut Change, tag: &XmlTag) -> Result<(), OdsError> {
+    for t in tags(tag) {
+        let mut buf = String::new();
+        t.extract_text(&mut buf);
+        match t.name() {
+            "dc:creator" => change.author = Some(buf),
+            "dc:date" => change.date = Some(parse_datetime(buf.trim().as_bytes())?),
+            "text:p" => match &mut change.comment {
+                Some(comment) => {
+                    comment.push('\n');
+                    comment.push_str(&buf);
+                }
+                None => change.comment = Some(buf),
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
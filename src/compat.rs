@@ -0,0 +1,70 @@
+//!
+//! Deprecated shims for APIs that have been renamed in 0.21.
+//!
+//! Code written against 0.20 keeps compiling, the deprecation warnings
+//! point to the replacement. These will be removed with the next breaking
+//! release.
+//!
+//! Renamed functions:
+//!
+//! * OdsOptions::use_clone_for_repeat() -> OdsOptions::use_clone_for_cells()
+//! * Sheet::used_cols() -> Sheet::col_header_max()
+//! * Sheet::used_rows() -> Sheet::row_header_max()
+//!
+//! Renamed metadata types are available here and in the metadata module
+//! under their old name.
+//!
+//! Not covered are the changes in signature or semantics:
+//!
+//! * OdsOptions::use_repeat_for_empty() is gone. ignore_empty_cells() may
+//!   be an alternative.
+//! * Sheet::header_rows() and Sheet::header_cols() return a Header.
+//! * &String parameters are &str now.
+//!
+
+use crate::metadata::{
+    MetaAutoReload, MetaDocumentStatistics, MetaHyperlinkBehaviour, MetaTemplate, MetaUserDefined,
+};
+use crate::{OdsOptions, Sheet};
+
+/// Renamed to MetaTemplate.
+#[deprecated(since = "0.21.0", note = "use metadata::MetaTemplate")]
+pub type Template = MetaTemplate;
+
+/// Renamed to MetaAutoReload.
+#[deprecated(since = "0.21.0", note = "use metadata::MetaAutoReload")]
+pub type AutoReload = MetaAutoReload;
+
+/// Renamed to MetaHyperlinkBehaviour.
+#[deprecated(since = "0.21.0", note = "use metadata::MetaHyperlinkBehaviour")]
+pub type HyperlinkBehaviour = MetaHyperlinkBehaviour;
+
+/// Renamed to MetaDocumentStatistics.
+#[deprecated(since = "0.21.0", note = "use metadata::MetaDocumentStatistics")]
+pub type DocumentStatistics = MetaDocumentStatistics;
+
+/// Renamed to MetaUserDefined.
+#[deprecated(since = "0.21.0", note = "use metadata::MetaUserDefined")]
+pub type UserDefined = MetaUserDefined;
+
+impl OdsOptions {
+    /// Renamed to use_clone_for_cells().
+    #[deprecated(since = "0.21.0", note = "use use_clone_for_cells()")]
+    pub fn use_clone_for_repeat(self) -> Self {
+        self.use_clone_for_cells()
+    }
+}
+
+impl Sheet {
+    /// Renamed to col_header_max().
+    #[deprecated(since = "0.21.0", note = "use col_header_max()")]
+    pub fn used_cols(&self) -> u32 {
+        self.col_header_max()
+    }
+
+    /// Renamed to row_header_max().
+    #[deprecated(since = "0.21.0", note = "use row_header_max()")]
+    pub fn used_rows(&self) -> u32 {
+        self.row_header_max()
+    }
+}
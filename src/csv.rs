@@ -0,0 +1,154 @@
+//!
+//! Exports a range of a sheet as csv or as markdown table.
+//!
+//! The values are formatted like the html export does. Csv and markdown
+//! can't span cells, so merged cells are either repeated in or blanked
+//! from the cells they cover, see [MergedCells].
+//!
+//! ```
+//! use spreadsheet_ods::{CellRange, Sheet, WorkBook};
+//! use spreadsheet_ods::csv::CsvOptions;
+//! use spreadsheet_ods::sheet::MergedCells;
+//!
+//! let mut book = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("report");
+//! sheet.set_value(0, 0, "total");
+//! sheet.set_col_span(0, 0, 2);
+//! sheet.set_value(1, 0, 1);
+//! sheet.set_value(1, 1, 2);
+//! book.push_sheet(sheet);
+//!
+//! let range = CellRange::local(0, 0, 1, 1);
+//! let csv = book.sheet(0).to_csv(&book, &range, CsvOptions::default());
+//! assert_eq!(csv, "total,\n1,2\n");
+//!
+//! let options = CsvOptions::default().merged(MergedCells::Repeat);
+//! let csv = book.sheet(0).to_csv(&book, &range, options);
+//! assert_eq!(csv, "total,total\n1,2\n");
+//! ```
+
+use crate::html::{cell_style, merged_origins, value_text};
+use crate::sheet::MergedCells;
+use crate::{CellRange, Sheet, WorkBook};
+
+/// Options for the csv and markdown export.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    separator: char,
+    formats: bool,
+    merged: MergedCells,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            separator: ',',
+            formats: true,
+            merged: MergedCells::Blank,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Field separator for csv. Default is ','. Not used for markdown.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Format the values with their value format. Default is true.
+    /// Otherwise the plain values are written.
+    pub fn formats(mut self, formats: bool) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// How merged cells are written. Default is MergedCells::Blank.
+    /// MergedCells::Span is written like Blank.
+    pub fn merged(mut self, merged: MergedCells) -> Self {
+        self.merged = merged;
+        self
+    }
+}
+
+impl Sheet {
+    /// Exports the range as csv. The book is needed for the value formats.
+    ///
+    /// Fields that contain the separator, quotes or line breaks are
+    /// quoted. Each row ends with a '\n'.
+    pub fn to_csv(&self, book: &WorkBook, range: &CellRange, options: CsvOptions) -> String {
+        let mut buf = String::new();
+        for fields in self.text_rows(book, range, &options) {
+            for (idx, field) in fields.iter().enumerate() {
+                if idx > 0 {
+                    buf.push(options.separator);
+                }
+                if field.contains([options.separator, '"', '\n', '\r']) {
+                    buf.push('"');
+                    buf.push_str(&field.replace('"', "\"\""));
+                    buf.push('"');
+                } else {
+                    buf.push_str(field);
+                }
+            }
+            buf.push('\n');
+        }
+        buf
+    }
+
+    /// Exports the range as markdown table. The first row of the range
+    /// is the header. The book is needed for the value formats.
+    pub fn to_markdown(&self, book: &WorkBook, range: &CellRange, options: CsvOptions) -> String {
+        let mut buf = String::new();
+        for (row, fields) in self.text_rows(book, range, &options).iter().enumerate() {
+            buf.push('|');
+            for field in fields {
+                buf.push(' ');
+                buf.push_str(&field.replace('|', "\\|").replace('\n', "<br>"));
+                buf.push_str(" |");
+            }
+            buf.push('\n');
+            if row == 0 {
+                buf.push('|');
+                for _ in fields {
+                    buf.push_str(" --- |");
+                }
+                buf.push('\n');
+            }
+        }
+        buf
+    }
+
+    // The text of all cells in the range, row by row.
+    fn text_rows(
+        &self,
+        book: &WorkBook,
+        range: &CellRange,
+        options: &CsvOptions,
+    ) -> Vec<Vec<String>> {
+        let origins = merged_origins(self, range);
+
+        let mut rows = Vec::new();
+        for row in range.row()..=range.to_row() {
+            let mut fields = Vec::new();
+            for col in range.col()..=range.to_col() {
+                let (o_row, o_col) = match origins.get(&(row, col)) {
+                    Some(origin) if options.merged == MergedCells::Repeat => *origin,
+                    Some(_) => {
+                        fields.push(String::new());
+                        continue;
+                    }
+                    None => (row, col),
+                };
+                fields.push(value_text(
+                    book,
+                    self.value(o_row, o_col),
+                    cell_style(book, self, o_row, o_col),
+                    options.formats,
+                ));
+            }
+            rows.push(fields);
+        }
+        rows
+    }
+}
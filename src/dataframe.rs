@@ -0,0 +1,295 @@
+//!
+//! Conversion between sheets and arrow RecordBatches.
+//!
+//! Needs the feature "arrow".
+//!
+//! | arrow                           | Value                        |
+//! |---------------------------------|------------------------------|
+//! | Boolean                         | Boolean                      |
+//! | Int*, UInt*, Float*, Decimal*   | Number                       |
+//! | Utf8, LargeUtf8                 | Text                         |
+//! | Date32, Date64, Timestamp       | DateTime                     |
+//! | Timestamp with timezone         | DateTimeTz                   |
+//! | Duration                        | TimeDuration                 |
+//! | null                            | Empty                        |
+//!
+//! The value formats for dates and durations are the default formats of
+//! the workbook, see WorkBook::new().
+//!
+
+use crate::refs::CCol;
+use crate::{CellRange, OdsError, Sheet, Value};
+use arrow::array::timezone::Tz;
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, DurationMillisecondArray, Float64Array, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{
+    DataType, DurationMicrosecondType, DurationMillisecondType, DurationNanosecondType,
+    DurationSecondType, Field, Float64Type, Schema, TimeUnit, TimestampMillisecondType,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use chrono::Duration;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// Timezone for DateTimeTz values. The offsets can differ per value,
+// the timestamps are all UTC.
+const UTC: &str = "+00:00";
+
+impl Sheet {
+    /// Creates a sheet with the data of the RecordBatch.
+    ///
+    /// The first row contains the field names, the data starts
+    /// with the second row. Nulls are left empty.
+    pub fn from_dataframe<S: Into<String>>(
+        name: S,
+        batch: &RecordBatch,
+    ) -> Result<Sheet, OdsError> {
+        let mut sheet = Sheet::new(name);
+
+        let schema = batch.schema();
+        sheet.set_row_values(0, 0, schema.fields().iter().map(|v| v.name().as_str()));
+
+        let mut cells = Vec::new();
+        for (col, column) in batch.columns().iter().enumerate() {
+            cells.extend(
+                column_values(column)?
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, v)| *v != Value::Empty)
+                    .map(|(row, v)| ((row as u32 + 1, col as u32), v)),
+            );
+        }
+        // Row by row, the order of the cells in the sheet.
+        cells.sort_by_key(|(pos, _)| *pos);
+        sheet.set_values_bulk(cells.into_iter());
+
+        Ok(sheet)
+    }
+
+    /// Converts the range to a RecordBatch.
+    ///
+    /// The first row of the range contains the field names. Empty header
+    /// cells use the column name instead. The type of each field is
+    /// derived from the values: Boolean, Float64, Timestamp(ms), Duration(ms)
+    /// if all values have the same type, Utf8 otherwise. Date-times with a
+    /// timezone become a Timestamp(ms, "+00:00"). Empty cells are nulls.
+    pub fn to_dataframe(&self, range: &CellRange) -> Result<RecordBatch, OdsError> {
+        let mut fields = Vec::new();
+        let mut columns: Vec<ArrayRef> = Vec::new();
+
+        for col in range.col()..=range.to_col() {
+            let name = match value_string(self.value(range.row(), col)) {
+                Some(v) if !v.is_empty() => v,
+                _ => CCol::new(col).to_string(),
+            };
+
+            let values: Vec<&Value> = (range.row() + 1..=range.to_row())
+                .map(|row| self.value(row, col))
+                .collect();
+            let column = column_array(&values);
+
+            fields.push(Field::new(name, column.data_type().clone(), true));
+            columns.push(column);
+        }
+
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(arrow_err)
+    }
+}
+
+fn column_values(column: &ArrayRef) -> Result<Vec<Value>, OdsError> {
+    let values = match column.data_type() {
+        DataType::Null => vec![Value::Empty; column.len()],
+        DataType::Boolean => column
+            .as_boolean()
+            .iter()
+            .map(|v| v.map(Value::Boolean).unwrap_or_default())
+            .collect(),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Decimal128(_, _)
+        | DataType::Decimal256(_, _) => cast(column, &DataType::Float64)
+            .map_err(arrow_err)?
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|v| v.map(Value::Number).unwrap_or_default())
+            .collect(),
+        DataType::Utf8 | DataType::LargeUtf8 => cast(column, &DataType::Utf8)
+            .map_err(arrow_err)?
+            .as_string::<i32>()
+            .iter()
+            .map(|v| v.map(Value::from).unwrap_or_default())
+            .collect(),
+        DataType::Timestamp(_, Some(tz)) => {
+            let column = cast(
+                column,
+                &DataType::Timestamp(TimeUnit::Millisecond, Some(tz.clone())),
+            )
+            .map_err(arrow_err)?;
+            let tz = Tz::from_str(tz).map_err(arrow_err)?;
+            let column = column.as_primitive::<TimestampMillisecondType>();
+            (0..column.len())
+                .map(|i| {
+                    if column.is_valid(i) {
+                        column
+                            .value_as_datetime_with_tz(i, tz)
+                            .map(|v| Value::DateTimeTz(v.fixed_offset()))
+                            .unwrap_or_default()
+                    } else {
+                        Value::Empty
+                    }
+                })
+                .collect()
+        }
+        DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, None) => {
+            let column = cast(column, &DataType::Timestamp(TimeUnit::Millisecond, None))
+                .map_err(arrow_err)?;
+            let column = column.as_primitive::<TimestampMillisecondType>();
+            (0..column.len())
+                .map(|i| {
+                    if column.is_valid(i) {
+                        column
+                            .value_as_datetime(i)
+                            .map(Value::DateTime)
+                            .unwrap_or_default()
+                    } else {
+                        Value::Empty
+                    }
+                })
+                .collect()
+        }
+        DataType::Duration(TimeUnit::Second) => column
+            .as_primitive::<DurationSecondType>()
+            .iter()
+            .map(|v| {
+                v.map(|v| Value::TimeDuration(Duration::seconds(v)))
+                    .unwrap_or_default()
+            })
+            .collect(),
+        DataType::Duration(TimeUnit::Millisecond) => column
+            .as_primitive::<DurationMillisecondType>()
+            .iter()
+            .map(|v| {
+                v.map(|v| Value::TimeDuration(Duration::milliseconds(v)))
+                    .unwrap_or_default()
+            })
+            .collect(),
+        DataType::Duration(TimeUnit::Microsecond) => column
+            .as_primitive::<DurationMicrosecondType>()
+            .iter()
+            .map(|v| {
+                v.map(|v| Value::TimeDuration(Duration::microseconds(v)))
+                    .unwrap_or_default()
+            })
+            .collect(),
+        DataType::Duration(TimeUnit::Nanosecond) => column
+            .as_primitive::<DurationNanosecondType>()
+            .iter()
+            .map(|v| {
+                v.map(|v| Value::TimeDuration(Duration::nanoseconds(v)))
+                    .unwrap_or_default()
+            })
+            .collect(),
+        other => {
+            return Err(OdsError::Ods(format!(
+                "Arrow type {} is not supported",
+                other
+            )))
+        }
+    };
+    Ok(values)
+}
+
+fn arrow_err(err: ArrowError) -> OdsError {
+    OdsError::Ods(format!("Arrow {}", err))
+}
+
+fn column_array(values: &[&Value]) -> ArrayRef {
+    let mut value_type = None;
+    for v in values.iter() {
+        let t = match v {
+            Value::Empty => continue,
+            Value::Boolean(_) => DataType::Boolean,
+            Value::Number(_) | Value::Percentage(_) | Value::Currency(_, _) => DataType::Float64,
+            Value::DateTime(_) => DataType::Timestamp(TimeUnit::Millisecond, None),
+            Value::DateTimeTz(_) => DataType::Timestamp(TimeUnit::Millisecond, Some(UTC.into())),
+            Value::TimeDuration(_) => DataType::Duration(TimeUnit::Millisecond),
+            Value::Text(_) | Value::TextXml(_) => DataType::Utf8,
+        };
+        match &value_type {
+            None => value_type = Some(t),
+            Some(vt) if *vt == t => {}
+            Some(_) => {
+                value_type = Some(DataType::Utf8);
+                break;
+            }
+        }
+    }
+
+    match value_type.unwrap_or(DataType::Utf8) {
+        DataType::Boolean => Arc::new(BooleanArray::from(
+            values
+                .iter()
+                .map(|v| match v {
+                    Value::Boolean(v) => Some(*v),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Float64 => Arc::new(Float64Array::from(
+            values.iter().map(|v| v.as_f64_opt()).collect::<Vec<_>>(),
+        )),
+        DataType::Timestamp(_, None) => Arc::new(TimestampMillisecondArray::from(
+            values
+                .iter()
+                .map(|v| v.as_datetime_opt().map(|v| v.and_utc().timestamp_millis()))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Timestamp(_, Some(_)) => Arc::new(
+            TimestampMillisecondArray::from(
+                values
+                    .iter()
+                    .map(|v| match v {
+                        Value::DateTimeTz(v) => Some(v.timestamp_millis()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .with_timezone(UTC),
+        ),
+        DataType::Duration(_) => Arc::new(DurationMillisecondArray::from(
+            values
+                .iter()
+                .map(|v| v.as_timeduration_opt().map(|v| v.num_milliseconds()))
+                .collect::<Vec<_>>(),
+        )),
+        _ => Arc::new(StringArray::from(
+            values.iter().map(|v| value_string(v)).collect::<Vec<_>>(),
+        )),
+    }
+}
+
+fn value_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Empty => None,
+        Value::Boolean(v) => Some(v.to_string()),
+        Value::Number(v) | Value::Percentage(v) => Some(v.to_string()),
+        Value::Currency(v, c) => Some(format!("{} {}", v, c)),
+        Value::Text(_) | Value::TextXml(_) => Some(value.as_cow_str_or("").to_string()),
+        Value::DateTime(v) => Some(v.to_string()),
+        Value::DateTimeTz(v) => Some(v.to_string()),
+        Value::TimeDuration(v) => Some(v.to_string()),
+    }
+}
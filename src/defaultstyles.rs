@@ -36,6 +36,11 @@ impl DefaultFormat {
         ValueFormatRef::from("percent1")
     }
 
+    /// Default fraction format.
+    pub fn fraction() -> ValueFormatRef {
+        ValueFormatRef::from("fraction1")
+    }
+
     /// Default currency format.
     pub fn currency() -> ValueFormatRef {
         ValueFormatRef::from("currency1")
@@ -60,6 +65,20 @@ impl DefaultFormat {
     pub fn time_interval() -> ValueFormatRef {
         ValueFormatRef::from("interval1")
     }
+
+    /// Default format for the value-type. This is the format used by
+    /// the corresponding DefaultStyle::for_value_type().
+    pub fn for_value_type(value_type: ValueType) -> Option<ValueFormatRef> {
+        match value_type {
+            ValueType::Boolean => Some(Self::bool()),
+            ValueType::Number => Some(Self::number()),
+            ValueType::Percentage => Some(Self::percent()),
+            ValueType::Currency => Some(Self::currency()),
+            ValueType::DateTime => Some(Self::date()),
+            ValueType::TimeDuration => Some(Self::time_interval()),
+            ValueType::Empty | ValueType::Text | ValueType::TextXml => None,
+        }
+    }
 }
 
 ///
@@ -85,6 +104,11 @@ impl DefaultStyle {
         CellStyleRef::from("default-percent")
     }
 
+    /// Default fraction style. Sheet::set_fraction() creates it on demand.
+    pub fn fraction() -> CellStyleRef {
+        CellStyleRef::from("default-fraction")
+    }
+
     /// Default currency style.
     pub fn currency() -> CellStyleRef {
         CellStyleRef::from("default-currency")
@@ -109,6 +133,20 @@ impl DefaultStyle {
     pub fn time_interval() -> CellStyleRef {
         CellStyleRef::from("default-interval")
     }
+
+    /// Default style for the value-type, as registered with
+    /// WorkBook::add_def_style().
+    pub fn for_value_type(value_type: ValueType) -> Option<CellStyleRef> {
+        match value_type {
+            ValueType::Boolean => Some(Self::bool()),
+            ValueType::Number => Some(Self::number()),
+            ValueType::Percentage => Some(Self::percent()),
+            ValueType::Currency => Some(Self::currency()),
+            ValueType::DateTime => Some(Self::date()),
+            ValueType::TimeDuration => Some(Self::time_interval()),
+            ValueType::Empty | ValueType::Text | ValueType::TextXml => None,
+        }
+    }
 }
 
 /// Replaced with WorkBook::locale_settings() or WorkBook::new(l: Locale).
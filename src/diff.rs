@@ -0,0 +1,279 @@
+//!
+//! Compares two workbooks.
+//!
+//! Sheets are matched by name, cells by position. Cells compare the
+//! values, formulas, spans, annotations and the cell style by value.
+//! The cell styles themselves are matched by name.
+//!
+//! Repeat counts of cells and rows are resolved, each repeated position
+//! counts as a cell of its own.
+//!
+//! ```
+//! use spreadsheet_ods::{diff, Sheet, WorkBook};
+//! use spreadsheet_ods::diff::DiffKind;
+//!
+//! let mut a = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("report");
+//! sheet.set_value(0, 0, "total");
+//! sheet.set_value(0, 1, 100);
+//! a.push_sheet(sheet);
+//!
+//! let mut b = a.clone();
+//! b.sheet_mut(0).set_value(0, 1, 101);
+//!
+//! let d = diff(&a, &b);
+//! assert_eq!(d.cells().len(), 1);
+//! assert_eq!(d.cells()[0].kind(), DiffKind::Changed);
+//! assert_eq!((d.cells()[0].row(), d.cells()[0].col()), (0, 1));
+//! ```
+
+use crate::digest::{cell_blocks, cellstyle_key, sheet_props_key, CellBlock};
+use crate::{CellContent, Sheet, WorkBook};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Kind of difference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only in the second workbook.
+    Added,
+    /// Only in the first workbook.
+    Removed,
+    /// In both, but different.
+    Changed,
+}
+
+/// Difference of a sheet. Changed means the sheet properties like
+/// visibility, table style, header rows/columns or print ranges differ.
+/// The cells are listed separately.
+#[derive(Debug, Clone)]
+pub struct SheetDiff {
+    name: String,
+    kind: DiffKind,
+}
+
+impl SheetDiff {
+    /// Sheet name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Added, removed or changed.
+    pub fn kind(&self) -> DiffKind {
+        self.kind
+    }
+}
+
+/// Difference of a single cell.
+#[derive(Debug, Clone)]
+pub struct CellDiff {
+    sheet: String,
+    row: u32,
+    col: u32,
+    kind: DiffKind,
+    old: Option<CellContent>,
+    new: Option<CellContent>,
+}
+
+impl CellDiff {
+    /// Sheet name.
+    pub fn sheet(&self) -> &str {
+        &self.sheet
+    }
+
+    /// Row.
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Column.
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Added, removed or changed.
+    pub fn kind(&self) -> DiffKind {
+        self.kind
+    }
+
+    /// Cell in the first workbook.
+    pub fn old_cell(&self) -> Option<&CellContent> {
+        self.old.as_ref()
+    }
+
+    /// Cell in the second workbook.
+    pub fn new_cell(&self) -> Option<&CellContent> {
+        self.new.as_ref()
+    }
+}
+
+/// Difference of a cell style.
+#[derive(Debug, Clone)]
+pub struct StyleDiff {
+    name: String,
+    kind: DiffKind,
+}
+
+impl StyleDiff {
+    /// Style name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Added, removed or changed.
+    pub fn kind(&self) -> DiffKind {
+        self.kind
+    }
+}
+
+/// All differences between two workbooks.
+#[derive(Debug, Clone, Default)]
+pub struct WorkBookDiff {
+    sheets: Vec<SheetDiff>,
+    cells: Vec<CellDiff>,
+    styles: Vec<StyleDiff>,
+}
+
+impl WorkBookDiff {
+    /// No differences at all.
+    pub fn is_empty(&self) -> bool {
+        self.sheets.is_empty() && self.cells.is_empty() && self.styles.is_empty()
+    }
+
+    /// Added, removed and changed sheets.
+    pub fn sheets(&self) -> &Vec<SheetDiff> {
+        &self.sheets
+    }
+
+    /// Added, removed and changed cells. Per sheet sorted by row and column.
+    pub fn cells(&self) -> &Vec<CellDiff> {
+        &self.cells
+    }
+
+    /// Added, removed and changed cell styles. Sorted by name.
+    pub fn styles(&self) -> &Vec<StyleDiff> {
+        &self.styles
+    }
+}
+
+/// Compares two workbooks.
+pub fn diff(a: &WorkBook, b: &WorkBook) -> WorkBookDiff {
+    let mut result = WorkBookDiff::default();
+
+    for sheet_a in a.iter_sheets() {
+        match b.iter_sheets().find(|v| v.name() == sheet_a.name()) {
+            Some(sheet_b) => {
+                if sheet_props_key(a, sheet_a) != sheet_props_key(b, sheet_b) {
+                    result.sheets.push(SheetDiff {
+                        name: sheet_a.name().clone(),
+                        kind: DiffKind::Changed,
+                    });
+                }
+                diff_cells(&mut result, a, sheet_a, b, sheet_b);
+            }
+            None => {
+                result.sheets.push(SheetDiff {
+                    name: sheet_a.name().clone(),
+                    kind: DiffKind::Removed,
+                });
+            }
+        }
+    }
+    for sheet_b in b.iter_sheets() {
+        if !a.iter_sheets().any(|v| v.name() == sheet_b.name()) {
+            result.sheets.push(SheetDiff {
+                name: sheet_b.name().clone(),
+                kind: DiffKind::Added,
+            });
+        }
+    }
+
+    let styles_a: BTreeMap<_, _> = a.iter_cellstyles().map(|v| (v.name(), v)).collect();
+    let styles_b: BTreeMap<_, _> = b.iter_cellstyles().map(|v| (v.name(), v)).collect();
+    let names: BTreeSet<_> = styles_a.keys().chain(styles_b.keys()).collect();
+    for name in names {
+        let kind = match (styles_a.get(name), styles_b.get(name)) {
+            (Some(sa), Some(sb)) => {
+                if cellstyle_key(a, sa) == cellstyle_key(b, sb) {
+                    continue;
+                }
+                DiffKind::Changed
+            }
+            (Some(_), None) => DiffKind::Removed,
+            (None, Some(_)) => DiffKind::Added,
+            (None, None) => unreachable!(),
+        };
+        result.styles.push(StyleDiff {
+            name: name.to_string(),
+            kind,
+        });
+    }
+
+    result
+}
+
+// Cell of a repeat as it would be written on its own.
+fn single_cell(block: &CellBlock<'_>) -> CellContent {
+    let mut cell = block.cell.cloned_cell_content();
+    cell.repeat = 1;
+    cell
+}
+
+// Expands the blocks to single positions.
+fn positions<'a>(
+    blocks: impl Iterator<Item = &'a CellBlock<'a>>,
+) -> BTreeMap<(u32, u32), &'a CellBlock<'a>> {
+    let mut pos = BTreeMap::new();
+    for block in blocks {
+        for row in block.row..block.row + block.rows {
+            for col in block.col..block.col + block.cols {
+                pos.insert((row, col), block);
+            }
+        }
+    }
+    pos
+}
+
+fn diff_cells(
+    result: &mut WorkBookDiff,
+    a: &WorkBook,
+    sheet_a: &Sheet,
+    b: &WorkBook,
+    sheet_b: &Sheet,
+) {
+    let blocks_a = cell_blocks(a, sheet_a);
+    let blocks_b = cell_blocks(b, sheet_b);
+
+    // equal blocks are not expanded.
+    let extent = |v: &CellBlock<'_>| (v.row, v.col, v.rows, v.cols);
+    let equal = |v: &CellBlock<'_>, w: &[CellBlock<'_>]| {
+        w.binary_search_by_key(&(v.row, v.col), |w| (w.row, w.col))
+            .is_ok_and(|i| extent(&w[i]) == extent(v) && w[i].key == v.key)
+    };
+    let pos_a = positions(blocks_a.iter().filter(|v| !equal(v, &blocks_b)));
+    let pos_b = positions(blocks_b.iter().filter(|v| !equal(v, &blocks_a)));
+
+    let pos: BTreeSet<_> = pos_a.keys().chain(pos_b.keys()).collect();
+    for (row, col) in pos {
+        let cell_a = pos_a.get(&(*row, *col));
+        let cell_b = pos_b.get(&(*row, *col));
+        let kind = match (cell_a, cell_b) {
+            (Some(ca), Some(cb)) => {
+                if ca.key == cb.key {
+                    continue;
+                }
+                DiffKind::Changed
+            }
+            (Some(_), None) => DiffKind::Removed,
+            (None, Some(_)) => DiffKind::Added,
+            (None, None) => continue,
+        };
+        result.cells.push(CellDiff {
+            sheet: sheet_a.name().clone(),
+            row: *row,
+            col: *col,
+            kind,
+            old: cell_a.map(|v| single_cell(v)),
+            new: cell_b.map(|v| single_cell(v)),
+        });
+    }
+}
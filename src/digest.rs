@@ -0,0 +1,456 @@
+//!
+//! Content digest for change detection.
+//!
+//! Hashes the cell values, formulas and the cell styles by value.
+//! Style names, metadata, settings and everything else that changes
+//! with each save are ignored.
+//!
+//! Repeat counts of cells and rows are resolved, a cell with a repeat
+//! count is the same as the equal cells written one by one.
+//!
+//! The hash is FNV-1a, which is stable across platforms and versions.
+//! The diff compares the same data unhashed.
+
+use crate::attrmap2::AttrMap2;
+use crate::cell_::CellData;
+use crate::format::ValueFormatTrait;
+use crate::style::CellStyle;
+use crate::text::TextTag;
+use crate::xmltree::XmlContent;
+use crate::{Sheet, Value, WorkBook};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// Hashes the data, or collects it for an exact comparison.
+struct Digest {
+    hash: u64,
+    raw: Option<Vec<u8>>,
+}
+
+impl Digest {
+    fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET,
+            raw: None,
+        }
+    }
+
+    fn new_raw() -> Self {
+        Self {
+            hash: FNV_OFFSET,
+            raw: Some(Vec::new()),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn finish_raw(self) -> Vec<u8> {
+        self.raw.unwrap_or_default()
+    }
+
+    fn bytes(&mut self, buf: &[u8]) {
+        if let Some(raw) = &mut self.raw {
+            raw.extend_from_slice(buf);
+        } else {
+            for b in buf {
+                self.hash ^= *b as u64;
+                self.hash = self.hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.bytes(&[v]);
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn f64(&mut self, v: f64) {
+        self.u64(v.to_bits());
+    }
+
+    fn str(&mut self, v: &str) {
+        self.u64(v.len() as u64);
+        self.bytes(v.as_bytes());
+    }
+
+    fn opt_str(&mut self, v: Option<&str>) {
+        match v {
+            Some(v) => {
+                self.u8(1);
+                self.str(v);
+            }
+            None => self.u8(0),
+        }
+    }
+}
+
+/// Digest of the whole workbook.
+pub(crate) fn workbook_digest(book: &WorkBook) -> u64 {
+    let mut d = Digest::new();
+    d.u64(book.sheets.len() as u64);
+    for sheet in book.sheets.iter() {
+        d.u64(sheet_digest(book, sheet.as_ref()));
+    }
+    d.finish()
+}
+
+/// Digest of one sheet.
+pub(crate) fn sheet_digest(book: &WorkBook, sheet: &Sheet) -> u64 {
+    let mut d = Digest::new();
+    d.str(&sheet.name);
+    for block in cell_blocks(book, sheet) {
+        d.u32(block.row);
+        d.u32(block.rows);
+        d.u32(block.col);
+        d.u32(block.cols);
+        d.bytes(&block.key);
+    }
+    d.finish()
+}
+
+/// Data of a cell for an exact comparison. Same as for the digest, but
+/// not hashed. The repeat count is not part of it.
+pub(crate) fn cell_key(book: &WorkBook, cell: &CellData) -> Vec<u8> {
+    let mut d = Digest::new_raw();
+    digest_cell(&mut d, book, cell);
+    d.finish_raw()
+}
+
+/// Block of equal cells with the repeat counts resolved.
+pub(crate) struct CellBlock<'a> {
+    pub(crate) row: u32,
+    pub(crate) rows: u32,
+    pub(crate) col: u32,
+    pub(crate) cols: u32,
+    pub(crate) cell: &'a CellData,
+    pub(crate) key: Vec<u8>,
+}
+
+/// The non-blank cells of the sheet as blocks of equal cells.
+///
+/// Adjacent equal cells in a row and adjacent equal rows are joined,
+/// so the blocks are the same whether the cells were written one by one
+/// or with a repeat count.
+pub(crate) fn cell_blocks<'a>(book: &WorkBook, sheet: &'a Sheet) -> Vec<CellBlock<'a>> {
+    let mut blocks = Vec::new();
+    // current run of equal rows.
+    let mut run: Vec<CellBlock<'a>> = Vec::new();
+
+    let mut it = sheet.data.iter().peekable();
+    while let Some(((row, _), _)) = it.peek() {
+        let row = *row;
+
+        let mut cells: Vec<CellBlock<'a>> = Vec::new();
+        while let Some(((_, col), cell)) = it.next_if(|((r, _), _)| *r == row) {
+            if is_blank(cell) {
+                continue;
+            }
+            let cols = cell.repeat.max(1);
+            let key = cell_key(book, cell);
+            match cells.last_mut() {
+                Some(last) if last.col + last.cols == col && last.key == key => {
+                    last.cols += cols;
+                }
+                _ => cells.push(CellBlock {
+                    row,
+                    rows: 1,
+                    col,
+                    cols,
+                    cell,
+                    key,
+                }),
+            }
+        }
+        if cells.is_empty() {
+            continue;
+        }
+
+        // the repeat count of the row ends at the next row with data.
+        let mut rows = row_repeat(sheet, row);
+        if let Some(((next_row, _), _)) = it.peek() {
+            rows = rows.min(next_row - row);
+        }
+
+        let joined = run.first().is_some_and(|v| v.row + v.rows == row)
+            && run.len() == cells.len()
+            && run
+                .iter()
+                .zip(cells.iter())
+                .all(|(a, b)| a.col == b.col && a.cols == b.cols && a.key == b.key);
+        if joined {
+            for block in run.iter_mut() {
+                block.rows += rows;
+            }
+        } else {
+            blocks.append(&mut run);
+            for block in cells.iter_mut() {
+                block.rows = rows;
+            }
+            run = cells;
+        }
+    }
+    blocks.append(&mut run);
+
+    blocks
+}
+
+// Repeat count of the row, if it is the last row of a row-header.
+fn row_repeat(sheet: &Sheet, row: u32) -> u32 {
+    match sheet.row_header.range(..=row).next_back() {
+        Some((base_row, header)) if *base_row + header.span.max(1) - 1 == row => {
+            header.repeat.max(1)
+        }
+        _ => 1,
+    }
+}
+
+/// Data of a cell style by value for an exact comparison.
+pub(crate) fn cellstyle_key(book: &WorkBook, style: &CellStyle) -> Vec<u8> {
+    let mut d = Digest::new_raw();
+    digest_cellstyle(&mut d, book, style);
+    d.finish_raw()
+}
+
+/// Data of the sheet properties, without the cells, for an exact comparison.
+pub(crate) fn sheet_props_key(book: &WorkBook, sheet: &Sheet) -> Vec<u8> {
+    let mut d = Digest::new_raw();
+    digest_sheet_props(&mut d, book, sheet);
+    d.finish_raw()
+}
+
+fn digest_sheet_props(d: &mut Digest, book: &WorkBook, sheet: &Sheet) {
+    d.u8(sheet.display as u8);
+    d.u8(sheet.print as u8);
+    match sheet.style.as_ref().and_then(|v| book.tablestyle(v)) {
+        Some(style) => {
+            d.u8(1);
+            digest_attr(d, book, style.attrmap());
+            digest_attr(d, book, style.tablestyle());
+        }
+        None => d.u8(0),
+    }
+    for header in [&sheet.header_rows, &sheet.header_cols] {
+        match header {
+            Some(header) => {
+                d.u8(1);
+                d.u32(header.from);
+                d.u32(header.to);
+            }
+            None => d.u8(0),
+        }
+    }
+    match &sheet.print_ranges {
+        Some(print_ranges) => {
+            d.u8(1);
+            d.u64(print_ranges.len() as u64);
+            for range in print_ranges {
+                d.str(&range.to_string());
+            }
+        }
+        None => d.u8(0),
+    }
+}
+
+// Cells without any content are not always written, so they don't count.
+pub(crate) fn is_blank(cell: &CellData) -> bool {
+    cell.value == Value::Empty
+        && cell.formula.is_none()
+        && cell.style.is_none()
+        && cell.extra.is_none()
+}
+
+fn digest_cell(d: &mut Digest, book: &WorkBook, cell: &CellData) {
+    digest_value(d, &cell.value);
+    d.opt_str(cell.formula.as_deref());
+    match cell.style.as_ref().map(|v| (v, book.cellstyle(v))) {
+        Some((_, Some(style))) => {
+            d.u8(1);
+            digest_cellstyle(d, book, style);
+        }
+        Some((name, None)) => {
+            d.u8(2);
+            d.str(name.as_str());
+        }
+        None => d.u8(0),
+    }
+    if let Some(extra) = &cell.extra {
+        d.u32(extra.span.row_span);
+        d.u32(extra.span.col_span);
+        d.u32(extra.matrix_span.row_span);
+        d.u32(extra.matrix_span.col_span);
+        match &extra.annotation {
+            Some(annotation) => {
+                d.u8(1);
+                digest_text(d, annotation.text());
+            }
+            None => d.u8(0),
+        }
+    }
+}
+
+fn digest_value(d: &mut Digest, value: &Value) {
+    match value {
+        Value::Empty => d.u8(0),
+        Value::Boolean(v) => {
+            d.u8(1);
+            d.u8(*v as u8);
+        }
+        Value::Number(v) => {
+            d.u8(2);
+            d.f64(*v);
+        }
+        Value::Percentage(v) => {
+            d.u8(3);
+            d.f64(*v);
+        }
+        Value::Currency(v, c) => {
+            d.u8(4);
+            d.f64(*v);
+            d.str(c);
+        }
+        Value::Text(v) => {
+            d.u8(5);
+            d.str(v);
+        }
+        Value::TextXml(v) => {
+            d.u8(5);
+            digest_text(d, v);
+        }
+        Value::DateTime(v) => {
+            d.u8(6);
+            d.str(&v.to_string());
+        }
+        Value::DateTimeTz(v) => {
+            d.u8(8);
+            d.str(&v.to_string());
+        }
+        Value::TimeDuration(v) => {
+            d.u8(7);
+            d.str(&v.to_string());
+        }
+    }
+}
+
+// Plain paragraphs hash the same as the equivalent Value::Text.
+fn digest_text(d: &mut Digest, text: &[TextTag]) {
+    match plain_text(text) {
+        Some(v) => d.str(&v),
+        None => {
+            for t in text {
+                d.str(&t.to_string());
+            }
+        }
+    }
+}
+
+fn plain_text(text: &[TextTag]) -> Option<String> {
+    let mut buf = String::new();
+    for (i, t) in text.iter().enumerate() {
+        if t.name() != "text:p" || !t.attrmap().is_empty() {
+            return None;
+        }
+        if i > 0 {
+            buf.push('\n');
+        }
+        for c in t.content() {
+            match c {
+                XmlContent::Text(v) => buf.push_str(v),
+                XmlContent::Tag(_) => return None,
+            }
+        }
+    }
+    Some(buf)
+}
+
+fn digest_cellstyle(d: &mut Digest, book: &WorkBook, style: &CellStyle) {
+    digest_attr(d, book, style.attrmap());
+    digest_attr(d, book, style.cellstyle());
+    digest_attr(d, book, style.paragraphstyle());
+    digest_attr(d, book, style.textstyle());
+    if let Some(stylemaps) = style.stylemaps() {
+        for sm in stylemaps {
+            d.str(&sm.condition().to_string());
+            d.str(sm.applied_style().as_str());
+            d.opt_str(sm.base_cell().map(|v| v.to_string()).as_deref());
+        }
+    }
+}
+
+// Attributes sorted by name. The value format is resolved by value.
+fn digest_attr(d: &mut Digest, book: &WorkBook, attr: &AttrMap2) {
+    let mut attr: Vec<(&str, &str)> = attr
+        .iter()
+        .map(|(k, v)| (k.as_ref(), v))
+        .filter(|(k, _)| *k != "style:name" && *k != "style:family")
+        .collect();
+    attr.sort();
+    d.u64(attr.len() as u64);
+    for (k, v) in attr {
+        d.str(k);
+        if k == "style:data-style-name" && digest_format(d, book, v, true) {
+            continue;
+        }
+        d.str(v);
+    }
+}
+
+// Conditional formats refer to other formats, these are resolved
+// only one level deep.
+fn digest_format(d: &mut Digest, book: &WorkBook, name: &str, maps: bool) -> bool {
+    if let Some(f) = book.boolean_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else if let Some(f) = book.number_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else if let Some(f) = book.percentage_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else if let Some(f) = book.currency_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else if let Some(f) = book.text_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else if let Some(f) = book.datetime_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else if let Some(f) = book.timeduration_format(name) {
+        digest_format_value(d, book, f, maps);
+    } else {
+        return false;
+    }
+    true
+}
+
+fn digest_format_value<T: ValueFormatTrait>(
+    d: &mut Digest,
+    book: &WorkBook,
+    format: &T,
+    maps: bool,
+) {
+    d.u8(format.value_type() as u8);
+    digest_attr(d, book, format.attrmap());
+    digest_attr(d, book, format.textstyle());
+    for part in format.parts() {
+        d.u8(part.part_type() as u8);
+        digest_attr(d, book, part.attrmap());
+        for text in part.embedded_text() {
+            d.u32(text.position() as u32);
+            d.str(text.text());
+        }
+        d.opt_str(part.content().map(|v| v.as_str()).filter(|v| !v.is_empty()));
+    }
+    if let Some(stylemaps) = format.stylemaps().filter(|_| maps) {
+        for sm in stylemaps {
+            d.str(&sm.condition().to_string());
+            if !digest_format(d, book, sm.applied_style(), false) {
+                d.str(sm.applied_style());
+            }
+        }
+    }
+}
@@ -3,11 +3,13 @@
 //!
 
 use crate::attrmap2::AttrMap2;
-use crate::style::units::RelativeScale;
+use crate::sheet_::Visibility;
+use crate::style::units::{LengthUnit, RelativeScale};
 use crate::style::{GraphicStyleRef, ParagraphStyleRef};
 use crate::text::{TextP, TextTag};
 use crate::xlink::{XLinkActuate, XLinkShow, XLinkType};
-use crate::{CellRef, Length, OdsError};
+use crate::xmltree::XmlTag;
+use crate::{CellRef, Length, OdsError, Sheet};
 use base64::Engine;
 use chrono::NaiveDateTime;
 use get_size::GetSize;
@@ -120,6 +122,11 @@ impl Annotation {
         &self.text
     }
 
+    /// Text.
+    pub(crate) fn text_mut(&mut self) -> &mut Vec<TextTag> {
+        &mut self.text
+    }
+
     /// Text.
     pub fn push_text(&mut self, text: TextTag) {
         self.text.push(text);
@@ -156,71 +163,176 @@ impl Annotation {
     xml_id!(attr);
 }
 
-// /// The <draw:rect> element represents a rectangular drawing shape.
-// #[derive(Debug, Clone)]
-// pub struct DrawRect {
-//     ///
-//     name: String,
-//     ///
-//     attr: AttrMap2,
-// }
-//
-// impl DrawRect {
-//     pub fn new_empty() -> Self {
-//         Self {
-//             name: "".to_string(),
-//             attr: Default::default(),
-//         }
-//     }
-//
-//     pub fn new<S: Into<String>>(name: S) -> Self {
-//         Self {
-//             name: name.into(),
-//             attr: Default::default(),
-//         }
-//     }
-//
-//     /// Allows access to all attributes of the style itself.
-//     pub fn attrmap(&self) -> &AttrMap2 {
-//         &self.attr
-//     }
-//
-//     /// Allows access to all attributes of the style itself.
-//     pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
-//         &mut self.attr
-//     }
-//
-//     /// Name
-//     pub fn name(&self) -> &str {
-//         &self.name
-//     }
-//
-//     /// Name
-//     pub fn set_name<S: Into<String>>(&mut self, name: S) {
-//         self.name = name.into();
-//     }
-//
-//     draw_caption_id!(attr);
-//     draw_class_names!(attr);
-//     draw_corner_radius!(attr);
-//     draw_id!(attr);
-//     draw_layer!(attr);
-//     draw_style_name!(attr);
-//     draw_text_style_name!(attr);
-//     draw_transform!(attr);
-//     draw_z_index!(attr);
-//     svg_height!(attr);
-//     svg_width!(attr);
-//     svg_rx!(attr);
-//     svg_ry!(attr);
-//     svg_x!(attr);
-//     svg_y!(attr);
-//     table_end_cell_address!(attr);
-//     table_end_x!(attr);
-//     table_end_y!(attr);
-//     table_table_background!(attr);
-//     xml_id!(attr);
-// }
+/// A shape in the drawing layer of a sheet.
+#[derive(Debug, Clone, GetSize)]
+pub enum DrawShape {
+    /// Frame
+    Frame(DrawFrame),
+    /// Rectangle
+    Rect(DrawRect),
+    /// Line
+    Line(DrawLine),
+    /// Any other shape is kept as is.
+    Xml(XmlTag),
+}
+
+impl From<DrawFrame> for DrawShape {
+    fn from(value: DrawFrame) -> Self {
+        DrawShape::Frame(value)
+    }
+}
+
+impl From<DrawRect> for DrawShape {
+    fn from(value: DrawRect) -> Self {
+        DrawShape::Rect(value)
+    }
+}
+
+impl From<DrawLine> for DrawShape {
+    fn from(value: DrawLine) -> Self {
+        DrawShape::Line(value)
+    }
+}
+
+// Accessors shared by the simple shapes.
+macro_rules! draw_shape {
+    () => {
+        /// Allows access to all attributes of the style itself.
+        pub fn attrmap(&self) -> &AttrMap2 {
+            &self.attr
+        }
+
+        /// Allows access to all attributes of the style itself.
+        pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+            &mut self.attr
+        }
+
+        /// Desc
+        pub fn desc(&self) -> Option<&String> {
+            self.desc.as_ref()
+        }
+
+        /// Desc
+        pub fn set_desc<S: Into<String>>(&mut self, desc: S) {
+            self.desc = Some(desc.into())
+        }
+
+        /// Desc
+        pub fn clear_desc(&mut self) {
+            self.desc = None;
+        }
+
+        /// Title
+        pub fn title(&self) -> Option<&String> {
+            self.title.as_ref()
+        }
+
+        /// Title
+        pub fn set_title<S: Into<String>>(&mut self, title: S) {
+            self.title = Some(title.into());
+        }
+
+        /// Title
+        pub fn clear_title(&mut self) {
+            self.title = None;
+        }
+
+        /// Text
+        pub fn text(&self) -> &Vec<TextTag> {
+            &self.text
+        }
+
+        /// Text
+        pub(crate) fn text_mut(&mut self) -> &mut Vec<TextTag> {
+            &mut self.text
+        }
+
+        /// Text
+        pub fn push_text(&mut self, text: TextTag) {
+            self.text.push(text);
+        }
+
+        /// Text
+        pub fn push_text_str<S: Into<String>>(&mut self, text: S) {
+            self.text.push(TextP::new().text(text).into_xmltag());
+        }
+
+        /// Text
+        pub fn set_text(&mut self, text: Vec<TextTag>) {
+            self.text = text;
+        }
+    };
+}
+
+/// The <draw:rect> element represents a rectangular drawing shape.
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct DrawRect {
+    /// The <svg:title> element specifies a name for a graphic object.
+    title: Option<String>,
+    /// The <svg:desc> element specifies a prose description of a graphic object.
+    desc: Option<String>,
+    /// ...
+    attr: AttrMap2,
+    /// ...
+    text: Vec<TextTag>,
+}
+
+impl DrawRect {
+    /// New.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    draw_shape!();
+
+    draw_shape_attr!(attr);
+    draw_caption_id!(attr);
+    draw_corner_radius!(attr);
+    svg_height!(attr);
+    svg_width!(attr);
+    svg_rx!(attr);
+    svg_ry!(attr);
+    svg_x!(attr);
+    svg_y!(attr);
+}
+
+/// The <draw:line> element represents a line.
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct DrawLine {
+    /// The <svg:title> element specifies a name for a graphic object.
+    title: Option<String>,
+    /// The <svg:desc> element specifies a prose description of a graphic object.
+    desc: Option<String>,
+    /// ...
+    attr: AttrMap2,
+    /// ...
+    text: Vec<TextTag>,
+}
+
+impl DrawLine {
+    /// New.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Line from (x1, y1) to (x2, y2).
+    pub fn new_line(x1: Length, y1: Length, x2: Length, y2: Length) -> Self {
+        let mut line = Self::new();
+        line.svg_x1(x1);
+        line.svg_y1(y1);
+        line.svg_x2(x2);
+        line.svg_y2(y2);
+        line
+    }
+
+    draw_shape!();
+
+    draw_shape_attr!(attr);
+    svg_x1!(attr);
+    svg_y1!(attr);
+    svg_x2!(attr);
+    svg_y2!(attr);
+}
 
 /// The <draw:frame> element represents a frame and serves as the container for elements that
 /// may occur in a frame.
@@ -243,6 +355,8 @@ pub struct DrawFrame {
 pub enum DrawFrameContent {
     /// Image
     Image(DrawImage),
+    /// Embedded object
+    Object(DrawObject),
 }
 
 impl DrawFrame {
@@ -440,3 +554,221 @@ impl DrawImage {
     xlink_type!(attr);
     xml_id!(attr);
 }
+
+/// The <draw:object> element represents an embedded object. The object is
+/// a sub-document in the zip, linked with xlink:href, or is contained inline
+/// as MathML.
+///
+/// The sub-document is added with WorkBook::add_embedded_object(). Usually the
+/// frame contains a second draw:image with a replacement image.
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct DrawObject {
+    attr: AttrMap2,
+    content: Option<XmlTag>,
+}
+
+impl DrawObject {
+    /// New.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Object linked to a sub-document in the zip.
+    pub fn new_href<S: Into<String>>(href: S) -> Self {
+        let mut r = Self::new();
+        r.set_xlink_href(href);
+        r.set_xlink_type(XLinkType::Simple);
+        r.set_xlink_show(XLinkShow::Embed);
+        r.set_xlink_actuate(XLinkActuate::OnLoad);
+        r
+    }
+
+    /// Allows access to all attributes of the style itself.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// Allows access to all attributes of the style itself.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+
+    /// Inline content, usually a math:math element.
+    pub fn content(&self) -> Option<&XmlTag> {
+        self.content.as_ref()
+    }
+
+    /// Inline content, usually a math:math element.
+    pub fn set_content(&mut self, content: XmlTag) {
+        self.content = Some(content);
+    }
+
+    /// Inline content.
+    pub fn clear_content(&mut self) {
+        self.content = None;
+    }
+
+    xlink_actuate!(attr);
+    xlink_href!(attr);
+    xlink_show!(attr);
+    xlink_type!(attr);
+    xml_id!(attr);
+}
+
+/// Position and size of a drawing that is anchored to a cell and resizes
+/// with it.
+///
+/// The position is given as offset from the top-left corner of the anchor
+/// cell. The end-cell and the end offsets are calculated with the column
+/// widths and row heights of the sheet. Columns and rows without an explicit
+/// size use the defaults of LibreOffice, hidden ones count with zero size.
+///
+/// ```
+/// use spreadsheet_ods::draw::{Anchor, DrawFrame};
+/// use spreadsheet_ods::{Length, Sheet};
+///
+/// let mut sheet = Sheet::new("one");
+/// sheet.set_col_width(1, Length::Cm(2.0));
+/// sheet.set_col_width(2, Length::Cm(2.0));
+/// sheet.set_row_height(1, Length::Cm(1.0));
+/// sheet.set_row_height(2, Length::Cm(1.0));
+///
+/// let anchor = Anchor::new(1, 1, Length::Cm(0.5), Length::Cm(0.5), Length::Cm(3.0), Length::Cm(1.0));
+/// let mut frame = DrawFrame::new();
+/// anchor.set_frame(&sheet, &mut frame);
+/// assert_eq!(frame.attrmap().attr("table:end-cell-address"), Some(".C3"));
+/// assert_eq!(frame.attrmap().attr("table:end-x"), Some("1.5cm"));
+/// assert_eq!(frame.attrmap().attr("table:end-y"), Some("0.5cm"));
+/// sheet.add_draw_frame(1, 1, frame);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    row: u32,
+    col: u32,
+    x: Length,
+    y: Length,
+    width: Length,
+    height: Length,
+}
+
+/// Default column width of LibreOffice in inch.
+const DEFAULT_COL_WIDTH: f64 = 0.8889;
+/// Default row height of LibreOffice in inch.
+const DEFAULT_ROW_HEIGHT: f64 = 0.1783;
+
+impl Anchor {
+    /// Drawing at the given cell with the offset x/y inside the cell.
+    pub fn new(row: u32, col: u32, x: Length, y: Length, width: Length, height: Length) -> Self {
+        Self {
+            row,
+            col,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Anchor row.
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Anchor column.
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Position of the drawing relative to the top-left corner of the sheet.
+    /// This is what svg:x and svg:y contain.
+    pub fn position(&self, sheet: &Sheet) -> (Length, Length) {
+        let x = (0..self.col).map(|c| col_width(sheet, c)).sum::<f64>() + inch(self.x, 0.0);
+        let y = (0..self.row).map(|r| row_height(sheet, r)).sum::<f64>() + inch(self.y, 0.0);
+        (cm(x), cm(y))
+    }
+
+    /// Calculates the cell where the bottom-right corner of the drawing
+    /// ends, and the offset inside this cell.
+    pub fn end(&self, sheet: &Sheet) -> (CellRef, Length, Length) {
+        let (end_col, end_x) = end_of(self.col, inch(self.x, 0.0) + inch(self.width, 0.0), |c| {
+            col_width(sheet, c)
+        });
+        let (end_row, end_y) = end_of(self.row, inch(self.y, 0.0) + inch(self.height, 0.0), |r| {
+            row_height(sheet, r)
+        });
+        (CellRef::local(end_row, end_col), cm(end_x), cm(end_y))
+    }
+
+    /// Sets position, size and the end-cell for a frame.
+    /// Images, charts and embedded objects all live in a frame.
+    pub fn set_frame(&self, sheet: &Sheet, frame: &mut DrawFrame) {
+        self.set_attr(sheet, frame.attrmap_mut());
+    }
+
+    /// Sets position, size and the end-cell for a rectangle.
+    pub fn set_rect(&self, sheet: &Sheet, rect: &mut DrawRect) {
+        self.set_attr(sheet, rect.attrmap_mut());
+    }
+
+    /// Sets position, size and the end-cell for an annotation.
+    pub fn set_annotation(&self, sheet: &Sheet, annotation: &mut Annotation) {
+        self.set_attr(sheet, annotation.attrmap_mut());
+    }
+
+    fn set_attr(&self, sheet: &Sheet, attr: &mut AttrMap2) {
+        let (x, y) = self.position(sheet);
+        let (end_cell, end_x, end_y) = self.end(sheet);
+        attr.set_attr("svg:x", x.to_string());
+        attr.set_attr("svg:y", y.to_string());
+        attr.set_attr("svg:width", self.width.to_string());
+        attr.set_attr("svg:height", self.height.to_string());
+        attr.set_attr("table:end-cell-address", end_cell.to_string());
+        attr.set_attr("table:end-x", end_x.to_string());
+        attr.set_attr("table:end-y", end_y.to_string());
+    }
+}
+
+// Walks from the start cell until the extent fits into a cell.
+fn end_of<F: Fn(u32) -> f64>(start: u32, mut extent: f64, size: F) -> (u32, f64) {
+    let mut idx = start;
+    loop {
+        let s = size(idx);
+        if extent <= s || idx == u32::MAX {
+            return (idx, extent);
+        }
+        extent -= s;
+        idx += 1;
+    }
+}
+
+fn col_width(sheet: &Sheet, col: u32) -> f64 {
+    if sheet.col_visible(col) != Visibility::Visible {
+        0.0
+    } else {
+        inch(sheet.col_width(col), DEFAULT_COL_WIDTH)
+    }
+}
+
+fn row_height(sheet: &Sheet, row: u32) -> f64 {
+    if sheet.row_visible(row) != Visibility::Visible {
+        0.0
+    } else {
+        inch(sheet.row_height(row), DEFAULT_ROW_HEIGHT)
+    }
+}
+
+// Not rounded, the sums would drift otherwise.
+fn inch(length: Length, default: f64) -> f64 {
+    match length {
+        Length::Default | Length::Em(_) => default,
+        Length::Cm(v) => v / 2.54,
+        Length::Mm(v) => v / 25.4,
+        Length::In(v) => v,
+        Length::Pt(v) => v / 72.0,
+        Length::Pc(v) => v / 6.0,
+    }
+}
+
+fn cm(inch: f64) -> Length {
+    Length::In(inch).to_unit(LengthUnit::Cm)
+}
@@ -0,0 +1,885 @@
+//! Storage for the cells of a sheet.
+
+use crate::cell_::CellData;
+use crate::value_::Value;
+use crate::CellStyleRef;
+use chrono::{DateTime, Duration, FixedOffset};
+use get_size::GetSize;
+use std::collections::{btree_map, BTreeMap};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::iter::FusedIterator;
+use std::mem::size_of;
+use std::ops::{Bound, Index, RangeBounds, RangeInclusive};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::{fs, process, slice};
+
+/// The cells of a sheet, bucketed by row.
+///
+/// Each row holds its columns and its cells in two vecs sorted by column.
+/// Compared to a BTreeMap<(u32, u32), CellData> this saves the key per
+/// cell and the half-empty btree nodes, which is about half the memory
+/// for a dense sheet. The api mimics the BTreeMap, but the keys are
+/// returned by value.
+///
+/// Rows can be moved to a temporary file with spill(). They are read
+/// back transparently when they are accessed again. A failure to read
+/// the temporary file panics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CellMap {
+    rows: BTreeMap<u32, RowSlot>,
+    len: usize,
+    spill: Option<Arc<SpillFile>>,
+}
+
+// A row that may live in the spill file.
+#[derive(Debug, Clone, Default)]
+struct RowSlot {
+    // Not initialized while the row is only in the spill file.
+    cells: OnceLock<CellRow>,
+    // Position and length in the spill file, as long as that copy
+    // is up to date.
+    spilled: Option<(u64, u32)>,
+}
+
+impl RowSlot {
+    fn cells(&self, spill: Option<&SpillFile>) -> &CellRow {
+        self.cells.get_or_init(|| match (spill, self.spilled) {
+            (Some(spill), Some((pos, len))) => spill
+                .read(pos, len)
+                .unwrap_or_else(|e| panic!("reading spilled cells failed: {}", e)),
+            _ => CellRow::default(),
+        })
+    }
+
+    // Any modification makes the copy in the spill file outdated.
+    fn cells_mut(&mut self, spill: Option<&SpillFile>) -> &mut CellRow {
+        self.cells(spill);
+        self.spilled = None;
+        self.cells.get_mut().expect("cells")
+    }
+
+    fn into_cells(self, spill: Option<&SpillFile>) -> CellRow {
+        self.cells(spill);
+        self.cells.into_inner().expect("cells")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CellRow {
+    cols: Vec<u32>,
+    cells: Vec<CellData>,
+}
+
+impl CellRow {
+    fn find(&self, col: u32) -> Result<usize, usize> {
+        // appending at the end is the common case.
+        match self.cols.last() {
+            Some(last) if *last < col => Err(self.cols.len()),
+            None => Err(0),
+            _ => self.cols.binary_search(&col),
+        }
+    }
+
+    fn insert(&mut self, idx: usize, col: u32, cell: CellData) {
+        // Grow by a quarter instead of doubling, the slack of a row
+        // would eat much of the savings otherwise.
+        if self.cols.len() == self.cols.capacity() {
+            let more = (self.cols.len() / 4).max(4);
+            self.cols.reserve_exact(more);
+            self.cells.reserve_exact(more);
+        }
+        self.cols.insert(idx, col);
+        self.cells.insert(idx, cell);
+    }
+}
+
+// Index range of the cells of one row that lie within the bounds.
+fn col_span(
+    row: u32,
+    cols: &[u32],
+    start: Bound<(u32, u32)>,
+    end: Bound<(u32, u32)>,
+) -> std::ops::Range<usize> {
+    let lo = match start {
+        Bound::Included((r, c)) if r == row => cols.partition_point(|v| *v < c),
+        Bound::Excluded((r, c)) if r == row => cols.partition_point(|v| *v <= c),
+        _ => 0,
+    };
+    let hi = match end {
+        Bound::Included((r, c)) if r == row => cols.partition_point(|v| *v <= c),
+        Bound::Excluded((r, c)) if r == row => cols.partition_point(|v| *v < c),
+        _ => cols.len(),
+    };
+    lo..hi.max(lo)
+}
+
+// The bounds for the row buckets. An inverted range is empty.
+fn row_bounds(start: Bound<(u32, u32)>, end: Bound<(u32, u32)>) -> (Bound<u32>, Bound<u32>) {
+    let start = match start {
+        Bound::Included((r, _)) | Bound::Excluded((r, _)) => Some(r),
+        Bound::Unbounded => None,
+    };
+    let end = match end {
+        Bound::Included((r, _)) | Bound::Excluded((r, _)) => Some(r),
+        Bound::Unbounded => None,
+    };
+    match (start, end) {
+        (Some(s), Some(e)) if s > e => (Bound::Included(s), Bound::Excluded(s)),
+        (s, e) => (
+            s.map_or(Bound::Unbounded, Bound::Included),
+            e.map_or(Bound::Unbounded, Bound::Included),
+        ),
+    }
+}
+
+impl CellMap {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cells.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.rows.clear();
+        self.len = 0;
+        self.spill = None;
+    }
+
+    pub(crate) fn get(&self, (row, col): &(u32, u32)) -> Option<&CellData> {
+        let cells = self.rows.get(row)?.cells(self.spill.as_deref());
+        let idx = cells.find(*col).ok()?;
+        Some(&cells.cells[idx])
+    }
+
+    pub(crate) fn get_mut(&mut self, (row, col): &(u32, u32)) -> Option<&mut CellData> {
+        let cells = self.rows.get_mut(row)?.cells_mut(self.spill.as_deref());
+        let idx = cells.find(*col).ok()?;
+        Some(&mut cells.cells[idx])
+    }
+
+    pub(crate) fn contains_key(&self, key: &(u32, u32)) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts the cell and returns the cell that was there before.
+    pub(crate) fn insert(&mut self, (row, col): (u32, u32), cell: CellData) -> Option<CellData> {
+        let cells = self
+            .rows
+            .entry(row)
+            .or_default()
+            .cells_mut(self.spill.as_deref());
+        match cells.find(col) {
+            Ok(idx) => Some(std::mem::replace(&mut cells.cells[idx], cell)),
+            Err(idx) => {
+                cells.insert(idx, col, cell);
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Returns the cell, a new cell is created with the function.
+    pub(crate) fn get_or_insert_with<F>(&mut self, (row, col): (u32, u32), f: F) -> &mut CellData
+    where
+        F: FnOnce() -> CellData,
+    {
+        let cells = self
+            .rows
+            .entry(row)
+            .or_default()
+            .cells_mut(self.spill.as_deref());
+        let idx = match cells.find(col) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                cells.insert(idx, col, f());
+                self.len += 1;
+                idx
+            }
+        };
+        &mut cells.cells[idx]
+    }
+
+    pub(crate) fn remove(&mut self, (row, col): &(u32, u32)) -> Option<CellData> {
+        let btree_map::Entry::Occupied(mut entry) = self.rows.entry(*row) else {
+            return None;
+        };
+        let cells = entry.get_mut().cells_mut(self.spill.as_deref());
+        let idx = cells.find(*col).ok()?;
+        cells.cols.remove(idx);
+        let cell = cells.cells.remove(idx);
+        if cells.cols.is_empty() {
+            entry.remove();
+        }
+        self.len -= 1;
+        Some(cell)
+    }
+
+    /// Keeps only the cells for which the predicate returns true.
+    pub(crate) fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&(u32, u32), &mut CellData) -> bool,
+    {
+        let mut len = 0;
+        let spill = self.spill.as_deref();
+        self.rows.retain(|row, slot| {
+            let cells = slot.cells_mut(spill);
+            let mut keep = 0;
+            for idx in 0..cells.cols.len() {
+                if f(&(*row, cells.cols[idx]), &mut cells.cells[idx]) {
+                    cells.cols.swap(keep, idx);
+                    cells.cells.swap(keep, idx);
+                    keep += 1;
+                }
+            }
+            cells.cols.truncate(keep);
+            cells.cells.truncate(keep);
+            len += keep;
+            keep > 0
+        });
+        self.len = len;
+    }
+
+    /// Cells in lexical order (row, col).
+    pub(crate) fn range<R: RangeBounds<(u32, u32)>>(&self, range: R) -> Range<'_> {
+        let start = range.start_bound().cloned();
+        let end = range.end_bound().cloned();
+        Range {
+            rows: self.rows.range(row_bounds(start, end)),
+            spill: self.spill.as_deref(),
+            start,
+            end,
+            front: Default::default(),
+            back: Default::default(),
+        }
+    }
+
+    /// Cells in lexical order (row, col).
+    pub(crate) fn range_mut<R: RangeBounds<(u32, u32)>>(&mut self, range: R) -> RangeMut<'_> {
+        let start = range.start_bound().cloned();
+        let end = range.end_bound().cloned();
+        RangeMut {
+            rows: self.rows.range_mut(row_bounds(start, end)),
+            spill: self.spill.as_deref(),
+            start,
+            end,
+            front: Default::default(),
+            back: Default::default(),
+        }
+    }
+
+    /// The rows with cells inside the columns. Each row comes with an
+    /// iterator over these cells.
+    pub(crate) fn rows_in(&self, rows: RangeInclusive<u32>, cols: RangeInclusive<u32>) -> Rows<'_> {
+        let rows = if rows.start() <= rows.end() {
+            self.rows.range(rows)
+        } else {
+            self.rows.range(0..0)
+        };
+        Rows {
+            map: &self.rows,
+            rows,
+            spill: self.spill.as_deref(),
+            cols,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Range<'_> {
+        self.range(..)
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> RangeMut<'_> {
+        self.range_mut(..)
+    }
+
+    pub(crate) fn keys(&self) -> impl DoubleEndedIterator<Item = (u32, u32)> + '_ {
+        let spill = self.spill.as_deref();
+        self.rows
+            .iter()
+            .flat_map(move |(row, slot)| slot.cells(spill).cols.iter().map(move |col| (*row, *col)))
+    }
+
+    pub(crate) fn values(&self) -> impl DoubleEndedIterator<Item = &CellData> {
+        let spill = self.spill.as_deref();
+        self.rows
+            .values()
+            .flat_map(move |slot| slot.cells(spill).cells.iter())
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut CellData> {
+        let spill = self.spill.as_deref();
+        self.rows
+            .values_mut()
+            .flat_map(move |slot| slot.cells_mut(spill).cells.iter_mut())
+    }
+
+    /// Moves the rows to a temporary file. Rows with cells that can't be
+    /// written there (annotations, draw-frames, spans, validations or
+    /// formatted text) stay in memory.
+    ///
+    /// Rows that were read back and not modified since are only dropped
+    /// from memory.
+    pub(crate) fn spill<R: RangeBounds<u32>>(&mut self, rows: R) -> io::Result<()> {
+        let mut buf = Vec::new();
+        for slot in self.rows.range_mut(rows).map(|(_, v)| v) {
+            let Some(cells) = slot.cells.get() else {
+                continue;
+            };
+            if slot.spilled.is_none() {
+                buf.clear();
+                if !encode_row(cells, &mut buf) {
+                    continue;
+                }
+                let file = match &self.spill {
+                    Some(file) => file,
+                    None => self.spill.insert(Arc::new(SpillFile::new()?)),
+                };
+                let len =
+                    u32::try_from(buf.len()).map_err(|_| io::Error::other("row too large"))?;
+                slot.spilled = Some((file.write(&buf)?, len));
+            }
+            slot.cells = OnceLock::new();
+        }
+        Ok(())
+    }
+}
+
+impl GetSize for CellMap {
+    fn get_heap_size(&self) -> usize {
+        // Counts the capacity of the rows, not only the used part.
+        // Spilled rows only count with their slot.
+        self.rows
+            .values()
+            .map(|slot| {
+                size_of::<u32>()
+                    + size_of::<RowSlot>()
+                    + slot.cells.get().map_or(0, |cells| {
+                        cells.cols.capacity() * size_of::<u32>()
+                            + cells.cells.capacity() * size_of::<CellData>()
+                            + cells.cells.iter().map(|v| v.get_heap_size()).sum::<usize>()
+                    })
+            })
+            .sum()
+    }
+}
+
+impl Index<&(u32, u32)> for CellMap {
+    type Output = CellData;
+
+    fn index(&self, key: &(u32, u32)) -> &Self::Output {
+        self.get(key).expect("no cell at this position")
+    }
+}
+
+impl Extend<((u32, u32), CellData)> for CellMap {
+    fn extend<T: IntoIterator<Item = ((u32, u32), CellData)>>(&mut self, iter: T) {
+        for (key, cell) in iter {
+            self.insert(key, cell);
+        }
+    }
+}
+
+impl FromIterator<((u32, u32), CellData)> for CellMap {
+    fn from_iter<T: IntoIterator<Item = ((u32, u32), CellData)>>(iter: T) -> Self {
+        let mut map = CellMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a> IntoIterator for &'a CellMap {
+    type Item = ((u32, u32), &'a CellData);
+    type IntoIter = Range<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut CellMap {
+    type Item = ((u32, u32), &'a mut CellData);
+    type IntoIter = RangeMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for CellMap {
+    type Item = ((u32, u32), CellData);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            rows: self.rows.into_iter(),
+            spill: self.spill,
+            row: 0,
+            cols: Default::default(),
+            cells: Default::default(),
+        }
+    }
+}
+
+/// Owning iterator over the cells.
+#[derive(Debug)]
+pub(crate) struct IntoIter {
+    rows: btree_map::IntoIter<u32, RowSlot>,
+    spill: Option<Arc<SpillFile>>,
+    row: u32,
+    cols: std::vec::IntoIter<u32>,
+    cells: std::vec::IntoIter<CellData>,
+}
+
+impl FusedIterator for IntoIter {}
+
+impl Iterator for IntoIter {
+    type Item = ((u32, u32), CellData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let (Some(col), Some(cell)) = (self.cols.next(), self.cells.next()) {
+                return Some(((self.row, col), cell));
+            }
+            let (row, slot) = self.rows.next()?;
+            let cells = slot.into_cells(self.spill.as_deref());
+            self.row = row;
+            self.cols = cells.cols.into_iter();
+            self.cells = cells.cells.into_iter();
+        }
+    }
+}
+
+// Cells of one row.
+#[derive(Debug, Clone, Default)]
+struct RowIter<'a> {
+    row: u32,
+    cols: slice::Iter<'a, u32>,
+    cells: slice::Iter<'a, CellData>,
+}
+
+impl<'a> RowIter<'a> {
+    fn new(row: u32, cells: &'a CellRow, start: Bound<(u32, u32)>, end: Bound<(u32, u32)>) -> Self {
+        let span = col_span(row, &cells.cols, start, end);
+        Self {
+            row,
+            cols: cells.cols[span.clone()].iter(),
+            cells: cells.cells[span].iter(),
+        }
+    }
+
+    fn next(&mut self) -> Option<((u32, u32), &'a CellData)> {
+        Some(((self.row, *self.cols.next()?), self.cells.next()?))
+    }
+
+    fn next_back(&mut self) -> Option<((u32, u32), &'a CellData)> {
+        Some(((self.row, *self.cols.next_back()?), self.cells.next_back()?))
+    }
+}
+
+/// Iterator over a range of cells.
+#[derive(Debug, Clone)]
+pub(crate) struct Range<'a> {
+    rows: btree_map::Range<'a, u32, RowSlot>,
+    spill: Option<&'a SpillFile>,
+    start: Bound<(u32, u32)>,
+    end: Bound<(u32, u32)>,
+    front: RowIter<'a>,
+    back: RowIter<'a>,
+}
+
+impl FusedIterator for Range<'_> {}
+
+impl<'a> Iterator for Range<'a> {
+    type Item = ((u32, u32), &'a CellData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.front.next() {
+                return Some(v);
+            }
+            let Some((row, slot)) = self.rows.next() else {
+                return self.back.next();
+            };
+            let cells = slot.cells(self.spill);
+            self.front = RowIter::new(*row, cells, self.start, self.end);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.front.cols.len() + self.back.cols.len(), None)
+    }
+}
+
+impl DoubleEndedIterator for Range<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.back.next_back() {
+                return Some(v);
+            }
+            let Some((row, slot)) = self.rows.next_back() else {
+                return self.front.next_back();
+            };
+            let cells = slot.cells(self.spill);
+            self.back = RowIter::new(*row, cells, self.start, self.end);
+        }
+    }
+}
+
+// Cells of one row.
+#[derive(Debug, Default)]
+struct RowIterMut<'a> {
+    row: u32,
+    cols: slice::Iter<'a, u32>,
+    cells: slice::IterMut<'a, CellData>,
+}
+
+impl<'a> RowIterMut<'a> {
+    fn new(
+        row: u32,
+        cells: &'a mut CellRow,
+        start: Bound<(u32, u32)>,
+        end: Bound<(u32, u32)>,
+    ) -> Self {
+        let span = col_span(row, &cells.cols, start, end);
+        Self {
+            row,
+            cols: cells.cols[span.clone()].iter(),
+            cells: cells.cells[span].iter_mut(),
+        }
+    }
+
+    fn next(&mut self) -> Option<((u32, u32), &'a mut CellData)> {
+        Some(((self.row, *self.cols.next()?), self.cells.next()?))
+    }
+
+    fn next_back(&mut self) -> Option<((u32, u32), &'a mut CellData)> {
+        Some(((self.row, *self.cols.next_back()?), self.cells.next_back()?))
+    }
+}
+
+/// Mutable iterator over a range of cells.
+#[derive(Debug)]
+pub(crate) struct RangeMut<'a> {
+    rows: btree_map::RangeMut<'a, u32, RowSlot>,
+    spill: Option<&'a SpillFile>,
+    start: Bound<(u32, u32)>,
+    end: Bound<(u32, u32)>,
+    front: RowIterMut<'a>,
+    back: RowIterMut<'a>,
+}
+
+impl FusedIterator for RangeMut<'_> {}
+
+impl<'a> Iterator for RangeMut<'a> {
+    type Item = ((u32, u32), &'a mut CellData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.front.next() {
+                return Some(v);
+            }
+            let Some((row, slot)) = self.rows.next() else {
+                return self.back.next();
+            };
+            let cells = slot.cells_mut(self.spill);
+            self.front = RowIterMut::new(*row, cells, self.start, self.end);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.front.cols.len() + self.back.cols.len(), None)
+    }
+}
+
+impl DoubleEndedIterator for RangeMut<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.back.next_back() {
+                return Some(v);
+            }
+            let Some((row, slot)) = self.rows.next_back() else {
+                return self.front.next_back();
+            };
+            let cells = slot.cells_mut(self.spill);
+            self.back = RowIterMut::new(*row, cells, self.start, self.end);
+        }
+    }
+}
+
+/// Iterator over the rows with cells in a range of columns.
+#[derive(Debug, Clone)]
+pub(crate) struct Rows<'a> {
+    map: &'a BTreeMap<u32, RowSlot>,
+    rows: btree_map::Range<'a, u32, RowSlot>,
+    spill: Option<&'a SpillFile>,
+    cols: RangeInclusive<u32>,
+}
+
+impl FusedIterator for Rows<'_> {}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = (u32, Range<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (row, slot) = self.rows.next()?;
+            let cells = slot.cells(self.spill);
+            let start = Bound::Included((*row, *self.cols.start()));
+            let end = Bound::Included((*row, *self.cols.end()));
+            let front = RowIter::new(*row, cells, start, end);
+            if front.cols.len() > 0 {
+                return Some((
+                    *row,
+                    Range {
+                        // empty, the row is in front.
+                        rows: self.map.range(*row..*row),
+                        spill: self.spill,
+                        start,
+                        end,
+                        front,
+                        back: Default::default(),
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// Temporary file for the spilled rows. It is removed when the last
+/// CellMap using it is dropped.
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl SpillFile {
+    fn new() -> io::Result<Self> {
+        static COUNT: AtomicU32 = AtomicU32::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "spreadsheet-ods-{}-{}.tmp",
+            process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    // Appends the data and returns its position.
+    fn write(&self, buf: &[u8]) -> io::Result<u64> {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let pos = file.seek(SeekFrom::End(0))?;
+        file.write_all(buf)?;
+        Ok(pos)
+    }
+
+    fn read(&self, pos: u64, len: u32) -> io::Result<CellRow> {
+        let mut buf = vec![0; len as usize];
+        {
+            let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            file.seek(SeekFrom::Start(pos))?;
+            file.read_exact(&mut buf)?;
+        }
+        decode_row(&buf)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn put_u32(buf: &mut Vec<u8>, v: u32) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put_i64(buf: &mut Vec<u8>, v: i64) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put_f64(buf: &mut Vec<u8>, v: f64) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+// u32::MAX marks None.
+fn put_str(buf: &mut Vec<u8>, v: Option<&str>) {
+    match v {
+        Some(v) => {
+            put_u32(buf, v.len() as u32);
+            buf.extend_from_slice(v.as_bytes());
+        }
+        None => put_u32(buf, u32::MAX),
+    }
+}
+
+// Writes the cells of the row. Returns false if there is any data
+// that can't be written, the row stays in memory then.
+fn encode_row(cells: &CellRow, buf: &mut Vec<u8>) -> bool {
+    put_u32(buf, cells.cols.len() as u32);
+    for (col, cell) in cells.cols.iter().zip(cells.cells.iter()) {
+        if cell.extra.is_some() {
+            return false;
+        }
+        put_u32(buf, *col);
+        put_u32(buf, cell.repeat);
+        put_str(buf, cell.style.as_ref().map(|v| v.as_str()));
+        put_str(buf, cell.formula.as_deref());
+        match &cell.value {
+            Value::Empty => buf.push(0),
+            Value::Boolean(v) => {
+                buf.push(1);
+                buf.push(*v as u8);
+            }
+            Value::Number(v) => {
+                buf.push(2);
+                put_f64(buf, *v);
+            }
+            Value::Percentage(v) => {
+                buf.push(3);
+                put_f64(buf, *v);
+            }
+            Value::Currency(v, c) => {
+                buf.push(4);
+                put_f64(buf, *v);
+                put_str(buf, Some(c));
+            }
+            Value::Text(v) => {
+                buf.push(5);
+                put_str(buf, Some(v));
+            }
+            Value::TextXml(_) => return false,
+            Value::DateTime(v) => {
+                buf.push(6);
+                let v = v.and_utc();
+                put_i64(buf, v.timestamp());
+                put_u32(buf, v.timestamp_subsec_nanos());
+            }
+            Value::DateTimeTz(v) => {
+                buf.push(7);
+                put_i64(buf, v.timestamp());
+                put_u32(buf, v.timestamp_subsec_nanos());
+                buf.extend_from_slice(&v.offset().local_minus_utc().to_le_bytes());
+            }
+            Value::TimeDuration(v) => {
+                buf.push(8);
+                put_i64(buf, v.num_seconds());
+                buf.extend_from_slice(&v.subsec_nanos().to_le_bytes());
+            }
+        }
+    }
+    true
+}
+
+fn invalid() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid spill data")
+}
+
+struct Decoder<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let (v, buf) = self.buf.split_at_checked(N).ok_or_else(invalid)?;
+        self.buf = buf;
+        Ok(v.try_into().expect("len"))
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.bytes()?))
+    }
+
+    fn i64(&mut self) -> io::Result<i64> {
+        Ok(i64::from_le_bytes(self.bytes()?))
+    }
+
+    fn f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_le_bytes(self.bytes()?))
+    }
+
+    fn str(&mut self) -> io::Result<Option<&'a str>> {
+        let len = self.u32()?;
+        if len == u32::MAX {
+            return Ok(None);
+        }
+        let (v, buf) = self
+            .buf
+            .split_at_checked(len as usize)
+            .ok_or_else(invalid)?;
+        self.buf = buf;
+        Ok(Some(std::str::from_utf8(v).map_err(|_| invalid())?))
+    }
+
+    fn datetime(&mut self) -> io::Result<DateTime<chrono::Utc>> {
+        let secs = self.i64()?;
+        let nanos = self.u32()?;
+        DateTime::from_timestamp(secs, nanos).ok_or_else(invalid)
+    }
+}
+
+fn decode_row(buf: &[u8]) -> io::Result<CellRow> {
+    let mut buf = Decoder { buf };
+    let len = buf.u32()? as usize;
+    let mut cells = CellRow {
+        cols: Vec::with_capacity(len),
+        cells: Vec::with_capacity(len),
+    };
+    for _ in 0..len {
+        cells.cols.push(buf.u32()?);
+        let repeat = buf.u32()?;
+        let style = buf.str()?.map(CellStyleRef::from);
+        let formula = buf.str()?.map(|v| v.to_string());
+        let value = match buf.u8()? {
+            0 => Value::Empty,
+            1 => Value::Boolean(buf.u8()? != 0),
+            2 => Value::Number(buf.f64()?),
+            3 => Value::Percentage(buf.f64()?),
+            4 => {
+                let v = buf.f64()?;
+                Value::Currency(v, buf.str()?.ok_or_else(invalid)?.into())
+            }
+            5 => Value::Text(buf.str()?.ok_or_else(invalid)?.into()),
+            6 => Value::DateTime(buf.datetime()?.naive_utc()),
+            7 => {
+                let v = buf.datetime()?;
+                let offset = FixedOffset::east_opt(buf.i32()?).ok_or_else(invalid)?;
+                Value::DateTimeTz(v.with_timezone(&offset))
+            }
+            8 => {
+                let secs = buf.i64()?;
+                let nanos = buf.i32()?;
+                Value::TimeDuration(Duration::seconds(secs) + Duration::nanoseconds(nanos as i64))
+            }
+            _ => return Err(invalid()),
+        };
+        cells.cells.push(CellData {
+            value,
+            formula,
+            style,
+            repeat,
+            extra: None,
+        });
+    }
+    Ok(cells)
+}
@@ -1 +1,2 @@
+pub(crate) mod cellmap;
 pub(crate) mod detach;
@@ -2,6 +2,9 @@
 //! Error type.
 //!
 
+use crate::CellRef;
+use get_size::GetSize;
+use get_size_derive::GetSize;
 use kparse::{Code, TokenizerError};
 use nom_locate::LocatedSpan;
 use std::error::Error;
@@ -19,6 +22,7 @@ pub type OdsResult<T> = Result<T, OdsError>;
 
 #[derive(Debug)]
 #[allow(missing_docs)]
+#[non_exhaustive]
 pub enum OdsError {
     Ods(String),
     Io(std::io::Error),
@@ -33,6 +37,85 @@ pub enum OdsError {
     Chrono(chrono::format::ParseError),
     SystemTime(std::time::SystemTimeError),
     Base64(base64::DecodeError),
+    /// An error while reading with the position where it occurred.
+    /// The original error is available via source().
+    Located {
+        /// File in the package, e.g. content.xml.
+        part: String,
+        /// Byte offset in the part.
+        offset: u64,
+        /// Sheet.
+        sheet: Option<String>,
+        /// Row, zero based.
+        row: Option<u32>,
+        /// Column, zero based.
+        col: Option<u32>,
+        /// The original error.
+        source: Box<OdsError>,
+    },
+    /// A limit set with OdsOptions was exceeded while reading.
+    LimitExceeded {
+        /// Which limit.
+        limit: &'static str,
+        /// The configured maximum.
+        max: u64,
+    },
+}
+
+impl OdsError {
+    /// Returns the original error if this is a Located error.
+    pub fn innermost(&self) -> &OdsError {
+        match self {
+            OdsError::Located { source, .. } => source.innermost(),
+            e => e,
+        }
+    }
+
+    // Adds the file and the position in the file.
+    pub(crate) fn at_part(self, part: &str, offset: u64) -> OdsError {
+        match self {
+            OdsError::Located {
+                part: p,
+                sheet,
+                row,
+                col,
+                source,
+                ..
+            } if p.is_empty() => OdsError::Located {
+                part: part.to_string(),
+                offset,
+                sheet,
+                row,
+                col,
+                source,
+            },
+            e @ OdsError::Located { .. } => e,
+            e => OdsError::Located {
+                part: part.to_string(),
+                offset,
+                sheet: None,
+                row: None,
+                col: None,
+                source: Box::new(e),
+            },
+        }
+    }
+
+    // Adds the sheet, row and column. The position in the file is added
+    // later with at_part().
+    pub(crate) fn at_cell(self, sheet: &str, row: u32, col: Option<u32>) -> OdsError {
+        match self {
+            e @ OdsError::Located { .. } => e,
+            e => OdsError::Located {
+                part: Default::default(),
+                offset: 0,
+                sheet: Some(sheet.to_string()),
+                row: Some(row),
+                col,
+                source: Box::new(e),
+            },
+        }
+    }
 }
 
 impl Display for OdsError {
@@ -51,6 +134,30 @@ impl Display for OdsError {
             OdsError::SystemTime(e) => write!(f, "SystemTime {}", e)?,
             OdsError::Utf8(e) => write!(f, "UTF8 {}", e)?,
             OdsError::Base64(e) => write!(f, "Base64 {}", e)?,
+            OdsError::Located {
+                part,
+                offset,
+                sheet,
+                row,
+                col,
+                source,
+            } => {
+                write!(f, "{} at byte {}", part, offset)?;
+                match (sheet, row, col) {
+                    (Some(sheet), Some(row), Some(col)) => {
+                        write!(f, " cell {}", CellRef::remote(sheet, *row, *col))?
+                    }
+                    (Some(sheet), Some(row), None) => {
+                        write!(f, " sheet {} row {}", sheet, row + 1)?
+                    }
+                    (Some(sheet), None, _) => write!(f, " sheet {}", sheet)?,
+                    _ => {}
+                }
+                write!(f, ": {}", source)?;
+            }
+            OdsError::LimitExceeded { limit, max } => {
+                write!(f, "Limit exceeded {} max {}", limit, max)?
+            }
         }
 
         Ok(())
@@ -58,7 +165,7 @@ impl Display for OdsError {
 }
 
 impl Error for OdsError {
-    fn cause(&self) -> Option<&dyn Error> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             OdsError::Ods(_) => None,
             OdsError::Io(e) => Some(e),
@@ -73,6 +180,8 @@ impl Error for OdsError {
             OdsError::SystemTime(e) => Some(e),
             OdsError::Utf8(e) => Some(e),
             OdsError::Base64(e) => Some(e),
+            OdsError::Located { source, .. } => Some(source.as_ref()),
+            OdsError::LimitExceeded { .. } => None,
         }
     }
 }
@@ -200,3 +309,68 @@ where
         }
     }
 }
+
+/// Problems found while reading that don't stop the read.
+#[derive(Debug, Clone, PartialEq, Eq, GetSize)]
+pub enum OdsWarning {
+    /// An error that was skipped with OdsOptions::lenient(), or a
+    /// duplicate sheet name accepted by push_sheet().
+    Recovered {
+        /// Where the error occurred.
+        context: String,
+        /// The error message.
+        message: String,
+    },
+    /// An attribute that is not understood.
+    /// Only recorded with OdsOptions::report_unused().
+    UnusedAttr {
+        /// Part of the reader.
+        context: String,
+        /// Element name.
+        tag: String,
+        /// Attribute name.
+        attr: String,
+        /// Attribute value.
+        value: String,
+    },
+    /// An element that is not understood.
+    /// Only recorded with OdsOptions::report_unused().
+    UnusedElement {
+        /// Part of the reader.
+        context: String,
+        /// Element name.
+        tag: String,
+    },
+    /// Text that is not understood.
+    /// Only recorded with OdsOptions::report_unused().
+    UnusedText {
+        /// Part of the reader.
+        context: String,
+        /// The text.
+        text: String,
+    },
+}
+
+impl Display for OdsWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            OdsWarning::Recovered { context, message } => write!(f, "{}: {}", context, message),
+            OdsWarning::UnusedAttr {
+                context,
+                tag,
+                attr,
+                value,
+            } => write!(
+                f,
+                "{}: unused attribute {}='{}' of {}",
+                context, attr, value, tag
+            ),
+            OdsWarning::UnusedElement { context, tag } => {
+                write!(f, "{}: unused element {}", context, tag)
+            }
+            OdsWarning::UnusedText { context, text } => {
+                write!(f, "{}: unused text '{}'", context, text)
+            }
+        }
+    }
+}
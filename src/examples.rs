@@ -0,0 +1,198 @@
+//!
+//! Canonical example documents built with the public api.
+//!
+//! They are meant as executable references for the api. corpus()
+//! collects all of them for round-trip tests.
+//!
+//! ```
+//! use spreadsheet_ods::examples;
+//! use spreadsheet_ods::{read_ods_buf, write_ods_buf};
+//!
+//! let mut wb = examples::matrix(10, 5);
+//! let buf = write_ods_buf(&mut wb, Vec::new()).unwrap();
+//! let wb = read_ods_buf(&buf).unwrap();
+//! assert_eq!(wb.sheet(0).cell_count(), 50);
+//! ```
+//!
+
+use crate::defaultstyles::{DefaultFormat, DefaultStyle};
+use crate::formula::{fcellref, frangeref};
+use crate::{CellStyle, Sheet, Value, ValueFormatCurrency, WorkBook};
+use chrono::NaiveDate;
+use icu_locid::{locale, Locale};
+
+/// One line of an invoice.
+#[derive(Debug, Clone)]
+pub struct InvoiceItem {
+    /// Description
+    pub description: String,
+    /// Quantity
+    pub quantity: f64,
+    /// Price per unit.
+    pub price: f64,
+}
+
+/// Data for the invoice example.
+#[derive(Debug, Clone)]
+pub struct InvoiceData {
+    /// Invoice number.
+    pub number: String,
+    /// Invoice date.
+    pub date: NaiveDate,
+    /// Customer.
+    pub customer: String,
+    /// ISO currency code.
+    pub currency: String,
+    /// Tax rate, 0.2 for 20%.
+    pub tax_rate: f64,
+    /// Locale for the formats.
+    pub locale: Locale,
+    /// Invoice lines.
+    pub items: Vec<InvoiceItem>,
+}
+
+impl InvoiceData {
+    /// Some sample data.
+    pub fn sample() -> Self {
+        Self {
+            number: "2024-0815".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).expect("date"),
+            customer: "ACME Corp.".to_string(),
+            currency: "EUR".to_string(),
+            tax_rate: 0.2,
+            locale: locale!("de_AT"),
+            items: vec![
+                InvoiceItem {
+                    description: "Widget".to_string(),
+                    quantity: 4.0,
+                    price: 12.5,
+                },
+                InvoiceItem {
+                    description: "Gadget".to_string(),
+                    quantity: 1.0,
+                    price: 99.9,
+                },
+                InvoiceItem {
+                    description: "Shipping".to_string(),
+                    quantity: 1.0,
+                    price: 7.0,
+                },
+            ],
+        }
+    }
+}
+
+/// Creates an invoice.
+///
+/// The header contains number, date and customer. Below are the items
+/// with quantity, price and a formula for the line total, followed by
+/// the net total, the tax and the gross total as formulas.
+///
+/// Without items the net total is 0.
+pub fn invoice(data: &InvoiceData) -> WorkBook {
+    let mut wb = WorkBook::new(data.locale.clone());
+
+    let mut st_header = CellStyle::new("invoice_header", &DefaultFormat::default());
+    st_header.set_font_bold();
+    let st_header = wb.add_cellstyle(st_header);
+
+    let f_currency = wb.add_currency_format(ValueFormatCurrency::for_iso(
+        &data.currency,
+        data.locale.clone(),
+    ));
+    let st_currency = wb.add_cellstyle(CellStyle::new("invoice_currency", &f_currency));
+
+    let mut sh = Sheet::new("Invoice");
+
+    sh.set_styled_value(0, 0, "Invoice", &st_header);
+    sh.set_value(0, 1, data.number.as_str());
+    sh.set_styled_value(1, 0, "Date", &st_header);
+    sh.set_styled_value(1, 1, data.date, &DefaultStyle::date());
+    sh.set_styled_value(2, 0, "Customer", &st_header);
+    sh.set_value(2, 1, data.customer.as_str());
+
+    let first = 5;
+    for (col, title) in ["Description", "Quantity", "Price", "Total"]
+        .iter()
+        .enumerate()
+    {
+        sh.set_styled_value(first - 1, col as u32, *title, &st_header);
+    }
+
+    let mut row = first;
+    for item in &data.items {
+        sh.set_value(row, 0, item.description.as_str());
+        sh.set_value(row, 1, item.quantity);
+        sh.set_styled_value(
+            row,
+            2,
+            Value::new_currency(&data.currency, item.price),
+            &st_currency,
+        );
+        sh.set_formula(
+            row,
+            3,
+            format!("of:={}*{}", fcellref(row, 1), fcellref(row, 2)),
+        );
+        sh.set_cellstyle(row, 3, &st_currency);
+        row += 1;
+    }
+
+    sh.set_styled_value(row, 2, "Net", &st_header);
+    if row > first {
+        sh.set_formula(
+            row,
+            3,
+            format!("of:=SUM({})", frangeref(first, 3, row - 1, 3)),
+        );
+        sh.set_cellstyle(row, 3, &st_currency);
+    } else {
+        sh.set_styled_value(
+            row,
+            3,
+            Value::new_currency(&data.currency, 0.0),
+            &st_currency,
+        );
+    }
+
+    sh.set_styled_value(row + 1, 2, "Tax", &st_header);
+    sh.set_formula(
+        row + 1,
+        3,
+        format!("of:={}*{}", fcellref(row, 3), data.tax_rate),
+    );
+    sh.set_cellstyle(row + 1, 3, &st_currency);
+
+    sh.set_styled_value(row + 2, 2, "Total", &st_header);
+    sh.set_formula(
+        row + 2,
+        3,
+        format!("of:={}+{}", fcellref(row, 3), fcellref(row + 1, 3)),
+    );
+    sh.set_cellstyle(row + 2, 3, &st_currency);
+
+    wb.push_sheet(sh);
+    wb
+}
+
+/// Creates a sheet "matrix" with rows x cols numbers. The cell at
+/// (row, col) contains row * cols + col.
+pub fn matrix(rows: u32, cols: u32) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("matrix");
+    for r in 0..rows {
+        for c in 0..cols {
+            sh.set_value(r, c, r as f64 * cols as f64 + c as f64);
+        }
+    }
+    wb.push_sheet(sh);
+    wb
+}
+
+/// All example documents with a name. Used as regression corpus.
+pub fn corpus() -> Vec<(&'static str, WorkBook)> {
+    vec![
+        ("invoice", invoice(&InvoiceData::sample())),
+        ("matrix", matrix(100, 20)),
+    ]
+}
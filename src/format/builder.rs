@@ -140,15 +140,16 @@ impl<'vf, T: ValueFormatTrait> PartNumberBuilder<'vf, T> {
     ///
     /// The number:embedded-text element has no child elements.
     /// The number:embedded-text element has character data content
+    ///
+    /// Can be called repeatedly for text at different positions.
     #[must_use]
     pub fn embedded_text<S: Into<String>>(mut self, text: S, pos: i32) -> Self {
-        self.part.position = Some(pos);
-        self.part.content = Some(text.into());
+        self.part.push_embedded_text(pos, text);
         self
     }
 }
 
-/// Builder for FormatPart with type Number.
+/// Builder for FormatPart with type FillCharacter.
 #[derive(Debug)]
 pub struct PartFillCharacterBuilder<'vf, T: ValueFormatTrait> {
     part: FormatPart,
@@ -185,8 +186,8 @@ impl<'vf, T: ValueFormatTrait> PartFillCharacterBuilder<'vf, T> {
         }
     }
 
-    /// If the number:decimal-places attribute is not specified, the number of decimal places
-    /// specified by the default table cell style is used.
+    /// The character is repeated to fill the remaining width of the cell.
+    /// Only one fill character is allowed per format.
     #[must_use]
     pub fn fill_char(mut self, c: char) -> Self {
         self.part.set_content(c.to_string());
@@ -258,12 +259,19 @@ impl<'vf, T: ValueFormatTrait> PartScientificBuilder<'vf, T> {
     /// attribute.
     /// The default value for this attribute is 1.
     #[must_use]
-    pub fn expontent_interval(mut self, v: u8) -> Self {
+    pub fn exponent_interval(mut self, v: u8) -> Self {
         self.part
             .set_attr("number:exponent-interval", v.to_string());
         self
     }
 
+    /// Use exponent_interval instead.
+    #[deprecated]
+    #[must_use]
+    pub fn expontent_interval(self, v: u8) -> Self {
+        self.exponent_interval(v)
+    }
+
     /// The number:forced-exponent-sign attribute specifies whether the sign of the exponent for a
     /// scientific number is always displayed.
     ///
@@ -433,7 +441,7 @@ impl<'vf, T: ValueFormatTrait> PartFractionBuilder<'vf, T> {
     #[must_use]
     pub fn min_integer_digits(mut self, v: u8) -> Self {
         self.part
-            .set_attr("number:min-numerator-digits", v.to_string());
+            .set_attr("number:min-integer-digits", v.to_string());
         self
     }
 
@@ -0,0 +1,525 @@
+//!
+//! Translates format codes as used by LibreOffice and Excel,
+//! e.g. "#,##0.00;[RED]-#,##0.00", into value formats.
+//!
+
+use crate::color::Rgb;
+use crate::condition::ValueCondition;
+use crate::format::{FormatPart, FormatPartType, ValueFormatTrait, ValueStyleMap};
+use crate::style::color_string;
+use crate::{OdsError, ValueType};
+
+/// Parses the format code and creates one value format per section.
+///
+/// With more than one section the last format is the one to use,
+/// it references the others via style-maps. The others are named
+/// name + "P0", name + "P1".
+pub(crate) fn parse_format_code<T, F>(name: &str, code: &str, new: F) -> Result<Vec<T>, OdsError>
+where
+    T: ValueFormatTrait,
+    F: Fn(String) -> T,
+{
+    let sections = split_sections(code)?;
+    let conditions = match sections.len() {
+        1 => Vec::new(),
+        2 => vec![ValueCondition::value_ge(0)],
+        3 => vec![ValueCondition::value_gt(0), ValueCondition::value_lt(0)],
+        _ => {
+            return Err(OdsError::Parse(
+                "format code with more than 3 sections",
+                Some(code.to_string()),
+            ))
+        }
+    };
+
+    let mut formats = Vec::new();
+    for (idx, section) in sections[..sections.len() - 1].iter().enumerate() {
+        let mut format = new(format!("{}P{}", name, idx));
+        format.attrmap_mut().set_attr("style:volatile", "true");
+        parse_section(&mut format, section, code)?;
+        formats.push(format);
+    }
+
+    let mut format = new(name.to_string());
+    parse_section(&mut format, sections[sections.len() - 1], code)?;
+    for (condition, applied) in conditions.into_iter().zip(formats.iter()) {
+        format.push_stylemap(ValueStyleMap::new(condition, applied.name()));
+    }
+    formats.push(format);
+
+    Ok(formats)
+}
+
+/// Splits at ';' outside of quotes and brackets.
+fn split_sections(code: &str) -> Result<Vec<&str>, OdsError> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut bracket = false;
+    let mut escaped = false;
+    for (idx, c) in code.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' | '_' | '*' if !quoted && !bracket => escaped = true,
+            '"' if !bracket => quoted = !quoted,
+            '[' if !quoted => bracket = true,
+            ']' if !quoted => bracket = false,
+            ';' if !quoted && !bracket => {
+                sections.push(&code[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    if quoted || bracket {
+        return Err(OdsError::Parse(
+            "unterminated quote or bracket in format code",
+            Some(code.to_string()),
+        ));
+    }
+    sections.push(&code[start..]);
+    Ok(sections)
+}
+
+fn parse_section<T: ValueFormatTrait>(
+    format: &mut T,
+    section: &str,
+    code: &str,
+) -> Result<(), OdsError> {
+    let mut parser = SectionParser {
+        datetime: matches!(
+            format.value_type(),
+            ValueType::DateTime | ValueType::TimeDuration
+        ),
+        format,
+        chars: section.chars().collect(),
+        pos: 0,
+        text: String::new(),
+        last: None,
+    };
+    parser.parse(code)
+}
+
+struct SectionParser<'a, T> {
+    format: &'a mut T,
+    datetime: bool,
+    chars: Vec<char>,
+    pos: usize,
+    /// Pending literal text.
+    text: String,
+    /// Last part pushed.
+    last: Option<FormatPartType>,
+}
+
+impl<T: ValueFormatTrait> SectionParser<'_, T> {
+    fn parse(&mut self, code: &str) -> Result<(), OdsError> {
+        while let Some(c) = self.peek(0) {
+            match c {
+                '"' => {
+                    let end = self.find('"', self.pos + 1);
+                    self.text.extend(&self.chars[self.pos + 1..end]);
+                    self.pos = end + 1;
+                }
+                '\\' => {
+                    if let Some(c) = self.peek(1) {
+                        self.text.push(c);
+                    }
+                    self.pos += 2;
+                }
+                '_' => {
+                    self.text.push(' ');
+                    self.pos += 2;
+                }
+                '*' => {
+                    if let Some(c) = self.peek(1) {
+                        let mut part = FormatPart::new(FormatPartType::FillCharacter);
+                        part.set_content(c.to_string());
+                        self.push(part);
+                    }
+                    self.pos += 2;
+                }
+                '[' => {
+                    let end = self.find(']', self.pos + 1);
+                    let content: String = self.chars[self.pos + 1..end].iter().collect();
+                    self.pos = end + 1;
+                    self.bracket(&content, code)?;
+                }
+                '@' => {
+                    return Err(OdsError::Parse(
+                        "text placeholder in format code",
+                        Some(code.to_string()),
+                    ));
+                }
+                '0' | '#' | '?' | ',' | '.' if !self.datetime => {
+                    self.number();
+                }
+                '$' | '€' | '£' | '¥'
+                    if self.format.value_type() == ValueType::Currency
+                        && !self.has_part(FormatPartType::CurrencySymbol) =>
+                {
+                    let mut part = FormatPart::new(FormatPartType::CurrencySymbol);
+                    part.set_content(c.to_string());
+                    self.push(part);
+                    self.pos += 1;
+                }
+                _ if self.starts_with("general") => {
+                    let mut part = FormatPart::new(FormatPartType::Number);
+                    part.set_attr("number:min-integer-digits", "1".to_string());
+                    self.push(part);
+                    self.pos += 7;
+                }
+                _ if self.datetime && c.is_ascii_alphabetic() => {
+                    self.datetime_part(c);
+                }
+                _ => {
+                    self.text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        self.flush();
+        Ok(())
+    }
+
+    /// Colors, currencies and elapsed time.
+    fn bracket(&mut self, content: &str, code: &str) -> Result<(), OdsError> {
+        if let Some(currency) = content.strip_prefix('$') {
+            let symbol = match currency.split_once('-') {
+                Some((symbol, _lcid)) => symbol,
+                None => currency,
+            };
+            if !symbol.is_empty() {
+                let mut part = FormatPart::new(FormatPartType::CurrencySymbol);
+                part.set_content(symbol.to_string());
+                self.push(part);
+            }
+        } else if let Some(color) = color(content) {
+            self.format
+                .textstyle_mut()
+                .set_attr("fo:color", color_string(color));
+        } else if self.datetime
+            && !content.is_empty()
+            && content
+                .chars()
+                .all(|c| matches!(c.to_ascii_lowercase(), 'h' | 'm' | 's'))
+        {
+            self.format
+                .attrmap_mut()
+                .set_attr("number:truncate-on-overflow", "false");
+            let part_type = match content.chars().next().map(|c| c.to_ascii_lowercase()) {
+                Some('h') => FormatPartType::Hours,
+                Some('m') => FormatPartType::Minutes,
+                _ => FormatPartType::Seconds,
+            };
+            self.push_styled(part_type, content.len() > 1);
+        } else {
+            return Err(OdsError::Parse(
+                "unsupported bracket in format code",
+                Some(code.to_string()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Digit placeholders with grouping, decimals, scientific notation
+    /// or fractions.
+    fn number(&mut self) {
+        let start = self.pos;
+        let digits = self.digits();
+        if digits.integer == 0 && digits.decimals == 0 {
+            // only separators.
+            self.text.extend(&self.chars[start..self.pos]);
+            return;
+        }
+
+        if digits.decimals == 0 {
+            if self.peek(0) == Some('/') {
+                self.fraction(None, digits);
+                return;
+            } else if self.peek(0) == Some(' ') && self.lookahead_fraction() {
+                self.pos += 1;
+                let numerator = self.digits();
+                self.fraction(Some(digits), numerator);
+                return;
+            }
+        }
+
+        let mut part = if matches!(self.peek(0), Some('E') | Some('e'))
+            && matches!(self.peek(1), Some('+') | Some('-'))
+            && matches!(self.peek(2), Some('0') | Some('#'))
+        {
+            let forced_sign = self.peek(1) == Some('+');
+            self.pos += 2;
+            let exponent = self.take_while(|c| matches!(c, '0' | '#'));
+            let mut part = FormatPart::new(FormatPartType::ScientificNumber);
+            part.set_attr(
+                "number:min-exponent-digits",
+                exponent.matches('0').count().to_string(),
+            );
+            if !forced_sign {
+                part.set_attr("number:forced-exponent-sign", "false".to_string());
+            }
+            part
+        } else {
+            let mut part = FormatPart::new(FormatPartType::Number);
+            if digits.scale > 0 {
+                part.set_attr(
+                    "number:display-factor",
+                    1000u64.pow(digits.scale).to_string(),
+                );
+            }
+            part
+        };
+        part.set_attr("number:min-integer-digits", digits.min_integer.to_string());
+        part.set_attr("number:decimal-places", digits.decimals.to_string());
+        part.set_attr("number:min-decimal-places", digits.min_decimals.to_string());
+        if digits.grouping {
+            part.set_attr("number:grouping", "true".to_string());
+        }
+        self.push(part);
+    }
+
+    /// Fraction, the current position is at the '/'.
+    fn fraction(&mut self, integer: Option<Digits>, numerator: Digits) {
+        self.pos += 1;
+
+        let mut part = FormatPart::new(FormatPartType::Fraction);
+        part.set_attr(
+            "number:min-integer-digits",
+            integer.map(|v| v.min_integer).unwrap_or(0).to_string(),
+        );
+        part.set_attr("number:min-numerator-digits", numerator.integer.to_string());
+        let denominator = self.take_while(|c| c.is_ascii_digit());
+        if !denominator.is_empty() {
+            part.set_attr("number:denominator-value", denominator);
+        } else {
+            let placeholders = self.take_while(|c| matches!(c, '0' | '#' | '?'));
+            part.set_attr(
+                "number:min-denominator-digits",
+                placeholders.len().to_string(),
+            );
+        }
+        if integer.map(|v| v.grouping).unwrap_or(false) {
+            part.set_attr("number:grouping", "true".to_string());
+        }
+        self.push(part);
+    }
+
+    /// Parses a run of placeholders and separators.
+    fn digits(&mut self) -> Digits {
+        let mut digits = Digits::default();
+        let mut in_decimals = false;
+        let mut commas = 0;
+
+        while let Some(c) = self.peek(0) {
+            match c {
+                '0' | '#' | '?' if in_decimals => {
+                    digits.decimals += 1;
+                    if c == '0' {
+                        digits.min_decimals += 1;
+                    }
+                }
+                '0' | '#' | '?' => {
+                    if commas > 0 && digits.integer > 0 {
+                        digits.grouping = true;
+                    }
+                    commas = 0;
+                    digits.integer += 1;
+                    if c == '0' {
+                        digits.min_integer += 1;
+                    }
+                }
+                ',' => commas += 1,
+                '.' if !in_decimals => {
+                    digits.scale += commas;
+                    commas = 0;
+                    in_decimals = true;
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        digits.scale += commas;
+
+        digits
+    }
+
+    /// Is this ' ?/?' the start of a fraction.
+    fn lookahead_fraction(&self) -> bool {
+        let mut idx = 1;
+        let mut placeholders = 0;
+        while let Some(c) = self.peek(idx) {
+            match c {
+                '0' | '#' | '?' => placeholders += 1,
+                '/' => return placeholders > 0,
+                _ => return false,
+            }
+            idx += 1;
+        }
+        false
+    }
+
+    /// Date and time letters.
+    fn datetime_part(&mut self, c: char) {
+        let lower = c.to_ascii_lowercase();
+        if lower == 'a' {
+            for am_pm in ["am/pm", "a/p"] {
+                if self.starts_with(am_pm) {
+                    self.push(FormatPart::new(FormatPartType::AmPm));
+                    self.pos += am_pm.len();
+                    return;
+                }
+            }
+        }
+
+        let count = self.chars[self.pos..]
+            .iter()
+            .take_while(|v| v.to_ascii_lowercase() == lower)
+            .count();
+        self.pos += count;
+
+        match lower {
+            'y' => self.push_styled(FormatPartType::Year, count > 2),
+            'm' if count > 2 => {
+                self.push_styled(FormatPartType::Month, count > 3);
+                if let Some(part) = self.format.parts_mut().last_mut() {
+                    part.set_attr("number:textual", "true".to_string());
+                }
+            }
+            'm' => {
+                if self.last == Some(FormatPartType::Hours) || self.next_letter() == Some('s') {
+                    self.push_styled(FormatPartType::Minutes, count > 1);
+                } else {
+                    self.push_styled(FormatPartType::Month, count > 1);
+                }
+            }
+            'd' if count > 2 => self.push_styled(FormatPartType::DayOfWeek, count > 3),
+            'd' => self.push_styled(FormatPartType::Day, count > 1),
+            'h' => self.push_styled(FormatPartType::Hours, count > 1),
+            's' => {
+                self.push_styled(FormatPartType::Seconds, count > 1);
+                if self.peek(0) == Some('.') && self.peek(1) == Some('0') {
+                    self.pos += 1;
+                    let decimals = self.take_while(|c| c == '0');
+                    if let Some(part) = self.format.parts_mut().last_mut() {
+                        part.set_attr("number:decimal-places", decimals.len().to_string());
+                    }
+                }
+            }
+            'q' => self.push_styled(FormatPartType::Quarter, count > 1),
+            'w' => self.push_styled(FormatPartType::WeekOfYear, false),
+            _ => {
+                self.text.extend(&self.chars[self.pos - count..self.pos]);
+            }
+        }
+    }
+
+    /// Next letter that is not part of a literal.
+    fn next_letter(&self) -> Option<char> {
+        let mut idx = self.pos;
+        while let Some(&c) = self.chars.get(idx) {
+            match c {
+                '"' => idx = self.find('"', idx + 1),
+                '\\' => idx += 1,
+                c if c.is_ascii_alphabetic() => return Some(c.to_ascii_lowercase()),
+                _ => {}
+            }
+            idx += 1;
+        }
+        None
+    }
+
+    fn push_styled(&mut self, part_type: FormatPartType, long: bool) {
+        let mut part = FormatPart::new(part_type);
+        if long {
+            part.set_attr("number:style", "long".to_string());
+        }
+        self.push(part);
+    }
+
+    fn push(&mut self, part: FormatPart) {
+        self.flush();
+        self.last = Some(part.part_type());
+        self.format.push_part(part);
+    }
+
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            let mut part = FormatPart::new(FormatPartType::Text);
+            part.set_content(std::mem::take(&mut self.text));
+            self.format.push_part(part);
+        }
+    }
+
+    fn has_part(&self, part_type: FormatPartType) -> bool {
+        self.format
+            .parts()
+            .iter()
+            .any(|v| v.part_type() == part_type)
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    /// Case-insensitive.
+    fn starts_with(&self, text: &str) -> bool {
+        let mut idx = self.pos;
+        for c in text.chars() {
+            match self.chars.get(idx) {
+                Some(v) if v.to_ascii_lowercase() == c => idx += 1,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, test: F) -> String {
+        let start = self.pos;
+        while matches!(self.peek(0), Some(c) if test(c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Position of the closing char or the end.
+    fn find(&self, c: char, start: usize) -> usize {
+        self.chars[start.min(self.chars.len())..]
+            .iter()
+            .position(|v| *v == c)
+            .map(|v| start + v)
+            .unwrap_or(self.chars.len())
+    }
+}
+
+/// Placeholders of a number.
+#[derive(Debug, Default, Clone, Copy)]
+struct Digits {
+    /// Integer placeholders.
+    integer: u32,
+    /// Integer '0' placeholders.
+    min_integer: u32,
+    grouping: bool,
+    decimals: u32,
+    min_decimals: u32,
+    /// Trailing ',' scale by 1000 each.
+    scale: u32,
+}
+
+/// Named colors.
+fn color(name: &str) -> Option<Rgb<u8>> {
+    match name.to_ascii_lowercase().as_str() {
+        "black" => Some(Rgb::new(0, 0, 0)),
+        "blue" => Some(Rgb::new(0, 0, 255)),
+        "cyan" => Some(Rgb::new(0, 255, 255)),
+        "green" => Some(Rgb::new(0, 255, 0)),
+        "magenta" => Some(Rgb::new(255, 0, 255)),
+        "red" => Some(Rgb::new(255, 0, 0)),
+        "white" => Some(Rgb::new(255, 255, 255)),
+        "yellow" => Some(Rgb::new(255, 255, 0)),
+        _ => None,
+    }
+}
@@ -1,4 +1,6 @@
-use crate::format::FormatNumberStyle;
+use crate::condition::ValueCondition;
+use crate::format::{FormatCalendarStyle, FormatNumberStyle, ValueFormatTrait, ValueStyleMap};
+use crate::value_::iso_currency_symbol;
 use crate::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
     ValueFormatPercentage, ValueFormatTimeDuration,
@@ -55,6 +57,23 @@ pub fn create_loc_percentage_format<S: AsRef<str>>(
     v
 }
 
+/// Creates a new fraction format, with an integer part and up to the
+/// given number of digits for the denominator.
+pub fn create_loc_fraction_format<S: AsRef<str>>(
+    name: S,
+    locale: Locale,
+    denominator_digits: u8,
+) -> ValueFormatNumber {
+    let mut v = ValueFormatNumber::new_localized(name, locale);
+    v.part_fraction()
+        .min_integer_digits(0)
+        .min_numerator_digits(1)
+        .min_denominator_digits(denominator_digits)
+        .max_denominator(10_i64.pow(denominator_digits as u32) - 1)
+        .build();
+    v
+}
+
 /// Creates a new currency format.
 pub fn create_loc_currency_prefix<S1, S2>(
     name: S1,
@@ -110,6 +129,52 @@ where
     v
 }
 
+/// Creates a currency format for an ISO 4217 currency code.
+///
+/// The symbol is looked up in a small built-in table, unknown codes are
+/// displayed as is. The locale decides whether the symbol goes before or
+/// after the number, the currency decides the number of decimal places.
+pub fn create_loc_currency_iso<S: AsRef<str>>(
+    name: S,
+    iso: &str,
+    locale: Locale,
+) -> ValueFormatCurrency {
+    let symbol = iso_currency_symbol(iso).unwrap_or(iso);
+    let decimals = iso_currency_decimals(iso);
+    // (symbol first, separated by a space)
+    let (prefix, space) = match (
+        locale.id.language.as_str(),
+        locale.id.region.as_ref().map(|v| v.as_str()),
+    ) {
+        ("de", Some("AT")) | ("de", Some("CH")) | ("nl", _) => (true, true),
+        ("en", _) | ("ja", _) | ("zh", _) | ("ko", _) | ("he", _) | ("tr", _) => (true, false),
+        _ => (false, true),
+    };
+
+    let mut v = ValueFormatCurrency::new_localized(name, locale.clone());
+    if prefix {
+        v.part_currency()
+            .locale(locale.clone())
+            .symbol(symbol)
+            .build();
+        if space {
+            v.part_text(" ").build();
+        }
+    }
+    v.part_number()
+        .min_integer_digits(1)
+        .fixed_decimal_places(decimals)
+        .grouping()
+        .build();
+    if !prefix {
+        if space {
+            v.part_text(" ").build();
+        }
+        v.part_currency().locale(locale).symbol(symbol).build();
+    }
+    v
+}
+
 /// Creates a new date format D.M.Y
 pub fn create_loc_date_dmy_format<S: AsRef<str>>(name: S, locale: Locale) -> ValueFormatDateTime {
     let mut v = ValueFormatDateTime::new_localized(name, locale);
@@ -183,6 +248,27 @@ pub fn create_boolean_format<S: AsRef<str>>(name: S) -> ValueFormatBoolean {
     v
 }
 
+/// Creates number formats that show a boolean value as a checkbox.
+///
+/// Returns the format for the checked state ☑ and the format for the
+/// unchecked state ☐. Both have to be added to the workbook, but only the
+/// second one is used with a cell-style, it switches to the first one
+/// via a style-map.
+pub fn create_checkbox_format<S: AsRef<str>>(name: S) -> (ValueFormatNumber, ValueFormatNumber) {
+    let mut checked = ValueFormatNumber::new_named(format!("{}_checked", name.as_ref()));
+    checked.set_volatile(true);
+    checked.part_text("\u{2611}").build();
+
+    let mut unchecked = ValueFormatNumber::new_named(name);
+    unchecked.part_text("\u{2610}").build();
+    unchecked.push_stylemap(ValueStyleMap::new(
+        ValueCondition::value_ne(0),
+        checked.name(),
+    ));
+
+    (checked, unchecked)
+}
+
 /// Creates a new number format.
 pub fn create_number_format<S: AsRef<str>>(
     name: S,
@@ -220,6 +306,43 @@ pub fn create_percentage_format<S: AsRef<str>>(name: S, decimal: u8) -> ValueFor
     v
 }
 
+/// Creates a new fraction format, with an integer part and up to the
+/// given number of digits for the denominator.
+pub fn create_fraction_format<S: AsRef<str>>(name: S, denominator_digits: u8) -> ValueFormatNumber {
+    let mut v = ValueFormatNumber::new_named(name);
+    v.part_fraction()
+        .min_integer_digits(0)
+        .min_numerator_digits(1)
+        .min_denominator_digits(denominator_digits)
+        .max_denominator(10_i64.pow(denominator_digits as u32) - 1)
+        .build();
+    v
+}
+
+/// Creates a new format in scientific notation, e.g. 1.23E+04.
+pub fn create_scientific_format<S: AsRef<str>>(name: S, decimal: u8) -> ValueFormatNumber {
+    let mut v = ValueFormatNumber::new_named(name);
+    v.part_scientific()
+        .decimal_places(decimal)
+        .min_integer_digits(1)
+        .min_exponent_digits(2)
+        .build();
+    v
+}
+
+/// Creates a new format in engineering notation, the exponent is
+/// always a multiple of 3, e.g. 12.3E+03.
+pub fn create_engineering_format<S: AsRef<str>>(name: S, decimal: u8) -> ValueFormatNumber {
+    let mut v = ValueFormatNumber::new_named(name);
+    v.part_scientific()
+        .decimal_places(decimal)
+        .min_integer_digits(1)
+        .min_exponent_digits(2)
+        .exponent_interval(3)
+        .build();
+    v
+}
+
 /// Creates a new currency format.
 pub fn create_currency_prefix<S1, S2>(
     name: S1,
@@ -260,6 +383,59 @@ where
     v
 }
 
+/// Creates an accounting format for an ISO 4217 currency code. The
+/// currency symbol is aligned left, the number right, negative numbers
+/// are shown in parentheses and zero as a dash.
+///
+/// The symbol and the number of decimal places are chosen as with
+/// create_loc_currency_iso().
+///
+/// Returns the formats for positive and negative numbers and, as last
+/// one, the format for zero. This one is used with a cell-style and
+/// references the others via style-maps. All of them must be added to
+/// the workbook.
+pub fn create_accounting_format<S: AsRef<str>>(
+    name: S,
+    locale: Locale,
+    iso: &str,
+) -> Vec<ValueFormatCurrency> {
+    let symbol = iso_currency_symbol(iso).unwrap_or(iso);
+    let decimals = iso_currency_decimals(iso);
+    let section = |prefix: &str, number: bool, suffix: &str| {
+        let mut v = ValueFormatCurrency::new_localized(name.as_ref(), locale.clone());
+        v.part_currency()
+            .locale(locale.clone())
+            .symbol(symbol)
+            .build();
+        v.part_fill_character().fill_char(' ').build();
+        v.part_text(prefix).build();
+        if number {
+            v.part_number()
+                .min_integer_digits(1)
+                .fixed_decimal_places(decimals)
+                .grouping()
+                .build();
+        }
+        v.part_text(suffix).build();
+        v
+    };
+
+    ValueFormatCurrency::with_sections(
+        section(" ", true, " "),
+        section(" (", true, ")"),
+        section(" ", false, "- "),
+    )
+}
+
+// Decimal places of the currency.
+fn iso_currency_decimals(iso: &str) -> u8 {
+    match iso {
+        "JPY" | "KRW" | "CLP" | "ISK" | "VND" => 0,
+        "BHD" | "JOD" | "KWD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
 /// Creates a new date format YYYY-MM-DD
 pub fn create_date_iso_format<S: AsRef<str>>(name: S) -> ValueFormatDateTime {
     let mut v = ValueFormatDateTime::new_named(name);
@@ -271,6 +447,38 @@ pub fn create_date_iso_format<S: AsRef<str>>(name: S) -> ValueFormatDateTime {
     v
 }
 
+/// Creates a date format Y-M-D in the given calendar.
+///
+/// The calendar is set for each part of the date. The Japanese years are
+/// counted per era, so for Gengou the era is written before the year.
+pub fn create_date_format_with_calendar<S: AsRef<str>>(
+    name: S,
+    calendar: FormatCalendarStyle,
+) -> ValueFormatDateTime {
+    let mut v = ValueFormatDateTime::new_named(name);
+    if calendar == FormatCalendarStyle::Gengou {
+        v.part_era()
+            .style(FormatNumberStyle::Short)
+            .calendar(calendar)
+            .build();
+    }
+    v.part_year()
+        .style(FormatNumberStyle::Long)
+        .calendar(calendar)
+        .build();
+    v.part_text("-").build();
+    v.part_month()
+        .style(FormatNumberStyle::Long)
+        .calendar(calendar)
+        .build();
+    v.part_text("-").build();
+    v.part_day()
+        .style(FormatNumberStyle::Long)
+        .calendar(calendar)
+        .build();
+    v
+}
+
 /// Creates a new date format D.M.Y
 pub fn create_date_dmy_format<S: AsRef<str>>(name: S) -> ValueFormatDateTime {
     let mut v = ValueFormatDateTime::new_named(name);
@@ -310,6 +518,37 @@ pub fn create_datetime_format<S: AsRef<str>>(name: S) -> ValueFormatDateTime {
     v
 }
 
+/// Creates an ISO 8601 datetime format YYYY-MM-DDTHH:MM:SS
+pub fn create_iso_datetime_format<S: AsRef<str>>(name: S) -> ValueFormatDateTime {
+    let mut v = ValueFormatDateTime::new_named(name);
+    v.part_year().style(FormatNumberStyle::Long).build();
+    v.part_text("-").build();
+    v.part_month().style(FormatNumberStyle::Long).build();
+    v.part_text("-").build();
+    v.part_day().style(FormatNumberStyle::Long).build();
+    v.part_text("T").build();
+    v.part_hours().style(FormatNumberStyle::Long).build();
+    v.part_text(":").build();
+    v.part_minutes().style(FormatNumberStyle::Long).build();
+    v.part_text(":").build();
+    v.part_seconds().style(FormatNumberStyle::Long).build();
+    v
+}
+
+/// Creates a week format YYYY-Www, e.g. 2024-W09.
+///
+/// This looks like an ISO 8601 week date, but it isn't one. ODF has no
+/// element for the week-based year, the year is the calendar year. The
+/// first days of January can show the last week of the previous year
+/// with the new year, e.g. 2021-W53 for 2021-01-01.
+pub fn create_year_week_format<S: AsRef<str>>(name: S) -> ValueFormatDateTime {
+    let mut v = ValueFormatDateTime::new_named(name);
+    v.part_year().style(FormatNumberStyle::Long).build();
+    v.part_text("-W").build();
+    v.part_week_of_year().build();
+    v
+}
+
 /// Creates a new time format H:M:S
 pub fn create_time_of_day_format<S: AsRef<str>>(name: S) -> ValueFormatTimeDuration {
     let mut v = ValueFormatTimeDuration::new_named(name);
@@ -35,15 +35,19 @@
 //
 
 mod builder;
+mod code;
 mod create;
 mod stylemap;
+mod view;
 
 pub use builder::*;
 pub use create::*;
 pub use stylemap::*;
+pub use view::*;
 
 use crate::attrmap2::AttrMap2;
 use crate::color::Rgb;
+use crate::condition::ValueCondition;
 use crate::style::units::{
     Angle, FontSize, FontStyle, FontVariant, FontWeight, FormatSource, Length, LetterSpacing,
     LineMode, LineStyle, LineType, LineWidth, Percent, RotationScale, TextCombine, TextCondition,
@@ -140,6 +144,15 @@ impl ValueFormatBoolean {
 valueformat!(ValueFormatNumber, ValueType::Number);
 
 impl ValueFormatNumber {
+    /// Creates the format from a format code like "#,##0.00;[RED]-#,##0.00".
+    ///
+    /// Each section of the code results in one format. With more than one
+    /// section the last format is the one to use, it references the others
+    /// via style-maps. All of them must be added to the workbook.
+    pub fn from_format_code<S: AsRef<str>>(name: S, code: &str) -> Result<Vec<Self>, OdsError> {
+        code::parse_format_code(name.as_ref(), code, Self::new_named)
+    }
+
     part_fill_character!();
     part_fraction!();
     part_number!();
@@ -157,6 +170,15 @@ impl ValueFormatNumber {
 valueformat!(ValueFormatPercentage, ValueType::Percentage);
 
 impl ValueFormatPercentage {
+    /// Creates the format from a format code like "0.00%".
+    ///
+    /// Each section of the code results in one format. With more than one
+    /// section the last format is the one to use, it references the others
+    /// via style-maps. All of them must be added to the workbook.
+    pub fn from_format_code<S: AsRef<str>>(name: S, code: &str) -> Result<Vec<Self>, OdsError> {
+        code::parse_format_code(name.as_ref(), code, Self::new_named)
+    }
+
     part_fill_character!();
     part_number!();
     part_text!();
@@ -170,6 +192,15 @@ impl ValueFormatPercentage {
 valueformat!(ValueFormatCurrency, ValueType::Currency);
 
 impl ValueFormatCurrency {
+    /// Creates the format from a format code like "[$€-407] #,##0.00;[RED]-[$€-407] #,##0.00".
+    ///
+    /// Each section of the code results in one format. With more than one
+    /// section the last format is the one to use, it references the others
+    /// via style-maps. All of them must be added to the workbook.
+    pub fn from_format_code<S: AsRef<str>>(name: S, code: &str) -> Result<Vec<Self>, OdsError> {
+        code::parse_format_code(name.as_ref(), code, Self::new_named)
+    }
+
     number_automatic_order!(attr);
 
     part_currency!();
@@ -181,6 +212,39 @@ impl ValueFormatCurrency {
     push_number!();
     push_number_fix!();
     push_text!();
+
+    /// Creates a currency format for an ISO 4217 currency code, e.g. "EUR".
+    /// See create_loc_currency_iso(). The format is named after the
+    /// currency and the locale, e.g. "EUR-de-AT".
+    pub fn for_iso(iso: &str, locale: Locale) -> Self {
+        let name = format!("{}-{}", iso, locale);
+        create_loc_currency_iso(name, iso, locale)
+    }
+
+    /// Returns the currency symbol as it is displayed by this format.
+    pub fn currency_symbol(&self) -> Option<&str> {
+        self.parts
+            .iter()
+            .find(|v| v.part_type == FormatPartType::CurrencySymbol)
+            .and_then(|v| v.content.as_deref())
+    }
+
+    /// Is the currency symbol displayed before the number?
+    /// Returns None if there is no currency symbol.
+    pub fn currency_symbol_first(&self) -> Option<bool> {
+        for part in &self.parts {
+            match part.part_type {
+                FormatPartType::CurrencySymbol => return Some(true),
+                FormatPartType::Number => break,
+                _ => {}
+            }
+        }
+        if self.currency_symbol().is_some() {
+            Some(false)
+        } else {
+            None
+        }
+    }
 }
 
 // 16.29.26 <number:text-style>
@@ -199,6 +263,15 @@ impl ValueFormatText {
 valueformat!(ValueFormatDateTime, ValueType::DateTime);
 
 impl ValueFormatDateTime {
+    /// Creates the format from a format code like "DD.MM.YYYY HH:MM".
+    ///
+    /// Each section of the code results in one format. With more than one
+    /// section the last format is the one to use, it references the others
+    /// via style-maps. All of them must be added to the workbook.
+    pub fn from_format_code<S: AsRef<str>>(name: S, code: &str) -> Result<Vec<Self>, OdsError> {
+        code::parse_format_code(name.as_ref(), code, Self::new_named)
+    }
+
     number_automatic_order!(attr);
     number_format_source!(attr);
 
@@ -234,6 +307,15 @@ impl ValueFormatDateTime {
 valueformat!(ValueFormatTimeDuration, ValueType::TimeDuration);
 
 impl ValueFormatTimeDuration {
+    /// Creates the format from a format code like "[HH]:MM:SS".
+    ///
+    /// Each section of the code results in one format. With more than one
+    /// section the last format is the one to use, it references the others
+    /// via style-maps. All of them must be added to the workbook.
+    pub fn from_format_code<S: AsRef<str>>(name: S, code: &str) -> Result<Vec<Self>, OdsError> {
+        code::parse_format_code(name.as_ref(), code, Self::new_named)
+    }
+
     number_format_source!(attr);
     number_truncate_on_overflow!(attr);
 
@@ -277,23 +359,61 @@ pub enum FormatPartType {
 }
 
 /// One structural part of a value format.
-#[derive(Debug, Clone, GetSize)]
+#[derive(Debug, Clone, PartialEq, GetSize)]
 pub struct FormatPart {
     /// What kind of format part is this?
     part_type: FormatPartType,
     /// Properties of this part.
     attr: AttrMap2,
-    /// Textposition for embedded text when acting as a number format part.
-    ///
+    /// Embedded text when acting as a number format part.
+    embedded_text: Vec<EmbeddedText>,
+    /// Some content.
+    content: Option<String>,
+}
+
+/// The number:embedded-text element of a number format part.
+/// Specifies text that is displayed at one specific position within a number.
+#[derive(Debug, Clone, PartialEq, Eq, GetSize)]
+pub struct EmbeddedText {
     /// The number:position attribute specifies the position where text appears.
     /// The index of a position starts with 1 and is counted by digits from right to left in the integer part of
     /// a number, starting left from a decimal separator if one exists, or from the last digit of the number.
     /// Text is inserted before the digit at the specified position. If the value of number:position
     /// attribute is greater than the value of number:min-integer-digits and greater than
     /// the number of integer digits in the number, text is prepended to the number.
-    position: Option<i32>,
-    /// Some content.
-    content: Option<String>,
+    position: i32,
+    /// The text.
+    text: String,
+}
+
+impl EmbeddedText {
+    /// New embedded text.
+    pub fn new<S: Into<String>>(position: i32, text: S) -> Self {
+        Self {
+            position,
+            text: text.into(),
+        }
+    }
+
+    /// Position of the text.
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Sets the position of the text.
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+
+    /// The text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Sets the text.
+    pub fn set_text<S: Into<String>>(&mut self, text: S) {
+        self.text = text.into();
+    }
 }
 
 /// Flag for several PartTypes.
@@ -313,6 +433,20 @@ impl Display for FormatNumberStyle {
     }
 }
 
+impl ParseStyleAttr<FormatNumberStyle> for FormatNumberStyle {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<FormatNumberStyle>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "short" => Ok(Some(FormatNumberStyle::Short)),
+                "long" => Ok(Some(FormatNumberStyle::Long)),
+                _ => Err(OdsError::Parse("invalid style", Some(attr.to_string()))),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// Calendar types.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[allow(missing_docs)]
@@ -340,13 +474,32 @@ impl Display for FormatCalendarStyle {
     }
 }
 
+impl ParseStyleAttr<FormatCalendarStyle> for FormatCalendarStyle {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<FormatCalendarStyle>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "gregorian" => Ok(Some(FormatCalendarStyle::Gregorian)),
+                "gengou" => Ok(Some(FormatCalendarStyle::Gengou)),
+                "ROC" => Ok(Some(FormatCalendarStyle::Roc)),
+                "hanja" => Ok(Some(FormatCalendarStyle::Hanja)),
+                "hijri" => Ok(Some(FormatCalendarStyle::Hijri)),
+                "jewish" => Ok(Some(FormatCalendarStyle::Jewish)),
+                "buddhist" => Ok(Some(FormatCalendarStyle::Buddhist)),
+                _ => Err(OdsError::Parse("invalid calendar", Some(attr.to_string()))),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl FormatPart {
     /// New, empty
     pub fn new(ftype: FormatPartType) -> Self {
         FormatPart {
             part_type: ftype,
             attr: Default::default(),
-            position: None,
+            embedded_text: Default::default(),
             content: None,
         }
     }
@@ -384,19 +537,48 @@ impl FormatPart {
         self.attr.attr_def(name, default)
     }
 
-    /// Sets the position for embedded text in a number format part.
+    /// Calendar of a date part. None if the attribute is not set or
+    /// contains a calendar not known here.
+    pub fn calendar(&self) -> Option<FormatCalendarStyle> {
+        FormatCalendarStyle::parse_attr(self.attr.attr("number:calendar"))
+            .ok()
+            .flatten()
+    }
+
+    /// Adds an embedded text to a number format part.
+    pub fn push_embedded_text<S: Into<String>>(&mut self, position: i32, text: S) {
+        self.embedded_text.push(EmbeddedText::new(position, text));
+    }
+
+    /// Embedded texts of a number format part.
+    pub fn embedded_text(&self) -> &[EmbeddedText] {
+        &self.embedded_text
+    }
+
+    /// Embedded texts of a number format part.
+    pub fn embedded_text_mut(&mut self) -> &mut Vec<EmbeddedText> {
+        &mut self.embedded_text
+    }
+
+    /// Sets the position of the first embedded text in a number format part.
+    #[deprecated(note = "use push_embedded_text()")]
     pub fn set_position(&mut self, pos: i32) {
-        self.position = Some(pos);
+        match self.embedded_text.first_mut() {
+            Some(v) => v.position = pos,
+            None => self.embedded_text.push(EmbeddedText::new(pos, "")),
+        }
     }
 
-    /// Clear the position for embedded text in a number format part.
+    /// Removes all embedded text in a number format part.
+    #[deprecated(note = "use embedded_text_mut().clear()")]
     pub fn clear_position(&mut self) {
-        self.position = None;
+        self.embedded_text.clear();
     }
 
-    /// The position for embedded text in a number format part.
+    /// The position of the first embedded text in a number format part.
+    #[deprecated(note = "use embedded_text()")]
     pub fn position(&self) -> Option<i32> {
-        self.position
+        self.embedded_text.first().map(|v| v.position)
     }
 
     /// Sets a textual content for this part. This is only used
@@ -0,0 +1,249 @@
+use crate::format::{
+    EmbeddedText, FormatCalendarStyle, FormatNumberStyle, FormatPart, FormatPartType,
+};
+use crate::style::ParseStyleAttr;
+
+fn attr_u8(part: &FormatPart, name: &str) -> Option<u8> {
+    part.attrmap().attr(name).and_then(|v| v.parse().ok())
+}
+
+fn attr_i64(part: &FormatPart, name: &str) -> Option<i64> {
+    part.attrmap().attr(name).and_then(|v| v.parse().ok())
+}
+
+fn attr_bool(part: &FormatPart, name: &str) -> bool {
+    part.attrmap().attr(name) == Some("true")
+}
+
+impl FormatPart {
+    /// Typed access to a number part.
+    ///
+    /// ```
+    /// use spreadsheet_ods::format::ValueFormatTrait;
+    /// use spreadsheet_ods::ValueFormatNumber;
+    ///
+    /// let mut v = ValueFormatNumber::new_named("n2");
+    /// v.part_number().fixed_decimal_places(2).grouping().build();
+    ///
+    /// let number = v.parts()[0].as_number().expect("number");
+    /// assert_eq!(number.decimal_places(), Some(2));
+    /// assert!(number.grouping());
+    /// ```
+    pub fn as_number(&self) -> Option<NumberPartView<'_>> {
+        (self.part_type() == FormatPartType::Number).then_some(NumberPartView { part: self })
+    }
+
+    /// Typed access to a scientific number part.
+    pub fn as_scientific(&self) -> Option<ScientificPartView<'_>> {
+        (self.part_type() == FormatPartType::ScientificNumber)
+            .then_some(ScientificPartView { part: self })
+    }
+
+    /// Typed access to a fraction part.
+    pub fn as_fraction(&self) -> Option<FractionPartView<'_>> {
+        (self.part_type() == FormatPartType::Fraction).then_some(FractionPartView { part: self })
+    }
+
+    /// Typed access to the date and time parts, from day to seconds.
+    pub fn as_datetime(&self) -> Option<DateTimePartView<'_>> {
+        match self.part_type() {
+            FormatPartType::Day
+            | FormatPartType::Month
+            | FormatPartType::Year
+            | FormatPartType::Era
+            | FormatPartType::DayOfWeek
+            | FormatPartType::WeekOfYear
+            | FormatPartType::Quarter
+            | FormatPartType::Hours
+            | FormatPartType::Minutes
+            | FormatPartType::Seconds => Some(DateTimePartView { part: self }),
+            _ => None,
+        }
+    }
+}
+
+/// Read access to a number part.
+#[derive(Debug, Clone, Copy)]
+pub struct NumberPartView<'a> {
+    part: &'a FormatPart,
+}
+
+impl<'a> NumberPartView<'a> {
+    /// The underlying part.
+    pub fn part(&self) -> &'a FormatPart {
+        self.part
+    }
+
+    /// Maximum number of decimal places.
+    pub fn decimal_places(&self) -> Option<u8> {
+        attr_u8(self.part, "number:decimal-places")
+    }
+
+    /// Minimum number of decimal places.
+    pub fn min_decimal_places(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-decimal-places")
+    }
+
+    /// Minimum number of integer digits.
+    pub fn min_integer_digits(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-integer-digits")
+    }
+
+    /// Thousands separator.
+    pub fn grouping(&self) -> bool {
+        attr_bool(self.part, "number:grouping")
+    }
+
+    /// The number is divided by this factor before display.
+    pub fn display_factor(&self) -> Option<f64> {
+        self.part
+            .attrmap()
+            .attr("number:display-factor")
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Replacement text for the decimal places of an integer.
+    pub fn decimal_replacement(&self) -> Option<&'a str> {
+        self.part.attrmap().attr("number:decimal-replacement")
+    }
+
+    /// Text within the number.
+    pub fn embedded_text(&self) -> &'a [EmbeddedText] {
+        self.part.embedded_text()
+    }
+}
+
+/// Read access to a scientific number part.
+#[derive(Debug, Clone, Copy)]
+pub struct ScientificPartView<'a> {
+    part: &'a FormatPart,
+}
+
+impl<'a> ScientificPartView<'a> {
+    /// The underlying part.
+    pub fn part(&self) -> &'a FormatPart {
+        self.part
+    }
+
+    /// Maximum number of decimal places.
+    pub fn decimal_places(&self) -> Option<u8> {
+        attr_u8(self.part, "number:decimal-places")
+    }
+
+    /// Minimum number of decimal places.
+    pub fn min_decimal_places(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-decimal-places")
+    }
+
+    /// Minimum number of integer digits.
+    pub fn min_integer_digits(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-integer-digits")
+    }
+
+    /// Minimum number of digits of the exponent.
+    pub fn min_exponent_digits(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-exponent-digits")
+    }
+
+    /// The exponent is a multiple of this value.
+    pub fn exponent_interval(&self) -> Option<u8> {
+        attr_u8(self.part, "number:exponent-interval")
+    }
+
+    /// The exponent always has a sign. None if not set, which
+    /// defaults to true.
+    pub fn forced_exponent_sign(&self) -> Option<bool> {
+        self.part
+            .attrmap()
+            .attr("number:forced-exponent-sign")
+            .map(|v| v == "true")
+    }
+
+    /// Thousands separator.
+    pub fn grouping(&self) -> bool {
+        attr_bool(self.part, "number:grouping")
+    }
+}
+
+/// Read access to a fraction part.
+#[derive(Debug, Clone, Copy)]
+pub struct FractionPartView<'a> {
+    part: &'a FormatPart,
+}
+
+impl<'a> FractionPartView<'a> {
+    /// The underlying part.
+    pub fn part(&self) -> &'a FormatPart {
+        self.part
+    }
+
+    /// Minimum number of integer digits.
+    pub fn min_integer_digits(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-integer-digits")
+    }
+
+    /// Minimum number of digits of the numerator.
+    pub fn min_numerator_digits(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-numerator-digits")
+    }
+
+    /// Minimum number of digits of the denominator.
+    pub fn min_denominator_digits(&self) -> Option<u8> {
+        attr_u8(self.part, "number:min-denominator-digits")
+    }
+
+    /// Fixed denominator.
+    pub fn denominator(&self) -> Option<i64> {
+        attr_i64(self.part, "number:denominator-value")
+    }
+
+    /// Maximum denominator.
+    pub fn max_denominator(&self) -> Option<i64> {
+        attr_i64(self.part, "number:max-denominator-value")
+    }
+
+    /// Thousands separator.
+    pub fn grouping(&self) -> bool {
+        attr_bool(self.part, "number:grouping")
+    }
+}
+
+/// Read access to a date or time part.
+#[derive(Debug, Clone, Copy)]
+pub struct DateTimePartView<'a> {
+    part: &'a FormatPart,
+}
+
+impl<'a> DateTimePartView<'a> {
+    /// The underlying part.
+    pub fn part(&self) -> &'a FormatPart {
+        self.part
+    }
+
+    /// Short or long style. None if not set, which defaults to short.
+    pub fn style(&self) -> Option<FormatNumberStyle> {
+        FormatNumberStyle::parse_attr(self.part.attrmap().attr("number:style"))
+            .ok()
+            .flatten()
+    }
+
+    /// Calendar.
+    pub fn calendar(&self) -> Option<FormatCalendarStyle> {
+        self.part.calendar()
+    }
+
+    /// Month as name instead of a number.
+    pub fn textual(&self) -> bool {
+        attr_bool(self.part, "number:textual")
+    }
+
+    /// Possessive form of the month name.
+    pub fn possessive_form(&self) -> bool {
+        attr_bool(self.part, "number:possessive-form")
+    }
+
+    /// Decimal places of the seconds.
+    pub fn decimal_places(&self) -> Option<u8> {
+        attr_u8(self.part, "number:decimal-places")
+    }
+}
@@ -2,7 +2,13 @@
 //! For now defines functions to create cell references for formulas.
 //!
 
-use crate::refs::{CellRange, CellRef};
+use crate::attrmap2::AttrMap2;
+use crate::refs::{
+    format_cellranges, parse_cellrange, parse_cellranges, parse_cellref, parse_colrange,
+    parse_rowrange, CellRange, CellRef, ColRange, RowRange,
+};
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::HashMap;
 
 /// Simple macro for formula.
 #[macro_export]
@@ -520,3 +526,297 @@ pub fn frangerefa_table<S: Into<String>>(
         .absolute()
         .to_formula()
 }
+
+/// Moves the relative cell references in a formula by the given number
+/// of rows and columns, as happens when a formula is copied to another
+/// cell. Absolute references stay as they are.
+///
+/// References that would end up outside of the sheet are replaced
+/// with #REF!. Column and row ranges are not changed.
+pub fn move_formula_refs(formula: &str, rows: i64, cols: i64) -> String {
+    map_formula_refs(formula, |reference| move_ref(reference, rows, cols))
+}
+
+/// Changes the references in formulas and attributes.
+pub(crate) trait MapRefs {
+    /// Changes a cell reference. Returns true if it changed.
+    fn cellref(&self, cell: &mut CellRef) -> bool;
+
+    /// Changes a cell range. Returns true if it changed.
+    fn cellrange(&self, range: &mut CellRange) -> bool;
+
+    /// Changes a column range. Returns true if it changed.
+    fn colrange(&self, _range: &mut ColRange) -> bool {
+        false
+    }
+
+    /// Changes a row range. Returns true if it changed.
+    fn rowrange(&self, _range: &mut RowRange) -> bool {
+        false
+    }
+}
+
+/// Replaces the sheet names after some sheets have been renamed.
+/// The map goes from the old to the new name. References to external
+/// files are not changed.
+pub(crate) struct RenameSheets<'a>(pub(crate) &'a HashMap<String, String>);
+
+impl RenameSheets<'_> {
+    fn new_name(&self, iri: Option<&String>, table: Option<&String>) -> Option<String> {
+        if iri.is_some() {
+            None
+        } else {
+            table.and_then(|v| self.0.get(v)).cloned()
+        }
+    }
+}
+
+impl MapRefs for RenameSheets<'_> {
+    fn cellref(&self, cell: &mut CellRef) -> bool {
+        if let Some(table) = self.new_name(cell.iri(), cell.table()) {
+            cell.set_table(table);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn cellrange(&self, range: &mut CellRange) -> bool {
+        let table = self.new_name(range.iri(), range.table());
+        let to_table = self.new_name(range.iri(), range.to_table());
+        let changed = table.is_some() || to_table.is_some();
+        if let Some(table) = table {
+            range.set_table(table);
+        }
+        if let Some(to_table) = to_table {
+            range.set_to_table(to_table);
+        }
+        changed
+    }
+
+    fn colrange(&self, range: &mut ColRange) -> bool {
+        let table = self.new_name(range.iri(), range.table());
+        let to_table = self.new_name(range.iri(), range.to_table());
+        let changed = table.is_some() || to_table.is_some();
+        if let Some(table) = table {
+            range.set_table(table);
+        }
+        if let Some(to_table) = to_table {
+            range.set_to_table(to_table);
+        }
+        changed
+    }
+
+    fn rowrange(&self, range: &mut RowRange) -> bool {
+        let table = self.new_name(range.iri(), range.table());
+        let to_table = self.new_name(range.iri(), range.to_table());
+        let changed = table.is_some() || to_table.is_some();
+        if let Some(table) = table {
+            range.set_table(table);
+        }
+        if let Some(to_table) = to_table {
+            range.set_to_table(to_table);
+        }
+        changed
+    }
+}
+
+/// Adjusts the references after the row of the sheet has been repeated
+/// n more times. References to rows below move down, ranges that end at
+/// the row are extended to cover the copies.
+///
+/// With local set, references without a sheet name count as references
+/// to the sheet too.
+pub(crate) struct RepeatRow<'a> {
+    pub(crate) sheet: &'a str,
+    pub(crate) local: bool,
+    pub(crate) row: u32,
+    pub(crate) n: u32,
+}
+
+impl RepeatRow<'_> {
+    fn same_sheet(&self, iri: Option<&String>, table: Option<&String>) -> bool {
+        iri.is_none() && table.map_or(self.local, |v| v == self.sheet)
+    }
+}
+
+impl MapRefs for RepeatRow<'_> {
+    fn cellref(&self, cell: &mut CellRef) -> bool {
+        if !self.same_sheet(cell.iri(), cell.table()) || cell.row() <= self.row {
+            return false;
+        }
+        cell.set_row(cell.row().saturating_add(self.n));
+        true
+    }
+
+    fn cellrange(&self, range: &mut CellRange) -> bool {
+        if !self.same_sheet(range.iri(), range.table()) || range.to_row() < self.row {
+            return false;
+        }
+        if range.row() > self.row {
+            range.set_row(range.row().saturating_add(self.n));
+        }
+        range.set_to_row(range.to_row().saturating_add(self.n));
+        true
+    }
+}
+
+/// Changes the references of a formula.
+pub(crate) fn map_refs(formula: &str, m: &dyn MapRefs) -> String {
+    map_formula_refs(formula, |reference| {
+        map_address(reference, m).map(|v| format!("[{}]", v))
+    })
+}
+
+/// Changes a reference or a list of references as used in attributes,
+/// without brackets. Returns None if nothing changes.
+pub(crate) fn map_address(address: &str, m: &dyn MapRefs) -> Option<String> {
+    if let Ok(mut cell) = parse_cellref(address) {
+        m.cellref(&mut cell).then(|| cell.to_string())
+    } else if let Ok(mut range) = parse_cellrange(address) {
+        m.cellrange(&mut range).then(|| range.to_string())
+    } else if let Ok(mut range) = parse_colrange(address) {
+        m.colrange(&mut range).then(|| range.to_string())
+    } else if let Ok(mut range) = parse_rowrange(address) {
+        m.rowrange(&mut range).then(|| range.to_string())
+    } else if let Ok(Some(mut ranges)) = parse_cellranges(address) {
+        let mut changed = false;
+        for range in ranges.iter_mut() {
+            changed |= m.cellrange(range);
+        }
+        changed.then(|| format_cellranges(&ranges).to_string())
+    } else {
+        None
+    }
+}
+
+/// Changes the references in the attributes of the xml and all
+/// contained tags.
+pub(crate) fn map_xml_refs(tag: &mut XmlTag, m: &dyn MapRefs) {
+    map_attr_refs(tag.attrmap_mut(), m);
+    for content in tag.content_mut() {
+        if let XmlContent::Tag(tag) = content {
+            map_xml_refs(tag, m);
+        }
+    }
+}
+
+/// Changes the references in the attributes. Attributes named
+/// "*-address" contain plain references, all others are treated
+/// as formulas.
+pub(crate) fn map_attr_refs(attr: &mut AttrMap2, m: &dyn MapRefs) {
+    let mut changed = Vec::new();
+    for (name, value) in attr.iter() {
+        let new_value = if name.ends_with("-address") {
+            map_address(value, m)
+        } else if value.contains('[') {
+            Some(map_refs(value, m)).filter(|v| v != value)
+        } else {
+            None
+        };
+        if let Some(new_value) = new_value {
+            changed.push((name.to_string(), new_value));
+        }
+    }
+    for (name, value) in changed {
+        attr.set_attr(&name, value);
+    }
+}
+
+// Runs the function for every reference in the formula and replaces
+// the reference if it returns Some.
+fn map_formula_refs<F>(formula: &str, f: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let bytes = formula.as_bytes();
+    let mut buf = String::with_capacity(formula.len());
+    let mut last = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                // string literal, a doubled quote is part of the string.
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == b'"' {
+                        if bytes.get(i + 1) == Some(&b'"') {
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'[' => {
+                let start = i;
+                let mut quoted = false;
+                i += 1;
+                while i < bytes.len() && (quoted || bytes[i] != b']') {
+                    if bytes[i] == b'\'' {
+                        quoted = !quoted;
+                    }
+                    i += 1;
+                }
+                if i < bytes.len() {
+                    if let Some(moved) = f(&formula[start + 1..i]) {
+                        buf.push_str(&formula[last..start]);
+                        buf.push_str(&moved);
+                        last = i + 1;
+                    }
+                }
+                i += 1;
+            }
+            _ => {
+                i += 1;
+            }
+        }
+    }
+    buf.push_str(&formula[last..]);
+    buf
+}
+
+// Moves a single reference. Returns None if this is no cell reference.
+fn move_ref(reference: &str, rows: i64, cols: i64) -> Option<String> {
+    if let Ok(mut range) = parse_cellrange(reference) {
+        match (
+            move_pos(range.row(), range.row_abs(), rows),
+            move_pos(range.col(), range.col_abs(), cols),
+            move_pos(range.to_row(), range.to_row_abs(), rows),
+            move_pos(range.to_col(), range.to_col_abs(), cols),
+        ) {
+            (Some(row), Some(col), Some(to_row), Some(to_col)) => {
+                range.set_row(row);
+                range.set_col(col);
+                range.set_to_row(to_row);
+                range.set_to_col(to_col);
+                Some(range.to_formula())
+            }
+            _ => Some("#REF!".to_string()),
+        }
+    } else if let Ok(mut cell) = parse_cellref(reference) {
+        match (
+            move_pos(cell.row(), cell.row_abs(), rows),
+            move_pos(cell.col(), cell.col_abs(), cols),
+        ) {
+            (Some(row), Some(col)) => {
+                cell.set_row(row);
+                cell.set_col(col);
+                Some(cell.to_formula())
+            }
+            _ => Some("#REF!".to_string()),
+        }
+    } else {
+        None
+    }
+}
+
+fn move_pos(pos: u32, abs: bool, delta: i64) -> Option<u32> {
+    if abs {
+        Some(pos)
+    } else {
+        u32::try_from(pos as i64 + delta).ok()
+    }
+}
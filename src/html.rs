@@ -0,0 +1,539 @@
+//!
+//! Exports a range of a sheet as html table.
+//!
+//! The values are formatted with their value format, as far as this
+//! is possible without the locale data of LibreOffice. Decimal and
+//! grouping separators are always '.' and ','. Bold, italic, underline,
+//! colors, borders and alignment of the cell styles are written as
+//! inline css. Merged cells get a colspan/rowspan, or their content is
+//! repeated or blanked in the covered cells, see [MergedCells].
+//!
+//! ```
+//! use spreadsheet_ods::{CellRange, Sheet, WorkBook};
+//! use spreadsheet_ods::html::HtmlOptions;
+//!
+//! let mut book = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("report");
+//! sheet.set_value(0, 0, "total");
+//! sheet.set_value(0, 1, 1234.5);
+//! book.push_sheet(sheet);
+//!
+//! let html = book
+//!     .sheet(0)
+//!     .to_html(&book, &CellRange::local(0, 0, 0, 1), HtmlOptions::default());
+//! assert!(html.contains("<td>total</td>"));
+//! ```
+
+use crate::format::{FormatPart, FormatPartType, ValueFormatTrait};
+use crate::sheet::MergedCells;
+use crate::style::CellStyle;
+use crate::{CellRange, Sheet, Value, WorkBook};
+use chrono::{Duration, NaiveDateTime, Timelike};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Options for the html export.
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    styles: bool,
+    formats: bool,
+    class: Option<String>,
+    merged: MergedCells,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            styles: true,
+            formats: true,
+            class: None,
+            merged: MergedCells::Span,
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Write the cell styles as inline css. Default is true.
+    pub fn styles(mut self, styles: bool) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Format the values with their value format. Default is true.
+    /// Otherwise the plain values are written.
+    pub fn formats(mut self, formats: bool) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Class attribute for the table element.
+    pub fn class<S: Into<String>>(mut self, class: S) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+
+    /// How merged cells are written. Default is MergedCells::Span.
+    pub fn merged(mut self, merged: MergedCells) -> Self {
+        self.merged = merged;
+        self
+    }
+}
+
+impl Sheet {
+    /// Exports the range as html table. The book is needed for the
+    /// styles and value formats.
+    pub fn to_html(&self, book: &WorkBook, range: &CellRange, options: HtmlOptions) -> String {
+        let mut buf = String::new();
+        let mut covered = HashSet::new();
+
+        let empty = Value::Empty;
+        let mut merged = HashMap::new();
+        let mut origins = HashMap::new();
+        if options.merged == MergedCells::Span {
+            merged = merged_spans(self, range);
+        } else {
+            origins = merged_origins(self, range);
+        }
+
+        match &options.class {
+            Some(class) => {
+                let _ = writeln!(buf, "<table class=\"{}\">", escape(class));
+            }
+            None => buf.push_str("<table>\n"),
+        }
+        for row in range.row()..=range.to_row() {
+            buf.push_str("<tr>");
+            for col in range.col()..=range.to_col() {
+                if covered.contains(&(row, col)) {
+                    continue;
+                }
+
+                let ((o_row, o_col), row_span, col_span) = merged
+                    .get(&(row, col))
+                    .copied()
+                    .unwrap_or(((row, col), 1, 1));
+
+                let (value, style) = match origins.get(&(row, col)) {
+                    Some(_) if options.merged == MergedCells::Blank => {
+                        (&empty, cell_style(book, self, row, col))
+                    }
+                    Some((o_row, o_col)) => (
+                        self.value(*o_row, *o_col),
+                        cell_style(book, self, *o_row, *o_col),
+                    ),
+                    None => (
+                        self.value(o_row, o_col),
+                        cell_style(book, self, o_row, o_col),
+                    ),
+                };
+
+                buf.push_str("<td");
+                if row_span > 1 {
+                    let _ = write!(buf, " rowspan=\"{}\"", row_span);
+                }
+                if col_span > 1 {
+                    let _ = write!(buf, " colspan=\"{}\"", col_span);
+                }
+                for r in row..row + row_span {
+                    for c in col..col + col_span {
+                        covered.insert((r, c));
+                    }
+                }
+                if options.styles {
+                    let css = css(value, style);
+                    if !css.is_empty() {
+                        let _ = write!(buf, " style=\"{}\"", escape(&css));
+                    }
+                }
+                buf.push('>');
+
+                let text = value_text(book, value, style, options.formats);
+                buf.push_str(&escape(&text).replace('\n', "<br>"));
+
+                buf.push_str("</td>");
+            }
+            buf.push_str("</tr>\n");
+        }
+        buf.push_str("</table>\n");
+
+        buf
+    }
+}
+
+// Origin, row span and column span of a merge.
+type MergedSpan = ((u32, u32), u32, u32);
+
+// Merged ranges that overlap the exported range, by their first
+// visible cell. A merge that starts outside is clipped and shows
+// the content of its origin.
+fn merged_spans(sheet: &Sheet, range: &CellRange) -> HashMap<(u32, u32), MergedSpan> {
+    let mut merged = HashMap::new();
+    for merge in sheet.merged_ranges() {
+        let row = merge.row().max(range.row());
+        let col = merge.col().max(range.col());
+        let to_row = merge.to_row().min(range.to_row());
+        let to_col = merge.to_col().min(range.to_col());
+        if row <= to_row && col <= to_col {
+            merged.insert(
+                (row, col),
+                (
+                    (merge.row(), merge.col()),
+                    to_row - row + 1,
+                    to_col - col + 1,
+                ),
+            );
+        }
+    }
+    merged
+}
+
+/// The origin of the merge for all covered cells in the range.
+pub(crate) fn merged_origins(sheet: &Sheet, range: &CellRange) -> HashMap<(u32, u32), (u32, u32)> {
+    let mut origins = HashMap::new();
+    for merge in sheet.merged_ranges() {
+        let row = merge.row().max(range.row());
+        let col = merge.col().max(range.col());
+        let to_row = merge.to_row().min(range.to_row());
+        let to_col = merge.to_col().min(range.to_col());
+        for r in row..=to_row {
+            for c in col..=to_col {
+                if (r, c) != (merge.row(), merge.col()) {
+                    origins.insert((r, c), (merge.row(), merge.col()));
+                }
+            }
+        }
+    }
+    origins
+}
+
+/// Cell style of the cell or the default of its column.
+pub(crate) fn cell_style<'a>(
+    book: &'a WorkBook,
+    sheet: &Sheet,
+    row: u32,
+    col: u32,
+) -> Option<&'a CellStyle> {
+    sheet
+        .cellstyle(row, col)
+        .or_else(|| sheet.col_cellstyle(col))
+        .and_then(|v| book.cellstyle(v))
+}
+
+/// The value as text, formatted with its value format or plain.
+pub(crate) fn value_text(
+    book: &WorkBook,
+    value: &Value,
+    style: Option<&CellStyle>,
+    formats: bool,
+) -> String {
+    if formats {
+        format_value(book, value, style)
+    } else {
+        plain_value(value)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut buf = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            c => buf.push(c),
+        }
+    }
+    buf
+}
+
+fn css(value: &Value, style: Option<&CellStyle>) -> String {
+    let mut css = Vec::new();
+
+    let mut align = None;
+    if let Some(style) = style {
+        let text = style.textstyle();
+        if let Some(v) = text.attr("fo:font-weight").filter(|v| *v != "normal") {
+            css.push(format!("font-weight:{}", v));
+        }
+        if let Some(v) = text.attr("fo:font-style").filter(|v| *v != "normal") {
+            css.push(format!("font-style:{}", v));
+        }
+        if text
+            .attr("style:text-underline-style")
+            .filter(|v| *v != "none")
+            .is_some()
+        {
+            css.push("text-decoration:underline".to_string());
+        }
+        if let Some(v) = text.attr("fo:font-size") {
+            css.push(format!("font-size:{}", v));
+        }
+        if let Some(v) = text.attr("fo:color") {
+            css.push(format!("color:{}", v));
+        }
+
+        let cell = style.cellstyle();
+        if let Some(v) = cell.attr("fo:background-color") {
+            css.push(format!("background-color:{}", v));
+        }
+        for border in [
+            "fo:border",
+            "fo:border-top",
+            "fo:border-bottom",
+            "fo:border-left",
+            "fo:border-right",
+        ] {
+            if let Some(v) = cell.attr(border) {
+                css.push(format!("{}:{}", &border[3..], v));
+            }
+        }
+
+        align = match style.paragraphstyle().attr("fo:text-align") {
+            Some("start") | Some("inside") => Some("left"),
+            Some("end") | Some("outside") => Some("right"),
+            Some(v) => Some(v),
+            None => None,
+        };
+    }
+    let align = align.or(match value {
+        Value::Number(_)
+        | Value::Percentage(_)
+        | Value::Currency(_, _)
+        | Value::DateTime(_)
+        | Value::DateTimeTz(_)
+        | Value::TimeDuration(_) => Some("right"),
+        _ => None,
+    });
+    if let Some(align) = align {
+        css.push(format!("text-align:{}", align));
+    }
+
+    css.join(";")
+}
+
+pub(crate) fn plain_value(value: &Value) -> String {
+    match value {
+        Value::Empty => String::new(),
+        Value::Boolean(v) => if *v { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(v) => v.to_string(),
+        Value::Percentage(v) => format!("{}%", v * 100.0),
+        Value::Currency(v, c) => format!("{:.2} {}", v, c),
+        Value::Text(_) | Value::TextXml(_) => value.as_cow_str_or("").to_string(),
+        Value::DateTime(v) => {
+            if v.time().num_seconds_from_midnight() == 0 && v.nanosecond() == 0 {
+                v.format("%Y-%m-%d").to_string()
+            } else {
+                v.format("%Y-%m-%d %H:%M:%S").to_string()
+            }
+        }
+        Value::DateTimeTz(v) => v.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+        Value::TimeDuration(v) => {
+            let secs = v.num_seconds();
+            format!(
+                "{}{}:{:02}:{:02}",
+                if secs < 0 { "-" } else { "" },
+                secs.abs() / 3600,
+                secs.abs() / 60 % 60,
+                secs.abs() % 60
+            )
+        }
+    }
+}
+
+// Finds the value format by name.
+fn format_parts<'a>(book: &'a WorkBook, name: &str) -> Option<&'a Vec<FormatPart>> {
+    book.number_format(name)
+        .map(|v| v.parts())
+        .or_else(|| book.percentage_format(name).map(|v| v.parts()))
+        .or_else(|| book.currency_format(name).map(|v| v.parts()))
+        .or_else(|| book.datetime_format(name).map(|v| v.parts()))
+        .or_else(|| book.timeduration_format(name).map(|v| v.parts()))
+        .or_else(|| book.boolean_format(name).map(|v| v.parts()))
+        .or_else(|| book.text_format(name).map(|v| v.parts()))
+}
+
+fn format_value(book: &WorkBook, value: &Value, style: Option<&CellStyle>) -> String {
+    let parts = style
+        .and_then(|v| v.value_format())
+        .and_then(|v| format_parts(book, v));
+    let Some(parts) = parts else {
+        return plain_value(value);
+    };
+
+    match value {
+        Value::Number(v) => format_number(*v, parts),
+        Value::Percentage(v) => format_number(*v * 100.0, parts),
+        Value::Currency(v, _) => format_number(*v, parts),
+        Value::DateTime(v) => format_datetime(v, parts),
+        Value::DateTimeTz(v) => format_datetime(&v.naive_local(), parts),
+        Value::TimeDuration(v) => format_duration(v, parts),
+        Value::Boolean(_) | Value::Text(_) | Value::TextXml(_) => {
+            let mut buf = String::new();
+            for part in parts {
+                match part.part_type() {
+                    FormatPartType::Boolean | FormatPartType::TextContent => {
+                        buf.push_str(&plain_value(value))
+                    }
+                    FormatPartType::Text => buf.push_str(part_text(part)),
+                    _ => {}
+                }
+            }
+            buf
+        }
+        Value::Empty => String::new(),
+    }
+}
+
+fn part_text(part: &FormatPart) -> &str {
+    part.content().map(|v| v.as_str()).unwrap_or_default()
+}
+
+fn part_u32(part: &FormatPart, attr: &str) -> Option<u32> {
+    part.attr_def(attr, "").parse().ok()
+}
+
+fn is_long(part: &FormatPart) -> bool {
+    part.attr_def("number:style", "short") == "long"
+}
+
+fn format_number(value: f64, parts: &[FormatPart]) -> String {
+    let mut buf = String::new();
+    for part in parts {
+        match part.part_type() {
+            FormatPartType::Number | FormatPartType::Fraction => {
+                let decimals = part_u32(part, "number:decimal-places").unwrap_or(0) as usize;
+                let min_decimals =
+                    part_u32(part, "number:min-decimal-places").unwrap_or(decimals as u32) as usize;
+                let min_int = part_u32(part, "number:min-integer-digits").unwrap_or(1) as usize;
+                let grouping = part.attr_def("number:grouping", "false") == "true";
+
+                let digits = format!("{:.*}", decimals, value.abs());
+                let (int, frac) = match digits.split_once('.') {
+                    Some((int, frac)) => (int, Some(frac)),
+                    None => (digits.as_str(), None),
+                };
+                let int = format!("{:0>1$}", int, min_int);
+                let int = if int.chars().all(|v| v == '0') && min_int == 0 {
+                    ""
+                } else {
+                    int.as_str()
+                };
+
+                if value < 0.0 && digits.chars().any(|v| v != '0' && v != '.') {
+                    buf.push('-');
+                }
+                for (i, c) in int.chars().enumerate() {
+                    if grouping && i > 0 && (int.len() - i) % 3 == 0 {
+                        buf.push(',');
+                    }
+                    buf.push(c);
+                }
+                if let Some(frac) = frac {
+                    let trim = frac
+                        .trim_end_matches('0')
+                        .len()
+                        .max(min_decimals)
+                        .min(frac.len());
+                    if trim > 0 {
+                        buf.push('.');
+                        buf.push_str(&frac[..trim]);
+                    }
+                }
+            }
+            FormatPartType::ScientificNumber => {
+                let decimals = part_u32(part, "number:decimal-places").unwrap_or(0) as usize;
+                let exp_digits = part_u32(part, "number:min-exponent-digits").unwrap_or(2) as usize;
+                let interval = part_u32(part, "number:exponent-interval")
+                    .unwrap_or(1)
+                    .max(1) as i32;
+                let mut exp = if value == 0.0 {
+                    0
+                } else {
+                    (value.abs().log10().floor() as i32).div_euclid(interval) * interval
+                };
+                // Rounding can carry the mantissa over to the next exponent.
+                let scale = 10f64.powi(decimals as i32);
+                let mut mantissa = (value / 10f64.powi(exp) * scale).round() / scale;
+                if mantissa.abs() >= 10f64.powi(interval) {
+                    exp += interval;
+                    mantissa = (value / 10f64.powi(exp) * scale).round() / scale;
+                }
+                let _ = write!(
+                    buf,
+                    "{:.prec$}E{}{:0>width$}",
+                    mantissa,
+                    if exp < 0 { '-' } else { '+' },
+                    exp.unsigned_abs(),
+                    prec = decimals,
+                    width = exp_digits
+                );
+            }
+            FormatPartType::Text | FormatPartType::CurrencySymbol => {
+                buf.push_str(part_text(part));
+            }
+            _ => {}
+        }
+    }
+    buf
+}
+
+fn format_datetime(value: &NaiveDateTime, parts: &[FormatPart]) -> String {
+    let am_pm = parts.iter().any(|v| v.part_type() == FormatPartType::AmPm);
+
+    let mut fmt = String::new();
+    for part in parts {
+        match part.part_type() {
+            FormatPartType::Day => fmt.push_str(if is_long(part) { "%d" } else { "%-d" }),
+            FormatPartType::Month => {
+                let textual = part.attr_def("number:textual", "false") == "true";
+                fmt.push_str(match (textual, is_long(part)) {
+                    (true, true) => "%B",
+                    (true, false) => "%b",
+                    (false, true) => "%m",
+                    (false, false) => "%-m",
+                });
+            }
+            FormatPartType::Year => fmt.push_str(if is_long(part) { "%Y" } else { "%y" }),
+            FormatPartType::DayOfWeek => fmt.push_str(if is_long(part) { "%A" } else { "%a" }),
+            FormatPartType::WeekOfYear => fmt.push_str("%V"),
+            FormatPartType::Hours => fmt.push_str(match (am_pm, is_long(part)) {
+                (true, true) => "%I",
+                (true, false) => "%-I",
+                (false, true) => "%H",
+                (false, false) => "%-H",
+            }),
+            FormatPartType::Minutes => fmt.push_str(if is_long(part) { "%M" } else { "%-M" }),
+            FormatPartType::Seconds => fmt.push_str(if is_long(part) { "%S" } else { "%-S" }),
+            FormatPartType::AmPm => fmt.push_str("%p"),
+            FormatPartType::Text => fmt.push_str(&part_text(part).replace('%', "%%")),
+            _ => {}
+        }
+    }
+    value.format(&fmt).to_string()
+}
+
+fn format_duration(value: &Duration, parts: &[FormatPart]) -> String {
+    let mut buf = String::new();
+    if *value < Duration::zero() {
+        buf.push('-');
+    }
+    let secs = value.num_seconds().abs();
+    for part in parts {
+        let width = if is_long(part) { 2 } else { 1 };
+        match part.part_type() {
+            FormatPartType::Hours => {
+                let _ = write!(buf, "{:0>1$}", secs / 3600, width);
+            }
+            FormatPartType::Minutes => {
+                let _ = write!(buf, "{:0>1$}", secs / 60 % 60, width);
+            }
+            FormatPartType::Seconds => {
+                let _ = write!(buf, "{:0>1$}", secs % 60, width);
+            }
+            FormatPartType::Text => buf.push_str(part_text(part)),
+            _ => {}
+        }
+    }
+    buf
+}
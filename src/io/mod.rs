@@ -1,6 +1,6 @@
-use crate::HashMap;
 use get_size::GetSize;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 pub(crate) mod format;
 pub(crate) mod parse;
@@ -11,7 +11,8 @@ mod xmlwriter;
 
 #[derive(Clone, Debug)]
 pub(crate) struct NamespaceMap {
-    map: HashMap<Cow<'static, str>, Cow<'static, str>>,
+    // Sorted, keeps the output stable between runs.
+    map: BTreeMap<Cow<'static, str>, Cow<'static, str>>,
 }
 
 impl GetSize for NamespaceMap {}
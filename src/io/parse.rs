@@ -4,15 +4,17 @@
 //! For many cases this omits the transformation to a &str
 
 use crate::error::AsStatic;
-use crate::sheet::Visibility;
+use crate::sheet::{TableSourceMode, Visibility};
 use crate::xlink::{XLinkActuate, XLinkShow, XLinkType};
 use crate::OdsError;
 use chrono::Duration;
-use chrono::NaiveDateTime;
+use chrono::{FixedOffset, NaiveDateTime};
+use color::Rgb;
 use kparse::prelude::*;
 use kparse::{TokenizerError, TokenizerResult};
+use nom::branch::alt;
 use nom::character::complete::digit1;
-use nom::combinator::{all_consuming, eof, opt};
+use nom::combinator::{all_consuming, eof, map, opt};
 use nom::number::complete::double;
 use nom::sequence::{pair, preceded, terminated, tuple};
 use nom::AsChar;
@@ -77,6 +79,19 @@ pub(crate) fn parse_visibility(input: KSpan<'_>) -> Result<Visibility, OdsError>
     }
 }
 
+/// Parse as TableSourceMode.
+#[inline]
+pub(crate) fn parse_table_source_mode(input: KSpan<'_>) -> Result<TableSourceMode, OdsError> {
+    match input {
+        b"copy-all" => Ok(TableSourceMode::CopyAll),
+        b"copy-results-only" => Ok(TableSourceMode::CopyResultsOnly),
+        _ => Err(OdsError::Parse(
+            "invalid table:mode",
+            Some(from_utf8(input)?.to_string()),
+        )),
+    }
+}
+
 /// Parse XLinkActuate enum
 #[inline]
 pub(crate) fn parse_xlink_actuate(input: KSpan<'_>) -> Result<XLinkActuate, OdsError> {
@@ -96,6 +111,9 @@ pub(crate) fn parse_xlink_show(input: KSpan<'_>) -> Result<XLinkShow, OdsError>
     match input {
         b"new" => Ok(XLinkShow::New),
         b"replace" => Ok(XLinkShow::Replace),
+        b"embed" => Ok(XLinkShow::Embed),
+        b"other" => Ok(XLinkShow::Other),
+        b"none" => Ok(XLinkShow::NoShow),
         _ => Err(OdsError::Parse(
             "invalid xlink:show",
             Some(from_utf8(input)?.to_string()),
@@ -127,10 +145,25 @@ pub(crate) fn parse_string(input: KSpan<'_>) -> Result<String, OdsError> {
     Ok(String::from_utf8_lossy(input).to_string())
 }
 
-/// Parse a attribute value as a currency.
+/// Parse a color #rrggbb.
 #[inline]
-pub(crate) fn parse_currency(input: KSpan<'_>) -> Result<String, OdsError> {
-    Ok(String::from_utf8_lossy(input).to_string())
+pub(crate) fn parse_color(input: KSpan<'_>) -> Result<Rgb<u8>, OdsError> {
+    fn hex(input: KSpan<'_>) -> Option<u8> {
+        u8::from_str_radix(from_utf8(input).ok()?, 16).ok()
+    }
+    match input {
+        [b'#', r @ ..] if r.len() == 6 => match (hex(&r[0..2]), hex(&r[2..4]), hex(&r[4..6])) {
+            (Some(r), Some(g), Some(b)) => Ok(Rgb::new(r, g, b)),
+            _ => Err(OdsError::Parse(
+                "invalid color",
+                Some(from_utf8(input)?.into()),
+            )),
+        },
+        _ => Err(OdsError::Parse(
+            "invalid color",
+            Some(from_utf8(input)?.into()),
+        )),
+    }
 }
 
 /// Parse a bool.
@@ -145,6 +178,18 @@ pub(crate) fn parse_u32(input: KSpan<'_>) -> Result<u32, OdsError> {
     Ok(token_u32(input)?)
 }
 
+/// Parse a repeat count. Must be greater than 0.
+#[inline]
+pub(crate) fn parse_repeat(input: KSpan<'_>) -> Result<u32, OdsError> {
+    match token_u32(input)? {
+        0 => Err(OdsError::Parse(
+            "repeat must be greater than 0",
+            Some("0".into()),
+        )),
+        v => Ok(v),
+    }
+}
+
 /// Parse a i64.
 #[inline]
 pub(crate) fn parse_i64(input: KSpan<'_>) -> Result<i64, OdsError> {
@@ -169,9 +214,19 @@ pub(crate) fn parse_f64(input: KSpan<'_>) -> Result<f64, OdsError> {
     Ok(token_float(input)?)
 }
 
-/// Parse a XML Schema datetime.
+/// Parse a XML Schema datetime. A timezone is ignored, the result is
+/// the time as written.
 #[inline]
 pub(crate) fn parse_datetime(input: KSpan<'_>) -> Result<NaiveDateTime, OdsError> {
+    Ok(token_datetime(input)?.0)
+}
+
+/// Parse a XML Schema datetime. The timezone is returned separately,
+/// the datetime is the time as written.
+#[inline]
+pub(crate) fn parse_datetime_tz(
+    input: KSpan<'_>,
+) -> Result<(NaiveDateTime, Option<FixedOffset>), OdsError> {
     Ok(token_datetime(input)?)
 }
 
@@ -285,9 +340,40 @@ fn token_nano(input: KSpan<'_>) -> KTokenizerResult<'_, i64> {
     Ok((rest, v))
 }
 
+// Timezone, either Z or +hh:mm/-hh:mm.
 #[inline(always)]
-fn token_datetime(input: KSpan<'_>) -> KTokenResult<'_, NaiveDateTime> {
-    let (_, (minus, year, _, month, _, day, time, _)) = terminated(
+fn token_timezone(input: KSpan<'_>) -> KTokenizerResult<'_, FixedOffset> {
+    let (rest, (sign, hour, minute)) = alt((
+        map(byte(b'Z'), |_| (1, 0, 0)),
+        map(
+            tuple((
+                alt((map(byte(b'+'), |_| 1), map(byte(b'-'), |_| -1))),
+                token_datepart,
+                byte(b':'),
+                token_datepart,
+            )),
+            |(sign, hour, _, minute)| (sign, hour, minute),
+        ),
+    ))(input)?;
+
+    if hour > 23 || minute > 59 {
+        return Err(nom::Err::Error(KTokenizerError::new(
+            RCode::DateTime,
+            input,
+        )));
+    }
+    match FixedOffset::east_opt(sign * (hour as i32 * 3600 + minute as i32 * 60)) {
+        Some(v) => Ok((rest, v)),
+        None => Err(nom::Err::Error(KTokenizerError::new(
+            RCode::DateTime,
+            input,
+        ))),
+    }
+}
+
+#[inline(always)]
+fn token_datetime(input: KSpan<'_>) -> KTokenResult<'_, (NaiveDateTime, Option<FixedOffset>)> {
+    let (_, (minus, year, _, month, _, day, time, tz)) = terminated(
         tuple((
             opt(byte(b'-')),
             token_datepart,
@@ -304,7 +390,7 @@ fn token_datetime(input: KSpan<'_>) -> KTokenResult<'_, NaiveDateTime> {
                 token_datepart,
                 opt(tuple((byte(b'.'), token_nano))),
             ))),
-            opt(byte(b'Z')),
+            opt(token_timezone),
         )),
         eof,
     )(input)?;
@@ -333,7 +419,7 @@ fn token_datetime(input: KSpan<'_>) -> KTokenResult<'_, NaiveDateTime> {
         p.second = Some(0);
     }
     match p.to_naive_datetime_with_offset(0) {
-        Ok(v) => Ok(v),
+        Ok(v) => Ok((v, tz)),
         Err(_) => Err(nom::Err::Error(KTokenizerError::new(
             RCode::DateTime,
             input,
@@ -435,10 +521,11 @@ pub(crate) fn byte(c: u8) -> impl Fn(KSpan<'_>) -> KTokenizerResult<'_, ()> {
 #[cfg(test)]
 mod tests {
     use crate::io::parse::{
-        parse_bool, parse_datetime, parse_duration, parse_f64, parse_i32, parse_u32, token_nano,
+        parse_bool, parse_datetime, parse_datetime_tz, parse_duration, parse_f64, parse_i32,
+        parse_u32, token_nano,
     };
     use crate::OdsError;
-    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 
     #[test]
     fn test_u32() -> Result<(), OdsError> {
@@ -534,6 +621,26 @@ mod tests {
             )
         );
 
+        let local = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2000, 1, 1).expect("date"),
+            NaiveTime::from_hms_opt(11, 22, 33).expect("time"),
+        );
+        assert_eq!(parse_datetime(b"2000-01-01T11:22:33+02:00")?, local);
+        assert_eq!(
+            parse_datetime_tz(b"2000-01-01T11:22:33+02:00")?,
+            (local, FixedOffset::east_opt(7200))
+        );
+        assert_eq!(
+            parse_datetime_tz(b"2000-01-01T11:22:33.0-05:30")?,
+            (local, FixedOffset::west_opt(19800))
+        );
+        assert_eq!(
+            parse_datetime_tz(b"2000-01-01T11:22:33Z")?,
+            (local, FixedOffset::east_opt(0))
+        );
+        assert_eq!(parse_datetime_tz(b"2000-01-01T11:22:33")?, (local, None));
+        assert!(parse_datetime_tz(b"2000-01-01T11:22:33+25:00").is_err());
+
         Ok(())
     }
 
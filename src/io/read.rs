@@ -2,28 +2,37 @@ use crate::sheet_::Header;
 use std::borrow::Cow;
 use std::convert::{TryFrom, TryInto};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Cursor, Read, Seek, Write};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, Take, Write};
 use std::mem;
+#[cfg(feature = "rayon")]
+use std::ops::Range;
+use std::ops::{Bound, RangeBounds};
 use std::path::Path;
 use std::str::from_utf8;
 
-use chrono::{Duration, NaiveDateTime};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime};
 use quick_xml::events::attributes::Attribute;
 use quick_xml::events::{BytesStart, Event};
+use string_cache::DefaultAtom;
+#[cfg(feature = "async")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+use zip::read::ZipFile;
 use zip::ZipArchive;
 
 use crate::attrmap2::AttrMap2;
 use crate::cell_::CellData;
 use crate::condition::{Condition, ValueCondition};
 use crate::config::{Config, ConfigItem, ConfigItemType, ConfigValue};
-use crate::draw::{Annotation, DrawFrame, DrawFrameContent, DrawImage};
+use crate::draw::{
+    Annotation, DrawFrame, DrawFrameContent, DrawImage, DrawLine, DrawObject, DrawRect, DrawShape,
+};
 use crate::ds::detach::Detach;
-use crate::error::OdsError;
+use crate::error::{OdsError, OdsWarning};
 use crate::format::{FormatPart, FormatPartType, ValueFormatTrait, ValueStyleMap};
 use crate::io::parse::{
-    parse_bool, parse_currency, parse_datetime, parse_duration, parse_f64, parse_i16, parse_i32,
-    parse_i64, parse_string, parse_u32, parse_visibility, parse_xlink_actuate, parse_xlink_show,
-    parse_xlink_type,
+    parse_bool, parse_color, parse_datetime, parse_datetime_tz, parse_duration, parse_f64,
+    parse_i16, parse_i32, parse_i64, parse_repeat, parse_string, parse_table_source_mode,
+    parse_u32, parse_visibility, parse_xlink_actuate, parse_xlink_show, parse_xlink_type,
 };
 use crate::io::NamespaceMap;
 use crate::manifest::Manifest;
@@ -31,30 +40,50 @@ use crate::metadata::{
     MetaAutoReload, MetaDocumentStatistics, MetaHyperlinkBehaviour, MetaTemplate, MetaUserDefined,
     MetaValue,
 };
-use crate::refs::{parse_cellranges, parse_cellref};
-use crate::sheet::{Grouped, SplitMode};
-use crate::sheet_::{dedup_colheader, CellDataIter, CellDataIterMut, ColHeader, RowHeader};
+use crate::refs::{parse_cellrange, parse_cellranges, parse_cellref};
+use crate::sheet::{Grouped, Scenario, SplitMode, TableSource, Visibility};
+use crate::sheet_::{
+    dedup_colheader, CellDataIter, CellDataIterMut, ColHeader, RowHeader, EDITABLE_RANGE_PREFIX,
+};
+use crate::sparkline::{Sparkline, SparklineGroup, SparklineType};
 use crate::style::stylemap::StyleMap;
 use crate::style::tabstop::TabStop;
 use crate::style::{
-    AnyStyleRef, ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage,
-    MasterPageRef, PageStyle, ParagraphStyle, RowStyle, RowStyleRef, RubyStyle, StyleOrigin,
-    StyleUse, TableStyle, TableStyleRef, TextStyle,
+    AnyStyleRef, ColStyle, ColStyleRef, FillImage, FontFaceDecl, FontFaceUri, Gradient,
+    GraphicStyle, Hatch, HeaderFooter, MasterPage, MasterPageRef, PageStyle, ParagraphStyle,
+    ParseStyleAttr, RowStyle, RowStyleRef, RubyStyle, StyleOrigin, StyleUse, TableStyle,
+    TableStyleRef, TextStyle, Theme, ThemeColor,
 };
 use crate::text::{TextP, TextTag};
 use crate::validation::{MessageType, Validation, ValidationError, ValidationHelp, ValidationRef};
-use crate::workbook::{EventListener, Script};
-use crate::xmltree::XmlTag;
+use crate::value_::TextPool;
+use crate::workbook::{CalculationSettings, CellTag, EventListener, NamedView, Script};
+use crate::workbook_::{StylesPart, CELL_TAG_PREFIX, CELL_TAG_VALUE_PREFIX};
+use crate::xmltree::{XmlContent, XmlTag};
 use crate::{
-    CellStyle, CellStyleRef, Length, Sheet, Value, ValueFormatBoolean, ValueFormatCurrency,
-    ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage, ValueFormatText,
-    ValueFormatTimeDuration, ValueType, WorkBook,
+    CellRef, CellStyle, CellStyleRef, HashMap, Length, Sheet, Value, ValueFormatBoolean,
+    ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage,
+    ValueFormatText, ValueFormatTimeDuration, ValueType, WorkBook,
 };
 
 type OdsXmlReader<'a> = quick_xml::Reader<&'a mut dyn BufRead>;
 
+/// How the timezone of a date value is handled when reading.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimezonePolicy {
+    /// The timezone is dropped, the value is the time as written.
+    /// This is the default.
+    #[default]
+    Local,
+    /// The value is converted to UTC.
+    Utc,
+    /// The value is read as Value::DateTimeTz and written back with
+    /// its timezone.
+    Keep,
+}
+
 /// Read options for ods-files.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct OdsOptions {
     // parse the content only.
     content_only: bool,
@@ -62,6 +91,26 @@ pub struct OdsOptions {
     use_repeat_for_cells: bool,
     // ignore empty cells.
     ignore_empty_cells: bool,
+    // drop the results of formulas.
+    ignore_cached_values: bool,
+    // only read these sheets.
+    only_sheet_names: Vec<String>,
+    only_sheet_idx: Vec<usize>,
+    // collect recoverable errors as warnings.
+    lenient: bool,
+    // collect unknown attributes and elements as warnings.
+    report_unused: bool,
+    // keep unknown elements.
+    preserve_unknown: bool,
+    // timezones of date values.
+    timezone: TimezonePolicy,
+    // hard limits.
+    max_rows: Option<u32>,
+    max_repeat_expansion: Option<u64>,
+    max_xml_depth: Option<usize>,
+    max_inflate_ratio: Option<u64>,
+    // move cells to a temporary file.
+    spill_cells: Option<usize>,
 }
 
 impl OdsOptions {
@@ -130,6 +179,164 @@ impl OdsOptions {
         self
     }
 
+    /// Drops the stored results of formulas.
+    ///
+    /// Sheet::value() returns Value::Empty for all cells with a formula,
+    /// and formula() is the only content of such cells. Useful for data
+    /// extraction where an outdated result would be misleading, or if
+    /// the formulas are evaluated by some other means.
+    ///
+    /// This is the same as WorkBook::strip_cached_values() after reading.
+    pub fn ignore_cached_values(mut self) -> Self {
+        self.ignore_cached_values = true;
+        self
+    }
+
+    /// Reads the stored results of formulas.
+    ///
+    /// This is the default. Sheet::value() returns the result as it was
+    /// last calculated by the application that wrote the file,
+    /// Sheet::cached_value() does the same but only for formula cells.
+    pub fn read_cached_values(mut self) -> Self {
+        self.ignore_cached_values = false;
+        self
+    }
+
+    /// Only reads the sheets with the given names.
+    ///
+    /// All other table elements are skipped without parsing.
+    /// Can be combined with only_sheet_indices(), a sheet is read
+    /// if either its name or its index matches.
+    pub fn only_sheets<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.only_sheet_names = names.iter().map(|v| v.as_ref().to_string()).collect();
+        self
+    }
+
+    /// Only reads the sheets with the given index.
+    ///
+    /// The index is the position of the sheet in the file.
+    /// See only_sheets().
+    pub fn only_sheet_indices(mut self, idx: &[usize]) -> Self {
+        self.only_sheet_idx = idx.to_vec();
+        self
+    }
+
+    /// Recoverable problems don't abort the read, instead they are
+    /// collected in WorkBook::warnings().
+    ///
+    /// This includes broken meta.xml, settings.xml and styles.xml files,
+    /// missing extra files, unparseable cell values, unknown value types
+    /// and mismatched end tags.
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Attributes, elements and text the reader doesn't understand are
+    /// collected in WorkBook::warnings().
+    ///
+    /// This is always on with the feature "dump_unused".
+    pub fn report_unused(mut self) -> Self {
+        self.report_unused = true;
+        self
+    }
+
+    /// Keeps unknown elements and writes them back unchanged.
+    ///
+    /// This covers unknown elements in the spreadsheet body and in tables,
+    /// unknown elements in office:styles, office:automatic-styles and
+    /// office:master-styles and styles of unknown families.
+    /// Without this styles of unknown families are an error.
+    ///
+    /// Elements in the body that are not known are written after the
+    /// tables, those in a table before the columns.
+    pub fn preserve_unknown(mut self) -> Self {
+        self.preserve_unknown = true;
+        self
+    }
+
+    /// Sets how the timezone of date values in cells is handled.
+    ///
+    /// Without a timezone a date is always read as Value::DateTime.
+    /// See TimezonePolicy.
+    pub fn timezone(mut self, policy: TimezonePolicy) -> Self {
+        self.timezone = policy;
+        self
+    }
+
+    /// Maximum number of rows with cell-data in a sheet.
+    ///
+    /// Empty rows don't count, they are usually written with a huge
+    /// repeat count to fill up the sheet.
+    ///
+    /// Reading fails with OdsError::LimitExceeded if any row with
+    /// cell-data lies beyond this limit. Not limited by default.
+    pub fn max_rows(mut self, max_rows: u32) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Maximum number of cells that are created by cloning repeated
+    /// rows and cells. This is the total for the workbook.
+    ///
+    /// Reading fails with OdsError::LimitExceeded if more cells would be
+    /// created. Has no effect with use_repeat_for_cells(), nothing is
+    /// cloned then. Not limited by default.
+    pub fn max_repeat_expansion(mut self, max_cells: u64) -> Self {
+        self.max_repeat_expansion = Some(max_cells);
+        self
+    }
+
+    /// Maximum nesting of XML elements in text content and in unknown
+    /// elements kept with preserve_unknown().
+    ///
+    /// Reading fails with OdsError::LimitExceeded if the elements are
+    /// nested deeper. Not limited by default.
+    pub fn max_xml_depth(mut self, max_depth: usize) -> Self {
+        self.max_xml_depth = Some(max_depth);
+        self
+    }
+
+    /// Maximum ratio between the uncompressed and the compressed size of
+    /// any file in the zip.
+    ///
+    /// Reading fails with OdsError::LimitExceeded if a file exceeds this
+    /// ratio. The data read is capped at the size given in the zip, so the
+    /// check can't be avoided with a wrong size. Only used for .ods files.
+    /// Not limited by default.
+    pub fn max_inflate_ratio(mut self, max_ratio: u64) -> Self {
+        self.max_inflate_ratio = Some(max_ratio);
+        self
+    }
+
+    /// Moves the cells of a sheet to a temporary file while reading,
+    /// each time about max_cells new cells have been read.
+    ///
+    /// This way a sheet can be larger than the available memory. The
+    /// cells are read back when they are accessed, see Sheet::spill()
+    /// for the details. Not used by default.
+    pub fn spill_cells(mut self, max_cells: usize) -> Self {
+        self.spill_cells = Some(max_cells);
+        self
+    }
+
+    /// Reads a .ods file from an async reader.
+    ///
+    /// The data is read into memory and parsed with spawn_blocking().
+    #[cfg(feature = "async")]
+    pub async fn read_ods_async<T: AsyncRead + Unpin>(
+        &self,
+        mut read: T,
+    ) -> Result<WorkBook, OdsError> {
+        let mut buf = Vec::new();
+        read.read_to_end(&mut buf).await?;
+
+        let options = self.clone();
+        tokio::task::spawn_blocking(move || options.read_ods(Cursor::new(buf)))
+            .await
+            .map_err(|e| OdsError::Ods(e.to_string()))?
+    }
+
     /// Reads a .ods file.
     pub fn read_ods<T: Read + Seek>(&self, read: T) -> Result<WorkBook, OdsError> {
         let zip = ZipArchive::new(read)?;
@@ -161,6 +368,12 @@ pub fn read_ods_from<T: Read + Seek>(read: T) -> Result<WorkBook, OdsError> {
     OdsOptions::default().read_ods(read)
 }
 
+/// Reads an ODS-file from an async reader.
+#[cfg(feature = "async")]
+pub async fn read_ods_async<T: AsyncRead + Unpin>(read: T) -> Result<WorkBook, OdsError> {
+    OdsOptions::default().read_ods_async(read).await
+}
+
 /// Reads an ODS-file.
 pub fn read_ods<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
     let read = BufReader::new(File::open(path.as_ref())?);
@@ -193,11 +406,29 @@ struct OdsContext {
     content_only: bool,
     use_repeat_for_cells: bool,
     ignore_empty_cells: bool,
+    ignore_cached_values: bool,
+    only_sheet_names: Vec<String>,
+    only_sheet_idx: Vec<usize>,
+    // index of the next table element.
+    table_idx: usize,
+    lenient: bool,
+    report_unused: bool,
+    preserve_unknown: bool,
+    timezone: TimezonePolicy,
+    max_rows: Option<u32>,
+    max_repeat_expansion: Option<u64>,
+    max_xml_depth: Option<usize>,
+    max_inflate_ratio: Option<u64>,
+    spill_cells: Option<usize>,
+    // cells created by cloning so far.
+    repeat_expansion: u64,
 
     buffers: Vec<Vec<u8>>,
     xml_buffer: Vec<XmlTag>,
     col_group_buffer: Vec<Grouped>,
     row_group_buffer: Vec<Grouped>,
+    // attribute names already interned.
+    attr_names: HashMap<Box<[u8]>, DefaultAtom>,
 }
 
 impl OdsContext {
@@ -206,10 +437,136 @@ impl OdsContext {
             content_only: options.content_only,
             use_repeat_for_cells: options.use_repeat_for_cells,
             ignore_empty_cells: options.ignore_empty_cells,
+            ignore_cached_values: options.ignore_cached_values,
+            only_sheet_names: options.only_sheet_names.clone(),
+            only_sheet_idx: options.only_sheet_idx.clone(),
+            lenient: options.lenient,
+            report_unused: options.report_unused || cfg!(feature = "dump_unused"),
+            preserve_unknown: options.preserve_unknown,
+            timezone: options.timezone,
+            max_rows: options.max_rows,
+            max_repeat_expansion: options.max_repeat_expansion,
+            max_xml_depth: options.max_xml_depth,
+            max_inflate_ratio: options.max_inflate_ratio,
+            spill_cells: options.spill_cells,
             ..Default::default()
         }
     }
 
+    // In lenient mode the error is stored as a warning and None is returned.
+    fn recover<T>(
+        &mut self,
+        what: impl FnOnce() -> String,
+        res: Result<T, OdsError>,
+    ) -> Result<Option<T>, OdsError> {
+        match res {
+            Ok(v) => Ok(Some(v)),
+            Err(e) if self.can_recover(&e) => {
+                self.book.warnings.push(OdsWarning::Recovered {
+                    context: what(),
+                    message: e.to_string(),
+                });
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn can_recover(&self, err: &OdsError) -> bool {
+        can_recover(self.lenient, err)
+    }
+
+    // Nesting depth of xml elements.
+    fn check_xml_depth(&self, depth: usize) -> Result<(), OdsError> {
+        match self.max_xml_depth {
+            Some(max) if depth > max => Err(OdsError::LimitExceeded {
+                limit: "xml depth",
+                max: max as u64,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    // Row with cell-data.
+    // Checks the last row of a repeated row.
+    fn check_row(&self, row: u32, row_repeat: u32) -> Result<(), OdsError> {
+        match self.max_rows {
+            Some(max) if row.saturating_add(row_repeat.max(1) - 1) >= max => {
+                Err(OdsError::LimitExceeded {
+                    limit: "rows",
+                    max: max as u64,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Records an attribute the reader doesn't understand.
+    fn unused_attr(
+        &mut self,
+        func: &str,
+        tag: &[u8],
+        attr: &Attribute<'_>,
+    ) -> Result<(), OdsError> {
+        if self.report_unused {
+            self.book.warnings.push(OdsWarning::UnusedAttr {
+                context: func.to_string(),
+                tag: String::from_utf8_lossy(tag).to_string(),
+                attr: String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                value: String::from_utf8_lossy(attr.value.as_ref()).to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    // Records an element or text the reader doesn't understand.
+    fn unused_event(&mut self, func: &str, evt: &Event<'_>) -> Result<(), OdsError> {
+        if self.report_unused {
+            match evt {
+                Event::Start(xml_tag) | Event::Empty(xml_tag) => {
+                    self.book.warnings.push(OdsWarning::UnusedElement {
+                        context: func.to_string(),
+                        tag: String::from_utf8_lossy(xml_tag.name().as_ref()).to_string(),
+                    });
+                }
+                Event::Text(xml_text) => {
+                    let text = xml_text.unescape()?;
+                    if !text.trim().is_empty() {
+                        self.book.warnings.push(OdsWarning::UnusedText {
+                            context: func.to_string(),
+                            text: text.trim().to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Interned attribute name. The same few hundred names occur over
+    // and over again, the cache avoids the utf8 check and the lookup in
+    // the global atom table.
+    fn attr_name(&mut self, key: &[u8]) -> Result<DefaultAtom, OdsError> {
+        if let Some(name) = self.attr_names.get(key) {
+            Ok(name.clone())
+        } else {
+            let name = DefaultAtom::from(from_utf8(key)?);
+            self.attr_names.insert(key.into(), name.clone());
+            Ok(name)
+        }
+    }
+
+    // Is the table with this name and the current index read at all?
+    fn read_sheet(&self, name: &str) -> bool {
+        if self.only_sheet_names.is_empty() && self.only_sheet_idx.is_empty() {
+            true
+        } else {
+            self.only_sheet_names.iter().any(|v| v == name)
+                || self.only_sheet_idx.contains(&self.table_idx)
+        }
+    }
+
     fn pop_xml_buf(&mut self) -> Vec<XmlTag> {
         mem::take(&mut self.xml_buffer)
     }
@@ -252,7 +609,20 @@ impl OdsContext {
 fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
     let mut xml = quick_xml::Reader::from_reader(read);
+    xml.check_end_names(!ctx.lenient);
+
+    read_fods_document(&mut ctx, &mut xml)
+        .map_err(|e| e.at_part("fods.xml", xml.buffer_position() as u64))?;
+
+    // We do some data duplication here, to make everything easier to use.
+    let res = calc_derived(&mut ctx.book);
+    ctx.recover(|| "settings".into(), res)?;
+
+    Ok(ctx.book)
+}
 
+// Reads the complete fods document.
+fn read_fods_document(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
@@ -262,7 +632,7 @@ fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBo
 
         match &evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document" => {
-                let (version, xmlns) = read_namespaces_and_version(&mut xml, xml_tag)?;
+                let (version, xmlns) = read_namespaces_and_version(ctx, xml, xml_tag)?;
                 ctx.book.xmlns.insert("fods.xml".to_string(), xmlns);
                 if let Some(version) = version {
                     ctx.book.set_version(version);
@@ -271,28 +641,28 @@ fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBo
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document" => {}
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:meta" => {
-                read_office_meta(&mut ctx, &mut xml)?;
+                read_office_meta(ctx, xml)?;
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:settings" => {
-                read_office_settings(&mut ctx, &mut xml)?;
+                read_office_settings(ctx, xml)?;
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:scripts" => {
-                read_scripts(&mut ctx, &mut xml)?
+                read_scripts(ctx, xml)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:font-face-decls" => {
-                read_office_font_face_decls(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_font_face_decls(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:styles" => {
-                read_office_styles(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_styles(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:automatic-styles" => {
-                read_office_automatic_styles(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_automatic_styles(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:master-styles" => {
-                read_office_master_styles(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_master_styles(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:body" => {
-                read_office_body(&mut ctx, &mut xml)?;
+                read_office_body(ctx, xml)?;
             }
 
             Event::Decl(_) => {}
@@ -300,18 +670,13 @@ fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBo
                 break;
             }
             _ => {
-                unused_event("read_fods_content", &evt)?;
+                ctx.unused_event("read_fods_content", &evt)?;
             }
         }
     }
     ctx.push_buf(buf);
 
-    calculations(&mut ctx)?;
-
-    // We do some data duplication here, to make everything easier to use.
-    calc_derived(&mut ctx.book)?;
-
-    Ok(ctx.book)
+    Ok(())
 }
 
 fn read_fods_impl_content_only(
@@ -320,7 +685,16 @@ fn read_fods_impl_content_only(
 ) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
     let mut xml: quick_xml::Reader<&mut dyn BufRead> = quick_xml::Reader::from_reader(read);
+    xml.check_end_names(!ctx.lenient);
+
+    read_fods_body(&mut ctx, &mut xml)
+        .map_err(|e| e.at_part("fods.xml", xml.buffer_position() as u64))?;
 
+    Ok(ctx.book)
+}
+
+// Reads only the office:body of the fods document.
+fn read_fods_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
@@ -330,7 +704,7 @@ fn read_fods_impl_content_only(
 
         match &evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:body" => {
-                read_office_body(&mut ctx, &mut xml)?;
+                read_office_body(ctx, xml)?;
             }
             Event::Eof => {
                 break;
@@ -342,9 +716,7 @@ fn read_fods_impl_content_only(
     }
     ctx.push_buf(buf);
 
-    calculations(&mut ctx)?;
-
-    Ok(ctx.book)
+    Ok(())
 }
 
 /// Reads an ODS-file.
@@ -355,50 +727,394 @@ fn read_ods_impl<R: Read + Seek>(
     let mut ctx = OdsContext::new(options);
 
     if let Ok(z) = zip.by_name("META-INF/manifest.xml") {
-        let mut read = BufReader::new(z);
+        let mut read = BufReader::new(limit_inflate(z, ctx.max_inflate_ratio)?);
         let read: &mut dyn BufRead = &mut read;
         let mut xml = quick_xml::Reader::from_reader(read);
+        xml.check_end_names(!ctx.lenient);
 
-        read_ods_manifest(&mut ctx, &mut xml)?;
+        let res = read_ods_manifest(&mut ctx, &mut xml)
+            .map_err(|e| e.at_part("META-INF/manifest.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "META-INF/manifest.xml".into(), res)?;
     }
 
     read_ods_extras(&mut ctx, &mut zip)?;
 
+    #[cfg(feature = "rayon")]
+    read_ods_parallel(&mut ctx, &mut zip, options)?;
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        read_ods_meta(&mut ctx, &mut zip)?;
+        read_ods_styles_content(&mut ctx, &mut zip)?;
+    }
+
+    // We do some data duplication here, to make everything easier to use.
+    calc_derived(&mut ctx.book)?;
+
+    Ok(ctx.book)
+}
+
+// Reads meta.xml and settings.xml.
+#[cfg(not(feature = "rayon"))]
+fn read_ods_meta<R: Read + Seek>(
+    ctx: &mut OdsContext,
+    zip: &mut ZipArchive<R>,
+) -> Result<(), OdsError> {
     if let Ok(z) = zip.by_name("meta.xml") {
-        let mut read = BufReader::new(z);
+        let mut read = BufReader::new(limit_inflate(z, ctx.max_inflate_ratio)?);
         let read: &mut dyn BufRead = &mut read;
         let mut xml = quick_xml::Reader::from_reader(read);
+        xml.check_end_names(!ctx.lenient);
 
-        read_ods_metadata(&mut ctx, &mut xml)?;
+        let res = read_ods_metadata(ctx, &mut xml)
+            .map_err(|e| e.at_part("meta.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "meta.xml".into(), res)?;
     }
 
     if let Ok(z) = zip.by_name("settings.xml") {
-        let mut read = BufReader::new(z);
+        let mut read = BufReader::new(limit_inflate(z, ctx.max_inflate_ratio)?);
         let read: &mut dyn BufRead = &mut read;
         let mut xml = quick_xml::Reader::from_reader(read);
-        read_ods_settings(&mut ctx, &mut xml)?;
+        xml.check_end_names(!ctx.lenient);
+        let res = read_ods_settings(ctx, &mut xml)
+            .map_err(|e| e.at_part("settings.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "settings.xml".into(), res)?;
     }
 
-    if let Ok(z) = zip.by_name("styles.xml") {
-        let mut read = BufReader::new(z);
+    Ok(())
+}
+
+// The parts don't depend on each other. meta.xml, settings.xml,
+// styles.xml and each table of content.xml are parsed in the thread
+// pool, the rest of content.xml in this thread. The zip itself stays
+// here, it need not be Send.
+#[cfg(feature = "rayon")]
+fn read_ods_parallel<R: Read + Seek>(
+    ctx: &mut OdsContext,
+    zip: &mut ZipArchive<R>,
+    options: &OdsOptions,
+) -> Result<(), OdsError> {
+    let meta = read_opt_zip_entry(ctx, zip, "meta.xml")?;
+    let settings = read_opt_zip_entry(ctx, zip, "settings.xml")?;
+    let styles = read_opt_zip_entry(ctx, zip, "styles.xml")?;
+    let content = read_zip_entry(zip, "content.xml", ctx.max_inflate_ratio)?;
+
+    // If the tables can't be found reliably, content.xml is read in one go.
+    let tables = split_tables(&content).unwrap_or_default();
+    let mut rest = Vec::with_capacity(content.len());
+    let mut last = 0;
+    for table in &tables {
+        rest.extend_from_slice(&content[last..table.start]);
+        last = table.end;
+    }
+    rest.extend_from_slice(&content[last..]);
+
+    // The styles of content.xml replace those of styles.xml, and both
+    // replace the defaults. To merge them in this order later, the
+    // defaults are moved out of the way.
+    let mut defaults = WorkBook::new_empty();
+    move_styles(&mut defaults, &mut ctx.book);
+    let mut warnings = mem::take(&mut ctx.book.warnings);
+    let text_pool = ctx.book.text_pool.clone();
+
+    let mut side = None;
+    let mut side_styles = None;
+    let mut side_tables: Vec<_> = tables.iter().map(|_| None).collect();
+    let res = rayon::scope(|s| {
+        let side = &mut side;
+        s.spawn(move |_| *side = Some(read_ods_meta_settings(options, meta, settings)));
+        let side_styles = &mut side_styles;
+        s.spawn(move |_| *side_styles = Some(read_ods_styles_part(options, styles)));
+        for (idx, (table, side_table)) in tables.iter().zip(side_tables.iter_mut()).enumerate() {
+            let content = &content;
+            let text_pool = &text_pool;
+            s.spawn(move |_| {
+                *side_table = Some(read_table_part(
+                    options,
+                    text_pool,
+                    idx,
+                    table.start,
+                    &content[table.clone()],
+                ))
+            });
+        }
+
+        let mut read = rest.as_slice();
         let read: &mut dyn BufRead = &mut read;
         let mut xml = quick_xml::Reader::from_reader(read);
-        read_ods_styles(&mut ctx, &mut xml)?;
+        xml.check_end_names(!ctx.lenient);
+        read_ods_content(ctx, &mut xml).map_err(|e| {
+            // the position in content.xml with the tables.
+            let mut pos = xml.buffer_position();
+            for table in &tables {
+                if table.start <= pos {
+                    pos += table.len();
+                }
+            }
+            e.at_part("content.xml", pos as u64)
+        })
+    });
+
+    // report the first error in file order.
+    let side = side.expect("meta and settings")?;
+    let side_styles = side_styles.expect("styles")?;
+    res?;
+    let side_tables = side_tables
+        .into_iter()
+        .map(|v| v.expect("table"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ctx.book.metadata = side.book.metadata;
+    ctx.book.config = side.book.config;
+    ctx.book.xmlns.extend(side.book.xmlns);
+
+    let mut styles_book = side_styles.book;
+    ctx.book.xmlns.extend(mem::take(&mut styles_book.xmlns));
+    let mut content_styles = WorkBook::new_empty();
+    move_styles(&mut content_styles, &mut ctx.book);
+    move_styles(&mut defaults, &mut styles_book);
+    move_styles(&mut defaults, &mut content_styles);
+    move_styles(&mut ctx.book, &mut defaults);
+
+    for mut side_table in side_tables {
+        ctx.book.sheets.append(&mut side_table.book.sheets);
+        ctx.book.warnings.append(&mut side_table.book.warnings);
+        // the limit is for the whole workbook.
+        add_expansion(
+            ctx.max_repeat_expansion,
+            &mut ctx.repeat_expansion,
+            side_table.repeat_expansion,
+        )?;
     }
 
-    {
-        let mut read = BufReader::new(zip.by_name("content.xml")?);
+    // keep the warnings in file order.
+    warnings.extend(side.book.warnings);
+    warnings.extend(styles_book.warnings);
+    warnings.append(&mut ctx.book.warnings);
+    ctx.book.warnings = warnings;
+
+    Ok(())
+}
+
+// Moves the fonts, styles and formats from one book to the other.
+// Existing entries are replaced. These are the parts that are read from
+// both styles.xml and content.xml.
+#[cfg(feature = "rayon")]
+fn move_styles(book: &mut WorkBook, from: &mut WorkBook) {
+    book.fonts.extend(from.fonts.drain());
+    for (prefix, n) in from.autonum.drain() {
+        let v = book.autonum.entry(prefix).or_insert(n);
+        *v = (*v).max(n);
+    }
+    book.tablestyles.extend(from.tablestyles.drain());
+    book.rowstyles.extend(from.rowstyles.drain());
+    book.colstyles.extend(from.colstyles.drain());
+    book.cellstyles.extend(from.cellstyles.drain());
+    book.paragraphstyles.extend(from.paragraphstyles.drain());
+    book.textstyles.extend(from.textstyles.drain());
+    book.rubystyles.extend(from.rubystyles.drain());
+    book.graphicstyles.extend(from.graphicstyles.drain());
+    book.gradients.extend(from.gradients.drain());
+    book.hatches.extend(from.hatches.drain());
+    book.fill_images.extend(from.fill_images.drain());
+    if from.theme.is_some() {
+        book.theme = from.theme.take();
+    }
+    book.formats_boolean.extend(from.formats_boolean.drain());
+    book.formats_number.extend(from.formats_number.drain());
+    book.formats_percentage
+        .extend(from.formats_percentage.drain());
+    book.formats_currency.extend(from.formats_currency.drain());
+    book.formats_text.extend(from.formats_text.drain());
+    book.formats_datetime.extend(from.formats_datetime.drain());
+    book.formats_timeduration
+        .extend(from.formats_timeduration.drain());
+    book.pagestyles.extend(from.pagestyles.drain());
+    book.masterpages.extend(from.masterpages.drain());
+    book.extra_styles.append(&mut from.extra_styles);
+}
+
+// Finds the table:table elements in content.xml. Comments, CDATA and
+// processing instructions are skipped. Empty tables are left as they
+// are. Returns None if the elements don't nest.
+#[cfg(feature = "rayon")]
+fn split_tables(content: &[u8]) -> Option<Vec<Range<usize>>> {
+    // Checks the cheap delimiter first, most tags are table:table-cell.
+    fn is_tag(buf: &[u8], name: &[u8]) -> bool {
+        matches!(
+            buf.get(name.len()),
+            Some(b' ' | b'\t' | b'\r' | b'\n' | b'/' | b'>')
+        ) && buf.starts_with(name)
+    }
+    // The end of a start tag. Attribute values can contain a '>'.
+    fn tag_end(buf: &[u8]) -> Option<usize> {
+        let mut quote = None;
+        for (idx, c) in buf.iter().enumerate() {
+            match (quote, *c) {
+                (None, b'"' | b'\'') => quote = Some(*c),
+                (None, b'>') => return Some(idx + 1),
+                (Some(q), c) if q == c => quote = None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    let mut tables = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut pos = 0;
+    while let Some(n) = memchr::memchr(b'<', &content[pos..]) {
+        pos += n;
+        let buf = &content[pos..];
+        if buf.starts_with(b"<!--") {
+            pos += memchr::memmem::find(buf, b"-->")? + 3;
+        } else if buf.starts_with(b"<![CDATA[") {
+            pos += memchr::memmem::find(buf, b"]]>")? + 3;
+        } else if buf.starts_with(b"<?") {
+            pos += memchr::memmem::find(buf, b"?>")? + 2;
+        } else if buf.starts_with(b"<!") {
+            // no doctype in odf.
+            return None;
+        } else if is_tag(buf, b"</table:table") {
+            pos += memchr::memchr(b'>', buf)? + 1;
+            depth = depth.checked_sub(1)?;
+            if depth == 0 {
+                tables.push(start..pos);
+            }
+        } else if is_tag(buf, b"<table:table") {
+            let end = tag_end(buf)?;
+            if buf[end - 2] != b'/' {
+                if depth == 0 {
+                    start = pos;
+                }
+                depth += 1;
+            }
+            pos += end;
+        } else {
+            pos += 1;
+        }
+    }
+
+    if depth == 0 {
+        Some(tables)
+    } else {
+        None
+    }
+}
+
+// Reads one table:table element of content.xml into a separate context.
+#[cfg(feature = "rayon")]
+fn read_table_part(
+    options: &OdsOptions,
+    text_pool: &TextPool,
+    table_idx: usize,
+    offset: usize,
+    table: &[u8],
+) -> Result<OdsContext, OdsError> {
+    let mut ctx = OdsContext::new(options);
+    ctx.book.text_pool = text_pool.clone();
+    ctx.table_idx = table_idx;
+
+    let mut read = table;
+    let read: &mut dyn BufRead = &mut read;
+    let mut xml = quick_xml::Reader::from_reader(read);
+    xml.check_end_names(!ctx.lenient);
+
+    let mut buf = ctx.pop_buf();
+    let res = match xml.read_event_into(&mut buf) {
+        Ok(Event::Start(xml_tag)) => read_table(&mut ctx, &mut xml, &xml_tag),
+        Ok(_) => Err(OdsError::Parse("table:table expected", None)),
+        Err(e) => Err(e.into()),
+    };
+    res.map_err(|e| e.at_part("content.xml", (offset + xml.buffer_position()) as u64))?;
+
+    Ok(ctx)
+}
+
+// Reads styles.xml into a separate context. It starts without the
+// default styles, so that only the styles of the file are merged.
+#[cfg(feature = "rayon")]
+fn read_ods_styles_part(
+    options: &OdsOptions,
+    styles: Option<Vec<u8>>,
+) -> Result<OdsContext, OdsError> {
+    let mut ctx = OdsContext::new(options);
+    ctx.book = WorkBook::new_empty();
+
+    if let Some(styles) = styles {
+        let mut read = styles.as_slice();
         let read: &mut dyn BufRead = &mut read;
         let mut xml = quick_xml::Reader::from_reader(read);
-        read_ods_content(&mut ctx, &mut xml)?;
+        xml.check_end_names(!ctx.lenient);
+        let res = read_ods_styles(&mut ctx, &mut xml)
+            .map_err(|e| e.at_part("styles.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "styles.xml".into(), res)?;
     }
 
-    calculations(&mut ctx)?;
+    Ok(ctx)
+}
 
-    // We do some data duplication here, to make everything easier to use.
-    calc_derived(&mut ctx.book)?;
+// Reads meta.xml and settings.xml into a separate context.
+#[cfg(feature = "rayon")]
+fn read_ods_meta_settings(
+    options: &OdsOptions,
+    meta: Option<Vec<u8>>,
+    settings: Option<Vec<u8>>,
+) -> Result<OdsContext, OdsError> {
+    let mut ctx = OdsContext::new(options);
 
-    Ok(ctx.book)
+    if let Some(meta) = meta {
+        let mut read = meta.as_slice();
+        let read: &mut dyn BufRead = &mut read;
+        let mut xml = quick_xml::Reader::from_reader(read);
+        xml.check_end_names(!ctx.lenient);
+
+        let res = read_ods_metadata(&mut ctx, &mut xml)
+            .map_err(|e| e.at_part("meta.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "meta.xml".into(), res)?;
+    }
+
+    if let Some(settings) = settings {
+        let mut read = settings.as_slice();
+        let read: &mut dyn BufRead = &mut read;
+        let mut xml = quick_xml::Reader::from_reader(read);
+        xml.check_end_names(!ctx.lenient);
+        let res = read_ods_settings(&mut ctx, &mut xml)
+            .map_err(|e| e.at_part("settings.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "settings.xml".into(), res)?;
+    }
+
+    Ok(ctx)
+}
+
+// Reads styles.xml and content.xml.
+#[cfg(not(feature = "rayon"))]
+fn read_ods_styles_content<R: Read + Seek>(
+    ctx: &mut OdsContext,
+    zip: &mut ZipArchive<R>,
+) -> Result<(), OdsError> {
+    if let Ok(z) = zip.by_name("styles.xml") {
+        let mut read = BufReader::new(limit_inflate(z, ctx.max_inflate_ratio)?);
+        let read: &mut dyn BufRead = &mut read;
+        let mut xml = quick_xml::Reader::from_reader(read);
+        xml.check_end_names(!ctx.lenient);
+        let res = read_ods_styles(ctx, &mut xml)
+            .map_err(|e| e.at_part("styles.xml", xml.buffer_position() as u64));
+        ctx.recover(|| "styles.xml".into(), res)?;
+    }
+
+    let mut read = BufReader::new(limit_inflate(
+        zip.by_name("content.xml")?,
+        ctx.max_inflate_ratio,
+    )?);
+    let read: &mut dyn BufRead = &mut read;
+    let mut xml = quick_xml::Reader::from_reader(read);
+    xml.check_end_names(!ctx.lenient);
+    read_ods_content(ctx, &mut xml)
+        .map_err(|e| e.at_part("content.xml", xml.buffer_position() as u64))?;
+
+    Ok(())
 }
 
 /// Reads an ODS-file.
@@ -408,38 +1124,104 @@ fn read_ods_impl_content_only<R: Read + Seek>(
 ) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
 
-    let mut read = BufReader::new(zip.by_name("content.xml")?);
+    let mut read = BufReader::new(limit_inflate(
+        zip.by_name("content.xml")?,
+        ctx.max_inflate_ratio,
+    )?);
     let read: &mut dyn BufRead = &mut read;
     let mut xml = quick_xml::Reader::from_reader(read);
+    xml.check_end_names(!ctx.lenient);
 
     // todo: this still reads styles etc from content.xml
-    read_ods_content(&mut ctx, &mut xml)?;
-
-    calculations(&mut ctx)?;
+    read_ods_content(&mut ctx, &mut xml)
+        .map_err(|e| e.at_part("content.xml", xml.buffer_position() as u64))?;
 
     Ok(ctx.book)
 }
 
+// Exceeded limits are never recovered.
+fn can_recover(lenient: bool, err: &OdsError) -> bool {
+    lenient && !matches!(err.innermost(), OdsError::LimitExceeded { .. })
+}
+
 fn read_ods_extras<R: Read + Seek>(
     ctx: &mut OdsContext,
     zip: &mut ZipArchive<R>,
 ) -> Result<(), OdsError> {
     // now the data if needed ...
+    let lenient = ctx.lenient;
+    let mut warnings = Vec::new();
     for manifest in ctx.book.manifest.values_mut().filter(|v| !v.is_dir()) {
         if !matches!(
             manifest.full_path.as_str(),
             "/" | "settings.xml" | "styles.xml" | "content.xml" | "meta.xml"
         ) {
-            let mut ze = zip.by_name(manifest.full_path.as_str())?;
-            let mut buf = Vec::new();
-            ze.read_to_end(&mut buf)?;
-            manifest.buffer = Some(buf);
+            match read_zip_entry(zip, manifest.full_path.as_str(), ctx.max_inflate_ratio) {
+                Ok(buf) => manifest.buffer = Some(buf),
+                Err(e) if can_recover(lenient, &e) => warnings.push(OdsWarning::Recovered {
+                    context: manifest.full_path.clone(),
+                    message: e.to_string(),
+                }),
+                Err(e) => return Err(e),
+            }
         }
     }
+    ctx.book.warnings.extend(warnings);
 
     Ok(())
 }
 
+fn read_zip_entry<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+    max_inflate_ratio: Option<u64>,
+) -> Result<Vec<u8>, OdsError> {
+    let ze = zip.by_name(name)?;
+    // The size in the header is only a hint, don't trust it blindly.
+    let mut buf = Vec::with_capacity(ze.size().min(1 << 28) as usize);
+    let mut ze = limit_inflate(ze, max_inflate_ratio)?;
+    ze.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+// A missing file is None. A broken file is an error, or None with a
+// warning if the context is lenient.
+#[cfg(feature = "rayon")]
+fn read_opt_zip_entry<R: Read + Seek>(
+    ctx: &mut OdsContext,
+    zip: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, OdsError> {
+    match read_zip_entry(zip, name, ctx.max_inflate_ratio) {
+        Ok(buf) => Ok(Some(buf)),
+        Err(OdsError::Zip(zip::result::ZipError::FileNotFound)) => Ok(None),
+        Err(e) => {
+            ctx.recover(|| name.into(), Err::<(), _>(e))?;
+            Ok(None)
+        }
+    }
+}
+
+// Checks the ratio between uncompressed and compressed size. Reading is
+// capped at the uncompressed size as given in the zip.
+fn limit_inflate(
+    ze: ZipFile<'_>,
+    max_inflate_ratio: Option<u64>,
+) -> Result<Take<ZipFile<'_>>, OdsError> {
+    if let Some(max) = max_inflate_ratio {
+        if ze.size() > ze.compressed_size().max(1).saturating_mul(max) {
+            return Err(OdsError::LimitExceeded {
+                limit: "inflate ratio",
+                max,
+            });
+        }
+        let size = ze.size();
+        Ok(ze.take(size))
+    } else {
+        Ok(ze.take(u64::MAX))
+    }
+}
+
 fn read_ods_manifest(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
     loop {
@@ -475,7 +1257,7 @@ fn read_ods_manifest(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result
                 break;
             }
             _ => {
-                unused_event("read_manifest", &evt)?;
+                ctx.unused_event("read_manifest", &evt)?;
             }
         }
         buf.clear();
@@ -484,34 +1266,68 @@ fn read_ods_manifest(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result
     Ok(())
 }
 
-// Clone cell-data.
-fn calculations(ctx: &mut OdsContext) -> Result<(), OdsError> {
-    for i in 0..ctx.book.num_sheets() {
-        dedup_colheader(ctx.book.sheet_mut(i))?;
-        if ctx.use_repeat_for_cells {
-            calc_repeat_sheet(ctx.book.sheet_mut(i))?;
-        } else {
-            calc_cloned_sheet(ctx.book.sheet_mut(i))?;
-        }
+// Clone cell-data for the rows from..to. The rows must be complete,
+// to is None for the final pass over the rest of the sheet.
+fn calc_rows(
+    ctx: &mut OdsContext,
+    sheet: &mut Sheet,
+    from: u32,
+    to: Option<u32>,
+) -> Result<(), OdsError> {
+    if to.is_none() {
+        calc_last_rows(sheet);
+    }
+    if ctx.use_repeat_for_cells {
+        calc_repeat_sheet(sheet, from, to)
+    } else {
+        calc_cloned_sheet(
+            sheet,
+            from,
+            to,
+            ctx.max_repeat_expansion,
+            &mut ctx.repeat_expansion,
+        )
     }
-    Ok(())
 }
 
-// Cleanup repeat cell-data.
-fn calc_repeat_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
-    let mut dropped = Vec::new();
+// The cells of the rows from..to.
+fn cell_bounds(from: u32, to: Option<u32>) -> impl RangeBounds<(u32, u32)> {
+    (
+        Bound::Included((from, 0)),
+        to.map_or(Bound::Unbounded, |to| Bound::Excluded((to, 0))),
+    )
+}
 
-    // clone by row-repeat
+// Counts the cells created by cloning.
+fn add_expansion(max: Option<u64>, expansion: &mut u64, cells: u64) -> Result<(), OdsError> {
+    *expansion += cells;
+    match max {
+        Some(max) if *expansion > max => Err(OdsError::LimitExceeded {
+            limit: "repeat expansion",
+            max,
+        }),
+        _ => Ok(()),
+    }
+}
 
-    // last two rows often have insane repeat values. clear now.
-    for (_row, rh) in sheet.row_header.iter_mut().rev().take(5) {
-        if rh.repeat > 1000 {
+// last two rows often have insane repeat values. clear now.
+// hidden rows without data keep their repeat.
+fn calc_last_rows(sheet: &mut Sheet) {
+    for (row, rh) in sheet.row_header.iter_mut().rev().take(5) {
+        let hidden = rh.visible != Visibility::Visible
+            && sheet.data.range((*row, 0)..(row + 1, 0)).next().is_none();
+        if rh.repeat > 1000 && !hidden {
             rh.repeat = 1;
         }
     }
+}
+
+// Cleanup repeat cell-data.
+fn calc_repeat_sheet(sheet: &mut Sheet, from: u32, to: Option<u32>) -> Result<(), OdsError> {
+    let mut dropped = Vec::new();
 
     // clone by cell-repeat
-    let mut it = CellDataIterMut::new(sheet.data.range_mut(..));
+    let mut it = CellDataIterMut::new(sheet.data.range_mut(cell_bounds(from, to)));
     loop {
         let Some(((row, col), data)) = it.next() else {
             break;
@@ -538,23 +1354,26 @@ fn calc_repeat_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
 }
 
 // Clone cell-data.
-fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
+fn calc_cloned_sheet(
+    sheet: &mut Sheet,
+    from: u32,
+    to: Option<u32>,
+    max_expansion: Option<u64>,
+    expansion: &mut u64,
+) -> Result<(), OdsError> {
     let mut cloned = Vec::new();
     let mut dropped = Vec::new();
+    let rows = (
+        Bound::Included(from),
+        to.map_or(Bound::Unbounded, Bound::Excluded),
+    );
 
-    // clone by row-repeat
-
-    // last two rows often have insane repeat values. clear now.
-    for (_row, rh) in sheet.row_header.iter_mut().rev().take(5) {
-        if rh.repeat > 1000 {
-            rh.repeat = 1;
-        }
-    }
     // duplicate by row-repeat
-    for (row, rh) in sheet.row_header.iter().filter(|(_, v)| v.repeat > 1) {
+    for (row, rh) in sheet.row_header.range(rows).filter(|(_, v)| v.repeat > 1) {
         // get one row
         let cit = CellDataIter::new(sheet.data.range((*row, 0)..(row + 1, 0)));
         for ((row, col), data) in cit {
+            add_expansion(max_expansion, expansion, rh.repeat as u64 - 1)?;
             for i in 1..rh.repeat {
                 cloned.push((row + i, col, data.clone()));
             }
@@ -565,13 +1384,13 @@ fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
     }
     // after the previous operation the repeat value is reduced to a span where
     // the header-values are valid. no longer denotes repeated row-data.
-    for (_row, rh) in sheet.row_header.iter_mut() {
+    for (_row, rh) in sheet.row_header.range_mut(rows) {
         mem::swap(&mut rh.repeat, &mut rh.span);
     }
 
     // clone by cell-repeat
 
-    let mut it = CellDataIterMut::new(sheet.data.range_mut(..));
+    let mut it = CellDataIterMut::new(sheet.data.range_mut(cell_bounds(from, to)));
     loop {
         let Some(((row, col), data)) = it.next() else {
             break;
@@ -591,6 +1410,7 @@ fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
                 continue;
             }
 
+            add_expansion(max_expansion, expansion, repeat as u64 - 1)?;
             for i in 1..repeat {
                 cloned.push((row, col + i, data.clone()));
             }
@@ -633,6 +1453,70 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
         book.config_mut().show_page_breaks = *n;
     }
 
+    let mut named_views = Vec::new();
+    if let Some(cc) = book.config.get(&["ooo:view-settings", "NamedViews"]) {
+        for (name, cv) in cc.iter() {
+            let mut view = NamedView::new(name, "");
+            if let Some(ConfigValue::String(n)) = cv.get_value_rec(&["ActiveTable"]) {
+                view.active_table = n.clone();
+            }
+            if let Some(ConfigValue::Int(n)) = cv.get_value_rec(&["CursorPositionX"]) {
+                view.cursor_x = *n as u32;
+            }
+            if let Some(ConfigValue::Int(n)) = cv.get_value_rec(&["CursorPositionY"]) {
+                view.cursor_y = *n as u32;
+            }
+            if let Some(ConfigValue::Int(n)) = cv.get_value_rec(&["PositionLeft"]) {
+                view.first_col = *n as u32;
+            }
+            if let Some(ConfigValue::Int(n)) = cv.get_value_rec(&["PositionBottom"]) {
+                view.first_row = *n as u32;
+            }
+            if let Some(ConfigValue::Int(n)) = cv.get_value_rec(&["ZoomValue"]) {
+                view.zoom_value = *n;
+            }
+            if let Some(ConfigValue::String(n)) = cv.get_value_rec(&["HiddenColumns"]) {
+                for col in n.split(',').filter(|v| !v.is_empty()) {
+                    match col.trim().parse() {
+                        Ok(col) => view.hidden_cols.push(col),
+                        Err(e) => book.warnings.push(OdsWarning::Recovered {
+                            context: format!("hidden columns of named view {}", name),
+                            message: format!("{} for {:?}", e, col),
+                        }),
+                    }
+                }
+            }
+            named_views.push(view);
+        }
+    }
+    book.named_views = named_views;
+
+    let mut cell_tags = Vec::new();
+    for (name, expr) in take_named_expressions(&mut book.extra, CELL_TAG_PREFIX) {
+        let range = expr
+            .get_attr("table:cell-range-address")
+            .unwrap_or_default();
+        match parse_cellrange(range) {
+            Ok(range) => cell_tags.push(CellTag::new(name, range, "")),
+            Err(e) => book.warnings.push(OdsWarning::Recovered {
+                context: format!("cell tag {}", name),
+                message: format!("{} for {:?}", e, range),
+            }),
+        }
+    }
+    for (name, expr) in take_named_expressions(&mut book.extra, CELL_TAG_VALUE_PREFIX) {
+        let value = expr
+            .get_attr("table:expression")
+            .and_then(|v| v.strip_prefix("of:=\""))
+            .and_then(|v| v.strip_suffix('"'));
+        if let Some(tag) = cell_tags.iter_mut().find(|v| v.name == name) {
+            if let Some(value) = value {
+                tag.value = value.replace("\"\"", "\"");
+            }
+        }
+    }
+    book.cell_tags = cell_tags;
+
     for i in 0..book.num_sheets() {
         let mut sheet = book.detach_sheet(i);
 
@@ -715,12 +1599,53 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
             }
         }
 
+        for (name, expr) in take_named_expressions(&mut sheet.extra, EDITABLE_RANGE_PREFIX) {
+            let range = expr
+                .get_attr("table:cell-range-address")
+                .unwrap_or_default();
+            match parse_cellrange(range) {
+                Ok(range) => sheet.add_editable_range(name, range),
+                Err(e) => book.warnings.push(OdsWarning::Recovered {
+                    context: format!("editable range {} of {}", name, sheet.name()),
+                    message: format!("{} for {:?}", e, range),
+                }),
+            }
+        }
+
         book.attach_sheet(sheet);
     }
 
     Ok(())
 }
 
+// Removes the named ranges and expressions with the prefix from the
+// named expressions. Returns them with the name without the prefix.
+fn take_named_expressions(extra: &mut Vec<XmlTag>, prefix: &str) -> Vec<(String, XmlTag)> {
+    let mut taken = Vec::new();
+    let Some(idx) = extra
+        .iter()
+        .position(|v| v.name() == "table:named-expressions")
+    else {
+        return taken;
+    };
+    for content in mem::take(extra[idx].content_mut()) {
+        match content {
+            XmlContent::Tag(expr) => match expr
+                .get_attr("table:name")
+                .and_then(|v| v.strip_prefix(prefix))
+            {
+                Some(name) => taken.push((name.to_string(), expr)),
+                None => extra[idx].add_tag(expr),
+            },
+            XmlContent::Text(text) => extra[idx].add_text(text),
+        }
+    }
+    if extra[idx].content().is_empty() {
+        extra.remove(idx);
+    }
+    taken
+}
+
 // Reads the content.xml
 fn read_ods_content(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
@@ -733,7 +1658,7 @@ fn read_ods_content(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
             Event::Decl(_) => {}
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document-content" => {
-                let (version, xmlns) = read_namespaces_and_version(xml, xml_tag)?;
+                let (version, xmlns) = read_namespaces_and_version(ctx, xml, xml_tag)?;
                 if let Some(version) = version {
                     ctx.book.set_version(version);
                 }
@@ -765,7 +1690,7 @@ fn read_ods_content(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 break;
             }
             _ => {
-                unused_event("read_ods_content", &evt)?;
+                ctx.unused_event("read_ods_content", &evt)?;
             }
         }
 
@@ -801,10 +1726,15 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 read_table(ctx, xml, xml_tag)?
             }
 
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"table:calculation-settings" =>
+            {
+                read_calculation_settings(ctx, xml, xml_tag, empty_tag)?;
+            }
+
             // from the prelude
             Event::Empty(xml_tag) | Event::Start(xml_tag)
-                if xml_tag.name().as_ref() == b"table:calculation-settings"
-                    || xml_tag.name().as_ref() == b"table:label-ranges"
+                if xml_tag.name().as_ref() == b"table:label-ranges"
                     || xml_tag.name().as_ref() == b"table:tracked-changes"
                     || xml_tag.name().as_ref() == b"text:alphabetical-index-auto-mark-file"
                     || xml_tag.name().as_ref() == b"text:dde-connection-decls"
@@ -829,8 +1759,7 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
             }
             // from the prelude
             Event::End(xml_tag)
-                if xml_tag.name().as_ref() == b"table:calculation-settings"
-                    || xml_tag.name().as_ref() == b"table:label-ranges"
+                if xml_tag.name().as_ref() == b"table:label-ranges"
                     || xml_tag.name().as_ref() == b"table:tracked-changes"
                     || xml_tag.name().as_ref() == b"text:alphabetical-index-auto-mark-file"
                     || xml_tag.name().as_ref() == b"text:dde-connection-decls"
@@ -849,7 +1778,9 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 break;
             }
             _ => {
-                unused_event("read_office_body", &evt)?;
+                if let Some(v) = read_unknown(ctx, xml, "read_office_body", &evt)? {
+                    ctx.book.extra.push(v);
+                }
             }
         }
 
@@ -860,7 +1791,124 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
     Ok(())
 }
 
+// Reads table:calculation-settings
+fn read_calculation_settings(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+    empty_tag: bool,
+) -> Result<(), OdsError> {
+    let mut settings = CalculationSettings::default();
+
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"table:case-sensitive" => {
+                settings.case_sensitive = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:precision-as-shown" => {
+                settings.precision_as_shown = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:search-criteria-must-apply-to-whole-cell" => {
+                settings.search_criteria_must_apply_to_whole_cell = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:automatic-find-labels" => {
+                settings.automatic_find_labels = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:use-regular-expressions" => {
+                settings.use_regular_expressions = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:use-wildcards" => {
+                settings.use_wildcards = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:null-year" => {
+                settings.null_year = parse_u32(&attr.value)?;
+            }
+            attr => {
+                ctx.unused_attr(
+                    "read_calculation_settings",
+                    super_tag.name().as_ref(),
+                    &attr,
+                )?;
+            }
+        }
+    }
+
+    if !empty_tag {
+        let mut buf = ctx.pop_buf();
+        loop {
+            let evt = xml.read_event_into(&mut buf)?;
+            if cfg!(feature = "dump_xml") {
+                println!(" read_calculation_settings {:?}", evt);
+            }
+            match &evt {
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"table:null-date" =>
+                {
+                    for attr in xml_tag.attributes().with_checks(false) {
+                        match attr? {
+                            attr if attr.key.as_ref() == b"table:date-value" => {
+                                settings.null_date = parse_datetime(&attr.value)?.date();
+                            }
+                            // always date
+                            attr if attr.key.as_ref() == b"table:value-type" => {}
+                            attr => {
+                                ctx.unused_attr(
+                                    "read_calculation_settings",
+                                    xml_tag.name().as_ref(),
+                                    &attr,
+                                )?;
+                            }
+                        }
+                    }
+                }
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:null-date" => {}
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"table:iteration" =>
+                {
+                    for attr in xml_tag.attributes().with_checks(false) {
+                        match attr? {
+                            attr if attr.key.as_ref() == b"table:status" => {
+                                settings.iteration = attr.value.as_ref() == b"enable";
+                            }
+                            attr if attr.key.as_ref() == b"table:steps" => {
+                                settings.iteration_steps = parse_u32(&attr.value)?;
+                            }
+                            attr if attr.key.as_ref() == b"table:minimum-difference" => {
+                                settings.iteration_minimum_difference = parse_f64(&attr.value)?;
+                            }
+                            attr => {
+                                ctx.unused_attr(
+                                    "read_calculation_settings",
+                                    xml_tag.name().as_ref(),
+                                    &attr,
+                                )?;
+                            }
+                        }
+                    }
+                }
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:iteration" => {}
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:calculation-settings" => {
+                    break;
+                }
+                Event::Eof => {
+                    break;
+                }
+                _ => {
+                    ctx.unused_event("read_calculation_settings", &evt)?;
+                }
+            }
+            buf.clear();
+        }
+        ctx.push_buf(buf);
+    }
+
+    ctx.book.calculation_settings = settings;
+
+    Ok(())
+}
+
 fn read_namespaces_and_version(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     super_tag: &BytesStart<'_>,
 ) -> Result<(Option<String>, NamespaceMap), OdsError> {
@@ -888,7 +1936,7 @@ fn read_namespaces_and_version(
                 }
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_namespaces_and_version",
                     super_tag.name().as_ref(),
                     &attr,
@@ -906,8 +1954,18 @@ fn read_table(
     super_tag: &BytesStart<'_>,
 ) -> Result<(), OdsError> {
     let mut sheet = Sheet::new("");
+    sheet.text_pool = Some(ctx.book.text_pool.clone());
+
+    read_table_attr(ctx, xml, &mut sheet, super_tag)?;
 
-    read_table_attr(xml, &mut sheet, super_tag)?;
+    let read_sheet = ctx.read_sheet(sheet.name());
+    ctx.table_idx += 1;
+    if !read_sheet {
+        let mut buf = ctx.pop_buf();
+        xml.read_to_end_into(super_tag.name(), &mut buf)?;
+        ctx.push_buf(buf);
+        return Ok(());
+    }
 
     // Cell
     let mut row: u32 = 0;
@@ -923,6 +1981,10 @@ fn read_table(
     let mut row_range_from = 0;
     let mut row_group = ctx.pop_rowgroup_buf();
 
+    // Spill
+    let mut spill_from: u32 = 0;
+    let mut spill_mark: usize = 0;
+
     let mut buf = ctx.pop_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
@@ -931,30 +1993,99 @@ fn read_table(
             println!(" read_table {:?}", evt);
         }
         match &evt {
+            //
+            // table cells and rows come first, they are by far the most
+            // frequent events.
+            //
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"table:table-cell"
+                    || xml_tag.name().as_ref() == b"table:covered-table-cell" =>
+            {
+                let (cell_repeat, have_data) =
+                    read_table_cell(ctx, xml, &mut sheet, row, col, xml_tag, empty_tag)
+                        .map_err(|e| e.at_cell(sheet.name(), row, Some(col)))?;
+                col = checked_pos(col, cell_repeat)?;
+                col_data |= have_data;
+            }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:table-row" => {
+                col = 0;
+                row_repeat = read_table_row_attr(ctx, xml, &mut sheet, row, xml_tag)
+                    .map_err(|e| e.at_cell(sheet.name(), row, None))?;
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-row" => {
+                if col_data {
+                    ctx.check_row(row, row_repeat)?;
+                    // row-repeat is ignored unless there is any cell-data in that row.
+                    sheet.set_row_repeat(row, row_repeat);
+                }
+                row = checked_pos(row, row_repeat)?;
+                row_repeat = 1;
+                col_data = false;
+
+                match ctx.spill_cells {
+                    Some(max) if sheet.data.len() >= spill_mark.saturating_add(max) => {
+                        // The last rows are only finished at the end.
+                        if let Some(to) = sheet.row_header.keys().rev().nth(4).copied() {
+                            calc_rows(ctx, &mut sheet, spill_from, Some(to))?;
+                            sheet.data.spill(spill_from..to)?;
+                            spill_from = to;
+                        }
+                        spill_mark = sheet.data.len();
+                    }
+                    _ => {}
+                }
+            }
+
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table" => {
                 break;
             }
 
             // Prelude
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"table:table-source" =>
+            {
+                sheet.table_source = Some(Box::new(read_table_source(ctx, xml, xml_tag)?));
+                if !empty_tag {
+                    let mut buf = ctx.pop_buf();
+                    xml.read_to_end_into(xml_tag.name(), &mut buf)?;
+                    ctx.push_buf(buf);
+                }
+            }
             Event::Start(xml_tag) | Event::Empty(xml_tag)
                 if xml_tag.name().as_ref() == b"table:title"
                     || xml_tag.name().as_ref() == b"table:desc"
-                    || xml_tag.name().as_ref() == b"table:table-source"
                     || xml_tag.name().as_ref() == b"office:dde-source"
-                    || xml_tag.name().as_ref() == b"table:scenario"
-                    || xml_tag.name().as_ref() == b"office:forms"
-                    || xml_tag.name().as_ref() == b"table:shapes" =>
+                    || xml_tag.name().as_ref() == b"office:forms" =>
             {
                 sheet.extra.push(read_xml(ctx, xml, xml_tag, empty_tag)?);
             }
             Event::End(xml_tag)
                 if xml_tag.name().as_ref() == b"table:title"
                     || xml_tag.name().as_ref() == b"table:desc"
-                    || xml_tag.name().as_ref() == b"table:table-source"
                     || xml_tag.name().as_ref() == b"office:dde-source"
-                    || xml_tag.name().as_ref() == b"table:scenario"
-                    || xml_tag.name().as_ref() == b"office:forms"
-                    || xml_tag.name().as_ref() == b"table:shapes" => {}
+                    || xml_tag.name().as_ref() == b"office:forms" => {}
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"table:scenario" =>
+            {
+                sheet.scenario = Some(Box::new(read_scenario(ctx, xml, xml_tag)?));
+                if !empty_tag {
+                    let mut buf = ctx.pop_buf();
+                    xml.read_to_end_into(xml_tag.name(), &mut buf)?;
+                    ctx.push_buf(buf);
+                }
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"table:shapes" =>
+            {
+                let shapes = read_shapes(ctx, xml, xml_tag, empty_tag)?;
+                sheet.shapes.extend(shapes);
+            }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparkline-groups" => {}
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparkline-groups" => {}
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparkline-group" => {
+                let group = read_sparkline_group(ctx, xml, xml_tag)?;
+                sheet.sparkline_groups.push(group);
+            }
 
             // Epilogue
             Event::Start(xml_tag) | Event::Empty(xml_tag)
@@ -971,13 +2102,16 @@ fn read_table(
             // table columns
             //
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:table-column-group" => {
-                let v = read_table_column_group_attr(col, xml_tag)?;
+                let v = read_table_column_group_attr(ctx, col, xml_tag)?;
                 col_group.push(v);
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-column-group" => {
                 if let Some(mut v) = col_group.pop() {
-                    v.set_to(col - 1);
-                    sheet.group_cols.push(v);
+                    // drop empty groups.
+                    if col > v.from() {
+                        v.set_to(col - 1);
+                        sheet.group_cols.push(v);
+                    }
                 }
             }
 
@@ -985,7 +2119,9 @@ fn read_table(
                 col_range_from = col;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-header-columns" => {
-                if let Some(header_cols) = &mut sheet.header_cols {
+                if col == 0 {
+                    // empty
+                } else if let Some(header_cols) = &mut sheet.header_cols {
                     header_cols.to = col - 1;
                 } else {
                     sheet.header_cols = Some(Header {
@@ -999,21 +2135,24 @@ fn read_table(
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-columns" => {}
 
             Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"table:table-column" => {
-                let col_repeat = read_table_col_attr(xml, &mut sheet, xml_tag, col)?;
-                col += col_repeat;
+                let col_repeat = read_table_col_attr(ctx, xml, &mut sheet, xml_tag, col)?;
+                col = checked_pos(col, col_repeat)?;
             }
 
             //
             // table rows
             //
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:table-row-group" => {
-                let v = read_table_row_group_attr(row, xml_tag)?;
+                let v = read_table_row_group_attr(ctx, row, xml_tag)?;
                 row_group.push(v);
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-row-group" => {
                 if let Some(mut v) = row_group.pop() {
-                    v.set_to(row - 1);
-                    sheet.group_rows.push(v);
+                    // drop empty groups.
+                    if row > v.from() {
+                        v.set_to(row - 1);
+                        sheet.group_rows.push(v);
+                    }
                 } else {
                     // there are no unbalanced tags.
                 }
@@ -1023,7 +2162,9 @@ fn read_table(
                 row_range_from = row;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-header-rows" => {
-                if let Some(header_rows) = &mut sheet.header_rows {
+                if row == 0 {
+                    // empty
+                } else if let Some(header_rows) = &mut sheet.header_rows {
                     header_rows.to = row - 1;
                 } else {
                     sheet.header_rows = Some(Header {
@@ -1039,35 +2180,16 @@ fn read_table(
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-rows" => {
                 // noop
             }
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:table-row" => {
-                col = 0;
-                row_repeat = read_table_row_attr(xml, &mut sheet, row, xml_tag)?;
-            }
-            Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-row" => {
-                if col_data {
-                    // row-repeat is ignored unless there is any cell-data in that row.
-                    sheet.set_row_repeat(row, row_repeat);
-                }
-                row += row_repeat;
-                row_repeat = 1;
-                col_data = false;
-            }
-
-            //
-            // table cells
-            //
-            Event::Empty(xml_tag) | Event::Start(xml_tag)
-                if xml_tag.name().as_ref() == b"table:table-cell"
-                    || xml_tag.name().as_ref() == b"table:covered-table-cell" =>
-            {
-                let (cell_repeat, have_data) =
-                    read_table_cell(ctx, xml, &mut sheet, row, col, xml_tag, empty_tag)?;
-                col += cell_repeat;
-                col_data |= have_data;
-            }
 
             _ => {
-                unused_event("read_table", &evt)?;
+                if let Some(v) = read_unknown(ctx, xml, "read_table", &evt)? {
+                    // keep the position relative to columns and rows.
+                    if col == 0 && row == 0 {
+                        sheet.extra.push(v);
+                    } else {
+                        sheet.extra_rows.push((row, v));
+                    }
+                }
             }
         }
         buf.clear();
@@ -1077,13 +2199,18 @@ fn read_table(
     ctx.push_colgroup_buf(col_group);
     ctx.push_rowgroup_buf(row_group);
 
-    ctx.book.push_sheet(sheet);
+    dedup_colheader(&mut sheet)?;
+    calc_rows(ctx, &mut sheet, spill_from, None)?;
+
+    // keep the name as is, even if it is a duplicate.
+    ctx.book.sheets.push(sheet.into());
 
     Ok(())
 }
 
 // Reads the table attributes.
 fn read_table_attr(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     sheet: &mut Sheet,
     super_tag: &BytesStart<'_>,
@@ -1103,12 +2230,15 @@ fn read_table_attr(
             attr if attr.key.as_ref() == b"table:display" => {
                 sheet.set_display(parse_bool(&attr.value)?);
             }
+            attr if attr.key.as_ref() == b"table:protected" => {
+                sheet.set_protected(parse_bool(&attr.value)?);
+            }
             attr if attr.key.as_ref() == b"table:print-ranges" => {
                 let v = attr.decode_and_unescape_value(xml)?;
                 sheet.print_ranges = parse_cellranges(v.as_ref())?;
             }
             attr => {
-                unused_attr("read_table_attr", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_table_attr", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1118,6 +2248,7 @@ fn read_table_attr(
 
 // Reads table-row attributes. Returns the repeat-count.
 fn read_table_row_attr(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     sheet: &mut Sheet,
     row: u32,
@@ -1127,27 +2258,28 @@ fn read_table_row_attr(
     let mut row_header = None;
 
     for attr in super_tag.attributes().with_checks(false) {
-        match attr? {
+        let attr = attr?;
+        match attr.key.as_ref() {
             // table:default-cell-style-name 19.615, table:visibility 19.749 and xml:id 19.914.
-            attr if attr.key.as_ref() == b"table:number-rows-repeated" => {
-                row_repeat = parse_u32(&attr.value)?;
+            b"table:number-rows-repeated" => {
+                row_repeat = parse_repeat(&attr.value)?;
             }
-            attr if attr.key.as_ref() == b"table:style-name" => {
+            b"table:style-name" => {
                 let name = attr.decode_and_unescape_value(xml)?;
                 row_header.get_or_insert_with(RowHeader::default).style =
                     Some(RowStyleRef::from(name.as_ref()));
             }
-            attr if attr.key.as_ref() == b"table:default-cell-style-name" => {
+            b"table:default-cell-style-name" => {
                 let name = attr.decode_and_unescape_value(xml)?;
                 row_header.get_or_insert_with(RowHeader::default).cellstyle =
                     Some(CellStyleRef::from(name.as_ref()));
             }
-            attr if attr.key.as_ref() == b"table:visibility" => {
+            b"table:visibility" => {
                 let visible = parse_visibility(&attr.value)?;
                 row_header.get_or_insert_with(RowHeader::default).visible = visible;
             }
-            attr => {
-                unused_attr("read_table_row_attr", super_tag.name().as_ref(), &attr)?;
+            _ => {
+                ctx.unused_attr("read_table_row_attr", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1162,6 +2294,7 @@ fn read_table_row_attr(
 
 // Reads the table:table-column-group attributes.
 fn read_table_column_group_attr(
+    ctx: &mut OdsContext,
     table_col: u32,
     super_tag: &BytesStart<'_>,
 ) -> Result<Grouped, OdsError> {
@@ -1173,7 +2306,7 @@ fn read_table_column_group_attr(
                 display = parse_bool(&attr.value)?;
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_table_column_group_attr",
                     super_tag.name().as_ref(),
                     &attr,
@@ -1189,8 +2322,177 @@ fn read_table_column_group_attr(
     })
 }
 
+// Reads the table:table-source attributes.
+fn read_table_source(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<TableSource, OdsError> {
+    let mut table_source = TableSource::default();
+
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"xlink:href" => {
+                table_source.href = attr.decode_and_unescape_value(xml)?.to_string();
+            }
+            // always simple/onRequest
+            attr if attr.key.as_ref() == b"xlink:type" => {}
+            attr if attr.key.as_ref() == b"xlink:actuate" => {}
+            attr if attr.key.as_ref() == b"table:table-name" => {
+                table_source.table_name = Some(attr.decode_and_unescape_value(xml)?.to_string());
+            }
+            attr if attr.key.as_ref() == b"table:filter-name" => {
+                table_source.filter_name = Some(attr.decode_and_unescape_value(xml)?.to_string());
+            }
+            attr if attr.key.as_ref() == b"table:filter-options" => {
+                table_source.filter_options =
+                    Some(attr.decode_and_unescape_value(xml)?.to_string());
+            }
+            attr if attr.key.as_ref() == b"table:mode" => {
+                table_source.mode = parse_table_source_mode(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:refresh-delay" => {
+                table_source.refresh_delay = Some(parse_duration(
+                    attr.decode_and_unescape_value(xml)?.as_bytes(),
+                )?);
+            }
+            attr => {
+                ctx.unused_attr("read_table_source", super_tag.name().as_ref(), &attr)?;
+            }
+        }
+    }
+
+    Ok(table_source)
+}
+
+fn read_sparkline_group(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<SparklineGroup, OdsError> {
+    let mut group = SparklineGroup::default();
+
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"calcext:id" => {
+                group.set_id(attr.decode_and_unescape_value(xml)?.as_ref());
+            }
+            attr if attr.key.as_ref() == b"calcext:type" => {
+                group.set_sparkline_type(SparklineType::try_from(attr.value.as_ref())?);
+            }
+            attr => {
+                let k = ctx.attr_name(attr.key.as_ref())?;
+                let v = Box::<str>::from(attr.decode_and_unescape_value(xml)?.as_ref());
+                group.attrmap_mut().push_attr_atom(k, v);
+            }
+        }
+    }
+
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        if cfg!(feature = "dump_xml") {
+            println!("read_sparkline_group {:?}", evt);
+        }
+        match &evt {
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparkline-group" => {
+                break;
+            }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparklines" => {}
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparklines" => {}
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"calcext:sparkline" =>
+            {
+                let mut sparkline = Sparkline::default();
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"calcext:cell-address" => {
+                            let v = attr.decode_and_unescape_value(xml)?;
+                            sparkline.set_cell(parse_cellref(v.as_ref())?);
+                        }
+                        attr if attr.key.as_ref() == b"calcext:data-range" => {
+                            let v = attr.decode_and_unescape_value(xml)?;
+                            sparkline.set_data_range(parse_cellrange(v.as_ref())?);
+                        }
+                        attr => {
+                            ctx.unused_attr(
+                                "read_sparkline_group",
+                                xml_tag.name().as_ref(),
+                                &attr,
+                            )?;
+                        }
+                    }
+                }
+                group.push_sparkline(sparkline);
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"calcext:sparkline" => {}
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                ctx.unused_event("read_sparkline_group", &evt)?;
+            }
+        }
+
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(group)
+}
+
+fn read_scenario(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<Scenario, OdsError> {
+    let mut scenario = Scenario::default();
+
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"table:scenario-ranges" => {
+                let v = attr.decode_and_unescape_value(xml)?;
+                scenario.ranges = parse_cellranges(v.as_ref())?.unwrap_or_default();
+            }
+            attr if attr.key.as_ref() == b"table:is-active" => {
+                scenario.is_active = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:display-border" => {
+                scenario.display_border = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:border-color" => {
+                scenario.border_color = Some(parse_color(&attr.value)?);
+            }
+            attr if attr.key.as_ref() == b"table:copy-back" => {
+                scenario.copy_back = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:copy-styles" => {
+                scenario.copy_styles = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:copy-formulas" => {
+                scenario.copy_formulas = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:comment" => {
+                scenario.comment = Some(attr.decode_and_unescape_value(xml)?.to_string());
+            }
+            attr if attr.key.as_ref() == b"table:protected" => {
+                scenario.protected = parse_bool(&attr.value)?;
+            }
+            attr => {
+                ctx.unused_attr("read_scenario", super_tag.name().as_ref(), &attr)?;
+            }
+        }
+    }
+
+    Ok(scenario)
+}
+
 // Reads the table:table-row-group attributes.
-fn read_table_row_group_attr(row: u32, super_tag: &BytesStart<'_>) -> Result<Grouped, OdsError> {
+fn read_table_row_group_attr(
+    ctx: &mut OdsContext,
+    row: u32,
+    super_tag: &BytesStart<'_>,
+) -> Result<Grouped, OdsError> {
     let mut display = true;
 
     for attr in super_tag.attributes().with_checks(false) {
@@ -1199,7 +2501,7 @@ fn read_table_row_group_attr(row: u32, super_tag: &BytesStart<'_>) -> Result<Gro
                 display = parse_bool(&attr.value)?;
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_table_row_group_attr",
                     super_tag.name().as_ref(),
                     &attr,
@@ -1217,6 +2519,7 @@ fn read_table_row_group_attr(row: u32, super_tag: &BytesStart<'_>) -> Result<Gro
 
 // Reads the table-column attributes. Creates as many copies as indicated.
 fn read_table_col_attr(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     sheet: &mut Sheet,
     super_tag: &BytesStart<'_>,
@@ -1228,7 +2531,7 @@ fn read_table_col_attr(
     for attr in super_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key.as_ref() == b"table:number-columns-repeated" => {
-                col_repeat = parse_u32(&attr.value)?;
+                col_repeat = parse_repeat(&attr.value)?;
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
                 let name = attr.decode_and_unescape_value(xml)?;
@@ -1245,7 +2548,7 @@ fn read_table_col_attr(
                 col_header.get_or_insert_with(ColHeader::default).visible = visible;
             }
             attr => {
-                unused_attr("read_table_col_attr", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_table_col_attr", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1271,6 +2574,7 @@ enum TextContent {
 struct ReadTableCell {
     val_type: ValueType,
     val_datetime: Option<NaiveDateTime>,
+    val_datetime_tz: Option<DateTime<FixedOffset>>,
     val_duration: Option<Duration>,
     val_float: Option<f64>,
     val_bool: Option<bool>,
@@ -1302,6 +2606,7 @@ fn read_table_cell(
     let mut tc = ReadTableCell {
         val_type: ValueType::Empty,
         val_datetime: None,
+        val_datetime_tz: None,
         val_duration: None,
         val_float: None,
         val_bool: None,
@@ -1309,13 +2614,16 @@ fn read_table_cell(
         val_currency: None,
         content: TextContent::Empty,
     };
+    // lenient: a value could not be parsed.
+    let mut value_broken = false;
 
     for attr in super_tag.attributes().with_checks(false) {
-        match attr? {
-            attr if attr.key.as_ref() == b"table:number-columns-repeated" => {
-                repeat = parse_u32(&attr.value)?;
+        let attr = attr?;
+        match attr.key.as_ref() {
+            b"table:number-columns-repeated" => {
+                repeat = parse_repeat(&attr.value)?;
             }
-            attr if attr.key.as_ref() == b"table:number-rows-spanned" => {
+            b"table:number-rows-spanned" => {
                 let row_span = parse_u32(&attr.value)?;
                 if row_span > 1 {
                     cell.get_or_insert_with(CellData::default)
@@ -1324,7 +2632,7 @@ fn read_table_cell(
                         .row_span = row_span;
                 }
             }
-            attr if attr.key.as_ref() == b"table:number-columns-spanned" => {
+            b"table:number-columns-spanned" => {
                 let col_span = parse_u32(&attr.value)?;
                 if col_span > 1 {
                     cell.get_or_insert_with(CellData::default)
@@ -1333,7 +2641,7 @@ fn read_table_cell(
                         .col_span = col_span;
                 }
             }
-            attr if attr.key.as_ref() == b"table:number-matrix-rows-spanned" => {
+            b"table:number-matrix-rows-spanned" => {
                 let row_span = parse_u32(&attr.value)?;
                 if row_span > 1 {
                     cell.get_or_insert_with(CellData::default)
@@ -1342,7 +2650,7 @@ fn read_table_cell(
                         .row_span = row_span;
                 }
             }
-            attr if attr.key.as_ref() == b"table:number-matrix-columns-spanned" => {
+            b"table:number-matrix-columns-spanned" => {
                 let col_span = parse_u32(&attr.value)?;
                 if col_span > 1 {
                     cell.get_or_insert_with(CellData::default)
@@ -1351,16 +2659,16 @@ fn read_table_cell(
                         .col_span = col_span;
                 }
             }
-            attr if attr.key.as_ref() == b"table:content-validation-name" => {
+            b"table:content-validation-name" => {
                 let name = attr.decode_and_unescape_value(xml)?;
                 cell.get_or_insert_with(CellData::default)
                     .extra_mut()
                     .validation_name = Some(ValidationRef::from(name.as_ref()));
             }
-            attr if attr.key.as_ref() == b"calcext:value-type" => {
+            b"calcext:value-type" => {
                 // not used. office:value-type seems to be good enough.
             }
-            attr if attr.key.as_ref() == b"office:value-type" => {
+            b"office:value-type" => {
                 cell.get_or_insert_with(CellData::default);
                 tc.val_type = match attr.value.as_ref() {
                     b"string" => ValueType::Text,
@@ -1371,52 +2679,80 @@ fn read_table_cell(
                     b"boolean" => ValueType::Boolean,
                     b"currency" => ValueType::Currency,
                     other => {
-                        return Err(OdsError::Parse(
+                        let err = OdsError::Parse(
                             "Unknown cell-type {:?}",
                             Some(from_utf8(other)?.into()),
-                        ));
+                        );
+                        ctx.recover(|| cell_pos(sheet, row, col), Err::<(), _>(err))?;
+                        ValueType::Text
                     }
                 }
             }
-            attr if attr.key.as_ref() == b"office:date-value" => {
+            b"office:date-value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_datetime = Some(parse_datetime(&attr.value)?);
+                let res = parse_datetime_tz(&attr.value);
+                let res = ctx.recover(|| cell_pos(sheet, row, col), res)?;
+                value_broken |= res.is_none();
+                if let Some((v, tz)) = res {
+                    tc.val_datetime = Some(v);
+                    if let Some(tz) = tz {
+                        match ctx.timezone {
+                            TimezonePolicy::Local => {}
+                            TimezonePolicy::Utc => {
+                                tc.val_datetime =
+                                    v.and_local_timezone(tz).single().map(|v| v.naive_utc());
+                            }
+                            TimezonePolicy::Keep => {
+                                tc.val_datetime_tz = v.and_local_timezone(tz).single();
+                            }
+                        }
+                    }
+                }
             }
-            attr if attr.key.as_ref() == b"office:time-value" => {
+            b"office:time-value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_duration = Some(parse_duration(&attr.value)?);
+                let res = parse_duration(&attr.value);
+                tc.val_duration = ctx.recover(|| cell_pos(sheet, row, col), res)?;
+                value_broken |= tc.val_duration.is_none();
             }
-            attr if attr.key.as_ref() == b"office:value" => {
+            b"office:value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_float = Some(parse_f64(&attr.value)?);
+                let res = parse_f64(&attr.value);
+                tc.val_float = ctx.recover(|| cell_pos(sheet, row, col), res)?;
+                value_broken |= tc.val_float.is_none();
             }
-            attr if attr.key.as_ref() == b"office:boolean-value" => {
+            b"office:boolean-value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_bool = Some(parse_bool(&attr.value)?);
+                let res = parse_bool(&attr.value);
+                tc.val_bool = ctx.recover(|| cell_pos(sheet, row, col), res)?;
+                value_broken |= tc.val_bool.is_none();
             }
-            attr if attr.key.as_ref() == b"office:string-value" => {
+            b"office:string-value" => {
                 cell.get_or_insert_with(CellData::default);
                 tc.val_string = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
-            attr if attr.key.as_ref() == b"office:currency" => {
+            b"office:currency" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_currency = Some(parse_currency(&attr.value)?);
+                tc.val_currency = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
-            attr if attr.key.as_ref() == b"table:formula" => {
+            b"table:formula" => {
                 cell.get_or_insert_with(CellData::default).formula =
                     Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
-            attr if attr.key.as_ref() == b"table:style-name" => {
+            b"table:style-name" => {
                 let name = attr.decode_and_unescape_value(xml)?;
                 cell.get_or_insert_with(CellData::default).style =
                     Some(CellStyleRef::from(name.as_ref()));
             }
-            attr => {
-                unused_attr("read_table_cell2", super_tag.name().as_ref(), &attr)?;
+            _ => {
+                ctx.unused_attr("read_table_cell2", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
 
+    // The text is only the value of string cells, or of a broken value.
+    let need_text = value_broken || (tc.val_type == ValueType::Text && tc.val_string.is_none());
+
     if !empty_tag {
         let mut buf = ctx.pop_buf();
         loop {
@@ -1427,8 +2763,14 @@ fn read_table_cell(
             match &evt {
                 Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"text:p" => {}
                 Event::Start(xml_tag) if xml_tag.name().as_ref() == b"text:p" => {
-                    let new_txt = read_text_or_tag(ctx, xml, xml_tag, false)?;
-                    tc.content = append_text(new_txt, tc.content);
+                    if need_text {
+                        let new_txt = read_text_or_tag(ctx, xml, xml_tag, false)?;
+                        tc.content = append_text(new_txt, tc.content);
+                    } else {
+                        let mut buf = ctx.pop_buf();
+                        xml.read_to_end_into(xml_tag.name(), &mut buf)?;
+                        ctx.push_buf(buf);
+                    }
                 }
 
                 Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:annotation" => {
@@ -1452,7 +2794,7 @@ fn read_table_cell(
                     break;
                 }
                 _ => {
-                    unused_event("read_table_cell", &evt)?;
+                    ctx.unused_event("read_table_cell", &evt)?;
                 }
             }
 
@@ -1461,9 +2803,17 @@ fn read_table_cell(
         ctx.push_buf(buf);
     }
 
+    // keep the text content instead.
+    if value_broken {
+        tc.val_type = ValueType::Text;
+    }
+
     let have_data = if let Some(mut cell) = cell {
         // composes a Value
-        set_value(tc, &mut cell)?;
+        set_value(tc, &mut cell, &ctx.book.text_pool)?;
+        if ctx.ignore_cached_values && cell.formula.is_some() {
+            cell.value = Value::Empty;
+        }
 
         // store cell-data
         if ignore_cell(ctx, default_cellstyle, &cell) {
@@ -1515,8 +2865,8 @@ fn append_text(new_txt: TextContent, mut content: TextContent) -> TextContent {
                 TextContent::Xml(xml) => {
                     vec.push(xml);
                 }
-                TextContent::XmlVec(_) => {
-                    unreachable!();
+                TextContent::XmlVec(v) => {
+                    vec.extend(v);
                 }
             }
             TextContent::XmlVec(vec)
@@ -1532,8 +2882,8 @@ fn append_text(new_txt: TextContent, mut content: TextContent) -> TextContent {
                 TextContent::Xml(xml) => {
                     vec.push(xml);
                 }
-                TextContent::XmlVec(_) => {
-                    unreachable!();
+                TextContent::XmlVec(v) => {
+                    vec.extend(v);
                 }
             }
             TextContent::XmlVec(vec)
@@ -1548,8 +2898,8 @@ fn append_text(new_txt: TextContent, mut content: TextContent) -> TextContent {
                 TextContent::Xml(xml) => {
                     vec.push(xml);
                 }
-                TextContent::XmlVec(_) => {
-                    unreachable!();
+                TextContent::XmlVec(v) => {
+                    vec.extend(v);
                 }
             }
             TextContent::XmlVec(vec)
@@ -1560,7 +2910,7 @@ fn append_text(new_txt: TextContent, mut content: TextContent) -> TextContent {
 }
 
 #[inline(always)]
-fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
+fn set_value(tc: ReadTableCell, cell: &mut CellData, text_pool: &TextPool) -> Result<(), OdsError> {
     match tc.val_type {
         ValueType::Empty => {
             // noop
@@ -1599,14 +2949,14 @@ fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
         }
         ValueType::Text => {
             if let Some(v) = tc.val_string {
-                cell.value = Value::Text(v);
+                cell.value = Value::Text(text_pool.intern(&v));
             } else {
                 match tc.content {
                     TextContent::Empty => {
                         // noop
                     }
                     TextContent::Text(txt) => {
-                        cell.value = Value::Text(txt);
+                        cell.value = Value::Text(text_pool.intern(&txt));
                     }
                     TextContent::Xml(xml) => {
                         cell.value = Value::TextXml(vec![xml]);
@@ -1618,10 +2968,12 @@ fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
             }
         }
         ValueType::TextXml => {
-            unreachable!();
+            return Err(OdsError::Parse("invalid value type", None));
         }
         ValueType::DateTime => {
-            if let Some(v) = tc.val_datetime {
+            if let Some(v) = tc.val_datetime_tz {
+                cell.value = Value::DateTimeTz(v);
+            } else if let Some(v) = tc.val_datetime {
                 cell.value = Value::DateTime(v);
             } else {
                 return Err(OdsError::Parse("no datetime value", None));
@@ -1695,69 +3047,289 @@ fn read_annotation(
                 break;
             }
             _ => {
-                unused_event("read_annotation", &evt)?;
+                ctx.unused_event("read_annotation", &evt)?;
+            }
+        }
+
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(annotation)
+}
+
+fn read_draw_frame(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<DrawFrame, OdsError> {
+    let mut draw_frame = DrawFrame::new();
+
+    copy_attr2(ctx, xml, draw_frame.attrmap_mut(), super_tag)?;
+
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        let empty_tag = matches!(evt, Event::Empty(_));
+        if cfg!(feature = "dump_xml") {
+            println!("read_draw_frame {:?}", evt);
+        }
+        match &evt {
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"draw:frame" => {
+                break;
+            }
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"draw:image" =>
+            {
+                draw_frame.push_content(DrawFrameContent::Image(read_image(
+                    ctx, xml, xml_tag, empty_tag,
+                )?));
+            }
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"draw:object" =>
+            {
+                draw_frame.push_content(DrawFrameContent::Object(read_draw_object(
+                    ctx, xml, xml_tag, empty_tag,
+                )?));
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {}
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {
+                if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
+                    draw_frame.set_desc(v);
+                }
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {}
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {
+                if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
+                    draw_frame.set_title(v);
+                }
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                ctx.unused_event("read_draw_frame", &evt)?;
+            }
+        }
+
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(draw_frame)
+}
+
+fn read_shapes(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+    empty_tag: bool,
+) -> Result<Vec<DrawShape>, OdsError> {
+    let mut shapes = Vec::new();
+    if empty_tag {
+        return Ok(shapes);
+    }
+
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        let empty_tag = matches!(evt, Event::Empty(_));
+        if cfg!(feature = "dump_xml") {
+            println!("read_shapes {:?}", evt);
+        }
+        match &evt {
+            Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
+                break;
+            }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"draw:frame" => {
+                shapes.push(DrawShape::Frame(read_draw_frame(ctx, xml, xml_tag)?));
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"draw:rect" =>
+            {
+                shapes.push(DrawShape::Rect(read_draw_rect(
+                    ctx, xml, xml_tag, empty_tag,
+                )?));
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"draw:line" =>
+            {
+                shapes.push(DrawShape::Line(read_draw_line(
+                    ctx, xml, xml_tag, empty_tag,
+                )?));
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag) => {
+                shapes.push(DrawShape::Xml(read_xml(ctx, xml, xml_tag, empty_tag)?));
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                ctx.unused_event("read_shapes", &evt)?;
+            }
+        }
+
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(shapes)
+}
+
+fn read_draw_rect(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+    empty_tag: bool,
+) -> Result<DrawRect, OdsError> {
+    let mut draw_rect = DrawRect::new();
+
+    copy_attr2(ctx, xml, draw_rect.attrmap_mut(), super_tag)?;
+
+    if !empty_tag {
+        let mut buf = ctx.pop_buf();
+        loop {
+            let evt = xml.read_event_into(&mut buf)?;
+            let empty_tag = matches!(evt, Event::Empty(_));
+            if cfg!(feature = "dump_xml") {
+                println!("read_draw_rect {:?}", evt);
+            }
+            match &evt {
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"draw:rect" => {
+                    break;
+                }
+                Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {}
+                Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {
+                    if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
+                        draw_rect.set_desc(v);
+                    }
+                }
+                Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {}
+                Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {
+                    if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
+                        draw_rect.set_title(v);
+                    }
+                }
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"text:list"
+                        || xml_tag.name().as_ref() == b"text:p" =>
+                {
+                    draw_rect.push_text(read_xml(ctx, xml, xml_tag, empty_tag)?);
+                }
+                Event::Eof => {
+                    break;
+                }
+                _ => {
+                    ctx.unused_event("read_draw_rect", &evt)?;
+                }
             }
-        }
 
-        buf.clear();
+            buf.clear();
+        }
+        ctx.push_buf(buf);
     }
-    ctx.push_buf(buf);
 
-    Ok(annotation)
+    Ok(draw_rect)
 }
 
-fn read_draw_frame(
+fn read_draw_line(
     ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     super_tag: &BytesStart<'_>,
-) -> Result<DrawFrame, OdsError> {
-    let mut draw_frame = DrawFrame::new();
+    empty_tag: bool,
+) -> Result<DrawLine, OdsError> {
+    let mut draw_line = DrawLine::new();
 
-    copy_attr2(xml, draw_frame.attrmap_mut(), super_tag)?;
+    copy_attr2(ctx, xml, draw_line.attrmap_mut(), super_tag)?;
 
-    let mut buf = ctx.pop_buf();
-    loop {
-        let evt = xml.read_event_into(&mut buf)?;
-        let empty_tag = matches!(evt, Event::Empty(_));
-        if cfg!(feature = "dump_xml") {
-            println!("read_draw_frame {:?}", evt);
-        }
-        match &evt {
-            Event::End(xml_tag) if xml_tag.name().as_ref() == b"draw:frame" => {
-                break;
-            }
-            Event::Empty(xml_tag) | Event::Start(xml_tag)
-                if xml_tag.name().as_ref() == b"draw:image" =>
-            {
-                draw_frame.push_content(DrawFrameContent::Image(read_image(
-                    ctx, xml, xml_tag, empty_tag,
-                )?));
+    if !empty_tag {
+        let mut buf = ctx.pop_buf();
+        loop {
+            let evt = xml.read_event_into(&mut buf)?;
+            let empty_tag = matches!(evt, Event::Empty(_));
+            if cfg!(feature = "dump_xml") {
+                println!("read_draw_line {:?}", evt);
             }
-            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {}
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {
-                if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
-                    draw_frame.set_desc(v);
+            match &evt {
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"draw:line" => {
+                    break;
                 }
-            }
-            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {}
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {
-                if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
-                    draw_frame.set_title(v);
+                Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {}
+                Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {
+                    if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
+                        draw_line.set_desc(v);
+                    }
+                }
+                Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {}
+                Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:title" => {
+                    if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
+                        draw_line.set_title(v);
+                    }
+                }
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"text:list"
+                        || xml_tag.name().as_ref() == b"text:p" =>
+                {
+                    draw_line.push_text(read_xml(ctx, xml, xml_tag, empty_tag)?);
+                }
+                Event::Eof => {
+                    break;
+                }
+                _ => {
+                    ctx.unused_event("read_draw_line", &evt)?;
                 }
             }
-            Event::Eof => {
-                break;
+
+            buf.clear();
+        }
+        ctx.push_buf(buf);
+    }
+
+    Ok(draw_line)
+}
+
+fn read_draw_object(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+    empty_tag: bool,
+) -> Result<DrawObject, OdsError> {
+    let mut draw_object = DrawObject::new();
+
+    copy_attr2(ctx, xml, draw_object.attrmap_mut(), super_tag)?;
+
+    if !empty_tag {
+        let mut buf = ctx.pop_buf();
+        loop {
+            let evt = xml.read_event_into(&mut buf)?;
+            let empty_tag = matches!(evt, Event::Empty(_));
+            if cfg!(feature = "dump_xml") {
+                println!("read_draw_object {:?}", evt);
             }
-            _ => {
-                unused_event("read_draw_frame", &evt)?;
+            match &evt {
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"draw:object" => {
+                    break;
+                }
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"math:math"
+                        || xml_tag.name().as_ref() == b"office:document" =>
+                {
+                    draw_object.set_content(read_xml(ctx, xml, xml_tag, empty_tag)?);
+                }
+                Event::Eof => {
+                    break;
+                }
+                _ => {
+                    ctx.unused_event("read_draw_object", &evt)?;
+                }
             }
-        }
 
-        buf.clear();
+            buf.clear();
+        }
+        ctx.push_buf(buf);
     }
-    ctx.push_buf(buf);
 
-    Ok(draw_frame)
+    Ok(draw_object)
 }
 
 fn read_image(
@@ -1768,7 +3340,7 @@ fn read_image(
 ) -> Result<DrawImage, OdsError> {
     let mut draw_image = DrawImage::new();
 
-    copy_attr2(xml, draw_image.attrmap_mut(), super_tag)?;
+    copy_attr2(ctx, xml, draw_image.attrmap_mut(), super_tag)?;
 
     if !empty_tag {
         let mut buf = ctx.pop_buf();
@@ -1799,7 +3371,7 @@ fn read_image(
                     break;
                 }
                 _ => {
-                    unused_event("read_image", &evt)?;
+                    ctx.unused_event("read_image", &evt)?;
                 }
             }
 
@@ -1836,8 +3408,8 @@ fn read_scripts(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(),
             Event::Start(xml_tag) | Event::Empty(xml_tag)
                 if xml_tag.name().as_ref() == b"script:event-listener" =>
             {
-                ctx.book
-                    .add_event_listener(read_event_listener(xml, xml_tag)?);
+                let event_listener = read_event_listener(ctx, xml, xml_tag)?;
+                ctx.book.add_event_listener(event_listener);
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"script:event-listener" => {}
 
@@ -1845,7 +3417,7 @@ fn read_scripts(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(),
                 break;
             }
             _ => {
-                unused_event("read_scripts", &evt)?;
+                ctx.unused_event("read_scripts", &evt)?;
             }
         }
 
@@ -1874,6 +3446,7 @@ fn read_script(
 
 // reads the page-layout tag
 fn read_event_listener(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     super_tag: &BytesStart<'_>,
 ) -> Result<EventListener, OdsError> {
@@ -1899,7 +3472,7 @@ fn read_event_listener(
                 evt.link_type = parse_xlink_type(attr.decode_and_unescape_value(xml)?.as_bytes())?;
             }
             attr => {
-                unused_attr("read_event_listener", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_event_listener", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1922,16 +3495,45 @@ fn read_office_font_face_decls(
             println!(" read_fonts {:?}", evt);
         }
         match &evt {
-            Event::Start(xml_tag) | Event::Empty(xml_tag)
-                if xml_tag.name().as_ref() == b"style:font-face" =>
-            {
-                let name = copy_style_attr(xml, font.attrmap_mut(), xml_tag)?;
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"style:font-face" => {
+                let name = copy_style_attr(ctx, xml, font.attrmap_mut(), xml_tag)?;
+                font.set_name(name);
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"style:font-face" => {
+                let name = copy_style_attr(ctx, xml, font.attrmap_mut(), xml_tag)?;
                 font.set_name(name);
                 ctx.book.add_font(font);
 
                 font = FontFaceDecl::new_empty();
-                font.set_origin(StyleOrigin::Content);
+                font.set_origin(origin);
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:font-face" => {
+                ctx.book.add_font(font);
+
+                font = FontFaceDecl::new_empty();
+                font.set_origin(origin);
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"svg:font-face-uri" =>
+            {
+                let mut uri = FontFaceUri::new_empty();
+                copy_attr2(ctx, xml, uri.attrmap_mut(), xml_tag)?;
+                font.push_src(uri);
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:font-face-format" => {
+                for attr in xml_tag.attributes().with_checks(false) {
+                    let attr = attr?;
+                    if attr.key.as_ref() == b"svg:string" {
+                        if let Some(uri) = font.src_mut().last_mut() {
+                            uri.set_format(attr.decode_and_unescape_value(xml)?);
+                        }
+                    }
+                }
             }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:font-face-src" => {}
+            Event::End(xml_tag)
+                if xml_tag.name().as_ref() == b"svg:font-face-src"
+                    || xml_tag.name().as_ref() == b"svg:font-face-uri" => {}
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:font-face-decls" => {
                 break;
             }
@@ -1939,7 +3541,7 @@ fn read_office_font_face_decls(
                 break;
             }
             _ => {
-                unused_event("read_fonts", &evt)?;
+                ctx.unused_event("read_fonts", &evt)?;
             }
         }
 
@@ -1968,7 +3570,7 @@ fn read_page_style(
                 pl.master_page_usage = Some(value.to_string());
             }
             attr => {
-                unused_attr("read_page_style", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_page_style", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1986,7 +3588,7 @@ fn read_page_style(
             Event::Start(xml_tag) | Event::Empty(xml_tag)
                 if xml_tag.name().as_ref() == b"style:page-layout-properties" =>
             {
-                copy_attr2(xml, pl.style_mut(), xml_tag)?;
+                copy_attr2(ctx, xml, pl.style_mut(), xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:page-layout-properties" => {}
 
@@ -2012,10 +3614,10 @@ fn read_page_style(
                 if xml_tag.name().as_ref() == b"style:header-footer-properties" =>
             {
                 if headerstyle {
-                    copy_attr2(xml, pl.headerstyle_mut().style_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, pl.headerstyle_mut().style_mut(), xml_tag)?;
                 }
                 if footerstyle {
-                    copy_attr2(xml, pl.footerstyle_mut().style_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, pl.footerstyle_mut().style_mut(), xml_tag)?;
                 }
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:header-footer-properties" => {
@@ -2033,7 +3635,7 @@ fn read_page_style(
             Event::Text(_) => (),
             Event::Eof => break,
             _ => {
-                unused_event("read_page_layout", &evt)?;
+                ctx.unused_event("read_page_layout", &evt)?;
             }
         }
 
@@ -2058,12 +3660,12 @@ fn read_validations(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
         }
         match &evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:content-validation" => {
-                read_validation(xml, &mut valid, xml_tag)?;
+                read_validation(ctx, xml, &mut valid, xml_tag)?;
                 ctx.book.add_validation(valid);
                 valid = Validation::new();
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:content-validation" => {
-                read_validation(xml, &mut valid, xml_tag)?;
+                read_validation(ctx, xml, &mut valid, xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:content-validation" => {
                 ctx.book.add_validation(valid);
@@ -2089,7 +3691,7 @@ fn read_validations(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
             Event::Text(_) => (),
             Event::Eof => break,
             _ => {
-                unused_event("read_validations", &evt)?;
+                ctx.unused_event("read_validations", &evt)?;
             }
         }
     }
@@ -2116,7 +3718,7 @@ fn read_validation_help(
                 vh.set_title(Some(attr.decode_and_unescape_value(xml)?.to_string()));
             }
             attr => {
-                unused_attr("read_validations", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_validations", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2170,7 +3772,7 @@ fn read_validation_error(
                 ve.set_title(Some(attr.decode_and_unescape_value(xml)?.to_string()));
             }
             attr => {
-                unused_attr("read_validations", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_validations", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2194,6 +3796,7 @@ fn read_validation_error(
 }
 
 fn read_validation(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     valid: &mut Validation,
     super_tag: &BytesStart<'_>,
@@ -2219,7 +3822,7 @@ fn read_validation(
                 valid.set_display(attr.value.as_ref().try_into()?);
             }
             attr => {
-                unused_attr("read_validation", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_validation", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2250,7 +3853,11 @@ fn read_office_master_styles(
             Event::Text(_) => (),
             Event::Eof => break,
             _ => {
-                unused_event("read_master_styles", &evt)?;
+                if let Some(v) = read_unknown(ctx, xml, "read_master_styles", &evt)? {
+                    ctx.book
+                        .extra_styles
+                        .push((origin, StylesPart::MasterStyles, v));
+                }
             }
         }
 
@@ -2286,7 +3893,7 @@ fn read_master_page(
                 masterpage.set_next_masterpage(&MasterPageRef::from(v));
             }
             attr => {
-                unused_attr("read_master_page", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_master_page", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2327,7 +3934,7 @@ fn read_master_page(
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_master_page", &evt)?;
+                ctx.unused_event("read_master_page", &evt)?;
             }
         }
 
@@ -2355,7 +3962,7 @@ fn read_headerfooter(
                 hf.set_display(parse_bool(&attr.value)?);
             }
             attr => {
-                unused_attr("read_headerfooter", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_headerfooter", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2414,7 +4021,7 @@ fn read_headerfooter(
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_headerfooter", &evt)?;
+                ctx.unused_event("read_headerfooter", &evt)?;
             }
         }
 
@@ -2460,13 +4067,105 @@ fn read_office_styles(
             {
                 read_value_format(ctx, xml, origin, StyleUse::Named, xml_tag)?;
             }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"draw:gradient" => {
+                let mut gradient = Gradient::new_empty();
+                let name = copy_draw_attr(ctx, xml, gradient.attrmap_mut(), xml_tag)?;
+                gradient.set_name(name);
+                ctx.book.add_gradient(gradient);
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"draw:hatch" => {
+                let mut hatch = Hatch::new_empty();
+                let name = copy_draw_attr(ctx, xml, hatch.attrmap_mut(), xml_tag)?;
+                hatch.set_name(name);
+                ctx.book.add_hatch(hatch);
+            }
+            // images with office:binary-data end up in extra_styles.
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"draw:fill-image" => {
+                let mut image = FillImage::new_empty();
+                let name = copy_draw_attr(ctx, xml, image.attrmap_mut(), xml_tag)?;
+                image.set_name(name);
+                ctx.book.add_fill_image(image);
+            }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"loext:theme" => {
+                read_theme(ctx, xml, xml_tag)?;
+            }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:styles" => {
                 break;
             }
             Event::Text(_) => (),
             Event::Eof => break,
             _ => {
-                unused_event("read_styles_tag", &evt)?;
+                if let Some(v) = read_unknown(ctx, xml, "read_styles_tag", &evt)? {
+                    ctx.book.extra_styles.push((origin, StylesPart::Styles, v));
+                }
+            }
+        }
+
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(())
+}
+
+// reads the loext:theme tag
+fn read_theme(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<(), OdsError> {
+    let mut theme = Theme::default();
+    for attr in super_tag.attributes().with_checks(false) {
+        let attr = attr?;
+        if attr.key.as_ref() == b"loext:name" {
+            theme.set_name(attr.decode_and_unescape_value(xml)?);
+        }
+    }
+
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        if cfg!(feature = "dump_xml") {
+            println!(" read_theme {:?}", evt);
+        }
+        match &evt {
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"loext:theme-colors" => {
+                for attr in xml_tag.attributes().with_checks(false) {
+                    let attr = attr?;
+                    if attr.key.as_ref() == b"loext:name" {
+                        theme.set_colors_name(attr.decode_and_unescape_value(xml)?);
+                    }
+                }
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"loext:color" => {
+                let mut slot = None;
+                let mut color = None;
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"loext:name" => {
+                            let v = attr.decode_and_unescape_value(xml)?;
+                            // unknown slots are skipped.
+                            slot = ThemeColor::parse_attr(Some(v.as_ref())).ok().flatten();
+                        }
+                        attr if attr.key.as_ref() == b"loext:color" => {
+                            color = Some(parse_color(&attr.value)?);
+                        }
+                        attr => {
+                            ctx.unused_attr("read_theme", xml_tag.name().as_ref(), &attr)?;
+                        }
+                    }
+                }
+                if let (Some(slot), Some(color)) = (slot, color) {
+                    theme.set_color(slot, color);
+                }
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"loext:theme-colors" => {}
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"loext:theme" => {
+                break;
+            }
+            Event::Eof => break,
+            _ => {
+                ctx.unused_event("read_theme", &evt)?;
             }
         }
 
@@ -2474,6 +4173,8 @@ fn read_office_styles(
     }
     ctx.push_buf(buf);
 
+    ctx.book.theme = Some(theme);
+
     Ok(())
 }
 
@@ -2520,7 +4221,11 @@ fn read_office_automatic_styles(
             Event::Text(_) => (),
             Event::Eof => break,
             _ => {
-                unused_event("read_auto_styles", &evt)?;
+                if let Some(v) = read_unknown(ctx, xml, "read_auto_styles", &evt)? {
+                    ctx.book
+                        .extra_styles
+                        .push((origin, StylesPart::AutomaticStyles, v));
+                }
             }
         }
 
@@ -2576,12 +4281,7 @@ fn read_value_format(
             ctx.book.add_text_format(valuestyle);
         }
         _ => {
-            if cfg!(feature = "dump_unused") {
-                println!(
-                    " read_value_format unused {}",
-                    from_utf8(super_tag.name().as_ref())?
-                );
-            }
+            ctx.unused_event("read_value_format", &Event::Empty(super_tag.borrow()))?;
         }
     }
 
@@ -2600,7 +4300,7 @@ fn read_value_format_parts<T: ValueFormatTrait>(
     //
     valuestyle.set_origin(origin);
     valuestyle.set_styleuse(styleuse);
-    let name = copy_style_attr(xml, valuestyle.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, valuestyle.attrmap_mut(), super_tag)?;
     valuestyle.set_name(name.as_str());
 
     let mut buf = ctx.pop_buf();
@@ -2828,19 +4528,19 @@ fn read_value_format_parts<T: ValueFormatTrait>(
             Event::Start(xml_tag) | Event::Empty(xml_tag)
                 if xml_tag.name().as_ref() == b"style:map" =>
             {
-                valuestyle.push_stylemap(read_value_stylemap(xml, xml_tag)?);
+                valuestyle.push_stylemap(read_value_stylemap(ctx, xml, xml_tag)?);
             }
             Event::Start(xml_tag) | Event::Empty(xml_tag)
                 if xml_tag.name().as_ref() == b"style:text-properties" =>
             {
-                copy_attr2(xml, valuestyle.textstyle_mut(), xml_tag)?;
+                copy_attr2(ctx, xml, valuestyle.textstyle_mut(), xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                 break;
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_value_format_parts", &evt)?;
+                ctx.unused_event("read_value_format_parts", &evt)?;
             }
         }
 
@@ -2859,7 +4559,7 @@ fn read_part(
     part_type: FormatPartType,
 ) -> Result<FormatPart, OdsError> {
     let mut part = FormatPart::new(part_type);
-    copy_attr2(xml, part.attrmap_mut(), super_tag)?;
+    copy_attr2(ctx, xml, part.attrmap_mut(), super_tag)?;
 
     if !empty_tag {
         let mut buf = ctx.pop_buf();
@@ -2876,7 +4576,7 @@ fn read_part(
                     break;
                 }
                 _ => {
-                    unused_event("read_part", &evt)?;
+                    ctx.unused_event("read_part", &evt)?;
                 }
             }
         }
@@ -2895,7 +4595,7 @@ fn read_part_text(
     part_type: FormatPartType,
 ) -> Result<FormatPart, OdsError> {
     let mut part = FormatPart::new(part_type);
-    copy_attr2(xml, part.attrmap_mut(), super_tag)?;
+    copy_attr2(ctx, xml, part.attrmap_mut(), super_tag)?;
 
     if !empty_tag {
         let mut buf = ctx.pop_buf();
@@ -2915,7 +4615,7 @@ fn read_part_text(
                     break;
                 }
                 _ => {
-                    unused_event("read_part_text", &evt)?;
+                    ctx.unused_event("read_part_text", &evt)?;
                 }
             }
         }
@@ -2933,10 +4633,11 @@ fn read_part_number(
     part_type: FormatPartType,
 ) -> Result<FormatPart, OdsError> {
     let mut part = FormatPart::new(part_type);
-    copy_attr2(xml, part.attrmap_mut(), super_tag)?;
+    copy_attr2(ctx, xml, part.attrmap_mut(), super_tag)?;
 
     if !empty_tag {
         let mut buf = ctx.pop_buf();
+        let mut in_embedded_text = false;
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if cfg!(feature = "dump_xml") {
@@ -2946,14 +4647,15 @@ fn read_part_number(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"number:embedded-text" =>
                 {
+                    let mut position = 0;
                     for attr in xml_tag.attributes().with_checks(false) {
                         let attr = attr?;
                         match attr.key.as_ref() {
                             b"number:position" => {
-                                part.set_position(parse_i32(&attr.value)?);
+                                position = parse_i32(&attr.value)?;
                             }
                             _ => {
-                                unused_attr(
+                                ctx.unused_attr(
                                     "read_part_embedded_text",
                                     xml_tag.name().as_ref(),
                                     &attr,
@@ -2961,10 +4663,16 @@ fn read_part_number(
                             }
                         }
                     }
+                    part.push_embedded_text(position, "");
+                    in_embedded_text = matches!(evt, Event::Start(_));
                 }
-                Event::End(xml_tag) if xml_tag.name().as_ref() == b"number:embedded-text" => {}
-                Event::Text(xml_text) => {
-                    part.set_content(parse_string(xml_text.as_ref())?);
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"number:embedded-text" => {
+                    in_embedded_text = false;
+                }
+                Event::Text(xml_text) if in_embedded_text => {
+                    if let Some(embedded_text) = part.embedded_text_mut().last_mut() {
+                        embedded_text.set_text(xml_text.unescape()?);
+                    }
                 }
                 Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                     break;
@@ -2973,7 +4681,7 @@ fn read_part_number(
                     break;
                 }
                 _ => {
-                    unused_event("read_part_embedded_text", &evt)?;
+                    ctx.unused_event("read_part_embedded_text", &evt)?;
                 }
             }
         }
@@ -3015,6 +4723,14 @@ fn read_style_style(
                     }
                     b"text" => read_textstyle(ctx, xml, origin, style_use, super_tag, empty_tag)?,
                     b"ruby" => read_rubystyle(ctx, xml, origin, style_use, super_tag, empty_tag)?,
+                    _ if ctx.preserve_unknown => {
+                        let v = read_xml(ctx, xml, super_tag, empty_tag)?;
+                        let part = match style_use {
+                            StyleUse::Automatic => StylesPart::AutomaticStyles,
+                            _ => StylesPart::Styles,
+                        };
+                        ctx.book.extra_styles.push((origin, part, v));
+                    }
                     value => {
                         return Err(OdsError::Ods(format!(
                             "style:family unknown {} ",
@@ -3046,7 +4762,7 @@ fn read_tablestyle(
     let mut style = TableStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3061,9 +4777,11 @@ fn read_tablestyle(
             }
             match &evt {
                 Event::Start(xml_tag) | Event::Empty(xml_tag) => match xml_tag.name().as_ref() {
-                    b"style:table-properties" => copy_attr2(xml, style.tablestyle_mut(), xml_tag)?,
+                    b"style:table-properties" => {
+                        copy_attr2(ctx, xml, style.tablestyle_mut(), xml_tag)?
+                    }
                     _ => {
-                        unused_event("read_table_style", &evt)?;
+                        ctx.unused_event("read_table_style", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
@@ -3072,12 +4790,12 @@ fn read_tablestyle(
                         ctx.book.add_tablestyle(style);
                         break;
                     } else {
-                        unused_event("read_table_style", &evt)?;
+                        ctx.unused_event("read_table_style", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_table_style", &evt)?;
+                    ctx.unused_event("read_table_style", &evt)?;
                 }
             }
         }
@@ -3102,7 +4820,7 @@ fn read_rowstyle(
     let mut style = RowStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3118,10 +4836,10 @@ fn read_rowstyle(
             match &evt {
                 Event::Start(xml_tag) | Event::Empty(xml_tag) => match xml_tag.name().as_ref() {
                     b"style:table-row-properties" => {
-                        copy_attr2(xml, style.rowstyle_mut(), xml_tag)?
+                        copy_attr2(ctx, xml, style.rowstyle_mut(), xml_tag)?
                     }
                     _ => {
-                        unused_event("read_rowstyle", &evt)?;
+                        ctx.unused_event("read_rowstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
@@ -3130,12 +4848,12 @@ fn read_rowstyle(
                         ctx.book.add_rowstyle(style);
                         break;
                     } else {
-                        unused_event("read_rowstyle", &evt)?;
+                        ctx.unused_event("read_rowstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_rowstyle", &evt)?;
+                    ctx.unused_event("read_rowstyle", &evt)?;
                 }
             }
         }
@@ -3159,7 +4877,7 @@ fn read_colstyle(
     let mut style = ColStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3175,10 +4893,10 @@ fn read_colstyle(
             match &evt {
                 Event::Start(xml_tag) | Event::Empty(xml_tag) => match xml_tag.name().as_ref() {
                     b"style:table-column-properties" => {
-                        copy_attr2(xml, style.colstyle_mut(), xml_tag)?
+                        copy_attr2(ctx, xml, style.colstyle_mut(), xml_tag)?
                     }
                     _ => {
-                        unused_event("read_colstyle", &evt)?;
+                        ctx.unused_event("read_colstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
@@ -3187,12 +4905,12 @@ fn read_colstyle(
                         ctx.book.add_colstyle(style);
                         break;
                     } else {
-                        unused_event("read_colstyle", &evt)?;
+                        ctx.unused_event("read_colstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_colstyle", &evt)?;
+                    ctx.unused_event("read_colstyle", &evt)?;
                 }
             }
         }
@@ -3216,7 +4934,7 @@ fn read_cellstyle(
     let mut style = CellStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3233,17 +4951,17 @@ fn read_cellstyle(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:table-cell-properties" =>
                 {
-                    copy_attr2(xml, style.cellstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.cellstyle_mut(), xml_tag)?;
                 }
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:text-properties" =>
                 {
-                    copy_attr2(xml, style.textstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.textstyle_mut(), xml_tag)?;
                 }
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:paragraph-properties" =>
                 {
-                    copy_attr2(xml, style.paragraphstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.paragraphstyle_mut(), xml_tag)?;
                 }
                 Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:paragraph-properties" => {
                 }
@@ -3255,7 +4973,7 @@ fn read_cellstyle(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:map" =>
                 {
-                    style.push_stylemap(read_stylemap(xml, xml_tag)?);
+                    style.push_stylemap(read_stylemap(ctx, xml, xml_tag)?);
                 }
                 // todo: tab-stops
                 // b"style:tab-stops" => (),
@@ -3271,7 +4989,7 @@ fn read_cellstyle(
                 }
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_cellstyle", &evt)?;
+                    ctx.unused_event("read_cellstyle", &evt)?;
                 }
             }
         }
@@ -3295,7 +5013,7 @@ fn read_paragraphstyle(
     let mut style = ParagraphStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3312,17 +5030,17 @@ fn read_paragraphstyle(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:text-properties" =>
                 {
-                    copy_attr2(xml, style.textstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.textstyle_mut(), xml_tag)?;
                 }
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:paragraph-properties" =>
                 {
-                    copy_attr2(xml, style.paragraphstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.paragraphstyle_mut(), xml_tag)?;
                 }
                 Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:paragraph-properties" => {
                 }
                 // b"style:graphic-properties" => copy_attr(style.graphic_mut(), xml, xml_tag)?,
-                // b"style:map" => style.push_stylemap(read_stylemap(xml, xml_tag)?),
+                // b"style:map" => style.push_stylemap(read_stylemap(ctx, xml, xml_tag)?),
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:tab-stops" => {}
                 Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:tab-stops" => {}
@@ -3330,7 +5048,7 @@ fn read_paragraphstyle(
                     if xml_tag.name().as_ref() == b"style:tab-stop" =>
                 {
                     let mut ts = TabStop::new();
-                    copy_attr2(xml, ts.attrmap_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, ts.attrmap_mut(), xml_tag)?;
                     style.add_tabstop(ts);
                 }
 
@@ -3342,7 +5060,7 @@ fn read_paragraphstyle(
                 Event::Text(_) => (),
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_paragraphstyle", &evt)?;
+                    ctx.unused_event("read_paragraphstyle", &evt)?;
                 }
             }
         }
@@ -3366,7 +5084,7 @@ fn read_textstyle(
     let mut style = TextStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3383,7 +5101,7 @@ fn read_textstyle(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:text-properties" =>
                 {
-                    copy_attr2(xml, style.textstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.textstyle_mut(), xml_tag)?;
                 }
                 Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                     ctx.book.add_textstyle(style);
@@ -3392,7 +5110,7 @@ fn read_textstyle(
                 Event::Text(_) => (),
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_textstyle", &evt)?;
+                    ctx.unused_event("read_textstyle", &evt)?;
                 }
             }
         }
@@ -3416,7 +5134,7 @@ fn read_rubystyle(
     let mut style = RubyStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3433,7 +5151,7 @@ fn read_rubystyle(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:ruby-properties" =>
                 {
-                    copy_attr2(xml, style.rubystyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.rubystyle_mut(), xml_tag)?;
                 }
                 Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                     ctx.book.add_rubystyle(style);
@@ -3442,7 +5160,7 @@ fn read_rubystyle(
                 Event::Text(_) => (),
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_rubystyle", &evt)?;
+                    ctx.unused_event("read_rubystyle", &evt)?;
                 }
             }
         }
@@ -3466,7 +5184,7 @@ fn read_graphicstyle(
     let mut style = GraphicStyle::new_empty();
     style.set_origin(origin);
     style.set_styleuse(style_use);
-    let name = copy_style_attr(xml, style.attrmap_mut(), super_tag)?;
+    let name = copy_style_attr(ctx, xml, style.attrmap_mut(), super_tag)?;
     style.set_name(name);
 
     // In case of an empty xml-tag we are done here.
@@ -3483,17 +5201,17 @@ fn read_graphicstyle(
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:graphic-properties" =>
                 {
-                    copy_attr2(xml, style.graphicstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.graphicstyle_mut(), xml_tag)?;
                 }
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:paragraph-properties" =>
                 {
-                    copy_attr2(xml, style.paragraphstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.paragraphstyle_mut(), xml_tag)?;
                 }
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:text-properties" =>
                 {
-                    copy_attr2(xml, style.textstyle_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, style.textstyle_mut(), xml_tag)?;
                 }
                 Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                     ctx.book.add_graphicstyle(style);
@@ -3502,7 +5220,7 @@ fn read_graphicstyle(
                 Event::Text(_) => (),
                 Event::Eof => break,
                 _ => {
-                    unused_event("read_graphicstyle", &evt)?;
+                    ctx.unused_event("read_graphicstyle", &evt)?;
                 }
             }
         }
@@ -3514,6 +5232,7 @@ fn read_graphicstyle(
 
 // style:map inside a number style.
 fn read_value_stylemap(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     super_tag: &BytesStart<'_>,
 ) -> Result<ValueStyleMap, OdsError> {
@@ -3529,7 +5248,7 @@ fn read_value_stylemap(
                 sm.set_applied_style(attr.decode_and_unescape_value(xml)?);
             }
             attr => {
-                unused_attr("read_value_stylemap", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_value_stylemap", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -3538,6 +5257,7 @@ fn read_value_stylemap(
 }
 
 fn read_stylemap(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     super_tag: &BytesStart<'_>,
 ) -> Result<StyleMap, OdsError> {
@@ -3558,7 +5278,7 @@ fn read_stylemap(
                 sm.set_base_cell(Some(parse_cellref(v.as_ref())?));
             }
             attr => {
-                unused_attr("read_stylemap", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_stylemap", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -3568,6 +5288,7 @@ fn read_stylemap(
 
 /// Copies all attributes to the map, excluding "style:name" which is returned.
 fn copy_style_attr(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     attrmap: &mut AttrMap2,
     super_tag: &BytesStart<'_>,
@@ -3580,9 +5301,34 @@ fn copy_style_attr(
                 name = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
             attr => {
-                let k = from_utf8(attr.key.as_ref())?;
-                let v = attr.decode_and_unescape_value(xml)?.to_string();
-                attrmap.push_attr(k, v);
+                let k = ctx.attr_name(attr.key.as_ref())?;
+                let v = Box::<str>::from(attr.decode_and_unescape_value(xml)?.as_ref());
+                attrmap.push_attr_atom(k, v);
+            }
+        }
+    }
+
+    Ok(name.unwrap_or_default())
+}
+
+/// Copies all attributes to the map, except draw:name which is returned.
+fn copy_draw_attr(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    attrmap: &mut AttrMap2,
+    super_tag: &BytesStart<'_>,
+) -> Result<String, OdsError> {
+    let mut name = None;
+
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"draw:name" => {
+                name = Some(attr.decode_and_unescape_value(xml)?.to_string());
+            }
+            attr => {
+                let k = ctx.attr_name(attr.key.as_ref())?;
+                let v = Box::<str>::from(attr.decode_and_unescape_value(xml)?.as_ref());
+                attrmap.push_attr_atom(k, v);
             }
         }
     }
@@ -3592,6 +5338,7 @@ fn copy_style_attr(
 
 /// Copies all attributes to the given map.
 fn copy_attr2(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     attrmap: &mut AttrMap2,
     super_tag: &BytesStart<'_>,
@@ -3599,9 +5346,9 @@ fn copy_attr2(
     for attr in super_tag.attributes().with_checks(false) {
         let attr = attr?;
 
-        let k = from_utf8(attr.key.as_ref())?;
-        let v = attr.decode_and_unescape_value(xml)?.to_string();
-        attrmap.push_attr(k, v);
+        let k = ctx.attr_name(attr.key.as_ref())?;
+        let v = Box::<str>::from(attr.decode_and_unescape_value(xml)?.as_ref());
+        attrmap.push_attr_atom(k, v);
     }
 
     Ok(())
@@ -3617,7 +5364,7 @@ fn read_ods_styles(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(
         match &evt {
             Event::Decl(_) => {}
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document-styles" => {
-                let (_, xmlns) = read_namespaces_and_version(xml, xml_tag)?;
+                let (_, xmlns) = read_namespaces_and_version(ctx, xml, xml_tag)?;
                 ctx.book.xmlns.insert("styles.xml".to_string(), xmlns);
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document-styles" => {
@@ -3639,7 +5386,7 @@ fn read_ods_styles(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(
                 break;
             }
             _ => {
-                unused_event("read_styles", &evt)?;
+                ctx.unused_event("read_styles", &evt)?;
             }
         }
 
@@ -3752,7 +5499,7 @@ fn read_ods_metadata(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result
 
         match &evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document-meta" => {
-                let (_, xmlns) = read_namespaces_and_version(xml, xml_tag)?;
+                let (_, xmlns) = read_namespaces_and_version(ctx, xml, xml_tag)?;
                 ctx.book.xmlns.insert("meta.xml".to_string(), xmlns);
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document-meta" => {}
@@ -3766,7 +5513,7 @@ fn read_ods_metadata(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result
                 break;
             }
             _ => {
-                unused_event("read_ods_metadata", &evt)?;
+                ctx.unused_event("read_ods_metadata", &evt)?;
             }
         }
 
@@ -3910,29 +5657,38 @@ fn read_office_meta(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                     })?;
             }
 
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"meta:template" => {
-                ctx.book.metadata.template = read_metadata_template(xml, xml_tag)?;
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"meta:template" =>
+            {
+                ctx.book.metadata.template = read_metadata_template(ctx, xml, xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"meta:template" => {}
 
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"meta:auto-reload" => {
-                ctx.book.metadata.auto_reload = read_metadata_auto_reload(xml, xml_tag)?;
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"meta:auto-reload" =>
+            {
+                ctx.book.metadata.auto_reload = read_metadata_auto_reload(ctx, xml, xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"meta:auto-reload" => {}
 
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"meta:hyperlink-behaviour" => {
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"meta:hyperlink-behaviour" =>
+            {
                 ctx.book.metadata.hyperlink_behaviour =
-                    read_metadata_hyperlink_behaviour(xml, xml_tag)?;
+                    read_metadata_hyperlink_behaviour(ctx, xml, xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"meta:hyperlink-behaviour" => {}
 
-            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"meta:document-statistic" => {
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"meta:document-statistic" =>
+            {
                 ctx.book.metadata.document_statistics =
-                    read_metadata_document_statistics(xml, xml_tag)?;
+                    read_metadata_document_statistics(ctx, xml, xml_tag)?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"meta:document-statistic" => {}
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"meta:user-defined" => {
+                // keep duplicates as they are.
                 let userdefined = read_metadata_user_defined(ctx, xml, xml_tag)?;
                 ctx.book.metadata.user_defined.push(userdefined);
             }
@@ -3943,18 +5699,20 @@ fn read_office_meta(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 break;
             }
             _ => {
-                unused_event("read_metadata", &evt)?;
+                ctx.unused_event("read_metadata", &evt)?;
             }
         }
 
         buf.clear();
     }
     ctx.push_buf(buf);
+    ctx.book.metadata.index_user_defined();
 
     Ok(())
 }
 
 fn read_metadata_template(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     tag: &BytesStart<'_>,
 ) -> Result<MetaTemplate, OdsError> {
@@ -3984,7 +5742,7 @@ fn read_metadata_template(
                 )?);
             }
             attr => {
-                unused_attr("read_metadata_template", tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_metadata_template", tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -3993,6 +5751,7 @@ fn read_metadata_template(
 }
 
 fn read_metadata_auto_reload(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     tag: &BytesStart<'_>,
 ) -> Result<MetaAutoReload, OdsError> {
@@ -4024,7 +5783,7 @@ fn read_metadata_auto_reload(
                 )?);
             }
             attr => {
-                unused_attr("read_metadata_auto_reload", tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_metadata_auto_reload", tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -4033,6 +5792,7 @@ fn read_metadata_auto_reload(
 }
 
 fn read_metadata_hyperlink_behaviour(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     tag: &BytesStart<'_>,
 ) -> Result<MetaHyperlinkBehaviour, OdsError> {
@@ -4050,7 +5810,7 @@ fn read_metadata_hyperlink_behaviour(
                 )?);
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_metadata_hyperlink_behaviour",
                     tag.name().as_ref(),
                     &attr,
@@ -4063,6 +5823,7 @@ fn read_metadata_hyperlink_behaviour(
 }
 
 fn read_metadata_document_statistics(
+    ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
     tag: &BytesStart<'_>,
 ) -> Result<MetaDocumentStatistics, OdsError> {
@@ -4087,7 +5848,7 @@ fn read_metadata_document_statistics(
                     parse_u32(attr.decode_and_unescape_value(xml)?.as_bytes())?;
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_metadata_document_statistics",
                     tag.name().as_ref(),
                     &attr,
@@ -4121,7 +5882,7 @@ fn read_metadata_user_defined(
                 });
             }
             attr => {
-                unused_attr("read_meta_user_defined", tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_meta_user_defined", tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -4156,7 +5917,7 @@ fn read_metadata_user_defined(
                 break;
             }
             _ => {
-                unused_event("read_meta_user_defined", &evt)?;
+                ctx.unused_event("read_meta_user_defined", &evt)?;
             }
         }
 
@@ -4194,7 +5955,7 @@ fn read_metadata_value<T>(
                 break;
             }
             _ => {
-                unused_event("read_metadata_value", &evt)?;
+                ctx.unused_event("read_metadata_value", &evt)?;
             }
         }
 
@@ -4217,7 +5978,7 @@ fn read_ods_settings(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result
             Event::Decl(_) => {}
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document-settings" => {
-                let (_, xmlns) = read_namespaces_and_version(xml, xml_tag)?;
+                let (_, xmlns) = read_namespaces_and_version(ctx, xml, xml_tag)?;
                 ctx.book.xmlns.insert("settings.xml".to_string(), xmlns);
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document-settings" => {}
@@ -4230,7 +5991,7 @@ fn read_ods_settings(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result
                 break;
             }
             _ => {
-                unused_event("read_settings", &evt)?;
+                ctx.unused_event("read_settings", &evt)?;
             }
         }
 
@@ -4262,7 +6023,7 @@ fn read_office_settings(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Res
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_office_settings", &evt)?;
+                ctx.unused_event("read_office_settings", &evt)?;
             }
         }
 
@@ -4290,7 +6051,7 @@ fn read_config_item_set(
                 name = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
             attr => {
-                unused_attr("read_config_item_set", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_config_item_set", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -4332,7 +6093,7 @@ fn read_config_item_set(
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_config_item_set", &evt)?;
+                ctx.unused_event("read_config_item_set", &evt)?;
             }
         }
 
@@ -4358,7 +6119,7 @@ fn read_config_item_map_indexed(
                 name = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_config_item_map_indexed",
                     super_tag.name().as_ref(),
                     &attr,
@@ -4394,7 +6155,7 @@ fn read_config_item_map_indexed(
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_config_item_map_indexed", &evt)?;
+                ctx.unused_event("read_config_item_map_indexed", &evt)?;
             }
         }
 
@@ -4420,7 +6181,7 @@ fn read_config_item_map_named(
                 name = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_config_item_map_named",
                     super_tag.name().as_ref(),
                     &attr,
@@ -4462,7 +6223,7 @@ fn read_config_item_map_named(
             }
             Event::Eof => break,
             _ => {
-                unused_event("read_config_item_map_named", &evt)?;
+                ctx.unused_event("read_config_item_map_named", &evt)?;
             }
         }
 
@@ -4488,7 +6249,7 @@ fn read_config_item_map_entry(
                 name = Some(attr.decode_and_unescape_value(xml)?.to_string());
             }
             attr => {
-                unused_attr(
+                ctx.unused_attr(
                     "read_config_item_map_entry",
                     super_tag.name().as_ref(),
                     &attr,
@@ -4532,7 +6293,7 @@ fn read_config_item_map_entry(
 
             Event::Eof => break,
             _ => {
-                unused_event("read_config_item_map_entry", &evt)?;
+                ctx.unused_event("read_config_item_map_entry", &evt)?;
             }
         }
 
@@ -4590,7 +6351,7 @@ fn read_config_item(
                 };
             }
             attr => {
-                unused_attr("read_config_item", super_tag.name().as_ref(), &attr)?;
+                ctx.unused_attr("read_config_item", super_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -4654,7 +6415,7 @@ fn read_config_item(
                 break;
             }
             _ => {
-                unused_event("read_config_item", &evt)?;
+                ctx.unused_event("read_config_item", &evt)?;
             }
         }
 
@@ -4676,6 +6437,26 @@ fn read_config_item(
 }
 
 // Reads a part of the XML as XmlTag's.
+// Reads an unknown element with OdsOptions::preserve_unknown(),
+// otherwise it's only reported.
+fn read_unknown(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    func: &str,
+    evt: &Event<'_>,
+) -> Result<Option<XmlTag>, OdsError> {
+    match evt {
+        Event::Start(xml_tag) | Event::Empty(xml_tag) if ctx.preserve_unknown => {
+            let empty_tag = matches!(evt, Event::Empty(_));
+            Ok(Some(read_xml(ctx, xml, xml_tag, empty_tag)?))
+        }
+        _ => {
+            ctx.unused_event(func, evt)?;
+            Ok(None)
+        }
+    }
+}
+
 fn read_xml(
     ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
@@ -4685,7 +6466,7 @@ fn read_xml(
     let mut stack = ctx.pop_xml_buf();
 
     let mut tag = XmlTag::new(from_utf8(super_tag.name().as_ref())?);
-    copy_attr2(xml, tag.attrmap_mut(), super_tag)?;
+    copy_attr2(ctx, xml, tag.attrmap_mut(), super_tag)?;
     stack.push(tag);
 
     if !empty_tag {
@@ -4698,43 +6479,44 @@ fn read_xml(
             match &evt {
                 Event::Start(xml_tag) => {
                     let mut tag = XmlTag::new(from_utf8(xml_tag.name().as_ref())?);
-                    copy_attr2(xml, tag.attrmap_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, tag.attrmap_mut(), xml_tag)?;
                     stack.push(tag);
+                    ctx.check_xml_depth(stack.len())?;
                 }
                 Event::End(xml_tag) => {
                     if xml_tag.name() == super_tag.name() {
                         break;
-                    } else {
-                        let tag = stack.pop().expect("valid stack");
-                        if let Some(parent) = stack.last_mut() {
-                            parent.add_tag(tag);
-                        } else {
-                            unreachable!()
+                    } else if stack.len() > 1 {
+                        if let Some(tag) = stack.pop() {
+                            if let Some(parent) = stack.last_mut() {
+                                parent.add_tag(tag);
+                            }
                         }
+                    } else {
+                        return Err(OdsError::Xml(quick_xml::Error::UnexpectedToken(format!(
+                            "XML corrupted. Endtag {} occured without start tag",
+                            from_utf8(xml_tag.name().as_ref())?
+                        ))));
                     }
                 }
                 Event::Empty(xml_tag) => {
                     let mut emptytag = XmlTag::new(from_utf8(xml_tag.name().as_ref())?);
-                    copy_attr2(xml, emptytag.attrmap_mut(), xml_tag)?;
+                    copy_attr2(ctx, xml, emptytag.attrmap_mut(), xml_tag)?;
 
                     if let Some(parent) = stack.last_mut() {
                         parent.add_tag(emptytag);
-                    } else {
-                        unreachable!()
                     }
                 }
                 Event::Text(xml_text) => {
                     if let Some(parent) = stack.last_mut() {
                         parent.add_text(xml_text.unescape()?.as_ref());
-                    } else {
-                        unreachable!()
                     }
                 }
                 Event::Eof => {
                     break;
                 }
                 _ => {
-                    unused_event("read_xml", &evt)?;
+                    ctx.unused_event("read_xml", &evt)?;
                 }
             }
             buf.clear();
@@ -4743,9 +6525,22 @@ fn read_xml(
         ctx.push_buf(buf);
     }
 
-    let tag = stack.pop().unwrap();
+    // the toplevel tag is the first, any other unclosed tags are added to
+    // their parents.
+    while stack.len() > 1 {
+        if let Some(tag) = stack.pop() {
+            if let Some(parent) = stack.last_mut() {
+                parent.add_tag(tag);
+            }
+        }
+    }
+    let tag = stack.pop();
     ctx.push_xml_buf(stack);
-    Ok(tag)
+    tag.ok_or_else(|| {
+        OdsError::Xml(quick_xml::Error::UnexpectedToken(
+            "XML corrupted. No toplevel tag".to_string(),
+        ))
+    })
 }
 
 fn read_text_or_tag(
@@ -4760,16 +6555,18 @@ fn read_text_or_tag(
     // The toplevel element is passed in with the xml_tag.
     // It is only created if there are further xml tags in the
     // element. If there is only text this is not needed.
-    let create_toplevel =
-        |xml: &mut OdsXmlReader<'_>, t: Option<String>| -> Result<XmlTag, OdsError> {
-            // No parent tag on the stack. Create the parent.
-            let mut toplevel = XmlTag::new(from_utf8(super_tag.name().as_ref())?);
-            copy_attr2(xml, toplevel.attrmap_mut(), super_tag)?;
-            if let Some(t) = t {
-                toplevel.add_text(t);
-            }
-            Ok(toplevel)
-        };
+    let create_toplevel = |ctx: &mut OdsContext,
+                           xml: &mut OdsXmlReader<'_>,
+                           t: Option<String>|
+     -> Result<XmlTag, OdsError> {
+        // No parent tag on the stack. Create the parent.
+        let mut toplevel = XmlTag::new(from_utf8(super_tag.name().as_ref())?);
+        copy_attr2(ctx, xml, toplevel.attrmap_mut(), super_tag)?;
+        if let Some(t) = t {
+            toplevel.add_text(t);
+        }
+        Ok(toplevel)
+    };
 
     if !empty_tag {
         let mut buf = ctx.pop_buf();
@@ -4782,10 +6579,10 @@ fn read_text_or_tag(
                 Event::Start(xml_tag) => {
                     match cellcontent {
                         TextContent::Empty => {
-                            stack.push(create_toplevel(xml, None)?);
+                            stack.push(create_toplevel(ctx, xml, None)?);
                         }
                         TextContent::Text(old_txt) => {
-                            stack.push(create_toplevel(xml, Some(old_txt))?);
+                            stack.push(create_toplevel(ctx, xml, Some(old_txt))?);
                         }
                         TextContent::Xml(parent) => {
                             stack.push(parent);
@@ -4797,16 +6594,17 @@ fn read_text_or_tag(
 
                     // Set the new tag.
                     let mut new_tag = XmlTag::new(from_utf8(xml_tag.name().as_ref())?);
-                    copy_attr2(xml, new_tag.attrmap_mut(), xml_tag)?;
-                    cellcontent = TextContent::Xml(new_tag)
+                    copy_attr2(ctx, xml, new_tag.attrmap_mut(), xml_tag)?;
+                    cellcontent = TextContent::Xml(new_tag);
+                    ctx.check_xml_depth(stack.len() + 1)?;
                 }
                 Event::Empty(xml_tag) => {
                     match cellcontent {
                         TextContent::Empty => {
-                            stack.push(create_toplevel(xml, None)?);
+                            stack.push(create_toplevel(ctx, xml, None)?);
                         }
                         TextContent::Text(txt) => {
-                            stack.push(create_toplevel(xml, Some(txt))?);
+                            stack.push(create_toplevel(ctx, xml, Some(txt))?);
                         }
                         TextContent::Xml(parent) => {
                             stack.push(parent);
@@ -4818,7 +6616,7 @@ fn read_text_or_tag(
                     if let Some(mut parent) = stack.pop() {
                         // Create the tag and append it immediately to the parent.
                         let mut emptytag = XmlTag::new(from_utf8(xml_tag.name().as_ref())?);
-                        copy_attr2(xml, emptytag.attrmap_mut(), xml_tag)?;
+                        copy_attr2(ctx, xml, emptytag.attrmap_mut(), xml_tag)?;
                         parent.add_tag(emptytag);
 
                         cellcontent = TextContent::Xml(parent);
@@ -4891,7 +6689,7 @@ fn read_text_or_tag(
                 }
 
                 _ => {
-                    unused_event("read_text_or_tag", &evt)?;
+                    ctx.unused_event("read_text_or_tag", &evt)?;
                 }
             }
         }
@@ -4946,7 +6744,7 @@ where
                     break;
                 }
                 _ => {
-                    unused_event("read_text", &evt)?;
+                    ctx.unused_event("read_text", &evt)?;
                 }
             }
         }
@@ -4959,30 +6757,13 @@ where
     }
 }
 
-#[inline(always)]
-fn unused_attr(func: &str, tag: &[u8], attr: &Attribute<'_>) -> Result<(), OdsError> {
-    if cfg!(feature = "dump_unused") {
-        let tag = from_utf8(tag)?;
-        let key = from_utf8(attr.key.as_ref())?;
-        let value = from_utf8(attr.value.as_ref())?;
-        println!("unused attr: {} '{}' ({}:{})", func, tag, key, value);
-    }
-    Ok(())
+// Next row/column after a repeat.
+fn checked_pos(pos: u32, repeat: u32) -> Result<u32, OdsError> {
+    pos.checked_add(repeat)
+        .ok_or_else(|| OdsError::Ods(format!("Repeat {} at {} is out of range", repeat, pos)))
 }
 
-#[inline(always)]
-fn unused_event(func: &str, evt: &Event<'_>) -> Result<(), OdsError> {
-    if cfg!(feature = "dump_unused") {
-        match &evt {
-            Event::Text(xml_text) => {
-                if !xml_text.unescape()?.trim().is_empty() {
-                    println!("unused text: {} ({:?})", func, evt);
-                }
-            }
-            _ => {
-                println!("unused event: {} ({:?})", func, evt);
-            }
-        }
-    }
-    Ok(())
+// Position of a cell for warnings.
+fn cell_pos(sheet: &Sheet, row: u32, col: u32) -> String {
+    CellRef::remote(sheet.name(), row, col).to_string()
 }
@@ -1,6 +1,9 @@
+use crate::attrmap2::AttrMap2;
 use crate::cell_::CellData;
 use crate::config::{ConfigItem, ConfigItemType, ConfigValue};
-use crate::draw::{Annotation, DrawFrame, DrawFrameContent, DrawImage};
+use crate::draw::{
+    Annotation, DrawFrame, DrawFrameContent, DrawImage, DrawLine, DrawObject, DrawRect, DrawShape,
+};
 use crate::error::OdsError;
 use crate::format::{FormatPartType, ValueFormatTrait};
 use crate::io::format::{format_duration2, format_validation_condition};
@@ -8,26 +11,32 @@ use crate::io::xmlwriter::XmlWriter;
 use crate::io::NamespaceMap;
 use crate::manifest::Manifest;
 use crate::metadata::MetaValue;
-use crate::refs::{format_cellranges, CellRange};
-use crate::sheet::Visibility;
-use crate::sheet_::{dedup_colheader, CellDataIter};
+use crate::refs::{format_cellranges, CellRange, CellRef};
+use crate::sheet::{Scenario, TableSource, TableSourceMode, Visibility};
+use crate::sheet_::{dedup_colheader, CellDataIter, EDITABLE_RANGE_PREFIX, MAX_COLS, MAX_ROWS};
+use crate::sparkline::new_guid;
+use crate::style::units::{CellProtect, LengthUnit};
 use crate::style::{
-    CellStyle, ColStyle, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage, MasterPageRef,
-    PageStyle, PageStyleRef, ParagraphStyle, RowStyle, RubyStyle, StyleOrigin, StyleUse,
-    TableStyle, TextStyle,
+    color_string, CellStyle, ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, HeaderFooter,
+    MasterPage, PageStyle, ParagraphStyle, RowStyle, RubyStyle, StyleOrigin, StyleUse, TableStyle,
+    TextStyle,
 };
 use crate::validation::ValidationDisplay;
-use crate::workbook::{EventListener, Script};
+use crate::workbook::{CalculationSettings, EventListener, Script};
+use crate::workbook_::{StylesPart, CELL_TAG_PREFIX, CELL_TAG_VALUE_PREFIX};
 use crate::xmltree::{XmlContent, XmlTag};
 use crate::HashMap;
-use crate::{Length, Sheet, Value, ValueType, WorkBook};
-use std::borrow::Cow;
-use std::cmp::max;
+use crate::{CellStyleRef, Length, Sheet, Value, ValueType, WorkBook};
+use std::borrow::{Borrow, Cow};
+use std::cmp::{max, min};
 use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
 use std::io::{BufWriter, Cursor, Seek, Write};
+use std::iter::Peekable;
 use std::path::Path;
 use std::{io, mem};
+#[cfg(feature = "async")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use zip::write::FileOptions;
 use zip::{CompressionMethod, ZipWriter};
 
@@ -37,6 +46,30 @@ mod tests;
 type OdsXmlWriter<'a> = XmlWriter<&'a mut dyn Write>;
 
 const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DATETIME_TZ_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f%:z";
+
+// Extra tags of office:spreadsheet before the tables. The label-ranges
+// come after the content-validations, the rest before the
+// calculation-settings.
+const BODY_PRELUDE: [&str; 7] = [
+    "table:label-ranges",
+    "table:tracked-changes",
+    "text:alphabetical-index-auto-mark-file",
+    "text:dde-connection-decls",
+    "text:sequence-decls",
+    "text:user-field-decls",
+    "text:variable-decls",
+];
+
+// Extra tags of office:spreadsheet after the tables.
+const BODY_EPILOGUE: [&str; 6] = [
+    "table:consolidation",
+    "table:data-pilot-tables",
+    "table:database-ranges",
+    "table:dde-links",
+    "table:named-expressions",
+    "calcext:conditional-formats",
+];
 
 #[allow(dead_code)]
 trait SeekWrite: Seek + Write {}
@@ -44,10 +77,37 @@ trait SeekWrite: Seek + Write {}
 impl<T> SeekWrite for T where T: Seek + Write {}
 
 /// Write options for ods-files.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct OdsWriteOptions {
     method: CompressionMethod,
     level: Option<i64>,
+    dedup_styles: bool,
+    normalize_units: Option<LengthUnit>,
+    strict: bool,
+    settings: bool,
+    line_break: bool,
+    indent: bool,
+    version: Option<String>,
+    generator: Option<String>,
+    update_metadata: bool,
+}
+
+impl Default for OdsWriteOptions {
+    fn default() -> Self {
+        Self {
+            method: Default::default(),
+            level: None,
+            dedup_styles: false,
+            normalize_units: None,
+            strict: false,
+            settings: true,
+            line_break: false,
+            indent: false,
+            version: None,
+            generator: None,
+            update_metadata: true,
+        }
+    }
 }
 
 impl OdsWriteOptions {
@@ -63,6 +123,90 @@ impl OdsWriteOptions {
         self
     }
 
+    /// Merge identical automatic styles before writing.
+    ///
+    /// See WorkBook::dedup_styles().
+    pub fn dedup_styles(mut self, dedup: bool) -> Self {
+        self.dedup_styles = dedup;
+        self
+    }
+
+    /// Converts all lengths in the style attributes to one unit before
+    /// writing. Gives cleaner documents when styles were built up from
+    /// different sources.
+    ///
+    /// See WorkBook::normalize_units().
+    pub fn normalize_units(mut self, unit: LengthUnit) -> Self {
+        self.normalize_units = Some(unit);
+        self
+    }
+
+    /// Checks that every style, value format, master page, validation
+    /// and font referenced in the workbook actually exists before writing,
+    /// and that the sheet names are unique.
+    ///
+    /// Returns an error listing all dangling references.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Write settings.xml. This is the default.
+    ///
+    /// Without settings.xml the application uses its defaults for the
+    /// view settings. Cursor position, zoom, split-mode etc. are lost.
+    pub fn settings(mut self, settings: bool) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Pretty print the xml. Writes a line break after each element.
+    pub fn line_break(mut self, line_break: bool) -> Self {
+        self.line_break = line_break;
+        self
+    }
+
+    /// Pretty print the xml with indentation and the attributes sorted
+    /// by name. Gives a diff-friendly output, e.g. for FODS files kept
+    /// in version control.
+    ///
+    /// Paragraphs and other elements with text content are kept on one line.
+    pub fn indent(mut self, indent: bool) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets the ODF version of the output, e.g. "1.2".
+    /// Overwrites the version of the WorkBook.
+    pub fn version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Sets the generator string in the metadata.
+    /// Defaults to "spreadsheet-ods" plus the crate version.
+    pub fn generator<S: Into<String>>(mut self, generator: S) -> Self {
+        self.generator = Some(generator.into());
+        self
+    }
+
+    /// Update the metadata when writing. This is the default.
+    ///
+    /// Sets the generator and recalculates the document statistics.
+    /// Switch this off to write the metadata as it is, e.g. for
+    /// reproducible output. An explicit generator() is still used.
+    pub fn update_metadata(mut self, update: bool) -> Self {
+        self.update_metadata = update;
+        self
+    }
+
+    /// Write a flat ODS (FODS) to the given writer.
+    ///
+    /// The zip related options are not used.
+    pub fn write_fods<T: Write>(self, book: &mut WorkBook, mut write: T) -> Result<(), OdsError> {
+        write_fods_impl(self, &mut write, book)
+    }
+
     /// Write the ods to the given writer.
     pub fn write_ods<T: Write + Seek>(
         self,
@@ -75,6 +219,33 @@ impl OdsWriteOptions {
 
         Ok(())
     }
+
+    /// Writes the ODS file to an async writer.
+    ///
+    /// The file is created in memory with spawn_blocking(). The blocking
+    /// task writes a copy of the workbook, which replaces the workbook
+    /// when it's done. If the task panics the workbook is unchanged.
+    #[cfg(feature = "async")]
+    pub async fn write_ods_async<T: AsyncWrite + Unpin>(
+        self,
+        book: &mut WorkBook,
+        mut write: T,
+    ) -> Result<(), OdsError> {
+        let mut tmp = book.clone();
+        let (tmp, buf) = tokio::task::spawn_blocking(move || {
+            let mut buf = Cursor::new(Vec::new());
+            let result = self.write_ods(&mut tmp, &mut buf);
+            (tmp, result.map(|_| buf.into_inner()))
+        })
+        .await
+        .map_err(|e| OdsError::Ods(e.to_string()))?;
+        *book = tmp;
+
+        write.write_all(&buf?).await?;
+        write.flush().await?;
+
+        Ok(())
+    }
 }
 
 /// Writes the ODS file into a supplied buffer.
@@ -108,6 +279,18 @@ pub fn write_ods_to<T: Write + Seek>(book: &mut WorkBook, mut write: T) -> Resul
     Ok(())
 }
 
+/// Writes the ODS file to an async writer.
+#[cfg(feature = "async")]
+pub async fn write_ods_async<T: AsyncWrite + Unpin>(
+    book: &mut WorkBook,
+    write: T,
+) -> Result<(), OdsError> {
+    OdsWriteOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .write_ods_async(book, write)
+        .await
+}
+
 /// Writes the ODS file.
 pub fn write_ods<P: AsRef<Path>>(book: &mut WorkBook, ods_path: P) -> Result<(), OdsError> {
     let mut write = BufWriter::new(File::create(ods_path)?);
@@ -125,7 +308,7 @@ pub fn write_ods<P: AsRef<Path>>(book: &mut WorkBook, ods_path: P) -> Result<(),
 pub fn write_fods_buf(book: &mut WorkBook, mut buf: Vec<u8>) -> Result<Vec<u8>, OdsError> {
     let write: &mut dyn Write = &mut buf;
 
-    write_fods_impl(write, book)?;
+    write_fods_impl(OdsWriteOptions::default(), write, book)?;
 
     Ok(buf)
 }
@@ -134,7 +317,7 @@ pub fn write_fods_buf(book: &mut WorkBook, mut buf: Vec<u8>) -> Result<Vec<u8>,
 pub fn write_fods_to<T: Write + Seek>(book: &mut WorkBook, mut write: T) -> Result<(), OdsError> {
     let write: &mut dyn Write = &mut write;
 
-    write_fods_impl(write, book)?;
+    write_fods_impl(OdsWriteOptions::default(), write, book)?;
 
     Ok(())
 }
@@ -144,21 +327,33 @@ pub fn write_fods<P: AsRef<Path>>(book: &mut WorkBook, fods_path: P) -> Result<(
     let mut write = BufWriter::new(File::create(fods_path)?);
     let write: &mut dyn Write = &mut write;
 
-    write_fods_impl(write, book)?;
+    write_fods_impl(OdsWriteOptions::default(), write, book)?;
 
     Ok(())
 }
 
 /// Writes the ODS file.
 ///
-fn write_fods_impl(writer: &mut dyn Write, book: &mut WorkBook) -> Result<(), OdsError> {
+fn write_fods_impl(
+    cfg: OdsWriteOptions,
+    writer: &mut dyn Write,
+    book: &mut WorkBook,
+) -> Result<(), OdsError> {
+    if let Some(version) = &cfg.version {
+        book.set_version(version.clone());
+    }
+
     sanity_checks(book)?;
-    calculations(book)?;
+    calculations(book, &cfg)?;
+    if cfg.strict {
+        check_references(book)?;
+    }
 
     convert(book)?;
+    let copies = calc_write_copies(book);
 
-    let mut xml_out = XmlWriter::new(writer).line_break(true);
-    write_fods_content(book, &mut xml_out)?;
+    let mut xml_out = XmlWriter::new(writer).line_break(true).indent(cfg.indent);
+    write_fods_content(book, &copies, &mut xml_out)?;
 
     Ok(())
 }
@@ -210,11 +405,18 @@ fn convert(book: &mut WorkBook) -> Result<(), OdsError> {
     for v in book.formats_timeduration.values_mut() {
         v.set_origin(StyleOrigin::Content);
     }
+    for (origin, _, _) in book.extra_styles.iter_mut() {
+        *origin = StyleOrigin::Content;
+    }
 
     Ok(())
 }
 
-fn write_fods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_fods_content(
+    book: &mut WorkBook,
+    copies: &WriteCopies,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     let xmlns = book
         .xmlns
         .entry("meta.xml".into())
@@ -329,9 +531,9 @@ fn write_fods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Re
     write_office_scripts(book, xml_out)?;
     write_office_font_face_decls(book, StyleOrigin::Content, xml_out)?;
     write_office_styles(book, StyleOrigin::Content, xml_out)?;
-    write_office_automatic_styles(book, StyleOrigin::Content, xml_out)?;
-    write_office_master_styles(book, xml_out)?;
-    write_office_body(book, xml_out)?;
+    write_office_automatic_styles(book, StyleOrigin::Content, copies, xml_out)?;
+    write_office_master_styles(book, copies, xml_out)?;
+    write_office_body(book, copies, xml_out)?;
 
     xml_out.end_elem("office:document")?;
 
@@ -347,10 +549,18 @@ fn write_ods_impl<W: Write + Seek>(
     mut zip_writer: ZipWriter<W>,
     book: &mut WorkBook,
 ) -> Result<(), OdsError> {
+    if let Some(version) = &cfg.version {
+        book.set_version(version.clone());
+    }
+
     sanity_checks(book)?;
-    calculations(book)?;
+    calculations(book, &cfg)?;
+    if cfg.strict {
+        check_references(book)?;
+    }
 
-    create_manifest(book)?;
+    create_manifest(book, cfg.settings)?;
+    let copies = calc_write_copies(book);
 
     zip_writer.start_file(
         "mimetype",
@@ -365,7 +575,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_manifest(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_manifest(book, &mut ods_xml_writer(&mut zip_writer, &cfg))?;
 
     zip_writer.start_file(
         "meta.xml",
@@ -373,15 +583,17 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_metadata(book, &mut XmlWriter::new(&mut zip_writer))?;
-
-    zip_writer.start_file(
-        "settings.xml",
-        FileOptions::<()>::default()
-            .compression_method(cfg.method)
-            .compression_level(cfg.level),
-    )?;
-    write_ods_settings(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_metadata(book, &mut ods_xml_writer(&mut zip_writer, &cfg))?;
+
+    if cfg.settings {
+        zip_writer.start_file(
+            "settings.xml",
+            FileOptions::<()>::default()
+                .compression_method(cfg.method)
+                .compression_level(cfg.level),
+        )?;
+        write_ods_settings(book, &mut ods_xml_writer(&mut zip_writer, &cfg))?;
+    }
 
     zip_writer.start_file(
         "styles.xml",
@@ -389,7 +601,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_styles(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_styles(book, &copies, &mut ods_xml_writer(&mut zip_writer, &cfg))?;
 
     zip_writer.start_file(
         "content.xml",
@@ -397,7 +609,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_content(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_content(book, &copies, &mut ods_xml_writer(&mut zip_writer, &cfg))?;
 
     write_ods_extra(&cfg, &mut zip_writer, book)?;
 
@@ -406,6 +618,13 @@ fn write_ods_impl<W: Write + Seek>(
     Ok(())
 }
 
+/// Xml writer for one part of the zip.
+fn ods_xml_writer<'a>(writer: &'a mut dyn Write, cfg: &OdsWriteOptions) -> OdsXmlWriter<'a> {
+    XmlWriter::new(writer)
+        .line_break(cfg.line_break)
+        .indent(cfg.indent)
+}
+
 /// Sanity checks.
 fn sanity_checks(book: &mut WorkBook) -> Result<(), OdsError> {
     if book.sheets.is_empty() {
@@ -414,18 +633,305 @@ fn sanity_checks(book: &mut WorkBook) -> Result<(), OdsError> {
     Ok(())
 }
 
+/// Checks for references to non-existent styles, formats etc.
+fn check_references(book: &WorkBook) -> Result<(), OdsError> {
+    let mut dangling = Vec::new();
+
+    let has_format = |name: &str| {
+        book.formats_boolean.contains_key(name)
+            || book.formats_number.contains_key(name)
+            || book.formats_percentage.contains_key(name)
+            || book.formats_currency.contains_key(name)
+            || book.formats_text.contains_key(name)
+            || book.formats_datetime.contains_key(name)
+            || book.formats_timeduration.contains_key(name)
+    };
+    let check_fonts = |owner: &str, attr: &AttrMap2, dangling: &mut Vec<String>| {
+        for font_attr in [
+            "style:font-name",
+            "style:font-name-asian",
+            "style:font-name-complex",
+        ] {
+            if let Some(font) = attr.attr(font_attr) {
+                if !book.fonts.contains_key(font) {
+                    dangling.push(format!("{} references unknown font {}", owner, font));
+                }
+            }
+        }
+    };
+
+    let mut sheet_names = HashSet::new();
+    for sheet in book.iter_sheets() {
+        if !sheet.name.is_empty() && !sheet_names.insert(sheet.name.as_str()) {
+            dangling.push(format!("duplicate table name {}", sheet.name));
+        }
+        if let Some(style) = &sheet.style {
+            if !book.tablestyles.contains_key(style) {
+                dangling.push(format!(
+                    "table {} references unknown table-style {}",
+                    sheet.name,
+                    style.as_str()
+                ));
+            }
+        }
+        for (col, ch) in &sheet.col_header {
+            if let Some(style) = &ch.style {
+                if !book.colstyles.contains_key(style) {
+                    dangling.push(format!(
+                        "table {} column {} references unknown column-style {}",
+                        sheet.name,
+                        col,
+                        style.as_str()
+                    ));
+                }
+            }
+            if let Some(style) = &ch.cellstyle {
+                if !book.cellstyles.contains_key(style) {
+                    dangling.push(format!(
+                        "table {} column {} references unknown cell-style {}",
+                        sheet.name,
+                        col,
+                        style.as_str()
+                    ));
+                }
+            }
+        }
+        for (row, rh) in &sheet.row_header {
+            if let Some(style) = &rh.style {
+                if !book.rowstyles.contains_key(style) {
+                    dangling.push(format!(
+                        "table {} row {} references unknown row-style {}",
+                        sheet.name,
+                        row,
+                        style.as_str()
+                    ));
+                }
+            }
+            if let Some(style) = &rh.cellstyle {
+                if !book.cellstyles.contains_key(style) {
+                    dangling.push(format!(
+                        "table {} row {} references unknown cell-style {}",
+                        sheet.name,
+                        row,
+                        style.as_str()
+                    ));
+                }
+            }
+        }
+        for ((row, col), cell) in &sheet.data {
+            if let Some(style) = &cell.style {
+                if !book.cellstyles.contains_key(style) {
+                    dangling.push(format!(
+                        "table {} cell ({},{}) references unknown cell-style {}",
+                        sheet.name,
+                        row,
+                        col,
+                        style.as_str()
+                    ));
+                }
+            }
+            if let Some(validation) = cell.extra.as_ref().and_then(|v| v.validation_name.as_ref()) {
+                if !book.validations.contains_key(validation) {
+                    dangling.push(format!(
+                        "table {} cell ({},{}) references unknown validation {}",
+                        sheet.name,
+                        row,
+                        col,
+                        validation.as_str()
+                    ));
+                }
+            }
+        }
+    }
+
+    for (value_type, style) in &book.def_styles {
+        if !book.cellstyles.contains_key(style) {
+            dangling.push(format!(
+                "default style for {:?} references unknown cell-style {}",
+                value_type,
+                style.as_str()
+            ));
+        }
+    }
+
+    for style in book.cellstyles.values() {
+        if let Some(format) = style.value_format() {
+            if !has_format(format) {
+                dangling.push(format!(
+                    "cell-style {} references unknown value-format {}",
+                    style.name(),
+                    format
+                ));
+            }
+        }
+        check_fonts(
+            &format!("cell-style {}", style.name()),
+            style.textstyle(),
+            &mut dangling,
+        );
+    }
+    for style in book.paragraphstyles.values() {
+        check_fonts(
+            &format!("paragraph-style {}", style.name()),
+            style.textstyle(),
+            &mut dangling,
+        );
+    }
+    for style in book.textstyles.values() {
+        check_fonts(
+            &format!("text-style {}", style.name()),
+            style.textstyle(),
+            &mut dangling,
+        );
+    }
+    for style in book.tablestyles.values() {
+        if let Some(masterpage) = style.attrmap().attr("style:master-page-name") {
+            if !book.masterpages.contains_key(masterpage) {
+                dangling.push(format!(
+                    "table-style {} references unknown master-page {}",
+                    style.name(),
+                    masterpage
+                ));
+            }
+        }
+    }
+    for masterpage in book.masterpages.values() {
+        if let Some(pagestyle) = masterpage.pagestyle() {
+            if !book.pagestyles.contains_key(pagestyle) {
+                dangling.push(format!(
+                    "master-page {} references unknown page-style {}",
+                    masterpage.name(),
+                    pagestyle.as_str()
+                ));
+            }
+        }
+    }
+
+    if dangling.is_empty() {
+        Ok(())
+    } else {
+        dangling.sort();
+        Err(OdsError::Ods(format!(
+            "Dangling references: {}",
+            dangling.join("; ")
+        )))
+    }
+}
+
 /// Before write calculations.
-fn calculations(book: &mut WorkBook) -> Result<(), OdsError> {
-    calc_metadata(book)?;
+fn calculations(book: &mut WorkBook, cfg: &OdsWriteOptions) -> Result<(), OdsError> {
+    calc_metadata(book, cfg)?;
     calc_config(book)?;
 
     calc_row_header_styles(book)?;
     calc_col_header_styles(book)?;
+    if let Some(unit) = cfg.normalize_units {
+        book.normalize_units(unit);
+    }
+    if cfg.dedup_styles {
+        book.dedup_styles();
+    }
+    calc_groups(book)?;
     calc_col_headers(book)?;
 
     Ok(())
 }
 
+// Write-time copies of the sheets by sheet index, and the styles
+// that are only needed for writing. The workbook itself stays unchanged.
+#[derive(Default)]
+struct WriteCopies {
+    sheets: HashMap<usize, Sheet>,
+    cellstyles: Vec<CellStyle>,
+    tablestyles: Vec<TableStyle>,
+    pagestyles: Vec<PageStyle>,
+    masterpages: Vec<MasterPage>,
+}
+
+/// Calculations that work on write-time copies.
+fn calc_write_copies(book: &WorkBook) -> WriteCopies {
+    let mut copies = WriteCopies::default();
+    calc_print_setup(book, &mut copies);
+    calc_editable_ranges(book, &mut copies);
+    copies
+}
+
+/// Unprotects the cells of the editable ranges. This is done on a copy
+/// of the sheet, the cells keep their styles.
+fn calc_editable_ranges(book: &WorkBook, copies: &mut WriteCopies) {
+    let mut editable = HashMap::new();
+
+    for (idx, sheet) in book.sheets.iter().enumerate() {
+        if sheet.editable_ranges.is_empty() {
+            continue;
+        }
+        let copy = copies
+            .sheets
+            .entry(idx)
+            .or_insert_with(|| Sheet::clone(sheet));
+        for (_, range) in &sheet.editable_ranges {
+            copy.map_range_cellstyles(
+                range,
+                min(range.to_row(), MAX_ROWS - 1),
+                min(range.to_col(), MAX_COLS - 1),
+                |existing, value_type| {
+                    let existing = existing.or_else(|| book.def_style(value_type));
+                    editable_style(book, &mut copies.cellstyles, &mut editable, existing)
+                },
+            );
+        }
+    }
+}
+
+// Copy of the cell style that is not protected. None if the cell style
+// is not protected anyway.
+fn editable_style(
+    book: &WorkBook,
+    styles: &mut Vec<CellStyle>,
+    editable: &mut HashMap<Option<CellStyleRef>, Option<CellStyleRef>>,
+    existing: Option<&CellStyleRef>,
+) -> Option<CellStyleRef> {
+    if let Some(style) = editable.get(&existing.cloned()) {
+        return style.clone();
+    }
+
+    let copy = match existing {
+        Some(existing) => match book.cellstyle(existing) {
+            Some(style) if style.cellstyle().attr("style:cell-protect") != Some("none") => {
+                Some((format!("{}_editable", existing.as_str()), style.clone()))
+            }
+            _ => None,
+        },
+        None => Some(("editable".to_string(), CellStyle::new_empty())),
+    };
+    let style_ref = copy.map(|(base_name, mut style)| {
+        // a name not used by the workbook.
+        let mut name = base_name.clone();
+        let mut n = 2;
+        while book.cellstyle(&name).is_some() || styles.iter().any(|v| v.name() == name) {
+            name = format!("{}{}", base_name, n);
+            n += 1;
+        }
+        style.set_name(&name);
+        style.set_origin(StyleOrigin::Content);
+        style.set_styleuse(StyleUse::Automatic);
+        style.set_cell_protect(CellProtect::None);
+        styles.push(style);
+        CellStyleRef::from(name)
+    });
+
+    editable.insert(existing.cloned(), style_ref.clone());
+    style_ref
+}
+
+/// Check the nesting of row/column groups.
+fn calc_groups(book: &mut WorkBook) -> Result<(), OdsError> {
+    for sheet in book.sheets.iter_mut() {
+        sheet.validate_groups()?;
+    }
+    Ok(())
+}
+
 /// Compacting and normalizing column-headers.
 fn calc_col_headers(book: &mut WorkBook) -> Result<(), OdsError> {
     for i in 0..book.num_sheets() {
@@ -482,6 +988,13 @@ fn calc_col_headers(book: &mut WorkBook) -> Result<(), OdsError> {
 
 /// Sync row/column styles with row/col header values.
 fn calc_col_header_styles(book: &mut WorkBook) -> Result<(), OdsError> {
+    // Width written to each col-style so far.
+    let mut style_width: HashMap<ColStyleRef, Length> = HashMap::new();
+    // Copies of a col-style that are used for a different width.
+    let mut style_copies: Vec<(ColStyleRef, Length, ColStyleRef)> = Vec::new();
+    // New col-styles for columns with a width but no style.
+    let mut width_styles: Vec<(Length, ColStyleRef)> = Vec::new();
+
     for i in 0..book.num_sheets() {
         let mut sheet = book.detach_sheet(i);
 
@@ -489,8 +1002,38 @@ fn calc_col_header_styles(book: &mut WorkBook) -> Result<(), OdsError> {
         for ch in sheet.col_header.values_mut() {
             // Any non default values?
             if ch.width != Length::Default && ch.style.is_none() {
-                let colstyle = book.add_colstyle(ColStyle::new_empty());
-                ch.style = Some(colstyle);
+                if let Some((_, colstyle)) = width_styles.iter().find(|(w, _)| *w == ch.width) {
+                    ch.style = Some(colstyle.clone());
+                } else {
+                    let colstyle = book.add_colstyle(ColStyle::new_empty());
+                    width_styles.push((ch.width, colstyle.clone()));
+                    ch.style = Some(colstyle);
+                }
+            }
+
+            // Columns can share a style and still have different widths.
+            // These get their own copy of the style.
+            if let Some(style_name) = ch.style.clone() {
+                match style_width.get(&style_name) {
+                    None => {
+                        style_width.insert(style_name, ch.width);
+                    }
+                    Some(width) if *width == ch.width => {}
+                    Some(_) => {
+                        if let Some((_, _, copy)) = style_copies
+                            .iter()
+                            .find(|(s, w, _)| *s == style_name && *w == ch.width)
+                        {
+                            ch.style = Some(copy.clone());
+                        } else if let Some(style) = book.colstyle(&style_name) {
+                            let mut copy = style.clone();
+                            copy.set_name("");
+                            let copy = book.add_colstyle(copy);
+                            style_copies.push((style_name, ch.width, copy.clone()));
+                            ch.style = Some(copy);
+                        }
+                    }
+                }
             }
 
             // Write back to the style.
@@ -500,6 +1043,10 @@ fn calc_col_header_styles(book: &mut WorkBook) -> Result<(), OdsError> {
                         style.set_use_optimal_col_width(true);
                         style.set_col_width(Length::Default);
                     } else {
+                        // The style can be a copy of one with optimal width.
+                        style
+                            .colstyle_mut()
+                            .clear_attr("style:use-optimal-column-width");
                         style.set_col_width(ch.width);
                     }
                 }
@@ -541,16 +1088,111 @@ fn calc_row_header_styles(book: &mut WorkBook) -> Result<(), OdsError> {
     Ok(())
 }
 
+/// Each sheet with a print setup gets its own master page and page layout.
+/// They are named after the sheet and exist only in the written file.
+fn calc_print_setup(book: &WorkBook, copies: &mut WriteCopies) {
+    for (idx, sheet) in book.sheets.iter().enumerate() {
+        if let Some(setup) = &sheet.print_setup {
+            let sheet = copies
+                .sheets
+                .entry(idx)
+                .or_insert_with(|| Sheet::clone(sheet));
+            if let Some(range) = &setup.range {
+                sheet.print_ranges = Some(vec![range.clone()]);
+            }
+
+            let tablestyle = sheet.style.as_ref().and_then(|v| book.tablestyle(v));
+            let masterpage_name = tablestyle
+                .and_then(|v| v.attrmap().attr("style:master-page-name"))
+                .unwrap_or("Default");
+
+            let mut masterpage = book
+                .masterpage(masterpage_name)
+                .cloned()
+                .unwrap_or_else(MasterPage::new_empty);
+            let mut pagestyle = masterpage
+                .pagestyle()
+                .and_then(|v| book.pagestyle(v))
+                .cloned()
+                .unwrap_or_else(PageStyle::new_empty);
+
+            // a name not used by the workbook.
+            let base_name: String = sheet
+                .name()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            let base_name = format!("PrintSetup_{}", base_name);
+            let mut print_name = base_name.clone();
+            let mut n = 2;
+            while book.pagestyle(&print_name).is_some()
+                || book.masterpage(&print_name).is_some()
+                || book.tablestyle(&print_name).is_some()
+                || copies.masterpages.iter().any(|v| *v.name() == print_name)
+            {
+                print_name = format!("{}_{}", base_name, n);
+                n += 1;
+            }
+
+            pagestyle.set_name(print_name.clone());
+            if setup.fit_width.is_some() || setup.fit_height.is_some() {
+                pagestyle.style_mut().clear_attr("style:scale-to");
+                pagestyle.style_mut().clear_attr("style:scale-to-pages");
+                pagestyle.set_scale_to_x(setup.fit_width.unwrap_or(0));
+                pagestyle.set_scale_to_y(setup.fit_height.unwrap_or(0));
+            }
+            if let Some(page_order) = setup.page_order {
+                pagestyle.set_print_page_order(page_order);
+            }
+            if setup.grid.is_some() || setup.headers.is_some() {
+                let print = pagestyle
+                    .style()
+                    .attr("style:print")
+                    .unwrap_or("objects charts drawings zero-values");
+                let grid = setup.grid.unwrap_or(print.contains("grid"));
+                let headers = setup.headers.unwrap_or(print.contains("headers"));
+
+                let mut print: Vec<&str> = print
+                    .split_whitespace()
+                    .filter(|v| *v != "grid" && *v != "headers")
+                    .collect();
+                if headers {
+                    print.push("headers");
+                }
+                if grid {
+                    print.push("grid");
+                }
+                let print = print.join(" ");
+                pagestyle.style_mut().set_attr("style:print", print);
+            }
+            masterpage.set_name(print_name.clone());
+            masterpage.set_pagestyle(&pagestyle.style_ref());
+
+            let mut tablestyle = tablestyle.cloned().unwrap_or_else(TableStyle::new_empty);
+            tablestyle.set_name(print_name);
+            tablestyle.set_origin(StyleOrigin::Content);
+            tablestyle.set_styleuse(StyleUse::Automatic);
+            tablestyle.set_master_page(&masterpage.masterpage_ref());
+            sheet.style = Some(tablestyle.style_ref());
+
+            copies.pagestyles.push(pagestyle);
+            copies.masterpages.push(masterpage);
+            copies.tablestyles.push(tablestyle);
+        }
+    }
+}
+
 /// Calculate metadata values.
-fn calc_metadata(book: &mut WorkBook) -> Result<(), OdsError> {
+fn calc_metadata(book: &mut WorkBook, cfg: &OdsWriteOptions) -> Result<(), OdsError> {
     // Manifest
-    book.metadata.generator = format!("spreadsheet-ods {}", env!("CARGO_PKG_VERSION"));
-    book.metadata.document_statistics.table_count = book.sheets.len() as u32;
-    let mut cell_count = 0;
-    for sheet in book.iter_sheets() {
-        cell_count += sheet.data.len() as u32;
+    if let Some(generator) = &cfg.generator {
+        book.metadata.generator = generator.clone();
+    } else if cfg.update_metadata {
+        book.metadata.generator = format!("spreadsheet-ods {}", env!("CARGO_PKG_VERSION"));
+    }
+    if cfg.update_metadata {
+        book.recalculate_metadata();
     }
-    book.metadata.document_statistics.cell_count = cell_count;
 
     Ok(())
 }
@@ -616,14 +1258,41 @@ fn calc_config(book: &mut WorkBook) -> Result<(), OdsError> {
         book.attach_sheet(sheet);
     }
 
+    let has_named_views = config.get(&["ooo:view-settings", "NamedViews"]).is_some();
+    if !book.named_views.is_empty() || has_named_views {
+        let mut named_views = ConfigItem::new_map();
+        for view in &book.named_views {
+            let mut bc = ConfigItem::new_entry();
+            bc.insert("ActiveTable", view.active_table.clone());
+            bc.insert("CursorPositionX", view.cursor_x);
+            bc.insert("CursorPositionY", view.cursor_y);
+            bc.insert("PositionLeft", view.first_col);
+            bc.insert("PositionBottom", view.first_row);
+            bc.insert("ZoomValue", view.zoom_value);
+            bc.insert(
+                "HiddenColumns",
+                view.hidden_cols
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+            named_views.insert(view.name.as_str(), bc);
+        }
+        let bc = config.create_path(&[("ooo:view-settings", ConfigItemType::Set)]);
+        bc.insert("NamedViews", named_views);
+    }
+
     book.config.attach(config);
 
     Ok(())
 }
 
 // Create the standard manifest entries.
-fn create_manifest(book: &mut WorkBook) -> Result<(), OdsError> {
-    if !book.manifest.contains_key("/") {
+fn create_manifest(book: &mut WorkBook, settings: bool) -> Result<(), OdsError> {
+    if let Some(manifest) = book.manifest.get_mut("/") {
+        manifest.version = Some(book.version.clone());
+    } else {
         book.add_manifest(Manifest {
             full_path: "/".to_string(),
             version: Some(book.version().clone()),
@@ -643,7 +1312,9 @@ fn create_manifest(book: &mut WorkBook) -> Result<(), OdsError> {
     if !book.manifest.contains_key("content.xml") {
         book.add_manifest(Manifest::new("content.xml", "text/xml"));
     }
-    if !book.manifest.contains_key("settings.xml") {
+    if !settings {
+        book.manifest.remove("settings.xml");
+    } else if !book.manifest.contains_key("settings.xml") {
         book.add_manifest(Manifest::new("settings.xml", "text/xml"));
     }
 
@@ -707,7 +1378,7 @@ fn write_ods_manifest(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result
     )?;
     xml_out.attr_esc("manifest:version", &book.version())?;
 
-    for manifest in book.manifest.values() {
+    for manifest in sorted_values(&book.manifest) {
         xml_out.empty("manifest:file-entry")?;
         xml_out.attr_esc("manifest:full-path", &manifest.full_path)?;
         if let Some(version) = &manifest.version {
@@ -723,6 +1394,13 @@ fn write_ods_manifest(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result
     Ok(())
 }
 
+// Values of the map sorted by key. Keeps the output stable between runs.
+fn sorted_values<K: Borrow<str>, V>(map: &HashMap<K, V>) -> Vec<&V> {
+    let mut entries: Vec<(&K, &V)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.borrow().cmp(b.0.borrow()));
+    entries.into_iter().map(|(_, v)| v).collect()
+}
+
 fn write_xmlns(xmlns: &NamespaceMap, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
     for (k, v) in xmlns.entries() {
         match k {
@@ -834,7 +1512,7 @@ fn write_office_meta(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<
     }
 
     if !book.metadata.auto_reload.is_empty() {
-        xml_out.empty("meta:auto_reload")?;
+        xml_out.empty("meta:auto-reload")?;
         if let Some(v) = book.metadata.auto_reload.delay {
             xml_out.attr("meta:delay", &format_duration2(v))?;
         }
@@ -1138,7 +1816,11 @@ fn write_config_item(
     Ok(())
 }
 
-fn write_ods_styles(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_ods_styles(
+    book: &mut WorkBook,
+    copies: &WriteCopies,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     let xmlns = book
         .xmlns
         .entry("styles.xml".into())
@@ -1235,8 +1917,8 @@ fn write_ods_styles(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Resu
 
     write_office_font_face_decls(book, StyleOrigin::Styles, xml_out)?;
     write_office_styles(book, StyleOrigin::Styles, xml_out)?;
-    write_office_automatic_styles(book, StyleOrigin::Styles, xml_out)?;
-    write_office_master_styles(book, xml_out)?;
+    write_office_automatic_styles(book, StyleOrigin::Styles, copies, xml_out)?;
+    write_office_master_styles(book, copies, xml_out)?;
 
     xml_out.end_elem("office:document-styles")?;
 
@@ -1245,7 +1927,11 @@ fn write_ods_styles(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Resu
     Ok(())
 }
 
-fn write_ods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_ods_content(
+    book: &mut WorkBook,
+    copies: &WriteCopies,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     let xmlns = book
         .xmlns
         .entry("content.xml".into())
@@ -1349,9 +2035,9 @@ fn write_ods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Res
 
     write_office_scripts(book, xml_out)?;
     write_office_font_face_decls(book, StyleOrigin::Content, xml_out)?;
-    write_office_automatic_styles(book, StyleOrigin::Content, xml_out)?;
+    write_office_automatic_styles(book, StyleOrigin::Content, copies, xml_out)?;
 
-    write_office_body(book, xml_out)?;
+    write_office_body(book, copies, xml_out)?;
 
     xml_out.end_elem("office:document-content")?;
 
@@ -1360,40 +2046,56 @@ fn write_ods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Res
     Ok(())
 }
 
-fn write_office_body(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_office_body(
+    book: &WorkBook,
+    copies: &WriteCopies,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     xml_out.elem("office:body")?;
     xml_out.elem("office:spreadsheet")?;
 
     // extra tags. pass through only
     for tag in &book.extra {
-        if tag.name() == "table:calculation-settings"
-            || tag.name() == "table:label-ranges"
-            || tag.name() == "table:tracked-changes"
-            || tag.name() == "text:alphabetical-index-auto-mark-file"
-            || tag.name() == "text:dde-connection-decls"
-            || tag.name() == "text:sequence-decls"
-            || tag.name() == "text:user-field-decls"
-            || tag.name() == "text:variable-decls"
-        {
+        if BODY_PRELUDE.contains(&tag.name()) && tag.name() != "table:label-ranges" {
             write_xmltag(tag, xml_out)?;
         }
     }
 
+    write_calculation_settings(&book.calculation_settings, xml_out)?;
     write_content_validations(book, xml_out)?;
 
-    for sheet in &book.sheets {
+    for tag in &book.extra {
+        if tag.name() == "table:label-ranges" {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+
+    for (idx, sheet) in book.sheets.iter().enumerate() {
+        let sheet = copies.sheets.get(&idx).unwrap_or(sheet);
         write_sheet(book, sheet, xml_out)?;
     }
 
+    // the cell tags go with the named expressions.
+    let named_expressions = book
+        .extra
+        .iter()
+        .find(|v| v.name() == "table:named-expressions");
+    if named_expressions.is_none() && !book.cell_tags.is_empty() {
+        write_named_expressions(None, |xml_out| write_cell_tags(book, xml_out), xml_out)?;
+    }
+
     // extra tags. pass through only
     for tag in &book.extra {
-        if tag.name() == "table:consolidation"
-            || tag.name() == "table:data-pilot-tables"
-            || tag.name() == "table:database-ranges"
-            || tag.name() == "table:dde-links"
-            || tag.name() == "table:named-expressions"
-            || tag.name() == "calcext:conditional-formats"
-        {
+        if tag.name() == "table:named-expressions" {
+            write_named_expressions(Some(tag), |xml_out| write_cell_tags(book, xml_out), xml_out)?;
+        } else if BODY_EPILOGUE.contains(&tag.name()) {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+
+    // unknown tags, see OdsOptions::preserve_unknown().
+    for tag in &book.extra {
+        if !BODY_PRELUDE.contains(&tag.name()) && !BODY_EPILOGUE.contains(&tag.name()) {
             write_xmltag(tag, xml_out)?;
         }
     }
@@ -1403,11 +2105,155 @@ fn write_office_body(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<
     Ok(())
 }
 
+// Named expressions with the named ranges of add.
+fn write_named_expressions<'a, F>(
+    named_expressions: Option<&XmlTag>,
+    add: F,
+    xml_out: &mut OdsXmlWriter<'a>,
+) -> Result<(), OdsError>
+where
+    F: FnOnce(&mut OdsXmlWriter<'a>) -> Result<(), OdsError>,
+{
+    xml_out.elem("table:named-expressions")?;
+    if let Some(named_expressions) = named_expressions {
+        for c in named_expressions.content() {
+            match c {
+                XmlContent::Text(t) => {
+                    xml_out.text_esc(t)?;
+                }
+                XmlContent::Tag(t) => {
+                    write_xmltag(t, xml_out)?;
+                }
+            }
+        }
+    }
+    add(xml_out)?;
+    xml_out.end_elem("table:named-expressions")?;
+    Ok(())
+}
+
+// Writes a named range and returns the base cell.
+fn write_named_range(
+    name: &str,
+    range: &CellRange,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<CellRef, OdsError> {
+    let range = range.clone().absolute();
+    let mut base = CellRef::local(range.row(), range.col()).absolute();
+    if let Some(table) = range.table() {
+        base.set_table(table.as_str());
+    }
+
+    xml_out.empty("table:named-range")?;
+    xml_out.attr_esc("table:name", name)?;
+    xml_out.attr_esc("table:cell-range-address", &range)?;
+    xml_out.attr_esc("table:base-cell-address", &base)?;
+
+    Ok(base)
+}
+
+fn write_cell_tags(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    for tag in &book.cell_tags {
+        let name = format!("{}{}", CELL_TAG_PREFIX, tag.name);
+        let base = write_named_range(&name, &tag.range, xml_out)?;
+
+        if !tag.value.is_empty() {
+            xml_out.empty("table:named-expression")?;
+            xml_out.attr_esc(
+                "table:name",
+                &format!("{}{}", CELL_TAG_VALUE_PREFIX, tag.name),
+            )?;
+            xml_out.attr_esc(
+                "table:expression",
+                &format!("of:=\"{}\"", tag.value.replace('"', "\"\"")),
+            )?;
+            xml_out.attr_esc("table:base-cell-address", &base)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_editable_ranges(sheet: &Sheet, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    for (name, range) in &sheet.editable_ranges {
+        let mut range = range.clone();
+        if range.table().is_none() {
+            range.set_table(sheet.name().as_str());
+        }
+        range.set_to_row(min(range.to_row(), MAX_ROWS - 1));
+        range.set_to_col(min(range.to_col(), MAX_COLS - 1));
+        let name = format!("{}{}", EDITABLE_RANGE_PREFIX, name);
+        write_named_range(&name, &range, xml_out)?;
+    }
+    Ok(())
+}
+
+fn write_calculation_settings(
+    settings: &CalculationSettings,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    let default = CalculationSettings::default();
+    if *settings == default {
+        return Ok(());
+    }
+
+    let null_date = settings.null_date != default.null_date;
+    let iteration = settings.iteration
+        || settings.iteration_steps != default.iteration_steps
+        || settings.iteration_minimum_difference != default.iteration_minimum_difference;
+
+    xml_out.elem_if(null_date || iteration, "table:calculation-settings")?;
+    if !settings.case_sensitive {
+        xml_out.attr_str("table:case-sensitive", "false")?;
+    }
+    if settings.precision_as_shown {
+        xml_out.attr_str("table:precision-as-shown", "true")?;
+    }
+    if !settings.search_criteria_must_apply_to_whole_cell {
+        xml_out.attr_str("table:search-criteria-must-apply-to-whole-cell", "false")?;
+    }
+    if !settings.automatic_find_labels {
+        xml_out.attr_str("table:automatic-find-labels", "false")?;
+    }
+    if !settings.use_regular_expressions {
+        xml_out.attr_str("table:use-regular-expressions", "false")?;
+    }
+    if settings.use_wildcards {
+        xml_out.attr_str("table:use-wildcards", "true")?;
+    }
+    if settings.null_year != default.null_year {
+        xml_out.attr("table:null-year", &settings.null_year)?;
+    }
+    if null_date {
+        xml_out.empty("table:null-date")?;
+        xml_out.attr_str("table:value-type", "date")?;
+        xml_out.attr("table:date-value", &settings.null_date.format("%Y-%m-%d"))?;
+    }
+    if iteration {
+        xml_out.empty("table:iteration")?;
+        if settings.iteration {
+            xml_out.attr_str("table:status", "enable")?;
+        }
+        if settings.iteration_steps != default.iteration_steps {
+            xml_out.attr("table:steps", &settings.iteration_steps)?;
+        }
+        if settings.iteration_minimum_difference != default.iteration_minimum_difference {
+            xml_out.attr(
+                "table:minimum-difference",
+                &settings.iteration_minimum_difference,
+            )?;
+        }
+    }
+    xml_out.end_elem_if(null_date || iteration, "table:calculation-settings")?;
+
+    Ok(())
+}
+
 fn write_office_scripts(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
-    xml_out.elem_if(!book.scripts.is_empty(), "office:scripts")?;
+    let has_content = !book.scripts.is_empty() || !book.event_listener.is_empty();
+    xml_out.elem_if(has_content, "office:scripts")?;
     write_scripts(&book.scripts, xml_out)?;
     write_event_listeners(&book.event_listener, xml_out)?;
-    xml_out.end_elem_if(!book.scripts.is_empty(), "office:scripts")?;
+    xml_out.end_elem_if(has_content, "office:scripts")?;
     Ok(())
 }
 
@@ -1418,7 +2264,7 @@ fn write_content_validations(
     if !book.validations.is_empty() {
         xml_out.elem("table:content-validations")?;
 
-        for valid in book.validations.values() {
+        for valid in sorted_values(&book.validations) {
             xml_out.elem("table:content-validation")?;
             xml_out.attr_esc("table:name", valid.name())?;
             xml_out.attr_esc("table:condition", &format_validation_condition(valid))?;
@@ -1567,10 +2413,19 @@ fn split_hidden(ranges: &[CellRange], row: u32, col: u32, repeat: u32, out: &mut
 
 /// Removes any outlived Ranges from the vector.
 fn remove_outlived(ranges: &mut Vec<CellRange>, row: u32, col: u32) {
-    *ranges = ranges
-        .drain(..)
-        .filter(|s| !s.out_looped(row, col))
-        .collect();
+    ranges.retain(|s| !s.out_looped(row, col));
+}
+
+// Unknown tags that come before the row.
+fn write_extra_rows<'a>(
+    extra_rows: &mut Peekable<impl Iterator<Item = &'a (u32, XmlTag)>>,
+    row: u32,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    while let Some((_, tag)) = extra_rows.next_if(|(extra_row, _)| *extra_row <= row) {
+        write_xmltag(tag, xml_out)?;
+    }
+    Ok(())
 }
 
 fn write_sheet(
@@ -1592,24 +2447,48 @@ fn write_sheet(
     if !sheet.display() {
         xml_out.attr_str("table:display", "false")?;
     }
+    if sheet.protected() {
+        xml_out.attr_str("table:protected", "true")?;
+    }
 
+    // prelude and unknown tags.
     for tag in &sheet.extra {
-        if tag.name() == "table:title"
-            || tag.name() == "table:desc"
-            || tag.name() == "table:table-source"
-            || tag.name() == "office:dde-source"
-            || tag.name() == "table:scenario"
-            || tag.name() == "office:forms"
-            || tag.name() == "table:shapes"
+        if tag.name() == "table:title" || tag.name() == "table:desc" {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+    if let Some(table_source) = sheet.table_source() {
+        write_table_source(table_source, xml_out)?;
+    }
+    if let Some(scenario) = sheet.scenario() {
+        write_scenario(scenario, xml_out)?;
+    }
+    for tag in &sheet.extra {
+        if tag.name() != "table:title"
+            && tag.name() != "table:desc"
+            && tag.name() != "table:named-expressions"
+            && tag.name() != "calcext:conditional-formats"
         {
             write_xmltag(tag, xml_out)?;
         }
     }
+    if !sheet.shapes.is_empty() {
+        xml_out.elem("table:shapes")?;
+        for shape in &sheet.shapes {
+            write_draw_shape(shape, xml_out)?;
+        }
+        xml_out.end_elem("table:shapes")?;
+    }
 
     let max_cell = sheet.used_grid_size();
 
     write_table_columns(sheet, max_cell, xml_out)?;
 
+    // unknown tags between the rows.
+    let mut extra_rows: Vec<&(u32, XmlTag)> = sheet.extra_rows.iter().collect();
+    extra_rows.sort_by_key(|(row, _)| *row);
+    let mut extra_rows = extra_rows.into_iter().peekable();
+
     // list of current spans
     let mut spans = Vec::<CellRange>::new();
     let mut split = Vec::<SplitCols>::new();
@@ -1622,6 +2501,16 @@ fn write_sheet(
     let mut row_group_count = 0;
     let mut row_header = false;
 
+    // End of the last hidden rows.
+    let hidden_end = sheet
+        .row_header
+        .iter()
+        .rev()
+        .find(|(_, v)| v.visible != Visibility::Visible)
+        .map(|(r, v)| r + v.span - 1 + v.repeat);
+
+    let def_number = book.def_style(ValueType::Number);
+
     let mut it = CellDataIter::new(sheet.data.range(..));
     while let Some(((cur_row, cur_col), cell)) = it.next() {
         // Row repeat count.
@@ -1699,6 +2588,7 @@ fn write_sheet(
             let synth_row = cur_row - synth_row_repeat;
 
             if synth_row_repeat > 0 {
+                write_extra_rows(&mut extra_rows, synth_row, xml_out)?;
                 write_empty_rows_before(
                     sheet,
                     synth_row,
@@ -1714,6 +2604,7 @@ fn write_sheet(
         // Start a new row if there is a delta or we are at the start.
         // Fills in any blank cells before the current cell.
         if backward_delta_row > 0 || first_cell {
+            write_extra_rows(&mut extra_rows, cur_row, xml_out)?;
             write_start_current_row(
                 sheet,
                 cur_row,
@@ -1741,7 +2632,7 @@ fn write_sheet(
 
         // And now to something completely different ...
         for s in &split {
-            write_cell(book, cell, s.hidden, s.repeat(), xml_out)?;
+            write_cell(book, def_number, cell, s.hidden, s.repeat(), xml_out)?;
         }
 
         // There may be some blank cells until the next one.
@@ -1788,7 +2679,31 @@ fn write_sheet(
         // The last cell we will write? We can close the last row here,
         // where we have all the data.
         if is_last_cell {
-            write_end_last_row(&mut row_group_count, &mut row_header, xml_out)?;
+            // Hidden rows after the last cell must be written too.
+            let trailing_row = cur_row + cur_row_repeat;
+            if let Some(hidden_end) = hidden_end.filter(|v| *v > trailing_row) {
+                write_end_prev_row(
+                    sheet,
+                    cur_row,
+                    cur_row_repeat,
+                    &mut row_group_count,
+                    &mut row_header,
+                    xml_out,
+                )?;
+                write_extra_rows(&mut extra_rows, trailing_row, xml_out)?;
+                write_empty_rows_before(
+                    sheet,
+                    trailing_row,
+                    hidden_end - trailing_row,
+                    max_cell,
+                    &mut row_group_count,
+                    &mut row_header,
+                    xml_out,
+                )?;
+                write_end_row_groups(&mut row_group_count, &mut row_header, xml_out)?;
+            } else {
+                write_end_last_row(&mut row_group_count, &mut row_header, xml_out)?;
+            }
         }
 
         first_cell = false;
@@ -1796,18 +2711,130 @@ fn write_sheet(
         prev_row_repeat = cur_row_repeat;
         prev_col = cur_col;
     }
+    write_extra_rows(&mut extra_rows, u32::MAX, xml_out)?;
+
+    // the editable ranges go with the named expressions.
+    let named_expressions = sheet
+        .extra
+        .iter()
+        .find(|v| v.name() == "table:named-expressions");
+    if named_expressions.is_none() && !sheet.editable_ranges.is_empty() {
+        write_named_expressions(
+            None,
+            |xml_out| write_editable_ranges(sheet, xml_out),
+            xml_out,
+        )?;
+    }
+    for tag in &sheet.extra {
+        if tag.name() == "table:named-expressions" {
+            write_named_expressions(
+                Some(tag),
+                |xml_out| write_editable_ranges(sheet, xml_out),
+                xml_out,
+            )?;
+        } else if tag.name() == "calcext:conditional-formats" {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+    if !sheet.sparkline_groups.is_empty() {
+        write_sparkline_groups(sheet, xml_out)?;
+    }
 
     xml_out.end_elem("table:table")?;
 
-    for tag in &sheet.extra {
-        if tag.name() == "table:named-expressions" || tag.name() == "calcext:conditional-formats" {
-            write_xmltag(tag, xml_out)?;
+    Ok(())
+}
+
+fn write_sparkline_groups(sheet: &Sheet, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    xml_out.elem("calcext:sparkline-groups")?;
+    for group in &sheet.sparkline_groups {
+        xml_out.elem("calcext:sparkline-group")?;
+        if group.id().is_empty() {
+            xml_out.attr("calcext:id", &new_guid())?;
+        } else {
+            xml_out.attr_esc("calcext:id", group.id())?;
+        }
+        xml_out.attr("calcext:type", &group.sparkline_type())?;
+        for (k, v) in group.attrmap().iter() {
+            xml_out.attr_esc(k.as_ref(), v)?;
         }
+
+        xml_out.elem("calcext:sparklines")?;
+        for sparkline in group.sparklines() {
+            xml_out.empty("calcext:sparkline")?;
+            xml_out.attr_esc("calcext:cell-address", sparkline.cell())?;
+            xml_out.attr_esc("calcext:data-range", sparkline.data_range())?;
+        }
+        xml_out.end_elem("calcext:sparklines")?;
+
+        xml_out.end_elem("calcext:sparkline-group")?;
     }
+    xml_out.end_elem("calcext:sparkline-groups")?;
 
     Ok(())
 }
 
+fn write_scenario(scenario: &Scenario, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    xml_out.empty("table:scenario")?;
+    xml_out.attr_esc(
+        "table:scenario-ranges",
+        &format_cellranges(&scenario.ranges),
+    )?;
+    xml_out.attr_str(
+        "table:is-active",
+        if scenario.is_active { "true" } else { "false" },
+    )?;
+    if !scenario.display_border {
+        xml_out.attr_str("table:display-border", "false")?;
+    }
+    if let Some(color) = scenario.border_color {
+        xml_out.attr("table:border-color", &color_string(color))?;
+    }
+    if !scenario.copy_back {
+        xml_out.attr_str("table:copy-back", "false")?;
+    }
+    if !scenario.copy_styles {
+        xml_out.attr_str("table:copy-styles", "false")?;
+    }
+    if !scenario.copy_formulas {
+        xml_out.attr_str("table:copy-formulas", "false")?;
+    }
+    if let Some(comment) = &scenario.comment {
+        xml_out.attr_esc("table:comment", comment)?;
+    }
+    if scenario.protected {
+        xml_out.attr_str("table:protected", "true")?;
+    }
+
+    Ok(())
+}
+
+fn write_table_source(
+    table_source: &TableSource,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    xml_out.empty("table:table-source")?;
+    xml_out.attr_str("xlink:type", "simple")?;
+    xml_out.attr_str("xlink:actuate", "onRequest")?;
+    xml_out.attr_esc("xlink:href", &table_source.href)?;
+    if let Some(v) = &table_source.table_name {
+        xml_out.attr_esc("table:table-name", v)?;
+    }
+    if let Some(v) = &table_source.filter_name {
+        xml_out.attr_esc("table:filter-name", v)?;
+    }
+    if let Some(v) = &table_source.filter_options {
+        xml_out.attr_esc("table:filter-options", v)?;
+    }
+    if table_source.mode != TableSourceMode::CopyAll {
+        xml_out.attr("table:mode", &table_source.mode)?;
+    }
+    if let Some(v) = table_source.refresh_delay {
+        xml_out.attr("table:refresh-delay", &format_duration2(v))?;
+    }
+    Ok(())
+}
+
 fn write_empty_cells(
     hidden: bool,
     repeat: u32,
@@ -1937,6 +2964,14 @@ fn write_end_last_row(
     // row
     xml_out.end_elem("table:table-row")?;
 
+    write_end_row_groups(row_group_count, row_header, xml_out)
+}
+
+fn write_end_row_groups(
+    row_group_count: &mut u32,
+    row_header: &mut bool,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     // end of the print-header.
     // todo: might loose some empty rows?
     if *row_header {
@@ -2003,7 +3038,24 @@ fn write_empty_rows_before(
             write_empty_row(sheet, r, 1, max_cell, xml_out)?;
         }
     } else {
-        write_empty_row(sheet, last_row, last_row_repeat, max_cell, xml_out)?;
+        // Split at the row-headers, they may differ within the range.
+        let end_row = last_row + last_row_repeat;
+        let mut r = last_row;
+        while r < end_row {
+            let next_row = if let Some((_, _, last)) = sheet.covering_row_header(r) {
+                last + 1
+            } else {
+                sheet
+                    .row_header
+                    .range(r..)
+                    .next()
+                    .map(|(next, _)| *next)
+                    .unwrap_or(end_row)
+            };
+            let next_row = min(next_row, end_row);
+            write_empty_row(sheet, r, next_row - r, max_cell, xml_out)?;
+            r = next_row;
+        }
     }
 
     Ok(())
@@ -2018,7 +3070,7 @@ fn write_empty_row(
 ) -> Result<(), OdsError> {
     xml_out.elem("table:table-row")?;
     xml_out.attr("table:number-rows-repeated", &row_repeat)?;
-    if let Some(row_header) = sheet.valid_row_header(cur_row) {
+    if let Some((_, row_header, _)) = sheet.covering_row_header(cur_row) {
         if let Some(rowstyle) = row_header.style.as_ref() {
             xml_out.attr_esc("table:style-name", rowstyle.as_str())?;
         }
@@ -2052,6 +3104,15 @@ fn write_table_columns(
     if let Some(header_cols) = &sheet.header_cols {
         max_col = max(max_col, header_cols.to + 1);
     }
+    // hidden columns after the last cell.
+    if let Some((col, col_header)) = sheet
+        .col_header
+        .iter()
+        .rev()
+        .find(|(_, v)| v.visible != Visibility::Visible)
+    {
+        max_col = max(max_col, col + col_header.span);
+    }
 
     // table:table-column
     let mut c = 0;
@@ -2093,7 +3154,35 @@ fn write_table_columns(
 
             col_header.span
         } else {
-            1
+            // Gap up to the next col-header or column-group.
+            let mut next = sheet
+                .col_header
+                .range(c + 1..)
+                .next()
+                .map(|(col, _)| *col)
+                .unwrap_or(max_col);
+            next = min(next, max_col);
+            for grp in &sheet.group_cols {
+                if grp.from > c {
+                    next = min(next, grp.from);
+                }
+                if grp.to >= c {
+                    next = min(next, grp.to + 1);
+                }
+            }
+            if let Some(header_cols) = &sheet.header_cols {
+                if header_cols.from > c {
+                    next = min(next, header_cols.from);
+                }
+                if header_cols.to >= c {
+                    next = min(next, header_cols.to + 1);
+                }
+            }
+            let span = next - c;
+            if span > 1 {
+                xml_out.attr("table:number-columns-repeated", &span)?;
+            }
+            span
         };
 
         if let Some(header_cols) = &sheet.header_cols {
@@ -2103,7 +3192,7 @@ fn write_table_columns(
         }
 
         for col_group in &sheet.group_cols {
-            if c == col_group.to() {
+            if (c..c + span).contains(&col_group.to()) {
                 xml_out.end_elem("table:table-column-group")?;
             }
         }
@@ -2118,11 +3207,19 @@ fn write_table_columns(
 #[allow(clippy::single_char_add_str)]
 fn write_cell(
     book: &WorkBook,
+    def_number: Option<&CellStyleRef>,
     cell: &CellData,
     is_hidden: bool,
     repeat: u32,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
+    // Plain numbers are by far the most common content.
+    if let Value::Number(v) = cell.value {
+        if !is_hidden && cell.formula.is_none() && cell.extra.is_none() {
+            return write_number_cell(def_number, cell, v, repeat, xml_out);
+        }
+    }
+
     let tag = if is_hidden {
         "table:covered-table-cell"
     } else {
@@ -2202,6 +3299,14 @@ fn write_cell(
             xml_out.text(&value)?;
             xml_out.end_elem("text:p")?;
         }
+        Value::DateTimeTz(d) => {
+            xml_out.attr_str("office:value-type", "date")?;
+            let value = d.format(DATETIME_TZ_FORMAT);
+            xml_out.attr("office:date-value", &value)?;
+            xml_out.elem("text:p")?;
+            xml_out.text(&value)?;
+            xml_out.end_elem("text:p")?;
+        }
         Value::TimeDuration(d) => {
             xml_out.attr_str("office:value-type", "time")?;
             let value = format_duration2(*d);
@@ -2222,9 +3327,16 @@ fn write_cell(
             xml_out.attr_esc("office:currency", c)?;
             xml_out.attr("office:value", v)?;
             xml_out.elem("text:p")?;
-            xml_out.text_esc(c)?;
-            xml_out.text_str(" ")?;
-            xml_out.text(v)?;
+            let (symbol, symbol_first) = currency_display(book, cell, c);
+            if symbol_first {
+                xml_out.text_esc(symbol)?;
+                xml_out.text_str(" ")?;
+                xml_out.text(v)?;
+            } else {
+                xml_out.text(v)?;
+                xml_out.text_str(" ")?;
+                xml_out.text_esc(symbol)?;
+            }
             xml_out.end_elem("text:p")?;
         }
         Value::Number(v) => {
@@ -2258,6 +3370,108 @@ fn write_cell(
     Ok(())
 }
 
+/// Number cell without formula, validation, spans or annotations.
+/// The default style for numbers is looked up once per sheet.
+fn write_number_cell(
+    def_number: Option<&CellStyleRef>,
+    cell: &CellData,
+    v: f64,
+    repeat: u32,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    xml_out.elem("table:table-cell")?;
+    if repeat > 1 {
+        xml_out.attr("table:number-columns-repeated", &repeat)?;
+    }
+    if let Some(style) = &cell.style {
+        xml_out.attr_esc("table:style-name", style.as_str())?;
+    } else if let Some(style) = def_number {
+        xml_out.attr_esc("table:style-name", style.as_str())?;
+    }
+    xml_out.attr_str("office:value-type", "float")?;
+    xml_out.attr_elem_f64("office:value", "text:p", v)?;
+    xml_out.end_elem("table:table-cell")?;
+    Ok(())
+}
+
+// Currency symbol and position from the value format of the cell.
+// Falls back to the currency code in front.
+fn currency_display<'a>(book: &'a WorkBook, cell: &CellData, code: &'a str) -> (&'a str, bool) {
+    let format = cell
+        .style
+        .as_ref()
+        .or_else(|| book.def_style(ValueType::Currency))
+        .and_then(|v| book.cellstyles.get(v))
+        .and_then(|v| v.value_format())
+        .and_then(|v| book.currency_format(v));
+    match format {
+        Some(format) => match (format.currency_symbol(), format.currency_symbol_first()) {
+            (Some(symbol), Some(first)) => (symbol, first),
+            _ => (code, true),
+        },
+        None => (code, true),
+    }
+}
+
+fn write_draw_shape(shape: &DrawShape, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    match shape {
+        DrawShape::Frame(draw_frame) => write_draw_frame(draw_frame, xml_out),
+        DrawShape::Rect(draw_rect) => write_draw_rect(draw_rect, xml_out),
+        DrawShape::Line(draw_line) => write_draw_line(draw_line, xml_out),
+        DrawShape::Xml(tag) => write_xmltag(tag, xml_out),
+    }
+}
+
+fn write_draw_rect(draw_rect: &DrawRect, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    xml_out.elem("draw:rect")?;
+    for (k, v) in draw_rect.attrmap().iter() {
+        xml_out.attr_esc(k.as_ref(), v)?;
+    }
+
+    if let Some(title) = draw_rect.title() {
+        xml_out.elem("svg:title")?;
+        xml_out.text_esc(title)?;
+        xml_out.end_elem("svg:title")?;
+    }
+    if let Some(desc) = draw_rect.desc() {
+        xml_out.elem("svg:desc")?;
+        xml_out.text_esc(desc)?;
+        xml_out.end_elem("svg:desc")?;
+    }
+    for content in draw_rect.text() {
+        write_xmltag(content, xml_out)?;
+    }
+
+    xml_out.end_elem("draw:rect")?;
+
+    Ok(())
+}
+
+fn write_draw_line(draw_line: &DrawLine, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    xml_out.elem("draw:line")?;
+    for (k, v) in draw_line.attrmap().iter() {
+        xml_out.attr_esc(k.as_ref(), v)?;
+    }
+
+    if let Some(title) = draw_line.title() {
+        xml_out.elem("svg:title")?;
+        xml_out.text_esc(title)?;
+        xml_out.end_elem("svg:title")?;
+    }
+    if let Some(desc) = draw_line.desc() {
+        xml_out.elem("svg:desc")?;
+        xml_out.text_esc(desc)?;
+        xml_out.end_elem("svg:desc")?;
+    }
+    for content in draw_line.text() {
+        write_xmltag(content, xml_out)?;
+    }
+
+    xml_out.end_elem("draw:line")?;
+
+    Ok(())
+}
+
 fn write_draw_frame(
     draw_frame: &DrawFrame,
     xml_out: &mut OdsXmlWriter<'_>,
@@ -2272,6 +3486,9 @@ fn write_draw_frame(
             DrawFrameContent::Image(img) => {
                 write_draw_image(img, xml_out)?;
             }
+            DrawFrameContent::Object(obj) => {
+                write_draw_object(obj, xml_out)?;
+            }
         }
     }
 
@@ -2291,6 +3508,22 @@ fn write_draw_frame(
     Ok(())
 }
 
+fn write_draw_object(
+    draw_object: &DrawObject,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    xml_out.elem_if(draw_object.content().is_some(), "draw:object")?;
+    for (k, v) in draw_object.attrmap().iter() {
+        xml_out.attr_esc(k.as_ref(), v)?;
+    }
+    if let Some(content) = draw_object.content() {
+        write_xmltag(content, xml_out)?;
+    }
+    xml_out.end_elem_if(draw_object.content().is_some(), "draw:object")?;
+
+    Ok(())
+}
+
 fn write_draw_image(
     draw_image: &DrawImage,
     xml_out: &mut OdsXmlWriter<'_>,
@@ -2352,7 +3585,7 @@ fn write_scripts(scripts: &Vec<Script>, xml_out: &mut OdsXmlWriter<'_>) -> Resul
             write_xmlcontent(content, xml_out)?;
         }
 
-        xml_out.end_elem("/office:script")?;
+        xml_out.end_elem("office:script")?;
     }
 
     Ok(())
@@ -2362,15 +3595,25 @@ fn write_event_listeners(
     events: &HashMap<String, EventListener>,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for event in events.values() {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    xml_out.elem("office:event-listeners")?;
+    for event in sorted_values(events) {
         xml_out.empty("script:event-listener")?;
         xml_out.attr_esc("script:event-name", &event.event_name)?;
         xml_out.attr_esc("script:language", &event.script_lang)?;
-        xml_out.attr_esc("script:macro-name", &event.macro_name)?;
-        xml_out.attr_esc("xlink:actuate", &event.actuate)?;
-        xml_out.attr_esc("xlink:href", &event.href)?;
-        xml_out.attr_esc("xlink:type", &event.link_type)?;
+        if !event.macro_name.is_empty() {
+            xml_out.attr_esc("script:macro-name", &event.macro_name)?;
+        }
+        if !event.href.is_empty() {
+            xml_out.attr_esc("xlink:actuate", &event.actuate)?;
+            xml_out.attr_esc("xlink:href", &event.href)?;
+            xml_out.attr_esc("xlink:type", &event.link_type)?;
+        }
     }
+    xml_out.end_elem("office:event-listeners")?;
 
     Ok(())
 }
@@ -2391,12 +3634,31 @@ fn write_style_font_face(
     origin: StyleOrigin,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for font in fonts.values().filter(|s| s.origin() == origin) {
-        xml_out.empty("style:font-face")?;
+    for font in sorted_values(fonts)
+        .into_iter()
+        .filter(|s| s.origin() == origin)
+    {
+        xml_out.elem_if(!font.src().is_empty(), "style:font-face")?;
         xml_out.attr_esc("style:name", font.name())?;
         for (a, v) in font.attrmap().iter() {
             xml_out.attr_esc(a.as_ref(), v)?;
         }
+        if !font.src().is_empty() {
+            xml_out.elem("svg:font-face-src")?;
+            for uri in font.src() {
+                xml_out.elem_if(uri.format().is_some(), "svg:font-face-uri")?;
+                for (a, v) in uri.attrmap().iter() {
+                    xml_out.attr_esc(a.as_ref(), v)?;
+                }
+                if let Some(format) = uri.format() {
+                    xml_out.empty("svg:font-face-format")?;
+                    xml_out.attr_esc("svg:string", format)?;
+                }
+                xml_out.end_elem_if(uri.format().is_some(), "svg:font-face-uri")?;
+            }
+            xml_out.end_elem("svg:font-face-src")?;
+        }
+        xml_out.end_elem_if(!font.src().is_empty(), "style:font-face")?;
     }
     Ok(())
 }
@@ -2407,79 +3669,167 @@ fn write_office_styles(
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
     xml_out.elem("office:styles")?;
+    write_theme(book, xml_out)?;
+    write_fills(book, xml_out)?;
     write_styles(book, origin, StyleUse::Default, xml_out)?;
     write_styles(book, origin, StyleUse::Named, xml_out)?;
     write_valuestyles(book, origin, StyleUse::Named, xml_out)?;
     write_valuestyles(book, origin, StyleUse::Default, xml_out)?;
+    write_extra_styles(book, origin, StylesPart::Styles, xml_out)?;
     xml_out.end_elem("office:styles")?;
     Ok(())
 }
 
+fn write_theme(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    if let Some(theme) = &book.theme {
+        xml_out.elem("loext:theme")?;
+        xml_out.attr_esc("loext:name", theme.name())?;
+        xml_out.elem("loext:theme-colors")?;
+        xml_out.attr_esc("loext:name", theme.colors_name())?;
+        for (slot, color) in theme.colors() {
+            xml_out.empty("loext:color")?;
+            xml_out.attr("loext:name", slot)?;
+            xml_out.attr("loext:color", &color_string(*color))?;
+        }
+        xml_out.end_elem("loext:theme-colors")?;
+        xml_out.end_elem("loext:theme")?;
+    }
+    Ok(())
+}
+
+fn write_fills(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    for gradient in sorted_values(&book.gradients) {
+        xml_out.empty("draw:gradient")?;
+        xml_out.attr_esc("draw:name", gradient.name())?;
+        for (a, v) in gradient.attrmap().iter() {
+            xml_out.attr_esc(a.as_ref(), v)?;
+        }
+    }
+    for hatch in sorted_values(&book.hatches) {
+        xml_out.empty("draw:hatch")?;
+        xml_out.attr_esc("draw:name", hatch.name())?;
+        for (a, v) in hatch.attrmap().iter() {
+            xml_out.attr_esc(a.as_ref(), v)?;
+        }
+    }
+    for image in sorted_values(&book.fill_images) {
+        xml_out.empty("draw:fill-image")?;
+        xml_out.attr_esc("draw:name", image.name())?;
+        for (a, v) in image.attrmap().iter() {
+            xml_out.attr_esc(a.as_ref(), v)?;
+        }
+    }
+    Ok(())
+}
+
 fn write_office_automatic_styles(
     book: &WorkBook,
     origin: StyleOrigin,
+    copies: &WriteCopies,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
     xml_out.elem("office:automatic-styles")?;
-    write_pagestyles(&book.pagestyles, xml_out)?;
+    write_pagestyles(
+        sorted_values(&book.pagestyles)
+            .into_iter()
+            .chain(&copies.pagestyles),
+        xml_out,
+    )?;
     write_styles(book, origin, StyleUse::Automatic, xml_out)?;
+    for style in &copies.tablestyles {
+        if style.origin() == origin {
+            write_tablestyle(style, xml_out)?;
+        }
+    }
+    for style in &copies.cellstyles {
+        if style.origin() == origin {
+            write_cellstyle(style, xml_out)?;
+        }
+    }
     write_valuestyles(book, origin, StyleUse::Automatic, xml_out)?;
+    write_extra_styles(book, origin, StylesPart::AutomaticStyles, xml_out)?;
     xml_out.end_elem("office:automatic-styles")?;
     Ok(())
 }
 
 fn write_office_master_styles(
     book: &WorkBook,
+    copies: &WriteCopies,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
     xml_out.elem("office:master-styles")?;
-    write_masterpage(&book.masterpages, xml_out)?;
+    write_masterpage(
+        sorted_values(&book.masterpages)
+            .into_iter()
+            .chain(&copies.masterpages),
+        xml_out,
+    )?;
+    for (_, part, tag) in &book.extra_styles {
+        if *part == StylesPart::MasterStyles {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
     xml_out.end_elem("office:master-styles")?;
     Ok(())
 }
 
+// Unknown style elements, see OdsOptions::preserve_unknown().
+fn write_extra_styles(
+    book: &WorkBook,
+    origin: StyleOrigin,
+    part: StylesPart,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    for (tag_origin, tag_part, tag) in &book.extra_styles {
+        if *tag_origin == origin && *tag_part == part {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+    Ok(())
+}
+
 fn write_styles(
     book: &WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for style in book.colstyles.values() {
+    for style in sorted_values(&book.colstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_colstyle(style, xml_out)?;
         }
     }
-    for style in book.rowstyles.values() {
+    for style in sorted_values(&book.rowstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_rowstyle(style, xml_out)?;
         }
     }
-    for style in book.tablestyles.values() {
+    for style in sorted_values(&book.tablestyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_tablestyle(style, xml_out)?;
         }
     }
-    for style in book.cellstyles.values() {
+    for style in sorted_values(&book.cellstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_cellstyle(style, xml_out)?;
         }
     }
-    for style in book.paragraphstyles.values() {
+    for style in sorted_values(&book.paragraphstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_paragraphstyle(style, xml_out)?;
         }
     }
-    for style in book.textstyles.values() {
+    for style in sorted_values(&book.textstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_textstyle(style, xml_out)?;
         }
     }
-    for style in book.rubystyles.values() {
+    for style in sorted_values(&book.rubystyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_rubystyle(style, xml_out)?;
         }
     }
-    for style in book.graphicstyles.values() {
+    for style in sorted_values(&book.graphicstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_graphicstyle(style, xml_out)?;
         }
@@ -2869,12 +4219,17 @@ fn write_valuestyle<T: ValueFormatTrait>(
     styleuse: StyleUse,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for value_format in value_formats
-        .values()
+    for value_format in sorted_values(value_formats)
+        .into_iter()
         .filter(|s| s.origin() == origin && s.styleuse() == styleuse)
     {
         let tag = match value_format.value_type() {
-            ValueType::Empty => unreachable!(),
+            ValueType::Empty => {
+                return Err(OdsError::Ods(format!(
+                    "Value format {} has no value type",
+                    value_format.name()
+                )))
+            }
             ValueType::Boolean => "number:boolean-style",
             ValueType::Number => "number:number-style",
             ValueType::Text => "number:text-style",
@@ -2935,21 +4290,20 @@ fn write_valuestyle<T: ValueFormatTrait>(
                 }
                 xml_out.end_elem_if(content.is_some(), part_tag)?;
             } else if part.part_type() == FormatPartType::Number {
-                if let Some(position) = part.position() {
+                if !part.embedded_text().is_empty() {
                     xml_out.elem(part_tag)?;
                     for (a, v) in part.attrmap().iter() {
                         xml_out.attr_esc(a.as_ref(), v)?;
                     }
 
-                    // embedded text
-                    if let Some(content) = part.content() {
-                        xml_out.elem("number:embedded-text")?;
-                        xml_out.attr_esc("number:position", &position)?;
-                        xml_out.text_esc(content)?;
-                        xml_out.end_elem("number:embedded-text")?;
-                    } else {
-                        xml_out.empty("number:embedded-text")?;
-                        xml_out.attr_esc("number:position", &position)?;
+                    for embedded_text in part.embedded_text() {
+                        let has_text = !embedded_text.text().is_empty();
+                        xml_out.elem_if(has_text, "number:embedded-text")?;
+                        xml_out.attr_esc("number:position", &embedded_text.position())?;
+                        if has_text {
+                            xml_out.text_esc(embedded_text.text())?;
+                        }
+                        xml_out.end_elem_if(has_text, "number:embedded-text")?;
                     }
 
                     xml_out.end_elem(part_tag)?;
@@ -2981,11 +4335,11 @@ fn write_valuestyle<T: ValueFormatTrait>(
     Ok(())
 }
 
-fn write_pagestyles(
-    styles: &HashMap<PageStyleRef, PageStyle>,
+fn write_pagestyles<'a>(
+    styles: impl Iterator<Item = &'a PageStyle>,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for style in styles.values() {
+    for style in styles {
         xml_out.elem("style:page-layout")?;
         xml_out.attr_esc("style:name", style.name())?;
         if let Some(master_page_usage) = &style.master_page_usage {
@@ -3023,11 +4377,11 @@ fn write_pagestyles(
     Ok(())
 }
 
-fn write_masterpage(
-    masterpages: &HashMap<MasterPageRef, MasterPage>,
+fn write_masterpage<'a>(
+    masterpages: impl Iterator<Item = &'a MasterPage>,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for masterpage in masterpages.values() {
+    for masterpage in masterpages {
         xml_out.elem("style:master-page")?;
         xml_out.attr_esc("style:name", masterpage.name())?;
         if !masterpage.display_name().is_empty() {
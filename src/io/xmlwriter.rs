@@ -67,6 +67,9 @@ impl Stack {
     }
 }
 
+/// Buffered output is handed to the writer once it exceeds this size.
+const FLUSH_SIZE: usize = 8192;
+
 /// The XmlWriter himself
 pub(crate) struct XmlWriter<W: Write> {
     writer: Box<W>,
@@ -75,6 +78,17 @@ pub(crate) struct XmlWriter<W: Write> {
     open: Open,
     line_break: bool,
 
+    // pretty printing
+    indent: bool,
+    started: bool,
+    depth: usize,
+    // depth of the outermost element with mixed content.
+    mixed: Option<usize>,
+    // last output was an end-tag or an empty element.
+    after_end: bool,
+    // start of each attribute of the open element.
+    attr_pos: Vec<usize>,
+
     // short time temp space
     tmp: Vec<u8>,
     tmp2: Vec<u8>,
@@ -99,6 +113,12 @@ impl<W: Write> XmlWriter<W> {
             writer: Box::new(writer),
             open: Open::None,
             line_break: false,
+            indent: false,
+            started: false,
+            depth: 0,
+            mixed: None,
+            after_end: false,
+            attr_pos: Default::default(),
             tmp: Default::default(),
             tmp2: Default::default(),
         }
@@ -109,6 +129,85 @@ impl<W: Write> XmlWriter<W> {
         self
     }
 
+    /// Indents nested elements and sorts the attributes by name.
+    /// Elements with text content are not indented, this replaces line_break.
+    pub(crate) fn indent(mut self, indent: bool) -> Self {
+        self.indent = indent;
+        if indent {
+            self.line_break = false;
+        }
+        self
+    }
+
+    // Elements that can contain text. Any whitespace would become part of the text.
+    fn is_mixed(name: &str) -> bool {
+        name.starts_with("text:") || name == "office:script"
+    }
+
+    // Line break and indentation before a tag.
+    fn new_line(&mut self, depth: usize) {
+        if self.indent && self.mixed.is_none() {
+            if self.started {
+                self.buf.push('\n');
+            }
+            for _ in 0..depth {
+                self.buf.push_str("  ");
+            }
+        }
+    }
+
+    // Before a start-tag.
+    fn start_tag(&mut self, name: &str, has_content: bool) {
+        self.new_line(self.depth);
+        self.started = true;
+        if self.indent && has_content {
+            if self.mixed.is_none() && Self::is_mixed(name) {
+                self.mixed = Some(self.depth);
+            }
+            self.depth += 1;
+        }
+        self.after_end = !has_content;
+    }
+
+    // Before an end-tag.
+    fn end_tag(&mut self) {
+        if self.indent {
+            self.depth = self.depth.saturating_sub(1);
+            if self.after_end {
+                self.new_line(self.depth);
+            }
+            if self.mixed == Some(self.depth) {
+                self.mixed = None;
+            }
+        }
+        self.after_end = true;
+    }
+
+    // Sorts the attributes of the open element.
+    fn sort_attr(&mut self) {
+        if self.attr_pos.len() > 1 {
+            let start = self.attr_pos[0];
+            let mut attr: Vec<String> = Vec::with_capacity(self.attr_pos.len());
+            for (i, pos) in self.attr_pos.iter().enumerate() {
+                let end = self.attr_pos.get(i + 1).copied().unwrap_or(self.buf.len());
+                attr.push(self.buf[*pos..end].to_string());
+            }
+            attr.sort();
+            self.buf.truncate(start);
+            for a in attr {
+                self.buf.push_str(&a);
+            }
+        }
+        self.attr_pos.clear();
+    }
+
+    // Remembers the start of an attribute for sorting.
+    fn start_attr(&mut self) {
+        if self.indent {
+            self.attr_pos.push(self.buf.len());
+        }
+    }
+
     /// Write the DTD. You have to take care of the encoding
     /// on the underlying Write yourself.
     pub(crate) fn dtd(&mut self, encoding: &str) -> io::Result<()> {
@@ -129,6 +228,7 @@ impl<W: Write> XmlWriter<W> {
         text: &T,
     ) -> io::Result<()> {
         self.close_elem()?;
+        self.start_tag(name, false);
 
         self.buf.push('<');
         self.buf.push_str(name);
@@ -155,6 +255,7 @@ impl<W: Write> XmlWriter<W> {
         text: &T,
     ) -> io::Result<()> {
         self.close_elem()?;
+        self.start_tag(name, false);
 
         self.buf.push('<');
         self.buf.push_str(name);
@@ -176,6 +277,7 @@ impl<W: Write> XmlWriter<W> {
     #[allow(dead_code)]
     pub(crate) fn comment(&mut self, comment: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.start_tag("", false);
 
         self.buf.push_str("<!--");
         self.buf.push_str(comment);
@@ -190,6 +292,7 @@ impl<W: Write> XmlWriter<W> {
     /// Begin an elem, make sure name contains only allowed chars
     pub(crate) fn elem(&mut self, name: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.start_tag(name, true);
 
         self.stack.push(name);
 
@@ -202,6 +305,7 @@ impl<W: Write> XmlWriter<W> {
     /// Begin an elem if has_content is true, otherwise begin a empty elem.
     pub(crate) fn elem_if(&mut self, has_content: bool, name: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.start_tag(name, has_content);
 
         if has_content {
             self.stack.push(name);
@@ -216,6 +320,7 @@ impl<W: Write> XmlWriter<W> {
     /// Begin an empty elem
     pub(crate) fn empty(&mut self, name: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.start_tag(name, false);
 
         self.buf.push('<');
         self.open = Open::Empty;
@@ -225,6 +330,9 @@ impl<W: Write> XmlWriter<W> {
 
     /// Close an elem if open, do nothing otherwise
     fn close_elem(&mut self) -> io::Result<()> {
+        if self.indent && self.open != Open::None {
+            self.sort_attr();
+        }
         match self.open {
             Open::None => {}
             Open::Elem => {
@@ -239,7 +347,9 @@ impl<W: Write> XmlWriter<W> {
             }
         }
         self.open = Open::None;
-        self.write_buf()?;
+        if self.buf.len() >= FLUSH_SIZE {
+            self.write_buf()?;
+        }
         Ok(())
     }
 
@@ -252,6 +362,7 @@ impl<W: Write> XmlWriter<W> {
                 self.stack
             );
         }
+        self.start_attr();
         self.buf.push(' ');
         self.buf.push_str(name);
         self.buf.push('=');
@@ -271,6 +382,7 @@ impl<W: Write> XmlWriter<W> {
             );
         }
 
+        self.start_attr();
         self.buf.push(' ');
         self.buf.push_str(name);
         self.buf.push('=');
@@ -280,6 +392,48 @@ impl<W: Write> XmlWriter<W> {
         Ok(())
     }
 
+    /// Write a number attr and an inlined element with the same text.
+    /// The value is formatted only once.
+    pub(crate) fn attr_elem_f64(&mut self, attr: &str, name: &str, value: f64) -> io::Result<()> {
+        if cfg!(feature = "check_xml") && self.open == Open::None {
+            panic!(
+                "Attempted to write attr to elem, when no elem was opened, stack {:?}",
+                self.stack
+            );
+        }
+
+        self.start_attr();
+        self.buf.push(' ');
+        self.buf.push_str(attr);
+        self.buf.push('=');
+        self.buf.push('"');
+        let start = self.buf.len();
+        push_f64(&mut self.buf, value);
+        self.tmp.clear();
+        self.tmp.extend_from_slice(&self.buf.as_bytes()[start..]);
+        self.buf.push('"');
+
+        self.close_elem()?;
+        self.start_tag(name, false);
+
+        self.buf.push('<');
+        self.buf.push_str(name);
+        self.buf.push('>');
+        // Safety: this is always from a string buffer.
+        unsafe {
+            self.buf.push_str(from_utf8_unchecked(&self.tmp));
+        }
+        self.buf.push('<');
+        self.buf.push('/');
+        self.buf.push_str(name);
+        self.buf.push('>');
+        if self.line_break {
+            self.buf.push('\n');
+        }
+
+        Ok(())
+    }
+
     /// Write an attr,  make sure name contains only allowed chars
     pub(crate) fn attr_esc<T: Display + ?Sized>(
         &mut self,
@@ -292,6 +446,7 @@ impl<W: Write> XmlWriter<W> {
                 self.stack
             );
         }
+        self.start_attr();
         self.buf.push(' ');
         self.escape_name(name)?;
         self.buf.push('=');
@@ -381,6 +536,7 @@ impl<W: Write> XmlWriter<W> {
     /// Write a text, doesn't escape the text.
     pub(crate) fn text_str(&mut self, text: &'static str) -> io::Result<()> {
         self.close_elem()?;
+        self.after_end = false;
         self.buf.push_str(text);
         Ok(())
     }
@@ -388,6 +544,7 @@ impl<W: Write> XmlWriter<W> {
     /// Write a text, doesn't escape the text.
     pub(crate) fn text<T: Display + ?Sized>(&mut self, text: &T) -> io::Result<()> {
         self.close_elem()?;
+        self.after_end = false;
         let _ = write!(self.buf, "{}", text);
         Ok(())
     }
@@ -395,6 +552,7 @@ impl<W: Write> XmlWriter<W> {
     /// Write a text, escapes the text automatically
     pub(crate) fn text_esc<T: Display + ?Sized>(&mut self, text: &T) -> io::Result<()> {
         self.close_elem()?;
+        self.after_end = false;
         self.escape(text)?;
         Ok(())
     }
@@ -424,6 +582,7 @@ impl<W: Write> XmlWriter<W> {
             }
         }
 
+        self.end_tag();
         self.buf.push('<');
         self.buf.push('/');
         self.buf.push_str(name);
@@ -460,3 +619,58 @@ impl<W: Write> XmlWriter<W> {
         Ok(())
     }
 }
+
+const POW10: [f64; 5] = [1.0, 10.0, 100.0, 1000.0, 10000.0];
+
+/// Same output as Display for f64, but numbers with up to four decimals
+/// are written without the general float formatting.
+///
+/// A value below 1e9 is exactly n / 10^d for at most one n with d <= 4,
+/// so the first d that gives back the value is the shortest output.
+fn push_f64(buf: &mut String, v: f64) {
+    if v != 0.0 && v.abs() < 1e9 {
+        for (decimals, pow) in POW10.iter().enumerate() {
+            let n = (v * pow).round();
+            if n / pow == v {
+                push_decimal(buf, n, *pow as u64, decimals);
+                return;
+            }
+        }
+    }
+    let _ = write!(buf, "{}", v);
+}
+
+// Writes n / pow with the given number of decimals.
+fn push_decimal(buf: &mut String, n: f64, pow: u64, decimals: usize) {
+    if n < 0.0 {
+        buf.push('-');
+    }
+    let n = n.abs() as u64;
+    push_u64(buf, n / pow);
+    if decimals > 0 {
+        buf.push('.');
+        let mut frac = n % pow;
+        let mut digits = [b'0'; 4];
+        for d in digits[..decimals].iter_mut().rev() {
+            *d = b'0' + (frac % 10) as u8;
+            frac /= 10;
+        }
+        // Safety: only ascii digits.
+        buf.push_str(unsafe { from_utf8_unchecked(&digits[..decimals]) });
+    }
+}
+
+fn push_u64(buf: &mut String, mut v: u64) {
+    let mut digits = [0u8; 20];
+    let mut i = digits.len();
+    loop {
+        i -= 1;
+        digits[i] = b'0' + (v % 10) as u8;
+        v /= 10;
+        if v == 0 {
+            break;
+        }
+    }
+    // Safety: only ascii digits.
+    buf.push_str(unsafe { from_utf8_unchecked(&digits[i..]) });
+}
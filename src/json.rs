@@ -0,0 +1,171 @@
+//!
+//! JSON snapshot of a workbook.
+//!
+//! Needs the feature "json".
+//!
+//! Contains the sheets with their cell values, formulas and the names
+//! of the cell styles. The styles themselves, value formats and everything
+//! else are not part of the snapshot. Rich text is reduced to plain text.
+//!
+//! ```json
+//! {
+//!   "sheets": [
+//!     {
+//!       "name": "report",
+//!       "cells": [
+//!         { "row": 0, "col": 0, "value": { "type": "text", "value": "total" } },
+//!         { "row": 0, "col": 1, "value": { "type": "number", "value": 3.0 },
+//!           "formula": "of:=SUM([.A1:.A2])", "style": "bold" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+
+use crate::digest::is_blank;
+use crate::{CellStyleRef, OdsError, Sheet, Value, WorkBook};
+use chrono::{DateTime, Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct JsonWorkBook {
+    sheets: Vec<JsonSheet>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSheet {
+    name: String,
+    cells: Vec<JsonCell>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonCell {
+    row: u32,
+    col: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    formula: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    style: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum JsonValue {
+    Boolean { value: bool },
+    Number { value: f64 },
+    Percentage { value: f64 },
+    Currency { value: f64, currency: String },
+    Text { value: String },
+    DateTime { value: String },
+    DateTimeTz { value: String },
+    TimeDuration { value: i64 },
+}
+
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+impl WorkBook {
+    /// Creates a JSON snapshot of the sheets.
+    ///
+    /// Contains the cell values, formulas and the names of the cell styles.
+    /// Values are written as `{ "type": "number", "value": 1.0 }`, the types
+    /// are boolean, number, percentage, currency, text, datetime, datetimetz
+    /// and timeduration. Dates are written as "2024-03-01T12:00:00", dates
+    /// with a timezone as RFC 3339, durations as milliseconds.
+    pub fn to_json(&self) -> Result<String, OdsError> {
+        let book = JsonWorkBook {
+            sheets: self.iter_sheets().map(sheet_to_json).collect(),
+        };
+        serde_json::to_string_pretty(&book).map_err(json_err)
+    }
+
+    /// Creates a workbook from a JSON snapshot.
+    ///
+    /// The cells refer to the cell styles by name only, they must be added
+    /// separately if needed.
+    pub fn from_json(json: &str) -> Result<WorkBook, OdsError> {
+        let json: JsonWorkBook = serde_json::from_str(json).map_err(json_err)?;
+
+        let mut book = WorkBook::new_empty();
+        for json_sheet in json.sheets {
+            let mut sheet = Sheet::new(json_sheet.name);
+            for cell in json_sheet.cells {
+                if let Some(value) = cell.value {
+                    sheet.set_value(cell.row, cell.col, value_from_json(value)?);
+                }
+                if let Some(formula) = cell.formula {
+                    sheet.set_formula(cell.row, cell.col, formula);
+                }
+                if let Some(style) = cell.style {
+                    sheet.set_cellstyle(cell.row, cell.col, &CellStyleRef::from(style));
+                }
+            }
+            book.push_sheet(sheet);
+        }
+        Ok(book)
+    }
+}
+
+fn sheet_to_json(sheet: &Sheet) -> JsonSheet {
+    JsonSheet {
+        name: sheet.name().clone(),
+        cells: sheet
+            .data
+            .iter()
+            .filter(|(_, v)| !is_blank(v))
+            .map(|((row, col), cell)| JsonCell {
+                row,
+                col,
+                value: value_to_json(&cell.value),
+                formula: cell.formula.clone(),
+                style: cell.style.as_ref().map(|v| v.as_str().to_string()),
+            })
+            .collect(),
+    }
+}
+
+fn value_to_json(value: &Value) -> Option<JsonValue> {
+    Some(match value {
+        Value::Empty => return None,
+        Value::Boolean(v) => JsonValue::Boolean { value: *v },
+        Value::Number(v) => JsonValue::Number { value: *v },
+        Value::Percentage(v) => JsonValue::Percentage { value: *v },
+        Value::Currency(v, c) => JsonValue::Currency {
+            value: *v,
+            currency: c.to_string(),
+        },
+        Value::Text(_) | Value::TextXml(_) => JsonValue::Text {
+            value: value.as_cow_str_or("").to_string(),
+        },
+        Value::DateTime(v) => JsonValue::DateTime {
+            value: v.format(DATETIME_FORMAT).to_string(),
+        },
+        Value::DateTimeTz(v) => JsonValue::DateTimeTz {
+            value: v.to_rfc3339(),
+        },
+        Value::TimeDuration(v) => JsonValue::TimeDuration {
+            value: v.num_milliseconds(),
+        },
+    })
+}
+
+fn json_err(err: serde_json::Error) -> OdsError {
+    OdsError::Ods(format!("Json {}", err))
+}
+
+fn value_from_json(value: JsonValue) -> Result<Value, OdsError> {
+    Ok(match value {
+        JsonValue::Boolean { value } => Value::Boolean(value),
+        JsonValue::Number { value } => Value::Number(value),
+        JsonValue::Percentage { value } => Value::Percentage(value),
+        JsonValue::Currency { value, currency } => Value::Currency(value, currency.into()),
+        JsonValue::Text { value } => Value::from(value),
+        JsonValue::DateTime { value } => {
+            Value::DateTime(NaiveDateTime::parse_from_str(&value, DATETIME_FORMAT)?)
+        }
+        JsonValue::DateTimeTz { value } => Value::DateTimeTz(DateTime::parse_from_rfc3339(&value)?),
+        JsonValue::TimeDuration { value } => Value::TimeDuration(Duration::milliseconds(value)),
+    })
+}
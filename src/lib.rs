@@ -4,14 +4,20 @@ pub use color;
 pub use zip::CompressionMethod;
 
 pub use crate::cell_::{CellContent, CellContentRef};
-pub use crate::error::{OdsError, OdsResult};
+pub use crate::diff::diff;
+pub use crate::error::{OdsError, OdsResult, OdsWarning};
 pub use crate::format::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
     ValueFormatPercentage, ValueFormatRef, ValueFormatText, ValueFormatTimeDuration,
 };
+#[cfg(feature = "async")]
+pub use crate::io::read::read_ods_async;
 pub use crate::io::read::{
     read_fods, read_fods_buf, read_fods_from, read_ods, read_ods_buf, read_ods_from, OdsOptions,
+    TimezonePolicy,
 };
+#[cfg(feature = "async")]
+pub use crate::io::write::write_ods_async;
 pub use crate::io::write::{
     write_fods, write_fods_buf, write_fods_to, write_ods, write_ods_buf,
     write_ods_buf_uncompressed, write_ods_to, OdsWriteOptions,
@@ -20,7 +26,7 @@ pub use crate::refs::{CCol, CRow, CellRange, CellRef, ColRange, RowRange};
 pub use crate::sheet_::Sheet;
 pub use crate::style::units::{Angle, Length};
 pub use crate::style::{CellStyle, CellStyleRef};
-pub use crate::value_::{Value, ValueType};
+pub use crate::value_::{Currency, Value, ValueType};
 pub use crate::workbook_::WorkBook;
 
 #[macro_use]
@@ -51,10 +57,14 @@ mod macro_text;
 mod attrmap2;
 mod cell_;
 mod config;
+#[cfg(feature = "arrow")]
+mod dataframe;
+mod digest;
 mod ds;
 mod error;
 mod io;
-mod locale;
+#[cfg(feature = "json")]
+mod json;
 mod sheet_;
 #[macro_use]
 mod value_;
@@ -62,27 +72,44 @@ mod workbook_;
 
 pub mod cell {
     //! Detail structs for a Cell.
-    pub use crate::cell_::CellSpan;
+    pub use crate::cell_::{CellContentBuilder, CellSpan};
 }
+pub mod changes;
+pub mod compat;
 pub mod condition;
+pub mod csv;
 pub mod defaultstyles;
+pub mod diff;
 pub mod draw;
+pub mod examples;
 pub mod format;
 #[macro_use]
 pub mod formula;
+pub mod html;
+pub mod lint;
+pub mod locale;
 pub mod manifest;
 pub mod metadata;
 pub mod refs;
+pub mod rules;
 pub mod sheet {
     //! Detail structs for a Sheet.
-    pub use crate::sheet_::{CellIter, Grouped, Range, SheetConfig, SplitMode, Visibility};
+    pub use crate::sheet_::{
+        sanitize_sheet_name, CellIter, ColumnSpec, Grouped, MergedCells, PrintSetup, Range, RowsIn,
+        Scenario, SheetConfig, SplitMode, TableSource, TableSourceMode, TableTemplate, Visibility,
+    };
 }
+pub mod sparkline;
 pub mod style;
+pub mod template;
 pub mod text;
 pub mod validation;
 pub mod workbook {
     //! Detail structs for the WorkBook.
-    pub use crate::workbook_::{EventListener, Script, WorkBookConfig};
+    pub use crate::workbook_::{
+        BasicLibrary, CalculationSettings, CellTag, EventListener, FrozenWorkBook, MergePolicy,
+        NamedView, Script, SheetNamePolicy, StyleFamily, StyleUsage, WorkBookConfig,
+    };
 }
 pub mod xlink;
 pub mod xmltree;
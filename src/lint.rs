@@ -0,0 +1,500 @@
+//!
+//! Lint rules for generated spreadsheets.
+//!
+//! ```
+//! use spreadsheet_ods::lint::{LintOptions, LintRule, Severity};
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//!
+//! let mut wb = WorkBook::new_empty();
+//! wb.push_sheet(Sheet::new("one"));
+//!
+//! let report = LintOptions::default()
+//!     .severity(LintRule::UnusedStyles, Severity::Error)
+//!     .lint(&wb);
+//! assert!(!report.has_errors());
+//! ```
+//!
+
+use crate::attrmap2::AttrMap2;
+use crate::refs::parse_cellref;
+use crate::style::units::length_attr_units;
+use crate::style::StyleUse;
+use crate::workbook::StyleFamily;
+use crate::{CellRef, CellStyleRef, HashMap, Sheet, Value, ValueType, WorkBook};
+use std::fmt::{Display, Formatter};
+
+/// Severity of a lint message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The rule is not checked.
+    Allow,
+    /// Reported, but not an error.
+    Warning,
+    /// Reported as an error.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Allow => write!(f, "allow"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The available lint rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintRule {
+    /// Automatic styles that are not used anywhere.
+    UnusedStyles,
+    /// Row or cell repeat counts above LintOptions::max_repeat().
+    HugeRepeat,
+    /// Date values without a value format.
+    DateWithoutFormat,
+    /// Formulas that reference empty cells.
+    FormulaRefEmpty,
+    /// Numbers stored as text that use different decimal separators
+    /// within one sheet.
+    MixedDecimalSeparator,
+    /// Styles that use different units for their lengths.
+    /// Font sizes are not counted.
+    MixedUnits,
+}
+
+impl LintRule {
+    /// All rules.
+    pub const ALL: [LintRule; 6] = [
+        LintRule::UnusedStyles,
+        LintRule::HugeRepeat,
+        LintRule::DateWithoutFormat,
+        LintRule::FormulaRefEmpty,
+        LintRule::MixedDecimalSeparator,
+        LintRule::MixedUnits,
+    ];
+
+    /// Name of the rule.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::UnusedStyles => "unused-styles",
+            LintRule::HugeRepeat => "huge-repeat",
+            LintRule::DateWithoutFormat => "date-without-format",
+            LintRule::FormulaRefEmpty => "formula-ref-empty",
+            LintRule::MixedDecimalSeparator => "mixed-decimal-separator",
+            LintRule::MixedUnits => "mixed-units",
+        }
+    }
+
+    /// Severity if nothing else is configured.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            LintRule::DateWithoutFormat => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One finding.
+#[derive(Debug, Clone)]
+pub struct LintMessage {
+    /// Rule.
+    pub rule: LintRule,
+    /// Configured severity of the rule.
+    pub severity: Severity,
+    /// Cell, if the finding is about a cell or row.
+    pub cell: Option<CellRef>,
+    /// Description.
+    pub message: String,
+}
+
+impl Display for LintMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}]", self.severity, self.rule)?;
+        if let Some(cell) = &self.cell {
+            write!(f, " {}", cell)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Result of a lint run.
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    messages: Vec<LintMessage>,
+}
+
+impl LintReport {
+    /// All findings.
+    pub fn messages(&self) -> &[LintMessage] {
+        &self.messages
+    }
+
+    /// No findings at all.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Any finding with severity error.
+    pub fn has_errors(&self) -> bool {
+        self.messages.iter().any(|v| v.severity == Severity::Error)
+    }
+
+    /// Findings for one rule.
+    pub fn rule(&self, rule: LintRule) -> impl Iterator<Item = &LintMessage> {
+        self.messages.iter().filter(move |v| v.rule == rule)
+    }
+}
+
+impl Display for LintReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for msg in &self.messages {
+            writeln!(f, "{}", msg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Configures and runs the lint rules.
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    severity: HashMap<LintRule, Severity>,
+    max_repeat: u32,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            severity: LintRule::ALL
+                .iter()
+                .map(|v| (*v, v.default_severity()))
+                .collect(),
+            max_repeat: 10000,
+        }
+    }
+}
+
+impl LintOptions {
+    /// Sets the severity for a rule. Severity::Allow disables the rule.
+    pub fn severity(mut self, rule: LintRule, severity: Severity) -> Self {
+        self.severity.insert(rule, severity);
+        self
+    }
+
+    /// Maximum repeat count for rows and cells. Default is 10000.
+    pub fn max_repeat(mut self, max_repeat: u32) -> Self {
+        self.max_repeat = max_repeat;
+        self
+    }
+
+    /// Runs all enabled rules.
+    pub fn lint(&self, book: &WorkBook) -> LintReport {
+        let mut report = Lint {
+            options: self,
+            messages: Vec::new(),
+        };
+
+        if report.enabled(LintRule::UnusedStyles) {
+            report.unused_styles(book);
+        }
+        if report.enabled(LintRule::MixedUnits) {
+            report.mixed_units(book);
+        }
+        for sheet in book.iter_sheets() {
+            if report.enabled(LintRule::HugeRepeat) {
+                report.huge_repeat(sheet);
+            }
+            if report.enabled(LintRule::DateWithoutFormat) {
+                report.date_without_format(book, sheet);
+            }
+            if report.enabled(LintRule::FormulaRefEmpty) {
+                report.formula_ref_empty(book, sheet);
+            }
+            if report.enabled(LintRule::MixedDecimalSeparator) {
+                report.mixed_decimal_separator(sheet);
+            }
+        }
+
+        LintReport {
+            messages: report.messages,
+        }
+    }
+}
+
+struct Lint<'a> {
+    options: &'a LintOptions,
+    messages: Vec<LintMessage>,
+}
+
+impl Lint<'_> {
+    fn severity(&self, rule: LintRule) -> Severity {
+        self.options
+            .severity
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+
+    fn enabled(&self, rule: LintRule) -> bool {
+        self.severity(rule) != Severity::Allow
+    }
+
+    fn push(&mut self, rule: LintRule, cell: Option<CellRef>, message: String) {
+        self.messages.push(LintMessage {
+            rule,
+            severity: self.severity(rule),
+            cell,
+            message,
+        });
+    }
+
+    fn unused_styles(&mut self, book: &WorkBook) {
+        let mut unused = Vec::new();
+        for ((family, name), usage) in book.style_usage() {
+            let (family, styleuse) = match family {
+                StyleFamily::Cell => ("cell", book.cellstyle(&name).map(|v| v.styleuse())),
+                StyleFamily::Row => ("row", book.rowstyle(&name).map(|v| v.styleuse())),
+                StyleFamily::Col => ("column", book.colstyle(&name).map(|v| v.styleuse())),
+                StyleFamily::Table => ("table", book.tablestyle(&name).map(|v| v.styleuse())),
+                StyleFamily::ValueFormat => continue,
+            };
+            if styleuse == Some(StyleUse::Automatic) && usage.is_unused() {
+                unused.push(format!("unused {} style {}", family, name));
+            }
+        }
+
+        unused.sort();
+        for msg in unused {
+            self.push(LintRule::UnusedStyles, None, msg);
+        }
+    }
+
+    fn mixed_units(&mut self, book: &WorkBook) {
+        let mut mixed = Vec::new();
+        let mut check = |family: &str, name: &str, attrmaps: &[&AttrMap2]| {
+            let mut units = Vec::new();
+            for attr in attrmaps {
+                for (k, v) in attr.iter() {
+                    if k.contains("font-size") {
+                        continue;
+                    }
+                    for unit in length_attr_units(k, v) {
+                        if !units.contains(&unit) {
+                            units.push(unit);
+                        }
+                    }
+                }
+            }
+            if units.len() > 1 {
+                let units: Vec<String> = units.iter().map(|v| v.to_string()).collect();
+                mixed.push(format!(
+                    "{} style {} mixes units {}",
+                    family,
+                    name,
+                    units.join(", ")
+                ));
+            }
+        };
+        for s in book.cellstyles.values() {
+            check(
+                "cell",
+                s.name(),
+                &[s.cellstyle(), s.paragraphstyle(), s.textstyle()],
+            );
+        }
+        for s in book.rowstyles.values() {
+            check("row", s.name(), &[s.rowstyle()]);
+        }
+        for s in book.colstyles.values() {
+            check("column", s.name(), &[s.colstyle()]);
+        }
+        for s in book.tablestyles.values() {
+            check("table", s.name(), &[s.tablestyle()]);
+        }
+        for s in book.paragraphstyles.values() {
+            check("paragraph", s.name(), &[s.paragraphstyle(), s.textstyle()]);
+        }
+        for s in book.textstyles.values() {
+            check("text", s.name(), &[s.textstyle()]);
+        }
+        for s in book.graphicstyles.values() {
+            check(
+                "graphic",
+                s.name(),
+                &[s.graphicstyle(), s.paragraphstyle(), s.textstyle()],
+            );
+        }
+        for s in book.pagestyles.values() {
+            check(
+                "page",
+                s.name(),
+                &[s.style(), s.headerstyle().style(), s.footerstyle().style()],
+            );
+        }
+
+        mixed.sort();
+        for msg in mixed {
+            self.push(LintRule::MixedUnits, None, msg);
+        }
+    }
+
+    fn huge_repeat(&mut self, sheet: &Sheet) {
+        let max_repeat = self.options.max_repeat;
+        for (row, rh) in &sheet.row_header {
+            if rh.repeat > max_repeat {
+                self.push(
+                    LintRule::HugeRepeat,
+                    Some(CellRef::remote(sheet.name(), *row, 0)),
+                    format!("row repeated {} times", rh.repeat),
+                );
+            }
+        }
+        for ((row, col), cell) in &sheet.data {
+            if cell.repeat > max_repeat {
+                self.push(
+                    LintRule::HugeRepeat,
+                    Some(CellRef::remote(sheet.name(), row, col)),
+                    format!("cell repeated {} times", cell.repeat),
+                );
+            }
+        }
+    }
+
+    fn date_without_format(&mut self, book: &WorkBook, sheet: &Sheet) {
+        for ((row, col), cell) in &sheet.data {
+            if !matches!(cell.value, Value::DateTime(_) | Value::DateTimeTz(_)) {
+                continue;
+            }
+
+            let style: Option<&CellStyleRef> = cell
+                .style
+                .as_ref()
+                .or_else(|| sheet.row_cellstyle(row))
+                .or_else(|| sheet.col_cellstyle(col))
+                .or_else(|| book.def_style(ValueType::DateTime));
+            let message = match style {
+                None => Some("date without a cell style".to_string()),
+                Some(style) => match book.cellstyles.get(style) {
+                    Some(s) if s.value_format().is_none() => Some(format!(
+                        "date with cell style {} without a value format",
+                        style.as_str()
+                    )),
+                    _ => None,
+                },
+            };
+            if let Some(message) = message {
+                self.push(
+                    LintRule::DateWithoutFormat,
+                    Some(CellRef::remote(sheet.name(), row, col)),
+                    message,
+                );
+            }
+        }
+    }
+
+    fn formula_ref_empty(&mut self, book: &WorkBook, sheet: &Sheet) {
+        for ((row, col), cell) in &sheet.data {
+            let formula = match &cell.formula {
+                Some(v) => v,
+                None => continue,
+            };
+
+            for cell_ref in formula_cellrefs(formula) {
+                if cell_ref.iri().is_some() {
+                    continue;
+                }
+                let ref_sheet = match cell_ref.table() {
+                    Some(table) => match book.sheet_idx(table) {
+                        Some(idx) => book.sheet(idx),
+                        None => continue,
+                    },
+                    None => sheet,
+                };
+                let empty = match ref_sheet.data.get(&(cell_ref.row(), cell_ref.col())) {
+                    Some(v) => v.value == Value::Empty && v.formula.is_none(),
+                    None => true,
+                };
+                if empty {
+                    self.push(
+                        LintRule::FormulaRefEmpty,
+                        Some(CellRef::remote(sheet.name(), row, col)),
+                        format!("formula references the empty cell {}", cell_ref),
+                    );
+                }
+            }
+        }
+    }
+
+    fn mixed_decimal_separator(&mut self, sheet: &Sheet) {
+        let mut found = Vec::new();
+        for ((row, col), cell) in &sheet.data {
+            if let Value::Text(text) = &cell.value {
+                if let Some(sep) = decimal_separator(text) {
+                    found.push((row, col, sep));
+                }
+            }
+        }
+
+        let n_comma = found.iter().filter(|(_, _, sep)| *sep == ',').count();
+        let n_dot = found.len() - n_comma;
+        if n_comma == 0 || n_dot == 0 {
+            return;
+        }
+        let minority = if n_comma > n_dot { '.' } else { ',' };
+        for (row, col, sep) in found {
+            if sep == minority {
+                self.push(
+                    LintRule::MixedDecimalSeparator,
+                    Some(CellRef::remote(sheet.name(), row, col)),
+                    format!(
+                        "number as text uses '{}', most others use '{}'",
+                        minority,
+                        if minority == ',' { '.' } else { ',' }
+                    ),
+                );
+            }
+        }
+    }
+}
+
+// Extracts all single cell references from a formula.
+// Ranges and anything that doesn't parse are ignored.
+fn formula_cellrefs(formula: &str) -> Vec<CellRef> {
+    let mut refs = Vec::new();
+    let mut rest = formula;
+    while let Some(start) = rest.find('[') {
+        rest = &rest[start + 1..];
+        if let Some(end) = rest.find(']') {
+            if let Ok(cell_ref) = parse_cellref(&rest[..end]) {
+                refs.push(cell_ref);
+            }
+            rest = &rest[end + 1..];
+        } else {
+            break;
+        }
+    }
+    refs
+}
+
+// Returns the decimal separator if the text looks like a decimal number.
+fn decimal_separator(text: &str) -> Option<char> {
+    let text = text.trim();
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let pos = text.find(['.', ','])?;
+    let (int, frac) = (&text[..pos], &text[pos + 1..]);
+    if !int.is_empty()
+        && !frac.is_empty()
+        && int.chars().all(|c| c.is_ascii_digit())
+        && frac.chars().all(|c| c.is_ascii_digit())
+    {
+        text[pos..].chars().next()
+    } else {
+        None
+    }
+}
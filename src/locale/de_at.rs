@@ -1,3 +1,7 @@
+//!
+//! Formats for de_AT. Dates as 31.12.2024, currency as € 1.234,56.
+//!
+
 use crate::defaultstyles::DefaultFormat;
 use crate::format::FormatNumberStyle;
 use crate::locale::LocalizedValueFormat;
@@ -9,8 +13,6 @@ use icu_locid::{locale, Locale};
 
 pub(crate) struct LocaleDeAt {}
 
-pub(crate) static LOCALE_DE_AT: LocaleDeAt = LocaleDeAt {};
-
 impl LocaleDeAt {
     const LOCALE: Locale = locale!("de_AT");
 }
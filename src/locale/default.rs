@@ -1,3 +1,8 @@
+//!
+//! Formats for the locale "en". Dates as 2024-12-31, currency as
+//! $ 1,234.56.
+//!
+
 use crate::defaultstyles::DefaultFormat;
 use crate::format::FormatNumberStyle;
 use crate::locale::LocalizedValueFormat;
@@ -9,8 +14,6 @@ use icu_locid::{locale, Locale};
 
 pub(crate) struct LocaleDefault {}
 
-pub(crate) static LOCALE_DEFAULT: LocaleDefault = LocaleDefault {};
-
 impl LocaleDefault {
     const LOCALE: Locale = locale!("en");
 }
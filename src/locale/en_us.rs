@@ -1,3 +1,8 @@
+//!
+//! Formats for en_US. Dates as 12/31/2024, currency as $ 1,234.56,
+//! time of day with AM/PM.
+//!
+
 use crate::defaultstyles::DefaultFormat;
 use crate::format::FormatNumberStyle;
 use crate::locale::LocalizedValueFormat;
@@ -9,8 +14,6 @@ use icu_locid::{locale, Locale};
 
 pub(crate) struct LocaleEnUs {}
 
-pub(crate) static LOCALE_EN_US: LocaleEnUs = LocaleEnUs {};
-
 impl LocaleEnUs {
     const LOCALE: Locale = locale!("en_US");
 }
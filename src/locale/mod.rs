@@ -1,13 +1,78 @@
 //!
 //! Defines localized versions for all default formats.
 //!
+//! Applications can register their own locales. The formats must use
+//! the names from DefaultFormat, that's how the default styles find them.
+//!
+//! ```
+//! use icu_locid::{locale, Locale};
+//! use spreadsheet_ods::defaultstyles::DefaultFormat;
+//! use spreadsheet_ods::locale::LocalizedValueFormat;
+//! use spreadsheet_ods::{
+//!     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
+//!     ValueFormatPercentage, ValueFormatTimeDuration, WorkBook,
+//! };
+//!
+//! struct LocaleDeCh;
+//!
+//! impl LocalizedValueFormat for LocaleDeCh {
+//!     fn locale(&self) -> Locale {
+//!         locale!("de_CH")
+//!     }
+//!     fn boolean_format(&self) -> ValueFormatBoolean {
+//!         let mut v = ValueFormatBoolean::new_localized(DefaultFormat::bool(), self.locale());
+//!         v.part_boolean().build();
+//!         v
+//!     }
+//!     fn number_format(&self) -> ValueFormatNumber {
+//!         let mut v = ValueFormatNumber::new_localized(DefaultFormat::number(), self.locale());
+//!         v.part_number().min_integer_digits(1).decimal_places(2).build();
+//!         v
+//!     }
+//!     // ...
+//! #    fn percentage_format(&self) -> ValueFormatPercentage {
+//! #        ValueFormatPercentage::new_localized(DefaultFormat::percent(), self.locale())
+//! #    }
+//! #    fn currency_format(&self) -> ValueFormatCurrency {
+//! #        ValueFormatCurrency::new_localized(DefaultFormat::currency(), self.locale())
+//! #    }
+//! #    fn date_format(&self) -> ValueFormatDateTime {
+//! #        ValueFormatDateTime::new_localized(DefaultFormat::date(), self.locale())
+//! #    }
+//! #    fn datetime_format(&self) -> ValueFormatDateTime {
+//! #        ValueFormatDateTime::new_localized(DefaultFormat::datetime(), self.locale())
+//! #    }
+//! #    fn time_of_day_format(&self) -> ValueFormatDateTime {
+//! #        ValueFormatDateTime::new_localized(DefaultFormat::time_of_day(), self.locale())
+//! #    }
+//! #    fn time_interval_format(&self) -> ValueFormatTimeDuration {
+//! #        ValueFormatTimeDuration::new_localized(DefaultFormat::time_interval(), self.locale())
+//! #    }
+//! }
+//!
+//! spreadsheet_ods::locale::register(LocaleDeCh);
+//!
+//! let wb = WorkBook::new(locale!("de_CH"));
+//! assert!(wb.number_format(DefaultFormat::number().as_str()).is_some());
+//! ```
+//!
 
 mod default;
 
 #[cfg(feature = "locale_de_AT")]
 mod de_at;
+#[cfg(feature = "locale_de_DE")]
+mod de_de;
+#[cfg(feature = "locale_en_GB")]
+mod en_gb;
 #[cfg(feature = "locale_en_US")]
 mod en_us;
+#[cfg(feature = "locale_es_ES")]
+mod es_es;
+#[cfg(feature = "locale_fr_FR")]
+mod fr_fr;
+#[cfg(feature = "locale_ja_JP")]
+mod ja_jp;
 
 use crate::HashMap;
 use crate::{
@@ -16,11 +81,12 @@ use crate::{
 };
 use icu_locid::Locale;
 use lazy_static::lazy_static;
+use std::sync::{Arc, PoisonError, RwLock};
 
 /// Defines functions that generate the standard formats for various
 /// value types.
-#[allow(dead_code)]
-pub(crate) trait LocalizedValueFormat: Sync {
+pub trait LocalizedValueFormat: Send + Sync {
+    /// The locale.
     fn locale(&self) -> Locale;
     /// Default boolean format.
     fn boolean_format(&self) -> ValueFormatBoolean;
@@ -40,25 +106,73 @@ pub(crate) trait LocalizedValueFormat: Sync {
     fn time_interval_format(&self) -> ValueFormatTimeDuration;
 }
 
+type LocaleMap = HashMap<Locale, Arc<dyn LocalizedValueFormat>>;
+
 lazy_static! {
-    static ref LOCALE_DATA: HashMap<Locale, &'static dyn LocalizedValueFormat> = {
+    static ref LOCALE_DATA: RwLock<LocaleMap> = {
         #[allow(unused_mut)]
-        let mut lm: HashMap<Locale, &'static dyn LocalizedValueFormat> = HashMap::new();
+        let mut lm: LocaleMap = HashMap::new();
 
-        lm.insert(icu_locid::locale!("en"), &default::LOCALE_DEFAULT);
+        lm.insert(
+            icu_locid::locale!("en"),
+            Arc::new(default::LocaleDefault {}),
+        );
         #[cfg(feature = "locale_de_AT")]
         {
-            lm.insert(icu_locid::locale!("de_AT"), &de_at::LOCALE_DE_AT);
+            lm.insert(icu_locid::locale!("de_AT"), Arc::new(de_at::LocaleDeAt {}));
+        }
+        #[cfg(feature = "locale_de_DE")]
+        {
+            lm.insert(icu_locid::locale!("de_DE"), Arc::new(de_de::LocaleDeDe {}));
+        }
+        #[cfg(feature = "locale_en_GB")]
+        {
+            lm.insert(icu_locid::locale!("en_GB"), Arc::new(en_gb::LocaleEnGb {}));
         }
         #[cfg(feature = "locale_en_US")]
         {
-            lm.insert(icu_locid::locale!("en_US"), &en_us::LOCALE_EN_US);
+            lm.insert(icu_locid::locale!("en_US"), Arc::new(en_us::LocaleEnUs {}));
         }
-        lm
+        #[cfg(feature = "locale_es_ES")]
+        {
+            lm.insert(icu_locid::locale!("es_ES"), Arc::new(es_es::LocaleEsEs {}));
+        }
+        #[cfg(feature = "locale_fr_FR")]
+        {
+            lm.insert(icu_locid::locale!("fr_FR"), Arc::new(fr_fr::LocaleFrFr {}));
+        }
+        #[cfg(feature = "locale_ja_JP")]
+        {
+            lm.insert(icu_locid::locale!("ja_JP"), Arc::new(ja_jp::LocaleJaJp {}));
+        }
+        RwLock::new(lm)
     };
 }
 
+/// Registers the default formats for a locale. Replaces any existing
+/// formats for the same locale, including the built-in ones.
+pub fn register<L: LocalizedValueFormat + 'static>(format: L) {
+    LOCALE_DATA
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(format.locale(), Arc::new(format));
+}
+
+/// All locales with default formats.
+pub fn locales() -> Vec<Locale> {
+    LOCALE_DATA
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .keys()
+        .cloned()
+        .collect()
+}
+
 /// Returns the localized format or a fallback.
-pub(crate) fn localized_format(locale: Locale) -> Option<&'static dyn LocalizedValueFormat> {
-    LOCALE_DATA.get(&locale).copied()
+pub(crate) fn localized_format(locale: Locale) -> Option<Arc<dyn LocalizedValueFormat>> {
+    LOCALE_DATA
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&locale)
+        .cloned()
 }
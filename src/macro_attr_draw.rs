@@ -218,3 +218,65 @@ macro_rules! draw_mime_type {
         }
     };
 }
+
+macro_rules! draw_fill {
+    ($acc:ident) => {
+        /// No fill.
+        pub fn set_fill_none(&mut self) {
+            self.$acc.set_attr("draw:fill", "none".to_string());
+        }
+
+        /// Fills with a single color.
+        pub fn set_fill_color(&mut self, color: Rgb<u8>) {
+            self.$acc.set_attr("draw:fill", "solid".to_string());
+            self.$acc.set_attr("draw:fill-color", color_string(color));
+        }
+
+        /// Fills with a gradient defined in the workbook.
+        pub fn set_fill_gradient(&mut self, gradient: &GradientRef) {
+            self.$acc.set_attr("draw:fill", "gradient".to_string());
+            self.$acc
+                .set_attr("draw:fill-gradient-name", gradient.as_str());
+        }
+
+        /// Fills with a hatch defined in the workbook. With solid the
+        /// background is filled with the fill-color too.
+        pub fn set_fill_hatch(&mut self, hatch: &HatchRef, solid: bool) {
+            self.$acc.set_attr("draw:fill", "hatch".to_string());
+            self.$acc.set_attr("draw:fill-hatch-name", hatch.as_str());
+            self.$acc
+                .set_attr("draw:fill-hatch-solid", solid.to_string());
+        }
+
+        /// Fills with an image defined in the workbook.
+        pub fn set_fill_image(&mut self, image: &FillImageRef) {
+            self.$acc.set_attr("draw:fill", "bitmap".to_string());
+            self.$acc.set_attr("draw:fill-image-name", image.as_str());
+        }
+
+        /// The draw:fill attribute. One of none, solid, gradient, hatch,
+        /// bitmap.
+        pub fn fill(&self) -> Option<&str> {
+            self.$acc.attr("draw:fill")
+        }
+    };
+}
+
+// Attributes common to all shapes.
+macro_rules! draw_shape_attr {
+    ($acc:ident) => {
+        draw_name!($acc);
+        draw_class_names!($acc);
+        draw_id!($acc);
+        draw_layer!($acc);
+        draw_style_name!($acc);
+        draw_text_style_name!($acc);
+        draw_transform!($acc);
+        draw_z_index!($acc);
+        table_end_cell_address!($acc);
+        table_end_x!($acc);
+        table_end_y!($acc);
+        table_table_background!($acc);
+        xml_id!($acc);
+    };
+}
@@ -483,6 +483,16 @@ macro_rules! style_line_height_at_least {
             self.$acc
                 .set_attr("style:line-height-at-least", height.to_string());
         }
+
+        /// Same as set_line_height_at_least(), but fails for a negative line height
+        /// instead of panicking.
+        pub fn try_set_line_height_at_least(&mut self, height: Length) -> Result<(), OdsError> {
+            if !height.is_positive() {
+                return Err(OdsError::Ods(format!("Negative line height {}", height)));
+            }
+            self.set_line_height_at_least(height);
+            Ok(())
+        }
     };
 }
 
@@ -595,6 +605,16 @@ macro_rules! style_tab_stop_distance {
             self.$acc
                 .set_attr("style:tab-stop-distance", tab.to_string());
         }
+
+        /// Same as set_tab_stop_distance(), but fails for a negative tab stop distance
+        /// instead of panicking.
+        pub fn try_set_tab_stop_distance(&mut self, tab: Length) -> Result<(), OdsError> {
+            if !tab.is_positive() {
+                return Err(OdsError::Ods(format!("Negative tab stop distance {}", tab)));
+            }
+            self.set_tab_stop_distance(tab);
+            Ok(())
+        }
     };
 }
 
@@ -782,6 +802,16 @@ macro_rules! style_font_size_asian {
             self.$acc
                 .set_attr("style:font-size-asian", size.to_string());
         }
+
+        /// Same as set_font_size_asian(), but fails for a negative font size
+        /// instead of panicking.
+        pub fn try_set_font_size_asian(&mut self, size: FontSize) -> Result<(), OdsError> {
+            if !size.is_positive() {
+                return Err(OdsError::Ods(format!("Negative font size {}", size)));
+            }
+            self.set_font_size_asian(size);
+            Ok(())
+        }
     };
 }
 
@@ -909,6 +939,16 @@ macro_rules! style_font_size_complex {
             self.$acc
                 .set_attr("style:font-size-complex", size.to_string());
         }
+
+        /// Same as set_font_size_complex(), but fails for a negative font size
+        /// instead of panicking.
+        pub fn try_set_font_size_complex(&mut self, size: FontSize) -> Result<(), OdsError> {
+            if !size.is_positive() {
+                return Err(OdsError::Ods(format!("Negative font size {}", size)));
+            }
+            self.set_font_size_complex(size);
+            Ok(())
+        }
     };
 }
 macro_rules! style_font_size_rel_complex {
@@ -1581,6 +1621,26 @@ macro_rules! style_scale_to_pages {
     };
 }
 
+macro_rules! style_scale_to_x {
+    ($acc:ident) => {
+        /// The style:scale-to-X attribute specifies the number of pages in horizontal direction
+        /// a document should be printed on. 0 means no limit. Used together with style:scale-to-Y.
+        pub fn set_scale_to_x(&mut self, pages: u32) {
+            self.$acc.set_attr("style:scale-to-X", pages.to_string());
+        }
+    };
+}
+
+macro_rules! style_scale_to_y {
+    ($acc:ident) => {
+        /// The style:scale-to-Y attribute specifies the number of pages in vertical direction
+        /// a document should be printed on. 0 means no limit. Used together with style:scale-to-X.
+        pub fn set_scale_to_y(&mut self, pages: u32) {
+            self.$acc.set_attr("style:scale-to-Y", pages.to_string());
+        }
+    };
+}
+
 macro_rules! style_table_centering {
     ($acc:ident) => {
         /// The style:table-centering attribute specifies whether tables are centered horizontally
@@ -1610,6 +1670,16 @@ macro_rules! style_min_row_height {
             self.$acc
                 .set_attr("style:min-row-height", min_height.to_string());
         }
+
+        /// Same as set_min_row_height(), but fails for a negative row height
+        /// instead of panicking.
+        pub fn try_set_min_row_height(&mut self, min_height: Length) -> Result<(), OdsError> {
+            if !min_height.is_positive() {
+                return Err(OdsError::Ods(format!("Negative row height {}", min_height)));
+            }
+            self.set_min_row_height(min_height);
+            Ok(())
+        }
     };
 }
 
@@ -61,7 +61,6 @@ macro_rules! svg_width {
     };
 }
 
-#[allow(unused_macros)]
 macro_rules! svg_rx {
     ($acc:ident) => {
         /// See §9.4 of[SVG].
@@ -79,7 +78,6 @@ macro_rules! svg_rx {
     };
 }
 
-#[allow(unused_macros)]
 macro_rules! svg_ry {
     ($acc:ident) => {
         /// See §9.4 of [SVG].
@@ -113,3 +111,39 @@ macro_rules! svg_y {
         }
     };
 }
+
+macro_rules! svg_x1 {
+    ($acc:ident) => {
+        /// See §9.5 of SVG. The x-coordinate of the start point of a line.
+        pub fn svg_x1(&mut self, x: Length) {
+            self.$acc.set_attr("svg:x1", x.to_string());
+        }
+    };
+}
+
+macro_rules! svg_y1 {
+    ($acc:ident) => {
+        /// See §9.5 of SVG. The y-coordinate of the start point of a line.
+        pub fn svg_y1(&mut self, y: Length) {
+            self.$acc.set_attr("svg:y1", y.to_string());
+        }
+    };
+}
+
+macro_rules! svg_x2 {
+    ($acc:ident) => {
+        /// See §9.5 of SVG. The x-coordinate of the end point of a line.
+        pub fn svg_x2(&mut self, x: Length) {
+            self.$acc.set_attr("svg:x2", x.to_string());
+        }
+    };
+}
+
+macro_rules! svg_y2 {
+    ($acc:ident) => {
+        /// See §9.5 of SVG. The y-coordinate of the end point of a line.
+        pub fn svg_y2(&mut self, y: Length) {
+            self.$acc.set_attr("svg:y2", y.to_string());
+        }
+    };
+}
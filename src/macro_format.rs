@@ -68,6 +68,34 @@ macro_rules! valueformat {
                 v
             }
 
+            /// Combines the formats for positive numbers, negative numbers and zero,
+            /// like the format code "#,##0.00;[RED]-#,##0.00;\-".
+            ///
+            /// The format for zero is the one to use, it must have a name and
+            /// references the others via style-maps. The others are renamed to
+            /// "nameP0" and "nameP1". All of them must be added to the workbook.
+            ///
+            /// The negative format gets the absolute value, it must contain the
+            /// minus sign or the parentheses itself. Colors are set for each format
+            /// with set_color().
+            pub fn with_sections(pos: Self, neg: Self, mut zero: Self) -> Vec<Self> {
+                let mut formats = Vec::new();
+                for (idx, (mut format, condition)) in [
+                    (pos, ValueCondition::value_gt(0)),
+                    (neg, ValueCondition::value_lt(0)),
+                ]
+                .into_iter()
+                .enumerate()
+                {
+                    format.set_name(format!("{}P{}", zero.name(), idx));
+                    format.set_volatile(true);
+                    zero.push_stylemap(ValueStyleMap::new(condition, format.name()));
+                    formats.push(format);
+                }
+                formats.push(zero);
+                formats
+            }
+
             number_locale!(attr);
             number_title!(attr);
             number_transliteration_locale!(attr);
@@ -64,54 +64,76 @@ macro_rules! style_ref2 {
 }
 
 /// Generates a name reference for a style.
+///
+/// The name is interned, all references to the same style share one
+/// string and clone cheaply.
 macro_rules! style_ref2_base {
     ($l:ident) => {
         /// Reference
-        #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, PartialEq, Eq)]
         pub struct $l {
-            pub(crate) id: String,
+            pub(crate) id: string_cache::DefaultAtom,
         }
 
         impl GetSize for $l {
             fn get_heap_size(&self) -> usize {
-                self.id.get_heap_size()
+                // Static and inline atoms use no heap. A dynamic atom is
+                // shared, but it is counted for each reference like an Arc.
+                if self.id.is_dynamic() {
+                    self.id.len()
+                } else {
+                    0
+                }
+            }
+        }
+
+        impl std::hash::Hash for $l {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                // must match the hash of the Borrow<str>.
+                std::hash::Hash::hash(self.as_str(), state)
             }
         }
 
         impl From<String> for $l {
             fn from(id: String) -> Self {
-                Self { id }
+                Self {
+                    id: string_cache::DefaultAtom::from(id),
+                }
             }
         }
 
         impl From<&String> for $l {
             fn from(id: &String) -> Self {
-                Self { id: id.clone() }
+                Self {
+                    id: string_cache::DefaultAtom::from(id.as_str()),
+                }
             }
         }
 
         impl From<&str> for $l {
             fn from(id: &str) -> Self {
-                Self { id: id.to_string() }
+                Self {
+                    id: string_cache::DefaultAtom::from(id),
+                }
             }
         }
 
         impl Borrow<str> for $l {
             fn borrow(&self) -> &str {
-                self.id.borrow()
+                &self.id
             }
         }
 
         impl AsRef<str> for $l {
             fn as_ref(&self) -> &str {
-                self.id.as_ref()
+                &self.id
             }
         }
 
         impl $l {
             /// Reference as str.
             pub fn as_str(&self) -> &str {
-                self.id.as_str()
+                &self.id
             }
         }
     };
@@ -1,6 +1,22 @@
 //! The manifest for all files contained in the zip.
 //!
 //! For unprocessed zip entries this also contains the actual bytes.
+//! These are read and written as they are, which allows to attach any
+//! file to the package.
+//!
+//! ```
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//!
+//! let mut book = WorkBook::new_empty();
+//! book.push_sheet(Sheet::new("data"));
+//! book.add_attachment("sidecar/info.json", "application/json", br#"{"rev":1}"#.to_vec())
+//!     .unwrap();
+//!
+//! for file in book.iter_attachments() {
+//!     println!("{} {}", file.full_path, file.media_type);
+//! }
+//! assert_eq!(book.attachment("sidecar/info.json"), Some(br#"{"rev":1}"#.as_slice()));
+//! ```
 
 use get_size::GetSize;
 use get_size_derive::GetSize;
@@ -59,4 +75,19 @@ impl Manifest {
     pub fn is_dir(&self) -> bool {
         self.full_path.ends_with('/')
     }
+
+    /// Paths that are created when writing. They can't be used for
+    /// attachments.
+    pub fn is_reserved(path: &str) -> bool {
+        matches!(
+            path,
+            "/" | "mimetype"
+                | "content.xml"
+                | "styles.xml"
+                | "meta.xml"
+                | "settings.xml"
+                | "manifest.rdf"
+                | "META-INF/manifest.xml"
+        )
+    }
 }
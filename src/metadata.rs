@@ -1,6 +1,13 @@
 //! Document metadata.
 
 use crate::xlink::{XLinkActuate, XLinkShow, XLinkType};
+use crate::HashMap;
+
+// The old names before 0.21, see compat.
+#[allow(deprecated)]
+pub use crate::compat::{
+    AutoReload, DocumentStatistics, HyperlinkBehaviour, Template, UserDefined,
+};
 use chrono::{Duration, NaiveDateTime};
 use get_size::GetSize;
 use get_size_derive::GetSize;
@@ -66,7 +73,15 @@ pub struct Metadata {
     pub document_statistics: MetaDocumentStatistics,
     /// The <meta:user-defined> element specifies any additional user-defined metadata for a
     /// document.
+    ///
+    /// The by-name functions use an index of this list. It is rebuilt when
+    /// the number of fields changes. If you rename a field directly use
+    /// set_user_defined() and remove_user_defined() instead.
     pub user_defined: Vec<MetaUserDefined>,
+    /// Position and count of the user-defined fields by name.
+    /// Only valid as long as the length of user_defined doesn't change.
+    user_defined_idx: HashMap<String, (usize, usize)>,
+    user_defined_len: usize,
 }
 
 impl GetSize for Metadata {
@@ -86,6 +101,129 @@ impl GetSize for Metadata {
             + self.hyperlink_behaviour.get_heap_size()
             + self.document_statistics.get_heap_size()
             + self.user_defined.get_heap_size()
+            + self.user_defined_idx.get_heap_size()
+    }
+}
+
+impl Metadata {
+    // Rebuilds the index of the user-defined fields if user_defined
+    // has been changed directly.
+    pub(crate) fn index_user_defined(&mut self) {
+        if self.user_defined_len == self.user_defined.len() {
+            return;
+        }
+        self.user_defined_idx.clear();
+        for (idx, v) in self.user_defined.iter().enumerate() {
+            self.user_defined_idx
+                .entry(v.name.clone())
+                .and_modify(|(_, count)| *count += 1)
+                .or_insert((idx, 1));
+        }
+        self.user_defined_len = self.user_defined.len();
+    }
+
+    // Position of the first user-defined field with this name.
+    fn user_defined_pos(&self, name: &str) -> Option<usize> {
+        if self.user_defined_len == self.user_defined.len() {
+            match self.user_defined_idx.get(name) {
+                Some((idx, _)) if self.user_defined[*idx].name == name => return Some(*idx),
+                None => return None,
+                // changed in place
+                Some(_) => {}
+            }
+        }
+        self.user_defined.iter().position(|v| v.name == name)
+    }
+
+    /// Sets a user-defined field. Replaces an existing field with the same name.
+    pub fn set_user_defined<S: Into<String>, V: Into<MetaValue>>(&mut self, name: S, value: V) {
+        let name = name.into();
+        let value = value.into();
+        self.index_user_defined();
+        match self.user_defined_idx.get(&name).copied() {
+            Some((idx, count)) if self.user_defined[idx].name == name => {
+                self.user_defined[idx].value = value;
+                if count > 1 {
+                    // drop duplicates
+                    let tail = self.user_defined.split_off(idx + 1);
+                    self.user_defined
+                        .extend(tail.into_iter().filter(|v| v.name != name));
+                    self.user_defined_len = usize::MAX;
+                }
+            }
+            Some(_) => {
+                // changed in place
+                self.user_defined_len = usize::MAX;
+                self.set_user_defined(name, value);
+            }
+            None => {
+                self.user_defined_idx
+                    .insert(name.clone(), (self.user_defined.len(), 1));
+                self.user_defined.push(MetaUserDefined { name, value });
+                self.user_defined_len = self.user_defined.len();
+            }
+        }
+    }
+
+    /// Value of a user-defined field.
+    pub fn user_defined_value(&self, name: &str) -> Option<&MetaValue> {
+        self.user_defined_pos(name)
+            .map(|idx| &self.user_defined[idx].value)
+    }
+
+    /// Value of a user-defined field.
+    pub fn user_defined_value_mut(&mut self, name: &str) -> Option<&mut MetaValue> {
+        self.user_defined_pos(name)
+            .map(|idx| &mut self.user_defined[idx].value)
+    }
+
+    /// Removes a user-defined field.
+    pub fn remove_user_defined(&mut self, name: &str) -> Option<MetaValue> {
+        let idx = self.user_defined_pos(name)?;
+        let v = self.user_defined.remove(idx);
+        self.user_defined.retain(|v| v.name != name);
+        self.user_defined_len = usize::MAX;
+        Some(v.value)
+    }
+
+    /// String value of a user-defined field.
+    pub fn user_defined_str(&self, name: &str) -> Option<&str> {
+        match self.user_defined_value(name) {
+            Some(MetaValue::String(v)) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Float value of a user-defined field.
+    pub fn user_defined_f64(&self, name: &str) -> Option<f64> {
+        match self.user_defined_value(name) {
+            Some(MetaValue::Float(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Boolean value of a user-defined field.
+    pub fn user_defined_bool(&self, name: &str) -> Option<bool> {
+        match self.user_defined_value(name) {
+            Some(MetaValue::Boolean(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Datetime value of a user-defined field.
+    pub fn user_defined_datetime(&self, name: &str) -> Option<NaiveDateTime> {
+        match self.user_defined_value(name) {
+            Some(MetaValue::Datetime(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Duration value of a user-defined field.
+    pub fn user_defined_duration(&self, name: &str) -> Option<Duration> {
+        match self.user_defined_value(name) {
+            Some(MetaValue::TimeDuration(v)) => Some(*v),
+            _ => None,
+        }
     }
 }
 
@@ -222,7 +360,7 @@ impl Default for MetaUserDefined {
 }
 
 /// Value for user defined metadata.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MetaValue {
     /// bool
     Boolean(bool),
@@ -247,3 +385,39 @@ impl GetSize for MetaValue {
         }
     }
 }
+
+impl From<bool> for MetaValue {
+    fn from(v: bool) -> Self {
+        MetaValue::Boolean(v)
+    }
+}
+
+impl From<NaiveDateTime> for MetaValue {
+    fn from(v: NaiveDateTime) -> Self {
+        MetaValue::Datetime(v)
+    }
+}
+
+impl From<f64> for MetaValue {
+    fn from(v: f64) -> Self {
+        MetaValue::Float(v)
+    }
+}
+
+impl From<Duration> for MetaValue {
+    fn from(v: Duration) -> Self {
+        MetaValue::TimeDuration(v)
+    }
+}
+
+impl From<String> for MetaValue {
+    fn from(v: String) -> Self {
+        MetaValue::String(v)
+    }
+}
+
+impl From<&str> for MetaValue {
+    fn from(v: &str) -> Self {
+        MetaValue::String(v.to_string())
+    }
+}
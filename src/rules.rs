@@ -0,0 +1,142 @@
+//!
+//! Declarative styling rules for the columns of a sheet.
+//!
+//! ```
+//! use spreadsheet_ods::rules::SheetRules;
+//! use spreadsheet_ods::{CellStyleRef, Sheet, ValueType};
+//!
+//! let date_style = CellStyleRef::from("date");
+//! let num_style = CellStyleRef::from("right");
+//!
+//! let rules = SheetRules::new()
+//!     .header_contains("date", &date_style)
+//!     .value_type(ValueType::Number, &num_style);
+//!
+//! let mut sheet = Sheet::new("one");
+//! sheet.set_value(0, 0, "Order date");
+//! sheet.set_value(0, 1, "Amount");
+//! sheet.set_value(1, 1, 42);
+//! sheet.apply_rules(&rules);
+//!
+//! assert_eq!(sheet.cellstyle(1, 1), Some(&num_style));
+//! ```
+//!
+
+use crate::{CellStyleRef, Value, ValueType};
+
+/// Selects the columns a rule applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnMatch {
+    /// The header contains the text, ignoring case.
+    HeaderContains(String),
+    /// The header is exactly this text.
+    HeaderEquals(String),
+    /// All non-empty cells below the header have this value type.
+    ValueType(ValueType),
+    /// All non-empty cells below the header are numbers, percentages
+    /// or currencies.
+    Numeric,
+    /// The column with this index.
+    Col(u32),
+}
+
+impl ColumnMatch {
+    /// Does the column match? The header is the text of the header cell,
+    /// values are all non-empty values below the header.
+    pub(crate) fn matches(&self, col: u32, header: &str, values: &[&Value]) -> bool {
+        match self {
+            ColumnMatch::HeaderContains(v) => header.to_lowercase().contains(&v.to_lowercase()),
+            ColumnMatch::HeaderEquals(v) => header == v,
+            ColumnMatch::ValueType(t) => {
+                !values.is_empty() && values.iter().all(|v| v.value_type() == *t)
+            }
+            ColumnMatch::Numeric => {
+                !values.is_empty()
+                    && values.iter().all(|v| {
+                        matches!(
+                            v.value_type(),
+                            ValueType::Number | ValueType::Percentage | ValueType::Currency
+                        )
+                    })
+            }
+            ColumnMatch::Col(c) => col == *c,
+        }
+    }
+}
+
+/// A set of rules that assign cell styles to whole columns.
+///
+/// The first rule that matches a column wins. The style is set for all
+/// non-empty cells below the header row, existing styles are replaced.
+#[derive(Debug, Clone)]
+pub struct SheetRules {
+    header_row: Option<u32>,
+    rules: Vec<(ColumnMatch, CellStyleRef)>,
+}
+
+impl Default for SheetRules {
+    fn default() -> Self {
+        Self {
+            header_row: Some(0),
+            rules: Default::default(),
+        }
+    }
+}
+
+impl SheetRules {
+    /// New, empty. The header is in the first row.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Row that contains the headers.
+    pub fn header_row(mut self, row: u32) -> Self {
+        self.header_row = Some(row);
+        self
+    }
+
+    /// There is no header row. Rules matching the header never apply.
+    pub fn no_header(mut self) -> Self {
+        self.header_row = None;
+        self
+    }
+
+    /// Row that contains the headers.
+    pub fn header(&self) -> Option<u32> {
+        self.header_row
+    }
+
+    /// Adds a rule.
+    pub fn rule(mut self, column: ColumnMatch, style: &CellStyleRef) -> Self {
+        self.rules.push((column, style.clone()));
+        self
+    }
+
+    /// Columns whose header contains the text, ignoring case.
+    pub fn header_contains<S: Into<String>>(self, text: S, style: &CellStyleRef) -> Self {
+        self.rule(ColumnMatch::HeaderContains(text.into()), style)
+    }
+
+    /// Columns whose values all have this value type.
+    pub fn value_type(self, value_type: ValueType, style: &CellStyleRef) -> Self {
+        self.rule(ColumnMatch::ValueType(value_type), style)
+    }
+
+    /// Columns that contain only numeric values.
+    pub fn numeric(self, style: &CellStyleRef) -> Self {
+        self.rule(ColumnMatch::Numeric, style)
+    }
+
+    /// All rules.
+    pub fn rules(&self) -> &[(ColumnMatch, CellStyleRef)] {
+        &self.rules
+    }
+
+    /// Finds the style for a column.
+    pub(crate) fn find(&self, col: u32, header: &str, values: &[&Value]) -> Option<&CellStyleRef> {
+        self.rules
+            .iter()
+            .find(|(m, _)| m.matches(col, header, values))
+            .map(|(_, style)| style)
+    }
+}
@@ -7,16 +7,34 @@ use get_size_derive::GetSize;
 use std::collections::{BTreeMap, Bound};
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::FusedIterator;
-use std::ops::RangeBounds;
+use std::ops::{RangeBounds, RangeInclusive};
 use std::{fmt, mem};
 
-use crate::cell_::{CellContent, CellContentRef, CellData};
-use crate::draw::{Annotation, DrawFrame};
-use crate::style::{ColStyleRef, RowStyleRef, TableStyleRef};
+use crate::attrmap2::AttrMap2;
+use crate::cell_::{CellContent, CellContentRef, CellData, CellDataExt};
+use crate::defaultstyles::{DefaultFormat, DefaultStyle};
+use crate::digest::sheet_digest;
+use crate::draw::{Annotation, DrawFrame, DrawShape};
+use crate::ds::cellmap::{self, CellMap};
+use crate::format::{create_fraction_format, ValueFormatRef};
+use crate::formula::{
+    map_attr_refs, map_refs, map_xml_refs, move_formula_refs, MapRefs, RepeatRow,
+};
+use crate::rules::SheetRules;
+use crate::sparkline::{Sparkline, SparklineGroup, SparklineType};
+use crate::style::units::PrintOrder;
+use crate::style::{
+    col_width_pt, text_row_height, ColStyleRef, MasterPageRef, PageStyle, RowStyleRef, StyleOrigin,
+    StyleUse, TableStyle, TableStyleRef,
+};
 use crate::validation::ValidationRef;
-use crate::value_::Value;
+use crate::value_::{TextPool, Value, ValueType};
 use crate::xmltree::XmlTag;
-use crate::{CellRange, CellStyleRef, Length, OdsError};
+use crate::{
+    CellRange, CellRef, CellStyle, CellStyleRef, ColRange, Length, OdsError, RowRange, WorkBook,
+};
+use chrono::{Duration, NaiveDateTime};
+use color::Rgb;
 
 #[cfg(test)]
 mod tests;
@@ -41,6 +59,19 @@ impl Display for Visibility {
     }
 }
 
+/// How the exports show the cells covered by a merged cell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum MergedCells {
+    /// The merged cell spans the covered cells. Exports that have no
+    /// spans leave the covered cells empty.
+    #[default]
+    Span,
+    /// The content of the merged cell is repeated in all covered cells.
+    Repeat,
+    /// The covered cells are empty.
+    Blank,
+}
+
 /// Row data
 #[derive(Debug, Clone, GetSize)]
 pub(crate) struct RowHeader {
@@ -98,6 +129,55 @@ impl Default for ColHeader {
     }
 }
 
+// LibreOffice doesn't accept these in a sheet name.
+const INVALID_SHEET_NAME_CHARS: &[char] = &['[', ']', '*', '?', ':', '/', '\\'];
+
+// The first invalid character of the name. An apostrophe is only invalid
+// at the start and the end.
+fn invalid_sheet_name_char(name: &str) -> Option<char> {
+    if name.starts_with('\'') || name.ends_with('\'') {
+        Some('\'')
+    } else {
+        name.chars().find(|c| INVALID_SHEET_NAME_CHARS.contains(c))
+    }
+}
+
+/// Replaces all characters that are not allowed in a sheet name with '_'.
+/// These are [ ] * ? : / \ and ' at the start or the end.
+///
+/// ```
+/// use spreadsheet_ods::Sheet;
+/// use spreadsheet_ods::sheet::sanitize_sheet_name;
+///
+/// let name = sanitize_sheet_name("Q1: [draft]");
+/// assert_eq!(name, "Q1_ _draft_");
+/// assert!(Sheet::try_new(name).is_ok());
+///
+/// let name = sanitize_sheet_name("'Tom's'");
+/// assert_eq!(name, "_Tom's_");
+/// ```
+pub fn sanitize_sheet_name<S: AsRef<str>>(name: S) -> String {
+    let name = name.as_ref();
+    let last = name.chars().count().saturating_sub(1);
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if INVALID_SHEET_NAME_CHARS.contains(&c) || (c == '\'' && (i == 0 || i == last)) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+// Name prefix of the named ranges for the editable ranges.
+pub(crate) const EDITABLE_RANGE_PREFIX: &str = "EditableRange_";
+
+// Default size of a sheet in LibreOffice.
+pub(crate) const MAX_ROWS: u32 = 1_048_576;
+pub(crate) const MAX_COLS: u32 = 16_384;
+
 /// One sheet of the spreadsheet.
 ///
 /// Contains the data and the style-references. The can also be
@@ -108,24 +188,40 @@ pub struct Sheet {
     pub(crate) name: String,
     pub(crate) style: Option<TableStyleRef>,
 
-    pub(crate) data: BTreeMap<(u32, u32), CellData>,
+    pub(crate) data: CellMap,
 
     pub(crate) col_header: BTreeMap<u32, ColHeader>,
     pub(crate) row_header: BTreeMap<u32, RowHeader>,
 
     pub(crate) display: bool,
     pub(crate) print: bool,
+    pub(crate) protected: bool,
+    pub(crate) editable_ranges: Vec<(String, CellRange)>,
 
     pub(crate) header_rows: Option<Header>,
     pub(crate) header_cols: Option<Header>,
     pub(crate) print_ranges: Option<Vec<CellRange>>,
+    pub(crate) print_setup: Option<Box<PrintSetup>>,
 
     pub(crate) group_rows: Vec<Grouped>,
     pub(crate) group_cols: Vec<Grouped>,
 
     pub(crate) sheet_config: SheetConfig,
 
+    pub(crate) table_source: Option<Box<TableSource>>,
+
+    pub(crate) scenario: Option<Box<Scenario>>,
+
+    pub(crate) shapes: Vec<DrawShape>,
+
+    pub(crate) sparkline_groups: Vec<SparklineGroup>,
+
     pub(crate) extra: Vec<XmlTag>,
+    // Unknown tags after the columns, with the row they come before.
+    pub(crate) extra_rows: Vec<(u32, XmlTag)>,
+
+    // Shared texts of the workbook, once the sheet is added to one.
+    pub(crate) text_pool: Option<TextPool>,
 }
 
 impl<'a> IntoIterator for &'a Sheet {
@@ -144,13 +240,13 @@ impl<'a> IntoIterator for &'a Sheet {
 /// Iterator over cells.
 #[derive(Debug)]
 pub(crate) struct CellDataIter<'a> {
-    iter: std::collections::btree_map::Range<'a, (u32, u32), CellData>,
-    k_data: Option<&'a (u32, u32)>,
+    iter: cellmap::Range<'a>,
+    k_data: Option<(u32, u32)>,
     v_data: Option<&'a CellData>,
 }
 
 impl<'a> CellDataIter<'a> {
-    pub(crate) fn new(iter: std::collections::btree_map::Range<'a, (u32, u32), CellData>) -> Self {
+    pub(crate) fn new(iter: cellmap::Range<'a>) -> Self {
         Self {
             iter,
             k_data: None,
@@ -161,7 +257,7 @@ impl<'a> CellDataIter<'a> {
     /// Returns the (row,col) of the next cell.
     #[allow(dead_code)]
     pub(crate) fn peek_cell(&mut self) -> Option<(u32, u32)> {
-        self.k_data.copied()
+        self.k_data
     }
 
     fn load_next_data(&mut self) {
@@ -187,7 +283,7 @@ impl<'a> Iterator for CellDataIter<'a> {
 
         if let Some(k_data) = self.k_data {
             if let Some(v_data) = self.v_data {
-                let r = Some((*k_data, v_data));
+                let r = Some((k_data, v_data));
                 self.load_next_data();
                 r
             } else {
@@ -206,15 +302,13 @@ impl<'a> Iterator for CellDataIter<'a> {
 /// Iterator over cells.
 #[derive(Debug)]
 pub(crate) struct CellDataIterMut<'a> {
-    iter: std::collections::btree_map::RangeMut<'a, (u32, u32), CellData>,
-    k_data: Option<&'a (u32, u32)>,
+    iter: cellmap::RangeMut<'a>,
+    k_data: Option<(u32, u32)>,
     v_data: Option<&'a mut CellData>,
 }
 
 impl<'a> CellDataIterMut<'a> {
-    pub(crate) fn new(
-        iter: std::collections::btree_map::RangeMut<'a, (u32, u32), CellData>,
-    ) -> Self {
+    pub(crate) fn new(iter: cellmap::RangeMut<'a>) -> Self {
         Self {
             iter,
             k_data: None,
@@ -224,7 +318,7 @@ impl<'a> CellDataIterMut<'a> {
 
     /// Returns the (row,col) of the next cell.
     pub(crate) fn peek_cell(&mut self) -> Option<(u32, u32)> {
-        self.k_data.copied()
+        self.k_data
     }
 
     fn load_next_data(&mut self) {
@@ -250,7 +344,7 @@ impl<'a> Iterator for CellDataIterMut<'a> {
 
         if let Some(k_data) = self.k_data {
             if let Some(v_data) = self.v_data.take() {
-                let r = Some((*k_data, v_data));
+                let r = Some((k_data, v_data));
                 self.load_next_data();
                 r
             } else {
@@ -269,15 +363,15 @@ impl<'a> Iterator for CellDataIterMut<'a> {
 /// Iterator over cells.
 #[derive(Clone, Debug)]
 pub struct CellIter<'a> {
-    iter: std::collections::btree_map::Iter<'a, (u32, u32), CellData>,
-    k_data: Option<&'a (u32, u32)>,
+    iter: cellmap::Range<'a>,
+    k_data: Option<(u32, u32)>,
     v_data: Option<&'a CellData>,
 }
 
 impl CellIter<'_> {
     /// Returns the (row,col) of the next cell.
     pub fn peek_cell(&mut self) -> Option<(u32, u32)> {
-        self.k_data.copied()
+        self.k_data
     }
 
     fn load_next_data(&mut self) {
@@ -303,7 +397,7 @@ impl<'a> Iterator for CellIter<'a> {
 
         if let Some(k_data) = self.k_data {
             if let Some(v_data) = self.v_data {
-                let r = Some((*k_data, v_data.cell_content_ref()));
+                let r = Some((k_data, v_data.cell_content_ref()));
                 self.load_next_data();
                 r
             } else {
@@ -320,7 +414,7 @@ impl<'a> Iterator for CellIter<'a> {
 }
 
 struct IterRows<'a> {
-    iter: std::collections::btree_map::Range<'a, (u32, u32), CellData>,
+    iter: cellmap::Range<'a>,
     start: (u32, u32),
     end: (u32, u32),
     hint: usize,
@@ -356,8 +450,8 @@ impl<'a> Iterator for IterRows<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             if let Some(((r, c), d)) = self.iter.next() {
-                if *r < self.end.0 && *c >= self.start.1 && *c < self.end.1 {
-                    return Some(((*r, *c), d.cell_content_ref()));
+                if r < self.end.0 && c >= self.start.1 && c < self.end.1 {
+                    return Some(((r, c), d.cell_content_ref()));
                 }
             } else {
                 return None;
@@ -458,7 +552,7 @@ impl<'a> Iterator for IterCols<'a> {
 /// Range iterator.
 #[derive(Clone, Debug)]
 pub struct Range<'a> {
-    range: std::collections::btree_map::Range<'a, (u32, u32), CellData>,
+    range: cellmap::Range<'a>,
 }
 
 impl FusedIterator for Range<'_> {}
@@ -468,7 +562,7 @@ impl<'a> Iterator for Range<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some((k, v)) = self.range.next() {
-            Some((*k, v.cell_content_ref()))
+            Some((k, v.cell_content_ref()))
         } else {
             None
         }
@@ -482,7 +576,7 @@ impl<'a> Iterator for Range<'a> {
 impl DoubleEndedIterator for Range<'_> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if let Some((k, v)) = self.range.next_back() {
-            Some((*k, v.cell_content_ref()))
+            Some((k, v.cell_content_ref()))
         } else {
             None
         }
@@ -491,6 +585,24 @@ impl DoubleEndedIterator for Range<'_> {
 
 impl ExactSizeIterator for Range<'_> {}
 
+/// Iterator over the rows of a CellRange. Each item is the row and the
+/// cells of that row inside the range. Rows without any cells are skipped.
+#[derive(Clone, Debug)]
+pub struct RowsIn<'a> {
+    rows: cellmap::Rows<'a>,
+}
+
+impl FusedIterator for RowsIn<'_> {}
+
+impl<'a> Iterator for RowsIn<'a> {
+    type Item = (u32, Range<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (row, range) = self.rows.next()?;
+        Some((row, Range { range }))
+    }
+}
+
 impl Debug for Sheet {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         writeln!(f, "name {:?} style {:?}", self.name, self.style)?;
@@ -515,9 +627,30 @@ impl Debug for Sheet {
         for v in &self.group_rows {
             writeln!(f, "group rows {:?}", v)?;
         }
+        if let Some(table_source) = &self.table_source {
+            writeln!(f, "table source {:?}", table_source)?;
+        }
+        if let Some(scenario) = &self.scenario {
+            writeln!(f, "scenario {:?}", scenario)?;
+        }
+        if let Some(print_setup) = &self.print_setup {
+            writeln!(f, "print setup {:?}", print_setup)?;
+        }
+        for v in &self.editable_ranges {
+            writeln!(f, "editable range {:?}", v)?;
+        }
+        for v in &self.shapes {
+            writeln!(f, "shape {:?}", v)?;
+        }
+        for v in &self.sparkline_groups {
+            writeln!(f, "sparkline group {:?}", v)?;
+        }
         for xtr in &self.extra {
             writeln!(f, "extras {:?}", xtr)?;
         }
+        for (row, xtr) in &self.extra_rows {
+            writeln!(f, "extras before row {} {:?}", row, xtr)?;
+        }
         Ok(())
     }
 }
@@ -541,20 +674,47 @@ impl Sheet {
     pub fn new<S: Into<String>>(name: S) -> Self {
         Sheet {
             name: name.into(),
-            data: BTreeMap::new(),
+            data: CellMap::new(),
             col_header: Default::default(),
             style: None,
             header_rows: None,
             header_cols: None,
             print_ranges: None,
+            print_setup: None,
             group_rows: Default::default(),
             group_cols: Default::default(),
             sheet_config: Default::default(),
+            table_source: None,
+            scenario: None,
+            shapes: Default::default(),
+            sparkline_groups: Default::default(),
             extra: vec![],
+            extra_rows: vec![],
             row_header: Default::default(),
             display: true,
             print: true,
+            protected: false,
+            editable_ranges: Default::default(),
+            text_pool: None,
+        }
+    }
+
+    /// Create an empty sheet. Fails if the name is empty, contains
+    /// one of the characters [ ] * ? : / \ or starts or ends with '.
+    ///
+    /// See sanitize_sheet_name().
+    pub fn try_new<S: Into<String>>(name: S) -> Result<Self, OdsError> {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(OdsError::Ods("Empty sheet name".to_string()));
+        }
+        if let Some(c) = invalid_sheet_name_char(&name) {
+            return Err(OdsError::Ods(format!(
+                "Invalid character {:?} in sheet name {}",
+                c, name
+            )));
         }
+        Ok(Self::new(name))
     }
 
     /// Copy all the attributes but not the actual data.
@@ -567,14 +727,56 @@ impl Sheet {
             row_header: self.row_header.clone(),
             display: self.display,
             print: self.print,
+            protected: self.protected,
+            editable_ranges: self.editable_ranges.clone(),
             header_rows: self.header_rows,
             header_cols: self.header_cols,
             print_ranges: self.print_ranges.clone(),
+            print_setup: self.print_setup.clone(),
             group_rows: self.group_rows.clone(),
             group_cols: self.group_cols.clone(),
             sheet_config: Default::default(),
+            table_source: self.table_source.clone(),
+            scenario: self.scenario.clone(),
+            shapes: self.shapes.clone(),
+            sparkline_groups: self.sparkline_groups.clone(),
             extra: self.extra.clone(),
+            extra_rows: self.extra_rows.clone(),
+            text_pool: self.text_pool.clone(),
+        }
+    }
+
+    /// Copy the structure and the cell styles, but no content.
+    ///
+    /// Keeps the sheet configuration, cell styles, spans and validations.
+    /// Values, formulas, annotations and drawings are removed.
+    pub fn extract_skeleton(&self) -> Self {
+        let mut sheet = self.clone_no_data();
+        sheet.sheet_config = self.sheet_config.clone();
+        sheet.shapes.clear();
+        sheet.sparkline_groups.clear();
+        for (pos, cell) in &self.data {
+            let extra = cell.extra.as_ref().map(|extra| {
+                Box::new(CellDataExt {
+                    validation_name: extra.validation_name.clone(),
+                    span: extra.span,
+                    ..Default::default()
+                })
+            });
+            if cell.style.is_some() || extra.is_some() {
+                sheet.data.insert(
+                    pos,
+                    CellData {
+                        value: Value::Empty,
+                        formula: None,
+                        style: cell.style.clone(),
+                        repeat: cell.repeat,
+                        extra,
+                    },
+                );
+            }
         }
+        sheet
     }
 
     /// Iterate all cells.
@@ -587,6 +789,32 @@ impl Sheet {
         self.data.len()
     }
 
+    /// Estimated memory use of the sheet in bytes, including the cell data.
+    ///
+    /// The cells are stored per row, sorted by column. The unused capacity
+    /// of the rows is included. Spilled rows are not counted.
+    pub fn memory_usage(&self) -> usize {
+        self.get_size()
+    }
+
+    /// Moves the cells to a temporary file to save memory.
+    ///
+    /// The rows are read back as soon as they are accessed and stay in
+    /// memory until the next call to spill(). A large sheet can be
+    /// processed in blocks of rows with rows_in() and a spill() after
+    /// each block. Writing the sheet reads back all rows.
+    ///
+    /// Rows with annotations, draw-frames, spans, validations or
+    /// formatted text stay in memory. Texts are no longer shared with
+    /// other cells after they have been read back. The temporary file is
+    /// removed when the sheet is dropped.
+    ///
+    /// A failure to read back the temporary file panics.
+    pub fn spill(&mut self) -> Result<(), OdsError> {
+        self.data.spill(..)?;
+        Ok(())
+    }
+
     /// Iterate the range row-wise.
     ///
     /// If there is no upper bound this uses used_grid_size(), which
@@ -619,6 +847,30 @@ impl Sheet {
         }
     }
 
+    /// Iterate the cells inside the CellRange in lexical order.
+    ///
+    /// Only the cells of the range are visited, cells left or right of the
+    /// range are skipped via the index. The table name of the range is
+    /// not checked.
+    pub fn cells_in(
+        &self,
+        range: &CellRange,
+    ) -> impl Iterator<Item = ((u32, u32), CellContentRef<'_>)> {
+        self.rows_in(range).flat_map(|(_, cells)| cells)
+    }
+
+    /// Iterate the rows of the CellRange. For each row with any data
+    /// returns the row and an iterator over the cells of this row.
+    ///
+    /// The table name of the range is not checked.
+    pub fn rows_in(&self, range: &CellRange) -> RowsIn<'_> {
+        RowsIn {
+            rows: self
+                .data
+                .rows_in(range.row()..=range.to_row(), range.col()..=range.to_col()),
+        }
+    }
+
     /// Sheet name.
     pub fn set_name<V: Into<String>>(&mut self, name: V) {
         self.name = name.into();
@@ -649,6 +901,33 @@ impl Sheet {
         self.style.as_ref()
     }
 
+    /// Uses the master page for this sheet.
+    ///
+    /// The master page must already be added to the workbook. If it has
+    /// no page style, an empty one is created. The table style of the
+    /// sheet is copied with the new master page, or a new table style is
+    /// created, so other sheets using the same table style are not changed.
+    pub fn set_master_page(&mut self, book: &mut WorkBook, masterpage: &MasterPageRef) {
+        if matches!(book.masterpage(masterpage), Some(mp) if mp.pagestyle().is_none()) {
+            let pagestyle = book.add_pagestyle(PageStyle::new_empty());
+            if let Some(mp) = book.masterpage_mut(masterpage) {
+                mp.set_pagestyle(&pagestyle);
+            }
+        }
+
+        let tablestyle = self.style.as_ref().and_then(|v| book.tablestyle(v));
+        if tablestyle.and_then(|v| v.attrmap().attr("style:master-page-name"))
+            == Some(masterpage.as_str())
+        {
+            return;
+        }
+
+        let mut tablestyle = tablestyle.cloned().unwrap_or_else(TableStyle::new_empty);
+        tablestyle.set_name("");
+        tablestyle.set_master_page(masterpage);
+        self.style = Some(book.add_tablestyle(tablestyle));
+    }
+
     // find the col-header with the correct data.
     pub(crate) fn valid_col_header(&self, col: u32) -> Option<&ColHeader> {
         if let Some((base_col, col_header)) = self.col_header.range(..=col).last() {
@@ -687,7 +966,9 @@ impl Sheet {
                         left_hdr = Some((*base_col, tmp));
                     }
 
-                    split_hdr = Some((col, col_header.clone()));
+                    let mut tmp = col_header.clone();
+                    tmp.span = 1;
+                    split_hdr = Some((col, tmp));
 
                     let right_span = (*base_col + col_header.span) - (col + 1);
                     if right_span > 0 {
@@ -776,6 +1057,58 @@ impl Sheet {
         }
     }
 
+    /// Hides the columns.
+    pub fn hide_cols(&mut self, cols: &ColRange) {
+        self.set_col_range_visible(cols.col(), cols.to_col(), Visibility::Collapsed);
+    }
+
+    /// Shows the columns again.
+    pub fn show_cols(&mut self, cols: &ColRange) {
+        self.set_col_range_visible(cols.col(), cols.to_col(), Visibility::Visible);
+    }
+
+    /// Is the column collapsed or filtered.
+    pub fn is_col_hidden(&self, col: u32) -> bool {
+        self.col_visible(col) != Visibility::Visible
+    }
+
+    // Sets the visibility for the columns from..=to. Splits the col-headers
+    // at the borders and fills the gaps in between.
+    fn set_col_range_visible(&mut self, from: u32, to: u32, visible: Visibility) {
+        assert!(from <= to);
+        self.create_split_col_header(to);
+        self.create_split_col_header(from);
+
+        let mut col = from;
+        loop {
+            let last = if let Some(col_header) = self.col_header.get_mut(&col) {
+                col_header.visible = visible;
+                col + col_header.span - 1
+            } else {
+                let last = self
+                    .col_header
+                    .range(col..)
+                    .next()
+                    .map(|(c, _)| *c - 1)
+                    .unwrap_or(u32::MAX)
+                    .min(to);
+                self.col_header.insert(
+                    col,
+                    ColHeader {
+                        visible,
+                        span: last - col + 1,
+                        ..Default::default()
+                    },
+                );
+                last
+            };
+            if last >= to {
+                break;
+            }
+            col = last + 1;
+        }
+    }
+
     /// unstable internal method
     pub fn _set_col_header_span(&mut self, col: u32, span: u32) {
         self.create_split_col_header(col).span = span
@@ -820,8 +1153,12 @@ impl Sheet {
     // find the row-header with the correct data and do a three-way-split
     // to allow setting a value for a single row.
     // Create the row-header if necessary.
+    //
+    // The repeat count belongs to the last row of the span and stays there.
     #[allow(clippy::comparison_chain)]
     fn create_split_row_header(&mut self, row: u32) -> &mut RowHeader {
+        self.split_row_repeat(row);
+
         let mut cloned = Vec::new();
 
         if let Some((base_row, row_header)) = self.row_header.range_mut(..=row).last() {
@@ -833,29 +1170,29 @@ impl Sheet {
                 //      row
                 //      row +1 .. base_row+span
 
+                // back
+                if *base_row + base_span > row + 1 {
+                    let mut clone = row_header.clone();
+                    clone.span = *base_row + base_span - (row + 1);
+                    cloned.push((row + 1, clone));
+                    row_header.repeat = 1;
+                }
+
                 // front + target
                 if *base_row < row {
                     row_header.span = row - *base_row;
 
                     let mut clone = row_header.clone();
                     clone.span = 1;
+                    clone.repeat = row_header.repeat;
                     cloned.push((row, clone));
+
+                    row_header.repeat = 1;
                 } else if *base_row == row {
                     row_header.span = 1;
                 } else {
                     unreachable!();
                 }
-
-                // back
-                if *base_row + base_span > row {
-                    let mut clone = row_header.clone();
-                    clone.span = *base_row + base_span - (row + 1);
-                    cloned.push((row + 1, clone));
-                } else if *base_row + base_span == row {
-                    // noop
-                } else {
-                    unreachable!();
-                }
             } else {
                 self.row_header.insert(row, RowHeader::default());
             }
@@ -870,6 +1207,76 @@ impl Sheet {
         self.row_header.get_mut(&row).expect("row-header")
     }
 
+    // If the row is covered by a repeated row, the repeat is split in three
+    // parts. The row and the rows after get a copy of the repeated row.
+    fn split_row_repeat(&mut self, row: u32) {
+        let Some((base_row, row_header)) = self.row_header.range(..row).next_back() else {
+            return;
+        };
+        let last_row = *base_row + row_header.span - 1;
+        let end_row = last_row + row_header.repeat - 1;
+        if !(last_row < row && row <= end_row) {
+            return;
+        }
+
+        // the repeated row itself.
+        let mut header = self.create_split_row_header(last_row).clone();
+        header.span = 1;
+        self.row_header
+            .get_mut(&last_row)
+            .expect("row-header")
+            .repeat = row - last_row;
+
+        let cells: Vec<(u32, CellData)> = self
+            .data
+            .range((last_row, 0)..=(last_row, u32::MAX))
+            .map(|((_, col), cell)| (col, cell.clone()))
+            .collect();
+
+        let mut copy = |r: u32, repeat: u32| {
+            let mut header = header.clone();
+            header.repeat = repeat;
+            self.row_header.insert(r, header);
+            for (col, cell) in &cells {
+                self.data.get_or_insert_with((r, *col), || cell.clone());
+            }
+        };
+        copy(row, 1);
+        if row < end_row {
+            copy(row + 1, end_row - row);
+        }
+    }
+
+    // If the cell is covered by a repeated cell, the repeat is split in three
+    // parts like split_row_repeat().
+    fn split_cell_repeat(&mut self, row: u32, col: u32) {
+        self.split_row_repeat(row);
+
+        let Some(((_, base_col), cell)) = self.data.range((row, 0)..(row, col)).next_back() else {
+            return;
+        };
+        let end_col = base_col + cell.repeat.max(1) - 1;
+        if col > end_col {
+            return;
+        }
+
+        let mut cell = cell.clone();
+        cell.repeat = 1;
+        self.data.get_mut(&(row, base_col)).expect("cell").repeat = col - base_col;
+        if col < end_col {
+            let mut back = cell.clone();
+            back.repeat = end_col - col;
+            self.data.get_or_insert_with((row, col + 1), || back);
+        }
+        self.data.get_or_insert_with((row, col), || cell);
+    }
+
+    // Returns the cell for modification. Splits any repeat that covers it.
+    fn create_cell(&mut self, row: u32, col: u32) -> &mut CellData {
+        self.split_cell_repeat(row, col);
+        self.data.get_or_insert_with((row, col), CellData::default)
+    }
+
     /// unstable internal method.
     pub fn _row_header_span(&self, row: u32) -> Option<u32> {
         self.row_header.get(&row).map(|v| v.span)
@@ -934,11 +1341,87 @@ impl Sheet {
         }
     }
 
+    /// Hides the rows.
+    ///
+    /// Repeated rows are split if the range starts or ends within the repeat.
+    pub fn hide_rows(&mut self, rows: &RowRange) {
+        self.set_row_range_visible(rows.row(), rows.to_row(), Visibility::Collapsed);
+    }
+
+    /// Shows the rows again.
+    pub fn show_rows(&mut self, rows: &RowRange) {
+        self.set_row_range_visible(rows.row(), rows.to_row(), Visibility::Visible);
+    }
+
+    /// Is the row collapsed or filtered. This works for rows covered by
+    /// the repeat of another row too.
+    pub fn is_row_hidden(&self, row: u32) -> bool {
+        if let Some((_, row_header, _)) = self.covering_row_header(row) {
+            row_header.visible != Visibility::Visible
+        } else {
+            false
+        }
+    }
+
+    // Finds the row-header that applies to the row, either by its span
+    // or by the repeat of the last row of the span.
+    // Returns the first row of the header and the last row covered.
+    pub(crate) fn covering_row_header(&self, row: u32) -> Option<(u32, &RowHeader, u32)> {
+        let (base_row, row_header) = self.row_header.range(..=row).next_back()?;
+        let last_row = *base_row + row_header.span - 1 + row_header.repeat - 1;
+        if row <= last_row {
+            Some((*base_row, row_header, last_row))
+        } else {
+            None
+        }
+    }
+
+    // Sets the visibility for the rows from..=to. Splits the row-headers
+    // and repeats at the borders and fills the gaps in between.
+    fn set_row_range_visible(&mut self, from: u32, to: u32, visible: Visibility) {
+        assert!(from <= to);
+        self.create_split_row_header(to);
+        self.create_split_row_header(from);
+
+        let mut row = from;
+        loop {
+            let last = if let Some(row_header) = self.row_header.get_mut(&row) {
+                row_header.visible = visible;
+                row + row_header.span - 1 + row_header.repeat - 1
+            } else {
+                let last = self
+                    .row_header
+                    .range(row..)
+                    .next()
+                    .map(|(r, _)| *r - 1)
+                    .unwrap_or(u32::MAX)
+                    .min(to);
+                self.row_header.insert(
+                    row,
+                    RowHeader {
+                        visible,
+                        span: last - row + 1,
+                        ..Default::default()
+                    },
+                );
+                last
+            };
+            if last >= to {
+                break;
+            }
+            row = last + 1;
+        }
+    }
+
     /// Sets the repeat count for this row. Usually this is the last row
     /// with data in a sheet. Setting the repeat count will not change
     /// the row number of following rows. But they will be changed after
     /// writing to an ODS file and reading it again.
     ///
+    /// Setting a cell or a row property in one of the covered rows splits
+    /// the repeat. That row and the rows after it get a copy of the
+    /// repeated row. See row_repeat_origin() and fill_row_range().
+    ///
     /// Panics
     ///
     /// Panics if the repeat is 0.
@@ -947,6 +1430,16 @@ impl Sheet {
         self.create_split_row_header(row).repeat = repeat
     }
 
+    /// Sets the repeat count for this row. Fails if the repeat is 0.
+    /// See set_row_repeat().
+    pub fn try_set_row_repeat(&mut self, row: u32, repeat: u32) -> Result<(), OdsError> {
+        if repeat == 0 {
+            return Err(OdsError::Ods(format!("Row repeat 0 for row {}", row)));
+        }
+        self.set_row_repeat(row, repeat);
+        Ok(())
+    }
+
     /// Returns the repeat count for this row.
     pub fn row_repeat(&self, row: u32) -> u32 {
         if let Some(row_header) = self.valid_row_header(row) {
@@ -956,11 +1449,84 @@ impl Sheet {
         }
     }
 
+    /// Returns the row whose repeat count covers the given row.
+    ///
+    /// A repeated row occupies the following rows in the file.
+    /// Returns None for the repeated row itself.
+    pub fn row_repeat_origin(&self, row: u32) -> Option<u32> {
+        for (base_row, row_header) in self.row_header.range(..=row).rev() {
+            let last_row = *base_row + row_header.span - 1;
+            if row_header.repeat > 1 && last_row < row && last_row + row_header.repeat > row {
+                return Some(last_row);
+            }
+        }
+        None
+    }
+
+    /// Fills the rows with the same cells, without copying the cells
+    /// for each row.
+    ///
+    /// The cells are set in the first row of the range, and this row gets
+    /// a repeat count for the rest. All cells that were in the range
+    /// before are removed. The cells are given as (column, content).
+    pub fn fill_row_range<I>(&mut self, rows: RangeInclusive<u32>, cells: I) -> Result<(), OdsError>
+    where
+        I: IntoIterator<Item = (u32, CellContent)>,
+    {
+        let (from, to) = rows.into_inner();
+        if from > to {
+            return Err(OdsError::Ods(format!(
+                "Invalid row range {}..={}",
+                from, to
+            )));
+        }
+
+        let old: Vec<(u32, u32)> = self
+            .data
+            .range((from, 0)..=(to, u32::MAX))
+            .map(|(k, _)| k)
+            .collect();
+        for k in old {
+            self.data.remove(&k);
+        }
+        for (col, cell) in cells {
+            self.add_cell(from, col, cell);
+        }
+        self.set_row_repeat(from, to - from + 1);
+
+        Ok(())
+    }
+
     /// Sets the row-height.
     pub fn set_row_height(&mut self, row: u32, height: Length) {
         self.create_split_row_header(row).height = height;
     }
 
+    /// Sets the row height to fit the wrapped text of the row, estimated
+    /// with text_row_height(). The cells must use a style that wraps the
+    /// text. Only text values are measured, spanned columns are added to
+    /// the width.
+    pub fn fit_row_height(&mut self, row: u32, font_size: Length) {
+        let mut height = text_row_height("", Length::Default, font_size);
+        for ((_, col), cell) in self.data.range((row, 0)..=(row, u32::MAX)) {
+            if !matches!(cell.value, Value::Text(_) | Value::TextXml(_)) {
+                continue;
+            }
+            let span = cell.extra.as_ref().map(|v| v.span.col_span()).unwrap_or(1);
+            let width = (col..col.saturating_add(span))
+                .map(|c| col_width_pt(self.col_width(c)))
+                .sum::<f64>();
+            let text_height =
+                text_row_height(&cell.value.as_cow_str_or(""), Length::Pt(width), font_size);
+            if let (Length::Pt(h), Length::Pt(max)) = (text_height, height) {
+                if h > max {
+                    height = text_height;
+                }
+            }
+        }
+        self.set_row_height(row, height);
+    }
+
     /// Returns the row-height
     pub fn row_height(&self, row: u32) -> Length {
         if let Some(rh) = self.valid_row_header(row) {
@@ -993,8 +1559,8 @@ impl Sheet {
     /// Returns a tuple of (max(row)+1, max(col)+1)
     pub fn used_grid_size(&self) -> (u32, u32) {
         let max = self.data.keys().fold((0, 0), |mut max, (r, c)| {
-            max.0 = u32::max(max.0, *r);
-            max.1 = u32::max(max.1, *c);
+            max.0 = u32::max(max.0, r);
+            max.1 = u32::max(max.1, c);
             max
         });
 
@@ -1021,6 +1587,48 @@ impl Sheet {
         self.print
     }
 
+    /// Protects the sheet against changes. Only the cells with a cell style
+    /// that sets CellProtect::None or the editable ranges can be edited.
+    ///
+    /// There is no password, the protection can be removed by the user.
+    pub fn set_protected(&mut self, protected: bool) {
+        self.protected = protected;
+    }
+
+    /// Is the sheet protected?
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
+
+    /// Adds a range that can be edited when the sheet is protected.
+    /// Replaces an existing range with the same name.
+    ///
+    /// When writing, the cells of the range get a cell style that is not
+    /// protected. This is a copy of the cell style in use named
+    /// "style_editable", or "editable" if there is none. The sheet itself
+    /// is not changed. The ranges are stored as named ranges of the sheet
+    /// with the prefix "EditableRange_", and are read back with the table
+    /// name and as absolute ranges.
+    pub fn add_editable_range<S: Into<String>>(&mut self, name: S, range: CellRange) {
+        let name = name.into();
+        if let Some(v) = self.editable_ranges.iter_mut().find(|(n, _)| *n == name) {
+            v.1 = range;
+        } else {
+            self.editable_ranges.push((name, range));
+        }
+    }
+
+    /// Removes an editable range.
+    pub fn remove_editable_range(&mut self, name: &str) -> Option<CellRange> {
+        let idx = self.editable_ranges.iter().position(|(n, _)| n == name)?;
+        Some(self.editable_ranges.remove(idx).1)
+    }
+
+    /// Returns the editable ranges.
+    pub fn editable_ranges(&self) -> &Vec<(String, CellRange)> {
+        &self.editable_ranges
+    }
+
     /// Returns true if there is no SCell at the given position.
     pub fn is_empty(&self, row: u32, col: u32) -> bool {
         !self.data.contains_key(&(row, col))
@@ -1034,22 +1642,116 @@ impl Sheet {
     }
 
     /// Returns references to the cell data.
+    ///
+    /// Only the cell that is stored at this position is returned. Positions
+    /// covered by the repeat count of a cell or a row return None, see
+    /// cell_or_repeated().
     pub fn cell_ref(&self, row: u32, col: u32) -> Option<CellContentRef<'_>> {
         self.data.get(&(row, col)).map(CellData::cell_content_ref)
     }
 
-    /// Consumes the CellContent and sets the values.
+    /// Returns references to the cell data, resolving repeat counts as
+    /// a spreadsheet application would.
+    ///
+    /// If the position is covered by the repeat count of a cell or of a
+    /// row, the repeated cell is returned. The result contains the position
+    /// where the cell is actually stored, which is the position itself
+    /// for any cell without repeat.
+    ///
+    /// ```
+    /// use spreadsheet_ods::Sheet;
+    ///
+    /// let mut sheet = Sheet::new("one");
+    /// sheet.set_value(0, 0, "repeated");
+    /// sheet.set_cell_repeat(0, 0, 3);
+    /// sheet.set_row_repeat(0, 2);
+    ///
+    /// assert!(sheet.cell_ref(1, 2).is_none());
+    /// let (pos, cell) = sheet.cell_or_repeated(1, 2).expect("cell");
+    /// assert_eq!(pos, (0, 0));
+    /// assert_eq!(cell.value().as_str_or(""), "repeated");
+    /// ```
+    pub fn cell_or_repeated(&self, row: u32, col: u32) -> Option<((u32, u32), CellContentRef<'_>)> {
+        let (row, (col, cell)) = if let Some(cell) = self.repeated_cell(row, col) {
+            (row, cell)
+        } else {
+            let origin = self.row_repeat_origin(row)?;
+            (origin, self.repeated_cell(origin, col)?)
+        };
+        Some(((row, col), cell.cell_content_ref()))
+    }
+
+    // Cell at this position or the cell whose repeat count covers it.
+    fn repeated_cell(&self, row: u32, col: u32) -> Option<(u32, &CellData)> {
+        let ((_, base_col), cell) = self.data.range((row, 0)..=(row, col)).next_back()?;
+        if col - base_col < cell.repeat.max(1) {
+            Some((base_col, cell))
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the CellContent and sets the values. Replaces an
+    /// existing cell.
+    ///
+    /// Together with cell() this copies a cell complete with style,
+    /// formula, span, annotation etc., even between sheets.
     pub fn add_cell(&mut self, row: u32, col: u32, cell: CellContent) {
-        self.add_cell_data(row, col, cell.into_celldata());
+        self.split_cell_repeat(row, col);
+        let mut cell = cell.into_celldata();
+        cell.value = self.share_text(cell.value);
+        self.add_cell_data(row, col, cell);
     }
 
     /// Removes the cell and returns the values as CellContent.
+    ///
+    /// The annotation, validation, draw frames and spans go with the cell
+    /// and are part of the result. Removing the top-left cell of a merged
+    /// range or a matrix removes the merge.
+    ///
+    /// Removing a cell covered by a merged range shrinks the merge, so
+    /// that it ends above or left of the cell, whichever keeps more of it.
+    /// A cell covered by a repeated cell is split from the repeat first.
     pub fn remove_cell(&mut self, row: u32, col: u32) -> Option<CellContent> {
+        self.split_cell_repeat(row, col);
+        self.shrink_covering_span(row, col);
         self.data
             .remove(&(row, col))
             .map(CellData::into_cell_content)
     }
 
+    // Shrinks the merged range that covers the cell, but doesn't start
+    // there, so that it no longer contains the cell.
+    fn shrink_covering_span(&mut self, row: u32, col: u32) {
+        let Some((o_row, o_col)) = self.merged_origin(row, col) else {
+            return;
+        };
+        if (o_row, o_col) == (row, col) {
+            return;
+        }
+        let Some(CellData { extra: Some(c), .. }) = self.data.get_mut(&(o_row, o_col)) else {
+            return;
+        };
+        let above = u64::from(row - o_row) * u64::from(c.span.col_span());
+        let left = u64::from(c.span.row_span()) * u64::from(col - o_col);
+        if above >= left {
+            c.span.set_row_span(row - o_row);
+        } else {
+            c.span.set_col_span(col - o_col);
+        }
+    }
+
+    /// Keeps only the cells for which the predicate returns true.
+    /// All other cells are removed in one pass, together with their
+    /// spans, annotations and draw frames.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32, u32, CellContentRef<'_>) -> bool,
+    {
+        self.data
+            .retain(|(row, col), cell| f(*row, *col, cell.cell_content_ref()));
+    }
+
     /// Add a new cell. Main use is for reading the spreadsheet.
     pub(crate) fn add_cell_data(&mut self, row: u32, col: u32, cell: CellData) {
         self.data.insert((row, col), cell);
@@ -1075,18 +1777,120 @@ impl Sheet {
         value: V,
         style: &CellStyleRef,
     ) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.value = value.into();
+        let value = self.share_text(value.into());
+        let cell = self.create_cell(row, col);
+        cell.value = value;
         cell.style = Some(style.clone());
     }
 
     /// Sets a value for the specified cell. Creates a new cell if necessary.
+    ///
+    /// If the sheet is part of a WorkBook, a text is shared with all
+    /// equal texts of the workbook.
     pub fn set_value<V: Into<Value>>(&mut self, row: u32, col: u32, value: V) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.value = value.into();
+        let value = self.share_text(value.into());
+        let cell = self.create_cell(row, col);
+        cell.value = value;
     }
 
-    /// Returns a value
+    // Replaces a text with the shared text of the workbook.
+    fn share_text(&self, mut value: Value) -> Value {
+        if let Some(text_pool) = &self.text_pool {
+            text_pool.intern_value(&mut value);
+        }
+        value
+    }
+
+    /// Sets a percentage, 0.15 is shown as 15%.
+    ///
+    /// Without a cell-style the cell is written with the default style for
+    /// percentages, see WorkBook::def_style().
+    pub fn set_percentage(&mut self, row: u32, col: u32, value: f64) {
+        self.set_value(row, col, Value::Percentage(value));
+    }
+
+    /// Sets the value num/den with the cell-style DefaultStyle::fraction().
+    /// It shows the value as a fraction with up to two digits for the
+    /// denominator.
+    ///
+    /// The style and its format DefaultFormat::fraction() are added to
+    /// the workbook if they don't exist yet. Fails if the denominator is 0.
+    pub fn set_fraction(
+        &mut self,
+        book: &mut WorkBook,
+        row: u32,
+        col: u32,
+        num: i64,
+        den: i64,
+    ) -> Result<(), OdsError> {
+        if den == 0 {
+            return Err(OdsError::Ods(format!("Fraction {}/{}", num, den)));
+        }
+        if book.number_format(DefaultFormat::fraction()).is_none() {
+            book.add_number_format(create_fraction_format(DefaultFormat::fraction(), 2));
+        }
+        if book.cellstyle(DefaultStyle::fraction()).is_none() {
+            book.add_cellstyle(CellStyle::new(
+                DefaultStyle::fraction().as_str(),
+                &DefaultFormat::fraction(),
+            ));
+        }
+        self.set_styled_value(
+            row,
+            col,
+            Value::Number(num as f64 / den as f64),
+            &DefaultStyle::fraction(),
+        );
+        Ok(())
+    }
+
+    /// Sets the values for the column, starting with start_row.
+    ///
+    /// Existing cells keep their style and formula like with set_value().
+    /// New cells are collected and added in one go, which is much faster
+    /// than single inserts for large blocks of data. Values beyond the
+    /// last row are dropped.
+    pub fn set_col_values<V, I>(&mut self, start_row: u32, col: u32, values: I)
+    where
+        V: Into<Value>,
+        I: IntoIterator<Item = V>,
+    {
+        self.set_values_bulk(
+            (start_row..=u32::MAX)
+                .zip(values)
+                .map(|(row, value)| ((row, col), value.into())),
+        );
+    }
+
+    /// Sets the values for the row, starting with start_col.
+    ///
+    /// See set_col_values().
+    pub fn set_row_values<V, I>(&mut self, row: u32, start_col: u32, values: I)
+    where
+        V: Into<Value>,
+        I: IntoIterator<Item = V>,
+    {
+        self.set_values_bulk(
+            (start_col..=u32::MAX)
+                .zip(values)
+                .map(|(col, value)| ((row, col), value.into())),
+        );
+    }
+
+    pub(crate) fn set_values_bulk<I>(&mut self, values: I)
+    where
+        I: Iterator<Item = ((u32, u32), Value)>,
+    {
+        for ((row, col), value) in values {
+            let value = self.share_text(value);
+            self.create_cell(row, col).value = value;
+        }
+    }
+
+    /// Returns a value.
+    ///
+    /// For a cell with a formula this is the last calculated result as
+    /// stored in the file. See OdsOptions::ignore_cached_values().
     pub fn value(&self, row: u32, col: u32) -> &Value {
         if let Some(cell) = self.data.get(&(row, col)) {
             &cell.value
@@ -1097,7 +1901,7 @@ impl Sheet {
 
     /// Sets a formula for the specified cell. Creates a new cell if necessary.
     pub fn set_formula<V: Into<String>>(&mut self, row: u32, col: u32, formula: V) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.formula = Some(formula.into());
     }
 
@@ -1117,28 +1921,677 @@ impl Sheet {
         }
     }
 
-    /// Sets a repeat counter for the cell.
-    pub fn set_cell_repeat(&mut self, row: u32, col: u32, repeat: u32) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.repeat = repeat;
-    }
-
-    /// Returns the repeat counter for the cell within one row.
-    pub fn cell_repeat(&self, row: u32, col: u32) -> u32 {
+    /// Returns the last calculated result of a formula.
+    /// None if the cell has no formula.
+    pub fn cached_value(&self, row: u32, col: u32) -> Option<&Value> {
         if let Some(c) = self.data.get(&(row, col)) {
-            c.repeat
+            c.formula.as_ref().map(|_| &c.value)
         } else {
-            1
+            None
         }
     }
 
-    /// Sets the cell-style for the specified cell. Creates a new cell if necessary.
-    pub fn set_cellstyle(&mut self, row: u32, col: u32, style: &CellStyleRef) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.style = Some(style.clone());
+    /// Removes all formulas and keeps the last calculated values.
+    pub fn strip_formulas(&mut self) {
+        for cell in self.data.values_mut() {
+            if cell.formula.take().is_some() {
+                if let Some(extra) = &mut cell.extra {
+                    extra.matrix_span = Default::default();
+                }
+            }
+        }
     }
 
-    /// Removes the cell-style.
+    /// Removes the calculated values of all cells with a formula.
+    /// The formulas are kept and recalculated when opening the file.
+    pub fn strip_cached_values(&mut self) {
+        for cell in self.data.values_mut() {
+            if cell.formula.is_some() {
+                cell.value = Value::Empty;
+            }
+        }
+    }
+
+    /// Fills the destination range like dragging the fill handle in a
+    /// spreadsheet application.
+    ///
+    /// The destination continues the source range downwards, upwards, to the
+    /// right or to the left and covers the same columns resp. rows. Each
+    /// row/column of the source is continued on its own:
+    ///
+    /// * Numbers, percentages, currencies and dates continue with a constant
+    ///   step. A single number counts by one, a single date by one day.
+    ///   Upwards and to the left this counts down.
+    /// * English weekday and month names continue the week resp. the year.
+    /// * Text ending in a number counts this number.
+    /// * Everything else is repeated.
+    ///
+    /// Formulas are copied with their relative references moved along,
+    /// see formula::move_formula_refs(). Styles and validations are copied
+    /// from the source cells.
+    pub fn autofill(&mut self, src: CellRange, dst: CellRange) -> Result<(), OdsError> {
+        let vertical = src.col() == dst.col() && src.to_col() == dst.to_col();
+        let horizontal = src.row() == dst.row() && src.to_row() == dst.to_row();
+        let forward = if vertical && dst.row() > src.to_row() {
+            true
+        } else if vertical && dst.to_row() < src.row() {
+            false
+        } else if horizontal && dst.col() > src.to_col() {
+            true
+        } else if horizontal && dst.to_col() < src.col() {
+            false
+        } else {
+            return Err(OdsError::Ods(format!(
+                "Autofill range {} does not continue {}",
+                dst, src
+            )));
+        };
+
+        let (lines, mut src_along, mut dst_along) = if vertical {
+            (
+                src.col()..=src.to_col(),
+                (src.row()..=src.to_row()).collect::<Vec<_>>(),
+                (dst.row()..=dst.to_row()).collect::<Vec<_>>(),
+            )
+        } else {
+            (
+                src.row()..=src.to_row(),
+                (src.col()..=src.to_col()).collect::<Vec<_>>(),
+                (dst.col()..=dst.to_col()).collect::<Vec<_>>(),
+            )
+        };
+        // the last source cell is next to the destination.
+        if !forward {
+            src_along.reverse();
+            dst_along.reverse();
+        }
+        let pos = |line: u32, k: u32| if vertical { (k, line) } else { (line, k) };
+
+        for line in lines {
+            let cells: Vec<Option<CellContent>> = src_along
+                .iter()
+                .map(|k| {
+                    let (row, col) = pos(line, *k);
+                    self.cell(row, col)
+                })
+                .collect();
+            let series = fill_series(&cells, forward);
+
+            for (i, k) in dst_along.iter().enumerate() {
+                let (row, col) = pos(line, *k);
+                let (src_row, src_col) = pos(line, src_along[i % src_along.len()]);
+
+                if let Some(cell) = &cells[i % cells.len()] {
+                    let mut new_cell = CellContent {
+                        value: Value::Empty,
+                        style: cell.style.clone(),
+                        formula: None,
+                        repeat: 1,
+                        validation_name: cell.validation_name.clone(),
+                        ..Default::default()
+                    };
+                    if let Some(formula) = &cell.formula {
+                        new_cell.formula = Some(move_formula_refs(
+                            formula,
+                            row as i64 - src_row as i64,
+                            col as i64 - src_col as i64,
+                        ));
+                    } else if let Some(value) = fill_value(&series, i as i32 + 1) {
+                        new_cell.value = value;
+                    } else {
+                        new_cell.value = cell.value.clone();
+                    }
+                    self.add_cell(row, col, new_cell);
+                } else {
+                    self.remove_cell(row, col);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Repeats the row count times, the rows below move down. The formulas
+    // of the copies are moved along, in all references of the sheet the
+    // rows below and ranges that end at the row are adjusted. This
+    // includes print ranges, header rows and row groups. Cell spans that
+    // cover the row are extended, a span that starts at the row stays
+    // with the last copy.
+    pub(crate) fn repeat_row(&mut self, row: u32, count: u32) {
+        if count <= 1 {
+            return;
+        }
+        let n = count - 1;
+
+        self.split_row_repeat(row);
+        if self.valid_row_header(row).is_some() {
+            self.create_split_row_header(row);
+        }
+        for (r, mut rh) in mem::take(&mut self.row_header) {
+            if r < row {
+                self.row_header.insert(r, rh);
+            } else if r == row {
+                let repeat = rh.repeat;
+                rh.repeat = 1;
+                for i in 0..n {
+                    self.row_header.insert(row + i, rh.clone());
+                }
+                rh.repeat = repeat;
+                self.row_header.insert(row + n, rh);
+            } else {
+                self.row_header.insert(r.saturating_add(n), rh);
+            }
+        }
+
+        let name = self.name.clone();
+        self.map_refs(&RepeatRow {
+            sheet: &name,
+            local: true,
+            row,
+            n,
+        });
+        if let Some(header) = self.header_rows.as_mut() {
+            repeat_row_interval(&mut header.from, &mut header.to, row, n);
+        }
+        for group in self.group_rows.iter_mut() {
+            repeat_row_interval(&mut group.from, &mut group.to, row, n);
+        }
+
+        for ((r, c), mut cell) in mem::take(&mut self.data) {
+            if r < row {
+                if let Some(extra) = cell.extra.as_mut() {
+                    for span in [&mut extra.span, &mut extra.matrix_span] {
+                        if r.saturating_add(span.row_span) > row {
+                            span.row_span = span.row_span.saturating_add(n);
+                        }
+                    }
+                }
+                self.data.insert((r, c), cell);
+            } else if r == row {
+                let mut first = cell.clone();
+                if let Some(extra) = first.extra.as_mut() {
+                    extra.span.row_span = extra.span.row_span.min(1);
+                    extra.matrix_span.row_span = extra.matrix_span.row_span.min(1);
+                }
+                for i in 1..count {
+                    let mut copy = if i < n { first.clone() } else { cell.clone() };
+                    if let Some(formula) = &cell.formula {
+                        copy.formula = Some(move_formula_refs(formula, i as i64, 0));
+                    }
+                    self.data.insert((row + i, c), copy);
+                }
+                self.data.insert((r, c), first);
+            } else {
+                self.data.insert((r.saturating_add(n), c), cell);
+            }
+        }
+    }
+
+    // Changes all references of the sheet.
+    pub(crate) fn map_refs(&mut self, m: &dyn MapRefs) {
+        for cell in self.data.values_mut() {
+            if let Some(formula) = &cell.formula {
+                cell.formula = Some(map_refs(formula, m));
+            }
+            if let Some(extra) = cell.extra.as_mut() {
+                for frame in extra.draw_frames.iter_mut() {
+                    map_attr_refs(frame.attrmap_mut(), m);
+                }
+            }
+        }
+        for (_, range) in self.editable_ranges.iter_mut() {
+            m.cellrange(range);
+        }
+        if let Some(print_ranges) = self.print_ranges.as_mut() {
+            for range in print_ranges.iter_mut() {
+                m.cellrange(range);
+            }
+        }
+        for group in self.sparkline_groups.iter_mut() {
+            for sparkline in group.sparklines_mut() {
+                let mut cell = sparkline.cell().clone();
+                if m.cellref(&mut cell) {
+                    sparkline.set_cell(cell);
+                }
+                let mut range = sparkline.data_range().clone();
+                if m.cellrange(&mut range) {
+                    sparkline.set_data_range(range);
+                }
+            }
+        }
+        for shape in self.shapes.iter_mut() {
+            match shape {
+                DrawShape::Frame(v) => map_attr_refs(v.attrmap_mut(), m),
+                DrawShape::Rect(v) => map_attr_refs(v.attrmap_mut(), m),
+                DrawShape::Line(v) => map_attr_refs(v.attrmap_mut(), m),
+                DrawShape::Xml(v) => map_xml_refs(v, m),
+            }
+        }
+        for tag in self.extra.iter_mut() {
+            map_xml_refs(tag, m);
+        }
+        for (_, tag) in self.extra_rows.iter_mut() {
+            map_xml_refs(tag, m);
+        }
+    }
+
+    /// Colors the cells in the range according to their value, like a
+    /// 2- or 3-color scale conditional format that is calculated once.
+    ///
+    /// The cells get a plain cell style with the computed background color.
+    /// Cells with the same color share one style, named "colorscale_rrggbb".
+    /// These styles are returned and must be added to the workbook.
+    ///
+    /// Cells without a numeric value are not changed. Existing cell styles
+    /// of the other cells are replaced.
+    pub fn color_scale(
+        &mut self,
+        range: CellRange,
+        min_color: Rgb<u8>,
+        mid_color: Option<Rgb<u8>>,
+        max_color: Rgb<u8>,
+    ) -> Vec<CellStyle> {
+        let from = (range.row(), range.col());
+        let to = (range.to_row(), range.to_col());
+        let in_range = |col: u32| col >= range.col() && col <= range.to_col();
+
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+        for ((_, col), cell) in self.data.range(from..=to) {
+            if !in_range(col) {
+                continue;
+            }
+            if let Some(v) = cell.value.as_f64_opt() {
+                min = min.min(v);
+                max = max.max(v);
+            }
+        }
+
+        let mut styles = BTreeMap::new();
+        for ((_, col), cell) in self.data.range_mut(from..=to) {
+            if !in_range(col) {
+                continue;
+            }
+            if let Some(v) = cell.value.as_f64_opt() {
+                let t = if max > min {
+                    (v - min) / (max - min)
+                } else {
+                    0.0
+                };
+                let color = match mid_color {
+                    Some(mid_color) if t < 0.5 => mix_color(min_color, mid_color, t * 2.0),
+                    Some(mid_color) => mix_color(mid_color, max_color, (t - 0.5) * 2.0),
+                    None => mix_color(min_color, max_color, t),
+                };
+
+                let name = format!("colorscale_{:02x}{:02x}{:02x}", color.r, color.g, color.b);
+                let style = styles.entry(name).or_insert_with_key(|name| {
+                    let mut style = CellStyle::new_empty();
+                    style.set_name(name);
+                    style.set_background_color(color);
+                    style
+                });
+                cell.style = Some(style.style_ref());
+            }
+        }
+
+        styles.into_values().collect()
+    }
+
+    /// Sets up the columns for a table of values.
+    ///
+    /// Writes the headers to the first row and sets the width and the
+    /// default cell style of each column. A column with a value format
+    /// gets a cell style "schema_{format}" with this format. These styles
+    /// are returned and must be added to the workbook. Otherwise the
+    /// default style for the value type is used, see DefaultStyle.
+    pub fn apply_column_schema(&mut self, columns: &[ColumnSpec]) -> Vec<CellStyle> {
+        let mut styles = BTreeMap::new();
+        for (col, spec) in columns.iter().enumerate() {
+            let col = col as u32;
+            self.set_value(0, col, spec.header.as_str());
+            if spec.width != Length::Default {
+                self.set_col_width(col, spec.width);
+            }
+
+            let style = if let Some(format) = &spec.format {
+                let name = format!("schema_{}", format.as_str());
+                let style = styles
+                    .entry(name)
+                    .or_insert_with_key(|name| CellStyle::new(name, format));
+                Some(style.style_ref())
+            } else {
+                DefaultStyle::for_value_type(spec.value_type)
+            };
+            if let Some(style) = style {
+                self.set_col_cellstyle(col, &style);
+            }
+        }
+
+        styles.into_values().collect()
+    }
+
+    /// Sets the cell styles for whole columns as given by the rules.
+    /// See SheetRules.
+    pub fn apply_rules(&mut self, rules: &SheetRules) {
+        let header_row = rules.header();
+
+        let mut columns: BTreeMap<u32, (&str, Vec<&Value>)> = BTreeMap::new();
+        for ((row, col), cell) in &self.data {
+            let column = columns.entry(col).or_default();
+            match header_row {
+                Some(header_row) if row == header_row => {
+                    column.0 = cell.value.as_str_or("");
+                }
+                Some(header_row) if row < header_row => {}
+                _ => {
+                    if cell.value != Value::Empty {
+                        column.1.push(&cell.value);
+                    }
+                }
+            }
+        }
+
+        let styles: BTreeMap<u32, CellStyleRef> = columns
+            .into_iter()
+            .filter_map(|(col, (header, values))| {
+                rules
+                    .find(col, header, &values)
+                    .map(|style| (col, style.clone()))
+            })
+            .collect();
+
+        for ((row, col), cell) in self.data.iter_mut() {
+            if let Some(header_row) = header_row {
+                if row <= header_row {
+                    continue;
+                }
+            }
+            if cell.value == Value::Empty {
+                continue;
+            }
+            if let Some(style) = styles.get(&col) {
+                cell.style = Some(style.clone());
+            }
+        }
+    }
+
+    /// Sets a repeat counter for the cell.
+    ///
+    /// Setting one of the covered cells splits the repeat, like for rows.
+    pub fn set_cell_repeat(&mut self, row: u32, col: u32, repeat: u32) {
+        let cell = self.create_cell(row, col);
+        cell.repeat = repeat;
+    }
+
+    /// Returns the repeat counter for the cell within one row.
+    pub fn cell_repeat(&self, row: u32, col: u32) -> u32 {
+        if let Some(c) = self.data.get(&(row, col)) {
+            c.repeat
+        } else {
+            1
+        }
+    }
+
+    /// Sets the cell-style for the specified cell. Creates a new cell if necessary.
+    pub fn set_cellstyle(&mut self, row: u32, col: u32, style: &CellStyleRef) {
+        let cell = self.create_cell(row, col);
+        cell.style = Some(style.clone());
+    }
+
+    /// Applies the cell styles of the template to the range.
+    ///
+    /// The first row gets the header style, the last row the total style
+    /// and the first column the first column style, in this order. All other
+    /// rows alternate between the row style and the banded row style.
+    /// Cells without a matching style in the template are not changed.
+    ///
+    /// Existing cell styles are replaced, the template decides the look of
+    /// the whole table. Use stripe_rows() to keep them and layer the stripes
+    /// on top. The range is limited to the size of a sheet in LibreOffice,
+    /// each cell of the range gets its own style.
+    pub fn apply_table_style(&mut self, range: &CellRange, template: &TableTemplate) {
+        let to_row = range.to_row().min(MAX_ROWS - 1);
+        let to_col = range.to_col().min(MAX_COLS - 1);
+
+        for row in range.row()..=to_row {
+            let body_row = (row - range.row()).saturating_sub(u32::from(template.header.is_some()));
+            for col in range.col()..=to_col {
+                let style = if row == range.row() && template.header.is_some() {
+                    template.header.as_ref()
+                } else if row == to_row && template.total.is_some() {
+                    template.total.as_ref()
+                } else if col == range.col() && template.first_col.is_some() {
+                    template.first_col.as_ref()
+                } else if body_row % 2 == 1 && template.banded_rows.is_some() {
+                    template.banded_rows.as_ref()
+                } else {
+                    template.rows.as_ref()
+                };
+                if let Some(style) = style {
+                    self.set_cellstyle(row, col, style);
+                }
+            }
+        }
+    }
+
+    /// Applies the two cell styles to alternating rows of the range,
+    /// starting with style_even for the first row.
+    ///
+    /// Cells that already have a cell style, directly or as default of the
+    /// row or column, don't lose it. They get a combined style, a copy of
+    /// the existing style with the attributes of the stripe style that it
+    /// doesn't set itself. The combined styles are added to the workbook as
+    /// "style_stripe", or "style_stripe2" etc. if a different style already
+    /// has this name, and are reused by later calls. If one of the styles is
+    /// not in the workbook the cell is not changed.
+    ///
+    /// If the range spans whole rows, the stripes are set as the default
+    /// cell style of the rows. Empty cells in columns with a default cell
+    /// style get a repeated cell with the combined style. The range is
+    /// limited to the size of a sheet in LibreOffice.
+    ///
+    /// The sheet can't be part of the workbook at this time, use
+    /// WorkBook::detach_sheet() if necessary.
+    pub fn stripe_rows(
+        &mut self,
+        book: &mut WorkBook,
+        range: &CellRange,
+        style_even: &CellStyleRef,
+        style_odd: &CellStyleRef,
+    ) {
+        let whole_rows = range.col() == 0 && range.to_col() >= MAX_COLS - 1;
+        let to_row = range.to_row().min(MAX_ROWS - 1);
+        let to_col = range.to_col().min(MAX_COLS - 1);
+
+        for row in range.row()..=to_row {
+            let stripe = if (row - range.row()).is_multiple_of(2) {
+                style_even
+            } else {
+                style_odd
+            };
+            let mut stripe_style = |existing: Option<&CellStyleRef>| match existing {
+                None => Some(stripe.clone()),
+                Some(existing) if existing == stripe => None,
+                Some(existing) => combined_style(book, existing, stripe),
+            };
+
+            self.split_row_repeat(row);
+            self.split_row_repeat(row + 1);
+
+            if whole_rows {
+                // cells that don't use the default of the row.
+                let default = self.row_cellstyle(row).cloned();
+                if let Some(default) = &default {
+                    for (_, cell) in self.data.range_mut((row, 0)..=(row, u32::MAX)) {
+                        if cell.style.as_ref() == Some(default) {
+                            cell.style = None;
+                        }
+                    }
+                }
+                let used_cols = self
+                    .col_header
+                    .iter()
+                    .map(|(col, header)| col.saturating_add(header.span))
+                    .chain(
+                        self.data
+                            .range((row, 0)..=(row, u32::MAX))
+                            .next_back()
+                            .map(|((_, col), cell)| col.saturating_add(cell.repeat.max(1))),
+                    )
+                    .max()
+                    .unwrap_or(0)
+                    .min(MAX_COLS);
+                if used_cols > 0 {
+                    self.map_row_cellstyles(row, 0, used_cols - 1, |existing, _| {
+                        if existing.is_none() || existing == default.as_ref() {
+                            None
+                        } else {
+                            stripe_style(existing)
+                        }
+                    });
+                }
+                if let Some(style) = stripe_style(default.as_ref()) {
+                    self.set_row_cellstyle(row, &style);
+                }
+            } else {
+                for col in range.col()..=to_col {
+                    let existing = self
+                        .cellstyle(row, col)
+                        .or_else(|| self.row_cellstyle(row))
+                        .or_else(|| self.col_cellstyle(col));
+                    if let Some(style) = stripe_style(existing) {
+                        self.set_cellstyle(row, col, &style);
+                    }
+                }
+            }
+        }
+    }
+
+    // Maps the cell styles of the range. The function gets the cell style
+    // in effect, the style of the cell or the default of the row or column,
+    // and the value type, and returns the new style or None to keep it.
+    //
+    // Repeated rows inside the range are mapped once. Empty cells are set
+    // as one repeated cell for each run with the same default style, and
+    // the rows below the used area as one repeated row.
+    pub(crate) fn map_range_cellstyles<F>(
+        &mut self,
+        range: &CellRange,
+        to_row: u32,
+        to_col: u32,
+        mut f: F,
+    ) where
+        F: FnMut(Option<&CellStyleRef>, ValueType) -> Option<CellStyleRef>,
+    {
+        let (from_row, from_col) = (range.row(), range.col());
+        if from_row > to_row || from_col > to_col {
+            return;
+        }
+
+        self.split_row_repeat(from_row);
+        if to_row < u32::MAX {
+            self.split_row_repeat(to_row + 1);
+        }
+
+        let used_rows = self
+            .row_header
+            .iter()
+            .map(|(row, header)| row + header.span - 1 + header.repeat)
+            .chain(self.data.keys().next_back().map(|(row, _)| row + 1))
+            .max()
+            .unwrap_or(0);
+
+        let mut row = from_row;
+        while row <= to_row && row < used_rows {
+            let repeat = match self.row_header.range(..=row).next_back() {
+                Some((base_row, header)) if base_row + header.span - 1 == row => header.repeat,
+                _ => 1,
+            };
+            self.map_row_cellstyles(row, from_col, to_col, &mut f);
+            row += repeat.max(1);
+        }
+        if row <= to_row {
+            self.map_row_cellstyles(row, from_col, to_col, &mut f);
+            let has_cells = self.data.range((row, 0)..=(row, u32::MAX)).next().is_some();
+            if has_cells && row < to_row {
+                self.set_row_repeat(row, to_row - row + 1);
+            }
+        }
+    }
+
+    // Maps the cell styles of one row, see map_range_cellstyles().
+    pub(crate) fn map_row_cellstyles<F>(&mut self, row: u32, from_col: u32, to_col: u32, mut f: F)
+    where
+        F: FnMut(Option<&CellStyleRef>, ValueType) -> Option<CellStyleRef>,
+    {
+        // cut repeated cells at the borders.
+        self.split_cell_repeat(row, from_col);
+        if to_col < u32::MAX {
+            self.split_cell_repeat(row, to_col + 1);
+        }
+
+        let cells: Vec<(u32, u32)> = self
+            .data
+            .range((row, from_col)..=(row, to_col))
+            .map(|((_, col), cell)| (col, cell.repeat.max(1)))
+            .collect();
+        let mut col = from_col;
+        for (cell_col, repeat) in cells {
+            if col < cell_col {
+                self.map_empty_cellstyles(row, col, cell_col - 1, &mut f);
+            }
+            let existing = self
+                .cellstyle(row, cell_col)
+                .or_else(|| self.row_cellstyle(row))
+                .or_else(|| self.col_cellstyle(cell_col));
+            let value_type = self.data[&(row, cell_col)].value.value_type();
+            if let Some(style) = f(existing, value_type) {
+                self.data.get_mut(&(row, cell_col)).expect("cell").style = Some(style);
+            }
+            col = cell_col.saturating_add(repeat);
+        }
+        if col <= to_col {
+            self.map_empty_cellstyles(row, col, to_col, &mut f);
+        }
+    }
+
+    // Empty cells of a row, one repeated cell for each column header.
+    fn map_empty_cellstyles<F>(&mut self, row: u32, from_col: u32, to_col: u32, f: &mut F)
+    where
+        F: FnMut(Option<&CellStyleRef>, ValueType) -> Option<CellStyleRef>,
+    {
+        let mut col = from_col;
+        loop {
+            let end = match self.col_header.range(..=col).next_back() {
+                Some((base_col, header)) if base_col + header.span > col => {
+                    base_col + header.span - 1
+                }
+                _ => match self.col_header.range(col + 1..).next() {
+                    Some((next_col, _)) => next_col - 1,
+                    None => to_col,
+                },
+            }
+            .min(to_col);
+            let existing = self.row_cellstyle(row).or_else(|| self.col_cellstyle(col));
+            if let Some(style) = f(existing, ValueType::Empty) {
+                self.data.insert(
+                    (row, col),
+                    CellData {
+                        style: Some(style),
+                        repeat: end - col + 1,
+                        ..Default::default()
+                    },
+                );
+            }
+            if end >= to_col {
+                break;
+            }
+            col = end + 1;
+        }
+    }
+
+    /// Removes the cell-style.
     pub fn clear_cellstyle(&mut self, row: u32, col: u32) {
         if let Some(cell) = self.data.get_mut(&(row, col)) {
             cell.style = None;
@@ -1156,7 +2609,7 @@ impl Sheet {
 
     /// Sets a content-validation for this cell.
     pub fn set_validation(&mut self, row: u32, col: u32, validation: &ValidationRef) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().validation_name = Some(validation.clone());
     }
 
@@ -1178,10 +2631,22 @@ impl Sheet {
 
     /// Sets the rowspan of the cell. Must be greater than 0.
     pub fn set_row_span(&mut self, row: u32, col: u32, span: u32) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().span.set_row_span(span);
     }
 
+    /// Sets the rowspan of the cell. Fails if the span is 0.
+    pub fn try_set_row_span(&mut self, row: u32, col: u32, span: u32) -> Result<(), OdsError> {
+        if span == 0 {
+            return Err(OdsError::Ods(format!(
+                "Row span 0 for cell {}/{}",
+                row, col
+            )));
+        }
+        self.set_row_span(row, col, span);
+        Ok(())
+    }
+
     /// Rowspan of the cell.
     pub fn row_span(&self, row: u32, col: u32) -> u32 {
         if let Some(CellData { extra: Some(c), .. }) = self.data.get(&(row, col)) {
@@ -1194,10 +2659,22 @@ impl Sheet {
     /// Sets the colspan of the cell. Must be greater than 0.
     pub fn set_col_span(&mut self, row: u32, col: u32, span: u32) {
         assert!(span > 0);
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().span.set_col_span(span);
     }
 
+    /// Sets the colspan of the cell. Fails if the span is 0.
+    pub fn try_set_col_span(&mut self, row: u32, col: u32, span: u32) -> Result<(), OdsError> {
+        if span == 0 {
+            return Err(OdsError::Ods(format!(
+                "Col span 0 for cell {}/{}",
+                row, col
+            )));
+        }
+        self.set_col_span(row, col, span);
+        Ok(())
+    }
+
     /// Colspan of the cell.
     pub fn col_span(&self, row: u32, col: u32) -> u32 {
         if let Some(CellData { extra: Some(c), .. }) = self.data.get(&(row, col)) {
@@ -1209,7 +2686,7 @@ impl Sheet {
 
     /// Sets the rowspan of the cell. Must be greater than 0.
     pub fn set_matrix_row_span(&mut self, row: u32, col: u32, span: u32) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().matrix_span.set_row_span(span);
     }
 
@@ -1224,7 +2701,7 @@ impl Sheet {
 
     /// Sets the colspan of the cell. Must be greater than 0.
     pub fn set_matrix_col_span(&mut self, row: u32, col: u32, span: u32) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().matrix_span.set_col_span(span);
     }
 
@@ -1237,9 +2714,45 @@ impl Sheet {
         }
     }
 
+    /// All merged cell ranges, i.e. the cells with a row- or colspan.
+    pub fn merged_ranges(&self) -> Vec<CellRange> {
+        self.data
+            .iter()
+            .filter_map(|((row, col), cell)| match &cell.extra {
+                Some(c) if c.span.row_span() > 1 || c.span.col_span() > 1 => Some(
+                    CellRange::origin_span(row, col, (c.span.row_span(), c.span.col_span())),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the origin of the merged range that contains the cell.
+    /// For the origin itself this returns the cell itself, for cells that
+    /// are not merged None.
+    ///
+    /// This can be used to repeat, blank or span merged content when
+    /// converting the sheet to another format.
+    pub fn merged_origin(&self, row: u32, col: u32) -> Option<(u32, u32)> {
+        self.data
+            .range(..=(row, col))
+            .rev()
+            .find_map(|((o_row, o_col), cell)| match &cell.extra {
+                Some(c)
+                    if o_col <= col
+                        && o_row + c.span.row_span() > row
+                        && o_col + c.span.col_span() > col
+                        && (c.span.row_span() > 1 || c.span.col_span() > 1) =>
+                {
+                    Some((o_row, o_col))
+                }
+                _ => None,
+            })
+    }
+
     /// Sets a annotation for this cell.
     pub fn set_annotation(&mut self, row: u32, col: u32, annotation: Annotation) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().annotation = Some(Box::new(annotation));
     }
 
@@ -1270,7 +2783,7 @@ impl Sheet {
 
     /// Add a drawframe to a specific cell.
     pub fn add_draw_frame(&mut self, row: u32, col: u32, draw_frame: DrawFrame) {
-        let cell = self.data.entry((row, col)).or_default();
+        let cell = self.create_cell(row, col);
         cell.extra_mut().draw_frames.push(draw_frame);
     }
 
@@ -1299,6 +2812,67 @@ impl Sheet {
         }
     }
 
+    /// Shapes in the drawing layer of the sheet (table:shapes).
+    ///
+    /// These shapes are positioned relative to the sheet. The end of a shape
+    /// can be anchored to a cell with table:end-cell-address, table:end-x
+    /// and table:end-y. Frames that belong to one cell are added with
+    /// add_draw_frame().
+    pub fn shapes(&self) -> &Vec<DrawShape> {
+        &self.shapes
+    }
+
+    /// Shapes in the drawing layer of the sheet.
+    pub fn shapes_mut(&mut self) -> &mut Vec<DrawShape> {
+        &mut self.shapes
+    }
+
+    /// Adds a DrawFrame, DrawRect or DrawLine to the drawing layer.
+    pub fn add_shape<S: Into<DrawShape>>(&mut self, shape: S) {
+        self.shapes.push(shape.into());
+    }
+
+    /// Removes all shapes from the drawing layer.
+    pub fn clear_shapes(&mut self) {
+        self.shapes.clear();
+    }
+
+    /// Sparkline groups of the sheet.
+    pub fn sparkline_groups(&self) -> &Vec<SparklineGroup> {
+        &self.sparkline_groups
+    }
+
+    /// Sparkline groups of the sheet.
+    pub fn sparkline_groups_mut(&mut self) -> &mut Vec<SparklineGroup> {
+        &mut self.sparkline_groups
+    }
+
+    /// Adds a group of sparklines.
+    pub fn add_sparkline_group(&mut self, group: SparklineGroup) {
+        self.sparkline_groups.push(group);
+    }
+
+    /// Adds a sparkline for the cell in a new group and returns the group
+    /// to set the colors. A data-range without a table refers to this sheet.
+    pub fn add_sparkline(
+        &mut self,
+        row: u32,
+        col: u32,
+        mut data_range: CellRange,
+        sparkline_type: SparklineType,
+    ) -> &mut SparklineGroup {
+        if data_range.table().is_none() {
+            data_range.set_table(self.name.clone());
+        }
+        let mut group = SparklineGroup::new(sparkline_type);
+        group.push_sparkline(Sparkline::new(
+            CellRef::remote(self.name.clone(), row, col),
+            data_range,
+        ));
+        self.sparkline_groups.push(group);
+        self.sparkline_groups.last_mut().expect("sparkline_group")
+    }
+
     /// Defines a range of rows as header rows.
     /// These rows are repeated when printing on multiple pages.
     pub fn set_header_rows(&mut self, row_start: u32, row_end: u32) {
@@ -1354,6 +2928,27 @@ impl Sheet {
         self.print_ranges.as_ref()
     }
 
+    /// Print setup for this sheet. See PrintSetup.
+    pub fn print_setup(&self) -> Option<&PrintSetup> {
+        self.print_setup.as_deref()
+    }
+
+    /// Print setup for this sheet. Creates an empty one if necessary.
+    pub fn print_setup_mut(&mut self) -> &mut PrintSetup {
+        self.print_setup.get_or_insert_with(Default::default)
+    }
+
+    /// Sets the print setup for this sheet.
+    pub fn set_print_setup(&mut self, print_setup: PrintSetup) {
+        self.print_setup = Some(Box::new(print_setup));
+    }
+
+    /// Removes the print setup. Page styles that have already been
+    /// written for it are not removed.
+    pub fn clear_print_setup(&mut self) -> Option<PrintSetup> {
+        self.print_setup.take().map(|v| *v)
+    }
+
     /// Split horizontally on a cell boundary. The splitting is fixed in
     /// position.
     pub fn split_col_header(&mut self, col: u32) {
@@ -1401,6 +2996,12 @@ impl Sheet {
         self.group_cols.push(grp);
     }
 
+    /// Add a column group. Fails if the group overlaps with an
+    /// existing one, see group_cols().
+    pub fn try_add_col_group(&mut self, from: u32, to: u32) -> Result<(), OdsError> {
+        self.group_cols(from..=to, false)
+    }
+
     /// Remove a column group.
     pub fn remove_col_group(&mut self, from: u32, to: u32) {
         if let Some(idx) = self
@@ -1471,6 +3072,12 @@ impl Sheet {
         self.group_rows.push(grp);
     }
 
+    /// Add a row group. Fails if the group overlaps with an
+    /// existing one, see group_rows().
+    pub fn try_add_row_group(&mut self, from: u32, to: u32) -> Result<(), OdsError> {
+        self.group_rows(from..=to, false)
+    }
+
     /// Remove a row group.
     pub fn remove_row_group(&mut self, from: u32, to: u32) {
         if let Some(idx) = self
@@ -1520,6 +3127,241 @@ impl Sheet {
     pub fn row_group_iter(&self) -> impl Iterator<Item = &Grouped> {
         self.group_rows.iter()
     }
+
+    /// Groups the rows. A collapsed group hides the rows too.
+    ///
+    /// Groups can be nested, but they can't overlap. Returns an error
+    /// if the new group would overlap with an existing one or if the
+    /// same group exists already.
+    pub fn group_rows(
+        &mut self,
+        rows: RangeInclusive<u32>,
+        collapsed: bool,
+    ) -> Result<(), OdsError> {
+        let grp = new_group(&self.group_rows, rows, "row")?;
+        self.group_rows.push(grp);
+        if collapsed {
+            self.set_row_group_displayed(grp.from, grp.to, false);
+        }
+        Ok(())
+    }
+
+    /// Removes the row group and returns it. If the group was collapsed
+    /// the rows are visible again, unless another collapsed group still
+    /// contains them.
+    pub fn ungroup_rows(&mut self, rows: RangeInclusive<u32>) -> Option<Grouped> {
+        let idx = self
+            .group_rows
+            .iter()
+            .position(|v| v.from == *rows.start() && v.to == *rows.end())?;
+        let grp = self.group_rows.remove(idx);
+        for r in grp.from..=grp.to {
+            if self.row_visible(r) == Visibility::Collapsed && !collapsed_by(&self.group_rows, r) {
+                self.set_row_visible(r, Visibility::Visible);
+            }
+        }
+        Some(grp)
+    }
+
+    /// Iterates the row groups together with their outline level,
+    /// starting with 1 for the outermost groups.
+    ///
+    /// The groups are ordered by their start, enclosing groups come first.
+    pub fn row_groups(&self) -> impl Iterator<Item = (u32, &Grouped)> {
+        group_levels(&self.group_rows).into_iter()
+    }
+
+    /// Outline level of the row. 0 if the row is not part of any group.
+    pub fn row_outline_level(&self, row: u32) -> u32 {
+        self.group_rows
+            .iter()
+            .filter(|v| v.from <= row && row <= v.to)
+            .count() as u32
+    }
+
+    /// Groups the columns. A collapsed group hides the columns too.
+    ///
+    /// Groups can be nested, but they can't overlap. Returns an error
+    /// if the new group would overlap with an existing one or if the
+    /// same group exists already.
+    pub fn group_cols(
+        &mut self,
+        cols: RangeInclusive<u32>,
+        collapsed: bool,
+    ) -> Result<(), OdsError> {
+        let grp = new_group(&self.group_cols, cols, "column")?;
+        self.group_cols.push(grp);
+        if collapsed {
+            self.set_col_group_displayed(grp.from, grp.to, false);
+        }
+        Ok(())
+    }
+
+    /// Removes the column group and returns it. If the group was collapsed
+    /// the columns are visible again, unless another collapsed group still
+    /// contains them.
+    pub fn ungroup_cols(&mut self, cols: RangeInclusive<u32>) -> Option<Grouped> {
+        let idx = self
+            .group_cols
+            .iter()
+            .position(|v| v.from == *cols.start() && v.to == *cols.end())?;
+        let grp = self.group_cols.remove(idx);
+        for c in grp.from..=grp.to {
+            if self.col_visible(c) == Visibility::Collapsed && !collapsed_by(&self.group_cols, c) {
+                self.set_col_visible(c, Visibility::Visible);
+            }
+        }
+        Some(grp)
+    }
+
+    /// Iterates the column groups together with their outline level,
+    /// starting with 1 for the outermost groups.
+    ///
+    /// The groups are ordered by their start, enclosing groups come first.
+    pub fn col_groups(&self) -> impl Iterator<Item = (u32, &Grouped)> {
+        group_levels(&self.group_cols).into_iter()
+    }
+
+    /// Outline level of the column. 0 if the column is not part of any group.
+    pub fn col_outline_level(&self, col: u32) -> u32 {
+        self.group_cols
+            .iter()
+            .filter(|v| v.from <= col && col <= v.to)
+            .count() as u32
+    }
+
+    /// Checks that no groups overlap and orders them the way they
+    /// are nested. Groups can be changed via col_group_mut(), so this
+    /// is done before writing.
+    pub(crate) fn validate_groups(&mut self) -> Result<(), OdsError> {
+        for (family, groups) in [
+            ("row", &mut self.group_rows),
+            ("column", &mut self.group_cols),
+        ] {
+            for (i, g0) in groups.iter().enumerate() {
+                if g0.from > g0.to {
+                    return Err(OdsError::Ods(format!(
+                        "Invalid {} group {}..={} in sheet {}",
+                        family, g0.from, g0.to, self.name
+                    )));
+                }
+                for g1 in &groups[i + 1..] {
+                    if g0.from == g1.from && g0.to == g1.to {
+                        return Err(OdsError::Ods(format!(
+                            "Duplicate {} group {}..={} in sheet {}",
+                            family, g0.from, g0.to, self.name
+                        )));
+                    }
+                    if !(g0.contains(g1) || g1.contains(g0) || g0.disjunct(g1)) {
+                        return Err(OdsError::Ods(format!(
+                            "Overlapping {} groups {}..={} and {}..={} in sheet {}",
+                            family, g0.from, g0.to, g1.from, g1.to, self.name
+                        )));
+                    }
+                }
+            }
+            groups.sort_by(|a, b| a.from.cmp(&b.from).then(b.to.cmp(&a.to)));
+        }
+        Ok(())
+    }
+
+    /// The sheet is linked to a table in another document.
+    pub fn table_source(&self) -> Option<&TableSource> {
+        self.table_source.as_deref()
+    }
+
+    /// Links the sheet to a table in another document.
+    ///
+    /// The current content of the sheet is kept as cached copy of the
+    /// linked table. Use refresh_table_source() to replace it.
+    pub fn set_table_source(&mut self, table_source: TableSource) {
+        self.table_source = Some(Box::new(table_source));
+    }
+
+    /// Removes the link. The cached content stays as is.
+    pub fn clear_table_source(&mut self) -> Option<TableSource> {
+        self.table_source.take().map(|v| *v)
+    }
+
+    /// The sheet is a scenario for the preceding sheet.
+    pub fn scenario(&self) -> Option<&Scenario> {
+        self.scenario.as_deref()
+    }
+
+    /// The sheet is a scenario for the preceding sheet.
+    pub fn scenario_mut(&mut self) -> Option<&mut Scenario> {
+        self.scenario.as_deref_mut()
+    }
+
+    /// Makes this sheet a scenario. It must be added directly after
+    /// the sheet it applies to, after any other scenarios for it.
+    pub fn set_scenario(&mut self, scenario: Scenario) {
+        self.scenario = Some(Box::new(scenario));
+    }
+
+    /// Removes the scenario.
+    pub fn clear_scenario(&mut self) -> Option<Scenario> {
+        self.scenario.take().map(|v| *v)
+    }
+
+    /// Hash of the logical content of the sheet. See WorkBook::content_digest.
+    ///
+    /// The workbook is needed to compare the cell styles by value.
+    pub fn content_digest(&self, book: &WorkBook) -> u64 {
+        sheet_digest(book, self)
+    }
+
+    /// Replaces the cached content of a linked sheet with the data of
+    /// the linked document, that has been read by the caller.
+    ///
+    /// The source table is found via TableSource::table_name, or the
+    /// first sheet is used. Cell styles and validations belong to the
+    /// other document and are not copied. With
+    /// TableSourceMode::CopyResultsOnly the formulas are dropped too.
+    pub fn refresh_table_source(&mut self, source: &WorkBook) -> Result<(), OdsError> {
+        let table_source = match &self.table_source {
+            Some(v) => v,
+            None => {
+                return Err(OdsError::Ods(format!(
+                    "Sheet {} has no table source",
+                    self.name
+                )))
+            }
+        };
+        let src = match &table_source.table_name {
+            Some(name) => source.sheet_idx(name).map(|idx| source.sheet(idx)),
+            None => source.iter_sheets().next(),
+        };
+        let src = match src {
+            Some(v) => v,
+            None => {
+                return Err(OdsError::Ods(format!(
+                    "Table {} not found in {}",
+                    table_source.table_name.as_deref().unwrap_or_default(),
+                    table_source.href
+                )))
+            }
+        };
+
+        let copy_formulas = table_source.mode == TableSourceMode::CopyAll;
+        self.data.clear();
+        for (pos, cell) in &src.data {
+            let mut cell = cell.clone();
+            cell.style = None;
+            if let Some(extra) = &mut cell.extra {
+                extra.validation_name = None;
+            }
+            if !copy_formulas {
+                cell.formula = None;
+                if let Some(extra) = &mut cell.extra {
+                    extra.matrix_span = Default::default();
+                }
+            }
+            self.data.insert(pos, cell);
+        }
+
+        Ok(())
+    }
 }
 
 /// Describes header rows/columns.
@@ -1597,6 +3439,318 @@ impl Grouped {
     }
 }
 
+// Checks a new group against the existing ones.
+fn new_group(
+    groups: &[Grouped],
+    range: RangeInclusive<u32>,
+    family: &str,
+) -> Result<Grouped, OdsError> {
+    let (from, to) = range.into_inner();
+    if from > to {
+        return Err(OdsError::Ods(format!(
+            "Invalid {} group {}..={}",
+            family, from, to
+        )));
+    }
+    let grp = Grouped::new(from, to, true);
+    for v in groups {
+        if v.from == from && v.to == to {
+            return Err(OdsError::Ods(format!(
+                "Duplicate {} group {}..={}",
+                family, from, to
+            )));
+        }
+        if !(grp.contains(v) || v.contains(&grp) || grp.disjunct(v)) {
+            return Err(OdsError::Ods(format!(
+                "Overlapping {} groups {}..={} and {}..={}",
+                family, v.from, v.to, from, to
+            )));
+        }
+    }
+    Ok(grp)
+}
+
+// Adjusts an inclusive interval of rows for a repeated row, the same
+// as a range reference.
+fn repeat_row_interval(from: &mut u32, to: &mut u32, row: u32, n: u32) {
+    if *to >= row {
+        if *from > row {
+            *from = from.saturating_add(n);
+        }
+        *to = to.saturating_add(n);
+    }
+}
+
+// Is the row/column hidden by one of the collapsed groups?
+fn collapsed_by(groups: &[Grouped], idx: u32) -> bool {
+    groups
+        .iter()
+        .any(|v| !v.display && v.from <= idx && idx <= v.to)
+}
+
+// Groups with their nesting level, in nesting order.
+fn group_levels(groups: &[Grouped]) -> Vec<(u32, &Grouped)> {
+    let mut sorted: Vec<&Grouped> = groups.iter().collect();
+    sorted.sort_by(|a, b| a.from.cmp(&b.from).then(b.to.cmp(&a.to)));
+    sorted
+        .into_iter()
+        .map(|grp| {
+            let level = groups.iter().filter(|v| v.contains(grp)).count() as u32;
+            (level, grp)
+        })
+        .collect()
+}
+
+/// What is copied from a linked table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, GetSize)]
+pub enum TableSourceMode {
+    /// Values, formulas and formatting.
+    #[default]
+    CopyAll,
+    /// Only the results of formulas.
+    CopyResultsOnly,
+}
+
+impl Display for TableSourceMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TableSourceMode::CopyAll => write!(f, "copy-all"),
+            TableSourceMode::CopyResultsOnly => write!(f, "copy-results-only"),
+        }
+    }
+}
+
+/// Link of a sheet to a table in another document. The content of the
+/// sheet is a cached copy of the linked table.
+///
+/// See table:table-source §9.2.6.
+#[derive(Debug, Clone, Default)]
+pub struct TableSource {
+    /// IRI of the linked document.
+    pub href: String,
+    /// Name of the linked table. If not set, the first table is used.
+    pub table_name: Option<String>,
+    /// Filter that is used to load the linked document, e.g. "calc8".
+    pub filter_name: Option<String>,
+    /// Options for the filter.
+    pub filter_options: Option<String>,
+    /// What is copied.
+    pub mode: TableSourceMode,
+    /// Delay between two automatic refreshes.
+    pub refresh_delay: Option<Duration>,
+}
+
+impl GetSize for TableSource {
+    fn get_heap_size(&self) -> usize {
+        self.href.get_heap_size()
+            + self.table_name.get_heap_size()
+            + self.filter_name.get_heap_size()
+            + self.filter_options.get_heap_size()
+    }
+}
+
+impl TableSource {
+    /// Link to the given document.
+    pub fn new<S: Into<String>>(href: S) -> Self {
+        Self {
+            href: href.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Marks the sheet as a scenario for the ranges of the preceding sheet.
+/// Activating the scenario copies its cells to these ranges.
+///
+/// See table:scenario §9.2.7.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    /// Ranges that are replaced by the scenario.
+    pub ranges: Vec<CellRange>,
+    /// This is the active scenario.
+    pub is_active: bool,
+    /// Shows a border around the ranges.
+    pub display_border: bool,
+    /// Color of the border.
+    pub border_color: Option<Rgb<u8>>,
+    /// Changes in the ranges are copied back to the scenario.
+    pub copy_back: bool,
+    /// Copy the styles too.
+    pub copy_styles: bool,
+    /// Copy formulas, otherwise only the results are copied.
+    pub copy_formulas: bool,
+    /// Comment.
+    pub comment: Option<String>,
+    /// Protects the ranges.
+    pub protected: bool,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            ranges: Default::default(),
+            is_active: false,
+            display_border: true,
+            border_color: None,
+            copy_back: true,
+            copy_styles: true,
+            copy_formulas: true,
+            comment: None,
+            protected: false,
+        }
+    }
+}
+
+impl GetSize for Scenario {
+    fn get_heap_size(&self) -> usize {
+        self.ranges.get_heap_size() + self.comment.get_heap_size()
+    }
+}
+
+impl Scenario {
+    /// Scenario for the ranges.
+    pub fn new(ranges: Vec<CellRange>) -> Self {
+        Self {
+            ranges,
+            ..Default::default()
+        }
+    }
+}
+
+/// Cell styles for formatting a range as table. See Sheet::apply_table_style().
+///
+/// ```
+/// use spreadsheet_ods::sheet::TableTemplate;
+/// use spreadsheet_ods::{CellRange, CellStyle, Sheet, WorkBook};
+///
+/// let mut book = WorkBook::new_empty();
+/// let mut header = CellStyle::new_empty();
+/// header.set_font_bold();
+/// let header = book.add_cellstyle(header);
+/// let mut band = CellStyle::new_empty();
+/// band.set_background_color(spreadsheet_ods::color::Rgb::new(230, 230, 230));
+/// let band = book.add_cellstyle(band);
+///
+/// let mut sheet = Sheet::new("report");
+/// sheet.apply_table_style(
+///     &CellRange::local(0, 0, 10, 3),
+///     &TableTemplate {
+///         header: Some(header),
+///         banded_rows: Some(band),
+///         ..Default::default()
+///     },
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TableTemplate {
+    /// Style for the first row.
+    pub header: Option<CellStyleRef>,
+    /// Style for the last row.
+    pub total: Option<CellStyleRef>,
+    /// Style for the first column.
+    pub first_col: Option<CellStyleRef>,
+    /// Style for the body rows.
+    pub rows: Option<CellStyleRef>,
+    /// Style for every second body row. Uses the row style if not set.
+    pub banded_rows: Option<CellStyleRef>,
+}
+
+/// One column for Sheet::apply_column_schema().
+///
+/// ```
+/// use spreadsheet_ods::sheet::ColumnSpec;
+/// use spreadsheet_ods::{Sheet, ValueType, WorkBook};
+/// use spreadsheet_ods::defaultstyles::DefaultFormat;
+/// use spreadsheet_ods::Length;
+///
+/// let mut wb = WorkBook::new_empty();
+/// let mut sheet = Sheet::new("dump");
+/// let styles = sheet.apply_column_schema(&[
+///     ColumnSpec::new("name", ValueType::Text),
+///     ColumnSpec {
+///         format: Some(DefaultFormat::currency()),
+///         width: Length::Cm(3.0),
+///         ..ColumnSpec::new("amount", ValueType::Currency)
+///     },
+/// ]);
+/// for style in styles {
+///     wb.add_cellstyle(style);
+/// }
+/// sheet.set_value(1, 0, "apples");
+/// sheet.set_value(1, 1, 2.5);
+/// wb.push_sheet(sheet);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColumnSpec {
+    /// Text for the header row.
+    pub header: String,
+    /// Value type of the column. Selects the default style if there
+    /// is no value format.
+    pub value_type: ValueType,
+    /// Value format for the column.
+    pub format: Option<ValueFormatRef>,
+    /// Column width. Length::Default leaves the width as it is.
+    pub width: Length,
+}
+
+impl ColumnSpec {
+    /// Column with a header and a value type.
+    pub fn new<S: Into<String>>(header: S, value_type: ValueType) -> Self {
+        Self {
+            header: header.into(),
+            value_type,
+            format: None,
+            width: Length::Default,
+        }
+    }
+}
+
+/// Print settings for a single sheet.
+///
+/// When writing, the sheet gets its own master page and page layout
+/// with these settings. They are copied from the master page in use,
+/// which is the "Default" master page if the table style doesn't say
+/// otherwise. Unset values are left as they are.
+///
+/// These styles are named "PrintSetup_" plus the sheet name and are
+/// only added to the written file, the workbook is not changed.
+///
+/// ```
+/// use spreadsheet_ods::sheet::PrintSetup;
+/// use spreadsheet_ods::style::units::PrintOrder;
+/// use spreadsheet_ods::{CellRange, Sheet};
+///
+/// let mut sheet = Sheet::new("report");
+/// sheet.set_print_setup(PrintSetup {
+///     range: Some(CellRange::local(0, 0, 99, 9)),
+///     fit_width: Some(1),
+///     page_order: Some(PrintOrder::Ltr),
+///     grid: Some(false),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PrintSetup {
+    /// Replaces the print ranges of the sheet.
+    pub range: Option<CellRange>,
+    /// Scales the print to this many pages wide. 0 is unlimited.
+    pub fit_width: Option<u32>,
+    /// Scales the print to this many pages high. 0 is unlimited.
+    pub fit_height: Option<u32>,
+    /// Top to bottom or left to right.
+    pub page_order: Option<PrintOrder>,
+    /// Print the grid lines.
+    pub grid: Option<bool>,
+    /// Print the column and row headers.
+    pub headers: Option<bool>,
+}
+
+impl GetSize for PrintSetup {
+    fn get_heap_size(&self) -> usize {
+        self.range.get_heap_size()
+    }
+}
+
 /// There are two ways a sheet can be split. There are fixed column/row header
 /// like splits, and there is a moveable split.
 ///
@@ -1701,6 +3855,322 @@ impl Default for SheetConfig {
     }
 }
 
+const FILL_LISTS: [&[&str]; 4] = [
+    &[
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    &[
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    &[
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+];
+
+// How autofill continues the values of one row/column.
+#[derive(Debug)]
+enum FillSeries {
+    // Repeat the source.
+    Copy,
+    // Last value and step.
+    Number(Value, f64, f64),
+    DateTime(NaiveDateTime, Duration),
+    // List, index of the last value, step and upper/lower case.
+    Name(&'static [&'static str], usize, i64, Option<bool>),
+    // Prefix, last number, step and width with leading zeros.
+    Counter(String, i64, i64, usize),
+}
+
+// Finds the series in the source cells. A single value counts up when
+// filling forward and down when filling backward.
+fn fill_series(cells: &[Option<CellContent>], forward: bool) -> FillSeries {
+    let one = if forward { 1 } else { -1 };
+
+    let mut values = Vec::new();
+    for cell in cells {
+        match cell {
+            Some(cell) if cell.formula.is_none() => values.push(&cell.value),
+            _ => return FillSeries::Copy,
+        }
+    }
+    let last = match values.last() {
+        Some(v) => *v,
+        None => return FillSeries::Copy,
+    };
+
+    match last {
+        Value::Number(_) | Value::Percentage(_) | Value::Currency(_, _) => {
+            let mut nums = Vec::new();
+            for v in &values {
+                match v {
+                    Value::Number(v) | Value::Percentage(v) | Value::Currency(v, _) => {
+                        nums.push(*v)
+                    }
+                    _ => return FillSeries::Copy,
+                }
+            }
+            let step = if nums.len() == 1 {
+                one as f64
+            } else {
+                nums[1] - nums[0]
+            };
+            let eps = 1e-9 * step.abs().max(1.0);
+            if nums.windows(2).all(|w| (w[1] - w[0] - step).abs() <= eps) {
+                FillSeries::Number(last.clone(), nums[nums.len() - 1], step)
+            } else {
+                FillSeries::Copy
+            }
+        }
+        Value::DateTime(_) => {
+            let mut dates = Vec::new();
+            for v in &values {
+                match v {
+                    Value::DateTime(v) => dates.push(*v),
+                    _ => return FillSeries::Copy,
+                }
+            }
+            let step = if dates.len() == 1 {
+                Duration::days(one)
+            } else {
+                dates[1] - dates[0]
+            };
+            if dates.windows(2).all(|w| w[1] - w[0] == step) {
+                FillSeries::DateTime(dates[dates.len() - 1], step)
+            } else {
+                FillSeries::Copy
+            }
+        }
+        Value::Text(_) => {
+            let mut texts = Vec::new();
+            for v in &values {
+                match v {
+                    Value::Text(v) => texts.push(v.as_ref()),
+                    _ => return FillSeries::Copy,
+                }
+            }
+            fill_name_series(&texts, one)
+                .or_else(|| fill_counter_series(&texts, one))
+                .unwrap_or(FillSeries::Copy)
+        }
+        _ => FillSeries::Copy,
+    }
+}
+
+// Weekday and month names.
+fn fill_name_series(texts: &[&str], one: i64) -> Option<FillSeries> {
+    for list in FILL_LISTS {
+        let idx: Option<Vec<i64>> = texts
+            .iter()
+            .map(|text| {
+                list.iter()
+                    .position(|v| v.eq_ignore_ascii_case(text))
+                    .map(|v| v as i64)
+            })
+            .collect();
+        let idx = match idx {
+            Some(idx) => idx,
+            None => continue,
+        };
+        let len = list.len() as i64;
+        let step = if idx.len() == 1 {
+            one
+        } else {
+            (idx[1] - idx[0]).rem_euclid(len)
+        };
+        if !idx
+            .windows(2)
+            .all(|w| (w[1] - w[0]).rem_euclid(len) == step)
+        {
+            return None;
+        }
+        let last = texts[texts.len() - 1];
+        let upper = if last == last.to_uppercase() {
+            Some(true)
+        } else if last == last.to_lowercase() {
+            Some(false)
+        } else {
+            None
+        };
+        return Some(FillSeries::Name(
+            list,
+            idx[idx.len() - 1] as usize,
+            step,
+            upper,
+        ));
+    }
+    None
+}
+
+// Text ending in a number.
+fn fill_counter_series(texts: &[&str], one: i64) -> Option<FillSeries> {
+    let split = |text: &str| {
+        let prefix = text.trim_end_matches(|c: char| c.is_ascii_digit());
+        let digits = &text[prefix.len()..];
+        (prefix.to_string(), digits.to_string())
+    };
+
+    let (prefix, digits) = split(texts[texts.len() - 1]);
+    let mut nums = Vec::new();
+    for text in texts {
+        let (p, d) = split(text);
+        if p != prefix {
+            return None;
+        }
+        nums.push(d.parse::<i64>().ok()?);
+    }
+    let step = if nums.len() == 1 {
+        one
+    } else {
+        nums[1] - nums[0]
+    };
+    if !nums.windows(2).all(|w| w[1] - w[0] == step) {
+        return None;
+    }
+    let width = if digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    };
+    Some(FillSeries::Counter(
+        prefix,
+        nums[nums.len() - 1],
+        step,
+        width,
+    ))
+}
+
+// The n-th value after the source. None if the source is repeated.
+fn fill_value(series: &FillSeries, n: i32) -> Option<Value> {
+    match series {
+        FillSeries::Copy => None,
+        FillSeries::Number(last, v, step) => {
+            let v = v + step * n as f64;
+            Some(match last {
+                Value::Percentage(_) => Value::Percentage(v),
+                Value::Currency(_, c) => Value::Currency(v, c.clone()),
+                _ => Value::Number(v),
+            })
+        }
+        FillSeries::DateTime(v, step) => Some(Value::DateTime(*v + *step * n)),
+        FillSeries::Name(list, idx, step, upper) => {
+            let len = list.len() as i64;
+            let name = list[(*idx as i64 + step * n as i64).rem_euclid(len) as usize];
+            Some(Value::from(match upper {
+                Some(true) => name.to_uppercase(),
+                Some(false) => name.to_lowercase(),
+                None => name.to_string(),
+            }))
+        }
+        FillSeries::Counter(prefix, v, step, width) => {
+            // the counter is the digits at the end of the text, it can't
+            // go below 0.
+            let v = v.saturating_add(step.saturating_mul(n as i64)).max(0);
+            Some(Value::from(format!(
+                "{}{:0width$}",
+                prefix,
+                v,
+                width = *width
+            )))
+        }
+    }
+}
+
+// Copy of the existing style with the attributes of the stripe that
+// are not set.
+fn combined_style(
+    book: &mut WorkBook,
+    existing: &CellStyleRef,
+    stripe: &CellStyleRef,
+) -> Option<CellStyleRef> {
+    let stripe_style = book.cellstyle(stripe)?;
+    let mut style = book.cellstyle(existing)?.clone();
+    style.set_origin(StyleOrigin::Content);
+    style.set_styleuse(StyleUse::Automatic);
+    add_missing_attr(style.cellstyle_mut(), stripe_style.cellstyle());
+    add_missing_attr(style.paragraphstyle_mut(), stripe_style.paragraphstyle());
+    add_missing_attr(style.textstyle_mut(), stripe_style.textstyle());
+
+    // reuse a style with this name only if it is the same.
+    let base_name = format!("{}_{}", existing.as_str(), stripe.as_str());
+    let mut name = base_name.clone();
+    let mut n = 2;
+    loop {
+        match book.cellstyle(&name) {
+            None => break,
+            Some(other) if same_cellstyle(other, &style) => return Some(CellStyleRef::from(name)),
+            Some(_) => {
+                name = format!("{}{}", base_name, n);
+                n += 1;
+            }
+        }
+    }
+    style.set_name(&name);
+    Some(book.add_cellstyle(style))
+}
+
+// Same attributes and style maps. The name is not compared.
+fn same_cellstyle(style0: &CellStyle, style1: &CellStyle) -> bool {
+    fn attrs(attr: &AttrMap2) -> Vec<(&str, &str)> {
+        let mut attrs: Vec<(&str, &str)> = attr.iter().map(|(k, v)| (k.as_ref(), v)).collect();
+        attrs.sort();
+        attrs
+    }
+    fn stylemaps(style: &CellStyle) -> Vec<(String, &str, Option<&CellRef>)> {
+        style
+            .stylemaps()
+            .into_iter()
+            .flatten()
+            .map(|v| {
+                (
+                    v.condition().to_string(),
+                    v.applied_style().as_str(),
+                    v.base_cell(),
+                )
+            })
+            .collect()
+    }
+
+    style0.origin() == style1.origin()
+        && style0.styleuse() == style1.styleuse()
+        && attrs(style0.attrmap()) == attrs(style1.attrmap())
+        && attrs(style0.cellstyle()) == attrs(style1.cellstyle())
+        && attrs(style0.paragraphstyle()) == attrs(style1.paragraphstyle())
+        && attrs(style0.textstyle()) == attrs(style1.textstyle())
+        && stylemaps(style0) == stylemaps(style1)
+}
+
+fn add_missing_attr(attr: &mut AttrMap2, from: &AttrMap2) {
+    for (k, v) in from.iter() {
+        if attr.attr(k).is_none() {
+            attr.set_attr(k, v.to_string());
+        }
+    }
+}
+
+// Linear interpolation between two colors.
+fn mix_color(c0: Rgb<u8>, c1: Rgb<u8>, t: f64) -> Rgb<u8> {
+    let mix = |v0: u8, v1: u8| (v0 as f64 + (v1 as f64 - v0 as f64) * t).round() as u8;
+    Rgb::new(mix(c0.r, c1.r), mix(c0.g, c1.g), mix(c0.b, c1.b))
+}
+
 /// Cleanup repeat col-data.
 pub(crate) fn dedup_colheader(sheet: &mut Sheet) -> Result<(), OdsError> {
     fn limited_eq(ch1: &ColHeader, ch2: &ColHeader) -> bool {
@@ -1715,12 +4185,15 @@ pub(crate) fn dedup_colheader(sheet: &mut Sheet) -> Result<(), OdsError> {
     let mut new_col_header = BTreeMap::new();
     let mut new = None;
     for (col, header) in col_header {
+        if header.span == 0 {
+            continue;
+        }
         match new.as_mut() {
             None => {
                 new = Some((col, header));
             }
             Some((new_col, new_header)) => {
-                if limited_eq(new_header, &header) {
+                if *new_col + new_header.span == col && limited_eq(new_header, &header) {
                     new_header.span += header.span;
                 } else {
                     new_col_header
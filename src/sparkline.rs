@@ -0,0 +1,261 @@
+//!
+//! Sparklines as written by LibreOffice 7.4+.
+//!
+//! Sparklines are stored per sheet in groups that share the type and
+//! colors. Each sparkline draws the data of a cell-range into one cell.
+//!
+//! ```
+//! use spreadsheet_ods::{CellRange, Sheet};
+//! use spreadsheet_ods::sparkline::SparklineType;
+//! use spreadsheet_ods::color::Rgb;
+//!
+//! let mut sheet = Sheet::new("data");
+//! for c in 0..5 {
+//!     sheet.set_value(0, c, c * 2);
+//! }
+//! let group = sheet.add_sparkline(0, 5, CellRange::local(0, 0, 0, 4), SparklineType::Column);
+//! group.set_color_series(Rgb::new(0x37, 0x60, 0x92));
+//! ```
+
+use crate::attrmap2::AttrMap2;
+use crate::style::color_string;
+use crate::{CellRange, CellRef, OdsError};
+use color::Rgb;
+use get_size::GetSize;
+use get_size_derive::GetSize;
+use std::collections::hash_map::RandomState;
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hasher};
+use std::str::from_utf8;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Type of the sparkline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, GetSize)]
+pub enum SparklineType {
+    /// Line chart.
+    #[default]
+    Line,
+    /// Column chart.
+    Column,
+    /// Win/loss chart. Positive and negative values have the same height.
+    Stacked,
+}
+
+impl Display for SparklineType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SparklineType::Line => write!(f, "line"),
+            SparklineType::Column => write!(f, "column"),
+            SparklineType::Stacked => write!(f, "stacked"),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for SparklineType {
+    type Error = OdsError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match value {
+            b"line" => Ok(SparklineType::Line),
+            b"column" => Ok(SparklineType::Column),
+            b"stacked" => Ok(SparklineType::Stacked),
+            _ => Err(OdsError::Parse(
+                "invalid calcext:type",
+                Some(from_utf8(value)?.into()),
+            )),
+        }
+    }
+}
+
+/// One sparkline.
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct Sparkline {
+    cell: CellRef,
+    data_range: CellRange,
+}
+
+impl Sparkline {
+    /// Sparkline in the cell for the data.
+    pub fn new(cell: CellRef, data_range: CellRange) -> Self {
+        Self { cell, data_range }
+    }
+
+    /// Cell that shows the sparkline.
+    pub fn cell(&self) -> &CellRef {
+        &self.cell
+    }
+
+    /// Cell that shows the sparkline.
+    pub fn set_cell(&mut self, cell: CellRef) {
+        self.cell = cell;
+    }
+
+    /// Data for the sparkline.
+    pub fn data_range(&self) -> &CellRange {
+        &self.data_range
+    }
+
+    /// Data for the sparkline.
+    pub fn set_data_range(&mut self, data_range: CellRange) {
+        self.data_range = data_range;
+    }
+}
+
+/// Creates a random GUID (version 4) in the form LibreOffice uses.
+///
+/// The random bits come from the randomly seeded std hasher.
+pub(crate) fn new_guid() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let random = || {
+        let mut h = RandomState::new().build_hasher();
+        h.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        if let Ok(t) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            h.write_u128(t.as_nanos());
+        }
+        h.finish()
+    };
+    let hi = (random() & !0xf000) | 0x4000;
+    let lo = (random() & !(0xc << 60)) | (0x8 << 60);
+
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:04X}-{:012X}}}",
+        hi >> 32,
+        (hi >> 16) & 0xffff,
+        hi & 0xffff,
+        lo >> 48,
+        lo & 0xffff_ffff_ffff
+    )
+}
+
+/// A group of sparklines with common type and colors.
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct SparklineGroup {
+    id: String,
+    sparkline_type: SparklineType,
+    attr: AttrMap2,
+    sparklines: Vec<Sparkline>,
+}
+
+impl SparklineGroup {
+    /// New group.
+    pub fn new(sparkline_type: SparklineType) -> Self {
+        Self {
+            id: Default::default(),
+            sparkline_type,
+            attr: Default::default(),
+            sparklines: Default::default(),
+        }
+    }
+
+    /// Id of the group. If this is empty a random GUID is generated when
+    /// writing.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Id of the group. LibreOffice uses a GUID like
+    /// "{C4ACE3C8-9C3F-4E45-A4D7-8A4C4B2A3F5E}".
+    pub fn set_id<S: Into<String>>(&mut self, id: S) {
+        self.id = id.into();
+    }
+
+    /// Type of the sparklines.
+    pub fn sparkline_type(&self) -> SparklineType {
+        self.sparkline_type
+    }
+
+    /// Type of the sparklines.
+    pub fn set_sparkline_type(&mut self, sparkline_type: SparklineType) {
+        self.sparkline_type = sparkline_type;
+    }
+
+    /// Allows access to all attributes.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// Allows access to all attributes.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+
+    /// Sparklines.
+    pub fn sparklines(&self) -> &Vec<Sparkline> {
+        &self.sparklines
+    }
+
+    /// Sparklines.
+    pub fn sparklines_mut(&mut self) -> &mut Vec<Sparkline> {
+        &mut self.sparklines
+    }
+
+    /// Adds a sparkline.
+    pub fn push_sparkline(&mut self, sparkline: Sparkline) {
+        self.sparklines.push(sparkline);
+    }
+
+    /// Color of the data series.
+    pub fn set_color_series(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-series", color_string(color));
+    }
+
+    /// Color for negative values.
+    pub fn set_color_negative(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-negative", color_string(color));
+    }
+
+    /// Color of the x-axis.
+    pub fn set_color_axis(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-axis", color_string(color));
+    }
+
+    /// Color of the markers.
+    pub fn set_color_markers(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-markers", color_string(color));
+    }
+
+    /// Color of the first point.
+    pub fn set_color_first(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-first", color_string(color));
+    }
+
+    /// Color of the last point.
+    pub fn set_color_last(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-last", color_string(color));
+    }
+
+    /// Color of the highest point.
+    pub fn set_color_high(&mut self, color: Rgb<u8>) {
+        self.attr
+            .set_attr("calcext:color-high", color_string(color));
+    }
+
+    /// Color of the lowest point.
+    pub fn set_color_low(&mut self, color: Rgb<u8>) {
+        self.attr.set_attr("calcext:color-low", color_string(color));
+    }
+
+    /// Show markers for each point of a line.
+    pub fn set_markers(&mut self, markers: bool) {
+        self.attr.set_attr("calcext:markers", markers.to_string());
+    }
+
+    /// Highlight negative values.
+    pub fn set_negative(&mut self, negative: bool) {
+        self.attr.set_attr("calcext:negative", negative.to_string());
+    }
+
+    /// Show the x-axis.
+    pub fn set_display_x_axis(&mut self, display: bool) {
+        self.attr
+            .set_attr("calcext:display-x-axis", display.to_string());
+    }
+}
@@ -6,16 +6,17 @@ use crate::style::units::{
     Angle, Border, CellAlignVertical, CellProtect, FontSize, FontStyle, FontVariant, FontWeight,
     GlyphOrientation, Hyphenation, HyphenationLadderCount, Indent, Length, LetterSpacing,
     LineBreak, LineHeight, LineMode, LineStyle, LineType, LineWidth, Margin, PageBreak, PageNumber,
-    ParaAlignVertical, Percent, PunctuationWrap, RotationAlign, TextAlign, TextAlignLast,
+    ParaAlignVertical, Percent, PunctuationWrap, RotationAlign, Side, TextAlign, TextAlignLast,
     TextAlignSource, TextAutoSpace, TextCombine, TextCondition, TextDisplay, TextEmphasize,
     TextEmphasizePosition, TextKeep, TextPosition, TextRelief, TextTransform, WrapOption,
     WritingDirection, WritingMode,
 };
 use crate::style::AnyStyleRef;
 use crate::style::{
-    border_line_width_string, border_string, color_string, shadow_string, text_position,
-    StyleOrigin, StyleUse, TextStyleRef,
+    border_line_width_string, border_string, color_string, parse_border_string, shadow_string,
+    text_position, StyleOrigin, StyleUse, TextStyleRef,
 };
+use crate::OdsError;
 use core::borrow::Borrow;
 use get_size::GetSize;
 use get_size_derive::GetSize;
@@ -168,6 +169,44 @@ impl CellStyle {
         self.stylemaps.get_or_insert_with(Vec::new)
     }
 
+    /// Sets the same border for all four sides. Other than set_border()
+    /// this removes any border set for a single side.
+    pub fn set_border_all(&mut self, width: Length, border: Border, color: Rgb<u8>) {
+        for side in [Side::Top, Side::Bottom, Side::Left, Side::Right] {
+            self.cellstyle.clear_attr(side.border_attr());
+        }
+        self.set_border(width, border, color);
+    }
+
+    /// Sets the border for one side.
+    pub fn set_border_side(&mut self, side: Side, width: Length, border: Border, color: Rgb<u8>) {
+        self.cellstyle
+            .set_attr(side.border_attr(), border_string(width, border, color));
+    }
+
+    /// Removes the border for one side.
+    pub fn clear_border_side(&mut self, side: Side) {
+        self.cellstyle.clear_attr(side.border_attr());
+    }
+
+    /// Border for all four sides as set with set_border().
+    pub fn border_all(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+        self.cellstyle
+            .attr("fo:border")
+            .map(parse_border_string)
+            .transpose()
+    }
+
+    /// Border of one side. Uses fo:border if the side has no border
+    /// of its own.
+    pub fn border_side(&self, side: Side) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+        self.cellstyle
+            .attr(side.border_attr())
+            .or_else(|| self.cellstyle.attr("fo:border"))
+            .map(parse_border_string)
+            .transpose()
+    }
+
     // Cell attributes.
     fo_background_color!(cellstyle);
     fo_border!(cellstyle);
@@ -285,3 +324,61 @@ impl CellStyle {
 
     // TODO: background image
 }
+
+/// Cell style with all inherited attributes, see WorkBook::resolve_cellstyle().
+#[derive(Debug, Clone, Default)]
+pub struct ComputedCellStyle {
+    /// Names of the styles that were used, starting with the style itself.
+    pub(crate) chain: Vec<CellStyleRef>,
+    pub(crate) value_format: Option<String>,
+    pub(crate) cellstyle: AttrMap2,
+    pub(crate) paragraphstyle: AttrMap2,
+    pub(crate) textstyle: AttrMap2,
+}
+
+impl ComputedCellStyle {
+    /// Styles that were used, starting with the style itself, then the
+    /// parent styles and at last the default style.
+    pub fn chain(&self) -> &Vec<CellStyleRef> {
+        &self.chain
+    }
+
+    /// Resolved value format.
+    pub fn value_format(&self) -> Option<&str> {
+        self.value_format.as_deref()
+    }
+
+    /// Resolved cell attributes.
+    pub fn cellstyle(&self) -> &AttrMap2 {
+        &self.cellstyle
+    }
+
+    /// Resolved paragraph attributes.
+    pub fn paragraphstyle(&self) -> &AttrMap2 {
+        &self.paragraphstyle
+    }
+
+    /// Resolved text attributes.
+    pub fn textstyle(&self) -> &AttrMap2 {
+        &self.textstyle
+    }
+
+    // Adds all attributes of the style that are not yet set.
+    pub(crate) fn inherit(&mut self, style: &CellStyle) {
+        self.chain.push(style.style_ref());
+        if self.value_format.is_none() {
+            self.value_format = style.value_format().map(|v| v.to_string());
+        }
+        inherit_attr(&mut self.cellstyle, style.cellstyle());
+        inherit_attr(&mut self.paragraphstyle, style.paragraphstyle());
+        inherit_attr(&mut self.textstyle, style.textstyle());
+    }
+}
+
+fn inherit_attr(attr: &mut AttrMap2, parent: &AttrMap2) {
+    for (k, v) in parent.iter() {
+        if attr.attr(k.as_ref()).is_none() {
+            attr.set_attr(k.as_ref(), v);
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//!
+//! Fill definitions for graphic styles.
+//!
+//! Gradients, hatches and fill images are defined once in office:styles
+//! and are referenced by name from the graphic styles.
+//!
+//! ```
+//! use spreadsheet_ods::WorkBook;
+//! use spreadsheet_ods::color::Rgb;
+//! use spreadsheet_ods::style::{Gradient, GradientStyle, GraphicStyle};
+//! use spreadsheet_ods::style::units::Angle;
+//!
+//! let mut wb = WorkBook::new_empty();
+//!
+//! let mut gradient = Gradient::new("sunset", GradientStyle::Linear);
+//! gradient.set_start_color(Rgb::new(255, 200, 0));
+//! gradient.set_end_color(Rgb::new(160, 0, 0));
+//! gradient.set_angle(Angle::Deg(45.0));
+//! let gradient = wb.add_gradient(gradient);
+//!
+//! let mut gs = GraphicStyle::new("shape");
+//! gs.set_fill_gradient(&gradient);
+//! wb.add_graphicstyle(gs);
+//! ```
+//!
+
+use crate::attrmap2::AttrMap2;
+use crate::color::Rgb;
+use crate::style::color_string;
+use crate::style::units::{Angle, Length, Percent};
+use get_size::GetSize;
+use get_size_derive::GetSize;
+use std::borrow::Borrow;
+use std::fmt::{Display, Formatter};
+
+style_ref2_base!(GradientRef);
+style_ref2_base!(HatchRef);
+style_ref2_base!(FillImageRef);
+
+/// Kind of gradient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum GradientStyle {
+    Linear,
+    Axial,
+    Radial,
+    Ellipsoid,
+    Square,
+    Rectangular,
+}
+
+impl Display for GradientStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            GradientStyle::Linear => write!(f, "linear"),
+            GradientStyle::Axial => write!(f, "axial"),
+            GradientStyle::Radial => write!(f, "radial"),
+            GradientStyle::Ellipsoid => write!(f, "ellipsoid"),
+            GradientStyle::Square => write!(f, "square"),
+            GradientStyle::Rectangular => write!(f, "rectangular"),
+        }
+    }
+}
+
+/// Kind of hatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HatchStyle {
+    /// Parallel lines.
+    Single,
+    /// Lines in two directions.
+    Double,
+    /// Lines in three directions.
+    Triple,
+}
+
+impl Display for HatchStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            HatchStyle::Single => write!(f, "single"),
+            HatchStyle::Double => write!(f, "double"),
+            HatchStyle::Triple => write!(f, "triple"),
+        }
+    }
+}
+
+/// The <draw:gradient> element defines a gradient that can be used as
+/// a fill for graphic objects.
+#[derive(Clone, Debug, Default, GetSize)]
+pub struct Gradient {
+    name: String,
+    /// All other attributes.
+    attr: AttrMap2,
+}
+
+impl Gradient {
+    /// New, empty.
+    pub fn new_empty() -> Self {
+        Self {
+            name: Default::default(),
+            attr: Default::default(),
+        }
+    }
+
+    /// New gradient.
+    pub fn new<S: AsRef<str>>(name: S, style: GradientStyle) -> Self {
+        let mut s = Self {
+            name: name.as_ref().to_string(),
+            attr: Default::default(),
+        };
+        s.set_style(style);
+        s
+    }
+
+    /// Returns a reference to this gradient.
+    pub fn gradient_ref(&self) -> GradientRef {
+        GradientRef::from(self.name())
+    }
+
+    /// Set the name.
+    pub fn set_name<V: AsRef<str>>(&mut self, name: V) {
+        self.name = name.as_ref().to_string();
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// General attributes.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// General attributes.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+
+    /// Name shown in the UI.
+    pub fn set_display_name<S: Into<String>>(&mut self, name: S) {
+        self.attr.set_attr("draw:display-name", name.into());
+    }
+
+    /// Kind of gradient.
+    pub fn set_style(&mut self, style: GradientStyle) {
+        self.attr.set_attr("draw:style", style.to_string());
+    }
+
+    /// Start color.
+    pub fn set_start_color(&mut self, color: Rgb<u8>) {
+        self.attr.set_attr("draw:start-color", color_string(color));
+    }
+
+    /// End color.
+    pub fn set_end_color(&mut self, color: Rgb<u8>) {
+        self.attr.set_attr("draw:end-color", color_string(color));
+    }
+
+    /// Intensity of the start color.
+    pub fn set_start_intensity(&mut self, intensity: Percent) {
+        self.attr
+            .set_attr("draw:start-intensity", intensity.to_string());
+    }
+
+    /// Intensity of the end color.
+    pub fn set_end_intensity(&mut self, intensity: Percent) {
+        self.attr
+            .set_attr("draw:end-intensity", intensity.to_string());
+    }
+
+    /// Rotation of the gradient. Not used for radial gradients.
+    pub fn set_angle(&mut self, angle: Angle) {
+        self.attr.set_attr("draw:angle", angle.to_string());
+    }
+
+    /// Percentage of the area that is filled with the start color only.
+    pub fn set_border(&mut self, border: Percent) {
+        self.attr.set_attr("draw:border", border.to_string());
+    }
+
+    /// Center of the gradient for all but linear and axial gradients.
+    pub fn set_center(&mut self, cx: Percent, cy: Percent) {
+        self.attr.set_attr("draw:cx", cx.to_string());
+        self.attr.set_attr("draw:cy", cy.to_string());
+    }
+}
+
+/// The <draw:hatch> element defines a hatch that can be used as
+/// a fill for graphic objects.
+#[derive(Clone, Debug, Default, GetSize)]
+pub struct Hatch {
+    name: String,
+    /// All other attributes.
+    attr: AttrMap2,
+}
+
+impl Hatch {
+    /// New, empty.
+    pub fn new_empty() -> Self {
+        Self {
+            name: Default::default(),
+            attr: Default::default(),
+        }
+    }
+
+    /// New hatch.
+    pub fn new<S: AsRef<str>>(name: S, style: HatchStyle) -> Self {
+        let mut s = Self {
+            name: name.as_ref().to_string(),
+            attr: Default::default(),
+        };
+        s.set_style(style);
+        s
+    }
+
+    /// Returns a reference to this hatch.
+    pub fn hatch_ref(&self) -> HatchRef {
+        HatchRef::from(self.name())
+    }
+
+    /// Set the name.
+    pub fn set_name<V: AsRef<str>>(&mut self, name: V) {
+        self.name = name.as_ref().to_string();
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// General attributes.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// General attributes.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+
+    /// Name shown in the UI.
+    pub fn set_display_name<S: Into<String>>(&mut self, name: S) {
+        self.attr.set_attr("draw:display-name", name.into());
+    }
+
+    /// Kind of hatch.
+    pub fn set_style(&mut self, style: HatchStyle) {
+        self.attr.set_attr("draw:style", style.to_string());
+    }
+
+    /// Line color.
+    pub fn set_color(&mut self, color: Rgb<u8>) {
+        self.attr.set_attr("draw:color", color_string(color));
+    }
+
+    /// Distance between the lines.
+    pub fn set_distance(&mut self, distance: Length) {
+        self.attr.set_attr("draw:distance", distance.to_string());
+    }
+
+    /// Rotation of the lines.
+    pub fn set_rotation(&mut self, rotation: Angle) {
+        self.attr.set_attr("draw:rotation", rotation.to_string());
+    }
+}
+
+/// The <draw:fill-image> element defines an image that can be used as
+/// a fill for graphic objects.
+///
+/// The image itself is referenced via xlink:href, usually a file in the
+/// Pictures folder of the package. Images embedded as office:binary-data
+/// are kept as is when reading.
+#[derive(Clone, Debug, Default, GetSize)]
+pub struct FillImage {
+    name: String,
+    /// All other attributes.
+    attr: AttrMap2,
+}
+
+impl FillImage {
+    /// New, empty.
+    pub fn new_empty() -> Self {
+        Self {
+            name: Default::default(),
+            attr: Default::default(),
+        }
+    }
+
+    /// New fill image.
+    pub fn new<S: AsRef<str>, T: Into<String>>(name: S, href: T) -> Self {
+        let mut s = Self {
+            name: name.as_ref().to_string(),
+            attr: Default::default(),
+        };
+        s.set_href(href);
+        s
+    }
+
+    /// Returns a reference to this fill image.
+    pub fn fill_image_ref(&self) -> FillImageRef {
+        FillImageRef::from(self.name())
+    }
+
+    /// Set the name.
+    pub fn set_name<V: AsRef<str>>(&mut self, name: V) {
+        self.name = name.as_ref().to_string();
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// General attributes.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// General attributes.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+
+    /// Name shown in the UI.
+    pub fn set_display_name<S: Into<String>>(&mut self, name: S) {
+        self.attr.set_attr("draw:display-name", name.into());
+    }
+
+    /// Link to the image. Sets the other xlink attributes too.
+    pub fn set_href<S: Into<String>>(&mut self, href: S) {
+        self.attr.set_attr("xlink:href", href.into());
+        self.attr.set_attr("xlink:type", "simple".to_string());
+        self.attr.set_attr("xlink:show", "embed".to_string());
+        self.attr.set_attr("xlink:actuate", "onLoad".to_string());
+    }
+
+    /// Link to the image.
+    pub fn href(&self) -> Option<&str> {
+        self.attr.attr("xlink:href")
+    }
+
+    /// Size of the image.
+    pub fn set_size(&mut self, width: Length, height: Length) {
+        self.attr.set_attr("svg:width", width.to_string());
+        self.attr.set_attr("svg:height", height.to_string());
+    }
+}
@@ -33,6 +33,8 @@ pub struct FontFaceDecl {
     origin: StyleOrigin,
     /// All other attributes.
     attr: AttrMap2,
+    /// svg:font-face-src
+    src: Vec<FontFaceUri>,
 }
 
 impl FontFaceDecl {
@@ -42,6 +44,7 @@ impl FontFaceDecl {
             name: "".to_string(),
             origin: Default::default(),
             attr: Default::default(),
+            src: Default::default(),
         }
     }
 
@@ -51,6 +54,7 @@ impl FontFaceDecl {
             name: name.as_ref().to_string(),
             origin: StyleOrigin::Content,
             attr: Default::default(),
+            src: Default::default(),
         }
     }
 
@@ -90,6 +94,22 @@ impl FontFaceDecl {
         &mut self.attr
     }
 
+    /// Font files for this font. Fonts embedded in the document
+    /// refer to a file in the package.
+    pub fn src(&self) -> &Vec<FontFaceUri> {
+        &self.src
+    }
+
+    /// Font files for this font.
+    pub fn src_mut(&mut self) -> &mut Vec<FontFaceUri> {
+        &mut self.src
+    }
+
+    /// Adds a font file.
+    pub fn push_src(&mut self, uri: FontFaceUri) {
+        self.src.push(uri);
+    }
+
     style_font_family_generic!(attr);
     style_font_pitch!(attr);
     svg_font_family!(attr);
@@ -98,3 +118,55 @@ impl FontFaceDecl {
     svg_font_variant!(attr);
     svg_font_weight!(attr);
 }
+
+/// The <svg:font-face-uri> element links to a font file.
+#[derive(Clone, Debug, Default, GetSize)]
+pub struct FontFaceUri {
+    /// Attributes.
+    attr: AttrMap2,
+    /// svg:font-face-format
+    format: Option<String>,
+}
+
+impl FontFaceUri {
+    /// New, empty.
+    pub fn new_empty() -> Self {
+        Self {
+            attr: Default::default(),
+            format: None,
+        }
+    }
+
+    /// New, with a link to the font file.
+    pub fn new<S: Into<String>>(href: S) -> Self {
+        let mut s = Self::new_empty();
+        s.attr.set_attr("xlink:href", href.into());
+        s.attr.set_attr("xlink:type", "simple".to_string());
+        s
+    }
+
+    /// Link to the font file.
+    pub fn href(&self) -> Option<&str> {
+        self.attr.attr("xlink:href")
+    }
+
+    /// Format of the font file, e.g. "truetype" or "opentype".
+    pub fn set_format<S: Into<String>>(&mut self, format: S) {
+        self.format = Some(format.into());
+    }
+
+    /// Format of the font file.
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+
+    /// Attributes.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// Attributes.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+}
@@ -11,7 +11,8 @@ use crate::style::{
     border_line_width_string, border_string, color_string, shadow_string, text_position, Border,
     Length, Rgb,
 };
-use crate::style::{StyleOrigin, StyleUse, TextStyleRef};
+use crate::style::{FillImageRef, GradientRef, HatchRef, StyleOrigin, StyleUse, TextStyleRef};
+use crate::OdsError;
 use get_size::GetSize;
 use get_size_derive::GetSize;
 use icu_locid::Locale;
@@ -109,6 +110,8 @@ impl GraphicStyle {
         &mut self.textstyle
     }
 
+    draw_fill!(graphicstyle);
+
     fo_background_color!(paragraphstyle);
     fo_border!(paragraphstyle);
     fo_break!(paragraphstyle);
@@ -90,6 +90,7 @@
 //! then this default value is used. In all remaining cases an implementation-dependent value is used.
 
 use crate::color::Rgb;
+use crate::io::parse::parse_color;
 use crate::style::units::{Border, Length, Percent, TextPosition};
 use crate::OdsError;
 use get_size::GetSize;
@@ -99,6 +100,7 @@ use std::str::FromStr;
 
 pub use cellstyle::*;
 pub use colstyle::*;
+pub use fill::*;
 pub use fontface::*;
 pub use graphicstyle::*;
 pub use masterpage::*;
@@ -108,13 +110,16 @@ pub use rowstyle::*;
 pub use ruby::*;
 pub use tablestyle::*;
 pub use textstyle::*;
+pub use theme::*;
 
+pub mod presets;
 pub mod stylemap;
 pub mod tabstop;
 pub mod units;
 
 mod cellstyle;
 mod colstyle;
+mod fill;
 mod fontface;
 mod graphicstyle;
 mod masterpage;
@@ -124,6 +129,7 @@ mod rowstyle;
 mod ruby;
 mod tablestyle;
 mod textstyle;
+mod theme;
 
 // The <style:style> element has the following attributes:
 // ok: style:auto-update 19.467,
@@ -141,7 +147,7 @@ mod textstyle;
 // only for chart: style:percentage-data-style-name 19.511
 
 /// Origin of a style. Content.xml or Styles.xml.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, GetSize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, GetSize)]
 pub enum StyleOrigin {
     /// Style comes from Content.xml
     #[default]
@@ -180,6 +186,96 @@ pub enum StyleUse {
 // General style reference.
 style_ref2_base!(AnyStyleRef);
 
+/// Any style of the workbook tagged with its family.
+///
+/// See WorkBook::styles().
+#[derive(Debug, Clone, Copy)]
+pub enum AnyStyle<'a> {
+    /// Cell style.
+    Cell(&'a CellStyle),
+    /// Row style.
+    Row(&'a RowStyle),
+    /// Column style.
+    Col(&'a ColStyle),
+    /// Table style.
+    Table(&'a TableStyle),
+    /// Paragraph style.
+    Paragraph(&'a ParagraphStyle),
+    /// Text style.
+    Text(&'a TextStyle),
+    /// Graphic style.
+    Graphic(&'a GraphicStyle),
+    /// Ruby style.
+    Ruby(&'a RubyStyle),
+    /// Page style.
+    Page(&'a PageStyle),
+    /// Master page.
+    Master(&'a MasterPage),
+}
+
+impl<'a> AnyStyle<'a> {
+    /// Name of the style.
+    pub fn name(&self) -> &'a str {
+        match *self {
+            AnyStyle::Cell(s) => s.name(),
+            AnyStyle::Row(s) => s.name(),
+            AnyStyle::Col(s) => s.name(),
+            AnyStyle::Table(s) => s.name(),
+            AnyStyle::Paragraph(s) => s.name(),
+            AnyStyle::Text(s) => s.name(),
+            AnyStyle::Graphic(s) => s.name(),
+            AnyStyle::Ruby(s) => s.name(),
+            AnyStyle::Page(s) => s.name(),
+            AnyStyle::Master(s) => s.name(),
+        }
+    }
+}
+
+/// Any style of the workbook tagged with its family, mutable.
+///
+/// See WorkBook::styles_mut().
+#[derive(Debug)]
+pub enum AnyStyleMut<'a> {
+    /// Cell style.
+    Cell(&'a mut CellStyle),
+    /// Row style.
+    Row(&'a mut RowStyle),
+    /// Column style.
+    Col(&'a mut ColStyle),
+    /// Table style.
+    Table(&'a mut TableStyle),
+    /// Paragraph style.
+    Paragraph(&'a mut ParagraphStyle),
+    /// Text style.
+    Text(&'a mut TextStyle),
+    /// Graphic style.
+    Graphic(&'a mut GraphicStyle),
+    /// Ruby style.
+    Ruby(&'a mut RubyStyle),
+    /// Page style.
+    Page(&'a mut PageStyle),
+    /// Master page.
+    Master(&'a mut MasterPage),
+}
+
+impl AnyStyleMut<'_> {
+    /// Name of the style.
+    pub fn name(&self) -> &str {
+        match self {
+            AnyStyleMut::Cell(s) => s.name(),
+            AnyStyleMut::Row(s) => s.name(),
+            AnyStyleMut::Col(s) => s.name(),
+            AnyStyleMut::Table(s) => s.name(),
+            AnyStyleMut::Paragraph(s) => s.name(),
+            AnyStyleMut::Text(s) => s.name(),
+            AnyStyleMut::Graphic(s) => s.name(),
+            AnyStyleMut::Ruby(s) => s.name(),
+            AnyStyleMut::Page(s) => s.name(),
+            AnyStyleMut::Master(s) => s.name(),
+        }
+    }
+}
+
 /// Parses an attribute string to a value type.
 pub(crate) trait ParseStyleAttr<T> {
     fn parse_attr(attr: Option<&str>) -> Result<Option<T>, OdsError>;
@@ -230,6 +326,35 @@ pub(crate) fn border_string(width: Length, border: Border, color: Rgb<u8>) -> St
     )
 }
 
+/// Parses a border as written by border_string(). The parts may come
+/// in any order, missing parts are Length::Default, Border::None and black.
+///
+/// The width keywords thin, medium and thick are read as 0.75pt, 2.25pt
+/// and 3.75pt, the usual 1, 3 and 5 pixels.
+pub(crate) fn parse_border_string(value: &str) -> Result<(Length, Border, Rgb<u8>), OdsError> {
+    let mut width = Length::Default;
+    let mut border = Border::None;
+    let mut color = Rgb::new(0, 0, 0);
+    for v in value.split_whitespace() {
+        if v.starts_with('#') {
+            color = parse_color(v.as_bytes())?;
+        } else if v == "thin" {
+            width = Length::Pt(0.75);
+        } else if v == "medium" {
+            width = Length::Pt(2.25);
+        } else if v == "thick" {
+            width = Length::Pt(3.75);
+        } else if v.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            if let Some(v) = Length::parse_attr(Some(v))? {
+                width = v;
+            }
+        } else if let Some(v) = Border::parse_attr(Some(v))? {
+            border = v;
+        }
+    }
+    Ok((width, border, color))
+}
+
 pub(crate) fn border_line_width_string(inner: Length, space: Length, outer: Length) -> String {
     format!("{} {} {}", inner, space, outer)
 }
@@ -140,6 +140,8 @@ impl PageStyle {
     style_print_page_order!(style);
     style_scale_to!(style);
     style_scale_to_pages!(style);
+    style_scale_to_x!(style);
+    style_scale_to_y!(style);
     style_table_centering!(style);
     style_writing_mode!(style);
     fo_background_color!(style);
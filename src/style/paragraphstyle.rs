@@ -15,6 +15,7 @@ use crate::style::{
     border_line_width_string, border_string, color_string, shadow_string, text_position,
     StyleOrigin, StyleUse, TextStyleRef,
 };
+use crate::OdsError;
 use get_size::GetSize;
 use get_size_derive::GetSize;
 use icu_locid::Locale;
@@ -119,6 +120,10 @@ impl ParagraphStyle {
         self.tabstops.as_ref()
     }
 
+    pub(crate) fn tabstops_mut(&mut self) -> impl Iterator<Item = &mut TabStop> {
+        self.tabstops.iter_mut().flatten()
+    }
+
     fo_background_color!(paragraphstyle);
     fo_border!(paragraphstyle);
     fo_break!(paragraphstyle);
@@ -0,0 +1,93 @@
+//!
+//! Ready-made cell styles for common uses.
+//!
+//! The styles still have to be added to the workbook. The value formats
+//! are passed in, either the defaults of the workbook or any locale
+//! specific format.
+//!
+//! ```
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//! use spreadsheet_ods::defaultstyles::DefaultFormat;
+//! use spreadsheet_ods::format::create_loc_currency_iso;
+//! use spreadsheet_ods::style::presets;
+//! use icu_locid::locale;
+//!
+//! let mut wb = WorkBook::new(locale!("de_AT"));
+//!
+//! let header = wb.add_cellstyle(presets::header("header"));
+//! let eur = wb.add_currency_format(create_loc_currency_iso("eur", "EUR", locale!("de_AT")));
+//! let amount = wb.add_cellstyle(presets::currency("amount", &eur));
+//! let share = wb.add_cellstyle(presets::percentage("share", &DefaultFormat::percent()));
+//! let warning = wb.add_cellstyle(presets::warning("warning"));
+//!
+//! let mut sheet = Sheet::new("report");
+//! sheet.set_styled_value(0, 0, "amount", &header);
+//! sheet.set_styled_value(1, 0, 1234.5, &amount);
+//! sheet.set_styled_value(2, 0, 0.25, &share);
+//! sheet.set_styled_value(3, 0, "over budget", &warning);
+//! wb.push_sheet(sheet);
+//! ```
+//!
+
+use crate::color::Rgb;
+use crate::format::ValueFormatRef;
+use crate::style::units::{Border, CellAlignVertical, TextAlign, TextAlignSource};
+use crate::style::CellStyle;
+use crate::Length;
+
+/// Aligns the content to the end of the cell, as is usual for numbers.
+///
+/// Cells align numbers right by default, but this changes as soon as
+/// the style sets any alignment itself.
+pub fn align_number(style: &mut CellStyle) {
+    style.set_text_align_source(TextAlignSource::Fix);
+    style.set_text_align(TextAlign::End);
+}
+
+/// Centers the content of the cell.
+pub fn align_center(style: &mut CellStyle) {
+    style.set_text_align_source(TextAlignSource::Fix);
+    style.set_text_align(TextAlign::Center);
+    style.set_vertical_align(CellAlignVertical::Middle);
+}
+
+/// Header cell. Bold, centered and with a thin line at the bottom.
+pub fn header<S: AsRef<str>>(name: S) -> CellStyle {
+    let mut style = CellStyle::new_empty();
+    style.set_name(name);
+    style.set_font_bold();
+    align_center(&mut style);
+    style.set_border_bottom(Length::Pt(0.75), Border::Solid, Rgb::new(0, 0, 0));
+    style
+}
+
+/// Number with the given format, aligned right.
+pub fn number<S: AsRef<str>>(name: S, format: &ValueFormatRef) -> CellStyle {
+    let mut style = CellStyle::new(name, format);
+    align_number(&mut style);
+    style
+}
+
+/// Currency with the given format, aligned right.
+pub fn currency<S: AsRef<str>>(name: S, format: &ValueFormatRef) -> CellStyle {
+    let mut style = CellStyle::new(name, format);
+    align_number(&mut style);
+    style
+}
+
+/// Percentage with the given format, aligned right.
+pub fn percentage<S: AsRef<str>>(name: S, format: &ValueFormatRef) -> CellStyle {
+    let mut style = CellStyle::new(name, format);
+    align_number(&mut style);
+    style
+}
+
+/// Warning. Bold dark red text on a light red background.
+pub fn warning<S: AsRef<str>>(name: S) -> CellStyle {
+    let mut style = CellStyle::new_empty();
+    style.set_name(name);
+    style.set_font_bold();
+    style.set_color(Rgb::new(156, 0, 6));
+    style.set_background_color(Rgb::new(255, 199, 206));
+    style
+}
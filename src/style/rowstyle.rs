@@ -2,7 +2,7 @@ use crate::color::Rgb;
 use get_size::GetSize;
 
 use crate::attrmap2::AttrMap2;
-use crate::style::units::{Length, PageBreak, TextKeep};
+use crate::style::units::{Length, LengthUnit, PageBreak, TextKeep};
 use crate::style::AnyStyleRef;
 use crate::style::ParseStyleAttr;
 use crate::style::{color_string, StyleOrigin, StyleUse};
@@ -82,3 +82,76 @@ impl RowStyle {
     style_row_height!(rowstyle);
     style_use_optimal_row_height!(rowstyle);
 }
+
+/// Estimates the row height needed to show the wrapped text in a column of
+/// the given width.
+///
+/// LibreOffice calculates the optimal row height only when the file is
+/// opened, and only for rows it has to layout. This is a rough estimate
+/// instead, with an average character width of half the font size. One
+/// line with the default font size of 10pt gives the default row height.
+/// Length::Default is the default column width and the default font size.
+///
+/// ```
+/// use spreadsheet_ods::style::text_row_height;
+/// use spreadsheet_ods::{cm, pt, Length};
+///
+/// let h = text_row_height("lorem ipsum dolor sit amet", cm!(2), pt!(10));
+/// assert_eq!(h, pt!(36.24));
+/// ```
+pub fn text_row_height(text: &str, col_width: Length, font_size: Length) -> Length {
+    let font_size = points(font_size, DEFAULT_FONT_SIZE);
+    let col_width = col_width_pt(col_width);
+
+    let chars_per_line = ((col_width - 2.0 * CELL_PADDING) / (font_size * 0.5))
+        .floor()
+        .max(1.0) as usize;
+    let lines = wrapped_lines(text, chars_per_line);
+
+    let height = lines as f64 * font_size * LINE_HEIGHT + 2.0 * CELL_PADDING;
+    Length::Pt((height * 100.0).round() / 100.0)
+}
+
+/// Default font size in pt.
+const DEFAULT_FONT_SIZE: f64 = 10.0;
+/// Default column width of LibreOffice in pt.
+const DEFAULT_COL_WIDTH: f64 = 64.0;
+/// Line height relative to the font size.
+const LINE_HEIGHT: f64 = 1.17;
+/// Padding at the cell border in pt.
+const CELL_PADDING: f64 = 0.57;
+
+/// Column width in pt.
+pub(crate) fn col_width_pt(col_width: Length) -> f64 {
+    points(col_width, DEFAULT_COL_WIDTH)
+}
+
+fn points(length: Length, default: f64) -> f64 {
+    match length.to_unit(LengthUnit::Pt) {
+        Length::Pt(v) => v,
+        _ => default,
+    }
+}
+
+// Counts the lines with word-wrap. Words that are longer than a line
+// are broken at the border.
+fn wrapped_lines(text: &str, chars_per_line: usize) -> usize {
+    let mut lines = 0;
+    for para in text.lines() {
+        lines += 1;
+        let mut len = 0;
+        for word in para.split_whitespace() {
+            let w = word.chars().count();
+            if len > 0 && len + 1 + w <= chars_per_line {
+                len += 1 + w;
+            } else {
+                if len > 0 {
+                    lines += 1;
+                }
+                lines += (w - 1) / chars_per_line;
+                len = (w - 1) % chars_per_line + 1;
+            }
+        }
+    }
+    lines.max(1)
+}
@@ -7,6 +7,7 @@ use crate::style::units::{
 };
 use crate::style::AnyStyleRef;
 use crate::style::{color_string, shadow_string, text_position, StyleOrigin, StyleUse};
+use crate::OdsError;
 use core::borrow::Borrow;
 use get_size::GetSize;
 use get_size_derive::GetSize;
@@ -0,0 +1,165 @@
+//!
+//! Color theme of the document. LibreOffice stores the palette as
+//! loext:theme, the styles themselves use plain colors.
+//!
+
+use crate::color::Rgb;
+use crate::style::ParseStyleAttr;
+use crate::OdsError;
+use get_size::GetSize;
+use std::fmt::{Display, Formatter};
+
+/// The color slots of a theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum ThemeColor {
+    Dark1,
+    Light1,
+    Dark2,
+    Light2,
+    Accent1,
+    Accent2,
+    Accent3,
+    Accent4,
+    Accent5,
+    Accent6,
+    Hyperlink,
+    FollowedHyperlink,
+}
+
+impl Display for ThemeColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ThemeColor::Dark1 => write!(f, "dark1"),
+            ThemeColor::Light1 => write!(f, "light1"),
+            ThemeColor::Dark2 => write!(f, "dark2"),
+            ThemeColor::Light2 => write!(f, "light2"),
+            ThemeColor::Accent1 => write!(f, "accent1"),
+            ThemeColor::Accent2 => write!(f, "accent2"),
+            ThemeColor::Accent3 => write!(f, "accent3"),
+            ThemeColor::Accent4 => write!(f, "accent4"),
+            ThemeColor::Accent5 => write!(f, "accent5"),
+            ThemeColor::Accent6 => write!(f, "accent6"),
+            ThemeColor::Hyperlink => write!(f, "hyperlink"),
+            ThemeColor::FollowedHyperlink => write!(f, "followed-hyperlink"),
+        }
+    }
+}
+
+impl ParseStyleAttr<ThemeColor> for ThemeColor {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<ThemeColor>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "dark1" => Ok(Some(ThemeColor::Dark1)),
+                "light1" => Ok(Some(ThemeColor::Light1)),
+                "dark2" => Ok(Some(ThemeColor::Dark2)),
+                "light2" => Ok(Some(ThemeColor::Light2)),
+                "accent1" => Ok(Some(ThemeColor::Accent1)),
+                "accent2" => Ok(Some(ThemeColor::Accent2)),
+                "accent3" => Ok(Some(ThemeColor::Accent3)),
+                "accent4" => Ok(Some(ThemeColor::Accent4)),
+                "accent5" => Ok(Some(ThemeColor::Accent5)),
+                "accent6" => Ok(Some(ThemeColor::Accent6)),
+                "hyperlink" => Ok(Some(ThemeColor::Hyperlink)),
+                "followed-hyperlink" => Ok(Some(ThemeColor::FollowedHyperlink)),
+                _ => Err(OdsError::Parse(
+                    "invalid theme color",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// A color theme as written by LibreOffice (loext:theme).
+///
+/// The styles use the plain colors of the theme and don't change when the
+/// theme is replaced with WorkBook::set_theme().
+///
+/// ```
+/// use spreadsheet_ods::WorkBook;
+/// use spreadsheet_ods::color::Rgb;
+/// use spreadsheet_ods::style::{CellStyle, Theme, ThemeColor};
+///
+/// let mut wb = WorkBook::new_empty();
+///
+/// let mut theme = Theme::new("Corporate");
+/// theme.set_color(ThemeColor::Accent1, Rgb::new(0, 84, 159));
+/// wb.set_theme(theme);
+///
+/// let mut st = CellStyle::new_empty();
+/// st.set_color(wb.theme_color(ThemeColor::Accent1).expect("accent1"));
+/// let st = wb.add_cellstyle(st);
+///
+/// let st = wb.cellstyle(st).expect("style");
+/// assert_eq!(st.textstyle().attr("fo:color"), Some("#00549f"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    name: String,
+    colors_name: String,
+    colors: Vec<(ThemeColor, Rgb<u8>)>,
+}
+
+impl GetSize for Theme {
+    fn get_heap_size(&self) -> usize {
+        self.name.get_heap_size()
+            + self.colors_name.get_heap_size()
+            + self.colors.capacity() * size_of::<(ThemeColor, Rgb<u8>)>()
+    }
+}
+
+impl Theme {
+    /// New, empty theme. The palette gets the same name.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        let name = name.into();
+        Self {
+            colors_name: name.clone(),
+            name,
+            colors: Default::default(),
+        }
+    }
+
+    /// Theme name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Theme name.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = name.into();
+    }
+
+    /// Name of the palette.
+    pub fn colors_name(&self) -> &str {
+        &self.colors_name
+    }
+
+    /// Name of the palette.
+    pub fn set_colors_name<S: Into<String>>(&mut self, name: S) {
+        self.colors_name = name.into();
+    }
+
+    /// Sets the color for one slot.
+    pub fn set_color(&mut self, slot: ThemeColor, color: Rgb<u8>) {
+        match self.colors.iter_mut().find(|(s, _)| *s == slot) {
+            Some((_, c)) => *c = color,
+            None => self.colors.push((slot, color)),
+        }
+    }
+
+    /// Color of one slot.
+    pub fn color(&self, slot: ThemeColor) -> Option<Rgb<u8>> {
+        self.colors
+            .iter()
+            .find(|(s, _)| *s == slot)
+            .map(|(_, c)| *c)
+    }
+
+    /// All colors.
+    pub fn colors(&self) -> &Vec<(ThemeColor, Rgb<u8>)> {
+        &self.colors
+    }
+}
@@ -67,6 +67,41 @@ impl Length {
             Length::Em(v) => *v,
         }
     }
+
+    /// Unit of the length. None for Default and Em, those can't be
+    /// converted to an absolute unit.
+    pub fn unit(&self) -> Option<LengthUnit> {
+        match self {
+            Length::Default => None,
+            Length::Cm(_) => Some(LengthUnit::Cm),
+            Length::Mm(_) => Some(LengthUnit::Mm),
+            Length::In(_) => Some(LengthUnit::In),
+            Length::Pt(_) => Some(LengthUnit::Pt),
+            Length::Pc(_) => Some(LengthUnit::Pc),
+            Length::Em(_) => None,
+        }
+    }
+
+    /// Converts the length to the given unit. The result is rounded to
+    /// 4 decimals. Default and Em are returned unchanged.
+    pub fn to_unit(&self, unit: LengthUnit) -> Length {
+        let inch = match self {
+            Length::Default | Length::Em(_) => return *self,
+            Length::Cm(v) => *v / 2.54,
+            Length::Mm(v) => *v / 25.4,
+            Length::In(v) => *v,
+            Length::Pt(v) => *v / 72.0,
+            Length::Pc(v) => *v / 6.0,
+        };
+        let round = |v: f64| (v * 10000.0).round() / 10000.0;
+        match unit {
+            LengthUnit::Cm => Length::Cm(round(inch * 2.54)),
+            LengthUnit::Mm => Length::Mm(round(inch * 25.4)),
+            LengthUnit::In => Length::In(round(inch)),
+            LengthUnit::Pt => Length::Pt(round(inch * 72.0)),
+            LengthUnit::Pc => Length::Pc(round(inch * 6.0)),
+        }
+    }
 }
 
 impl Display for Length {
@@ -107,6 +142,78 @@ impl ParseStyleAttr<Length> for Length {
     }
 }
 
+/// Absolute units of a Length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    /// cm
+    Cm,
+    /// mm
+    Mm,
+    /// inch
+    In,
+    /// typographic points
+    Pt,
+    /// pica
+    Pc,
+}
+
+impl Display for LengthUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            LengthUnit::Cm => write!(f, "cm"),
+            LengthUnit::Mm => write!(f, "mm"),
+            LengthUnit::In => write!(f, "in"),
+            LengthUnit::Pt => write!(f, "pt"),
+            LengthUnit::Pc => write!(f, "pc"),
+        }
+    }
+}
+
+/// Attributes whose values are never treated as lengths.
+fn is_length_attr(name: &str) -> bool {
+    !name.ends_with("-name")
+}
+
+/// Units of all lengths in an attribute value. Values like borders
+/// contain more than one length, those are separated by blanks.
+pub(crate) fn length_attr_units<'a>(
+    name: &str,
+    value: &'a str,
+) -> impl Iterator<Item = LengthUnit> + 'a {
+    let check = is_length_attr(name);
+    value
+        .split(' ')
+        .filter(move |_| check)
+        .filter_map(|v| Length::parse_attr(Some(v)).ok().flatten())
+        .filter_map(|v| v.unit())
+}
+
+/// Converts all lengths in an attribute value to the given unit.
+/// Returns None if there was nothing to convert.
+pub(crate) fn convert_length_attr(name: &str, value: &str, unit: LengthUnit) -> Option<String> {
+    if !is_length_attr(name) {
+        return None;
+    }
+
+    let mut changed = false;
+    let converted: Vec<String> = value
+        .split(' ')
+        .map(|v| match Length::parse_attr(Some(v)) {
+            Ok(Some(len)) if len.unit().is_some() && len.unit() != Some(unit) => {
+                changed = true;
+                len.to_unit(unit).to_string()
+            }
+            _ => v.to_string(),
+        })
+        .collect();
+
+    if changed {
+        Some(converted.join(" "))
+    } else {
+        None
+    }
+}
+
 /// (Positive or negative) percentage values in conformance with §5.9.11 of XSL.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Percent {
@@ -431,6 +538,9 @@ impl Display for FontStretch {
 
 /// 20.183 fo-border Properties.
 /// See §7.29.3ff of XSL
+///
+/// DashDot, DashDotDot, FineDashed and DoubleThin are not part of XSL,
+/// but LibreOffice writes them.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Border {
@@ -444,6 +554,10 @@ pub enum Border {
     Ridge,
     Inset,
     Outset,
+    DashDot,
+    DashDotDot,
+    FineDashed,
+    DoubleThin,
 }
 
 impl Display for Border {
@@ -459,6 +573,61 @@ impl Display for Border {
             Border::Ridge => write!(f, "ridge"),
             Border::Inset => write!(f, "inset"),
             Border::Outset => write!(f, "outset"),
+            Border::DashDot => write!(f, "dash-dot"),
+            Border::DashDotDot => write!(f, "dash-dot-dot"),
+            Border::FineDashed => write!(f, "fine-dashed"),
+            Border::DoubleThin => write!(f, "double-thin"),
+        }
+    }
+}
+
+impl ParseStyleAttr<Border> for Border {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<Border>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "none" => Ok(Some(Border::None)),
+                "hidden" => Ok(Some(Border::Hidden)),
+                "dotted" => Ok(Some(Border::Dotted)),
+                "dashed" => Ok(Some(Border::Dashed)),
+                "solid" => Ok(Some(Border::Solid)),
+                "double" => Ok(Some(Border::Double)),
+                "groove" => Ok(Some(Border::Groove)),
+                "ridge" => Ok(Some(Border::Ridge)),
+                "inset" => Ok(Some(Border::Inset)),
+                "outset" => Ok(Some(Border::Outset)),
+                "dash-dot" => Ok(Some(Border::DashDot)),
+                "dash-dot-dot" => Ok(Some(Border::DashDotDot)),
+                "fine-dashed" => Ok(Some(Border::FineDashed)),
+                "double-thin" => Ok(Some(Border::DoubleThin)),
+                _ => Err(OdsError::Parse(
+                    "invalid border style",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// One side of a cell border.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Side {
+    /// Name of the fo:border-xxx attribute.
+    pub(crate) fn border_attr(&self) -> &'static str {
+        match self {
+            Side::Top => "fo:border-top",
+            Side::Bottom => "fo:border-bottom",
+            Side::Left => "fo:border-left",
+            Side::Right => "fo:border-right",
         }
     }
 }
@@ -0,0 +1,269 @@
+//!
+//! Fills a template with data.
+//!
+//! The template is a spreadsheet made with any office application. Text
+//! cells contain placeholders like `{{name}}` which are replaced with the
+//! values from a map. If the placeholder is the whole content of a cell
+//! the cell gets the value itself, numbers stay numbers and the value
+//! format of the cell style applies. Otherwise the placeholder is replaced
+//! with the value as text. This works for rich text too, as long as the
+//! placeholder has the same formatting throughout.
+//!
+//! A row with a placeholder `{{list.field}}` for a list is repeated for
+//! each entry of the list. The copies keep the row and cell styles, their
+//! formulas are moved along. The references to the rows below are adjusted
+//! and ranges that end at the template row are extended to the last copy,
+//! so a sum below the list covers all entries. This applies to the
+//! formulas of all sheets, named expressions, validations, print ranges
+//! and conditional formats. Header rows, row groups and merged cells that
+//! cover the template row are extended too. Drawings that are not anchored
+//! to a cell keep their position. An empty list leaves the template row
+//! with the placeholders removed.
+//!
+//! Placeholders without a value are left as they are.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//! use spreadsheet_ods::template::{Template, TemplateValue};
+//!
+//! let mut book = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("invoice");
+//! sheet.set_value(0, 0, "Invoice for {{customer}}");
+//! sheet.set_value(2, 0, "{{items.name}}");
+//! sheet.set_value(2, 1, "{{items.price}}");
+//! sheet.set_formula(3, 1, "of:=SUM([.B3:.B3])");
+//! book.push_sheet(sheet);
+//!
+//! let template = Template::new(book);
+//!
+//! let mut items = Vec::new();
+//! for (name, price) in [("apples", 2.5), ("pears", 3.0)] {
+//!     let mut item = HashMap::new();
+//!     item.insert("name".to_string(), name.into());
+//!     item.insert("price".to_string(), price.into());
+//!     items.push(item);
+//! }
+//! let mut data = HashMap::new();
+//! data.insert("customer".to_string(), TemplateValue::from("ACME"));
+//! data.insert("items".to_string(), TemplateValue::List(items));
+//!
+//! let book = template.fill(&data).unwrap();
+//! let sheet = book.sheet(0);
+//! assert_eq!(sheet.value(0, 0).as_str_or(""), "Invoice for ACME");
+//! assert_eq!(sheet.value(3, 1).as_f64_or(0.0), 3.0);
+//! assert_eq!(
+//!     sheet.formula(4, 1).map(|v| v.as_str()),
+//!     Some("of:=SUM([.B3:.B4])")
+//! );
+//! ```
+//!
+
+use crate::html::plain_value;
+use crate::text::TextTag;
+use crate::xmltree::XmlContent;
+use crate::{read_ods, OdsError, Value, WorkBook};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Data for a placeholder.
+#[derive(Debug, Clone)]
+pub enum TemplateValue {
+    /// Replaces the placeholder.
+    Value(Value),
+    /// One entry per copy of the template row. The placeholders in the
+    /// row are written as `{{list.field}}`.
+    List(Vec<HashMap<String, Value>>),
+}
+
+impl<T: Into<Value>> From<T> for TemplateValue {
+    fn from(value: T) -> Self {
+        TemplateValue::Value(value.into())
+    }
+}
+
+/// A workbook with placeholders.
+#[derive(Debug, Clone)]
+pub struct Template {
+    book: WorkBook,
+}
+
+impl Template {
+    /// Template from a workbook.
+    pub fn new(book: WorkBook) -> Self {
+        Self { book }
+    }
+
+    /// Reads the template.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, OdsError> {
+        Ok(Self::new(read_ods(path)?))
+    }
+
+    /// The template itself.
+    pub fn workbook(&self) -> &WorkBook {
+        &self.book
+    }
+
+    /// Creates a new workbook with the placeholders replaced.
+    ///
+    /// Fails if a row contains placeholders for more than one list.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet of the template is detached.
+    pub fn fill(&self, data: &HashMap<String, TemplateValue>) -> Result<WorkBook, OdsError> {
+        let mut book = self.book.clone();
+        for idx in 0..book.num_sheets() {
+            fill_sheet(&mut book, idx, data)?;
+        }
+        Ok(book)
+    }
+}
+
+fn fill_sheet(
+    book: &mut WorkBook,
+    idx: usize,
+    data: &HashMap<String, TemplateValue>,
+) -> Result<(), OdsError> {
+    let mut lists: BTreeMap<u32, &str> = BTreeMap::new();
+    for ((row, _), cell) in book.sheet(idx).data.iter() {
+        let text = match &cell.value {
+            Value::Text(_) | Value::TextXml(_) => cell.value.as_cow_str_or(""),
+            _ => continue,
+        };
+        let mut pos = 0;
+        while let Some((_, end, name)) = next_placeholder(&text[pos..]) {
+            pos += end;
+            let Some((list, _)) = name.split_once('.') else {
+                continue;
+            };
+            let Some((list, TemplateValue::List(_))) = data.get_key_value(list) else {
+                continue;
+            };
+            match lists.get(&row) {
+                Some(other) if *other != list => {
+                    return Err(OdsError::Ods(format!(
+                        "Row {} uses the lists {} and {}",
+                        row + 1,
+                        other,
+                        list
+                    )));
+                }
+                _ => {
+                    lists.insert(row, list.as_str());
+                }
+            }
+        }
+    }
+
+    let scalar = |name: &str| match data.get(name) {
+        Some(TemplateValue::Value(v)) => Some(v.clone()),
+        _ => None,
+    };
+
+    // bottom up, the repeated rows move everything below.
+    for (row, list) in lists.into_iter().rev() {
+        let Some(TemplateValue::List(entries)) = data.get(list) else {
+            continue;
+        };
+        let field = |name: &str| {
+            name.strip_prefix(list)
+                .and_then(|v| v.strip_prefix('.'))
+                .map(|v| v.to_string())
+        };
+
+        if entries.is_empty() {
+            let lookup = |name: &str| match field(name) {
+                Some(_) => Some(Value::Empty),
+                None => scalar(name),
+            };
+            let sheet = book.sheet_mut(idx);
+            for (_, cell) in sheet.data.range_mut((row, 0)..=(row, u32::MAX)) {
+                fill_value(&mut cell.value, &lookup);
+            }
+            continue;
+        }
+
+        book.repeat_row(idx, row, entries.len() as u32);
+        let sheet = book.sheet_mut(idx);
+        for (i, entry) in entries.iter().enumerate() {
+            let lookup = |name: &str| match field(name) {
+                Some(field) => entry.get(&field).cloned(),
+                None => scalar(name),
+            };
+            let row = row + i as u32;
+            for (_, cell) in sheet.data.range_mut((row, 0)..=(row, u32::MAX)) {
+                fill_value(&mut cell.value, &lookup);
+            }
+        }
+    }
+
+    for cell in book.sheet_mut(idx).data.values_mut() {
+        fill_value(&mut cell.value, &scalar);
+    }
+
+    Ok(())
+}
+
+// Finds the next placeholder. Returns start and end of the placeholder
+// and the trimmed name.
+fn next_placeholder(text: &str) -> Option<(usize, usize, &str)> {
+    let start = text.find("{{")?;
+    let len = text[start + 2..].find("}}")?;
+    let end = start + 2 + len + 2;
+    Some((start, end, text[start + 2..end - 2].trim()))
+}
+
+fn fill_value(value: &mut Value, lookup: &dyn Fn(&str) -> Option<Value>) {
+    match value {
+        Value::Text(text) => {
+            let whole = next_placeholder(text.trim())
+                .filter(|(start, end, _)| *start == 0 && *end == text.trim().len())
+                .and_then(|(_, _, name)| lookup(name));
+            if let Some(new_value) = whole {
+                *value = new_value;
+            } else if let Some(new_text) = replace_placeholders(text, lookup) {
+                *text = new_text.into();
+            }
+        }
+        Value::TextXml(tags) => {
+            for tag in tags {
+                fill_tag(tag, lookup);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn fill_tag(tag: &mut TextTag, lookup: &dyn Fn(&str) -> Option<Value>) {
+    for content in tag.content_mut() {
+        match content {
+            XmlContent::Text(text) => {
+                if let Some(new_text) = replace_placeholders(text, lookup) {
+                    *text = new_text;
+                }
+            }
+            XmlContent::Tag(tag) => fill_tag(tag, lookup),
+        }
+    }
+}
+
+// Replaces all placeholders with a value. Returns None if nothing
+// has been replaced.
+fn replace_placeholders(text: &str, lookup: &dyn Fn(&str) -> Option<Value>) -> Option<String> {
+    let mut buf = String::new();
+    let mut replaced = false;
+    let mut pos = 0;
+    while let Some((start, end, name)) = next_placeholder(&text[pos..]) {
+        buf.push_str(&text[pos..pos + start]);
+        if let Some(value) = lookup(name) {
+            buf.push_str(&plain_value(&value));
+            replaced = true;
+        } else {
+            buf.push_str(&text[pos + start..pos + end]);
+        }
+        pos += end;
+    }
+    buf.push_str(&text[pos..]);
+    replaced.then_some(buf)
+}
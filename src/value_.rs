@@ -1,6 +1,11 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::mem::size_of;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
-use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use get_size::GetSize;
 use get_size_derive::GetSize;
 use rust_decimal::prelude::FromPrimitive;
@@ -8,6 +13,7 @@ use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 
 use crate::text::TextTag;
+use crate::OdsError;
 
 /// Datatypes for the values. Only the discriminants of the Value enum.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, GetSize)]
@@ -25,6 +31,9 @@ pub enum ValueType {
 }
 
 /// Content-Values
+///
+/// Text is stored as `Arc<str>`. Sheets in a WorkBook share equal texts,
+/// see WorkBook::push_sheet().
 #[derive(Debug, Clone, PartialEq, Default)]
 #[allow(missing_docs)]
 pub enum Value {
@@ -34,9 +43,12 @@ pub enum Value {
     Number(f64),
     Percentage(f64),
     Currency(f64, Box<str>),
-    Text(String),
+    Text(Arc<str>),
     TextXml(Vec<TextTag>),
     DateTime(NaiveDateTime),
+    /// A datetime with a timezone. Only read with
+    /// OdsOptions::timezone(TimezonePolicy::Keep).
+    DateTimeTz(DateTime<FixedOffset>),
     TimeDuration(Duration),
 }
 
@@ -48,9 +60,13 @@ impl GetSize for Value {
             Value::Number(_) => 0,
             Value::Percentage(_) => 0,
             Value::Currency(_, v) => v.get_heap_size(),
-            Value::Text(v) => v.get_heap_size(),
+            Value::Text(v) => {
+                // A shared text is counted in equal parts by each reference.
+                (2 * size_of::<usize>() + v.len()) / Arc::strong_count(v)
+            }
             Value::TextXml(v) => v.get_heap_size(),
             Value::DateTime(_) => 0,
+            Value::DateTimeTz(_) => 0,
             Value::TimeDuration(_) => 0,
         }
     }
@@ -68,7 +84,7 @@ impl Value {
             Value::Text(_) => ValueType::Text,
             Value::TextXml(_) => ValueType::TextXml,
             Value::TimeDuration(_) => ValueType::TimeDuration,
-            Value::DateTime(_) => ValueType::DateTime,
+            Value::DateTime(_) | Value::DateTimeTz(_) => ValueType::DateTime,
         }
     }
 
@@ -315,7 +331,7 @@ impl Value {
     /// line-breaks are kept as \n.
     pub fn as_cow_str_or<'a>(&'a self, d: &'a str) -> Cow<'a, str> {
         match self {
-            Value::Text(s) => Cow::from(s),
+            Value::Text(s) => Cow::from(s.as_ref()),
             Value::TextXml(v) => {
                 let mut buf = String::new();
                 for t in v {
@@ -358,18 +374,33 @@ impl Value {
 
     /// Return the content as NaiveDateTime if the value is a DateTime.
     /// Default otherwise.
+    ///
+    /// For a DateTimeTz this is the local time without the timezone.
     pub fn as_datetime_or(&self, d: NaiveDateTime) -> NaiveDateTime {
         match self {
             Value::DateTime(dt) => *dt,
+            Value::DateTimeTz(dt) => dt.naive_local(),
             _ => d,
         }
     }
 
     /// Return the content as an optional NaiveDateTime if the value is
     /// a DateTime.
+    ///
+    /// For a DateTimeTz this is the local time without the timezone.
     pub fn as_datetime_opt(&self) -> Option<NaiveDateTime> {
         match self {
             Value::DateTime(dt) => Some(*dt),
+            Value::DateTimeTz(dt) => Some(dt.naive_local()),
+            _ => None,
+        }
+    }
+
+    /// Return the content as an optional DateTime if the value is
+    /// a DateTimeTz.
+    pub fn as_datetime_tz_opt(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Value::DateTimeTz(dt) => Some(*dt),
             _ => None,
         }
     }
@@ -379,6 +410,7 @@ impl Value {
     pub fn as_date_or(&self, d: NaiveDate) -> NaiveDate {
         match self {
             Value::DateTime(dt) => dt.date(),
+            Value::DateTimeTz(dt) => dt.date_naive(),
             _ => d,
         }
     }
@@ -388,6 +420,7 @@ impl Value {
     pub fn as_date_opt(&self) -> Option<NaiveDate> {
         match self {
             Value::DateTime(dt) => Some(dt.date()),
+            Value::DateTimeTz(dt) => Some(dt.date_naive()),
             _ => None,
         }
     }
@@ -400,7 +433,39 @@ impl Value {
         }
     }
 
+    /// Returns the currency code exactly as it was read or set.
+    pub fn currency_code(&self) -> Option<&str> {
+        match self {
+            Value::Currency(_, c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Returns the currency code as a Currency, if it is a valid
+    /// ISO 4217 code.
+    pub fn iso_currency(&self) -> Option<Currency> {
+        match self {
+            Value::Currency(_, c) => c.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns a display symbol for the currency code.
+    ///
+    /// This is only a lookup for common ISO 4217 codes, everything else
+    /// returns the code itself. The symbol actually displayed depends on
+    /// the value format, see ValueFormatCurrency::currency_symbol().
+    pub fn currency_symbol(&self) -> Option<&str> {
+        match self {
+            Value::Currency(_, c) => Some(iso_currency_symbol(c).unwrap_or(c)),
+            _ => None,
+        }
+    }
+
     /// Create a currency value.
+    ///
+    /// The currency code is taken as is. Use a Currency to be sure
+    /// it's a valid ISO 4217 code.
     #[allow(clippy::needless_range_loop)]
     pub fn new_currency<S: AsRef<str>>(cur: S, value: f64) -> Self {
         Value::Currency(value, cur.as_ref().into())
@@ -412,6 +477,102 @@ impl Value {
     }
 }
 
+/// ISO 4217 currency code, three uppercase ASCII letters.
+///
+/// Value::Currency itself stores the code as a string, as read from the
+/// file. This type can be used wherever a currency code is expected.
+///
+/// ```
+/// use spreadsheet_ods::{Currency, Value};
+///
+/// let eur: Currency = "EUR".parse().expect("iso code");
+/// let v = Value::new_currency(eur, 10.0);
+/// assert_eq!(v.iso_currency(), Some(eur));
+/// assert!("euro".parse::<Currency>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    /// Creates a currency from an ISO 4217 code.
+    ///
+    /// Only checks the format, not if the code is actually assigned.
+    pub fn new<S: AsRef<str>>(code: S) -> Result<Self, OdsError> {
+        let code = code.as_ref();
+        match code.as_bytes() {
+            [a, b, c] if code.bytes().all(|v| v.is_ascii_uppercase()) => Ok(Currency([*a, *b, *c])),
+            _ => Err(OdsError::Parse("invalid currency code", Some(code.into()))),
+        }
+    }
+
+    /// The currency code.
+    pub fn as_str(&self) -> &str {
+        // only ascii letters are accepted in new().
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    /// Display symbol for some common codes.
+    pub fn symbol(&self) -> Option<&'static str> {
+        iso_currency_symbol(self.as_str())
+    }
+}
+
+impl AsRef<str> for Currency {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::new(s)
+    }
+}
+
+#[cfg(feature = "iso_currency")]
+impl From<iso_currency::Currency> for Currency {
+    fn from(value: iso_currency::Currency) -> Self {
+        let code = value.code().as_bytes();
+        Currency([code[0], code[1], code[2]])
+    }
+}
+
+#[cfg(feature = "iso_currency")]
+impl TryFrom<Currency> for iso_currency::Currency {
+    type Error = OdsError;
+
+    fn try_from(value: Currency) -> Result<Self, Self::Error> {
+        iso_currency::Currency::from_code(value.as_str())
+            .ok_or_else(|| OdsError::Parse("unknown currency code", Some(value.to_string())))
+    }
+}
+
+/// Symbol for some ISO 4217 currency codes.
+pub(crate) fn iso_currency_symbol(code: &str) -> Option<&'static str> {
+    match code {
+        "EUR" => Some("€"),
+        "USD" => Some("$"),
+        "GBP" => Some("£"),
+        "JPY" | "CNY" => Some("¥"),
+        "INR" => Some("₹"),
+        "KRW" => Some("₩"),
+        "RUB" => Some("₽"),
+        "ILS" => Some("₪"),
+        "UAH" => Some("₴"),
+        "TRY" => Some("₺"),
+        "PLN" => Some("zł"),
+        _ => None,
+    }
+}
+
 /// currency value
 #[macro_export]
 macro_rules! currency {
@@ -436,19 +597,25 @@ impl From<()> for Value {
 
 impl From<&str> for Value {
     fn from(s: &str) -> Self {
-        Value::Text(s.to_string())
+        Value::Text(Arc::from(s))
     }
 }
 
 impl From<String> for Value {
     fn from(s: String) -> Self {
-        Value::Text(s)
+        Value::Text(Arc::from(s))
     }
 }
 
 impl From<&String> for Value {
     fn from(s: &String) -> Self {
-        Value::Text(s.to_string())
+        Value::Text(Arc::from(s.as_str()))
+    }
+}
+
+impl From<Arc<str>> for Value {
+    fn from(s: Arc<str>) -> Self {
+        Value::Text(s)
     }
 }
 
@@ -467,7 +634,7 @@ impl From<Vec<TextTag>> for Value {
 impl From<Option<&str>> for Value {
     fn from(s: Option<&str>) -> Self {
         if let Some(s) = s {
-            Value::Text(s.to_string())
+            Value::Text(Arc::from(s))
         } else {
             Value::Empty
         }
@@ -477,7 +644,7 @@ impl From<Option<&str>> for Value {
 impl From<Option<&String>> for Value {
     fn from(s: Option<&String>) -> Self {
         if let Some(s) = s {
-            Value::Text(s.to_string())
+            Value::Text(Arc::from(s.as_str()))
         } else {
             Value::Empty
         }
@@ -487,7 +654,7 @@ impl From<Option<&String>> for Value {
 impl From<Option<String>> for Value {
     fn from(s: Option<String>) -> Self {
         if let Some(s) = s {
-            Value::Text(s)
+            Value::Text(Arc::from(s))
         } else {
             Value::Empty
         }
@@ -595,6 +762,12 @@ impl From<Option<NaiveDateTime>> for Value {
     }
 }
 
+impl From<DateTime<FixedOffset>> for Value {
+    fn from(dt: DateTime<FixedOffset>) -> Self {
+        Value::DateTimeTz(dt)
+    }
+}
+
 impl From<NaiveDate> for Value {
     fn from(dt: NaiveDate) -> Self {
         Value::DateTime(dt.and_hms_opt(0, 0, 0).unwrap())
@@ -648,3 +821,72 @@ impl From<Option<Duration>> for Value {
         }
     }
 }
+
+/// Pool of the cell texts of a WorkBook.
+///
+/// The WorkBook and its sheets share one pool, equal texts are stored
+/// only once. Texts stay in the pool until shrink() or until the last
+/// sheet is dropped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TextPool {
+    texts: Arc<Mutex<HashSet<Arc<str>>>>,
+}
+
+impl GetSize for TextPool {
+    fn get_heap_size(&self) -> usize {
+        // The texts are counted by the values that use them.
+        0
+    }
+}
+
+impl TextPool {
+    /// Returns the shared text.
+    pub(crate) fn intern(&self, text: &str) -> Arc<str> {
+        let mut texts = self.texts.lock().expect("text pool");
+        Self::intern_in(&mut texts, text)
+    }
+
+    /// Replaces a text value with the shared text.
+    pub(crate) fn intern_value(&self, value: &mut Value) {
+        if let Value::Text(text) = value {
+            *text = self.intern(text);
+        }
+    }
+
+    /// Replaces all text values with the shared texts.
+    pub(crate) fn intern_values<'a>(&self, values: impl Iterator<Item = &'a mut Value>) {
+        let mut texts = self.texts.lock().expect("text pool");
+        for value in values {
+            if let Value::Text(text) = value {
+                *text = Self::intern_in(&mut texts, text);
+            }
+        }
+    }
+
+    fn intern_in(texts: &mut HashSet<Arc<str>>, text: &str) -> Arc<str> {
+        if let Some(shared) = texts.get(text) {
+            Arc::clone(shared)
+        } else {
+            let shared: Arc<str> = Arc::from(text);
+            texts.insert(Arc::clone(&shared));
+            shared
+        }
+    }
+
+    /// Both are the same pool.
+    pub(crate) fn same_pool(&self, other: &TextPool) -> bool {
+        Arc::ptr_eq(&self.texts, &other.texts)
+    }
+
+    /// Number of distinct texts.
+    pub(crate) fn len(&self) -> usize {
+        self.texts.lock().expect("text pool").len()
+    }
+
+    /// Removes the texts that are no longer used by any cell.
+    pub(crate) fn shrink(&self) {
+        let mut texts = self.texts.lock().expect("text pool");
+        texts.retain(|v| Arc::strong_count(v) > 1);
+        texts.shrink_to_fit();
+    }
+}
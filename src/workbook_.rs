@@ -2,44 +2,82 @@
 //! Workbook
 //!
 
+use chrono::NaiveDate;
 use get_size::GetSize;
 use get_size_derive::GetSize;
+use quick_xml::escape::escape;
 use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::fmt::Formatter;
 use std::hash::Hash;
+use std::mem;
+use std::ops::Deref;
+use std::slice;
+use std::sync::Arc;
 
 use icu_locid::{locale, Locale};
 
+use crate::attrmap2::AttrMap2;
+use crate::changes::{read_tracked_changes, TrackedChanges};
+use crate::color::Rgb;
+use crate::condition::Condition;
 use crate::config::Config;
 use crate::defaultstyles::{DefaultFormat, DefaultStyle};
+use crate::digest::workbook_digest;
+use crate::draw::DrawShape;
 use crate::ds::detach::{Detach, Detached};
+use crate::error::{OdsError, OdsWarning};
 use crate::format::ValueFormatTrait;
+use crate::formula::{map_refs, map_xml_refs, MapRefs, RenameSheets, RepeatRow};
 use crate::io::read::default_settings;
 use crate::io::NamespaceMap;
 use crate::manifest::Manifest;
 use crate::metadata::Metadata;
+use crate::sheet::Visibility;
 use crate::sheet_::Sheet;
+use crate::style::units::{convert_length_attr, LengthUnit, TextAlign, TextAlignSource};
 use crate::style::{
-    ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, GraphicStyleRef, MasterPage, MasterPageRef,
-    PageStyle, PageStyleRef, ParagraphStyle, ParagraphStyleRef, RowStyle, RowStyleRef, RubyStyle,
-    RubyStyleRef, TableStyle, TableStyleRef, TextStyle, TextStyleRef,
+    AnyStyle, AnyStyleMut, ColStyle, ColStyleRef, ComputedCellStyle, FillImage,
+    FillImageRef, FontFaceDecl, FontFaceUri, Gradient, GradientRef, GraphicStyle, GraphicStyleRef,
+    Hatch, HatchRef, MasterPage, MasterPageRef, PageStyle, PageStyleRef, ParagraphStyle,
+    ParagraphStyleRef, RowStyle, RowStyleRef, RubyStyle, RubyStyleRef, StyleOrigin, StyleUse,
+    TableStyle, TableStyleRef, TextStyle, TextStyleRef, Theme, ThemeColor,
 };
 use crate::validation::{Validation, ValidationRef};
-use crate::value_::ValueType;
+use crate::value_::{TextPool, Value, ValueType};
 use crate::xlink::{XLinkActuate, XLinkType};
 use crate::xmltree::{XmlContent, XmlTag};
 use crate::{
-    locale, CellStyle, CellStyleRef, HashMap, ValueFormatBoolean, ValueFormatCurrency,
-    ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage, ValueFormatRef, ValueFormatText,
-    ValueFormatTimeDuration,
+    format, locale, CellRange, CellStyle, CellStyleRef, HashMap, ValueFormatBoolean,
+    ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage,
+    ValueFormatRef, ValueFormatText, ValueFormatTimeDuration,
 };
 
+/// Text attributes that reference a font-face declaration.
+const FONT_NAME_ATTR: [&str; 3] = [
+    "style:font-name",
+    "style:font-name-asian",
+    "style:font-name-complex",
+];
+
+/// Container element of unknown style elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GetSize)]
+pub(crate) enum StylesPart {
+    Styles,
+    AutomaticStyles,
+    MasterStyles,
+}
+
 /// Book is the main structure for the Spreadsheet.
 #[derive(Clone, GetSize)]
 pub struct WorkBook {
     /// The data.
     pub(crate) sheets: Vec<Detach<Sheet>>,
+    /// Names of the detached sheets.
+    pub(crate) detached_names: HashMap<usize, String>,
+    /// Cell texts shared by all sheets.
+    pub(crate) text_pool: TextPool,
 
     /// ODS Version
     pub(crate) version: String,
@@ -64,6 +102,14 @@ pub struct WorkBook {
     pub(crate) rubystyles: HashMap<RubyStyleRef, RubyStyle>,
     pub(crate) graphicstyles: HashMap<GraphicStyleRef, GraphicStyle>,
 
+    /// Fill definitions for the graphic styles.
+    pub(crate) gradients: HashMap<GradientRef, Gradient>,
+    pub(crate) hatches: HashMap<HatchRef, Hatch>,
+    pub(crate) fill_images: HashMap<FillImageRef, FillImage>,
+
+    /// Color theme.
+    pub(crate) theme: Option<Theme>,
+
     /// Value-styles are actual formatting instructions for various datatypes.
     /// Represents the various number:xxx-style elements.
     pub(crate) formats_boolean: HashMap<String, ValueFormatBoolean>,
@@ -90,6 +136,12 @@ pub struct WorkBook {
     pub(crate) config: Detach<Config>,
     /// User modifiable config.
     pub(crate) workbook_config: WorkBookConfig,
+    /// Settings for formula calculation.
+    pub(crate) calculation_settings: CalculationSettings,
+    /// Named views.
+    pub(crate) named_views: Vec<NamedView>,
+    /// Tagged cells.
+    pub(crate) cell_tags: Vec<CellTag>,
     /// Keeps all the namespaces.
     pub(crate) xmlns: HashMap<String, NamespaceMap>,
 
@@ -101,6 +153,11 @@ pub struct WorkBook {
 
     /// other stuff ...
     pub(crate) extra: Vec<XmlTag>,
+    /// Unknown style elements, kept with OdsOptions::preserve_unknown().
+    pub(crate) extra_styles: Vec<(StyleOrigin, StylesPart, XmlTag)>,
+
+    /// Problems found when reading.
+    pub(crate) warnings: Vec<OdsWarning>,
 }
 
 impl fmt::Debug for WorkBook {
@@ -136,6 +193,18 @@ impl fmt::Debug for WorkBook {
         for s in self.graphicstyles.values() {
             writeln!(f, "{:?}", s)?;
         }
+        for s in self.gradients.values() {
+            writeln!(f, "{:?}", s)?;
+        }
+        for s in self.hatches.values() {
+            writeln!(f, "{:?}", s)?;
+        }
+        for s in self.fill_images.values() {
+            writeln!(f, "{:?}", s)?;
+        }
+        if let Some(theme) = &self.theme {
+            writeln!(f, "{:?}", theme)?;
+        }
         for s in self.formats_boolean.values() {
             writeln!(f, "{:?}", s)?;
         }
@@ -170,6 +239,13 @@ impl fmt::Debug for WorkBook {
             writeln!(f, "{:?}", s)?;
         }
         writeln!(f, "{:?}", &self.workbook_config)?;
+        writeln!(f, "{:?}", &self.calculation_settings)?;
+        for v in &self.named_views {
+            writeln!(f, "{:?}", v)?;
+        }
+        for v in &self.cell_tags {
+            writeln!(f, "{:?}", v)?;
+        }
         for v in self.manifest.values() {
             writeln!(f, "extras {:?}", v)?;
         }
@@ -177,6 +253,12 @@ impl fmt::Debug for WorkBook {
         for xtr in &self.extra {
             writeln!(f, "extras {:?}", xtr)?;
         }
+        for xtr in &self.extra_styles {
+            writeln!(f, "extra styles {:?}", xtr)?;
+        }
+        for w in &self.warnings {
+            writeln!(f, "warning {}", w)?;
+        }
         Ok(())
     }
 }
@@ -236,6 +318,323 @@ fn auto_style_name<T>(
     style_name
 }
 
+/// Key for comparing styles. Contains the origin and all attributes
+/// in a canonical order.
+type StyleKey = (StyleOrigin, Vec<(usize, String, String)>);
+
+/// Creates the comparison key for the attributes of a style.
+fn style_key(origin: StyleOrigin, attrs: &[&AttrMap2]) -> StyleKey {
+    let mut key = Vec::new();
+    for (i, attr) in attrs.iter().enumerate() {
+        for (k, v) in attr.iter() {
+            key.push((i, k.to_string(), v.to_string()));
+        }
+    }
+    key.sort();
+    (origin, key)
+}
+
+/// Removes all styles with an identical key. The style with the
+/// lowest name is kept.
+///
+/// Returns a map from the removed styles to the kept ones.
+fn dedup_style_map<K, V>(
+    styles: &mut HashMap<K, V>,
+    style_key: impl Fn(&V) -> Option<StyleKey>,
+) -> HashMap<K, K>
+where
+    K: AsRef<str> + Hash + Eq + Clone,
+{
+    let mut names: Vec<K> = styles.keys().cloned().collect();
+    names.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+    let mut kept: HashMap<StyleKey, K> = HashMap::new();
+    let mut renamed = HashMap::new();
+    for name in names {
+        let Some(key) = styles.get(&name).and_then(&style_key) else {
+            continue;
+        };
+        if let Some(keep) = kept.get(&key) {
+            styles.remove(&name);
+            renamed.insert(name, keep.clone());
+        } else {
+            kept.insert(key, name);
+        }
+    }
+    renamed
+}
+
+/// Replaces a style reference according to the map.
+fn remap_style<K>(style: &mut Option<K>, renamed: &HashMap<K, K>)
+where
+    K: Hash + Eq + Clone,
+{
+    if let Some(new_style) = style.as_ref().and_then(|v| renamed.get(v)) {
+        *style = Some(new_style.clone());
+    }
+}
+
+/// Replaces the style name in the attribute according to the map.
+fn remap_attr<K>(attr: &mut AttrMap2, name: &str, renamed: &HashMap<K, K>)
+where
+    K: AsRef<str> + Borrow<str> + Hash + Eq,
+{
+    if let Some(new_name) = attr.attr(name).and_then(|v| renamed.get(v)) {
+        attr.set_attr(name, new_name.as_ref().to_string());
+    }
+}
+
+/// Replaces the font names of a text style.
+fn remap_fonts(attr: &mut AttrMap2, renamed: &HashMap<String, String>) {
+    remap_attr(attr, "style:font-name", renamed);
+    remap_attr(attr, "style:font-name-asian", renamed);
+    remap_attr(attr, "style:font-name-complex", renamed);
+}
+
+/// Replaces the paragraph and text styles used in the text.
+fn remap_text_styles(
+    text: &mut [XmlTag],
+    para_map: &HashMap<ParagraphStyleRef, ParagraphStyleRef>,
+    text_map: &HashMap<TextStyleRef, TextStyleRef>,
+) {
+    for tag in text {
+        match tag.name() {
+            "text:p" | "text:h" => remap_attr(tag.attrmap_mut(), "text:style-name", para_map),
+            "text:span" | "text:a" => remap_attr(tag.attrmap_mut(), "text:style-name", text_map),
+            _ => {}
+        }
+        for content in tag.content_mut() {
+            if let XmlContent::Tag(tag) = content {
+                remap_text_styles(slice::from_mut(tag), para_map, text_map);
+            }
+        }
+    }
+}
+
+/// Replaces the graphic and paragraph style of a drawing.
+fn remap_draw_styles(
+    attr: &mut AttrMap2,
+    graphic_map: &HashMap<GraphicStyleRef, GraphicStyleRef>,
+    para_map: &HashMap<ParagraphStyleRef, ParagraphStyleRef>,
+) {
+    remap_attr(attr, "draw:style-name", graphic_map);
+    remap_attr(attr, "draw:text-style-name", para_map);
+}
+
+/// Changes the references of the condition and the base cell.
+fn map_validation_refs(valid: &mut Validation, m: &dyn MapRefs) {
+    let condition = valid.condition().to_string();
+    let new_condition = map_refs(&condition, m);
+    if new_condition != condition {
+        valid.set_condition(Condition::new(new_condition));
+    }
+    let mut base_cell = valid.base_cell().clone();
+    if m.cellref(&mut base_cell) {
+        valid.set_base_cell(base_cell);
+    }
+}
+
+/// Collects the named expressions of the other workbook that are added.
+/// Names that are already used are skipped, or fail with
+/// MergePolicy::Error.
+fn merge_named_expressions(
+    own: &[XmlTag],
+    other: &mut [XmlTag],
+    policy: MergePolicy,
+) -> Result<Vec<XmlTag>, OdsError> {
+    let named_tags = |v: &XmlTag| v.name() == "table:named-expressions";
+    let used: HashSet<&str> = own
+        .iter()
+        .filter(|v| named_tags(v))
+        .flat_map(|v| v.content())
+        .filter_map(|v| match v {
+            XmlContent::Tag(t) => t.get_attr("table:name"),
+            XmlContent::Text(_) => None,
+        })
+        .collect();
+
+    let mut add = Vec::new();
+    for tag in other.iter_mut().filter(|v| named_tags(v)) {
+        for content in mem::take(tag.content_mut()) {
+            if let XmlContent::Tag(expr) = content {
+                let name = expr.get_attr("table:name").unwrap_or_default();
+                if used.contains(name) {
+                    if policy == MergePolicy::Error {
+                        return Err(OdsError::Ods(format!(
+                            "Duplicate named expression {}",
+                            name
+                        )));
+                    }
+                    continue;
+                }
+                add.push(expr);
+            }
+        }
+    }
+    Ok(add)
+}
+
+/// Comparison key for a paragraph style. Contains the tab stops too.
+fn paragraphstyle_key(s: &ParagraphStyle) -> StyleKey {
+    let mut key = style_key(
+        s.origin(),
+        &[s.attrmap(), s.paragraphstyle(), s.textstyle()],
+    );
+    if let Some(tabstops) = s.tabstops() {
+        for (i, tabstop) in tabstops.iter().enumerate() {
+            for (k, v) in tabstop.attrmap().iter() {
+                key.1.push((3 + i, k.to_string(), v.to_string()));
+            }
+        }
+    }
+    key
+}
+
+/// Decides for each style of the other workbook whether it is added,
+/// shared with an identical style or renamed. Renamed styles are recorded
+/// in the map.
+///
+/// Returns the styles that must be added with their final name.
+fn merge_plan<K, V>(
+    own: &HashMap<K, V>,
+    mut other: HashMap<K, V>,
+    used: &mut HashSet<String>,
+    policy: MergePolicy,
+    same: impl Fn(&V, &V) -> bool,
+    renamed: &mut HashMap<K, K>,
+) -> Result<Vec<(K, V)>, OdsError>
+where
+    K: AsRef<str> + From<String> + Hash + Eq + Clone,
+{
+    let mut names: Vec<K> = other.keys().cloned().collect();
+    names.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+    let mut add = Vec::new();
+    for name in names {
+        let Some(style) = other.remove(&name) else {
+            continue;
+        };
+        let new_name = if !used.contains(name.as_ref()) {
+            name.clone()
+        } else {
+            match own.get(&name) {
+                Some(own_style) if same(own_style, &style) => continue,
+                Some(_) if policy == MergePolicy::KeepExisting => continue,
+                Some(_) if policy == MergePolicy::Error => {
+                    return Err(OdsError::Ods(format!(
+                        "Duplicate style name {}",
+                        name.as_ref()
+                    )));
+                }
+                // Rename, or the name is used by a different kind of
+                // value format.
+                _ => K::from(merge_name(name.as_ref(), used)),
+            }
+        };
+        used.insert(new_name.as_ref().to_string());
+        if new_name != name {
+            renamed.insert(name, new_name.clone());
+        }
+        add.push((new_name, style));
+    }
+    Ok(add)
+}
+
+/// Appends "_2", "_3" ... until the name is not used.
+fn merge_name(name: &str, used: &HashSet<String>) -> String {
+    let mut n = 2;
+    loop {
+        let test = format!("{}_{}", name, n);
+        if !used.contains(&test) {
+            return test;
+        }
+        n += 1;
+    }
+}
+
+/// Names of all styles of one kind.
+fn style_names<K: AsRef<str>, V>(styles: &HashMap<K, V>) -> HashSet<String> {
+    styles.keys().map(|v| v.as_ref().to_string()).collect()
+}
+
+/// Compares two value formats.
+fn same_format<T: ValueFormatTrait>(a: &T, b: &T) -> bool {
+    let stylemaps = |f: &T| {
+        f.stylemaps().map(|v| {
+            v.iter()
+                .map(|m| (m.condition().to_string(), m.applied_style().clone()))
+                .collect::<Vec<_>>()
+        })
+    };
+    style_key(a.origin(), &[a.attrmap(), a.textstyle()])
+        == style_key(b.origin(), &[b.attrmap(), b.textstyle()])
+        && a.parts() == b.parts()
+        && stylemaps(a) == stylemaps(b)
+}
+
+/// Comparison key for a cell style. Contains the style maps too.
+fn cellstyle_key(s: &CellStyle) -> StyleKey {
+    let mut key = style_key(
+        s.origin(),
+        &[
+            s.attrmap(),
+            s.cellstyle(),
+            s.paragraphstyle(),
+            s.textstyle(),
+        ],
+    );
+    if let Some(stylemaps) = s.stylemaps() {
+        for m in stylemaps {
+            key.1.push((
+                4,
+                m.condition().to_string(),
+                m.applied_style().as_str().to_string(),
+            ));
+        }
+    }
+    key
+}
+
+/// Adds the value formats and records the new names.
+fn merge_formats<T: ValueFormatTrait>(
+    own: &mut HashMap<String, T>,
+    add: Vec<(String, T)>,
+    renamed: &HashMap<String, String>,
+) {
+    for (name, mut format) in add {
+        format.set_name(name.clone());
+        for stylemap in format.stylemaps_mut() {
+            if let Some(new_name) = renamed.get(stylemap.applied_style()) {
+                stylemap.set_applied_style(new_name);
+            }
+        }
+        own.insert(name, format);
+    }
+}
+
+/// Attachments must stay within the package and can't replace
+/// any of the files written by the library.
+fn valid_attachment_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.starts_with("META-INF/")
+        && !path.contains('\\')
+        && !path.contains(':')
+        && !Manifest::is_reserved(path)
+        && path
+            .split('/')
+            .all(|v| !v.is_empty() && v != "." && v != "..")
+}
+
+/// Prefixes the name of a value format.
+fn prefixed<T: ValueFormatTrait>(mut format: T, prefix: &str) -> T {
+    if !prefix.is_empty() {
+        let name = format!("{}{}", prefix, format.name());
+        format.set_name(name);
+    }
+    format
+}
+
 impl Default for WorkBook {
     fn default() -> Self {
         WorkBook::new(locale!("en"))
@@ -249,6 +648,7 @@ impl WorkBook {
     pub fn new_empty() -> Self {
         WorkBook {
             sheets: Default::default(),
+            detached_names: Default::default(),
             version: "1.3".to_string(),
             fonts: Default::default(),
             autonum: Default::default(),
@@ -262,6 +662,10 @@ impl WorkBook {
             textstyles: Default::default(),
             rubystyles: Default::default(),
             graphicstyles: Default::default(),
+            gradients: Default::default(),
+            hatches: Default::default(),
+            fill_images: Default::default(),
+            theme: None,
             formats_boolean: Default::default(),
             formats_number: Default::default(),
             formats_percentage: Default::default(),
@@ -275,10 +679,16 @@ impl WorkBook {
             validations: Default::default(),
             config: default_settings(),
             workbook_config: Default::default(),
+            calculation_settings: Default::default(),
+            named_views: Default::default(),
+            cell_tags: Default::default(),
             extra: vec![],
+            extra_styles: vec![],
             manifest: Default::default(),
             metadata: Default::default(),
             xmlns: Default::default(),
+            warnings: Default::default(),
+            text_pool: Default::default(),
         }
     }
 
@@ -295,6 +705,17 @@ impl WorkBook {
         wb
     }
 
+    /// Creates a new workbook like new(), but all the names of the default
+    /// formats and styles are prefixed with the given string.
+    ///
+    /// Use this if the default names collide with your own formats.
+    /// The actual names can be queried with def_style() and def_format().
+    pub fn new_prefixed(locale: Locale, prefix: &str) -> Self {
+        let mut wb = WorkBook::new_empty();
+        wb.locale_settings_prefixed(locale, prefix);
+        wb
+    }
+
     /// Creates a set of default formats and styles for every value-type.
     ///
     /// If the locale is not supported no ValueFormat's are set and all
@@ -302,50 +723,59 @@ impl WorkBook {
     ///
     /// The available locales can be activated via feature-flags.
     pub fn locale_settings(&mut self, locale: Locale) {
+        self.locale_settings_prefixed(locale, "");
+    }
+
+    /// Creates a set of default formats and styles for every value-type.
+    /// All names are prefixed with the given string.
+    pub fn locale_settings_prefixed(&mut self, locale: Locale, prefix: &str) {
         if let Some(lf) = locale::localized_format(locale) {
-            self.add_boolean_format(lf.boolean_format());
-            self.add_number_format(lf.number_format());
-            self.add_percentage_format(lf.percentage_format());
-            self.add_currency_format(lf.currency_format());
-            self.add_datetime_format(lf.date_format());
-            self.add_datetime_format(lf.datetime_format());
-            self.add_datetime_format(lf.time_of_day_format());
-            self.add_timeduration_format(lf.time_interval_format());
-        }
-
-        self.add_cellstyle(CellStyle::new(DefaultStyle::bool(), &DefaultFormat::bool()));
-        self.add_cellstyle(CellStyle::new(
-            DefaultStyle::number(),
-            &DefaultFormat::number(),
-        ));
-        self.add_cellstyle(CellStyle::new(
-            DefaultStyle::percent(),
-            &DefaultFormat::percent(),
-        ));
-        self.add_cellstyle(CellStyle::new(
-            DefaultStyle::currency(),
-            &DefaultFormat::currency(),
-        ));
-        self.add_cellstyle(CellStyle::new(DefaultStyle::date(), &DefaultFormat::date()));
-        self.add_cellstyle(CellStyle::new(
-            DefaultStyle::datetime(),
-            &DefaultFormat::datetime(),
-        ));
-        self.add_cellstyle(CellStyle::new(
-            DefaultStyle::time_of_day(),
-            &DefaultFormat::time_of_day(),
-        ));
-        self.add_cellstyle(CellStyle::new(
-            DefaultStyle::time_interval(),
-            &DefaultFormat::time_interval(),
-        ));
+            self.add_boolean_format(prefixed(lf.boolean_format(), prefix));
+            self.add_number_format(prefixed(lf.number_format(), prefix));
+            self.add_percentage_format(prefixed(lf.percentage_format(), prefix));
+            self.add_currency_format(prefixed(lf.currency_format(), prefix));
+            self.add_datetime_format(prefixed(lf.date_format(), prefix));
+            self.add_datetime_format(prefixed(lf.datetime_format(), prefix));
+            self.add_datetime_format(prefixed(lf.time_of_day_format(), prefix));
+            self.add_timeduration_format(prefixed(lf.time_interval_format(), prefix));
+        }
+
+        let defaults = [
+            (DefaultStyle::bool(), DefaultFormat::bool()),
+            (DefaultStyle::number(), DefaultFormat::number()),
+            (DefaultStyle::percent(), DefaultFormat::percent()),
+            (DefaultStyle::currency(), DefaultFormat::currency()),
+            (DefaultStyle::date(), DefaultFormat::date()),
+            (DefaultStyle::datetime(), DefaultFormat::datetime()),
+            (DefaultStyle::time_of_day(), DefaultFormat::time_of_day()),
+            (
+                DefaultStyle::time_interval(),
+                DefaultFormat::time_interval(),
+            ),
+        ];
+        for (style, format) in defaults {
+            let format = ValueFormatRef::from(format!("{}{}", prefix, format.as_str()));
+            self.add_cellstyle(CellStyle::new(
+                format!("{}{}", prefix, style.as_str()),
+                &format,
+            ));
+        }
 
-        self.add_def_style(ValueType::Boolean, DefaultStyle::bool());
-        self.add_def_style(ValueType::Number, DefaultStyle::number());
-        self.add_def_style(ValueType::Percentage, DefaultStyle::percent());
-        self.add_def_style(ValueType::Currency, DefaultStyle::currency());
-        self.add_def_style(ValueType::DateTime, DefaultStyle::date());
-        self.add_def_style(ValueType::TimeDuration, DefaultStyle::time_interval());
+        for value_type in [
+            ValueType::Boolean,
+            ValueType::Number,
+            ValueType::Percentage,
+            ValueType::Currency,
+            ValueType::DateTime,
+            ValueType::TimeDuration,
+        ] {
+            if let Some(style) = DefaultStyle::for_value_type(value_type) {
+                self.add_def_style(
+                    value_type,
+                    CellStyleRef::from(format!("{}{}", prefix, style.as_str())),
+                );
+            }
+        }
     }
 
     /// ODS version. Defaults to 1.3.
@@ -369,6 +799,117 @@ impl WorkBook {
         &mut self.workbook_config
     }
 
+    /// Settings for formula calculation.
+    pub fn calculation_settings(&self) -> &CalculationSettings {
+        &self.calculation_settings
+    }
+
+    /// Settings for formula calculation.
+    pub fn calculation_settings_mut(&mut self) -> &mut CalculationSettings {
+        &mut self.calculation_settings
+    }
+
+    /// Adds a named view. Replaces an existing view with the same name.
+    pub fn add_named_view(&mut self, view: NamedView) {
+        if let Some(v) = self.named_views.iter_mut().find(|v| v.name == view.name) {
+            *v = view;
+        } else {
+            self.named_views.push(view);
+        }
+    }
+
+    /// Removes a named view.
+    pub fn remove_named_view(&mut self, name: &str) -> Option<NamedView> {
+        let idx = self.named_views.iter().position(|v| v.name == name)?;
+        Some(self.named_views.remove(idx))
+    }
+
+    /// Iterates the named views.
+    pub fn iter_named_views(&self) -> impl Iterator<Item = &NamedView> {
+        self.named_views.iter()
+    }
+
+    /// Returns the named view.
+    pub fn named_view(&self, name: &str) -> Option<&NamedView> {
+        self.named_views.iter().find(|v| v.name == name)
+    }
+
+    /// Returns the mutable named view.
+    pub fn named_view_mut(&mut self, name: &str) -> Option<&mut NamedView> {
+        self.named_views.iter_mut().find(|v| v.name == name)
+    }
+
+    /// Adds a cell tag. Replaces an existing tag with the same name.
+    pub fn add_cell_tag(&mut self, tag: CellTag) {
+        if let Some(v) = self.cell_tags.iter_mut().find(|v| v.name == tag.name) {
+            *v = tag;
+        } else {
+            self.cell_tags.push(tag);
+        }
+    }
+
+    /// Removes a cell tag.
+    pub fn remove_cell_tag(&mut self, name: &str) -> Option<CellTag> {
+        let idx = self.cell_tags.iter().position(|v| v.name == name)?;
+        Some(self.cell_tags.remove(idx))
+    }
+
+    /// Iterates the cell tags.
+    pub fn iter_cell_tags(&self) -> impl Iterator<Item = &CellTag> {
+        self.cell_tags.iter()
+    }
+
+    /// Returns the cell tag.
+    pub fn cell_tag(&self, name: &str) -> Option<&CellTag> {
+        self.cell_tags.iter().find(|v| v.name == name)
+    }
+
+    /// Makes the named view the startup view of the workbook.
+    ///
+    /// Sets the active table and the cursor, scroll-position and zoom of
+    /// this table. The hidden columns of the view are collapsed, columns
+    /// hidden by any other view of the same table are made visible again.
+    pub fn activate_named_view(&mut self, name: &str) -> Result<(), OdsError> {
+        let Some(view) = self.named_view(name).cloned() else {
+            return Err(OdsError::Ods(format!("Named view {} not found.", name)));
+        };
+        let Some(idx) = self.sheet_idx(&view.active_table) else {
+            return Err(OdsError::Ods(format!(
+                "Table {} of named view {} not found.",
+                view.active_table, name
+            )));
+        };
+
+        let mut sheet = self.detach_sheet(idx);
+        for other in self
+            .named_views
+            .iter()
+            .filter(|v| v.name != view.name && v.active_table == view.active_table)
+        {
+            for col in &other.hidden_cols {
+                if !view.hidden_cols.contains(col) {
+                    sheet.set_col_visible(*col, Visibility::Visible);
+                }
+            }
+        }
+        for col in &view.hidden_cols {
+            sheet.set_col_visible(*col, Visibility::Collapsed);
+        }
+
+        let config = sheet.config_mut();
+        config.cursor_x = view.cursor_x;
+        config.cursor_y = view.cursor_y;
+        config.position_left = view.first_col;
+        config.position_bottom = view.first_row;
+        config.zoom_type = 0;
+        config.zoom_value = view.zoom_value;
+        self.attach_sheet(sheet);
+
+        self.workbook_config.active_table = view.active_table;
+
+        Ok(())
+    }
+
     /// Number of sheets.
     pub fn num_sheets(&self) -> usize {
         self.sheets.len()
@@ -397,6 +938,7 @@ impl WorkBook {
     /// Panics if the sheet has already been detached.
     /// Panics if n is out of bounds.
     pub fn detach_sheet(&mut self, n: usize) -> Detached<usize, Sheet> {
+        self.detached_names.insert(n, self.sheets[n].name().to_string());
         self.sheets[n].detach(n)
     }
 
@@ -406,6 +948,7 @@ impl WorkBook {
     ///
     /// Panics if n is out of bounds.
     pub fn attach_sheet(&mut self, sheet: Detached<usize, Sheet>) {
+        self.detached_names.remove(&Detached::key(&sheet));
         self.sheets[Detached::key(&sheet)].attach(sheet)
     }
 
@@ -433,15 +976,56 @@ impl WorkBook {
     }
 
     /// Inserts the sheet at the given position.
-    pub fn insert_sheet(&mut self, i: usize, sheet: Sheet) {
+    ///
+    /// A duplicate name is accepted, but recorded in warnings(). Use
+    /// insert_sheet_checked() to reject or rename a sheet whose name is
+    /// already used.
+    pub fn insert_sheet(&mut self, i: usize, mut sheet: Sheet) {
+        self.warn_duplicate_sheet_name(sheet.name());
+        self.share_texts(&mut sheet);
         self.sheets.insert(i, sheet.into());
     }
 
     /// Appends a sheet.
-    pub fn push_sheet(&mut self, sheet: Sheet) {
+    ///
+    /// A duplicate name is accepted, but recorded in warnings(). Use
+    /// push_sheet_checked() to reject or rename a sheet whose name is
+    /// already used.
+    ///
+    /// Equal cell texts of all sheets are shared. The texts of the sheet
+    /// are replaced with the shared ones, and later set_value() calls on
+    /// the sheet use them too.
+    pub fn push_sheet(&mut self, mut sheet: Sheet) {
+        self.warn_duplicate_sheet_name(sheet.name());
+        self.share_texts(&mut sheet);
         self.sheets.push(sheet.into());
     }
 
+    // Replaces the texts of the sheet with the shared texts.
+    fn share_texts(&self, sheet: &mut Sheet) {
+        if !sheet
+            .text_pool
+            .as_ref()
+            .is_some_and(|v| v.same_pool(&self.text_pool))
+        {
+            self.text_pool
+                .intern_values(sheet.data.values_mut().map(|v| &mut v.value));
+            sheet.text_pool = Some(self.text_pool.clone());
+        }
+    }
+
+    /// Number of distinct cell texts shared by the sheets.
+    ///
+    /// Texts that are no longer used stay until shrink_texts().
+    pub fn text_count(&self) -> usize {
+        self.text_pool.len()
+    }
+
+    /// Drops the shared texts that are no longer used by any cell.
+    pub fn shrink_texts(&mut self) {
+        self.text_pool.shrink();
+    }
+
     /// Removes a sheet from the table.
     ///
     /// Panics
@@ -451,6 +1035,713 @@ impl WorkBook {
         self.sheets.remove(n).take()
     }
 
+    /// Appends a sheet. If the name of the sheet is already used the
+    /// policy decides whether this is an error or the sheet is renamed.
+    pub fn push_sheet_checked(
+        &mut self,
+        mut sheet: Sheet,
+        policy: SheetNamePolicy,
+    ) -> Result<(), OdsError> {
+        self.check_sheet_name(&mut sheet, None, policy)?;
+        self.share_texts(&mut sheet);
+        self.sheets.push(sheet.into());
+        Ok(())
+    }
+
+    /// Inserts the sheet at the given position. If the name of the sheet
+    /// is already used the policy decides whether this is an error or the
+    /// sheet is renamed.
+    pub fn insert_sheet_checked(
+        &mut self,
+        i: usize,
+        mut sheet: Sheet,
+        policy: SheetNamePolicy,
+    ) -> Result<(), OdsError> {
+        self.check_sheet_name(&mut sheet, None, policy)?;
+        self.share_texts(&mut sheet);
+        self.sheets.insert(i, sheet.into());
+        Ok(())
+    }
+
+    /// Reattaches the sheet in the place it was before. If the sheet
+    /// has been renamed to a name that is already used by another sheet,
+    /// the policy decides whether this is an error or the sheet is
+    /// renamed again.
+    ///
+    /// If this fails the sheet is not attached and returned with the
+    /// error.
+    ///
+    /// Panics
+    ///
+    /// Panics if n is out of bounds.
+    #[allow(clippy::result_large_err)]
+    pub fn attach_sheet_checked(
+        &mut self,
+        mut sheet: Detached<usize, Sheet>,
+        policy: SheetNamePolicy,
+    ) -> Result<(), (Detached<usize, Sheet>, OdsError)> {
+        let idx = Detached::key(&sheet);
+        if let Err(err) = self.check_sheet_name(&mut sheet, Some(idx), policy) {
+            return Err((sheet, err));
+        }
+        self.detached_names.remove(&idx);
+        self.sheets[idx].attach(sheet);
+        Ok(())
+    }
+
+    /// Renames all sheets whose name is already used by a sheet before
+    /// them. Some producers don't enforce unique names, but references
+    /// and the settings rely on them.
+    ///
+    /// References within a renamed sheet that use its old name are
+    /// changed to the new name, so they still point to the same sheet.
+    /// References from anywhere else can't tell the duplicates apart
+    /// and keep pointing to the first sheet with that name.
+    ///
+    /// Returns the number of renamed sheets.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet is detached.
+    pub fn ensure_unique_sheet_names(&mut self) -> usize {
+        let mut renamed = 0;
+        for idx in 1..self.sheets.len() {
+            let name = self.sheets[idx].name();
+            if self.sheets[..idx].iter().any(|v| v.name() == name) {
+                let new_name = self.unique_sheet_name(name);
+                let mut rename = HashMap::new();
+                rename.insert(name.to_string(), new_name.clone());
+                let sheet = self.sheets[idx].as_mut();
+                sheet.map_refs(&RenameSheets(&rename));
+                sheet.set_name(new_name);
+                renamed += 1;
+            }
+        }
+        renamed
+    }
+
+    /// Returns the name if no sheet uses it yet. Otherwise appends
+    /// " (2)", " (3)" ... until it finds an unused name.
+    ///
+    /// Detached sheets count with the name they had when they were
+    /// detached.
+    pub fn unique_sheet_name<S: AsRef<str>>(&self, name: S) -> String {
+        let name = name.as_ref();
+        if !self.sheet_name_used(name, None) {
+            return name.to_string();
+        }
+        let mut n = 2;
+        loop {
+            let test = format!("{} ({})", name, n);
+            if !self.sheet_name_used(&test, None) {
+                return test;
+            }
+            n += 1;
+        }
+    }
+
+    // Is the name used by any sheet other than skip.
+    // Empty names are never used, they are generated when the document
+    // is opened.
+    fn sheet_name_used(&self, name: &str, skip: Option<usize>) -> bool {
+        !name.is_empty()
+            && self.sheets.iter().enumerate().any(|(idx, sheet)| {
+                Some(idx) != skip
+                    && if sheet.is_detached() {
+                        self.detached_names.get(&idx).map(|v| v.as_str()) == Some(name)
+                    } else {
+                        sheet.name() == name
+                    }
+            })
+    }
+
+    fn warn_duplicate_sheet_name(&mut self, name: &str) {
+        if self.sheet_name_used(name, None) {
+            self.warnings.push(OdsWarning::Recovered {
+                context: format!("sheet {}", name),
+                message: "Duplicate sheet name".to_string(),
+            });
+        }
+    }
+
+    fn check_sheet_name(
+        &self,
+        sheet: &mut Sheet,
+        skip: Option<usize>,
+        policy: SheetNamePolicy,
+    ) -> Result<(), OdsError> {
+        if self.sheet_name_used(sheet.name(), skip) {
+            match policy {
+                SheetNamePolicy::Error => {
+                    return Err(OdsError::Ods(format!(
+                        "Duplicate sheet name {}",
+                        sheet.name()
+                    )));
+                }
+                SheetNamePolicy::Rename => {
+                    let new_name = self.unique_sheet_name(sheet.name());
+                    sheet.set_name(new_name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all formulas and keeps the last calculated values.
+    /// Runs Sheet::strip_formulas() for all sheets.
+    pub fn strip_formulas(&mut self) {
+        for sheet in self.sheets.iter_mut() {
+            sheet.as_mut().strip_formulas();
+        }
+    }
+
+    /// Removes the calculated values of all cells with a formula.
+    /// Runs Sheet::strip_cached_values() for all sheets.
+    pub fn strip_cached_values(&mut self) {
+        for sheet in self.sheets.iter_mut() {
+            sheet.as_mut().strip_cached_values();
+        }
+    }
+
+    /// Refreshes all sheets that are linked to the given document.
+    /// The linked document must be read by the caller.
+    ///
+    /// Returns the number of refreshed sheets.
+    /// See Sheet::refresh_table_source().
+    pub fn refresh_table_sources(
+        &mut self,
+        href: &str,
+        source: &WorkBook,
+    ) -> Result<usize, OdsError> {
+        let mut count = 0;
+        for sheet in self.sheets.iter_mut() {
+            let sheet = sheet.as_mut();
+            if sheet.table_source().map(|v| v.href.as_str()) == Some(href) {
+                sheet.refresh_table_source(source)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Tracked changes of the document, if there are any.
+    ///
+    /// This is a read-only view. The changes are written back unmodified.
+    pub fn tracked_changes(&self) -> Result<Option<TrackedChanges>, OdsError> {
+        match self
+            .extra
+            .iter()
+            .find(|v| v.name() == "table:tracked-changes")
+        {
+            Some(tag) => Ok(Some(read_tracked_changes(tag)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Hash of the logical content of all sheets. Cell values, formulas
+    /// and cell styles are included, styles are compared by value.
+    /// Metadata, settings and style names are ignored. Repeated cells and
+    /// rows count as if each was written on its own.
+    ///
+    /// Two workbooks with the same digest very likely have the same content.
+    /// The digest is stable across runs and platforms.
+    pub fn content_digest(&self) -> u64 {
+        workbook_digest(self)
+    }
+
+    /// Stacks the data of the named sheets into one new sheet.
+    ///
+    /// The header row of each sheet names the columns. Columns with the
+    /// same header text are combined, in the order of their first
+    /// appearance. The first column of the result contains the name of
+    /// the source sheet. Rows above the header row, columns without a header
+    /// and empty rows are skipped.
+    ///
+    /// Values and cell styles are copied, formulas are not.
+    pub fn consolidate_sheets<S: AsRef<str>>(
+        &self,
+        sheet_names: &[S],
+        header_row: u32,
+    ) -> Result<Sheet, OdsError> {
+        let mut result = Sheet::new("consolidated");
+        result.set_value(0, 0, "sheet");
+
+        let mut headers: Vec<&str> = Vec::new();
+        let mut row = 1;
+        for name in sheet_names {
+            let sheet = match self.sheet_idx(name) {
+                Some(idx) => self.sheet(idx),
+                None => return Err(OdsError::Ods(format!("Sheet {} not found", name.as_ref()))),
+            };
+
+            // source column -> result column
+            let mut columns: HashMap<u32, u32> = HashMap::new();
+            let mut last_row = None;
+            for ((cell_row, cell_col), cell) in sheet.iter() {
+                if cell_row < header_row {
+                    continue;
+                }
+                if cell_row == header_row {
+                    let header = cell.value.as_str_or("");
+                    if !header.is_empty() {
+                        let col = match headers.iter().position(|v| *v == header) {
+                            Some(col) => col,
+                            None => {
+                                headers.push(header);
+                                result.set_value(0, headers.len() as u32, header);
+                                headers.len() - 1
+                            }
+                        };
+                        columns.insert(cell_col, col as u32 + 1);
+                    }
+                    continue;
+                }
+                if *cell.value == Value::Empty {
+                    continue;
+                }
+                if let Some(col) = columns.get(&cell_col) {
+                    if last_row != Some(cell_row) {
+                        if last_row.is_some() {
+                            row += 1;
+                        }
+                        last_row = Some(cell_row);
+                        result.set_value(row, 0, sheet.name().as_str());
+                    }
+                    result.set_value(row, *col, cell.value.clone());
+                    if let Some(style) = cell.style {
+                        result.set_cellstyle(row, *col, style);
+                    }
+                }
+            }
+            if last_row.is_some() {
+                row += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Appends all sheets of the other workbook.
+    ///
+    /// The table-, row-, column-, cell-, paragraph-, text- and
+    /// graphic-styles, the font faces and the value formats of the other
+    /// workbook are copied too. Styles that are identical to an existing
+    /// style with the same name are shared, for the others the policy
+    /// decides. All references in the sheets are changed to the new
+    /// names. Validations are always renamed if the name is already used.
+    ///
+    /// Sheets with a name that is already used are renamed to "Name (2)",
+    /// "Name (3)" ... The formulas, named expressions, print ranges and
+    /// validations of the other workbook are changed to use the new sheet
+    /// names.
+    ///
+    /// The named expressions of the other workbook are added too. If a
+    /// name is already used the existing one is kept, or with
+    /// MergePolicy::Error this fails. The default styles of the other
+    /// workbook are not copied.
+    ///
+    /// Nothing is changed if this fails. It fails too if a sheet of the
+    /// other workbook is detached.
+    pub fn merge(&mut self, mut other: WorkBook, policy: MergePolicy) -> Result<(), OdsError> {
+        if other.sheets.iter().any(|v| v.is_detached()) {
+            return Err(OdsError::Ods(
+                "Can't merge a workbook with a detached sheet".to_string(),
+            ));
+        }
+
+        let mut font_map = HashMap::new();
+        let fonts = merge_plan(
+            &self.fonts,
+            mem::take(&mut other.fonts),
+            &mut style_names(&self.fonts),
+            policy,
+            |a, b| style_key(a.origin(), &[a.attrmap()]) == style_key(b.origin(), &[b.attrmap()]),
+            &mut font_map,
+        )?;
+        // Compare the styles with the new font names.
+        for style in other.cellstyles.values_mut() {
+            remap_fonts(style.textstyle_mut(), &font_map);
+        }
+        for style in other.paragraphstyles.values_mut() {
+            remap_fonts(style.textstyle_mut(), &font_map);
+        }
+        for style in other.textstyles.values_mut() {
+            remap_fonts(style.textstyle_mut(), &font_map);
+        }
+        for style in other.graphicstyles.values_mut() {
+            remap_fonts(style.textstyle_mut(), &font_map);
+        }
+
+        // All value formats share one namespace.
+        let mut used = HashSet::new();
+        used.extend(style_names(&self.formats_boolean));
+        used.extend(style_names(&self.formats_number));
+        used.extend(style_names(&self.formats_percentage));
+        used.extend(style_names(&self.formats_currency));
+        used.extend(style_names(&self.formats_text));
+        used.extend(style_names(&self.formats_datetime));
+        used.extend(style_names(&self.formats_timeduration));
+
+        let mut format_map = HashMap::new();
+        let formats_boolean = merge_plan(
+            &self.formats_boolean,
+            mem::take(&mut other.formats_boolean),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+        let formats_number = merge_plan(
+            &self.formats_number,
+            mem::take(&mut other.formats_number),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+        let formats_percentage = merge_plan(
+            &self.formats_percentage,
+            mem::take(&mut other.formats_percentage),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+        let formats_currency = merge_plan(
+            &self.formats_currency,
+            mem::take(&mut other.formats_currency),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+        let formats_text = merge_plan(
+            &self.formats_text,
+            mem::take(&mut other.formats_text),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+        let formats_datetime = merge_plan(
+            &self.formats_datetime,
+            mem::take(&mut other.formats_datetime),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+        let formats_timeduration = merge_plan(
+            &self.formats_timeduration,
+            mem::take(&mut other.formats_timeduration),
+            &mut used,
+            policy,
+            same_format,
+            &mut format_map,
+        )?;
+
+        for style in other.cellstyles.values_mut() {
+            if let Some(new_format) = style.value_format().and_then(|v| format_map.get(v)) {
+                style.set_value_format(&ValueFormatRef::from(new_format.as_str()));
+            }
+        }
+
+        let mut table_map = HashMap::new();
+        let tablestyles = merge_plan(
+            &self.tablestyles,
+            mem::take(&mut other.tablestyles),
+            &mut style_names(&self.tablestyles),
+            policy,
+            |a, b| {
+                style_key(a.origin(), &[a.attrmap(), a.tablestyle()])
+                    == style_key(b.origin(), &[b.attrmap(), b.tablestyle()])
+            },
+            &mut table_map,
+        )?;
+        let mut row_map = HashMap::new();
+        let rowstyles = merge_plan(
+            &self.rowstyles,
+            mem::take(&mut other.rowstyles),
+            &mut style_names(&self.rowstyles),
+            policy,
+            |a, b| {
+                style_key(a.origin(), &[a.attrmap(), a.rowstyle()])
+                    == style_key(b.origin(), &[b.attrmap(), b.rowstyle()])
+            },
+            &mut row_map,
+        )?;
+        let mut col_map = HashMap::new();
+        let colstyles = merge_plan(
+            &self.colstyles,
+            mem::take(&mut other.colstyles),
+            &mut style_names(&self.colstyles),
+            policy,
+            |a, b| {
+                style_key(a.origin(), &[a.attrmap(), a.colstyle()])
+                    == style_key(b.origin(), &[b.attrmap(), b.colstyle()])
+            },
+            &mut col_map,
+        )?;
+        let mut cell_map = HashMap::new();
+        let cellstyles = merge_plan(
+            &self.cellstyles,
+            mem::take(&mut other.cellstyles),
+            &mut style_names(&self.cellstyles),
+            policy,
+            |a, b| cellstyle_key(a) == cellstyle_key(b),
+            &mut cell_map,
+        )?;
+        let mut para_map = HashMap::new();
+        let paragraphstyles = merge_plan(
+            &self.paragraphstyles,
+            mem::take(&mut other.paragraphstyles),
+            &mut style_names(&self.paragraphstyles),
+            policy,
+            |a, b| paragraphstyle_key(a) == paragraphstyle_key(b),
+            &mut para_map,
+        )?;
+        let mut text_map = HashMap::new();
+        let textstyles = merge_plan(
+            &self.textstyles,
+            mem::take(&mut other.textstyles),
+            &mut style_names(&self.textstyles),
+            policy,
+            |a, b| {
+                style_key(a.origin(), &[a.attrmap(), a.textstyle()])
+                    == style_key(b.origin(), &[b.attrmap(), b.textstyle()])
+            },
+            &mut text_map,
+        )?;
+        let mut graphic_map = HashMap::new();
+        let graphicstyles = merge_plan(
+            &self.graphicstyles,
+            mem::take(&mut other.graphicstyles),
+            &mut style_names(&self.graphicstyles),
+            policy,
+            |a, b| {
+                style_key(
+                    a.origin(),
+                    &[
+                        a.attrmap(),
+                        a.graphicstyle(),
+                        a.paragraphstyle(),
+                        a.textstyle(),
+                    ],
+                ) == style_key(
+                    b.origin(),
+                    &[
+                        b.attrmap(),
+                        b.graphicstyle(),
+                        b.paragraphstyle(),
+                        b.textstyle(),
+                    ],
+                )
+            },
+            &mut graphic_map,
+        )?;
+        let mut valid_map = HashMap::new();
+        let validations = merge_plan(
+            &self.validations,
+            mem::take(&mut other.validations),
+            &mut style_names(&self.validations),
+            MergePolicy::Rename,
+            |_, _| false,
+            &mut valid_map,
+        )?;
+        let named_expressions = merge_named_expressions(&self.extra, &mut other.extra, policy)?;
+
+        for (name, mut font) in fonts {
+            font.set_name(name.as_str());
+            self.fonts.insert(name, font);
+        }
+        merge_formats(&mut self.formats_boolean, formats_boolean, &format_map);
+        merge_formats(&mut self.formats_number, formats_number, &format_map);
+        merge_formats(
+            &mut self.formats_percentage,
+            formats_percentage,
+            &format_map,
+        );
+        merge_formats(&mut self.formats_currency, formats_currency, &format_map);
+        merge_formats(&mut self.formats_text, formats_text, &format_map);
+        merge_formats(&mut self.formats_datetime, formats_datetime, &format_map);
+        merge_formats(
+            &mut self.formats_timeduration,
+            formats_timeduration,
+            &format_map,
+        );
+        for (name, mut style) in tablestyles {
+            style.set_name(name.as_str());
+            self.tablestyles.insert(name, style);
+        }
+        for (name, mut style) in rowstyles {
+            style.set_name(name.as_str());
+            self.rowstyles.insert(name, style);
+        }
+        for (name, mut style) in colstyles {
+            style.set_name(name.as_str());
+            self.colstyles.insert(name, style);
+        }
+        for (name, mut style) in cellstyles {
+            style.set_name(name.as_str());
+            if let Some(new_parent) = style
+                .attrmap()
+                .attr("style:parent-style-name")
+                .and_then(|v| cell_map.get(v))
+                .cloned()
+            {
+                style.set_parent_style(&new_parent);
+            }
+            if style.stylemaps().is_some() {
+                for stylemap in style.stylemaps_mut() {
+                    if let Some(new_style) = cell_map.get(stylemap.applied_style().as_str()) {
+                        stylemap.set_applied_style(new_style.clone().into());
+                    }
+                }
+            }
+            self.cellstyles.insert(name, style);
+        }
+        for (name, mut style) in paragraphstyles {
+            style.set_name(name.as_str());
+            remap_attr(style.attrmap_mut(), "style:parent-style-name", &para_map);
+            remap_attr(style.attrmap_mut(), "style:next-style-name", &para_map);
+            self.paragraphstyles.insert(name, style);
+        }
+        for (name, mut style) in textstyles {
+            style.set_name(name.as_str());
+            remap_attr(style.attrmap_mut(), "style:parent-style-name", &text_map);
+            self.textstyles.insert(name, style);
+        }
+        for (name, mut style) in graphicstyles {
+            style.set_name(name.as_str());
+            remap_attr(style.attrmap_mut(), "style:parent-style-name", &graphic_map);
+            self.graphicstyles.insert(name, style);
+        }
+        let mut valid_names = Vec::new();
+        for (name, mut valid) in validations {
+            valid.set_name(name.as_str());
+            valid_names.push(name.clone());
+            self.validations.insert(name, valid);
+        }
+
+        let first_sheet = self.sheets.len();
+        let mut sheet_map = HashMap::new();
+        for sheet in other.sheets {
+            let mut sheet = sheet.take();
+            remap_style(&mut sheet.style, &table_map);
+            for ch in sheet.col_header.values_mut() {
+                remap_style(&mut ch.style, &col_map);
+                remap_style(&mut ch.cellstyle, &cell_map);
+            }
+            for rh in sheet.row_header.values_mut() {
+                remap_style(&mut rh.style, &row_map);
+                remap_style(&mut rh.cellstyle, &cell_map);
+            }
+            for cell in sheet.data.values_mut() {
+                remap_style(&mut cell.style, &cell_map);
+                if let Value::TextXml(text) = &mut cell.value {
+                    remap_text_styles(text, &para_map, &text_map);
+                }
+                if let Some(extra) = cell.extra.as_mut() {
+                    remap_style(&mut extra.validation_name, &valid_map);
+                    if let Some(annotation) = extra.annotation.as_mut() {
+                        remap_draw_styles(annotation.attrmap_mut(), &graphic_map, &para_map);
+                        remap_text_styles(annotation.text_mut(), &para_map, &text_map);
+                    }
+                    for frame in extra.draw_frames.iter_mut() {
+                        remap_draw_styles(frame.attrmap_mut(), &graphic_map, &para_map);
+                    }
+                }
+            }
+            for shape in sheet.shapes.iter_mut() {
+                match shape {
+                    DrawShape::Frame(v) => {
+                        remap_draw_styles(v.attrmap_mut(), &graphic_map, &para_map);
+                    }
+                    DrawShape::Rect(v) => {
+                        remap_draw_styles(v.attrmap_mut(), &graphic_map, &para_map);
+                        remap_text_styles(v.text_mut(), &para_map, &text_map);
+                    }
+                    DrawShape::Line(v) => {
+                        remap_draw_styles(v.attrmap_mut(), &graphic_map, &para_map);
+                        remap_text_styles(v.text_mut(), &para_map, &text_map);
+                    }
+                    DrawShape::Xml(_) => {}
+                }
+            }
+
+            let name = sheet.name().clone();
+            self.push_sheet_checked(sheet, SheetNamePolicy::Rename)?;
+            let new_name = self.sheets[self.sheets.len() - 1].name();
+            if *new_name != name {
+                sheet_map.entry(name).or_insert_with(|| new_name.clone());
+            }
+        }
+
+        // References to renamed sheets.
+        let rename = RenameSheets(&sheet_map);
+        for sheet in self.sheets[first_sheet..].iter_mut() {
+            sheet.as_mut().map_refs(&rename);
+        }
+        for name in valid_names {
+            if let Some(valid) = self.validations.get_mut(&name) {
+                map_validation_refs(valid, &rename);
+            }
+        }
+        if !named_expressions.is_empty() {
+            let idx = match self
+                .extra
+                .iter()
+                .position(|v| v.name() == "table:named-expressions")
+            {
+                Some(idx) => idx,
+                None => {
+                    self.extra.push(XmlTag::new("table:named-expressions"));
+                    self.extra.len() - 1
+                }
+            };
+            for mut expr in named_expressions {
+                map_xml_refs(&mut expr, &rename);
+                self.extra[idx].add_tag(expr);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Repeats the row of the sheet count times, see Sheet::repeat_row().
+    // The references from the other sheets, the named expressions, the
+    // validations and the cell tags are adjusted too.
+    //
+    // Panics if the sheet is detached.
+    pub(crate) fn repeat_row(&mut self, idx: usize, row: u32, count: u32) {
+        if count <= 1 {
+            return;
+        }
+        self.sheets[idx].as_mut().repeat_row(row, count);
+
+        let name = self.sheets[idx].name().clone();
+        let repeat = RepeatRow {
+            sheet: &name,
+            local: false,
+            row,
+            n: count - 1,
+        };
+        for (i, sheet) in self.sheets.iter_mut().enumerate() {
+            if i != idx && !sheet.is_detached() {
+                sheet.as_mut().map_refs(&repeat);
+            }
+        }
+        for tag in self.extra.iter_mut() {
+            map_xml_refs(tag, &repeat);
+        }
+        for valid in self.validations.values_mut() {
+            map_validation_refs(valid, &repeat);
+        }
+        for tag in self.cell_tags.iter_mut() {
+            repeat.cellrange(&mut tag.range);
+        }
+    }
+
     /// Scripts.
     pub fn add_script(&mut self, v: Script) {
         self.scripts.push(v);
@@ -496,6 +1787,105 @@ impl WorkBook {
         self.event_listener.get_mut(event_name)
     }
 
+    /// Runs a Basic macro of the document when it is opened.
+    /// The macro is given as "Library.Module.Sub".
+    pub fn set_on_load_macro(&mut self, macro_path: &str) {
+        self.add_event_listener(EventListener::new_basic_macro("dom:load", macro_path));
+    }
+
+    /// Embeds a Basic library in the document as "Basic/<name>/".
+    /// Replaces a library with the same name.
+    pub fn add_basic_library(&mut self, library: BasicLibrary) {
+        self.remove_basic_library(&library.name);
+
+        let dir = format!("Basic/{}", library.name);
+        let mut lb = String::new();
+        lb.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        lb.push_str("<!DOCTYPE library:library PUBLIC \"-//OpenOffice.org//DTD OfficeDocument 1.0//EN\" \"library.dtd\">\n");
+        lb.push_str(&format!(
+            "<library:library xmlns:library=\"http://openoffice.org/2000/library\" library:name=\"{}\" library:readonly=\"false\" library:passwordprotected=\"false\">\n",
+            escape(&library.name)
+        ));
+        for (name, source) in &library.modules {
+            lb.push_str(&format!(
+                " <library:element library:name=\"{}\"/>\n",
+                escape(name)
+            ));
+
+            let mut module = String::new();
+            module.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            module.push_str("<!DOCTYPE script:module PUBLIC \"-//OpenOffice.org//DTD OfficeDocument 1.0//EN\" \"module.dtd\">\n");
+            module.push_str(&format!(
+                "<script:module xmlns:script=\"http://openoffice.org/2000/script\" script:name=\"{}\" script:language=\"StarBasic\" script:moduleType=\"normal\">",
+                escape(name)
+            ));
+            module.push_str(&escape(source));
+            module.push_str("</script:module>");
+            self.add_manifest(Manifest::with_buf(
+                format!("{}/{}.xml", dir, name),
+                "text/xml",
+                module.into_bytes(),
+            ));
+        }
+        lb.push_str("</library:library>");
+        self.add_manifest(Manifest::with_buf(
+            format!("{}/script-lb.xml", dir),
+            "text/xml",
+            lb.into_bytes(),
+        ));
+
+        self.update_basic_libraries();
+    }
+
+    /// Removes an embedded Basic library.
+    pub fn remove_basic_library(&mut self, name: &str) {
+        let dir = format!("Basic/{}/", name);
+        let len = self.manifest.len();
+        self.manifest.retain(|k, _| !k.starts_with(dir.as_str()));
+        if len != self.manifest.len() {
+            self.update_basic_libraries();
+        }
+    }
+
+    /// Names of the embedded Basic libraries.
+    pub fn basic_libraries(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .manifest
+            .keys()
+            .filter_map(|k| k.strip_prefix("Basic/"))
+            .filter_map(|k| k.strip_suffix("/script-lb.xml"))
+            .map(|k| k.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    // Rewrites the library container Basic/script-lc.xml.
+    fn update_basic_libraries(&mut self) {
+        let names = self.basic_libraries();
+        if names.is_empty() {
+            self.manifest.remove("Basic/script-lc.xml");
+            return;
+        }
+
+        let mut lc = String::new();
+        lc.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        lc.push_str("<!DOCTYPE library:libraries PUBLIC \"-//OpenOffice.org//DTD OfficeDocument 1.0//EN\" \"libraries.dtd\">\n");
+        lc.push_str("<library:libraries xmlns:library=\"http://openoffice.org/2000/library\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">\n");
+        for name in &names {
+            lc.push_str(&format!(
+                " <library:library library:name=\"{}\" library:link=\"false\"/>\n",
+                escape(name)
+            ));
+        }
+        lc.push_str("</library:libraries>");
+        self.add_manifest(Manifest::with_buf(
+            "Basic/script-lc.xml",
+            "text/xml",
+            lc.into_bytes(),
+        ));
+    }
+
     /// Adds a default-style for all new values.
     /// This information is only used when writing the data to the ODS file.
     pub fn add_def_style(&mut self, value_type: ValueType, style: CellStyleRef) {
@@ -507,6 +1897,48 @@ impl WorkBook {
         self.def_styles.get(&value_type)
     }
 
+    /// Returns the name of the value format used by the default style.
+    pub fn def_format(&self, value_type: ValueType) -> Option<&str> {
+        self.def_styles
+            .get(&value_type)
+            .and_then(|v| self.cellstyle(v))
+            .and_then(|v| v.value_format())
+    }
+
+    /// Returns all registered default styles.
+    pub fn def_styles(&self) -> impl Iterator<Item = (&ValueType, &CellStyleRef)> {
+        self.def_styles.iter()
+    }
+
+    /// Creates the value formats and a centered cell-style to show
+    /// boolean values as checkboxes ☑/☐.
+    ///
+    /// Returns the existing style if it has already been added.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{Sheet, WorkBook};
+    ///
+    /// let mut book = WorkBook::new_empty();
+    /// let checkbox = book.add_checkbox_style();
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_styled_value(0, 0, true, &checkbox);
+    /// ```
+    pub fn add_checkbox_style(&mut self) -> CellStyleRef {
+        let style_ref = CellStyleRef::from("checkbox");
+        if !self.cellstyles.contains_key(&style_ref) {
+            let (checked, unchecked) = format::create_checkbox_format("checkbox");
+            self.add_number_format(checked);
+            let format_ref = self.add_number_format(unchecked);
+
+            let mut style = CellStyle::new(style_ref.as_str(), &format_ref);
+            style.set_text_align_source(TextAlignSource::Fix);
+            style.set_text_align(TextAlign::Center);
+            self.add_cellstyle(style);
+        }
+        style_ref
+    }
+
     /// Adds a font.
     pub fn add_font(&mut self, font: FontFaceDecl) {
         self.fonts.insert(font.name().to_string(), font);
@@ -532,6 +1964,106 @@ impl WorkBook {
         self.fonts.get_mut(name)
     }
 
+    /// Embeds a TrueType font in the document.
+    ///
+    /// The font file is stored as Fonts/<name>.ttf and linked from the
+    /// font declaration with the given name. The declaration is created
+    /// if it doesn't exist yet. Returns the path of the font file.
+    pub fn embed_font<S: AsRef<str>>(&mut self, name: S, buf: Vec<u8>) -> String {
+        let name = name.as_ref();
+        let file: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = format!("Fonts/{}.ttf", file);
+        self.add_manifest(Manifest::with_buf(&path, "application/x-font-ttf", buf));
+
+        let font = self.fonts.entry(name.to_string()).or_insert_with(|| {
+            let mut font = FontFaceDecl::new(name);
+            font.set_font_family(format!("'{}'", name));
+            font
+        });
+        font.src_mut().retain(|v| v.href() != Some(path.as_str()));
+        let mut uri = FontFaceUri::new(&path);
+        uri.set_format("truetype");
+        font.push_src(uri);
+
+        path
+    }
+
+    /// Iterates all fonts embedded in the document. Returns the font
+    /// declaration, the link to the font file and the content of the file.
+    pub fn iter_embedded_fonts(
+        &self,
+    ) -> impl Iterator<Item = (&FontFaceDecl, &FontFaceUri, &[u8])> {
+        self.fonts.values().flat_map(move |font| {
+            font.src().iter().filter_map(move |uri| {
+                let buf = self
+                    .manifest
+                    .get(uri.href()?.trim_start_matches("./"))?
+                    .buffer
+                    .as_deref()?;
+                Some((font, uri, buf))
+            })
+        })
+    }
+
+    /// Returns the names of all fonts referenced by any style.
+    ///
+    /// The result is sorted and contains each font only once.
+    pub fn fonts_used(&self) -> Vec<String> {
+        let textstyles = self
+            .cellstyles
+            .values()
+            .map(|v| v.textstyle())
+            .chain(self.paragraphstyles.values().map(|v| v.textstyle()))
+            .chain(self.textstyles.values().map(|v| v.textstyle()))
+            .chain(self.graphicstyles.values().map(|v| v.textstyle()));
+
+        let mut fonts = Vec::new();
+        for attr in textstyles {
+            for font_attr in FONT_NAME_ATTR {
+                if let Some(font) = attr.attr(font_attr) {
+                    fonts.push(font.to_string());
+                }
+            }
+        }
+        fonts.sort();
+        fonts.dedup();
+        fonts
+    }
+
+    /// Replaces all references to the font `old` with `new`.
+    ///
+    /// A font-face declaration for `old` is renamed to `new`, unless
+    /// there already is a declaration for `new`. In that case the old
+    /// declaration is removed.
+    pub fn replace_font(&mut self, old: &str, new: &str) {
+        let textstyles = self
+            .cellstyles
+            .values_mut()
+            .map(|v| v.textstyle_mut())
+            .chain(self.paragraphstyles.values_mut().map(|v| v.textstyle_mut()))
+            .chain(self.textstyles.values_mut().map(|v| v.textstyle_mut()))
+            .chain(self.graphicstyles.values_mut().map(|v| v.textstyle_mut()));
+
+        for attr in textstyles {
+            for font_attr in FONT_NAME_ATTR {
+                if attr.attr(font_attr) == Some(old) {
+                    attr.set_attr(font_attr, new);
+                }
+            }
+        }
+
+        if let Some(mut font) = self.fonts.remove(old) {
+            if !self.fonts.contains_key(new) {
+                font.set_name(new);
+                font.set_font_family(new);
+                self.fonts.insert(new.to_string(), font);
+            }
+        }
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_tablestyle(&mut self, mut style: TableStyle) -> TableStyleRef {
@@ -563,6 +2095,15 @@ impl WorkBook {
         self.tablestyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn tablestyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut TableStyle {
+        let name = name.as_ref();
+        self.tablestyles
+            .entry(TableStyleRef::from(name))
+            .or_insert_with(|| TableStyle::new(name))
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_rowstyle(&mut self, mut style: RowStyle) -> RowStyleRef {
@@ -589,6 +2130,15 @@ impl WorkBook {
         self.rowstyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn rowstyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut RowStyle {
+        let name = name.as_ref();
+        self.rowstyles
+            .entry(RowStyleRef::from(name))
+            .or_insert_with(|| RowStyle::new(name))
+    }
+
     /// Returns iterator over styles.
     pub fn iter_rowstyles(&self) -> impl Iterator<Item = &RowStyle> {
         self.rowstyles.values()
@@ -620,6 +2170,15 @@ impl WorkBook {
         self.colstyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn colstyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut ColStyle {
+        let name = name.as_ref();
+        self.colstyles
+            .entry(ColStyleRef::from(name))
+            .or_insert_with(|| ColStyle::new(name))
+    }
+
     /// Returns iterator over styles.
     pub fn iter_colstyles(&self) -> impl Iterator<Item = &ColStyle> {
         self.colstyles.values()
@@ -631,29 +2190,250 @@ impl WorkBook {
         if style.name().is_empty() {
             style.set_name(auto_style_name2(&mut self.autonum, "ce", &self.cellstyles));
         }
-        let sref = style.style_ref();
-        self.cellstyles.insert(style.style_ref(), style);
-        sref
-    }
-
-    /// Removes a style.
-    pub fn remove_cellstyle<S: AsRef<str>>(&mut self, name: S) -> Option<CellStyle> {
-        self.cellstyles.remove(name.as_ref())
+        let sref = style.style_ref();
+        self.cellstyles.insert(style.style_ref(), style);
+        sref
+    }
+
+    /// Removes a style.
+    pub fn remove_cellstyle<S: AsRef<str>>(&mut self, name: S) -> Option<CellStyle> {
+        self.cellstyles.remove(name.as_ref())
+    }
+
+    /// Returns iterator over styles.
+    pub fn iter_cellstyles(&self) -> impl Iterator<Item = &CellStyle> {
+        self.cellstyles.values()
+    }
+
+    /// Returns the style.
+    pub fn cellstyle<S: AsRef<str>>(&self, name: S) -> Option<&CellStyle> {
+        self.cellstyles.get(name.as_ref())
+    }
+
+    /// Returns the mutable style.
+    pub fn cellstyle_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut CellStyle> {
+        self.cellstyles.get_mut(name.as_ref())
+    }
+
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn cellstyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut CellStyle {
+        let name = name.as_ref();
+        self.cellstyles
+            .entry(CellStyleRef::from(name))
+            .or_insert_with(|| {
+                let mut style = CellStyle::new_empty();
+                style.set_name(name);
+                style
+            })
+    }
+
+    /// Resolves the inheritance of a cell style.
+    ///
+    /// Follows the parent styles and ends with the default cell style.
+    /// Styles that don't exist are ignored. The default cell styles of
+    /// rows and columns are not used, as they depend on the cell.
+    pub fn resolve_cellstyle<S: AsRef<str>>(&self, name: S) -> ComputedCellStyle {
+        let mut computed = ComputedCellStyle::default();
+
+        let mut next = self.cellstyle(name);
+        while let Some(style) = next {
+            // cyclic parents
+            if computed.chain.contains(&style.style_ref()) {
+                break;
+            }
+            computed.inherit(style);
+            next = style
+                .attrmap()
+                .attr("style:parent-style-name")
+                .and_then(|v| self.cellstyle(v));
+        }
+
+        if let Some(style) = self
+            .cellstyles
+            .values()
+            .find(|v| v.styleuse() == StyleUse::Default)
+        {
+            if !computed.chain.contains(&style.style_ref()) {
+                computed.inherit(style);
+            }
+        }
+
+        computed
+    }
+
+    /// Converts all lengths in the style attributes to the given unit.
+    ///
+    /// Em lengths are relative and are left as they are. Font sizes
+    /// keep their unit too, pt is the norm there.
+    pub fn normalize_units(&mut self, unit: LengthUnit) {
+        self.map_style_attr(|name, value| {
+            if name.contains("font-size") {
+                None
+            } else {
+                convert_length_attr(name, value, unit)
+            }
+        });
+    }
+
+    /// The color theme.
+    pub fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// The color theme. Changes here don't change the styles.
+    pub fn theme_mut(&mut self) -> Option<&mut Theme> {
+        self.theme.as_mut()
+    }
+
+    /// Color of a slot of the current theme.
+    pub fn theme_color(&self, slot: ThemeColor) -> Option<Rgb<u8>> {
+        self.theme.as_ref().and_then(|v| v.color(slot))
+    }
+
+    /// Sets the color theme and returns the previous one.
+    ///
+    /// The theme is only the palette that is written to the document.
+    /// Styles contain plain colors and don't reference the theme, so
+    /// they don't change with it. Use [WorkBook::theme_color] to take
+    /// the colors for a style from the theme.
+    pub fn set_theme(&mut self, theme: Theme) -> Option<Theme> {
+        self.theme.replace(theme)
+    }
+
+    /// Removes the color theme. The styles are not changed.
+    pub fn clear_theme(&mut self) -> Option<Theme> {
+        self.theme.take()
+    }
+
+    /// Runs the function for all attributes of all styles and replaces
+    /// the value if it returns Some.
+    fn map_style_attr<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &str) -> Option<String>,
+    {
+        let mut apply = |attr: &mut AttrMap2| {
+            attr.map_values(&mut f);
+        };
+
+        for s in self.cellstyles.values_mut() {
+            apply(s.cellstyle_mut());
+            apply(s.paragraphstyle_mut());
+            apply(s.textstyle_mut());
+        }
+        for s in self.rowstyles.values_mut() {
+            apply(s.rowstyle_mut());
+        }
+        for s in self.colstyles.values_mut() {
+            apply(s.colstyle_mut());
+        }
+        for s in self.tablestyles.values_mut() {
+            apply(s.tablestyle_mut());
+        }
+        for s in self.paragraphstyles.values_mut() {
+            apply(s.paragraphstyle_mut());
+            apply(s.textstyle_mut());
+            for ts in s.tabstops_mut() {
+                apply(ts.attrmap_mut());
+            }
+        }
+        for s in self.textstyles.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.rubystyles.values_mut() {
+            apply(s.rubystyle_mut());
+        }
+        for s in self.graphicstyles.values_mut() {
+            apply(s.graphicstyle_mut());
+            apply(s.paragraphstyle_mut());
+            apply(s.textstyle_mut());
+        }
+        for s in self.pagestyles.values_mut() {
+            apply(s.style_mut());
+            apply(s.headerstyle_mut().style_mut());
+            apply(s.footerstyle_mut().style_mut());
+        }
+        for s in self.formats_boolean.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.formats_number.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.formats_percentage.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.formats_currency.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.formats_text.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.formats_datetime.values_mut() {
+            apply(s.textstyle_mut());
+        }
+        for s in self.formats_timeduration.values_mut() {
+            apply(s.textstyle_mut());
+        }
     }
 
-    /// Returns iterator over styles.
-    pub fn iter_cellstyles(&self) -> impl Iterator<Item = &CellStyle> {
-        self.cellstyles.values()
-    }
+    /// Merges identical automatic row-, column- and cell-styles and
+    /// rewrites all references to the removed duplicates.
+    ///
+    /// Only styles with StyleUse::Automatic are merged, named styles are
+    /// never touched. Cell-styles with style-maps are left alone too.
+    pub fn dedup_styles(&mut self) {
+        let row_map = dedup_style_map(&mut self.rowstyles, |s| {
+            (s.styleuse() == StyleUse::Automatic)
+                .then(|| style_key(s.origin(), &[s.attrmap(), s.rowstyle()]))
+        });
+        let col_map = dedup_style_map(&mut self.colstyles, |s| {
+            (s.styleuse() == StyleUse::Automatic)
+                .then(|| style_key(s.origin(), &[s.attrmap(), s.colstyle()]))
+        });
+        let cell_map = dedup_style_map(&mut self.cellstyles, |s| {
+            (s.styleuse() == StyleUse::Automatic && s.stylemaps().is_none()).then(|| {
+                style_key(
+                    s.origin(),
+                    &[
+                        s.attrmap(),
+                        s.cellstyle(),
+                        s.paragraphstyle(),
+                        s.textstyle(),
+                    ],
+                )
+            })
+        });
+
+        for sheet in self.sheets.iter_mut() {
+            let sheet = sheet.as_mut();
+            for ch in sheet.col_header.values_mut() {
+                remap_style(&mut ch.style, &col_map);
+                remap_style(&mut ch.cellstyle, &cell_map);
+            }
+            for rh in sheet.row_header.values_mut() {
+                remap_style(&mut rh.style, &row_map);
+                remap_style(&mut rh.cellstyle, &cell_map);
+            }
+            for cell in sheet.data.values_mut() {
+                remap_style(&mut cell.style, &cell_map);
+            }
+        }
 
-    /// Returns the style.
-    pub fn cellstyle<S: AsRef<str>>(&self, name: S) -> Option<&CellStyle> {
-        self.cellstyles.get(name.as_ref())
-    }
+        for style in self.def_styles.values_mut() {
+            if let Some(new_style) = cell_map.get(style) {
+                *style = new_style.clone();
+            }
+        }
 
-    /// Returns the mutable style.
-    pub fn cellstyle_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut CellStyle> {
-        self.cellstyles.get_mut(name.as_ref())
+        for style in self.cellstyles.values_mut() {
+            if style.stylemaps().is_some() {
+                for stylemap in style.stylemaps_mut() {
+                    if let Some(new_style) = cell_map.get(stylemap.applied_style().as_str()) {
+                        stylemap.set_applied_style(new_style.clone().into());
+                    }
+                }
+            }
+        }
     }
 
     /// Adds a style.
@@ -691,6 +2471,15 @@ impl WorkBook {
         self.paragraphstyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn paragraphstyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut ParagraphStyle {
+        let name = name.as_ref();
+        self.paragraphstyles
+            .entry(ParagraphStyleRef::from(name))
+            .or_insert_with(|| ParagraphStyle::new(name))
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_textstyle(&mut self, mut style: TextStyle) -> TextStyleRef {
@@ -722,6 +2511,15 @@ impl WorkBook {
         self.textstyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn textstyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut TextStyle {
+        let name = name.as_ref();
+        self.textstyles
+            .entry(TextStyleRef::from(name))
+            .or_insert_with(|| TextStyle::new(name))
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_rubystyle(&mut self, mut style: RubyStyle) -> RubyStyleRef {
@@ -757,6 +2555,15 @@ impl WorkBook {
         self.rubystyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn rubystyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut RubyStyle {
+        let name = name.as_ref();
+        self.rubystyles
+            .entry(RubyStyleRef::from(name))
+            .or_insert_with(|| RubyStyle::new(name))
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_graphicstyle(&mut self, mut style: GraphicStyle) -> GraphicStyleRef {
@@ -792,6 +2599,118 @@ impl WorkBook {
         self.graphicstyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn graphicstyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut GraphicStyle {
+        let name = name.as_ref();
+        self.graphicstyles
+            .entry(GraphicStyleRef::from(name))
+            .or_insert_with(|| GraphicStyle::new(name))
+    }
+
+    /// Adds a gradient.
+    /// Unnamed gradients will be assigned an automatic name.
+    pub fn add_gradient(&mut self, mut gradient: Gradient) -> GradientRef {
+        if gradient.name().is_empty() {
+            gradient.set_name(auto_style_name2(
+                &mut self.autonum,
+                "gradient",
+                &self.gradients,
+            ));
+        }
+        let gref = gradient.gradient_ref();
+        self.gradients.insert(gradient.gradient_ref(), gradient);
+        gref
+    }
+
+    /// Removes a gradient.
+    pub fn remove_gradient<S: AsRef<str>>(&mut self, name: S) -> Option<Gradient> {
+        self.gradients.remove(name.as_ref())
+    }
+
+    /// Returns iterator over gradients.
+    pub fn iter_gradients(&self) -> impl Iterator<Item = &Gradient> {
+        self.gradients.values()
+    }
+
+    /// Returns the gradient.
+    pub fn gradient<S: AsRef<str>>(&self, name: S) -> Option<&Gradient> {
+        self.gradients.get(name.as_ref())
+    }
+
+    /// Returns the mutable gradient.
+    pub fn gradient_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut Gradient> {
+        self.gradients.get_mut(name.as_ref())
+    }
+
+    /// Adds a hatch.
+    /// Unnamed hatches will be assigned an automatic name.
+    pub fn add_hatch(&mut self, mut hatch: Hatch) -> HatchRef {
+        if hatch.name().is_empty() {
+            hatch.set_name(auto_style_name2(&mut self.autonum, "hatch", &self.hatches));
+        }
+        let href = hatch.hatch_ref();
+        self.hatches.insert(hatch.hatch_ref(), hatch);
+        href
+    }
+
+    /// Removes a hatch.
+    pub fn remove_hatch<S: AsRef<str>>(&mut self, name: S) -> Option<Hatch> {
+        self.hatches.remove(name.as_ref())
+    }
+
+    /// Returns iterator over hatches.
+    pub fn iter_hatches(&self) -> impl Iterator<Item = &Hatch> {
+        self.hatches.values()
+    }
+
+    /// Returns the hatch.
+    pub fn hatch<S: AsRef<str>>(&self, name: S) -> Option<&Hatch> {
+        self.hatches.get(name.as_ref())
+    }
+
+    /// Returns the mutable hatch.
+    pub fn hatch_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut Hatch> {
+        self.hatches.get_mut(name.as_ref())
+    }
+
+    /// Adds a fill image.
+    /// Unnamed images will be assigned an automatic name.
+    ///
+    /// The image file itself must be added to the manifest separately.
+    pub fn add_fill_image(&mut self, mut image: FillImage) -> FillImageRef {
+        if image.name().is_empty() {
+            image.set_name(auto_style_name2(
+                &mut self.autonum,
+                "bitmap",
+                &self.fill_images,
+            ));
+        }
+        let iref = image.fill_image_ref();
+        self.fill_images.insert(image.fill_image_ref(), image);
+        iref
+    }
+
+    /// Removes a fill image.
+    pub fn remove_fill_image<S: AsRef<str>>(&mut self, name: S) -> Option<FillImage> {
+        self.fill_images.remove(name.as_ref())
+    }
+
+    /// Returns iterator over fill images.
+    pub fn iter_fill_images(&self) -> impl Iterator<Item = &FillImage> {
+        self.fill_images.values()
+    }
+
+    /// Returns the fill image.
+    pub fn fill_image<S: AsRef<str>>(&self, name: S) -> Option<&FillImage> {
+        self.fill_images.get(name.as_ref())
+    }
+
+    /// Returns the mutable fill image.
+    pub fn fill_image_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut FillImage> {
+        self.fill_images.get_mut(name.as_ref())
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_boolean_format(&mut self, mut vstyle: ValueFormatBoolean) -> ValueFormatRef {
@@ -817,13 +2736,16 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn boolean_format(&self, name: &str) -> Option<&ValueFormatBoolean> {
-        self.formats_boolean.get(name)
+    pub fn boolean_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatBoolean> {
+        self.formats_boolean.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn boolean_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatBoolean> {
-        self.formats_boolean.get_mut(name)
+    pub fn boolean_format_mut<S: AsRef<str>>(
+        &mut self,
+        name: S,
+    ) -> Option<&mut ValueFormatBoolean> {
+        self.formats_boolean.get_mut(name.as_ref())
     }
 
     /// Adds a value format.
@@ -851,13 +2773,13 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn number_format(&self, name: &str) -> Option<&ValueFormatNumber> {
-        self.formats_number.get(name)
+    pub fn number_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatNumber> {
+        self.formats_number.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn number_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatNumber> {
-        self.formats_number.get_mut(name)
+    pub fn number_format_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut ValueFormatNumber> {
+        self.formats_number.get_mut(name.as_ref())
     }
 
     /// Adds a value format.
@@ -890,13 +2812,16 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn percentage_format(&self, name: &str) -> Option<&ValueFormatPercentage> {
-        self.formats_percentage.get(name)
+    pub fn percentage_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatPercentage> {
+        self.formats_percentage.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn percentage_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatPercentage> {
-        self.formats_percentage.get_mut(name)
+    pub fn percentage_format_mut<S: AsRef<str>>(
+        &mut self,
+        name: S,
+    ) -> Option<&mut ValueFormatPercentage> {
+        self.formats_percentage.get_mut(name.as_ref())
     }
 
     /// Adds a value format.
@@ -924,13 +2849,16 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn currency_format(&self, name: &str) -> Option<&ValueFormatCurrency> {
-        self.formats_currency.get(name)
+    pub fn currency_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatCurrency> {
+        self.formats_currency.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn currency_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatCurrency> {
-        self.formats_currency.get_mut(name)
+    pub fn currency_format_mut<S: AsRef<str>>(
+        &mut self,
+        name: S,
+    ) -> Option<&mut ValueFormatCurrency> {
+        self.formats_currency.get_mut(name.as_ref())
     }
 
     /// Adds a value format.
@@ -957,13 +2885,13 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn text_format(&self, name: &str) -> Option<&ValueFormatText> {
-        self.formats_text.get(name)
+    pub fn text_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatText> {
+        self.formats_text.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn text_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatText> {
-        self.formats_text.get_mut(name)
+    pub fn text_format_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut ValueFormatText> {
+        self.formats_text.get_mut(name.as_ref())
     }
 
     /// Adds a value format.
@@ -991,13 +2919,16 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn datetime_format(&self, name: &str) -> Option<&ValueFormatDateTime> {
-        self.formats_datetime.get(name)
+    pub fn datetime_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatDateTime> {
+        self.formats_datetime.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn datetime_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatDateTime> {
-        self.formats_datetime.get_mut(name)
+    pub fn datetime_format_mut<S: AsRef<str>>(
+        &mut self,
+        name: S,
+    ) -> Option<&mut ValueFormatDateTime> {
+        self.formats_datetime.get_mut(name.as_ref())
     }
 
     /// Adds a value format.
@@ -1033,13 +2964,249 @@ impl WorkBook {
     }
 
     /// Returns the format.
-    pub fn timeduration_format(&self, name: &str) -> Option<&ValueFormatTimeDuration> {
-        self.formats_timeduration.get(name)
+    pub fn timeduration_format<S: AsRef<str>>(&self, name: S) -> Option<&ValueFormatTimeDuration> {
+        self.formats_timeduration.get(name.as_ref())
     }
 
     /// Returns the mutable format.
-    pub fn timeduration_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatTimeDuration> {
-        self.formats_timeduration.get_mut(name)
+    pub fn timeduration_format_mut<S: AsRef<str>>(
+        &mut self,
+        name: S,
+    ) -> Option<&mut ValueFormatTimeDuration> {
+        self.formats_timeduration.get_mut(name.as_ref())
+    }
+
+    /// Counts the references to all table, row, column and cell styles
+    /// and all value formats.
+    ///
+    /// ```
+    /// use spreadsheet_ods::style::CellStyle;
+    /// use spreadsheet_ods::workbook::StyleFamily;
+    /// use spreadsheet_ods::{Sheet, WorkBook};
+    ///
+    /// let mut wb = WorkBook::new_empty();
+    /// let st = wb.add_cellstyle(CellStyle::new_empty());
+    /// let mut sheet = Sheet::new("one");
+    /// sheet.set_styled_value(0, 0, 1, &st);
+    /// sheet.set_styled_value(1, 0, 2, &st);
+    /// wb.push_sheet(sheet);
+    ///
+    /// let usage = wb.style_usage();
+    /// let st = usage[&(StyleFamily::Cell, st.as_str().to_string())];
+    /// assert_eq!(st.cells, 2);
+    /// ```
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet is detached.
+    pub fn style_usage(&self) -> BTreeMap<(StyleFamily, String), StyleUsage> {
+        fn names<'a, I: Iterator<Item = &'a str>>(names: I) -> HashMap<&'a str, StyleUsage> {
+            names.map(|v| (v, StyleUsage::default())).collect()
+        }
+
+        let mut tables = names(self.tablestyles.values().map(|v| v.name()));
+        let mut rows = names(self.rowstyles.values().map(|v| v.name()));
+        let mut cols = names(self.colstyles.values().map(|v| v.name()));
+        let mut cells = names(self.cellstyles.values().map(|v| v.name()));
+        let mut formats = names(
+            self.formats_boolean
+                .keys()
+                .chain(self.formats_number.keys())
+                .chain(self.formats_percentage.keys())
+                .chain(self.formats_currency.keys())
+                .chain(self.formats_text.keys())
+                .chain(self.formats_datetime.keys())
+                .chain(self.formats_timeduration.keys())
+                .map(|v| v.as_str()),
+        );
+
+        for sheet in self.iter_sheets() {
+            if let Some(u) = sheet
+                .style
+                .as_ref()
+                .and_then(|v| tables.get_mut(v.as_str()))
+            {
+                u.sheets += 1;
+            }
+            for rh in sheet.row_header.values() {
+                if let Some(u) = rh.style.as_ref().and_then(|v| rows.get_mut(v.as_str())) {
+                    u.rows += rh.span as usize;
+                }
+                if let Some(u) = rh
+                    .cellstyle
+                    .as_ref()
+                    .and_then(|v| cells.get_mut(v.as_str()))
+                {
+                    u.rows += rh.span as usize;
+                }
+            }
+            for ch in sheet.col_header.values() {
+                if let Some(u) = ch.style.as_ref().and_then(|v| cols.get_mut(v.as_str())) {
+                    u.cols += ch.span as usize;
+                }
+                if let Some(u) = ch
+                    .cellstyle
+                    .as_ref()
+                    .and_then(|v| cells.get_mut(v.as_str()))
+                {
+                    u.cols += ch.span as usize;
+                }
+            }
+            for cell in sheet.data.values() {
+                if let Some(u) = cell.style.as_ref().and_then(|v| cells.get_mut(v.as_str())) {
+                    u.cells += cell.repeat as usize;
+                }
+            }
+            // conditional formats are only kept as xml.
+            for tag in &sheet.extra {
+                if tag.name() == "calcext:conditional-formats" {
+                    count_applied_styles(tag, &mut cells);
+                }
+            }
+        }
+        for style in self.def_styles.values() {
+            if let Some(u) = cells.get_mut(style.as_str()) {
+                u.styles += 1;
+            }
+        }
+        for style in self.cellstyles.values() {
+            if let Some(parent) = style.attrmap().attr("style:parent-style-name") {
+                if let Some(u) = cells.get_mut(parent) {
+                    u.styles += 1;
+                }
+            }
+            if let Some(stylemaps) = style.stylemaps() {
+                for sm in stylemaps {
+                    if let Some(u) = cells.get_mut(sm.applied_style().as_str()) {
+                        u.styles += 1;
+                    }
+                }
+            }
+        }
+        for style in self.cellstyles.values() {
+            let used = cells[style.name()];
+            if let Some(u) = style.value_format().and_then(|v| formats.get_mut(v)) {
+                u.cells += used.cells;
+                u.rows += used.rows;
+                u.cols += used.cols;
+                u.styles += 1;
+            }
+        }
+        let format_stylemaps = self
+            .formats_boolean
+            .values()
+            .filter_map(|v| v.stylemaps())
+            .chain(self.formats_number.values().filter_map(|v| v.stylemaps()))
+            .chain(
+                self.formats_percentage
+                    .values()
+                    .filter_map(|v| v.stylemaps()),
+            )
+            .chain(self.formats_currency.values().filter_map(|v| v.stylemaps()))
+            .chain(self.formats_text.values().filter_map(|v| v.stylemaps()))
+            .chain(self.formats_datetime.values().filter_map(|v| v.stylemaps()))
+            .chain(
+                self.formats_timeduration
+                    .values()
+                    .filter_map(|v| v.stylemaps()),
+            )
+            .flatten();
+        for sm in format_stylemaps {
+            if let Some(u) = formats.get_mut(sm.applied_style().as_str()) {
+                u.styles += 1;
+            }
+        }
+
+        let mut usage = BTreeMap::new();
+        for (family, map) in [
+            (StyleFamily::Table, tables),
+            (StyleFamily::Row, rows),
+            (StyleFamily::Col, cols),
+            (StyleFamily::Cell, cells),
+            (StyleFamily::ValueFormat, formats),
+        ] {
+            for (name, u) in map {
+                usage.insert((family, name.to_string()), u);
+            }
+        }
+        usage
+    }
+
+    /// Removes all automatic table, row, column and cell styles and
+    /// value formats that are not used anywhere. Repeats until the
+    /// styles that were only used by removed styles are gone too.
+    ///
+    /// Returns the number of removed styles and formats.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet is detached.
+    pub fn remove_unused_styles(&mut self) -> usize {
+        let mut count = 0;
+        loop {
+            let unused: Vec<_> = self
+                .style_usage()
+                .into_iter()
+                .filter(|(_, u)| u.is_unused())
+                .map(|(k, _)| k)
+                .collect();
+
+            let mut removed = 0;
+            for (family, name) in unused {
+                let styleuse = match family {
+                    StyleFamily::Table => self.tablestyles.get(name.as_str()).map(|v| v.styleuse()),
+                    StyleFamily::Row => self.rowstyles.get(name.as_str()).map(|v| v.styleuse()),
+                    StyleFamily::Col => self.colstyles.get(name.as_str()).map(|v| v.styleuse()),
+                    StyleFamily::Cell => self.cellstyles.get(name.as_str()).map(|v| v.styleuse()),
+                    StyleFamily::ValueFormat => self.format_styleuse(&name),
+                };
+                if styleuse != Some(StyleUse::Automatic) {
+                    continue;
+                }
+                match family {
+                    StyleFamily::Table => {
+                        self.tablestyles.remove(name.as_str());
+                    }
+                    StyleFamily::Row => {
+                        self.rowstyles.remove(name.as_str());
+                    }
+                    StyleFamily::Col => {
+                        self.colstyles.remove(name.as_str());
+                    }
+                    StyleFamily::Cell => {
+                        self.cellstyles.remove(name.as_str());
+                    }
+                    StyleFamily::ValueFormat => {
+                        self.formats_boolean.remove(&name);
+                        self.formats_number.remove(&name);
+                        self.formats_percentage.remove(&name);
+                        self.formats_currency.remove(&name);
+                        self.formats_text.remove(&name);
+                        self.formats_datetime.remove(&name);
+                        self.formats_timeduration.remove(&name);
+                    }
+                }
+                removed += 1;
+            }
+
+            if removed == 0 {
+                break;
+            }
+            count += removed;
+        }
+        count
+    }
+
+    fn format_styleuse(&self, name: &str) -> Option<StyleUse> {
+        self.formats_boolean
+            .get(name)
+            .map(|v| v.styleuse())
+            .or_else(|| self.formats_number.get(name).map(|v| v.styleuse()))
+            .or_else(|| self.formats_percentage.get(name).map(|v| v.styleuse()))
+            .or_else(|| self.formats_currency.get(name).map(|v| v.styleuse()))
+            .or_else(|| self.formats_text.get(name).map(|v| v.styleuse()))
+            .or_else(|| self.formats_datetime.get(name).map(|v| v.styleuse()))
+            .or_else(|| self.formats_timeduration.get(name).map(|v| v.styleuse()))
     }
 
     /// Adds a value PageStyle.
@@ -1077,6 +3244,15 @@ impl WorkBook {
         self.pagestyles.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable style. An empty style with this name is created
+    /// if it doesn't exist yet.
+    pub fn pagestyle_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut PageStyle {
+        let name = name.as_ref();
+        self.pagestyles
+            .entry(PageStyleRef::from(name))
+            .or_insert_with(|| PageStyle::new(name))
+    }
+
     /// Adds a value MasterPage.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_masterpage(&mut self, mut mpage: MasterPage) -> MasterPageRef {
@@ -1108,6 +3284,53 @@ impl WorkBook {
         self.masterpages.get_mut(name.as_ref())
     }
 
+    /// Returns the mutable master page. An empty master page with this name is created
+    /// if it doesn't exist yet.
+    pub fn masterpage_or_default<S: AsRef<str>>(&mut self, name: S) -> &mut MasterPage {
+        let name = name.as_ref();
+        self.masterpages
+            .entry(MasterPageRef::from(name))
+            .or_insert_with(|| MasterPage::new(name))
+    }
+
+    /// Iterates all styles of all families, including page styles
+    /// and master pages.
+    pub fn styles(&self) -> impl Iterator<Item = AnyStyle<'_>> {
+        self.cellstyles
+            .values()
+            .map(AnyStyle::Cell)
+            .chain(self.rowstyles.values().map(AnyStyle::Row))
+            .chain(self.colstyles.values().map(AnyStyle::Col))
+            .chain(self.tablestyles.values().map(AnyStyle::Table))
+            .chain(self.paragraphstyles.values().map(AnyStyle::Paragraph))
+            .chain(self.textstyles.values().map(AnyStyle::Text))
+            .chain(self.graphicstyles.values().map(AnyStyle::Graphic))
+            .chain(self.rubystyles.values().map(AnyStyle::Ruby))
+            .chain(self.pagestyles.values().map(AnyStyle::Page))
+            .chain(self.masterpages.values().map(AnyStyle::Master))
+    }
+
+    /// Iterates all styles of all families, including page styles
+    /// and master pages.
+    pub fn styles_mut(&mut self) -> impl Iterator<Item = AnyStyleMut<'_>> {
+        self.cellstyles
+            .values_mut()
+            .map(AnyStyleMut::Cell)
+            .chain(self.rowstyles.values_mut().map(AnyStyleMut::Row))
+            .chain(self.colstyles.values_mut().map(AnyStyleMut::Col))
+            .chain(self.tablestyles.values_mut().map(AnyStyleMut::Table))
+            .chain(
+                self.paragraphstyles
+                    .values_mut()
+                    .map(AnyStyleMut::Paragraph),
+            )
+            .chain(self.textstyles.values_mut().map(AnyStyleMut::Text))
+            .chain(self.graphicstyles.values_mut().map(AnyStyleMut::Graphic))
+            .chain(self.rubystyles.values_mut().map(AnyStyleMut::Ruby))
+            .chain(self.pagestyles.values_mut().map(AnyStyleMut::Page))
+            .chain(self.masterpages.values_mut().map(AnyStyleMut::Master))
+    }
+
     /// Adds a Validation.
     /// Nameless validations will be assigned a name.
     pub fn add_validation(&mut self, mut valid: Validation) -> ValidationRef {
@@ -1168,6 +3391,100 @@ impl WorkBook {
         self.manifest.get_mut(path)
     }
 
+    /// Attaches an arbitrary file to the package, e.g. a JSON sidecar or
+    /// a license text. Replaces an existing attachment with the same path.
+    ///
+    /// The path is relative to the root of the package. Fails for the
+    /// paths that are written by this library itself, for anything in
+    /// META-INF, for absolute paths and for paths containing "..".
+    pub fn add_attachment<S: AsRef<str>, T: Into<String>>(
+        &mut self,
+        path: S,
+        media_type: T,
+        buf: Vec<u8>,
+    ) -> Result<(), OdsError> {
+        let path = path.as_ref().trim_start_matches("./");
+        if !valid_attachment_path(path) {
+            return Err(OdsError::Ods(format!("invalid attachment path {}", path)));
+        }
+        self.add_manifest(Manifest::with_buf(path, media_type, buf));
+        Ok(())
+    }
+
+    /// Iterates all files of the package that are not processed by this
+    /// library. This includes attachments, images and embedded objects.
+    pub fn iter_attachments(&self) -> impl Iterator<Item = &Manifest> {
+        self.manifest
+            .values()
+            .filter(|v| !v.is_dir() && !Manifest::is_reserved(&v.full_path))
+    }
+
+    /// Content of an attached file. A leading "./" of the path is ignored.
+    pub fn attachment(&self, path: &str) -> Option<&[u8]> {
+        self.manifest
+            .get(path.trim_start_matches("./"))
+            .and_then(|v| v.buffer.as_deref())
+    }
+
+    /// Adds an embedded object to the zip and returns the xlink:href for
+    /// DrawObject::new_href().
+    ///
+    /// OpenDocument sub-documents (application/vnd.oasis.opendocument.*) are
+    /// stored as a directory and the bytes are its content.xml. Everything
+    /// else is stored as a single file, e.g. an OLE object.
+    pub fn add_embedded_object<S: AsRef<str>, T: Into<String>>(
+        &mut self,
+        path: S,
+        media_type: T,
+        buf: Vec<u8>,
+    ) -> String {
+        let path = path.as_ref().trim_start_matches("./").trim_end_matches('/');
+        let media_type = media_type.into();
+        if media_type.starts_with("application/vnd.oasis.opendocument.") {
+            self.add_manifest(Manifest::new(format!("{}/", path), media_type));
+            self.add_manifest(Manifest::with_buf(
+                format!("{}/content.xml", path),
+                "text/xml",
+                buf,
+            ));
+        } else {
+            self.add_manifest(Manifest::with_buf(path, media_type, buf));
+        }
+        format!("./{}", path)
+    }
+
+    /// Sets the preview image "Thumbnails/thumbnail.png" that file managers show.
+    /// The image must be a PNG, LibreOffice uses 256x256 pixels max.
+    pub fn set_thumbnail(&mut self, buf: Vec<u8>) {
+        self.add_manifest(Manifest::with_buf(
+            "Thumbnails/thumbnail.png",
+            "image/png",
+            buf,
+        ));
+    }
+
+    /// The preview image.
+    pub fn thumbnail(&self) -> Option<&[u8]> {
+        self.manifest
+            .get("Thumbnails/thumbnail.png")
+            .and_then(|v| v.buffer.as_deref())
+    }
+
+    /// Removes the preview image.
+    pub fn clear_thumbnail(&mut self) -> Option<Vec<u8>> {
+        self.manifest
+            .remove("Thumbnails/thumbnail.png")
+            .and_then(|v| v.buffer)
+    }
+
+    /// Removes an embedded object with all its files.
+    pub fn remove_embedded_object(&mut self, path: &str) {
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        let dir = format!("{}/", path);
+        self.manifest
+            .retain(|k, _| k.as_str() != path && !k.starts_with(dir.as_str()));
+    }
+
     /// Gives access to meta-data.
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
@@ -1177,6 +3494,135 @@ impl WorkBook {
     pub fn metadata_mut(&mut self) -> &mut Metadata {
         &mut self.metadata
     }
+
+    /// Recalculates the document statistics in the metadata.
+    /// Sets the table-count and the cell-count.
+    ///
+    /// This is done when writing unless OdsWriteOptions::update_metadata()
+    /// is switched off.
+    pub fn recalculate_metadata(&mut self) {
+        self.metadata.document_statistics.table_count = self.sheets.len() as u32;
+        let mut cell_count = 0;
+        for sheet in self.iter_sheets() {
+            cell_count += sheet.cell_count() as u32;
+        }
+        self.metadata.document_statistics.cell_count = cell_count;
+    }
+
+    /// Problems found when reading. Recoverable errors are collected with
+    /// OdsOptions::lenient(), unknown content with OdsOptions::report_unused().
+    ///
+    /// push_sheet() and insert_sheet() add a warning for a duplicate
+    /// sheet name.
+    pub fn warnings(&self) -> &[OdsWarning] {
+        &self.warnings
+    }
+
+    /// Clears the warnings.
+    pub fn clear_warnings(&mut self) {
+        self.warnings.clear();
+    }
+
+    /// Returns a copy with all styles, formats, page layouts, validations
+    /// and the sheet structure, but without any cell content.
+    ///
+    /// See Sheet::extract_skeleton(). This can be used to turn an existing
+    /// report into a template.
+    pub fn extract_skeleton(&self) -> WorkBook {
+        WorkBook {
+            sheets: self
+                .sheets
+                .iter()
+                .map(|v| v.extract_skeleton().into())
+                .collect(),
+            detached_names: Default::default(),
+            version: self.version.clone(),
+            fonts: self.fonts.clone(),
+            autonum: self.autonum.clone(),
+            scripts: self.scripts.clone(),
+            event_listener: self.event_listener.clone(),
+            tablestyles: self.tablestyles.clone(),
+            rowstyles: self.rowstyles.clone(),
+            colstyles: self.colstyles.clone(),
+            cellstyles: self.cellstyles.clone(),
+            paragraphstyles: self.paragraphstyles.clone(),
+            textstyles: self.textstyles.clone(),
+            rubystyles: self.rubystyles.clone(),
+            graphicstyles: self.graphicstyles.clone(),
+            gradients: self.gradients.clone(),
+            hatches: self.hatches.clone(),
+            fill_images: self.fill_images.clone(),
+            theme: self.theme.clone(),
+            formats_boolean: self.formats_boolean.clone(),
+            formats_number: self.formats_number.clone(),
+            formats_percentage: self.formats_percentage.clone(),
+            formats_currency: self.formats_currency.clone(),
+            formats_text: self.formats_text.clone(),
+            formats_datetime: self.formats_datetime.clone(),
+            formats_timeduration: self.formats_timeduration.clone(),
+            def_styles: self.def_styles.clone(),
+            pagestyles: self.pagestyles.clone(),
+            masterpages: self.masterpages.clone(),
+            validations: self.validations.clone(),
+            config: self.config.clone(),
+            workbook_config: self.workbook_config.clone(),
+            calculation_settings: self.calculation_settings.clone(),
+            named_views: self.named_views.clone(),
+            cell_tags: self.cell_tags.clone(),
+            xmlns: self.xmlns.clone(),
+            manifest: self.manifest.clone(),
+            metadata: self.metadata.clone(),
+            extra: self.extra.clone(),
+            extra_styles: self.extra_styles.clone(),
+            warnings: Default::default(),
+            text_pool: self.text_pool.clone(),
+        }
+    }
+
+    /// Turns the workbook into an immutable snapshot that can be shared
+    /// between threads.
+    pub fn freeze(self) -> FrozenWorkBook {
+        FrozenWorkBook {
+            book: Arc::new(self),
+        }
+    }
+}
+
+/// Immutable snapshot of a WorkBook.
+///
+/// Clones are cheap and share the same data, which makes this suitable
+/// for answering many concurrent queries on a single parsed workbook.
+/// All the read accessors of the WorkBook are available via Deref.
+#[derive(Clone, Debug)]
+pub struct FrozenWorkBook {
+    book: Arc<WorkBook>,
+}
+
+impl FrozenWorkBook {
+    /// Returns the workbook.
+    pub fn workbook(&self) -> &WorkBook {
+        &self.book
+    }
+
+    /// Returns a mutable workbook again. Clones the data if there are
+    /// other references to this snapshot.
+    pub fn thaw(self) -> WorkBook {
+        Arc::try_unwrap(self.book).unwrap_or_else(|book| (*book).clone())
+    }
+}
+
+impl Deref for FrozenWorkBook {
+    type Target = WorkBook;
+
+    fn deref(&self) -> &Self::Target {
+        &self.book
+    }
+}
+
+impl From<WorkBook> for FrozenWorkBook {
+    fn from(book: WorkBook) -> Self {
+        book.freeze()
+    }
 }
 
 /// Subset of the Workbook wide configurations.
@@ -1203,6 +3649,236 @@ impl Default for WorkBookConfig {
     }
 }
 
+/// Settings for formula calculation. Stored as table:calculation-settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalculationSettings {
+    /// Text comparisons are case sensitive.
+    pub case_sensitive: bool,
+    /// Calculate with the values as they are shown, rounded by the
+    /// value-format of the cell.
+    pub precision_as_shown: bool,
+    /// Search criteria must match the whole cell content.
+    pub search_criteria_must_apply_to_whole_cell: bool,
+    /// Find row and column labels automatically.
+    pub automatic_find_labels: bool,
+    /// Search criteria can use regular expressions.
+    pub use_regular_expressions: bool,
+    /// Search criteria can use wildcards.
+    pub use_wildcards: bool,
+    /// Two-digit years are interpreted as null_year..null_year+99.
+    pub null_year: u32,
+    /// Date that corresponds to the value 0.
+    pub null_date: NaiveDate,
+    /// Iterative calculation of circular references.
+    pub iteration: bool,
+    /// Maximum number of iteration steps.
+    pub iteration_steps: u32,
+    /// Iteration stops if the change between two steps is smaller than this.
+    pub iteration_minimum_difference: f64,
+}
+
+// Only plain values, nothing on the heap.
+impl GetSize for CalculationSettings {}
+
+impl Default for CalculationSettings {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            precision_as_shown: false,
+            search_criteria_must_apply_to_whole_cell: true,
+            automatic_find_labels: true,
+            use_regular_expressions: true,
+            use_wildcards: false,
+            null_year: 1930,
+            null_date: NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date"),
+            iteration: false,
+            iteration_steps: 100,
+            iteration_minimum_difference: 0.001,
+        }
+    }
+}
+
+/// Policy for a sheet whose name is already used in the workbook.
+///
+/// Used by WorkBook::push_sheet_checked() and friends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetNamePolicy {
+    /// Fail with an error.
+    Error,
+    /// Rename the new sheet to "Name (2)", "Name (3)" ...
+    Rename,
+}
+
+/// Policy for a style or value format of WorkBook::merge() whose name is
+/// already used by a different style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Fail with an error.
+    Error,
+    /// Rename the new style to "name_2", "name_3" ...
+    Rename,
+    /// Use the existing style instead.
+    KeepExisting,
+}
+
+/// A named view of the workbook. Stores the active table, the position
+/// and zoom, and the columns hidden for this view.
+///
+/// The views are stored in settings.xml and can be activated with
+/// WorkBook::activate_named_view().
+#[derive(Clone, Debug, GetSize)]
+pub struct NamedView {
+    /// Name of the view.
+    pub name: String,
+    /// Active table for this view.
+    pub active_table: String,
+    /// Active column.
+    pub cursor_x: u32,
+    /// Active row.
+    pub cursor_y: u32,
+    /// First visible column.
+    pub first_col: u32,
+    /// First visible row.
+    pub first_row: u32,
+    /// Zoom in percent.
+    pub zoom_value: i32,
+    /// Columns hidden in this view.
+    pub hidden_cols: Vec<u32>,
+}
+
+impl NamedView {
+    /// Creates a view for the given table.
+    pub fn new<S: Into<String>, T: Into<String>>(name: S, active_table: T) -> Self {
+        Self {
+            name: name.into(),
+            active_table: active_table.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for NamedView {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            active_table: Default::default(),
+            cursor_x: 0,
+            cursor_y: 0,
+            first_col: 0,
+            first_row: 0,
+            zoom_value: 100,
+            hidden_cols: Default::default(),
+        }
+    }
+}
+
+// Name prefix of the named ranges and expressions for the cell tags.
+pub(crate) const CELL_TAG_PREFIX: &str = "CellTag_";
+pub(crate) const CELL_TAG_VALUE_PREFIX: &str = "CellTagValue_";
+
+/// Tags a cell or a range with a name and some application data.
+///
+/// The tags are meant for the application that wrote the file, e.g. to
+/// find the cell with the totals again. They are stored as named ranges
+/// "CellTag_name" and, for the value, named expressions "CellTagValue_name".
+/// The spreadsheet application keeps them and moves the range when rows
+/// or columns are inserted or deleted.
+///
+/// The name must be valid for a named range, letters, digits and
+/// underscores. The range must contain the table name and is read back
+/// as an absolute range.
+///
+/// ```
+/// use spreadsheet_ods::workbook::CellTag;
+/// use spreadsheet_ods::{CellRange, WorkBook};
+///
+/// let mut wb = WorkBook::new_empty();
+/// wb.add_cell_tag(CellTag::new(
+///     "totals",
+///     CellRange::remote("report", 10, 1, 10, 4),
+///     "sum of all items",
+/// ));
+///
+/// let tag = wb.cell_tag("totals").expect("tag");
+/// assert_eq!(tag.range.row(), 10);
+/// ```
+#[derive(Clone, Debug, Default, GetSize)]
+pub struct CellTag {
+    /// Name of the tag.
+    pub name: String,
+    /// Tagged cells.
+    pub range: CellRange,
+    /// Application data.
+    pub value: String,
+}
+
+impl CellTag {
+    /// Creates a tag for the range.
+    pub fn new<S: Into<String>, T: Into<String>>(name: S, range: CellRange, value: T) -> Self {
+        Self {
+            name: name.into(),
+            range,
+            value: value.into(),
+        }
+    }
+}
+
+/// Kind of style for WorkBook::style_usage().
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleFamily {
+    /// Table style.
+    Table,
+    /// Row style.
+    Row,
+    /// Column style.
+    Col,
+    /// Cell style.
+    Cell,
+    /// Value format.
+    ValueFormat,
+}
+
+/// References to a style or value format.
+///
+/// A value format counts the cells, rows and columns of the cell styles
+/// that use it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StyleUsage {
+    /// Cells. Repeated cells count for each repeat.
+    pub cells: usize,
+    /// Rows with the row style or the default cell style.
+    pub rows: usize,
+    /// Columns with the column style or the default cell style.
+    pub cols: usize,
+    /// Sheets with the table style.
+    pub sheets: usize,
+    /// References from other styles. Parent styles, style maps, the
+    /// default styles for the value types and conditional formats.
+    pub styles: usize,
+}
+
+impl StyleUsage {
+    /// Not used at all.
+    pub fn is_unused(&self) -> bool {
+        *self == StyleUsage::default()
+    }
+}
+
+// Counts the calcext:apply-style-name of the conditional formats.
+fn count_applied_styles(tag: &XmlTag, cells: &mut HashMap<&str, StyleUsage>) {
+    if let Some(u) = tag
+        .get_attr("calcext:apply-style-name")
+        .and_then(|v| cells.get_mut(v))
+    {
+        u.styles += 1;
+    }
+    for content in tag.content() {
+        if let XmlContent::Tag(tag) = content {
+            count_applied_styles(tag, cells);
+        }
+    }
+}
+
 /// Script.
 #[derive(Debug, Default, Clone, GetSize)]
 pub struct Script {
@@ -1240,6 +3916,40 @@ impl Script {
     }
 }
 
+/// A Basic macro library with its modules.
+///
+/// The library is embedded with WorkBook::add_basic_library().
+#[derive(Debug, Default, Clone, GetSize)]
+pub struct BasicLibrary {
+    pub(crate) name: String,
+    pub(crate) modules: Vec<(String, String)>,
+}
+
+impl BasicLibrary {
+    /// Empty library. LibreOffice uses "Standard" as default.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            modules: Default::default(),
+        }
+    }
+
+    /// Name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds a module with its Basic source code.
+    pub fn add_module<S: Into<String>, T: Into<String>>(&mut self, name: S, source: T) {
+        self.modules.push((name.into(), source.into()));
+    }
+
+    /// Modules as (name, source).
+    pub fn modules(&self) -> &Vec<(String, String)> {
+        &self.modules
+    }
+}
+
 /// Event-Listener.
 #[derive(Debug, Clone, GetSize)]
 pub struct EventListener {
@@ -1264,6 +3974,22 @@ impl EventListener {
         }
     }
 
+    /// EventListener that calls a Basic macro stored in the document.
+    /// The macro is given as "Library.Module.Sub".
+    pub fn new_basic_macro<S: Into<String>>(event_name: S, macro_path: &str) -> Self {
+        Self {
+            event_name: event_name.into(),
+            script_lang: "ooo:script".to_string(),
+            macro_name: Default::default(),
+            actuate: XLinkActuate::OnRequest,
+            href: format!(
+                "vnd.sun.star.script:{}?language=Basic&location=document",
+                macro_path
+            ),
+            link_type: XLinkType::Simple,
+        }
+    }
+
     /// Name
     pub fn event_name(&self) -> &str {
         &self.event_name
@@ -8,9 +8,11 @@ use chrono::{Duration, NaiveDateTime};
 
 use spreadsheet_ods::metadata::Metadata;
 use spreadsheet_ods::sheet::{Grouped, SheetConfig};
-use spreadsheet_ods::style::{TableStyle, TableStyleRef};
+use spreadsheet_ods::style::{CellStyleRef, TableStyle, TableStyleRef};
 use spreadsheet_ods::text::TextTag;
-use spreadsheet_ods::{CellRange, ColRange, RowRange, Sheet, Value, WorkBook};
+use spreadsheet_ods::{
+    read_ods_buf, write_ods_buf, CellRange, ColRange, OdsError, RowRange, Sheet, Value, WorkBook,
+};
 
 #[test]
 pub fn sizes() {
@@ -55,3 +57,73 @@ pub fn sizes2() {
     println!("Arc<str> {}", size_of::<Arc<str>>());
     println!("Box<str> {}", size_of::<Box<str>>());
 }
+
+#[test]
+pub fn sheet_memory() {
+    let mut sh = Sheet::new("dense");
+    let empty = sh.memory_usage();
+    for r in 0..100 {
+        for c in 0..100 {
+            sh.set_value(r, c, r as f64 * c as f64);
+        }
+    }
+    let used = sh.memory_usage();
+    let per_cell = (used - empty) / 10000;
+    assert!(per_cell >= size_of::<(u32, u32)>() + size_of::<Value>());
+    // key, value, formula, style, repeat and extra data.
+    assert!(per_cell <= 128);
+
+    let mut sh = Sheet::new("text");
+    let empty = sh.memory_usage();
+    sh.set_value(0, 0, "x".repeat(1000));
+    assert!(sh.memory_usage() - empty >= 1000);
+
+    let mut sh = Sheet::new("style");
+    sh.set_value(0, 0, 1);
+    let unstyled = sh.memory_usage();
+    sh.set_cellstyle(0, 0, &CellStyleRef::from("a_rather_long_style_name"));
+    assert!(sh.memory_usage() - unstyled >= "a_rather_long_style_name".len());
+}
+
+#[test]
+pub fn text_memory() -> Result<(), OdsError> {
+    let text = "not applicable for this row";
+
+    let mut sh = Sheet::new("text");
+    for r in 0..1000 {
+        sh.set_value(r, 0, text);
+    }
+    let separate = sh.memory_usage();
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    let shared = wb.sheet(0).memory_usage();
+    assert!(separate - shared >= 999 * text.len());
+    assert_eq!(wb.text_count(), 1);
+
+    // set_value() on a sheet of the workbook uses the shared text.
+    wb.sheet_mut(0).set_value(1000, 0, text);
+    let (Value::Text(t0), Value::Text(t1)) = (wb.sheet(0).value(0, 0), wb.sheet(0).value(1000, 0))
+    else {
+        panic!("text expected");
+    };
+    assert!(Arc::ptr_eq(t0, t1));
+
+    // reading shares the texts too.
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.text_count(), 1);
+    let (Value::Text(t0), Value::Text(t1)) = (wb.sheet(0).value(0, 0), wb.sheet(0).value(1000, 0))
+    else {
+        panic!("text expected");
+    };
+    assert!(Arc::ptr_eq(t0, t1));
+
+    for r in 0..=1000 {
+        wb.sheet_mut(0).remove_cell(r, 0);
+    }
+    wb.shrink_texts();
+    assert_eq!(wb.text_count(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "async")]
+
+use spreadsheet_ods::{read_ods_async, write_ods_async, OdsError, Sheet, WorkBook};
+
+#[test]
+fn read_write_async() -> Result<(), OdsError> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime");
+
+    rt.block_on(async {
+        let mut wb = WorkBook::new_empty();
+        let mut sh = Sheet::new("one");
+        sh.set_value(0, 0, "async");
+        wb.push_sheet(sh);
+
+        let mut buf = Vec::new();
+        write_ods_async(&mut wb, &mut buf).await?;
+        assert_eq!(wb.num_sheets(), 1);
+
+        let wb = read_ods_async(buf.as_slice()).await?;
+        assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "async");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,70 @@
+mod lib_test;
+
+use chrono::NaiveDate;
+use lib_test::*;
+use spreadsheet_ods::changes::{AcceptanceState, ChangeKind, ChangeType};
+use spreadsheet_ods::{read_ods, read_ods_buf, OdsError, WorkBook};
+
+#[test]
+fn test_tracked_changes() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/test_changes.ods")?;
+
+    let changes = wb.tracked_changes()?.expect("tracked_changes");
+    assert!(changes.track_changes());
+    assert_eq!(changes.changes().len(), 3);
+
+    let c = &changes.changes()[0];
+    assert_eq!(c.id(), "ct1");
+    assert_eq!(c.author(), Some("Anna"));
+    assert_eq!(
+        c.date(),
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .expect("date")
+            .and_hms_opt(10, 15, 0)
+    );
+    assert_eq!(
+        c.kind(),
+        &ChangeKind::CellContent {
+            sheet: 0,
+            row: 2,
+            col: 1,
+            previous: Some("12".to_string()),
+            previous_formula: None,
+        }
+    );
+
+    let c = &changes.changes()[1];
+    assert_eq!(c.acceptance_state(), AcceptanceState::Accepted);
+    assert_eq!(c.comment(), Some("two more rows"));
+    assert_eq!(
+        c.kind(),
+        &ChangeKind::Insertion {
+            change_type: ChangeType::Row,
+            position: 4,
+            count: 2,
+            sheet: 0,
+        }
+    );
+
+    let c = &changes.changes()[2];
+    assert_eq!(c.acceptance_state(), AcceptanceState::Pending);
+    assert_eq!(
+        c.kind(),
+        &ChangeKind::Deletion {
+            change_type: ChangeType::Column,
+            position: 3,
+            sheet: 0,
+        }
+    );
+
+    assert_eq!(changes.changes_by("Anna").count(), 2);
+
+    // unchanged after writing.
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.tracked_changes()?, Some(changes));
+
+    assert_eq!(WorkBook::new_empty().tracked_changes()?, None);
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+mod lib_test;
+
+use lib_test::*;
+use spreadsheet_ods::{read_ods_buf, Length, OdsError, Sheet, Value, WorkBook};
+
+const COLS: u32 = 40;
+
+// Small xorshift, good enough to shuffle the operations.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, n: u32) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as u32
+    }
+}
+
+fn width(n: u32) -> Length {
+    match n {
+        0 => Length::Default,
+        1 => Length::Cm(1.0),
+        2 => Length::Cm(2.5),
+        _ => Length::Cm(4.0),
+    }
+}
+
+fn check(sh: &Sheet, widths: &[Length], values: &[Option<f64>]) {
+    for col in 0..COLS {
+        assert_eq!(sh.col_width(col), widths[col as usize], "width col {}", col);
+        match values[col as usize] {
+            Some(v) => assert_eq!(sh.value(0, col), &Value::Number(v), "value col {}", col),
+            None => assert_eq!(sh.value(0, col), &Value::Empty, "value col {}", col),
+        }
+    }
+}
+
+fn interleaved(seed: u64) -> Result<(), OdsError> {
+    let mut rng = Rng(seed);
+
+    let mut widths = vec![Length::Default; COLS as usize];
+    let mut values = vec![None; COLS as usize];
+    // The last column keeps all columns in the written range.
+    values[COLS as usize - 1] = Some(0.0);
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, COLS - 1, 0.0);
+    wb.push_sheet(sh);
+
+    for _round in 0..4 {
+        let sh = wb.sheet_mut(0);
+        for _ in 0..60 {
+            let col = rng.next(COLS);
+            match rng.next(3) {
+                0 => {
+                    let w = width(rng.next(4));
+                    sh.set_col_width(col, w);
+                    widths[col as usize] = w;
+                }
+                1 => {
+                    // a run of equal widths.
+                    let w = width(rng.next(4));
+                    for c in col..(col + rng.next(8)).min(COLS) {
+                        sh.set_col_width(c, w);
+                        widths[c as usize] = w;
+                    }
+                }
+                _ => {
+                    if col != COLS - 1 {
+                        let v = rng.next(1000) as f64;
+                        sh.set_value(0, col, v);
+                        values[col as usize] = Some(v);
+                    }
+                }
+            }
+        }
+        check(wb.sheet(0), &widths, &values);
+
+        // Continue with the read back, which shares styles between columns.
+        let buf = test_write_odsbuf(&mut wb)?;
+        wb = read_ods_buf(&buf)?;
+        check(wb.sheet(0), &widths, &values);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_col_width_interleaved() -> Result<(), OdsError> {
+    for seed in 1..=25 {
+        interleaved(seed * 0x9E37_79B9)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_col_width_shared_style() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for col in 0..6 {
+        sh.set_col_width(col, Length::Cm(2.0));
+        sh.set_value(0, col, col);
+    }
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).colstyle(0), wb.sheet(0).colstyle(5));
+
+    // Same style, different width.
+    wb.sheet_mut(0).set_col_width(3, Length::Cm(5.0));
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    for col in 0..6 {
+        let w = if col == 3 { 5.0 } else { 2.0 };
+        assert_eq!(sh.col_width(col), Length::Cm(w));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_col_header_gap() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_col_width(2, Length::Cm(2.0));
+    sh.set_col_width(7, Length::Cm(2.0));
+    sh.set_value(0, 9, 1);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    for col in 0..10 {
+        let w = if col == 2 || col == 7 {
+            Length::Cm(2.0)
+        } else {
+            Length::Default
+        };
+        assert_eq!(sh.col_width(col), w, "col {}", col);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+#![allow(deprecated)]
+
+use spreadsheet_ods::compat::Template;
+use spreadsheet_ods::metadata::{AutoReload, UserDefined};
+use spreadsheet_ods::{OdsOptions, Sheet};
+
+#[test]
+fn test_compat() {
+    let mut sh = Sheet::new("one");
+    sh.set_value(4, 7, 1);
+    sh.set_row_repeat(2, 3);
+    assert_eq!(sh.used_rows(), sh.row_header_max());
+    assert_eq!(sh.used_cols(), sh.col_header_max());
+
+    let _ = OdsOptions::default().use_clone_for_repeat();
+    let _ = Template::default();
+    let _ = AutoReload::default();
+    let _ = UserDefined::default();
+}
@@ -1,8 +1,12 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::sheet::SplitMode;
-use spreadsheet_ods::{read_ods, OdsError};
+use spreadsheet_ods::sheet::{SplitMode, Visibility};
+use spreadsheet_ods::workbook::{CellTag, NamedView};
+use spreadsheet_ods::{
+    read_fods_buf, read_ods, read_ods_buf, write_fods_buf, CellRange, OdsError, OdsWarning, Sheet,
+    WorkBook,
+};
 
 #[test]
 fn read_orders() -> Result<(), OdsError> {
@@ -18,3 +22,159 @@ fn read_orders() -> Result<(), OdsError> {
     test_write_ods(&mut wb, "test_out/test_config.ods")?;
     Ok(())
 }
+
+#[test]
+fn named_views() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("one"));
+    wb.push_sheet(Sheet::new("two"));
+
+    let mut v = NamedView::new("sales", "two");
+    v.cursor_x = 3;
+    v.cursor_y = 7;
+    v.zoom_value = 150;
+    v.hidden_cols = vec![1, 2];
+    wb.add_named_view(v);
+
+    let mut v = NamedView::new("finance", "two");
+    v.hidden_cols = vec![4];
+    wb.add_named_view(v);
+
+    wb.activate_named_view("sales")?;
+    assert_eq!(wb.config().active_table, "two");
+    assert_eq!(wb.sheet(1).config().cursor_x, 3);
+    assert_eq!(wb.sheet(1).config().zoom_value, 150);
+    assert_eq!(wb.sheet(1).col_visible(1), Visibility::Collapsed);
+
+    wb.activate_named_view("finance")?;
+    assert_eq!(wb.sheet(1).col_visible(1), Visibility::Visible);
+    assert_eq!(wb.sheet(1).col_visible(4), Visibility::Collapsed);
+
+    assert!(wb.activate_named_view("unknown").is_err());
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let v = wb.named_view("sales").expect("view");
+    assert_eq!(v.active_table, "two");
+    assert_eq!(v.cursor_y, 7);
+    assert_eq!(v.hidden_cols, vec![1, 2]);
+    assert_eq!(wb.iter_named_views().count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn named_views_invalid_hidden_cols() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("one"));
+    let mut v = NamedView::new("sales", "one");
+    v.hidden_cols = vec![1, 2];
+    wb.add_named_view(v);
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let xml = String::from_utf8(buf)
+        .expect("utf8")
+        .replace(">1,2<", ">1,x,2<");
+    let wb = read_fods_buf(xml.as_bytes())?;
+
+    let v = wb.named_view("sales").expect("view");
+    assert_eq!(v.hidden_cols, vec![1, 2]);
+    assert!(wb.warnings().iter().any(|v| matches!(
+        v,
+        OdsWarning::Recovered { context, .. } if context == "hidden columns of named view sales"
+    )));
+
+    Ok(())
+}
+
+#[test]
+fn cell_tags() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("report"));
+
+    wb.add_cell_tag(CellTag::new(
+        "totals",
+        CellRange::remote("report", 10, 1, 10, 4),
+        "v1",
+    ));
+    wb.add_cell_tag(CellTag::new(
+        "input",
+        CellRange::remote("report", 0, 0, 0, 0),
+        "",
+    ));
+    wb.add_cell_tag(CellTag::new(
+        "totals",
+        CellRange::remote("report", 11, 1, 11, 4),
+        "say \"v2\"",
+    ));
+    assert_eq!(wb.iter_cell_tags().count(), 2);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+    let tag = wb.cell_tag("totals").expect("tag");
+    assert_eq!(
+        tag.range,
+        CellRange::remote("report", 11, 1, 11, 4).absolute()
+    );
+    assert_eq!(tag.value, "say \"v2\"");
+    assert!(wb.cell_tag("input").is_some());
+
+    wb.remove_cell_tag("input");
+    wb.remove_cell_tag("totals");
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.iter_cell_tags().count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn cell_tags_named_ranges() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("report"));
+    wb.add_cell_tag(CellTag::new(
+        "totals",
+        CellRange::remote("report", 10, 1, 10, 4),
+        "v1",
+    ));
+    wb.add_cell_tag(CellTag::new(
+        "broken",
+        CellRange::remote("report", 0, 0, 0, 0),
+        "",
+    ));
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let fods = String::from_utf8(buf).expect("utf8");
+    assert!(fods.contains(
+        r#"<table:named-range table:name="CellTag_totals" table:cell-range-address="$report.$B$11:.$E$11" table:base-cell-address="$report.$B$11"/>"#
+    ));
+    assert!(
+        fods.contains(r#"table:name="CellTagValue_totals" table:expression="of:=&quot;v1&quot;""#)
+    );
+
+    // other named ranges stay, a broken tag is skipped.
+    let fods = fods
+        .replace(
+            "<table:named-range table:name=\"CellTag_totals\"",
+            "<table:named-range table:name=\"other\" table:cell-range-address=\"$report.$A$1\"/><table:named-range table:name=\"CellTag_totals\"",
+        )
+        .replace("$report.$A$1:.$A$1", "report.x");
+    let mut wb = read_fods_buf(fods.as_bytes())?;
+    assert_eq!(wb.iter_cell_tags().count(), 1);
+    assert_eq!(
+        wb.cell_tag("totals").expect("tag").range,
+        CellRange::remote("report", 10, 1, 10, 4).absolute()
+    );
+    assert_eq!(wb.cell_tag("totals").expect("tag").value, "v1");
+    assert!(wb.warnings().iter().any(|v| matches!(
+        v,
+        OdsWarning::Recovered { context, .. } if context == "cell tag broken"
+    )));
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let fods = String::from_utf8(buf).expect("utf8");
+    assert!(fods.contains(r#"table:name="other""#));
+    assert_eq!(fods.matches("CellTag_totals").count(), 1);
+
+    Ok(())
+}
@@ -1,4 +1,3 @@
-use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 
@@ -113,43 +112,49 @@ fn test_write_gap_repeat() -> Result<(), OdsError> {
 }
 
 #[test]
-#[should_panic]
-fn test_write_row_overlap() -> () {
+fn test_write_row_overlap() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
     let mut sh = Sheet::new("Sheet1");
     sh.set_value(2, 0, 1);
     sh.set_row_repeat(2, 2);
-    sh.set_value(3, 0, 1);
+    sh.set_value(3, 0, 2);
+    assert_eq!(sh.row_repeat(2), 1);
+    assert_eq!(sh.row_repeat(3), 1);
     wb.push_sheet(sh);
 
-    match test_write_ods(&mut wb, "test_out/test_core_writing_loop_6.ods") {
-        Ok(_) => {}
-        Err(_) => {
-            let _ = fs::remove_file("test_out/test_core_writing_loop_6.ods");
-            panic!();
-        }
-    }
+    test_write_ods(&mut wb, "test_out/test_core_writing_loop_6.ods")?;
+
+    let wb = read_ods("test_out/test_core_writing_loop_6.ods")?;
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.value(2, 0).as_i32_or(0), 1);
+    assert_eq!(sh.value(3, 0).as_i32_or(0), 2);
+
+    Ok(())
 }
 
 #[test]
-#[should_panic]
-fn test_write_col_overlap() -> () {
+fn test_write_col_overlap() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
     let mut sh = Sheet::new("Sheet1");
     sh.set_value(3, 0, 100);
     sh.set_cell_repeat(3, 0, 5);
     sh.set_value(3, 4, 101);
+    assert_eq!(sh.cell_repeat(3, 0), 4);
     wb.push_sheet(sh);
 
-    match test_write_ods(&mut wb, "test_out/test_core_writing_loop_7.ods") {
-        Ok(_) => {}
-        Err(_) => {
-            let _ = fs::remove_file("test_out/test_core_writing_loop_7.ods");
-            panic!();
-        }
-    }
+    test_write_ods(&mut wb, "test_out/test_core_writing_loop_7.ods")?;
+
+    let wb = read_ods("test_out/test_core_writing_loop_7.ods")?;
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.value(3, 0).as_i32_or(0), 100);
+    assert_eq!(sh.value(3, 3).as_i32_or(0), 100);
+    assert_eq!(sh.value(3, 4).as_i32_or(0), 101);
+
+    Ok(())
 }
 
 #[test]
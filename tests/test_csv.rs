@@ -0,0 +1,79 @@
+use spreadsheet_ods::csv::CsvOptions;
+use spreadsheet_ods::sheet::MergedCells;
+use spreadsheet_ods::{CellRange, Sheet, WorkBook};
+
+fn merged_sheet() -> Sheet {
+    let mut sheet = Sheet::new("merged");
+    sheet.set_value(0, 0, "head");
+    sheet.set_col_span(0, 0, 2);
+    sheet.set_value(0, 2, "x");
+    sheet.set_value(1, 0, "a, \"b\"");
+    sheet.set_value(1, 1, "long");
+    sheet.set_row_span(1, 1, 2);
+    sheet.set_value(1, 2, 1);
+    sheet.set_value(2, 0, "c|d");
+    sheet.set_value(2, 2, 2);
+    sheet
+}
+
+#[test]
+fn test_csv_merged() {
+    let book = WorkBook::new_empty();
+    let sheet = merged_sheet();
+    let range = CellRange::local(0, 0, 2, 2);
+
+    let csv = sheet.to_csv(&book, &range, CsvOptions::default());
+    assert_eq!(csv, "head,,x\n\"a, \"\"b\"\"\",long,1\nc|d,,2\n");
+
+    let csv = sheet.to_csv(
+        &book,
+        &range,
+        CsvOptions::default()
+            .separator(';')
+            .merged(MergedCells::Repeat),
+    );
+    assert_eq!(csv, "head;head;x\n\"a, \"\"b\"\"\";long;1\nc|d;long;2\n");
+
+    let csv = sheet.to_csv(
+        &book,
+        &range,
+        CsvOptions::default().merged(MergedCells::Span),
+    );
+    assert_eq!(csv, "head,,x\n\"a, \"\"b\"\"\",long,1\nc|d,,2\n");
+}
+
+#[test]
+fn test_csv_clipped_merge() {
+    let book = WorkBook::new_empty();
+    let sheet = merged_sheet();
+
+    let csv = sheet.to_csv(
+        &book,
+        &CellRange::local(2, 1, 2, 2),
+        CsvOptions::default().merged(MergedCells::Repeat),
+    );
+    assert_eq!(csv, "long,2\n");
+}
+
+#[test]
+fn test_markdown_merged() {
+    let book = WorkBook::new_empty();
+    let sheet = merged_sheet();
+    let range = CellRange::local(0, 0, 2, 2);
+
+    let md = sheet.to_markdown(
+        &book,
+        &range,
+        CsvOptions::default().merged(MergedCells::Repeat),
+    );
+    assert_eq!(
+        md,
+        "| head | head | x |\n| --- | --- | --- |\n| a, \"b\" | long | 1 |\n| c\\|d | long | 2 |\n"
+    );
+
+    let md = sheet.to_markdown(&book, &range, CsvOptions::default());
+    assert_eq!(
+        md,
+        "| head |  | x |\n| --- | --- | --- |\n| a, \"b\" | long | 1 |\n| c\\|d |  | 2 |\n"
+    );
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "arrow")]
+
+use arrow::array::{
+    Array, AsArray, BooleanArray, Date32Array, Int32Array, StringArray, TimestampMillisecondArray,
+};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema, TimeUnit, TimestampMillisecondType};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, NaiveDate};
+use spreadsheet_ods::{CellRange, OdsError, Sheet, Value};
+use std::sync::Arc;
+
+#[test]
+fn test_from_dataframe() -> Result<(), OdsError> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("active", DataType::Boolean, true),
+        Field::new("since", DataType::Date32, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+            Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+            Arc::new(Date32Array::from(vec![Some(19000), None, Some(19002)])),
+        ],
+    )
+    .expect("batch");
+
+    let sh = Sheet::from_dataframe("data", &batch)?;
+    assert_eq!(sh.value(0, 0), &Value::Text("id".into()));
+    assert_eq!(sh.value(0, 3), &Value::Text("since".into()));
+    assert_eq!(sh.value(1, 0), &Value::Number(1.0));
+    assert_eq!(sh.value(3, 0), &Value::Number(3.0));
+    assert_eq!(sh.value(1, 1), &Value::Text("a".into()));
+    assert_eq!(sh.value(2, 1), &Value::Empty);
+    assert_eq!(sh.value(2, 2), &Value::Boolean(false));
+    assert_eq!(
+        sh.value(1, 3),
+        &Value::DateTime(
+            NaiveDate::from_ymd_opt(2022, 1, 8)
+                .expect("date")
+                .and_hms_opt(0, 0, 0)
+                .expect("time")
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_dataframe() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("data");
+    sh.set_row_values(0, 0, ["num", "", "mixed"]);
+    sh.set_col_values(1, 0, [1.5, 2.5]);
+    sh.set_value(1, 1, true);
+    sh.set_value(1, 2, "x");
+    sh.set_value(2, 2, 1);
+
+    let batch = sh.to_dataframe(&CellRange::local(0, 0, 3, 2))?;
+    assert_eq!(batch.num_rows(), 3);
+
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).name(), "num");
+    assert_eq!(schema.field(0).data_type(), &DataType::Float64);
+    assert_eq!(schema.field(1).name(), "B");
+    assert_eq!(schema.field(1).data_type(), &DataType::Boolean);
+    assert_eq!(schema.field(2).data_type(), &DataType::Utf8);
+
+    let num = batch.column(0).as_primitive::<Float64Type>();
+    assert_eq!(num.value(1), 2.5);
+    assert!(num.is_null(2));
+    let mixed = batch.column(2).as_string::<i32>();
+    assert_eq!(mixed.value(0), "x");
+    assert_eq!(mixed.value(1), "1");
+    Ok(())
+}
+
+#[test]
+fn test_dataframe_timezone() -> Result<(), OdsError> {
+    let schema = Schema::new(vec![Field::new(
+        "at",
+        DataType::Timestamp(TimeUnit::Millisecond, Some("+02:00".into())),
+        true,
+    )]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(
+            TimestampMillisecondArray::from(vec![Some(1_700_000_000_000), None])
+                .with_timezone("+02:00"),
+        )],
+    )
+    .expect("batch");
+
+    let sh = Sheet::from_dataframe("data", &batch)?;
+    let at = DateTime::parse_from_rfc3339("2023-11-15T00:13:20+02:00").expect("datetime");
+    assert_eq!(sh.value(1, 0), &Value::DateTimeTz(at));
+    assert_eq!(sh.value(2, 0), &Value::Empty);
+
+    let batch = sh.to_dataframe(&CellRange::local(0, 0, 2, 0))?;
+    assert_eq!(
+        batch.schema().field(0).data_type(),
+        &DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into()))
+    );
+    let at = batch.column(0).as_primitive::<TimestampMillisecondType>();
+    assert_eq!(at.value(0), 1_700_000_000_000);
+    assert!(at.is_null(1));
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use spreadsheet_ods::diff::DiffKind;
+use spreadsheet_ods::{diff, CellStyle, OdsError, Sheet, Value, WorkBook};
+
+#[test]
+fn test_diff() -> Result<(), OdsError> {
+    let mut a = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold");
+    st.set_font_bold();
+    let bold = a.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_value(0, 1, 2);
+    sh.set_value(0, 2, 3);
+    a.push_sheet(sh);
+    a.push_sheet(Sheet::new("two"));
+
+    let mut b = a.clone();
+    assert!(diff(&a, &b).is_empty());
+
+    b.sheet_mut(0).set_value(0, 1, 22);
+    b.sheet_mut(0).remove_cell(0, 2);
+    b.sheet_mut(0).set_cellstyle(0, 0, &bold);
+    b.sheet_mut(0).set_value(5, 5, "new");
+    b.remove_sheet(1);
+    b.push_sheet(Sheet::new("three"));
+    b.cellstyle_mut("bold").expect("style").set_font_italic();
+
+    let d = diff(&a, &b);
+
+    assert_eq!(d.sheets().len(), 2);
+    assert_eq!(d.sheets()[0].name(), "two");
+    assert_eq!(d.sheets()[0].kind(), DiffKind::Removed);
+    assert_eq!(d.sheets()[1].name(), "three");
+    assert_eq!(d.sheets()[1].kind(), DiffKind::Added);
+
+    let cells: Vec<_> = d
+        .cells()
+        .iter()
+        .map(|v| (v.sheet(), v.row(), v.col(), v.kind()))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            ("one", 0, 0, DiffKind::Changed),
+            ("one", 0, 1, DiffKind::Changed),
+            ("one", 0, 2, DiffKind::Removed),
+            ("one", 5, 5, DiffKind::Added),
+        ]
+    );
+    assert_eq!(
+        d.cells()[1].old_cell().map(|v| &v.value),
+        Some(&Value::Number(2.0))
+    );
+    assert_eq!(
+        d.cells()[1].new_cell().map(|v| &v.value),
+        Some(&Value::Number(22.0))
+    );
+    assert!(d.cells()[2].new_cell().is_none());
+
+    assert_eq!(d.styles().len(), 1);
+    assert_eq!(d.styles()[0].name(), "bold");
+    assert_eq!(d.styles()[0].kind(), DiffKind::Changed);
+
+    let mut c = a.clone();
+    c.sheet_mut(0).set_display(false);
+    let d = diff(&a, &c);
+    assert_eq!(d.sheets().len(), 1);
+    assert_eq!(d.sheets()[0].kind(), DiffKind::Changed);
+    assert!(d.cells().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_repeat() {
+    let mut a = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "x");
+    sh.set_value(0, 1, "x");
+    sh.set_value(1, 0, "y");
+    a.push_sheet(sh);
+
+    // same content written with a repeat count.
+    let mut b = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "x");
+    sh.set_cell_repeat(0, 0, 2);
+    sh.set_value(1, 0, "y");
+    b.push_sheet(sh);
+    assert!(diff(&a, &b).is_empty());
+
+    // repeating a row adds the copies.
+    b.sheet_mut(0).set_row_repeat(1, 3);
+    let d = diff(&a, &b);
+    let cells: Vec<_> = d
+        .cells()
+        .iter()
+        .map(|v| (v.row(), v.col(), v.kind()))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![(2, 0, DiffKind::Added), (3, 0, DiffKind::Added)]
+    );
+    assert_eq!(
+        d.cells()[0].new_cell().map(|v| (&v.value, v.repeat)),
+        Some((&Value::from("y"), 1))
+    );
+}
@@ -0,0 +1,106 @@
+mod lib_test;
+
+use lib_test::*;
+use spreadsheet_ods::format::ValueFormatNumber;
+use spreadsheet_ods::text::TextP;
+use spreadsheet_ods::{read_ods_buf, CellStyle, OdsError, Sheet, WorkBook};
+
+fn book(style: &str, format: &str, decimals: u8, value: f64) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut f = ValueFormatNumber::new_named(format);
+    f.part_number().fixed_decimal_places(decimals).build();
+    let f = wb.add_number_format(f);
+
+    let mut st = CellStyle::new(style, &f);
+    st.set_font_bold();
+    let st = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_styled_value(0, 0, value, &st);
+    sh.set_value(1, 0, "text");
+    sh.set_formula(2, 0, "of:=[.A1]*2");
+    wb.push_sheet(sh);
+
+    wb
+}
+
+#[test]
+fn test_content_digest() -> Result<(), OdsError> {
+    let wb = book("s1", "f1", 2, 1.5);
+    let digest = wb.content_digest();
+    assert_eq!(digest, wb.content_digest());
+    assert_eq!(
+        wb.sheet(0).content_digest(&wb),
+        book("s1", "f1", 2, 1.5).sheet(0).content_digest(&wb)
+    );
+
+    // names don't matter
+    assert_eq!(digest, book("other", "f99", 2, 1.5).content_digest());
+    // values and styles do
+    assert_ne!(digest, book("s1", "f1", 2, 2.5).content_digest());
+    assert_ne!(digest, book("s1", "f1", 3, 1.5).content_digest());
+
+    // metadata is ignored
+    let mut wb2 = book("s1", "f1", 2, 1.5);
+    wb2.metadata_mut().title = "title".to_string();
+    assert_eq!(digest, wb2.content_digest());
+
+    // plain text is the same either way
+    let mut wb2 = book("s1", "f1", 2, 1.5);
+    wb2.sheet_mut(0)
+        .set_value(1, 0, TextP::new().text("text").into_xmltag());
+    assert_eq!(digest, wb2.content_digest());
+
+    let mut wb2 = book("s1", "f1", 2, 1.5);
+    wb2.sheet_mut(0).set_formula(2, 0, "of:=[.A1]*3");
+    assert_ne!(digest, wb2.content_digest());
+
+    Ok(())
+}
+
+#[test]
+fn test_content_digest_write_read() -> Result<(), OdsError> {
+    let mut wb = book("s1", "f1", 2, 1.5);
+    let digest = wb.content_digest();
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(digest, wb.content_digest());
+
+    Ok(())
+}
+
+#[test]
+fn test_content_digest_repeat() -> Result<(), OdsError> {
+    let mut explicit = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "x");
+    sh.set_value(0, 1, "x");
+    sh.set_value(1, 0, "x");
+    sh.set_value(1, 1, "x");
+    explicit.push_sheet(sh);
+
+    let mut repeated = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "x");
+    sh.set_cell_repeat(0, 0, 2);
+    sh.set_row_repeat(0, 2);
+    repeated.push_sheet(sh);
+
+    assert_eq!(explicit.content_digest(), repeated.content_digest());
+    let buf = test_write_odsbuf(&mut repeated)?;
+    assert_eq!(
+        explicit.content_digest(),
+        read_ods_buf(&buf)?.content_digest()
+    );
+
+    // repeating a row is a change.
+    let digest = repeated.content_digest();
+    repeated.sheet_mut(0).set_row_repeat(0, 5);
+    assert_ne!(digest, repeated.content_digest());
+    repeated.sheet_mut(0).set_cell_repeat(0, 0, 1);
+    assert_ne!(digest, repeated.content_digest());
+
+    Ok(())
+}
@@ -1,7 +1,13 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::{read_ods, OdsError};
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::draw::{
+    Anchor, Annotation, DrawFrame, DrawFrameContent, DrawLine, DrawObject, DrawRect, DrawShape,
+};
+use spreadsheet_ods::sheet::Visibility;
+use spreadsheet_ods::style::{FillImage, Gradient, GradientStyle, GraphicStyle, Hatch, HatchStyle};
+use spreadsheet_ods::{read_ods, read_ods_buf, Angle, CellRef, Length, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_draw_image() -> Result<(), OdsError> {
@@ -32,3 +38,241 @@ fn test_images() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_shapes() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("flow");
+    sh.set_value(0, 0, "start");
+
+    let mut rect = DrawRect::new();
+    rect.set_draw_name("step1");
+    rect.svg_x(Length::Cm(1.0));
+    rect.svg_y(Length::Cm(1.0));
+    rect.set_width(Length::Cm(3.0));
+    rect.set_height(Length::Cm(1.5));
+    rect.set_table_end_cell_address(CellRef::local(4, 3));
+    rect.set_table_end_x(Length::Cm(0.5));
+    rect.set_table_end_y(Length::Cm(0.5));
+    rect.push_text_str("Step 1");
+    sh.add_shape(rect);
+
+    let mut line = DrawLine::new_line(
+        Length::Cm(4.0),
+        Length::Cm(1.75),
+        Length::Cm(6.0),
+        Length::Cm(1.75),
+    );
+    line.set_title("arrow");
+    sh.add_shape(line);
+
+    let mut frame = DrawFrame::new();
+    frame.set_draw_name("frame1");
+    sh.add_shape(frame);
+
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.shapes().len(), 3);
+    match &sh.shapes()[0] {
+        DrawShape::Rect(rect) => {
+            assert_eq!(rect.attrmap().attr("draw:name"), Some("step1"));
+            assert_eq!(rect.attrmap().attr("table:end-cell-address"), Some(".D5"));
+            assert_eq!(rect.text().len(), 1);
+        }
+        v => panic!("{:?}", v),
+    }
+    match &sh.shapes()[1] {
+        DrawShape::Line(line) => {
+            assert_eq!(line.title().map(|v| v.as_str()), Some("arrow"));
+            assert_eq!(line.attrmap().attr("svg:x2"), Some("6cm"));
+        }
+        v => panic!("{:?}", v),
+    }
+    assert!(matches!(&sh.shapes()[2], DrawShape::Frame(_)));
+
+    Ok(())
+}
+
+#[test]
+fn test_embedded_object() -> Result<(), OdsError> {
+    let math = br#"<?xml version="1.0" encoding="UTF-8"?>
+<math xmlns="http://www.w3.org/1998/Math/MathML"><semantics><mi>x</mi></semantics></math>"#;
+
+    let mut wb = WorkBook::new_empty();
+    let href = wb.add_embedded_object(
+        "Object 1",
+        "application/vnd.oasis.opendocument.formula",
+        math.to_vec(),
+    );
+    assert_eq!(href, "./Object 1");
+
+    let mut sh = Sheet::new("math");
+    let mut frame = DrawFrame::new();
+    frame.set_width(Length::Cm(2.0));
+    frame.set_height(Length::Cm(1.0));
+    frame.push_content(DrawFrameContent::Object(DrawObject::new_href(href)));
+    sh.add_draw_frame(1, 1, frame);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    assert_eq!(
+        wb.manifest("Object 1/").map(|v| v.media_type.as_str()),
+        Some("application/vnd.oasis.opendocument.formula")
+    );
+    assert_eq!(
+        wb.manifest("Object 1/content.xml")
+            .and_then(|v| v.buffer.as_deref()),
+        Some(math.as_slice())
+    );
+    let frames = wb.sheet(0).draw_frames(1, 1).expect("frames");
+    match frames[0].content_ref().first() {
+        Some(DrawFrameContent::Object(obj)) => {
+            assert_eq!(obj.attrmap().attr("xlink:href"), Some("./Object 1"));
+            assert_eq!(obj.attrmap().attr("xlink:show"), Some("embed"));
+        }
+        v => panic!("{:?}", v),
+    }
+
+    wb.remove_embedded_object("./Object 1");
+    assert!(wb.manifest("Object 1/").is_none());
+    assert!(wb.manifest("Object 1/content.xml").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_fills() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut gradient = Gradient::new("sunset", GradientStyle::Linear);
+    gradient.set_start_color(Rgb::new(255, 200, 0));
+    gradient.set_end_color(Rgb::new(160, 0, 0));
+    gradient.set_angle(Angle::Deg(45.0));
+    let gradient = wb.add_gradient(gradient);
+
+    let mut hatch = Hatch::new("", HatchStyle::Double);
+    hatch.set_color(Rgb::new(0, 0, 128));
+    hatch.set_distance(Length::Mm(1.0));
+    let hatch = wb.add_hatch(hatch);
+
+    let image = wb.add_fill_image(FillImage::new("paper", "Pictures/paper.png"));
+
+    let mut gs = GraphicStyle::new("g1");
+    gs.set_fill_gradient(&gradient);
+    wb.add_graphicstyle(gs);
+    let mut gs = GraphicStyle::new("g2");
+    gs.set_fill_color(Rgb::new(255, 255, 255));
+    gs.set_fill_hatch(&hatch, true);
+    wb.add_graphicstyle(gs);
+    let mut gs = GraphicStyle::new("g3");
+    gs.set_fill_image(&image);
+    wb.add_graphicstyle(gs);
+
+    wb.push_sheet(Sheet::new("one"));
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+
+    let gradient = wb.gradient("sunset").expect("gradient");
+    assert_eq!(gradient.attrmap().attr("draw:style"), Some("linear"));
+    assert_eq!(gradient.attrmap().attr("draw:start-color"), Some("#ffc800"));
+    assert_eq!(gradient.attrmap().attr("draw:angle"), Some("45deg"));
+    let hatch = wb.hatch(hatch).expect("hatch");
+    assert_eq!(hatch.attrmap().attr("draw:style"), Some("double"));
+    assert_eq!(hatch.attrmap().attr("draw:distance"), Some("1mm"));
+    assert_eq!(
+        wb.fill_image("paper").and_then(|v| v.href()),
+        Some("Pictures/paper.png")
+    );
+
+    let gs = wb.graphicstyle("g1").expect("g1");
+    assert_eq!(gs.fill(), Some("gradient"));
+    assert_eq!(
+        gs.graphicstyle().attr("draw:fill-gradient-name"),
+        Some("sunset")
+    );
+    let gs = wb.graphicstyle("g2").expect("g2");
+    assert_eq!(gs.fill(), Some("hatch"));
+    assert_eq!(
+        gs.graphicstyle().attr("draw:fill-hatch-solid"),
+        Some("true")
+    );
+    assert_eq!(gs.graphicstyle().attr("draw:fill-color"), Some("#ffffff"));
+    let gs = wb.graphicstyle("g3").expect("g3");
+    assert_eq!(
+        gs.graphicstyle().attr("draw:fill-image-name"),
+        Some("paper")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_anchor() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("anchor");
+    sh.set_col_width(0, Length::Cm(1.0));
+    sh.set_col_width(1, Length::Cm(2.0));
+    sh.set_col_visible(2, Visibility::Collapsed);
+    sh.set_col_width(3, Length::Cm(2.0));
+    sh.set_row_height(0, Length::Cm(0.5));
+    sh.set_row_height(1, Length::Cm(0.5));
+
+    let anchor = Anchor::new(
+        1,
+        1,
+        Length::Cm(1.0),
+        Length::Mm(2.0),
+        Length::Cm(2.0),
+        Length::Cm(0.5),
+    );
+    assert_eq!(anchor.position(&sh), (Length::Cm(2.0), Length::Cm(0.7)));
+    assert_eq!(
+        anchor.end(&sh),
+        (CellRef::local(2, 3), Length::Cm(1.0), Length::Cm(0.2))
+    );
+
+    let mut frame = DrawFrame::new();
+    anchor.set_frame(&sh, &mut frame);
+    sh.add_draw_frame(1, 1, frame);
+
+    // default sizes
+    let mut annotation = Annotation::new("note");
+    Anchor::new(
+        5,
+        5,
+        Length::Default,
+        Length::Default,
+        Length::Cm(3.0),
+        Length::Cm(1.0),
+    )
+    .set_annotation(&sh, &mut annotation);
+    assert_eq!(
+        annotation.attrmap().attr("table:end-cell-address"),
+        Some(".G8")
+    );
+    sh.set_annotation(5, 5, annotation);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    let frames = sh.draw_frames(1, 1).expect("frames");
+    assert_eq!(frames[0].attrmap().attr("svg:x"), Some("2cm"));
+    assert_eq!(
+        frames[0].attrmap().attr("table:end-cell-address"),
+        Some(".D3")
+    );
+    assert_eq!(frames[0].attrmap().attr("table:end-x"), Some("1cm"));
+    assert_eq!(frames[0].attrmap().attr("table:end-y"), Some("0.2cm"));
+    assert!(sh.annotation(5, 5).is_some());
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use spreadsheet_ods::examples::{corpus, invoice, matrix, InvoiceData};
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, OdsError, Value};
+
+#[test]
+fn test_corpus() -> Result<(), OdsError> {
+    for (name, mut wb) in corpus() {
+        let buf = write_ods_buf(&mut wb, Vec::new())?;
+        let wb2 = read_ods_buf(&buf)?;
+        assert_eq!(wb.num_sheets(), wb2.num_sheets(), "{}", name);
+        assert_eq!(
+            wb.sheet(0).cell_count(),
+            wb2.sheet(0).cell_count(),
+            "{}",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invoice() {
+    let data = InvoiceData::sample();
+    let wb = invoice(&data);
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.value(5, 0), &Value::from("Widget"));
+    assert_eq!(sh.formula(5, 3), Some(&"of:=[.B6]*[.C6]".to_string()));
+    assert_eq!(sh.formula(8, 3), Some(&"of:=SUM([.D6:.D8])".to_string()));
+    assert_eq!(sh.formula(10, 3), Some(&"of:=[.D9]+[.D10]".to_string()));
+}
+
+#[test]
+fn test_invoice_empty() {
+    let mut data = InvoiceData::sample();
+    data.items.clear();
+    let wb = invoice(&data);
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.formula(5, 3), None);
+    assert_eq!(sh.value(5, 3), &Value::new_currency("EUR", 0.0));
+    assert_eq!(sh.formula(6, 3), Some(&"of:=[.D6]*0.2".to_string()));
+}
+
+#[test]
+fn test_matrix() {
+    let wb = matrix(3, 4);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.cell_count(), 12);
+    assert_eq!(sh.value(2, 3), &Value::Number(11.0));
+}
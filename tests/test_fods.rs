@@ -1,4 +1,7 @@
-use spreadsheet_ods::{read_fods, read_ods, write_fods, OdsError};
+use spreadsheet_ods::text::{TextP, TextS};
+use spreadsheet_ods::{
+    read_fods, read_fods_buf, read_ods, write_fods, OdsError, OdsWriteOptions, Sheet, Value,
+};
 
 #[test]
 fn read_write_fods() -> Result<(), OdsError> {
@@ -7,3 +10,42 @@ fn read_write_fods() -> Result<(), OdsError> {
     let _wb = read_fods("test_out/test_fods.fods")?;
     Ok(())
 }
+
+#[test]
+fn write_fods_indent() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/test_fods.ods")?;
+    let mut sh = Sheet::new("indent");
+    sh.set_value(0, 0, "some text");
+    sh.set_value(
+        1,
+        0,
+        TextP::new()
+            .text("a")
+            .tag(TextS::new())
+            .text("b")
+            .into_xmltag(),
+    );
+    sh.set_value(2, 0, 42);
+    wb.push_sheet(sh);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default()
+        .indent(true)
+        .write_fods(&mut wb, &mut buf)?;
+    let mut buf2 = Vec::new();
+    OdsWriteOptions::default()
+        .indent(true)
+        .write_fods(&mut wb, &mut buf2)?;
+    assert_eq!(buf, buf2);
+
+    let xml = String::from_utf8_lossy(&buf);
+    assert!(xml.contains("\n  <office:body>\n    <office:spreadsheet>"));
+    assert!(xml.contains("<text:p>some text</text:p>"));
+    assert!(xml.contains("<text:p>a<text:s/>b</text:p>"));
+
+    let wb2 = read_fods_buf(&buf)?;
+    let sh = wb2.sheet(wb2.num_sheets() - 1);
+    assert_eq!(sh.value(0, 0), &Value::Text("some text".into()));
+    assert_eq!(sh.value(2, 0), &Value::Number(42.0));
+    Ok(())
+}
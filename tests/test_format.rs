@@ -3,11 +3,19 @@ mod lib_test;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use icu_locid::locale;
 use lib_test::*;
-use spreadsheet_ods::format::{FormatCalendarStyle, FormatNumberStyle};
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::format::{
+    create_accounting_format, create_currency_prefix, create_currency_suffix,
+    create_date_format_with_calendar, create_engineering_format, create_iso_datetime_format,
+    create_scientific_format, create_year_week_format, EmbeddedText, FormatCalendarStyle,
+    FormatNumberStyle, FormatPartType, ValueFormatTrait,
+};
+use spreadsheet_ods::html::HtmlOptions;
 use spreadsheet_ods::style::CellStyle;
 use spreadsheet_ods::{
-    OdsError, Sheet, ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime,
-    ValueFormatNumber, ValueFormatPercentage, WorkBook,
+    read_ods_buf, write_ods_buf, CellRange, Currency, OdsError, Sheet, Value, ValueFormatBoolean,
+    ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage,
+    ValueFormatTimeDuration, WorkBook,
 };
 
 #[test]
@@ -127,3 +135,492 @@ fn write_format() -> Result<(), OdsError> {
         test_write_ods(&mut wb, path)
     }
 }
+
+#[test]
+fn write_checkbox() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let checkbox = wb.add_checkbox_style();
+    assert_eq!(wb.add_checkbox_style(), checkbox);
+    assert!(wb.number_format("checkbox").is_some());
+    assert!(wb.number_format("checkbox_checked").is_some());
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, true, &checkbox);
+    sh.set_styled_value(1, 0, false, &checkbox);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_format_checkbox.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn read_write_currency() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let f1 = wb.add_currency_format(create_currency_suffix("f1", locale!("de_AT"), "€"));
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &f1));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, Value::new_currency("EUR", 12.5), &s1);
+    sh.set_value(1, 0, Value::new_currency("A&B", 1.0));
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).currency_code(), Some("EUR"));
+    assert_eq!(sh.value(0, 0).currency_symbol(), Some("€"));
+    assert_eq!(sh.value(1, 0).currency_code(), Some("A&B"));
+    assert_eq!(sh.value(1, 0).currency_symbol(), Some("A&B"));
+    assert_eq!(Value::Number(1.0).currency_code(), None);
+    assert_eq!(sh.value(0, 0).iso_currency(), Some(Currency::new("EUR")?));
+    assert_eq!(sh.value(1, 0).iso_currency(), None);
+
+    let usd: Currency = "USD".parse()?;
+    assert_eq!(usd.to_string(), "USD");
+    assert_eq!(usd.symbol(), Some("$"));
+    assert_eq!(Value::new_currency(usd, 1.0).currency_code(), Some("USD"));
+    assert!(Currency::new("usd").is_err());
+    assert!(Currency::new("USDT").is_err());
+
+    let f1 = wb.currency_format("f1").expect("format");
+    assert_eq!(f1.currency_symbol(), Some("€"));
+    assert_eq!(f1.currency_symbol_first(), Some(false));
+
+    let f2 = create_currency_prefix("f2", locale!("en_US"), "$");
+    assert_eq!(f2.currency_symbol_first(), Some(true));
+    assert_eq!(
+        ValueFormatCurrency::new_named("f3").currency_symbol_first(),
+        None
+    );
+
+    Ok(())
+}
+
+#[test]
+fn read_write_embedded_text() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    // 00"-"00"<&>"00
+    let mut v1 = ValueFormatNumber::new_named("v1");
+    v1.part_number()
+        .min_integer_digits(6)
+        .embedded_text("-", 2)
+        .embedded_text("<&>", 4)
+        .build();
+    let v1 = wb.add_number_format(v1);
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 123456, &s1);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let v1 = wb.number_format("v1").expect("format");
+    assert_eq!(v1.parts().len(), 1);
+    assert_eq!(
+        v1.parts()[0].embedded_text(),
+        &[EmbeddedText::new(2, "-"), EmbeddedText::new(4, "<&>")]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn format_code() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut f = ValueFormatNumber::from_format_code("n1", "#,##0.00;[RED]-#,##0.00")?;
+    assert_eq!(f.len(), 2);
+    assert_eq!(f[0].name(), "n1P0");
+    let n1 = f.pop().expect("format");
+    assert_eq!(n1.name(), "n1");
+    assert_eq!(n1.stylemaps().map(|v| v.len()), Some(1));
+    assert_eq!(n1.textstyle().attr("fo:color"), Some("#ff0000"));
+    assert_eq!(n1.parts().len(), 2);
+    assert_eq!(n1.parts()[0].part_type(), FormatPartType::Text);
+    assert_eq!(n1.parts()[1].part_type(), FormatPartType::Number);
+    assert_eq!(n1.parts()[1].attr_def("number:grouping", ""), "true");
+    assert_eq!(n1.parts()[1].attr_def("number:decimal-places", ""), "2");
+    assert_eq!(n1.parts()[1].attr_def("number:min-integer-digits", ""), "1");
+    wb.add_number_format(f.pop().expect("format"));
+    let n1 = wb.add_number_format(n1);
+
+    let f = ValueFormatNumber::from_format_code("n2", "# ?/4")?;
+    assert_eq!(f[0].parts()[0].part_type(), FormatPartType::Fraction);
+    assert_eq!(
+        f[0].parts()[0].attr_def("number:denominator-value", ""),
+        "4"
+    );
+
+    let f = ValueFormatNumber::from_format_code("n3", "0.00E+00")?;
+    assert_eq!(
+        f[0].parts()[0].part_type(),
+        FormatPartType::ScientificNumber
+    );
+
+    let f = ValueFormatPercentage::from_format_code("p1", "0.0%")?;
+    assert_eq!(f[0].parts().len(), 2);
+    assert_eq!(f[0].parts()[1].content().map(|v| v.as_str()), Some("%"));
+
+    let f = ValueFormatCurrency::from_format_code("c1", "[$€-407] #,##0.00")?;
+    assert_eq!(f[0].currency_symbol(), Some("€"));
+    assert_eq!(f[0].currency_symbol_first(), Some(true));
+
+    let f = ValueFormatDateTime::from_format_code("d1", "DD.MM.YYYY HH:MM")?;
+    let types: Vec<_> = f[0].parts().iter().map(|v| v.part_type()).collect();
+    assert_eq!(
+        types,
+        vec![
+            FormatPartType::Day,
+            FormatPartType::Text,
+            FormatPartType::Month,
+            FormatPartType::Text,
+            FormatPartType::Year,
+            FormatPartType::Text,
+            FormatPartType::Hours,
+            FormatPartType::Text,
+            FormatPartType::Minutes,
+        ]
+    );
+
+    let mut f = ValueFormatTimeDuration::from_format_code("t1", "[HH]:MM:SS")?;
+    assert_eq!(f[0].truncate_on_overflow(), Some(false));
+
+    assert!(ValueFormatNumber::from_format_code("e1", "0;0;0;@").is_err());
+    assert!(ValueFormatNumber::from_format_code("e2", "\"0").is_err());
+
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &n1));
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, -1234.5, &s1);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_format_code.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn currency_for_iso() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let eur = ValueFormatCurrency::for_iso("EUR", locale!("de_DE"));
+    assert_eq!(eur.name(), "EUR-de-DE");
+    assert_eq!(eur.currency_symbol(), Some("€"));
+    assert_eq!(eur.currency_symbol_first(), Some(false));
+
+    let usd = ValueFormatCurrency::for_iso("USD", locale!("en_US"));
+    assert_eq!(usd.currency_symbol(), Some("$"));
+    assert_eq!(usd.currency_symbol_first(), Some(true));
+    assert_eq!(usd.parts().len(), 2);
+
+    let jpy = ValueFormatCurrency::for_iso("JPY", locale!("ja_JP"));
+    assert_eq!(jpy.parts()[1].attr_def("number:decimal-places", ""), "0");
+
+    let chf = ValueFormatCurrency::for_iso("CHF", locale!("de_CH"));
+    assert_eq!(chf.currency_symbol(), Some("CHF"));
+    assert_eq!(chf.currency_symbol_first(), Some(true));
+
+    let eur = wb.add_currency_format(eur);
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &eur));
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, Value::new_currency("EUR", 1234.5), &s1);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_format_iso.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn format_sections() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut pos = ValueFormatNumber::new_empty();
+    pos.part_number().decimal_places(2).build();
+    let mut neg = ValueFormatNumber::new_empty();
+    neg.set_color(Rgb::new(255, 0, 0));
+    neg.part_text("-").build();
+    neg.part_number().decimal_places(2).build();
+    let mut zero = ValueFormatNumber::new_named("amount");
+    zero.part_text("-").build();
+
+    let f = ValueFormatNumber::with_sections(pos, neg, zero);
+    assert_eq!(f.len(), 3);
+    assert_eq!(f[0].name(), "amountP0");
+    assert_eq!(f[1].name(), "amountP1");
+    assert_eq!(f[1].textstyle().attr("fo:color"), Some("#ff0000"));
+    assert_eq!(f[1].attrmap().attr("style:volatile"), Some("true"));
+    let stylemaps = f[2].stylemaps().expect("stylemaps");
+    assert_eq!(stylemaps[0].condition().to_string(), "value()>0");
+    assert_eq!(stylemaps[0].applied_style(), "amountP0");
+    assert_eq!(stylemaps[1].condition().to_string(), "value()<0");
+    assert_eq!(stylemaps[1].applied_style(), "amountP1");
+
+    let mut amount = None;
+    for f in f {
+        amount = Some(wb.add_number_format(f));
+    }
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &amount.expect("format")));
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, -1234.5, &s1);
+    sh.set_styled_value(1, 0, 0, &s1);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let f = wb.number_format("amount").expect("format");
+    assert_eq!(f.stylemaps().map(|v| v.len()), Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn scientific_format() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let sci = create_scientific_format("sci", 2);
+    assert_eq!(sci.parts()[0].part_type(), FormatPartType::ScientificNumber);
+    assert_eq!(
+        sci.parts()[0].attr_def("number:min-exponent-digits", ""),
+        "2"
+    );
+    let eng = create_engineering_format("eng", 1);
+    assert_eq!(eng.parts()[0].attr_def("number:exponent-interval", ""), "3");
+
+    let sci = wb.add_number_format(sci);
+    let sci = wb.add_cellstyle(CellStyle::new("sci", &sci));
+    let eng = wb.add_number_format(eng);
+    let eng = wb.add_cellstyle(CellStyle::new("eng", &eng));
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 12345, &sci);
+    sh.set_styled_value(0, 1, 12345, &eng);
+    sh.set_styled_value(0, 2, 0.0012345, &eng);
+    wb.push_sheet(sh);
+
+    let html = wb.sheet(0).to_html(
+        &wb,
+        &CellRange::local(0, 0, 0, 2),
+        HtmlOptions::default().styles(false),
+    );
+    assert!(html.contains(">1.23E+04<"));
+    assert!(html.contains(">12.3E+03<"));
+    assert!(html.contains(">1.2E-03<"));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let eng = wb.number_format("eng").expect("format");
+    assert_eq!(eng.parts()[0].attr_def("number:exponent-interval", ""), "3");
+
+    Ok(())
+}
+
+#[test]
+fn iso_datetime_format() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let dt = wb.add_datetime_format(create_iso_datetime_format("iso"));
+    let dt = wb.add_cellstyle(CellStyle::new("iso", &dt));
+    let wk = wb.add_datetime_format(create_year_week_format("week"));
+    let wk = wb.add_cellstyle(CellStyle::new("week", &wk));
+
+    let v = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .expect("date")
+        .and_hms_opt(12, 30, 5)
+        .expect("time");
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, v, &dt);
+    sh.set_styled_value(0, 1, v, &wk);
+    wb.push_sheet(sh);
+
+    let html = wb.sheet(0).to_html(
+        &wb,
+        &CellRange::local(0, 0, 0, 1),
+        HtmlOptions::default().styles(false),
+    );
+    assert!(html.contains(">2024-03-01T12:30:05<"));
+    assert!(html.contains(">2024-W09<"));
+
+    test_write_ods(&mut wb, "test_out/test_format_iso_datetime.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn accounting_format() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let f = create_accounting_format("acc", locale!("en_US"), "USD");
+    assert_eq!(f.len(), 3);
+    assert_eq!(f[1].name(), "accP1");
+    let types: Vec<_> = f[1].parts().iter().map(|v| v.part_type()).collect();
+    assert_eq!(
+        types,
+        vec![
+            FormatPartType::CurrencySymbol,
+            FormatPartType::FillCharacter,
+            FormatPartType::Text,
+            FormatPartType::Number,
+            FormatPartType::Text,
+        ]
+    );
+    assert_eq!(f[1].parts()[1].content().map(|v| v.as_str()), Some(" "));
+    assert_eq!(f[1].parts()[2].content().map(|v| v.as_str()), Some(" ("));
+    assert_eq!(f[2].name(), "acc");
+    assert_eq!(f[2].currency_symbol(), Some("$"));
+    assert_eq!(f[2].stylemaps().map(|v| v.len()), Some(2));
+    assert_eq!(
+        f[0].parts()[3].as_number().and_then(|v| v.decimal_places()),
+        Some(2)
+    );
+
+    let yen = create_accounting_format("yen", locale!("ja_JP"), "JPY");
+    assert_eq!(yen[2].currency_symbol(), Some("¥"));
+    assert_eq!(
+        yen[0].parts()[3]
+            .as_number()
+            .and_then(|v| v.decimal_places()),
+        Some(0)
+    );
+    assert_eq!(yen[0].volatile(), Some(true));
+
+    let mut acc = None;
+    for f in f {
+        acc = Some(wb.add_currency_format(f));
+    }
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &acc.expect("format")));
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, Value::new_currency("USD", -1234.5), &s1);
+    sh.set_styled_value(1, 0, Value::new_currency("USD", 0.0), &s1);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let f = wb.currency_format("accP0").expect("format");
+    assert_eq!(f.parts()[1].part_type(), FormatPartType::FillCharacter);
+    assert_eq!(f.parts()[1].content().map(|v| v.as_str()), Some(" "));
+
+    Ok(())
+}
+
+#[test]
+fn calendar_format() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    wb.add_datetime_format(create_date_format_with_calendar(
+        "buddhist",
+        FormatCalendarStyle::Buddhist,
+    ));
+    wb.add_datetime_format(create_date_format_with_calendar(
+        "gengou",
+        FormatCalendarStyle::Gengou,
+    ));
+
+    let mut v = ValueFormatDateTime::new_named("hijri");
+    v.part_day_of_week()
+        .calendar(FormatCalendarStyle::Hijri)
+        .build();
+    v.part_text(" ").build();
+    v.part_week_of_year()
+        .calendar(FormatCalendarStyle::Hijri)
+        .build();
+    v.part_text(" ").build();
+    v.part_quarter()
+        .calendar(FormatCalendarStyle::Hijri)
+        .build();
+    wb.add_datetime_format(v);
+    wb.push_sheet(Sheet::new("1"));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    for (name, calendar, n_parts) in [
+        ("buddhist", FormatCalendarStyle::Buddhist, 3),
+        ("gengou", FormatCalendarStyle::Gengou, 4),
+        ("hijri", FormatCalendarStyle::Hijri, 3),
+    ] {
+        let f = wb.datetime_format(name).expect("format");
+        let parts: Vec<_> = f
+            .parts()
+            .iter()
+            .filter(|v| v.part_type() != FormatPartType::Text)
+            .collect();
+        assert_eq!(parts.len(), n_parts);
+        for part in parts {
+            assert_eq!(part.calendar(), Some(calendar));
+        }
+    }
+    let f = wb.datetime_format("gengou").expect("format");
+    assert_eq!(f.parts()[0].part_type(), FormatPartType::Era);
+    assert_eq!(f.parts()[0].attr_def("number:calendar", ""), "gengou");
+
+    Ok(())
+}
+
+#[test]
+fn format_part_views() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut v = ValueFormatNumber::new_named("num");
+    v.part_number()
+        .fixed_decimal_places(2)
+        .min_integer_digits(1)
+        .grouping()
+        .build();
+    wb.add_number_format(v);
+    wb.add_number_format(create_engineering_format("eng", 2));
+    let mut v = ValueFormatNumber::new_named("frac");
+    v.part_fraction()
+        .min_integer_digits(0)
+        .min_numerator_digits(1)
+        .min_denominator_digits(1)
+        .denominator(16)
+        .build();
+    wb.add_number_format(v);
+    let mut v = ValueFormatDateTime::new_named("date");
+    v.part_month().long_style().textual().build();
+    v.part_text(" ").build();
+    v.part_year().build();
+    wb.add_datetime_format(v);
+    wb.push_sheet(Sheet::new("1"));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let f = wb.number_format("num").expect("format");
+    assert!(f.parts()[0].as_scientific().is_none());
+    let number = f.parts()[0].as_number().expect("number");
+    assert_eq!(number.decimal_places(), Some(2));
+    assert_eq!(number.min_decimal_places(), Some(2));
+    assert_eq!(number.min_integer_digits(), Some(1));
+    assert!(number.grouping());
+    assert_eq!(number.display_factor(), None);
+
+    let f = wb.number_format("eng").expect("format");
+    let sci = f.parts()[0].as_scientific().expect("scientific");
+    assert_eq!(sci.decimal_places(), Some(2));
+    assert_eq!(sci.exponent_interval(), Some(3));
+    assert!(!sci.grouping());
+
+    let f = wb.number_format("frac").expect("format");
+    let frac = f.parts()[0].as_fraction().expect("fraction");
+    assert_eq!(frac.min_integer_digits(), Some(0));
+    assert_eq!(frac.denominator(), Some(16));
+    assert_eq!(frac.max_denominator(), None);
+
+    let f = wb.datetime_format("date").expect("format");
+    assert!(f.parts()[1].as_datetime().is_none());
+    let month = f.parts()[0].as_datetime().expect("month");
+    assert_eq!(month.style(), Some(FormatNumberStyle::Long));
+    assert!(month.textual());
+    let year = f.parts()[2].as_datetime().expect("year");
+    assert_eq!(year.style(), None);
+    assert!(!year.textual());
+
+    Ok(())
+}
@@ -1 +1,18 @@
+use spreadsheet_ods::formula::move_formula_refs;
 
+#[test]
+fn test_move_formula_refs() {
+    assert_eq!(
+        move_formula_refs("of:=SUM([.A1:.A3])+[.$B$1]&\"[.A1]\"", 2, 1),
+        "of:=SUM([.B3:.B5])+[.$B$1]&\"[.A1]\""
+    );
+    assert_eq!(
+        move_formula_refs("of:=[.$A1]+[.A$1]", 1, 1),
+        "of:=[.$A2]+[.B$1]"
+    );
+    assert_eq!(move_formula_refs("of:=[.A1]*2", -1, 0), "of:=#REF!*2");
+    assert_eq!(
+        move_formula_refs("of:=\"a\"\"[.A1]\"", 1, 0),
+        "of:=\"a\"\"[.A1]\""
+    );
+}
@@ -1,8 +1,8 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::sheet::Grouped;
-use spreadsheet_ods::{read_ods, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::sheet::{Grouped, Visibility};
+use spreadsheet_ods::{read_ods, read_ods_buf, write_ods_buf, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_write_group1() -> Result<(), OdsError> {
@@ -127,3 +127,70 @@ fn test_write_group6() {
     sh.add_row_group(1, 4);
     sh.add_row_group(2, 5);
 }
+
+#[test]
+fn test_group_api() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("Sheet1");
+    for r in 0..10 {
+        sh.set_value(r, 0, r);
+    }
+
+    sh.group_rows(1..=2, false)?;
+    sh.group_rows(0..=5, false)?;
+    sh.group_rows(7..=9, true)?;
+    assert!(sh.group_rows(4..=8, false).is_err());
+    assert!(sh.group_rows(1..=2, true).is_err());
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 3..=1;
+    assert!(sh.group_cols(reversed, false).is_err());
+    sh.group_cols(1..=3, false)?;
+
+    assert_eq!(sh.row_outline_level(1), 2);
+    assert_eq!(sh.row_outline_level(6), 0);
+    assert_eq!(sh.row_visible(8), Visibility::Collapsed);
+    assert_eq!(
+        sh.row_groups()
+            .map(|(l, g)| (l, g.from(), g.to()))
+            .collect::<Vec<_>>(),
+        vec![(1, 0, 5), (2, 1, 2), (1, 7, 9)]
+    );
+
+    let grp = sh.ungroup_rows(7..=9).expect("group");
+    assert!(!grp.display());
+    assert_eq!(sh.row_visible(8), Visibility::Visible);
+    assert!(sh.ungroup_cols(2..=3).is_none());
+
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.row_group_count(), 2);
+    assert_eq!(sh.col_group_count(), 1);
+
+    let mut sh = Sheet::new("Sheet1");
+    sh.group_rows(2..=3, true)?;
+    sh.group_rows(0..=5, true)?;
+    sh.ungroup_rows(2..=3).expect("group");
+    assert_eq!(sh.row_visible(2), Visibility::Collapsed);
+    sh.ungroup_rows(0..=5).expect("group");
+    assert_eq!(sh.row_visible(2), Visibility::Visible);
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("Sheet1");
+    sh.add_row_group(1, 2);
+    sh.add_row_group(1, 2);
+    wb.push_sheet(sh);
+    assert!(write_ods_buf(&mut wb, Vec::new()).is_err());
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("Sheet1");
+    sh.group_cols(1..=3, false)?;
+    sh.group_cols(5..=6, false)?;
+    sh.col_group_mut(1).expect("group").set_from(2);
+    wb.push_sheet(sh);
+    assert!(write_ods_buf(&mut wb, Vec::new()).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use chrono::NaiveDate;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::format::{create_scientific_format, ValueFormatNumber};
+use spreadsheet_ods::html::HtmlOptions;
+use spreadsheet_ods::sheet::MergedCells;
+use spreadsheet_ods::style::units::{Border, TextAlign};
+use spreadsheet_ods::{pt, CellRange, CellStyle, Length, Sheet, WorkBook};
+
+#[test]
+fn test_html() {
+    let mut book = WorkBook::new_empty();
+
+    let mut f = ValueFormatNumber::new_named("num");
+    f.part_number()
+        .fixed_decimal_places(2)
+        .grouping()
+        .min_integer_digits(1)
+        .build();
+    let f = book.add_number_format(f);
+
+    let mut st = CellStyle::new("bold", &f);
+    st.set_font_bold();
+    st.set_color(Rgb::new(255, 0, 0));
+    st.set_border(pt!(1), Border::Solid, Rgb::new(0, 0, 0));
+    st.set_text_align(TextAlign::Center);
+    let bold = book.add_cellstyle(st);
+
+    let mut sheet = Sheet::new("report");
+    sheet.set_value(0, 0, "a < b & c");
+    sheet.set_col_span(0, 0, 2);
+    sheet.set_value(0, 1, "covered");
+    sheet.set_styled_value(1, 0, 1234567.5, &bold);
+    sheet.set_value(1, 1, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+    sheet.set_value(2, 0, "one\ntwo");
+    sheet.set_value(2, 1, true);
+    book.push_sheet(sheet);
+
+    let html = book.sheet(0).to_html(
+        &book,
+        &CellRange::local(0, 0, 2, 1),
+        HtmlOptions::default().class("report"),
+    );
+    assert_eq!(
+        html,
+        "<table class=\"report\">\n\
+        <tr><td colspan=\"2\">a &lt; b &amp; c</td></tr>\n\
+        <tr><td style=\"font-weight:bold;color:#ff0000;border:1pt solid #000000;text-align:center\">1,234,567.50</td>\
+        <td style=\"text-align:right\">2024-03-01</td></tr>\n\
+        <tr><td>one<br>two</td><td>TRUE</td></tr>\n\
+        </table>\n"
+    );
+
+    let html = book.sheet(0).to_html(
+        &book,
+        &CellRange::local(1, 0, 1, 0),
+        HtmlOptions::default().styles(false).formats(false),
+    );
+    assert_eq!(html, "<table>\n<tr><td>1234567.5</td></tr>\n</table>\n");
+}
+
+#[test]
+fn test_html_scientific() {
+    let mut book = WorkBook::new_empty();
+
+    let f = book.add_number_format(create_scientific_format("sci", 2));
+    let sci = book.add_cellstyle(CellStyle::new("sci", &f));
+
+    let mut sheet = Sheet::new("sci");
+    sheet.set_styled_value(0, 0, 9.999, &sci);
+    sheet.set_styled_value(0, 1, 0.000123, &sci);
+    book.push_sheet(sheet);
+
+    let html = book.sheet(0).to_html(
+        &book,
+        &CellRange::local(0, 0, 0, 1),
+        HtmlOptions::default().styles(false),
+    );
+    assert_eq!(
+        html,
+        "<table>\n<tr><td>1.00E+01</td><td>1.23E-04</td></tr>\n</table>\n"
+    );
+}
+
+#[test]
+fn test_html_clipped_merge() {
+    let book = WorkBook::new_empty();
+
+    let mut sheet = Sheet::new("merged");
+    sheet.set_value(0, 0, "merged");
+    sheet.set_row_span(0, 0, 3);
+    sheet.set_col_span(0, 0, 2);
+    sheet.set_value(1, 2, "right");
+
+    let html = sheet.to_html(
+        &book,
+        &CellRange::local(1, 1, 2, 2),
+        HtmlOptions::default().styles(false),
+    );
+    assert_eq!(
+        html,
+        "<table>\n<tr><td rowspan=\"2\">merged</td><td>right</td></tr>\n<tr><td></td></tr>\n</table>\n"
+    );
+}
+
+#[test]
+fn test_html_merged_repeat_blank() {
+    let book = WorkBook::new_empty();
+
+    let mut sheet = Sheet::new("merged");
+    sheet.set_value(0, 0, "merged");
+    sheet.set_col_span(0, 0, 2);
+    sheet.set_value(0, 1, "hidden");
+    sheet.set_value(1, 0, "a");
+    sheet.set_value(1, 1, "b");
+
+    let range = CellRange::local(0, 0, 1, 1);
+    let html = sheet.to_html(
+        &book,
+        &range,
+        HtmlOptions::default()
+            .styles(false)
+            .merged(MergedCells::Repeat),
+    );
+    assert_eq!(
+        html,
+        "<table>\n<tr><td>merged</td><td>merged</td></tr>\n<tr><td>a</td><td>b</td></tr>\n</table>\n"
+    );
+
+    let html = sheet.to_html(
+        &book,
+        &range,
+        HtmlOptions::default()
+            .styles(false)
+            .merged(MergedCells::Blank),
+    );
+    assert_eq!(
+        html,
+        "<table>\n<tr><td>merged</td><td></td></tr>\n<tr><td>a</td><td>b</td></tr>\n</table>\n"
+    );
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "json")]
+
+use chrono::{Duration, NaiveDate};
+use spreadsheet_ods::{CellStyleRef, OdsError, Sheet, Value, WorkBook};
+
+#[test]
+fn test_json() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+    let mut sheet = Sheet::new("report");
+    sheet.set_value(0, 0, "total");
+    sheet.set_value(0, 1, 1.5);
+    sheet.set_formula(0, 1, "of:=SUM([.A2:.A3])");
+    sheet.set_cellstyle(0, 1, &CellStyleRef::from("bold"));
+    sheet.set_value(1, 0, true);
+    sheet.set_value(1, 1, Value::Currency(10.0, "EUR".into()));
+    sheet.set_value(
+        2,
+        0,
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_milli_opt(12, 30, 0, 500)
+            .unwrap(),
+    );
+    sheet.set_value(2, 1, Duration::milliseconds(90_500));
+    sheet.set_value(3, 0, Value::Percentage(0.25));
+    book.push_sheet(sheet);
+    book.push_sheet(Sheet::new("empty"));
+
+    let json = book.to_json()?;
+    assert!(json.contains("\"type\": \"currency\""));
+    assert!(json.contains("\"2024-03-01T12:30:00.500\""));
+
+    let book2 = WorkBook::from_json(&json)?;
+    assert_eq!(book2.num_sheets(), 2);
+    assert_eq!(book2.sheet(1).name(), "empty");
+    let sheet = book.sheet(0);
+    let sheet2 = book2.sheet(0);
+    for row in 0..4 {
+        for col in 0..2 {
+            assert_eq!(sheet.value(row, col), sheet2.value(row, col));
+            assert_eq!(sheet.formula(row, col), sheet2.formula(row, col));
+            assert_eq!(sheet.cellstyle(row, col), sheet2.cellstyle(row, col));
+        }
+    }
+
+    assert!(WorkBook::from_json("{ \"sheets\": 1 }").is_err());
+
+    Ok(())
+}
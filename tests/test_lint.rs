@@ -0,0 +1,88 @@
+use chrono::NaiveDate;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::lint::{LintOptions, LintRule, Severity};
+use spreadsheet_ods::style::units::{Border, LengthUnit};
+use spreadsheet_ods::{
+    inch, pt, read_ods_buf, CellStyle, Length, OdsResult, OdsWriteOptions, Sheet, WorkBook,
+};
+use std::io::Cursor;
+
+#[test]
+fn test_lint() {
+    let mut wb = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_name("unused");
+    wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_value(
+        0,
+        0,
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .expect("date")
+            .and_hms_opt(0, 0, 0)
+            .expect("time"),
+    );
+    sh.set_formula(0, 1, "of:=[.A1]+[.C5]");
+    sh.set_value(1, 0, "1.5");
+    sh.set_value(2, 0, "2.5");
+    sh.set_value(3, 0, "3,5");
+    sh.set_value(4, 0, 1);
+    sh.set_cell_repeat(4, 0, 20000);
+    wb.push_sheet(sh);
+
+    let report = LintOptions::default().lint(&wb);
+    let text = report.to_string();
+    assert_eq!(text.lines().count(), 5);
+    assert!(text.contains("error [date-without-format] one.A1: date without a cell style\n"));
+    assert!(text.contains("warning [huge-repeat] one.A5: cell repeated 20000 times\n"));
+
+    assert!(report.has_errors());
+    assert_eq!(report.rule(LintRule::UnusedStyles).count(), 1);
+    assert_eq!(report.rule(LintRule::DateWithoutFormat).count(), 1);
+    assert_eq!(report.rule(LintRule::FormulaRefEmpty).count(), 1);
+    assert_eq!(report.rule(LintRule::MixedDecimalSeparator).count(), 1);
+    assert_eq!(report.rule(LintRule::HugeRepeat).count(), 1);
+
+    let report = LintOptions::default()
+        .severity(LintRule::DateWithoutFormat, Severity::Allow)
+        .max_repeat(100000)
+        .lint(&wb);
+    assert!(!report.has_errors());
+    assert_eq!(report.rule(LintRule::DateWithoutFormat).count(), 0);
+    assert_eq!(report.rule(LintRule::HugeRepeat).count(), 0);
+}
+
+#[test]
+fn test_mixed_units() -> OdsResult<()> {
+    let mut wb = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_name("mixed");
+    st.set_padding(inch!(0.5));
+    st.set_border(pt!(1.5), Border::Solid, Rgb::new(0, 0, 0));
+    st.set_font_size(pt!(10));
+    wb.add_cellstyle(st);
+    wb.push_sheet(Sheet::new("one"));
+
+    let report = LintOptions::default().lint(&wb);
+    assert_eq!(report.rule(LintRule::MixedUnits).count(), 1);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .normalize_units(LengthUnit::Cm)
+        .write_ods(&mut wb, &mut buf)?;
+    let wb = read_ods_buf(buf.get_ref())?;
+
+    let st = wb.cellstyle("mixed").expect("style");
+    assert_eq!(st.cellstyle().attr("fo:padding"), Some("1.27cm"));
+    assert_eq!(
+        st.cellstyle().attr("fo:border"),
+        Some("0.0529cm solid #000000")
+    );
+    assert_eq!(st.textstyle().attr("fo:font-size"), Some("10pt"));
+
+    let report = LintOptions::default().lint(&wb);
+    assert_eq!(report.rule(LintRule::MixedUnits).count(), 0);
+
+    Ok(())
+}
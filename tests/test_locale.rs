@@ -3,8 +3,13 @@ mod lib_test;
 use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
 use icu_locid::{locale, Locale};
 use lib_test::*;
-use spreadsheet_ods::defaultstyles::DefaultStyle;
-use spreadsheet_ods::{read_ods, CellStyle, OdsError, Sheet, Value, ValueFormatCurrency, WorkBook};
+use spreadsheet_ods::defaultstyles::{DefaultFormat, DefaultStyle};
+use spreadsheet_ods::locale::{self, LocalizedValueFormat};
+use spreadsheet_ods::{
+    read_ods, CellStyle, OdsError, Sheet, Value, ValueFormatBoolean, ValueFormatCurrency,
+    ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage, ValueFormatTimeDuration,
+    ValueType, WorkBook,
+};
 
 #[test]
 pub fn test_locale1() -> Result<(), OdsError> {
@@ -157,3 +162,128 @@ pub fn test_locale4() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+pub fn test_locale_prefixed() -> Result<(), OdsError> {
+    let wb = WorkBook::new(locale!("en"));
+    assert_eq!(
+        wb.def_style(ValueType::Number),
+        Some(&DefaultStyle::number())
+    );
+    assert_eq!(
+        wb.def_format(ValueType::Number),
+        DefaultFormat::for_value_type(ValueType::Number)
+            .as_ref()
+            .map(|v| v.as_str())
+    );
+    assert_eq!(wb.def_format(ValueType::Text), None);
+
+    let mut wb = WorkBook::new_prefixed(locale!("en"), "x-");
+    assert_eq!(
+        wb.def_style(ValueType::Number).map(|v| v.as_str()),
+        Some("x-default-num")
+    );
+    assert_eq!(wb.def_format(ValueType::Number), Some("x-num1"));
+    assert!(wb.number_format("x-num1").is_some());
+    assert!(wb.number_format("num1").is_none());
+    assert!(wb.cellstyle("default-num").is_none());
+    assert_eq!(wb.def_styles().count(), 6);
+
+    let mut sheet = Sheet::new("sheet1");
+    sheet.set_value(0, 0, 1234.5);
+    wb.push_sheet(sheet);
+    test_write_ods(&mut wb, "test_out/test_locale_prefixed.ods")?;
+
+    Ok(())
+}
+
+struct LocaleTest;
+
+impl LocalizedValueFormat for LocaleTest {
+    fn locale(&self) -> Locale {
+        locale!("de_CH")
+    }
+
+    fn boolean_format(&self) -> ValueFormatBoolean {
+        let mut v = ValueFormatBoolean::new_localized(DefaultFormat::bool(), self.locale());
+        v.part_boolean().build();
+        v
+    }
+
+    fn number_format(&self) -> ValueFormatNumber {
+        let mut v = ValueFormatNumber::new_localized(DefaultFormat::number(), self.locale());
+        v.part_number().decimal_places(3).build();
+        v
+    }
+
+    fn percentage_format(&self) -> ValueFormatPercentage {
+        ValueFormatPercentage::new_localized(DefaultFormat::percent(), self.locale())
+    }
+
+    fn currency_format(&self) -> ValueFormatCurrency {
+        let mut v = ValueFormatCurrency::new_localized(DefaultFormat::currency(), self.locale());
+        v.part_currency().symbol("CHF").build();
+        v.part_text(" ").build();
+        v.part_number().fixed_decimal_places(2).build();
+        v
+    }
+
+    fn date_format(&self) -> ValueFormatDateTime {
+        ValueFormatDateTime::new_localized(DefaultFormat::date(), self.locale())
+    }
+
+    fn datetime_format(&self) -> ValueFormatDateTime {
+        ValueFormatDateTime::new_localized(DefaultFormat::datetime(), self.locale())
+    }
+
+    fn time_of_day_format(&self) -> ValueFormatDateTime {
+        ValueFormatDateTime::new_localized(DefaultFormat::time_of_day(), self.locale())
+    }
+
+    fn time_interval_format(&self) -> ValueFormatTimeDuration {
+        ValueFormatTimeDuration::new_localized(DefaultFormat::time_interval(), self.locale())
+    }
+}
+
+#[test]
+pub fn test_locale_register() -> Result<(), OdsError> {
+    assert!(!locale::locales().contains(&locale!("de_CH")));
+    locale::register(LocaleTest);
+    assert!(locale::locales().contains(&locale!("de_CH")));
+
+    let mut wb = WorkBook::new(locale!("de_CH"));
+    let cf = wb.currency_format("currency1").expect("format");
+    assert_eq!(cf.currency_symbol(), Some("CHF"));
+
+    let mut sheet = Sheet::new("sheet1");
+    sheet.set_styled_value(0, 0, 1234, &DefaultStyle::currency());
+    wb.push_sheet(sheet);
+    test_write_ods(&mut wb, "test_out/test_locale_register.ods")?;
+
+    Ok(())
+}
+
+#[cfg(all(
+    feature = "locale_de_DE",
+    feature = "locale_en_GB",
+    feature = "locale_es_ES",
+    feature = "locale_fr_FR",
+    feature = "locale_ja_JP"
+))]
+#[test]
+pub fn test_locale_builtin() -> Result<(), OdsError> {
+    for (l, symbol) in [
+        (locale!("de_DE"), "€"),
+        (locale!("en_GB"), "£"),
+        (locale!("es_ES"), "€"),
+        (locale!("fr_FR"), "€"),
+        (locale!("ja_JP"), "¥"),
+    ] {
+        let wb = WorkBook::new(l);
+        let cf = wb.currency_format("currency1").expect("format");
+        assert_eq!(cf.currency_symbol(), Some(symbol));
+        assert!(wb.datetime_format("time1").is_some());
+    }
+
+    Ok(())
+}
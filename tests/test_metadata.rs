@@ -3,7 +3,7 @@ mod lib_test;
 use chrono::{Duration, NaiveDateTime};
 use lib_test::*;
 use spreadsheet_ods::metadata::{MetaUserDefined, MetaValue};
-use spreadsheet_ods::{read_ods, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::{read_ods, read_ods_buf, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_write_read() -> Result<(), OdsError> {
@@ -42,3 +42,85 @@ fn test_write_read() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_user_defined() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+
+    let md = wb.metadata_mut();
+    md.set_user_defined("project", "alpha");
+    md.set_user_defined("budget", 1000.0);
+    md.set_user_defined("approved", false);
+    md.set_user_defined("project", "beta");
+    md.user_defined.push(MetaUserDefined {
+        name: "budget".to_string(),
+        value: MetaValue::Float(1.0),
+    });
+    md.set_user_defined("budget", 2000.0);
+    md.set_user_defined("spent", Duration::try_hours(3).expect("hours"));
+    assert_eq!(md.user_defined.len(), 4);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let md = wb.metadata_mut();
+    assert_eq!(md.user_defined_str("project"), Some("beta"));
+    assert_eq!(md.user_defined_f64("budget"), Some(2000.0));
+    assert_eq!(md.user_defined_bool("approved"), Some(false));
+    assert_eq!(
+        md.user_defined_duration("spent"),
+        Some(Duration::try_hours(3).expect("hours"))
+    );
+    assert_eq!(md.user_defined_f64("project"), None);
+
+    assert_eq!(
+        md.remove_user_defined("approved"),
+        Some(MetaValue::Boolean(false))
+    );
+    assert_eq!(md.user_defined_value("approved"), None);
+    assert_eq!(md.remove_user_defined("approved"), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_user_defined_duplicates() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+
+    let md = wb.metadata_mut();
+    for (name, value) in [("a", 1.0), ("b", 2.0), ("a", 3.0)] {
+        md.user_defined.push(MetaUserDefined {
+            name: name.to_string(),
+            value: MetaValue::Float(value),
+        });
+    }
+    assert_eq!(md.user_defined_f64("a"), Some(1.0));
+
+    // duplicates in the file are kept.
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+    let md = wb.metadata_mut();
+    assert_eq!(md.user_defined.len(), 3);
+    assert_eq!(md.user_defined_f64("a"), Some(1.0));
+    assert_eq!(md.user_defined_f64("b"), Some(2.0));
+    assert_eq!(md.user_defined_value("c"), None);
+
+    // direct changes are found too.
+    md.user_defined.push(MetaUserDefined {
+        name: "c".to_string(),
+        value: MetaValue::Float(4.0),
+    });
+    assert_eq!(md.user_defined_f64("c"), Some(4.0));
+    md.set_user_defined("a", 5.0);
+    assert_eq!(md.user_defined.len(), 3);
+    assert_eq!(md.user_defined_f64("a"), Some(5.0));
+    md.set_user_defined("d", 6.0);
+    assert_eq!(md.user_defined_f64("d"), Some(6.0));
+    assert_eq!(md.remove_user_defined("b"), Some(MetaValue::Float(2.0)));
+    assert_eq!(md.user_defined_f64("c"), Some(4.0));
+    assert_eq!(md.user_defined_f64("d"), Some(6.0));
+
+    Ok(())
+}
@@ -50,3 +50,45 @@ fn test_crpagelayout() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_set_master_page() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut mp = MasterPage::new("mp1");
+    mp.header_mut().center_mut().add_text("header");
+    let mp = wb.add_masterpage(mp);
+
+    let ts = wb.add_tablestyle(TableStyle::new("ts1"));
+    let mut sh0 = Sheet::new("0");
+    sh0.set_style(&ts);
+    let mut sh1 = Sheet::new("1");
+    sh1.set_style(&ts);
+
+    sh0.set_master_page(&mut wb, &mp);
+    let n_tablestyles = wb.iter_table_styles().count();
+    sh0.set_master_page(&mut wb, &mp);
+    assert_eq!(wb.iter_table_styles().count(), n_tablestyles);
+
+    assert_ne!(sh0.style(), Some(&ts));
+    assert_eq!(sh1.style(), Some(&ts));
+    let ts0 = wb
+        .tablestyle(sh0.style().expect("style"))
+        .expect("tablestyle");
+    assert_eq!(
+        ts0.attrmap().attr("style:master-page-name"),
+        Some(mp.as_str())
+    );
+    let ps = wb
+        .masterpage(&mp)
+        .expect("masterpage")
+        .pagestyle()
+        .expect("pagestyle");
+    assert!(wb.pagestyle(ps).is_some());
+
+    wb.push_sheet(sh0);
+    wb.push_sheet(sh1);
+    test_write_ods(&mut wb, "test_out/test_pagelayout_3.ods")?;
+
+    Ok(())
+}
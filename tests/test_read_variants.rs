@@ -1,4 +1,10 @@
-use spreadsheet_ods::{read_ods, OdsError};
+use chrono::{Duration, NaiveDate};
+use spreadsheet_ods::draw::Annotation;
+use spreadsheet_ods::format::ValueFormatRef;
+use spreadsheet_ods::{
+    read_ods, read_ods_buf, write_ods_buf, CellStyle, OdsError, OdsOptions, Sheet, Value, WorkBook,
+};
+use std::io::Cursor;
 
 #[test]
 fn read_google() -> Result<(), OdsError> {
@@ -20,3 +26,94 @@ fn read_office365() -> Result<(), OdsError> {
     // dbg!(wb.sheet(0).cell_ref(1, 1));
     Ok(())
 }
+
+#[test]
+fn read_many_sheets() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let st = wb.add_cellstyle(CellStyle::new("bold", &ValueFormatRef::from("")));
+    for i in 0..5 {
+        let mut sh = Sheet::new(format!("s>'{}'", i));
+        sh.set_styled_value(0, 0, i, &st);
+        sh.set_value(1, 0, "</table:table> <!-- text -->");
+        wb.push_sheet(sh);
+    }
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    assert_eq!(wb.num_sheets(), 5);
+    for i in 0..5 {
+        let sh = wb.sheet(i);
+        assert_eq!(sh.name(), &format!("s>'{}'", i));
+        assert_eq!(sh.value(0, 0).as_i32_or(-1), i as i32);
+        assert_eq!(sh.cellstyle(0, 0), Some(&st));
+        assert_eq!(sh.value(1, 0).as_str_or(""), "</table:table> <!-- text -->");
+    }
+    assert!(wb.cellstyle(&st).is_some());
+    Ok(())
+}
+
+#[test]
+fn read_spill_cells() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let st = wb.add_cellstyle(CellStyle::new("bold", &ValueFormatRef::from("")));
+    let mut sh = Sheet::new("one");
+    for row in (0..2000).filter(|v| !(501..503).contains(v)) {
+        sh.set_value(row, 0, row);
+        sh.set_styled_value(row, 1, format!("text {}", row), &st);
+        sh.set_value(row, 2, row % 2 == 0);
+        sh.set_value(row, 3, Value::Percentage(0.5));
+        sh.set_value(row, 4, Value::new_currency("EUR", row as f64));
+        sh.set_value(
+            row,
+            5,
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap(),
+        );
+        sh.set_value(row, 6, Duration::milliseconds(1500 * row as i64));
+        sh.set_formula(row, 7, "of:=[.A1]");
+    }
+    sh.set_row_repeat(500, 3);
+    sh.set_cell_repeat(600, 8, 4);
+    sh.set_value(600, 8, 1);
+    sh.set_annotation(700, 0, Annotation::new("note"));
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let dump = |sh: &Sheet| {
+        sh.iter()
+            .map(|(pos, cell)| format!("{:?} {:?}", pos, cell))
+            .collect::<Vec<_>>()
+    };
+
+    let wb = read_ods_buf(&buf)?;
+    let mut wb_spill = OdsOptions::default()
+        .spill_cells(1000)
+        .read_ods(Cursor::new(&buf))?;
+    assert!(wb_spill.sheet(0).memory_usage() < wb.sheet(0).memory_usage() / 4);
+    assert_eq!(dump(wb_spill.sheet(0)), dump(wb.sheet(0)));
+
+    let wb_repeat = OdsOptions::default()
+        .use_repeat_for_cells()
+        .read_ods(Cursor::new(&buf))?;
+    let wb_repeat_spill = OdsOptions::default()
+        .use_repeat_for_cells()
+        .spill_cells(1000)
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(dump(wb_repeat_spill.sheet(0)), dump(wb_repeat.sheet(0)));
+
+    let sh = wb_spill.sheet_mut(0);
+    sh.spill()?;
+    sh.set_value(10, 0, "changed");
+    sh.spill()?;
+    assert_eq!(sh.value(10, 0), &Value::from("changed"));
+    assert_eq!(sh.value(11, 0), &Value::from(11));
+
+    let buf = write_ods_buf(&mut wb_spill, Vec::new())?;
+    let wb_spill = read_ods_buf(&buf)?;
+    assert_eq!(wb_spill.sheet(0).value(10, 0), &Value::from("changed"));
+    assert_eq!(wb_spill.sheet(0).cell_count(), wb.sheet(0).cell_count());
+
+    Ok(())
+}
@@ -1,14 +1,24 @@
 mod lib_test;
 
+use chrono::{Duration, NaiveDate};
 use icu_locid::locale;
 use lib_test::*;
-use spreadsheet_ods::defaultstyles::DefaultFormat;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::defaultstyles::{DefaultFormat, DefaultStyle};
+use spreadsheet_ods::draw::Annotation;
+use spreadsheet_ods::rules::{ColumnMatch, SheetRules};
+use spreadsheet_ods::sheet::{
+    ColumnSpec, PrintSetup, Scenario, TableSource, TableSourceMode, TableTemplate,
+};
+use spreadsheet_ods::style::units::{PrintOrder, TextAlign};
+use spreadsheet_ods::style::RowStyle;
 use spreadsheet_ods::{
-    cm, currency, percent, read_ods, CellRange, CellStyle, CellStyleRef, Length, OdsError,
-    OdsOptions, Sheet, Value, ValueType, WorkBook,
+    cm, currency, percent, pt, read_fods_buf, read_ods, read_ods_buf, write_fods_buf,
+    write_ods_buf, CellContent, CellRange, CellStyle, CellStyleRef, ColRange, Length, OdsError,
+    OdsOptions, OdsWarning, RowRange, Sheet, Value, ValueType, WorkBook,
 };
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor};
 
 #[test]
 fn test_colwidth() -> Result<(), OdsError> {
@@ -169,6 +179,58 @@ fn test_print_range() -> Result<(), OdsError> {
     Ok(())
 }
 
+#[test]
+fn test_print_setup() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_print_setup(PrintSetup {
+        range: Some(CellRange::local(0, 0, 9, 4)),
+        fit_width: Some(1),
+        page_order: Some(PrintOrder::Ltr),
+        grid: Some(true),
+        headers: Some(false),
+        ..Default::default()
+    });
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("2"));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    // the print setup styles exist only in the written file.
+    assert_eq!(wb.iter_table_styles().count(), 0);
+    assert!(wb.masterpage("PrintSetup_1").is_none());
+    assert!(wb.pagestyle("PrintSetup_1").is_none());
+    assert!(wb.sheet(0).style().is_none());
+    assert!(wb.sheet(0).print_ranges().is_none());
+
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.print_ranges().unwrap()[0], CellRange::local(0, 0, 9, 4));
+
+    let ts = wb
+        .tablestyle(sh.style().expect("tablestyle"))
+        .expect("tablestyle");
+    let mp = ts
+        .attrmap()
+        .attr("style:master-page-name")
+        .expect("master-page");
+    assert_eq!(mp, "PrintSetup_1");
+    let mp = wb.masterpage(mp).expect("masterpage");
+    let ps = wb
+        .pagestyle(mp.pagestyle().expect("pagestyle"))
+        .expect("pagestyle");
+    assert_eq!(ps.style().attr("style:scale-to-X"), Some("1"));
+    assert_eq!(ps.style().attr("style:scale-to-Y"), Some("0"));
+    assert_eq!(ps.style().attr("style:print-page-order"), Some("ltr"));
+    assert_eq!(
+        ps.style().attr("style:print"),
+        Some("objects charts drawings zero-values grid")
+    );
+
+    Ok(())
+}
+
 #[test]
 fn display_print() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
@@ -233,6 +295,64 @@ fn test_iterator() {
     }
 }
 
+#[test]
+fn test_range() {
+    let mut sh = Sheet::new("1");
+    // inserted out of order.
+    for r in (0..20).rev() {
+        for c in [7, 2, 5, 0, 9] {
+            if (r + c) % 3 != 0 {
+                sh.set_value(r, c, r * 100 + c);
+            }
+        }
+    }
+    let all: Vec<(u32, u32)> = sh.iter().map(|(k, _)| k).collect();
+    let mut sorted = all.clone();
+    sorted.sort();
+    assert_eq!(all, sorted);
+    assert_eq!(sh.cell_count(), all.len());
+
+    let check = |from: (u32, u32), to: (u32, u32)| {
+        let expect: Vec<_> = all
+            .iter()
+            .copied()
+            .filter(|k| *k >= from && *k <= to)
+            .collect();
+        let fwd: Vec<_> = sh.range(from..=to).map(|(k, _)| k).collect();
+        assert_eq!(fwd, expect);
+        let excl: Vec<_> = sh.range(from..to).map(|(k, _)| k).collect();
+        assert_eq!(
+            &excl[..],
+            &expect[..expect.len() - usize::from(all.contains(&to))]
+        );
+    };
+    check((0, 0), (19, 9));
+    check((3, 5), (3, 7));
+    check((3, 6), (11, 1));
+    check((4, 0), (4, 0));
+    check((7, 8), (8, 3));
+
+    let cells: Vec<_> = sh
+        .cells_in(&CellRange::local(2, 2, 5, 7))
+        .map(|(k, _)| k)
+        .collect();
+    let expect: Vec<_> = all
+        .iter()
+        .copied()
+        .filter(|(r, c)| (2..=5).contains(r) && (2..=7).contains(c))
+        .collect();
+    assert_eq!(cells, expect);
+
+    sh.retain(|r, _, _| r % 2 == 0);
+    assert!(sh.iter().all(|((r, _), _)| r % 2 == 0));
+    for c in [0, 2, 5, 7, 9] {
+        sh.remove_cell(4, c);
+    }
+    assert!(sh.range((4, 0)..=(4, u32::MAX)).next().is_none());
+    assert_eq!(sh.cell_count(), sh.iter().count());
+    assert_eq!(sh.value(6, 2).as_u32_or(0), 602);
+}
+
 #[test]
 fn test_cell_style() {
     let mut wb = WorkBook::new(locale!("de_AT"));
@@ -243,3 +363,1044 @@ fn test_cell_style() {
     let ss0 = wb.cellstyle(&s0).expect("style");
     assert_eq!(ss0.name(), "a21");
 }
+
+#[test]
+fn test_strip_formulas() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_value(0, 1, 2);
+    sh.set_value(0, 2, 3);
+    sh.set_formula(0, 2, "of:=[.A1]+[.B1]");
+    wb.push_sheet(sh);
+
+    let mut wb2 = wb.clone();
+
+    wb.strip_formulas();
+    assert_eq!(wb.sheet(0).formula(0, 2), None);
+    assert_eq!(wb.sheet(0).value(0, 2), &Value::Number(3f64));
+
+    wb2.strip_cached_values();
+    assert!(wb2.sheet(0).formula(0, 2).is_some());
+    assert_eq!(wb2.sheet(0).value(0, 2), &Value::Empty);
+    assert_eq!(wb2.sheet(0).value(0, 0), &Value::Number(1f64));
+}
+
+#[test]
+fn test_cached_values() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_value(0, 1, 2);
+    sh.set_formula(0, 1, "of:=[.A1]*2");
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).value(0, 1), &Value::Number(2f64));
+    assert_eq!(wb.sheet(0).cached_value(0, 1), Some(&Value::Number(2f64)));
+    assert_eq!(wb.sheet(0).cached_value(0, 0), None);
+    let cell = wb.sheet(0).cell_ref(0, 1).expect("cell");
+    assert_eq!(cell.cached_value(), Some(&Value::Number(2f64)));
+
+    let wb = OdsOptions::default()
+        .ignore_cached_values()
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.sheet(0).value(0, 1), &Value::Empty);
+    assert_eq!(wb.sheet(0).formula(0, 1), Some(&"of:=[.A1]*2".to_string()));
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::Number(1f64));
+
+    Ok(())
+}
+
+#[test]
+fn test_color_scale() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for r in 0..=10 {
+        sh.set_value(r, 0, r);
+        sh.set_value(r, 1, r);
+    }
+    sh.set_value(11, 0, "text");
+
+    let styles = sh.color_scale(
+        CellRange::local(0, 0, 11, 0),
+        Rgb::new(255, 0, 0),
+        Some(Rgb::new(255, 255, 0)),
+        Rgb::new(0, 255, 0),
+    );
+    assert_eq!(styles.len(), 11);
+    assert_eq!(
+        sh.cellstyle(0, 0).map(|v| v.as_str()),
+        Some("colorscale_ff0000")
+    );
+    assert_eq!(
+        sh.cellstyle(5, 0).map(|v| v.as_str()),
+        Some("colorscale_ffff00")
+    );
+    assert_eq!(
+        sh.cellstyle(10, 0).map(|v| v.as_str()),
+        Some("colorscale_00ff00")
+    );
+    assert_eq!(sh.cellstyle(11, 0), None);
+    assert_eq!(sh.cellstyle(0, 1), None);
+
+    for style in styles {
+        wb.add_cellstyle(style);
+    }
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_sheet_color_scale.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_apply_rules() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut date_style = CellStyle::new_empty();
+    date_style.set_name("date");
+    let date_style = wb.add_cellstyle(date_style);
+    let mut num_style = CellStyle::new_empty();
+    num_style.set_name("num");
+    num_style.set_text_align(TextAlign::End);
+    let num_style = wb.add_cellstyle(num_style);
+    let text_style = CellStyleRef::from("text");
+
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "Due Date");
+    sh.set_value(0, 1, "Amount");
+    sh.set_value(0, 2, "Date count");
+    sh.set_value(0, 3, "Name");
+    for row in 1..4 {
+        sh.set_value(row, 0, NaiveDate::from_ymd_opt(2024, 1, row).expect("date"));
+        sh.set_value(row, 1, row as f64 * 1.5);
+        sh.set_value(row, 2, row);
+        sh.set_value(row, 3, "x");
+    }
+    sh.set_value(4, 1, percent!(0.5));
+    sh.set_cellstyle(5, 1, &text_style);
+
+    let rules = SheetRules::new()
+        .header_contains("DATE", &date_style)
+        .numeric(&num_style)
+        .rule(ColumnMatch::HeaderEquals("Name".into()), &text_style);
+    sh.apply_rules(&rules);
+
+    assert_eq!(sh.cellstyle(0, 0), None);
+    assert_eq!(sh.cellstyle(1, 0), Some(&date_style));
+    assert_eq!(sh.cellstyle(4, 1), Some(&num_style));
+    // first rule wins.
+    assert_eq!(sh.cellstyle(1, 2), Some(&date_style));
+    assert_eq!(sh.cellstyle(3, 3), Some(&text_style));
+    // empty cells are not changed.
+    assert_eq!(sh.cellstyle(5, 1), Some(&text_style));
+
+    let mut sh2 = Sheet::new("two");
+    sh2.set_value(0, 0, 1);
+    sh2.set_value(1, 0, "a");
+    sh2.apply_rules(&SheetRules::new().no_header().numeric(&num_style));
+    assert_eq!(sh2.cellstyle(0, 0), None);
+    sh2.apply_rules(
+        &SheetRules::new()
+            .no_header()
+            .rule(ColumnMatch::Col(0), &num_style),
+    );
+    assert_eq!(sh2.cellstyle(0, 0), Some(&num_style));
+    assert_eq!(sh2.cellstyle(1, 0), Some(&num_style));
+
+    wb.push_sheet(sh);
+    test_write_ods(&mut wb, "test_out/test_sheet_rules.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_merged_ranges() {
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "a");
+    sh.set_row_span(0, 0, 2);
+    sh.set_col_span(0, 0, 3);
+    sh.set_value(0, 3, "b");
+    sh.set_value(1, 1, "hidden");
+    sh.set_value(3, 1, "c");
+    sh.set_col_span(3, 1, 2);
+
+    assert_eq!(
+        sh.merged_ranges(),
+        vec![CellRange::local(0, 0, 1, 2), CellRange::local(3, 1, 3, 2)]
+    );
+
+    assert_eq!(sh.merged_origin(0, 0), Some((0, 0)));
+    assert_eq!(sh.merged_origin(1, 2), Some((0, 0)));
+    assert_eq!(sh.merged_origin(1, 1), Some((0, 0)));
+    assert_eq!(sh.merged_origin(0, 3), None);
+    assert_eq!(sh.merged_origin(2, 0), None);
+    assert_eq!(sh.merged_origin(3, 2), Some((3, 1)));
+    assert_eq!(sh.merged_origin(3, 3), None);
+}
+
+#[test]
+fn test_add_cell() {
+    let style = CellStyleRef::from("bold");
+    let cell = CellContent::builder()
+        .value(42)
+        .formula("of:=6*7")
+        .style(&style)
+        .span(2, 3)
+        .annotation(Annotation::new("note"))
+        .build();
+
+    let mut sh = Sheet::new("one");
+    sh.add_cell(1, 1, cell);
+    assert_eq!(sh.value(1, 1), &Value::Number(42.0));
+    assert_eq!(sh.formula(1, 1), Some(&"of:=6*7".to_string()));
+    assert_eq!(sh.cellstyle(1, 1), Some(&style));
+    assert_eq!(sh.row_span(1, 1), 2);
+    assert_eq!(sh.col_span(1, 1), 3);
+    assert!(sh.annotation(1, 1).is_some());
+    assert_eq!(sh.cell_repeat(1, 1), 1);
+
+    let mut sh2 = Sheet::new("two");
+    sh2.add_cell(0, 0, sh.cell(1, 1).expect("cell"));
+    assert_eq!(sh2.value(0, 0), &Value::Number(42.0));
+    assert_eq!(sh2.col_span(0, 0), 3);
+}
+
+#[test]
+fn test_table_source() -> Result<(), OdsError> {
+    let mut linked = WorkBook::new_empty();
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, 10);
+    sh.set_value(0, 1, 20);
+    sh.set_formula(0, 1, "of:=[.A1]*2");
+    linked.push_sheet(sh);
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("linked");
+    sh.set_value(5, 5, "stale");
+    let mut table_source = TableSource::new("file:///tmp/linked.ods");
+    table_source.table_name = Some("data".to_string());
+    table_source.filter_name = Some("calc8".to_string());
+    table_source.mode = TableSourceMode::CopyResultsOnly;
+    table_source.refresh_delay = Some(Duration::try_minutes(5).expect("duration"));
+    sh.set_table_source(table_source);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+    let table_source = wb.sheet(0).table_source().expect("table_source");
+    assert_eq!(table_source.href, "file:///tmp/linked.ods");
+    assert_eq!(table_source.table_name.as_deref(), Some("data"));
+    assert_eq!(table_source.filter_name.as_deref(), Some("calc8"));
+    assert_eq!(table_source.mode, TableSourceMode::CopyResultsOnly);
+    assert_eq!(
+        table_source.refresh_delay,
+        Some(Duration::try_minutes(5).expect("duration"))
+    );
+
+    assert_eq!(
+        wb.refresh_table_sources("file:///tmp/linked.ods", &linked)?,
+        1
+    );
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(5, 5), &Value::Empty);
+    assert_eq!(sh.value(0, 1), &Value::Number(20f64));
+    assert_eq!(sh.formula(0, 1), None);
+
+    assert!(Sheet::new("x").refresh_table_source(&linked).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_scenario() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new("best case");
+    sh.set_value(0, 0, 2);
+    let mut scenario = Scenario::new(vec![CellRange::remote("data", 0, 0, 2, 2)]);
+    scenario.is_active = true;
+    scenario.border_color = Some(Rgb::new(0xc0, 0xc0, 0xc0));
+    scenario.copy_back = false;
+    scenario.comment = Some("Best <case>".to_string());
+    sh.set_scenario(scenario);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    assert!(wb.sheet(0).scenario().is_none());
+    let scenario = wb.sheet(1).scenario().expect("scenario");
+    assert_eq!(scenario.ranges, vec![CellRange::remote("data", 0, 0, 2, 2)]);
+    assert!(scenario.is_active);
+    assert!(scenario.display_border);
+    assert_eq!(scenario.border_color, Some(Rgb::new(0xc0, 0xc0, 0xc0)));
+    assert!(!scenario.copy_back);
+    assert!(scenario.copy_styles);
+    assert!(scenario.copy_formulas);
+    assert_eq!(scenario.comment.as_deref(), Some("Best <case>"));
+    assert!(!scenario.protected);
+
+    assert!(wb.sheet_mut(1).clear_scenario().is_some());
+    assert!(wb.sheet(1).scenario().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_fill_row_range() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(3, 0, "overwritten");
+    sh.set_value(8, 0, "after");
+    sh.fill_row_range(
+        2..=5,
+        [
+            (0, CellContent::builder().value("fill").build()),
+            (1, CellContent::builder().value(1).build()),
+        ],
+    )?;
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 5..=2;
+    assert!(sh.fill_row_range(reversed, []).is_err());
+
+    assert_eq!(sh.row_repeat(2), 4);
+    assert_eq!(sh.row_repeat_origin(2), None);
+    assert_eq!(sh.row_repeat_origin(3), Some(2));
+    assert_eq!(sh.row_repeat_origin(5), Some(2));
+    assert_eq!(sh.row_repeat_origin(6), None);
+    assert_eq!(sh.value(3, 0), &Value::Empty);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    for r in 2..=5 {
+        assert_eq!(sh.value(r, 0), &Value::Text("fill".into()));
+        assert_eq!(sh.value(r, 1), &Value::Number(1f64));
+    }
+    assert_eq!(sh.value(6, 0), &Value::Empty);
+    assert_eq!(sh.value(8, 0), &Value::Text("after".into()));
+
+    Ok(())
+}
+
+#[test]
+fn test_cells_in() {
+    let mut sh = Sheet::new("1");
+    for r in 0..10 {
+        for c in 0..10 {
+            if (r + c) % 3 == 0 {
+                sh.set_value(r, c, r * 10 + c);
+            }
+        }
+    }
+    sh.set_value(20, 0, "outside");
+
+    let range = CellRange::local(2, 2, 5, 4);
+    let cells: Vec<_> = sh.cells_in(&range).map(|(pos, _)| pos).collect();
+    assert_eq!(cells, vec![(2, 4), (3, 3), (4, 2), (5, 4)]);
+
+    for ((r, c), cell) in sh.cells_in(&range) {
+        assert_eq!(cell.value, &Value::from(r * 10 + c));
+    }
+
+    let rows: Vec<_> = sh
+        .rows_in(&range)
+        .map(|(r, cells)| (r, cells.count()))
+        .collect();
+    assert_eq!(rows, vec![(2, 1), (3, 1), (4, 1), (5, 1)]);
+
+    // rows without data in the range are skipped.
+    let range = CellRange::local(0, 1, 9, 1);
+    let rows: Vec<_> = sh.rows_in(&range).map(|(r, _)| r).collect();
+    assert_eq!(rows, vec![2, 5, 8]);
+
+    assert_eq!(sh.cells_in(&CellRange::local(11, 0, 19, 9)).count(), 0);
+}
+
+#[test]
+fn test_retain() {
+    let mut sh = Sheet::new("1");
+    for r in 0..10 {
+        sh.set_value(r, 0, r);
+        sh.set_value(r, 1, "text");
+    }
+    sh.set_col_span(2, 1, 3);
+    sh.set_annotation(2, 1, Annotation::new("note"));
+
+    sh.retain(|row, col, cell| col == 0 && row % 2 == 0 && cell.value != &Value::Empty);
+
+    assert_eq!(sh.cell_count(), 5);
+    assert_eq!(sh.value(4, 0), &Value::from(4u32));
+    assert_eq!(sh.value(3, 0), &Value::Empty);
+    assert_eq!(sh.col_span(2, 1), 1);
+    assert!(sh.annotation(2, 1).is_none());
+}
+
+#[test]
+fn test_autofill() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_value(1, 0, 3);
+    sh.set_value(0, 1, "Monday");
+    sh.set_value(0, 2, "Item 09");
+    sh.set_value(0, 3, NaiveDate::from_ymd_opt(2024, 2, 28).unwrap());
+    sh.set_formula(0, 4, "of:=[.A1]*[.$A$1]");
+    sh.set_styled_value(0, 5, true, &CellStyleRef::from("bool"));
+    sh.set_value(1, 5, false);
+
+    sh.autofill(CellRange::local(0, 0, 1, 5), CellRange::local(2, 0, 4, 5))?;
+
+    assert_eq!(sh.value(2, 0), &Value::Number(5.0));
+    assert_eq!(sh.value(4, 0), &Value::Number(9.0));
+    // copied, because row 1 is empty.
+    assert_eq!(sh.value(2, 1), &Value::Text("Monday".into()));
+    assert_eq!(sh.value(3, 1), &Value::Empty);
+    assert_eq!(sh.formula(4, 4), Some(&"of:=[.A5]*[.$A$1]".to_string()));
+    assert_eq!(sh.value(4, 5), &Value::Boolean(true));
+    assert_eq!(sh.cellstyle(4, 5), Some(&CellStyleRef::from("bool")));
+
+    sh.autofill(CellRange::local(0, 1, 0, 3), CellRange::local(1, 1, 2, 3))?;
+    assert_eq!(sh.value(2, 1), &Value::Text("Wednesday".into()));
+    assert_eq!(sh.value(2, 2), &Value::Text("Item 11".into()));
+    assert_eq!(
+        sh.value(2, 3),
+        &Value::from(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+    );
+
+    sh.set_value(10, 1, "FEB");
+    sh.autofill(
+        CellRange::local(10, 1, 10, 1),
+        CellRange::local(10, 0, 10, 0),
+    )?;
+    assert_eq!(sh.value(10, 0), &Value::Text("JAN".into()));
+
+    // counting down stops at 0.
+    sh.set_value(12, 3, "Item 1");
+    sh.autofill(
+        CellRange::local(12, 3, 12, 3),
+        CellRange::local(12, 0, 12, 2),
+    )?;
+    assert_eq!(sh.value(12, 2), &Value::Text("Item 0".into()));
+    assert_eq!(sh.value(12, 1), &Value::Text("Item 0".into()));
+    assert_eq!(sh.value(12, 0), &Value::Text("Item 0".into()));
+
+    assert!(sh
+        .autofill(CellRange::local(0, 0, 1, 1), CellRange::local(1, 0, 3, 1))
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_remove_cell() {
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "merged");
+    sh.set_row_span(0, 0, 2);
+    sh.set_col_span(0, 0, 3);
+    sh.set_value(1, 2, "covered");
+    sh.set_value(4, 4, 1);
+    sh.set_annotation(4, 4, Annotation::new("note"));
+    sh.set_validation(4, 4, &"v0".into());
+
+    let cell = sh.remove_cell(4, 4).expect("cell");
+    assert_eq!(cell.value, Value::Number(1.0));
+    assert!(cell.annotation.is_some());
+    assert_eq!(cell.validation_name, Some("v0".into()));
+    assert!(sh.annotation(4, 4).is_none());
+    assert!(sh.validation(4, 4).is_none());
+    assert!(sh.remove_cell(4, 4).is_none());
+
+    // the span goes with the anchor.
+    let mut sh2 = sh.clone();
+    let anchor = sh2.remove_cell(0, 0).expect("cell");
+    assert_eq!(anchor.col_span(), 3);
+    assert_eq!(sh2.row_span(0, 0), 1);
+    assert_eq!(sh2.col_span(0, 0), 1);
+
+    // covered cells shrink the span.
+    sh.remove_cell(1, 2).expect("cell");
+    assert_eq!((sh.row_span(0, 0), sh.col_span(0, 0)), (2, 2));
+    assert!(sh.remove_cell(1, 1).is_none());
+    assert_eq!((sh.row_span(0, 0), sh.col_span(0, 0)), (1, 2));
+    assert!(sh.remove_cell(0, 1).is_none());
+    assert_eq!((sh.row_span(0, 0), sh.col_span(0, 0)), (1, 1));
+    assert_eq!(sh.value(0, 0).as_str_or(""), "merged");
+
+    // repeated cells are split.
+    sh.set_value(6, 0, "r");
+    sh.set_cell_repeat(6, 0, 3);
+    let split = sh.remove_cell(6, 1).expect("cell");
+    assert_eq!(split.value, Value::from("r"));
+    assert_eq!(split.repeat, 1);
+    assert_eq!(sh.cell_repeat(6, 0), 1);
+    assert_eq!(sh.value(6, 1), &Value::Empty);
+    assert_eq!(sh.value(6, 2).as_str_or(""), "r");
+    assert!(sh.cell_or_repeated(6, 1).is_none());
+
+    // restore
+    sh.add_cell(4, 4, cell);
+    assert!(sh.annotation(4, 4).is_some());
+    assert_eq!(sh.validation(4, 4), Some(&"v0".into()));
+}
+
+#[test]
+fn test_split_repeat() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(9, 0, "after");
+    sh.fill_row_range(
+        2..=6,
+        [
+            (0, CellContent::builder().value("fill").build()),
+            (1, CellContent::builder().value(1).build()),
+        ],
+    )?;
+    sh.set_annotation(2, 1, Annotation::new("note"));
+
+    sh.set_value(4, 1, 42);
+    assert_eq!(sh.row_repeat(2), 2);
+    assert_eq!(sh.row_repeat(4), 1);
+    assert_eq!(sh.row_repeat(5), 2);
+    assert_eq!(sh.row_repeat_origin(3), Some(2));
+    assert_eq!(sh.row_repeat_origin(4), None);
+    assert_eq!(sh.row_repeat_origin(6), Some(5));
+    assert_eq!(sh.value(4, 0), &Value::Text("fill".into()));
+    assert!(sh.annotation(4, 1).is_some());
+
+    // cell repeat
+    sh.set_value(0, 0, "x");
+    sh.set_cell_repeat(0, 0, 5);
+    sh.set_value(0, 2, "y");
+    assert_eq!(sh.cell_repeat(0, 0), 2);
+    assert_eq!(sh.cell_repeat(0, 2), 1);
+    assert_eq!(sh.cell_repeat(0, 3), 2);
+    assert_eq!(sh.value(0, 3), &Value::Text("x".into()));
+
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    for r in 2..=6 {
+        let v = if r == 4 { 42.0 } else { 1.0 };
+        assert_eq!(sh.value(r, 0), &Value::Text("fill".into()));
+        assert_eq!(sh.value(r, 1), &Value::Number(v));
+        assert!(sh.annotation(r, 1).is_some());
+    }
+    assert_eq!(sh.value(7, 0), &Value::Empty);
+    assert_eq!(sh.value(9, 0), &Value::Text("after".into()));
+    for c in 0..5 {
+        let v = if c == 2 { "y" } else { "x" };
+        assert_eq!(sh.value(0, c), &Value::Text(v.into()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bulk_values() {
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(3, 1, 0, &CellStyleRef::from("bold"));
+
+    sh.set_col_values(1, 1, 1..=5);
+    sh.set_row_values(0, 0, ["a", "b", "c"]);
+    sh.set_row_values(7, 2, vec![Value::Boolean(true), Value::Number(1.5)]);
+
+    assert_eq!(sh.value(0, 0), &Value::Text("a".into()));
+    assert_eq!(sh.value(0, 2), &Value::Text("c".into()));
+    for r in 1..=5 {
+        assert_eq!(sh.value(r, 1), &Value::Number(r as f64));
+    }
+    assert_eq!(sh.cellstyle(3, 1), Some(&CellStyleRef::from("bold")));
+    assert_eq!(sh.value(6, 1), &Value::Empty);
+    assert_eq!(sh.value(7, 2), &Value::Boolean(true));
+    assert_eq!(sh.value(7, 3), &Value::Number(1.5));
+    assert_eq!(sh.used_grid_size(), (8, 4));
+
+    // small batch into a larger sheet, overlapping and new cells.
+    sh.set_col_values(0, 10, 0..100);
+    sh.set_col_values(98, 10, ["x", "y", "z"]);
+    assert_eq!(sh.value(97, 10), &Value::Number(97.0));
+    assert_eq!(sh.value(99, 10), &Value::Text("y".into()));
+    assert_eq!(sh.value(100, 10), &Value::Text("z".into()));
+
+    // values beyond the last row/column are dropped.
+    let mut sh = Sheet::new("1");
+    sh.set_col_values(u32::MAX - 1, 0, [1, 2, 3]);
+    sh.set_row_values(0, u32::MAX, [1, 2]);
+    assert_eq!(sh.cell_count(), 3);
+    assert_eq!(sh.value(u32::MAX, 0), &Value::Number(2.0));
+}
+
+#[test]
+fn test_apply_table_style() {
+    let header = CellStyleRef::from("header");
+    let rows = CellStyleRef::from("rows");
+    let band = CellStyleRef::from("band");
+    let total = CellStyleRef::from("total");
+
+    let mut sh = Sheet::new("1");
+    sh.apply_table_style(
+        &CellRange::local(1, 1, 5, 2),
+        &TableTemplate {
+            header: Some(header.clone()),
+            total: Some(total.clone()),
+            rows: Some(rows.clone()),
+            banded_rows: Some(band.clone()),
+            ..Default::default()
+        },
+    );
+
+    for col in 1..=2 {
+        assert_eq!(sh.cellstyle(1, col), Some(&header));
+        assert_eq!(sh.cellstyle(2, col), Some(&rows));
+        assert_eq!(sh.cellstyle(3, col), Some(&band));
+        assert_eq!(sh.cellstyle(4, col), Some(&rows));
+        assert_eq!(sh.cellstyle(5, col), Some(&total));
+    }
+    assert_eq!(sh.cellstyle(0, 1), None);
+    assert_eq!(sh.cellstyle(2, 0), None);
+    assert_eq!(sh.cellstyle(2, 3), None);
+
+    // existing styles are replaced.
+    sh.set_cellstyle(10, 16383, &header);
+    // limited to 1048576 rows and 16384 columns.
+    sh.apply_table_style(
+        &CellRange::local(1048573, 16382, u32::MAX, u32::MAX),
+        &TableTemplate {
+            rows: Some(rows.clone()),
+            total: Some(total.clone()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(sh.cell_count(), 10 + 1 + 6);
+    assert_eq!(sh.cellstyle(1048574, 16383), Some(&rows));
+    assert_eq!(sh.cellstyle(1048575, 16383), Some(&total));
+    sh.apply_table_style(
+        &CellRange::local(10, 16383, 10, 16383),
+        &TableTemplate {
+            rows: Some(rows.clone()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(sh.cellstyle(10, 16383), Some(&rows));
+}
+
+#[test]
+fn test_hide_rows() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+
+    sh.set_value(0, 0, 1);
+    sh.set_value(10, 0, 2);
+    sh.set_value(12, 0, 3);
+    sh.set_row_repeat(12, 5);
+
+    sh.hide_rows(&RowRange::new(3, 5));
+    sh.hide_rows(&RowRange::new(14, 20));
+    sh.hide_cols(&ColRange::new(2, 3));
+
+    assert!(!sh.is_row_hidden(2));
+    assert!(sh.is_row_hidden(3));
+    assert!(sh.is_row_hidden(5));
+    assert!(!sh.is_row_hidden(6));
+    assert!(!sh.is_row_hidden(13));
+    assert!(sh.is_row_hidden(14));
+    assert!(sh.is_row_hidden(20));
+    assert!(!sh.is_row_hidden(21));
+    assert_eq!(sh.row_repeat(12), 2);
+    assert!(!sh.is_col_hidden(1));
+    assert!(sh.is_col_hidden(3));
+    assert!(!sh.is_col_hidden(4));
+
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    // partial edit
+    let sh = wb.sheet_mut(0);
+    sh.set_value(4, 0, 4);
+    sh.show_rows(&RowRange::new(5, 5));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let sh = wb.sheet(0);
+    assert!(!sh.is_row_hidden(2));
+    assert!(sh.is_row_hidden(3));
+    assert!(sh.is_row_hidden(4));
+    assert!(!sh.is_row_hidden(5));
+    assert!(!sh.is_row_hidden(10));
+    assert!(sh.is_row_hidden(14));
+    assert!(sh.is_row_hidden(20));
+    assert!(!sh.is_row_hidden(21));
+    assert_eq!(sh.value(4, 0).as_i32_or(0), 4);
+    assert!(sh.is_col_hidden(2));
+    assert!(sh.is_col_hidden(3));
+    assert!(!sh.is_col_hidden(4));
+
+    Ok(())
+}
+
+#[test]
+fn test_try_setters() {
+    let mut sh = Sheet::new("1");
+    assert!(sh.try_set_row_repeat(1, 0).is_err());
+    assert!(sh.try_set_row_span(1, 1, 0).is_err());
+    assert!(sh.try_set_col_span(1, 1, 0).is_err());
+
+    assert!(sh.try_set_row_repeat(1, 3).is_ok());
+    assert!(sh.try_set_row_span(1, 1, 2).is_ok());
+    assert!(sh.try_set_col_span(1, 1, 2).is_ok());
+    assert_eq!(sh.row_repeat(1), 3);
+    assert_eq!(sh.row_span(1, 1), 2);
+    assert_eq!(sh.col_span(1, 1), 2);
+
+    let mut cell = CellContent::new();
+    assert!(cell.try_set_repeat(0).is_err());
+    assert!(cell.try_set_row_span(0).is_err());
+    assert!(cell.try_set_col_span(0).is_err());
+    assert!(cell.try_set_col_span(3).is_ok());
+    assert_eq!(cell.col_span(), 3);
+
+    sh.add_row_group(1, 4);
+    assert!(sh.try_add_row_group(2, 5).is_err());
+    assert!(sh.try_add_row_group(2, 3).is_ok());
+    sh.add_col_group(1, 4);
+    assert!(sh.try_add_col_group(3, 7).is_err());
+
+    let mut st = RowStyle::new_empty();
+    assert!(st.try_set_min_row_height(Length::Pt(-1.0)).is_err());
+    assert!(st.try_set_min_row_height(Length::Pt(12.0)).is_ok());
+}
+
+#[test]
+fn test_cell_or_repeated() {
+    let mut sh = Sheet::new("1");
+    sh.set_value(2, 1, 1);
+    sh.set_cell_repeat(2, 1, 3);
+    sh.set_value(2, 5, 2);
+    sh.set_row_repeat(2, 4);
+
+    assert_eq!(sh.cell_or_repeated(2, 1).map(|v| v.0), Some((2, 1)));
+    assert_eq!(sh.cell_or_repeated(2, 3).map(|v| v.0), Some((2, 1)));
+    assert!(sh.cell_or_repeated(2, 4).is_none());
+    assert_eq!(sh.cell_or_repeated(5, 2).map(|v| v.0), Some((2, 1)));
+    assert_eq!(sh.cell_or_repeated(5, 5).map(|v| v.0), Some((2, 5)));
+    assert!(sh.cell_or_repeated(6, 1).is_none());
+    assert!(sh.cell_or_repeated(1, 1).is_none());
+    assert!(sh.cell_ref(5, 5).is_none());
+    assert_eq!(
+        sh.cell_or_repeated(4, 5)
+            .map(|v| v.1.value().as_f64_or(0.0)),
+        Some(2.0)
+    );
+}
+
+#[test]
+fn test_percentage_fraction() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new(locale!("en_US"));
+    let mut sh = Sheet::new("1");
+    sh.set_percentage(0, 0, 0.15);
+    assert!(wb.cellstyle(DefaultStyle::fraction()).is_none());
+    sh.set_fraction(&mut wb, 1, 0, 3, 4)?;
+    sh.set_fraction(&mut wb, 2, 0, 1, 3)?;
+    assert!(sh.set_fraction(&mut wb, 3, 0, 1, 0).is_err());
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Percentage(0.15));
+    assert_eq!(sh.value(1, 0), &Value::Number(0.75));
+    assert_eq!(sh.cellstyle(1, 0), Some(&DefaultStyle::fraction()));
+    assert!(wb
+        .number_format(DefaultFormat::fraction().as_str())
+        .is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_stripe_rows() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("even");
+    st.set_background_color(Rgb::new(255, 255, 255));
+    let even = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("odd");
+    st.set_background_color(Rgb::new(230, 230, 230));
+    let odd = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold");
+    st.set_font_bold();
+    let bold = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_styled_value(1, 0, 2, &bold);
+    sh.set_styled_value(3, 0, 4, &bold);
+    sh.set_col_cellstyle(1, &bold);
+    sh.stripe_rows(&mut wb, &CellRange::local(0, 0, 3, 1), &even, &odd);
+
+    assert_eq!(sh.cellstyle(0, 0), Some(&even));
+    assert_eq!(sh.cellstyle(2, 0), Some(&even));
+    assert_eq!(sh.cellstyle(1, 0).map(|v| v.as_str()), Some("bold_odd"));
+    assert_eq!(sh.cellstyle(3, 0), sh.cellstyle(1, 0));
+    assert_eq!(sh.cellstyle(0, 1).map(|v| v.as_str()), Some("bold_even"));
+    assert_eq!(sh.value(1, 0), &Value::Number(2.0));
+
+    let st = wb.cellstyle("bold_odd").expect("style");
+    assert_eq!(st.textstyle().attr("fo:font-weight"), Some("bold"));
+    assert_eq!(st.cellstyle().attr("fo:background-color"), Some("#e6e6e6"));
+    assert!(wb.cellstyle("bold").is_some());
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(
+        wb.sheet(0).cellstyle(1, 0).map(|v| v.as_str()),
+        Some("bold_odd")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_stripe_rows_whole() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("even");
+    st.set_background_color(Rgb::new(255, 255, 255));
+    let even = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("odd");
+    st.set_background_color(Rgb::new(230, 230, 230));
+    let odd = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold");
+    st.set_font_bold();
+    let bold = wb.add_cellstyle(st);
+    // unrelated style with the name of a combined style.
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold_odd");
+    st.set_font_italic();
+    wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_styled_value(1, 0, 2, &bold);
+    sh.set_col_cellstyle(2, &bold);
+    sh.set_row_cellstyle(3, &bold);
+    sh.set_value(3, 1, 4);
+    sh.stripe_rows(&mut wb, &CellRange::local(0, 0, 3, u32::MAX), &even, &odd);
+
+    // the stripes are the default of the rows.
+    assert_eq!(sh.row_cellstyle(0), Some(&even));
+    assert_eq!(sh.row_cellstyle(1), Some(&odd));
+    assert_eq!(sh.cellstyle(0, 0), None);
+    assert_eq!(sh.cellstyle(1, 0).map(|v| v.as_str()), Some("bold_odd2"));
+    assert_eq!(sh.cellstyle(1, 2).map(|v| v.as_str()), Some("bold_odd2"));
+    assert_eq!(sh.cellstyle(0, 2).map(|v| v.as_str()), Some("bold_even"));
+    assert_eq!(sh.row_cellstyle(3).map(|v| v.as_str()), Some("bold_odd2"));
+    assert_eq!(sh.cellstyle(3, 1), None);
+    assert!(sh.iter().count() < 10);
+
+    let st = wb.cellstyle("bold_odd").expect("style");
+    assert_eq!(st.textstyle().attr("fo:font-weight"), None);
+    let st = wb.cellstyle("bold_odd2").expect("style");
+    assert_eq!(st.textstyle().attr("fo:font-weight"), Some("bold"));
+
+    // later calls reuse the combined style.
+    sh.stripe_rows(&mut wb, &CellRange::local(5, 0, 6, 1), &even, &odd);
+    sh.set_styled_value(6, 0, 7, &bold);
+    sh.stripe_rows(&mut wb, &CellRange::local(5, 0, 6, 1), &even, &odd);
+    assert_eq!(sh.cellstyle(6, 0).map(|v| v.as_str()), Some("bold_odd2"));
+    assert_eq!(sh.cellstyle(6, 1), Some(&odd));
+    assert!(wb.cellstyle("bold_odd3").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_column_schema() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new(locale!("en_US"));
+
+    let mut sh = Sheet::new("one");
+    let styles = sh.apply_column_schema(&[
+        ColumnSpec::new("name", ValueType::Text),
+        ColumnSpec {
+            width: cm!(3),
+            ..ColumnSpec::new("count", ValueType::Number)
+        },
+        ColumnSpec {
+            format: Some(DefaultFormat::currency()),
+            ..ColumnSpec::new("price", ValueType::Currency)
+        },
+    ]);
+    assert_eq!(styles.len(), 1);
+    for st in styles {
+        wb.add_cellstyle(st);
+    }
+
+    assert_eq!(sh.value(0, 0).as_str_or(""), "name");
+    assert_eq!(sh.value(0, 2).as_str_or(""), "price");
+    assert_eq!(sh.col_cellstyle(0), None);
+    assert_eq!(sh.col_cellstyle(1), Some(&DefaultStyle::number()));
+    assert_eq!(
+        sh.col_cellstyle(2).map(|v| v.as_str()),
+        Some("schema_currency1")
+    );
+    assert_eq!(sh.col_width(1), cm!(3));
+    assert_eq!(sh.col_width(2), Length::Default);
+    assert_eq!(
+        wb.cellstyle("schema_currency1")
+            .and_then(|v| v.value_format()),
+        Some(DefaultFormat::currency().as_str())
+    );
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).col_width(1), cm!(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_editable_range() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold");
+    st.set_font_bold();
+    let bold = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_protected(true);
+    sh.set_value(0, 0, "input");
+    sh.set_styled_value(1, 1, 1, &bold);
+    sh.add_editable_range("input", CellRange::local(1, 0, 2, 1));
+    sh.add_editable_range("other", CellRange::local(5, 5, 5, 5));
+    sh.add_editable_range("input", CellRange::local(1, 1, 2, 1));
+    assert_eq!(sh.editable_ranges().len(), 2);
+    assert_eq!(
+        sh.remove_editable_range("other"),
+        Some(CellRange::local(5, 5, 5, 5))
+    );
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    // the written copy gets the styles, not the sheet.
+    assert_eq!(wb.sheet(0).cellstyle(1, 1), Some(&bold));
+    assert_eq!(wb.sheet(0).cellstyle(2, 1), None);
+    assert!(wb.cellstyle("bold_editable").is_none());
+
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert!(sh.protected());
+    assert_eq!(
+        sh.editable_ranges(),
+        &vec![(
+            "input".to_string(),
+            CellRange::remote("one", 1, 1, 2, 1).absolute()
+        )]
+    );
+    assert_eq!(sh.cellstyle(0, 0), None);
+    assert_eq!(
+        sh.cellstyle(1, 1).map(|v| v.as_str()),
+        Some("bold_editable")
+    );
+    assert_eq!(sh.cellstyle(2, 1).map(|v| v.as_str()), Some("editable"));
+    let st = wb.cellstyle("bold_editable").expect("style");
+    assert_eq!(st.cellstyle().attr("style:cell-protect"), Some("none"));
+    assert_eq!(st.textstyle().attr("fo:font-weight"), Some("bold"));
+
+    Ok(())
+}
+
+#[test]
+fn test_editable_range_columns() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold");
+    st.set_font_bold();
+    let bold = wb.add_cellstyle(st);
+    // not the copy of bold.
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold_editable");
+    wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("one");
+    sh.set_protected(true);
+    sh.set_value(0, 0, "header");
+    sh.set_styled_value(3, 1, 1, &bold);
+    sh.add_editable_range("column", CellRange::local(1, 1, u32::MAX, 2));
+    wb.push_sheet(sh);
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let fods = String::from_utf8(buf).expect("utf8");
+    // empty cells as repeated cells, the rest of the column as one row.
+    assert!(fods.contains(
+        r#"<table:table-cell table:number-columns-repeated="2" table:style-name="editable"/>"#
+    ));
+    assert!(fods.contains(r#"<table:table-row table:number-rows-repeated="1048572">"#));
+    assert!(fods.contains(r#"table:cell-range-address="$one.$B$2:.$C$1048576""#));
+
+    let wb = read_fods_buf(fods.as_bytes())?;
+    let sh = wb.sheet(0);
+    assert!(sh.iter().count() < 20);
+    assert_eq!(sh.cellstyle(0, 1), None);
+    assert_eq!(
+        sh.cellstyle(3, 1).map(|v| v.as_str()),
+        Some("bold_editable2")
+    );
+    assert_eq!(sh.cellstyle(3, 2).map(|v| v.as_str()), Some("editable"));
+    assert_eq!(sh.cellstyle(3, 3), None);
+
+    // an invalid range is skipped.
+    let fods = fods.replace("$one.$B$2:.$C$1048576", "one.x");
+    let wb = read_fods_buf(fods.as_bytes())?;
+    assert!(wb.sheet(0).editable_ranges().is_empty());
+    assert!(wb.warnings().iter().any(|v| matches!(
+        v,
+        OdsWarning::Recovered { context, .. } if context == "editable range column of one"
+    )));
+
+    Ok(())
+}
+
+#[test]
+fn test_fit_row_height() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("one");
+    sh.set_col_width(0, cm!(2));
+    sh.set_value(0, 0, "lorem ipsum dolor sit amet");
+    sh.set_value(0, 1, 12345);
+    sh.set_value(1, 0, "lorem ipsum dolor sit amet");
+    sh.set_col_span(1, 0, 2);
+    sh.set_value(2, 0, "lorem\nipsum\ndolor");
+
+    sh.fit_row_height(0, pt!(10));
+    sh.fit_row_height(1, pt!(10));
+    sh.fit_row_height(2, pt!(12));
+    sh.fit_row_height(3, pt!(10));
+
+    assert_eq!(sh.row_height(0), pt!(36.24));
+    assert_eq!(sh.row_height(1), pt!(24.54));
+    assert_eq!(sh.row_height(2), pt!(43.26));
+    assert_eq!(sh.row_height(3), pt!(12.84));
+
+    Ok(())
+}
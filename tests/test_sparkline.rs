@@ -0,0 +1,66 @@
+mod lib_test;
+
+use lib_test::*;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::sparkline::{Sparkline, SparklineGroup, SparklineType};
+use spreadsheet_ods::{read_ods_buf, CellRange, CellRef, OdsError, Sheet, WorkBook};
+
+#[test]
+fn test_sparkline() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("data");
+    for r in 0..3 {
+        for c in 0..5 {
+            sh.set_value(r, c, (r + 1) * c);
+        }
+    }
+
+    let group = sh.add_sparkline(0, 5, CellRange::local(0, 0, 0, 4), SparklineType::Column);
+    group.set_color_series(Rgb::new(0x37, 0x60, 0x92));
+    group.set_color_negative(Rgb::new(0x00, 0xb0, 0x50));
+
+    let mut group = SparklineGroup::new(SparklineType::Line);
+    group.set_id("{C4ACE3C8-9C3F-4E45-A4D7-8A4C4B2A3F5E}");
+    group.set_markers(true);
+    for r in 1..3 {
+        group.push_sparkline(Sparkline::new(
+            CellRef::remote("data", r, 5),
+            CellRange::remote("data", r, 0, r, 4),
+        ));
+    }
+    sh.add_sparkline_group(group);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    let groups = sh.sparkline_groups();
+    assert_eq!(groups.len(), 2);
+
+    assert_eq!(groups[0].sparkline_type(), SparklineType::Column);
+    let id = groups[0].id();
+    assert_eq!(id.len(), 38);
+    assert_eq!(&id[15..16], "4");
+    assert!(id.starts_with('{') && id.ends_with('}'));
+    assert_eq!(
+        groups[0].attrmap().attr("calcext:color-series"),
+        Some("#376092")
+    );
+    assert_eq!(groups[0].sparklines().len(), 1);
+    assert_eq!(
+        groups[0].sparklines()[0].cell(),
+        &CellRef::remote("data", 0, 5)
+    );
+    assert_eq!(
+        groups[0].sparklines()[0].data_range(),
+        &CellRange::remote("data", 0, 0, 0, 4)
+    );
+
+    assert_eq!(groups[1].sparkline_type(), SparklineType::Line);
+    assert_eq!(groups[1].id(), "{C4ACE3C8-9C3F-4E45-A4D7-8A4C4B2A3F5E}");
+    assert_eq!(groups[1].attrmap().attr("calcext:markers"), Some("true"));
+    assert_eq!(groups[1].sparklines().len(), 2);
+
+    Ok(())
+}
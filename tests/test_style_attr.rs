@@ -3,10 +3,12 @@ mod lib_test;
 use color::Rgb;
 use lib_test::*;
 use spreadsheet_ods::condition::Condition;
+use spreadsheet_ods::format::ValueFormatNumber;
+use spreadsheet_ods::style::presets;
 use spreadsheet_ods::style::stylemap::StyleMap;
 use spreadsheet_ods::style::units::{
     Angle, Border, CellAlignVertical, FontFamilyGeneric, FontPitch, FontWeight, Length, PageBreak,
-    ParaAlignVertical, RotationAlign, TextAlignSource, TextKeep, TextPosition, TextRelief,
+    ParaAlignVertical, RotationAlign, Side, TextAlignSource, TextKeep, TextPosition, TextRelief,
     TextTransform, WrapOption, WritingMode,
 };
 use spreadsheet_ods::style::{
@@ -270,3 +272,136 @@ fn test_stylemap() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_style_or_default() {
+    let mut wb = WorkBook::new_empty();
+
+    wb.cellstyle_or_default("bold").set_font_bold();
+    wb.cellstyle_or_default("bold").set_font_italic();
+    let bold = wb.cellstyle("bold").expect("cellstyle").style_ref();
+    let st = wb.cellstyle_mut(&bold).expect("cellstyle");
+    assert_eq!(st.name(), "bold");
+    assert_eq!(st.textstyle().attr("fo:font-weight"), Some("bold"));
+    assert_eq!(st.textstyle().attr("fo:font-style"), Some("italic"));
+    assert_eq!(wb.iter_cellstyles().count(), 1);
+
+    wb.pagestyle_or_default("ps1").set_page_width(cm!(21));
+    assert!(wb.pagestyle("ps1").is_some());
+    wb.masterpage_or_default("mp1");
+    assert!(wb.masterpage("mp1").is_some());
+
+    let mut v1 = ValueFormatNumber::new_named("v1");
+    v1.part_number().decimal_places(2).build();
+    let v1 = wb.add_number_format(v1);
+    assert!(wb.number_format(&v1).is_some());
+    assert!(wb.number_format_mut(&v1).is_some());
+}
+
+#[test]
+fn test_resolve_cellstyle() {
+    let mut wb = WorkBook::new_empty();
+
+    let mut def = CellStyle::new_empty();
+    def.set_styleuse(StyleUse::Default);
+    def.set_font_size(pt!(10));
+    def.set_color(Rgb::new(0, 0, 0));
+    wb.add_cellstyle(def);
+
+    let mut base = CellStyle::new("base", &"num1".into());
+    base.set_styleuse(StyleUse::Named);
+    base.set_font_bold();
+    base.set_color(Rgb::new(255, 0, 0));
+    let base = wb.add_cellstyle(base);
+
+    let mut st = CellStyle::new_empty();
+    st.set_parent_style(&base);
+    st.set_color(Rgb::new(0, 0, 255));
+    let st = wb.add_cellstyle(st);
+
+    let computed = wb.resolve_cellstyle(&st);
+    assert_eq!(computed.chain().len(), 3);
+    assert_eq!(computed.chain()[0], st);
+    assert_eq!(computed.chain()[1], base);
+    assert_eq!(computed.value_format(), Some("num1"));
+    assert_eq!(computed.textstyle().attr("fo:color"), Some("#0000ff"));
+    assert_eq!(computed.textstyle().attr("fo:font-weight"), Some("bold"));
+    assert_eq!(computed.textstyle().attr("fo:font-size"), Some("10pt"));
+
+    // cyclic parents end.
+    wb.cellstyle_mut(&base)
+        .expect("cellstyle")
+        .set_parent_style(&st);
+    assert_eq!(wb.resolve_cellstyle(&st).chain().len(), 3);
+
+    assert!(wb.resolve_cellstyle("missing").chain().len() <= 1);
+}
+
+#[test]
+fn test_border_side() -> Result<(), OdsError> {
+    let mut st = CellStyle::new_empty();
+    st.set_border_side(Side::Top, pt!(1), Border::Double, Rgb::new(255, 0, 0));
+    st.set_border_all(pt!(0.5), Border::Solid, Rgb::new(0, 0, 0));
+    assert_eq!(st.cellstyle().attr("fo:border-top"), None);
+    assert_eq!(
+        st.border_side(Side::Top)?,
+        Some((Length::Pt(0.5), Border::Solid, Rgb::new(0, 0, 0)))
+    );
+
+    st.set_border_side(Side::Bottom, mm!(1), Border::Dashed, Rgb::new(0, 0, 255));
+    assert_eq!(
+        st.border_side(Side::Bottom)?,
+        Some((Length::Mm(1.0), Border::Dashed, Rgb::new(0, 0, 255)))
+    );
+    assert_eq!(
+        st.border_all()?,
+        Some((Length::Pt(0.5), Border::Solid, Rgb::new(0, 0, 0)))
+    );
+
+    st.cellstyle_mut().set_attr("fo:border-left", "none");
+    assert_eq!(
+        st.border_side(Side::Left)?,
+        Some((Length::Default, Border::None, Rgb::new(0, 0, 0)))
+    );
+
+    st.cellstyle_mut()
+        .set_attr("fo:border-left", "0.74pt dash-dot #000000");
+    assert_eq!(
+        st.border_side(Side::Left)?,
+        Some((Length::Pt(0.74), Border::DashDot, Rgb::new(0, 0, 0)))
+    );
+    st.cellstyle_mut()
+        .set_attr("fo:border-left", "fine-dashed thick #ff0000");
+    assert_eq!(
+        st.border_side(Side::Left)?,
+        Some((Length::Pt(3.75), Border::FineDashed, Rgb::new(255, 0, 0)))
+    );
+    st.cellstyle_mut()
+        .set_attr("fo:border-left", "thin double-thin #000000");
+    assert_eq!(
+        st.border_side(Side::Left)?,
+        Some((Length::Pt(0.75), Border::DoubleThin, Rgb::new(0, 0, 0)))
+    );
+
+    let st = CellStyle::new_empty();
+    assert_eq!(st.border_side(Side::Right)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_presets() {
+    let st = presets::header("header");
+    assert_eq!(st.name(), "header");
+    assert_eq!(st.textstyle().attr("fo:font-weight"), Some("bold"));
+    assert_eq!(st.paragraphstyle().attr("fo:text-align"), Some("center"));
+
+    let st = presets::currency("amount", &"eur".into());
+    assert_eq!(st.value_format(), Some("eur"));
+    assert_eq!(st.paragraphstyle().attr("fo:text-align"), Some("end"));
+    assert_eq!(st.cellstyle().attr("style:text-align-source"), Some("fix"));
+
+    let st = presets::warning("");
+    assert!(st.name().is_empty());
+    assert_eq!(st.textstyle().attr("fo:color"), Some("#9c0006"));
+}
@@ -0,0 +1,159 @@
+mod lib_test;
+
+use lib_test::*;
+use spreadsheet_ods::style::{RowStyleRef, TextStyleRef};
+use spreadsheet_ods::template::{Template, TemplateValue};
+use spreadsheet_ods::text::{TextP, TextSpan};
+use spreadsheet_ods::{read_ods_buf, CellRange, CellStyleRef, OdsError, Sheet, Value, WorkBook};
+use std::collections::HashMap;
+
+fn template() -> Template {
+    let mut book = WorkBook::new_empty();
+    let mut sheet = Sheet::new("report");
+    sheet.set_value(
+        0,
+        0,
+        TextP::new()
+            .text("Report ")
+            .tag(
+                TextSpan::new()
+                    .style_name(&TextStyleRef::from("bold"))
+                    .text("{{ title }}"),
+            )
+            .into_xmltag(),
+    );
+    sheet.set_value(1, 0, "{{date}}");
+    sheet.set_value(3, 0, "{{rows.name}}");
+    sheet.set_styled_value(3, 1, "{{rows.amount}}", &CellStyleRef::from("amount"));
+    sheet.set_formula(3, 2, "of:=[.B4]*2");
+    sheet.set_rowstyle(3, &RowStyleRef::from("ro1"));
+    sheet.set_formula(4, 1, "of:=SUM([.B4:.B4])");
+    sheet.set_value(5, 0, "{{missing}}");
+    sheet.set_value(7, 0, "{{empty.name}} left");
+    book.push_sheet(sheet);
+    Template::new(book)
+}
+
+fn entry(name: &str, amount: f64) -> HashMap<String, Value> {
+    let mut entry = HashMap::new();
+    entry.insert("name".to_string(), name.into());
+    entry.insert("amount".to_string(), amount.into());
+    entry
+}
+
+#[test]
+fn test_template() -> Result<(), OdsError> {
+    let template = template();
+
+    let mut data = HashMap::new();
+    data.insert("title".to_string(), TemplateValue::from("Q1"));
+    data.insert("date".to_string(), TemplateValue::from(2024));
+    data.insert(
+        "rows".to_string(),
+        TemplateValue::List(vec![entry("a", 1.0), entry("b", 2.0), entry("c", 3.0)]),
+    );
+    data.insert("empty".to_string(), TemplateValue::List(Vec::new()));
+
+    let mut book = template.fill(&data)?;
+    let sheet = book.sheet(0);
+
+    assert_eq!(sheet.value(0, 0).as_cow_str_or(""), "Report Q1");
+    assert!(matches!(sheet.value(0, 0), Value::TextXml(_)));
+    assert_eq!(sheet.value(1, 0), &Value::Number(2024.0));
+    for (i, (name, amount)) in [("a", 1.0), ("b", 2.0), ("c", 3.0)].iter().enumerate() {
+        let row = 3 + i as u32;
+        assert_eq!(sheet.value(row, 0).as_str_or(""), *name);
+        assert_eq!(sheet.value(row, 1), &Value::Number(*amount));
+        assert_eq!(sheet.cellstyle(row, 1).map(|v| v.as_str()), Some("amount"));
+        assert_eq!(sheet.rowstyle(row).map(|v| v.as_str()), Some("ro1"));
+        assert_eq!(
+            sheet.formula(row, 2).map(|v| v.as_str()),
+            Some(format!("of:=[.B{}]*2", row + 1).as_str())
+        );
+    }
+    assert_eq!(
+        sheet.formula(6, 1).map(|v| v.as_str()),
+        Some("of:=SUM([.B4:.B6])")
+    );
+    assert!(sheet.rowstyle(6).is_none());
+    assert_eq!(sheet.value(7, 0).as_str_or(""), "{{missing}}");
+    assert_eq!(sheet.value(9, 0).as_str_or(""), " left");
+
+    // the template is unchanged.
+    assert_eq!(
+        template.workbook().sheet(0).value(1, 0).as_str_or(""),
+        "{{date}}"
+    );
+
+    let buf = test_write_odsbuf(&mut book)?;
+    let book = read_ods_buf(&buf)?;
+    assert_eq!(book.sheet(0).value(5, 1), &Value::Number(3.0));
+
+    Ok(())
+}
+
+#[test]
+fn test_template_refs() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+    let mut sheet = Sheet::new("report");
+    sheet.set_value(0, 0, "Name");
+    sheet.set_header_rows(0, 0);
+    sheet.set_value(1, 0, "{{rows.name}}");
+    sheet.set_value(1, 1, "{{rows.amount}}");
+    sheet.set_value(1, 2, "note");
+    sheet.set_row_span(1, 2, 2);
+    sheet.set_value(0, 3, "side");
+    sheet.set_row_span(0, 3, 3);
+    sheet.set_formula(3, 1, "of:=SUM([.B2:.B2])");
+    sheet.add_print_range(CellRange::remote("report", 0, 0, 3, 3));
+    sheet.group_rows(1..=3, false)?;
+    book.push_sheet(sheet);
+
+    let mut sheet = Sheet::new("summary");
+    sheet.set_formula(0, 0, "of:=[report.B4]+[.B4]");
+    book.push_sheet(sheet);
+
+    let mut data = HashMap::new();
+    data.insert(
+        "rows".to_string(),
+        TemplateValue::List(vec![entry("a", 1.0), entry("b", 2.0), entry("c", 3.0)]),
+    );
+    let book = Template::new(book).fill(&data)?;
+
+    let sheet = book.sheet(0);
+    assert_eq!(
+        sheet.formula(5, 1).map(|v| v.as_str()),
+        Some("of:=SUM([.B2:.B4])")
+    );
+    assert_eq!(sheet.header_rows().map(|v| (v.from, v.to)), Some((0, 0)));
+    let print = sheet.print_ranges().expect("print");
+    assert_eq!((print[0].row(), print[0].to_row()), (0, 5));
+    let group = sheet.row_group(0).expect("group");
+    assert_eq!((group.from, group.to), (1, 5));
+    assert_eq!(sheet.row_span(0, 3), 5);
+    assert_eq!(sheet.row_span(1, 2), 1);
+    assert_eq!(sheet.row_span(2, 2), 1);
+    assert_eq!(sheet.row_span(3, 2), 2);
+
+    assert_eq!(
+        book.sheet(1).formula(0, 0).map(|v| v.as_str()),
+        Some("of:=[report.B6]+[.B4]")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_template_two_lists() {
+    let mut book = WorkBook::new_empty();
+    let mut sheet = Sheet::new("report");
+    sheet.set_value(0, 0, "{{a.name}}");
+    sheet.set_value(0, 1, "{{b.name}}");
+    book.push_sheet(sheet);
+
+    let mut data = HashMap::new();
+    data.insert("a".to_string(), TemplateValue::List(vec![entry("x", 1.0)]));
+    data.insert("b".to_string(), TemplateValue::List(vec![entry("y", 1.0)]));
+
+    assert!(Template::new(book).fill(&data).is_err());
+}
@@ -1,4 +1,21 @@
-use spreadsheet_ods::{CellStyleRef, Sheet, ValueType, WorkBook};
+use chrono::NaiveDate;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::format::create_number_format;
+use spreadsheet_ods::sheet::sanitize_sheet_name;
+use spreadsheet_ods::style::units::Border;
+use spreadsheet_ods::style::{
+    AnyStyle, AnyStyleMut, ColStyle, FontFaceDecl, PageStyle, RowStyle, StyleUse, TextStyle, Theme,
+    ThemeColor,
+};
+use spreadsheet_ods::text::{TextP, TextSpan};
+use spreadsheet_ods::workbook::{
+    BasicLibrary, CalculationSettings, MergePolicy, SheetNamePolicy, StyleFamily,
+};
+use spreadsheet_ods::{
+    read_fods_buf, read_ods, read_ods_buf, write_fods_buf, write_ods_buf, CellRange, CellStyle,
+    CellStyleRef, Length, OdsError, OdsWarning, OdsWriteOptions, Sheet, Value, ValueType, WorkBook,
+};
+use std::io::Cursor;
 
 #[test]
 fn test_workbook() {
@@ -29,3 +46,765 @@ fn test_def_style() {
     );
     assert!(wb.def_style(ValueType::Text).is_none());
 }
+
+#[test]
+fn test_dedup_styles() {
+    let mut wb = WorkBook::new_empty();
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    st.set_color(Rgb::new(255, 0, 0));
+    let st0 = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_color(Rgb::new(255, 0, 0));
+    st.set_font_bold();
+    let st1 = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    let st2 = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    st.set_styleuse(StyleUse::Named);
+    let st3 = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 1, &st0);
+    sh.set_styled_value(0, 1, 2, &st1);
+    sh.set_styled_value(0, 2, 3, &st2);
+    sh.set_styled_value(0, 3, 4, &st3);
+    sh.set_col_cellstyle(1, &st1);
+    wb.push_sheet(sh);
+
+    wb.dedup_styles();
+
+    assert!(wb.cellstyle(&st0).is_some());
+    assert!(wb.cellstyle(&st1).is_none());
+    assert!(wb.cellstyle(&st2).is_some());
+    assert!(wb.cellstyle(&st3).is_some());
+    assert_eq!(wb.sheet(0).cellstyle(0, 1), Some(&st0));
+    assert_eq!(wb.sheet(0).cellstyle(0, 2), Some(&st2));
+    assert_eq!(wb.sheet(0).cellstyle(0, 3), Some(&st3));
+    assert_eq!(wb.sheet(0).col_cellstyle(1), Some(&st0));
+}
+
+fn merge_book(bold: bool) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+    let num = wb.add_number_format(create_number_format("num", if bold { 2 } else { 3 }, false));
+
+    let mut st = CellStyle::new("amount", &num);
+    if bold {
+        st.set_font_bold();
+    } else {
+        st.set_font_italic();
+    }
+    let amount = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("plain");
+    st.set_color(Rgb::new(0, 0, 255));
+    let plain = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("report");
+    sh.set_styled_value(0, 0, 1.5, &amount);
+    sh.set_styled_value(1, 0, "x", &plain);
+    wb.push_sheet(sh);
+    wb
+}
+
+#[test]
+fn test_merge() -> Result<(), OdsError> {
+    let mut wb = merge_book(true);
+    wb.merge(merge_book(false), MergePolicy::Rename)?;
+
+    assert_eq!(wb.num_sheets(), 2);
+    assert_eq!(wb.sheet(1).name(), "report (2)");
+    let amount = wb.sheet(1).cellstyle(0, 0).expect("style").clone();
+    assert_eq!(amount.as_str(), "amount_2");
+    let st = wb.cellstyle(&amount).expect("amount_2");
+    assert_eq!(st.value_format(), Some("num_2"));
+    assert!(wb.number_format("num_2").is_some());
+    assert_eq!(
+        wb.sheet(1).cellstyle(1, 0).map(|v| v.as_str()),
+        Some("plain")
+    );
+    assert_eq!(wb.iter_cellstyles().count(), 3);
+
+    let mut wb = merge_book(true);
+    wb.merge(merge_book(false), MergePolicy::KeepExisting)?;
+    assert_eq!(
+        wb.sheet(1).cellstyle(0, 0).map(|v| v.as_str()),
+        Some("amount")
+    );
+    assert_eq!(wb.iter_cellstyles().count(), 2);
+
+    let mut wb = merge_book(true);
+    assert!(wb.merge(merge_book(false), MergePolicy::Error).is_err());
+    assert_eq!(wb.num_sheets(), 1);
+    assert!(wb.number_format("num_2").is_none());
+    wb.merge(merge_book(true), MergePolicy::Error)?;
+    assert_eq!(wb.num_sheets(), 2);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.num_sheets(), 2);
+
+    Ok(())
+}
+
+fn merge_refs_book(family: &str) -> Result<WorkBook, OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut font = FontFaceDecl::new("Body");
+    font.set_font_family(family);
+    wb.add_font(font);
+    let mut st = TextStyle::new("emph");
+    st.set_font_name("Body");
+    let emph = wb.add_textstyle(st);
+
+    let mut sh = Sheet::new("report");
+    sh.set_value(
+        0,
+        0,
+        TextP::new()
+            .tag(TextSpan::new().style_name(&emph).text("x"))
+            .into_xmltag(),
+    );
+    sh.set_formula(1, 0, "of:=SUM([data.A1:.A3])+[.B1]");
+    sh.add_print_range(CellRange::remote("report", 0, 0, 5, 5));
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("data"));
+
+    // Workbook wide named expressions can only be read.
+    let xml = String::from_utf8(write_fods_buf(&mut wb, Vec::new())?).expect("utf8");
+    let xml = xml.replace(
+        "</office:spreadsheet>",
+        &format!(
+            "<table:named-expressions><table:named-range table:name=\"{}\" \
+             table:base-cell-address=\"$data.$A$1\" \
+             table:cell-range-address=\"$data.$A$1:.$A$3\"/></table:named-expressions>\
+             </office:spreadsheet>",
+            family
+        ),
+    );
+    read_fods_buf(xml.as_bytes())
+}
+
+#[test]
+fn test_merge_refs() -> Result<(), OdsError> {
+    let mut wb = merge_refs_book("Arial")?;
+    wb.merge(merge_refs_book("Courier")?, MergePolicy::Rename)?;
+
+    assert_eq!(wb.sheet(2).name(), "report (2)");
+    assert_eq!(wb.sheet(3).name(), "data (2)");
+    assert_eq!(
+        wb.sheet(2).formula(1, 0).map(|v| v.as_str()),
+        Some("of:=SUM(['data (2)'.A1:.A3])+[.B1]")
+    );
+    assert_eq!(
+        wb.sheet(2).print_ranges().expect("print")[0].table(),
+        Some(&"report (2)".to_string())
+    );
+
+    assert!(wb.font("Body_2").is_some());
+    let emph = wb.textstyle("emph_2").expect("emph_2");
+    assert_eq!(emph.textstyle().attr("style:font-name"), Some("Body_2"));
+    let Value::TextXml(text) = wb.sheet(2).value(0, 0) else {
+        panic!("text");
+    };
+    assert!(text[0].to_string().contains("text:style-name=\"emph_2\""));
+
+    let xml = String::from_utf8(write_fods_buf(&mut wb, Vec::new())?).expect("utf8");
+    assert!(xml.contains("table:cell-range-address=\"$data.$A$1:.$A$3\""));
+    assert!(xml.contains("table:cell-range-address=\"$&apos;data (2)&apos;.$A$1:.$A$3\""));
+
+    let mut wb = merge_refs_book("Arial")?;
+    let mut other = merge_refs_book("Arial")?;
+    other.detach_sheet(0);
+    assert!(wb.merge(other, MergePolicy::Rename).is_err());
+    assert_eq!(wb.num_sheets(), 2);
+
+    let mut wb = merge_refs_book("Arial")?;
+    assert!(wb
+        .merge(merge_refs_book("Arial")?, MergePolicy::Error)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_write() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 1, &CellStyleRef::from("missing"));
+    wb.push_sheet(sh);
+
+    let mut buf = Cursor::new(Vec::new());
+    let res = OdsWriteOptions::default()
+        .strict()
+        .write_ods(&mut wb, &mut buf);
+    let err = res.expect_err("dangling reference");
+    assert!(err.to_string().contains("missing"));
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("missing");
+    wb.add_cellstyle(st);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .strict()
+        .write_ods(&mut wb, &mut buf)
+        .expect("no dangling references");
+}
+
+#[test]
+fn test_replace_font() {
+    let mut wb = WorkBook::new_empty();
+    wb.add_font(FontFaceDecl::new("Arial"));
+    wb.add_font(FontFaceDecl::new("Courier"));
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_name("Arial");
+    wb.add_cellstyle(st);
+    let mut st = TextStyle::new("t1");
+    st.set_font_name("Courier");
+    st.set_font_name_asian("Arial");
+    wb.add_textstyle(st);
+
+    assert_eq!(wb.fonts_used(), vec!["Arial", "Courier"]);
+
+    wb.replace_font("Arial", "Liberation Sans");
+    assert_eq!(wb.fonts_used(), vec!["Courier", "Liberation Sans"]);
+    assert!(wb.font("Arial").is_none());
+    assert!(wb.font("Liberation Sans").is_some());
+
+    let st = wb.textstyle("t1").expect("style");
+    assert_eq!(
+        st.textstyle().attr("style:font-name-asian"),
+        Some("Liberation Sans")
+    );
+}
+
+#[test]
+fn test_freeze() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 42);
+    wb.push_sheet(sh);
+
+    let frozen = wb.freeze();
+    let handles = (0..4)
+        .map(|_| {
+            let frozen = frozen.clone();
+            std::thread::spawn(move || frozen.sheet(0).value(0, 0).as_i32_or(0))
+        })
+        .collect::<Vec<_>>();
+    for h in handles {
+        assert_eq!(h.join().expect("thread"), 42);
+    }
+
+    let mut wb = frozen.thaw();
+    wb.sheet_mut(0).set_value(0, 0, 43);
+    assert_eq!(wb.sheet(0).value(0, 0).as_i32_or(0), 43);
+}
+
+#[test]
+fn test_styles_iter() {
+    let mut wb = WorkBook::new_empty();
+    wb.add_cellstyle(CellStyle::new_empty());
+    wb.add_textstyle(TextStyle::new("t1"));
+    wb.add_pagestyle(PageStyle::new("page1"));
+
+    let mut names = wb
+        .styles()
+        .map(|v| match v {
+            AnyStyle::Cell(_) => format!("cell {}", v.name()),
+            AnyStyle::Text(_) => format!("text {}", v.name()),
+            AnyStyle::Page(_) => format!("page {}", v.name()),
+            _ => format!("other {}", v.name()),
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["cell ce0", "page page1", "text t1"]);
+
+    for style in wb.styles_mut() {
+        if let AnyStyleMut::Text(style) = style {
+            style.set_font_bold();
+        }
+    }
+    assert_eq!(
+        wb.textstyle("t1")
+            .expect("style")
+            .textstyle()
+            .attr("fo:font-weight"),
+        Some("bold")
+    );
+}
+
+#[test]
+fn test_extract_skeleton() {
+    let mut wb = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    let st = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("report");
+    sh.set_styled_value(0, 0, "Title", &st);
+    sh.set_value(1, 0, 42);
+    sh.set_formula(1, 1, "of:=[.A2]*2");
+    sh.set_col_width(0, Length::Cm(5.0));
+    wb.push_sheet(sh);
+
+    let skeleton = wb.extract_skeleton();
+    assert_eq!(skeleton.num_sheets(), 1);
+    assert!(skeleton.cellstyle(&st).is_some());
+
+    let sh = skeleton.sheet(0);
+    assert_eq!(sh.name(), "report");
+    assert_eq!(sh.cellstyle(0, 0), Some(&st));
+    assert_eq!(sh.value(0, 0), &Value::Empty);
+    assert_eq!(sh.cell_count(), 1);
+    assert_eq!(sh.col_width(0), Length::Cm(5.0));
+}
+
+#[test]
+fn test_consolidate_sheets() {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("jan");
+    sh.set_value(0, 0, "Report");
+    sh.set_value(1, 0, "Name");
+    sh.set_value(1, 1, "Amount");
+    sh.set_value(2, 0, "a");
+    sh.set_styled_value(2, 1, 1, &CellStyleRef::from("s0"));
+    sh.set_value(4, 0, "b");
+    sh.set_value(4, 1, 2);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new("feb");
+    sh.set_value(1, 0, "Amount");
+    sh.set_value(1, 1, "Region");
+    sh.set_value(1, 2, "Name");
+    sh.set_value(2, 0, 3);
+    sh.set_value(2, 1, "north");
+    sh.set_value(2, 2, "c");
+    wb.push_sheet(sh);
+
+    let sh = wb
+        .consolidate_sheets(&["jan", "feb"], 1)
+        .expect("consolidate");
+    assert_eq!(sh.value(0, 0).as_str_or(""), "sheet");
+    assert_eq!(sh.value(0, 1).as_str_or(""), "Name");
+    assert_eq!(sh.value(0, 2).as_str_or(""), "Amount");
+    assert_eq!(sh.value(0, 3).as_str_or(""), "Region");
+
+    assert_eq!(sh.value(1, 0).as_str_or(""), "jan");
+    assert_eq!(sh.value(1, 1).as_str_or(""), "a");
+    assert_eq!(sh.value(1, 2), &Value::Number(1.0));
+    assert_eq!(sh.cellstyle(1, 2), Some(&CellStyleRef::from("s0")));
+    assert_eq!(sh.value(2, 1).as_str_or(""), "b");
+    assert_eq!(sh.value(3, 0).as_str_or(""), "feb");
+    assert_eq!(sh.value(3, 1).as_str_or(""), "c");
+    assert_eq!(sh.value(3, 2), &Value::Number(3.0));
+    assert_eq!(sh.value(3, 3).as_str_or(""), "north");
+    assert_eq!(sh.used_grid_size(), (4, 4));
+
+    assert!(wb.consolidate_sheets(&["mar"], 0).is_err());
+}
+
+#[test]
+fn test_sheet_names() {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("One"));
+
+    assert!(wb
+        .push_sheet_checked(Sheet::new("One"), SheetNamePolicy::Error)
+        .is_err());
+    assert_eq!(wb.num_sheets(), 1);
+
+    wb.push_sheet_checked(Sheet::new("One"), SheetNamePolicy::Rename)
+        .unwrap();
+    wb.insert_sheet_checked(0, Sheet::new("One"), SheetNamePolicy::Rename)
+        .unwrap();
+    assert_eq!(wb.sheet(0).name(), "One (3)");
+    assert_eq!(wb.sheet(2).name(), "One (2)");
+
+    let mut sh = wb.detach_sheet(1);
+    sh.set_name("One (2)");
+    let sh = match wb.attach_sheet_checked(sh, SheetNamePolicy::Error) {
+        Ok(_) => panic!("duplicate name"),
+        Err((sh, _)) => sh,
+    };
+    wb.attach_sheet_checked(sh, SheetNamePolicy::Rename)
+        .unwrap();
+    assert_eq!(wb.sheet(1).name(), "One (2) (2)");
+
+    // Detached sheets keep their name.
+    let sh = wb.detach_sheet(0);
+    assert!(wb
+        .push_sheet_checked(Sheet::new("One (3)"), SheetNamePolicy::Error)
+        .is_err());
+    assert_eq!(wb.unique_sheet_name("One (3)"), "One (3) (2)");
+    wb.attach_sheet(sh);
+
+    // push_sheet() only warns, strict writing fails.
+    assert!(wb.warnings().is_empty());
+    wb.push_sheet(Sheet::new("One (3)"));
+    assert_eq!(wb.sheet(3).name(), "One (3)");
+    assert_eq!(
+        wb.warnings(),
+        &[OdsWarning::Recovered {
+            context: "sheet One (3)".to_string(),
+            message: "Duplicate sheet name".to_string(),
+        }]
+    );
+    assert!(OdsWriteOptions::default()
+        .strict()
+        .write_ods(&mut wb, Cursor::new(Vec::new()))
+        .is_err());
+
+    assert!(Sheet::try_new("One: [draft]").is_err());
+    assert!(Sheet::try_new("").is_err());
+    assert!(Sheet::try_new("a/b").is_err());
+    assert!(Sheet::try_new("a\\b").is_err());
+    assert!(Sheet::try_new("'quoted").is_err());
+    assert!(Sheet::try_new("quoted'").is_err());
+    assert!(Sheet::try_new("Tom's").is_ok());
+    let sh = Sheet::try_new(sanitize_sheet_name("One: [draft]")).unwrap();
+    assert_eq!(sh.name(), "One_ _draft_");
+    assert_eq!(sanitize_sheet_name("'a/b\\c'"), "_a_b_c_");
+
+    // as read from a file.
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("A"));
+    wb.push_sheet(Sheet::new("B"));
+    wb.push_sheet(Sheet::new("A (2)"));
+    let mut sh = wb.detach_sheet(1);
+    sh.set_name("A");
+    wb.attach_sheet(sh);
+    assert_eq!(wb.ensure_unique_sheet_names(), 1);
+    assert_eq!(wb.sheet(0).name(), "A");
+    assert_eq!(wb.sheet(1).name(), "A (3)");
+    assert_eq!(wb.sheet(2).name(), "A (2)");
+    assert_eq!(wb.ensure_unique_sheet_names(), 0);
+
+    // references within the renamed sheet follow it.
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("dup"));
+    wb.push_sheet(Sheet::new("other"));
+    let mut sh = wb.detach_sheet(1);
+    sh.set_name("dup");
+    sh.set_formula(0, 1, "of:=[dup.A1]+[.A2]");
+    sh.add_print_range(CellRange::remote("dup", 0, 0, 4, 4));
+    wb.attach_sheet(sh);
+    wb.push_sheet(Sheet::new("last"));
+    wb.sheet_mut(2).set_formula(0, 0, "of:=[dup.A1]");
+    assert_eq!(wb.ensure_unique_sheet_names(), 1);
+    assert_eq!(wb.sheet(1).name(), "dup (2)");
+    assert_eq!(
+        wb.sheet(1).formula(0, 1).unwrap(),
+        "of:=['dup (2)'.A1]+[.A2]"
+    );
+    assert_eq!(
+        wb.sheet(1).print_ranges().unwrap()[0],
+        CellRange::remote("dup (2)", 0, 0, 4, 4)
+    );
+    assert_eq!(wb.sheet(2).formula(0, 0).unwrap(), "of:=[dup.A1]");
+}
+
+#[test]
+fn test_style_ref_hash() {
+    use std::collections::HashSet;
+
+    let mut refs = HashSet::new();
+    refs.insert(CellStyleRef::from("ce1"));
+    refs.insert(CellStyleRef::from("ce1".to_string()));
+    assert_eq!(refs.len(), 1);
+    assert!(refs.contains("ce1"));
+    assert_eq!(CellStyleRef::from("ce1").as_str(), "ce1");
+}
+
+#[test]
+fn test_calculation_settings() -> Result<(), OdsError> {
+    let wb = read_ods("tests/test_config.ods")?;
+    let settings = wb.calculation_settings();
+    assert!(!settings.automatic_find_labels);
+    assert!(!settings.use_regular_expressions);
+    assert!(settings.use_wildcards);
+    assert!(!settings.iteration);
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let settings = wb.calculation_settings_mut();
+    settings.case_sensitive = false;
+    settings.precision_as_shown = true;
+    settings.null_year = 1950;
+    settings.null_date = NaiveDate::from_ymd_opt(1904, 1, 1).expect("date");
+    settings.iteration = true;
+    settings.iteration_steps = 500;
+    settings.iteration_minimum_difference = 0.0001;
+    let settings = settings.clone();
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.calculation_settings(), &settings);
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.calculation_settings(), &CalculationSettings::default());
+
+    // after the tracked changes, before the tables.
+    let mut wb = read_ods("tests/test_changes.ods")?;
+    wb.calculation_settings_mut().null_year = 1950;
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let xml = String::from_utf8(buf).expect("utf8");
+    let changes = xml.find("<table:tracked-changes").expect("changes");
+    let settings = xml.find("<table:calculation-settings").expect("settings");
+    let table = xml.find("<table:table ").expect("table");
+    assert!(changes < settings && settings < table);
+
+    Ok(())
+}
+
+#[test]
+fn test_basic_library() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+
+    let mut lib = BasicLibrary::new("Dashboard");
+    lib.add_module(
+        "Refresh",
+        "Sub Main\n  ThisComponent.calculateAll()\n  If 1 < 2 Then Beep\nEnd Sub\n",
+    );
+    wb.add_basic_library(lib);
+    wb.set_on_load_macro("Dashboard.Refresh.Main");
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    assert_eq!(wb.basic_libraries(), vec!["Dashboard".to_string()]);
+    let module = wb
+        .manifest("Basic/Dashboard/Refresh.xml")
+        .and_then(|v| v.buffer.as_ref())
+        .expect("module");
+    let module = String::from_utf8_lossy(module);
+    assert!(module.contains("script:name=\"Refresh\""));
+    assert!(module.contains("If 1 &lt; 2 Then Beep"));
+    let lc = wb
+        .manifest("Basic/script-lc.xml")
+        .and_then(|v| v.buffer.as_ref())
+        .expect("container");
+    assert!(String::from_utf8_lossy(lc).contains("library:name=\"Dashboard\""));
+
+    let evt = wb.event_listener("dom:load").expect("dom:load");
+    assert_eq!(evt.script_lang(), "ooo:script");
+    assert_eq!(
+        evt.href(),
+        "vnd.sun.star.script:Dashboard.Refresh.Main?language=Basic&location=document"
+    );
+
+    wb.remove_basic_library("Dashboard");
+    assert!(wb.basic_libraries().is_empty());
+    assert!(wb.manifest("Basic/script-lc.xml").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_thumbnail() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/test_config.ods")?;
+    let png = wb.thumbnail().expect("thumbnail").to_vec();
+    assert!(png.starts_with(b"\x89PNG"));
+    assert_eq!(wb.clear_thumbnail(), Some(png.clone()));
+    assert!(wb.thumbnail().is_none());
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    wb.set_thumbnail(png.clone());
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.thumbnail(), Some(png.as_slice()));
+    assert_eq!(
+        wb.manifest("Thumbnails/thumbnail.png")
+            .map(|v| v.media_type.as_str()),
+        Some("image/png")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_attachments() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    wb.add_attachment("LICENSE.txt", "text/plain", b"MIT".to_vec())?;
+    wb.add_attachment(
+        "./sidecar/info.json",
+        "application/json",
+        br#"{"rev":1}"#.to_vec(),
+    )?;
+    assert!(wb
+        .add_attachment("content.xml", "text/xml", Vec::new())
+        .is_err());
+    assert!(wb.add_attachment("sidecar/", "", Vec::new()).is_err());
+    for path in [
+        "../evil.txt",
+        "sidecar/../../evil.txt",
+        "/etc/passwd",
+        "C:/evil.txt",
+        "META-INF/signatures.xml",
+    ] {
+        assert!(wb.add_attachment(path, "", Vec::new()).is_err(), "{}", path);
+    }
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let mut paths: Vec<&str> = wb
+        .iter_attachments()
+        .map(|v| v.full_path.as_str())
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec!["LICENSE.txt", "sidecar/info.json"]);
+    assert_eq!(wb.attachment("LICENSE.txt"), Some(b"MIT".as_slice()));
+    assert_eq!(wb.attachment("./LICENSE.txt"), Some(b"MIT".as_slice()));
+    assert_eq!(
+        wb.manifest("sidecar/info.json")
+            .map(|v| v.media_type.as_str()),
+        Some("application/json")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_embed_font() -> Result<(), OdsError> {
+    let ttf = b"\x00\x01\x00\x00fake font".to_vec();
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    wb.add_font(FontFaceDecl::new("Arial"));
+    let path = wb.embed_font("Corporate Sans", ttf.clone());
+    assert_eq!(path, "Fonts/Corporate_Sans.ttf");
+    // again replaces the file.
+    wb.embed_font("Corporate Sans", ttf.clone());
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let fonts: Vec<_> = wb.iter_embedded_fonts().collect();
+    assert_eq!(fonts.len(), 1);
+    let (font, uri, data) = fonts[0];
+    assert_eq!(font.name(), "Corporate Sans");
+    assert_eq!(
+        font.attrmap().attr("svg:font-family"),
+        Some("'Corporate Sans'")
+    );
+    assert_eq!(uri.href(), Some("Fonts/Corporate_Sans.ttf"));
+    assert_eq!(uri.format(), Some("truetype"));
+    assert_eq!(data, ttf.as_slice());
+    assert!(wb.font("Arial").expect("arial").src().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_theme() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+
+    let mut theme = Theme::new("Corporate");
+    theme.set_color(ThemeColor::Dark1, Rgb::new(0, 0, 0));
+    theme.set_color(ThemeColor::Accent1, Rgb::new(0, 84, 159));
+    assert!(wb.set_theme(theme).is_none());
+
+    let mut st = CellStyle::new_empty();
+    st.set_color(wb.theme_color(ThemeColor::Accent1).expect("accent1"));
+    st.set_border(Length::Pt(1.0), Border::Solid, Rgb::new(0, 84, 159));
+    st.set_background_color(Rgb::new(255, 255, 255));
+    let st = wb.add_cellstyle(st);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let theme = wb.theme().expect("theme");
+    assert_eq!(theme.name(), "Corporate");
+    assert_eq!(theme.color(ThemeColor::Accent1), Some(Rgb::new(0, 84, 159)));
+    assert_eq!(theme.color(ThemeColor::Dark1), Some(Rgb::new(0, 0, 0)));
+
+    let mut theme = Theme::new("Rebrand");
+    theme.set_color(ThemeColor::Dark1, Rgb::new(0, 0, 0));
+    theme.set_color(ThemeColor::Accent1, Rgb::new(200, 16, 46));
+    let old = wb.set_theme(theme).expect("old theme");
+    assert_eq!(old.name(), "Corporate");
+
+    assert_eq!(
+        wb.theme_color(ThemeColor::Accent1),
+        Some(Rgb::new(200, 16, 46))
+    );
+
+    // Styles keep their colors.
+    let st = wb.cellstyle(st).expect("style");
+    assert_eq!(st.textstyle().attr("fo:color"), Some("#00549f"));
+    assert_eq!(st.cellstyle().attr("fo:border"), Some("1pt solid #00549f"));
+    assert_eq!(st.cellstyle().attr("fo:background-color"), Some("#ffffff"));
+
+    Ok(())
+}
+
+#[test]
+fn test_style_usage() {
+    let mut wb = WorkBook::new_empty();
+
+    let num = wb.add_number_format(create_number_format("num2", 2, false));
+    let dead_num = wb.add_number_format(create_number_format("dead_num", 3, false));
+
+    let mut base = CellStyle::new_empty();
+    base.set_name("base");
+    base.set_styleuse(StyleUse::Named);
+    let base = wb.add_cellstyle(base);
+    let mut amount = CellStyle::new("amount", &num);
+    amount.set_parent_style(&base);
+    let amount = wb.add_cellstyle(amount);
+    wb.add_cellstyle(CellStyle::new("dead", &dead_num));
+    let mut keep = CellStyle::new_empty();
+    keep.set_name("keep");
+    keep.set_styleuse(StyleUse::Named);
+    wb.add_cellstyle(keep);
+
+    let ro = wb.add_rowstyle(RowStyle::new("ro1"));
+    wb.add_colstyle(ColStyle::new("co1"));
+
+    let mut sh = Sheet::new("one");
+    sh.set_styled_value(0, 0, 1.5, &amount);
+    sh.set_styled_value(1, 0, 2.5, &amount);
+    sh.set_row_cellstyle(5, &amount);
+    sh.set_rowstyle(0, &ro);
+    sh.set_rowstyle(1, &ro);
+    wb.push_sheet(sh);
+
+    let usage = wb.style_usage();
+    let u = usage[&(StyleFamily::Cell, "amount".to_string())];
+    assert_eq!((u.cells, u.rows, u.cols, u.styles), (2, 1, 0, 0));
+    let u = usage[&(StyleFamily::Cell, "base".to_string())];
+    assert_eq!((u.cells, u.styles), (0, 1));
+    let u = usage[&(StyleFamily::ValueFormat, "num2".to_string())];
+    assert_eq!((u.cells, u.rows, u.styles), (2, 1, 1));
+    assert_eq!(usage[&(StyleFamily::Row, "ro1".to_string())].rows, 2);
+    assert!(usage[&(StyleFamily::Col, "co1".to_string())].is_unused());
+    assert!(usage[&(StyleFamily::Cell, "dead".to_string())].is_unused());
+    assert!(!usage[&(StyleFamily::ValueFormat, "dead_num".to_string())].is_unused());
+
+    // dead, co1 and then dead_num.
+    assert_eq!(wb.remove_unused_styles(), 3);
+    assert!(wb.cellstyle("dead").is_none());
+    assert!(wb.colstyle("co1").is_none());
+    assert!(wb.number_format("dead_num").is_none());
+    assert!(wb.cellstyle("keep").is_some());
+    assert!(wb.cellstyle("amount").is_some());
+    assert!(wb.number_format("num2").is_some());
+    assert_eq!(wb.remove_unused_styles(), 0);
+}
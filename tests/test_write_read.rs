@@ -1,10 +1,13 @@
 pub mod lib_test;
 
+use chrono::{DateTime, NaiveDate};
 use lib_test::*;
 use spreadsheet_ods::sheet::SplitMode;
 use spreadsheet_ods::{
-    read_ods, read_ods_buf, write_ods_buf, write_ods_to, OdsError, Sheet, ValueType, WorkBook,
+    read_ods, read_ods_buf, write_ods_buf, write_ods_to, OdsError, OdsOptions, OdsWarning,
+    OdsWriteOptions, Sheet, TimezonePolicy, Value, ValueType, WorkBook,
 };
+use std::error::Error;
 use std::fs::File;
 use std::io::{Cursor, Read, Write};
 use std::path::Path;
@@ -126,9 +129,7 @@ fn test_write_buf() -> Result<(), OdsError> {
         v.len()
     };
 
-    // This can sporadically fail because of some instability in hashtable.
-    // Writing syncs a few structures and then *something* happens.
-    // A different ordering of some attributes occurs and this diff fails.
+    // The output is stable, the namespaces and styles are sorted.
     assert_eq!(len_1, len_2);
 
     Ok(())
@@ -197,3 +198,464 @@ fn read_text() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn write_options() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "A");
+    wb.push_sheet(sh);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .settings(false)
+        .line_break(true)
+        .version("1.2")
+        .generator("test-generator")
+        .write_ods(&mut wb, &mut buf)?;
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(buf.into_inner()))?;
+    assert!(zip.by_name("settings.xml").is_err());
+    let mut meta = String::new();
+    zip.by_name("meta.xml")?.read_to_string(&mut meta)?;
+    assert!(meta.contains("test-generator"));
+    assert!(meta.contains("office:version=\"1.2\""));
+    assert!(meta.lines().count() > 3);
+
+    Ok(())
+}
+
+#[test]
+fn write_indent() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "A");
+    sh.set_value(1, 0, 42);
+    wb.push_sheet(sh);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .indent(true)
+        .write_ods(&mut wb, &mut buf)?;
+    let buf = buf.into_inner();
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(buf.clone()))?;
+    for name in ["content.xml", "styles.xml", "meta.xml", "settings.xml"] {
+        let mut xml = String::new();
+        zip.by_name(name)?.read_to_string(&mut xml)?;
+        assert!(xml.contains("\n  <"), "{} is not indented", name);
+    }
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+    assert!(content.contains("\n  <office:body>\n    <office:spreadsheet>"));
+    assert!(content.contains("<text:p>A</text:p>"));
+
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::Text("A".into()));
+    assert_eq!(wb.sheet(0).value(1, 0), &Value::Number(42.0));
+
+    Ok(())
+}
+
+#[test]
+fn write_keep_metadata() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "A");
+    wb.push_sheet(sh);
+    wb.metadata_mut().generator = "my-app 1.0".to_string();
+    wb.metadata_mut().document_statistics.cell_count = 99;
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .update_metadata(false)
+        .write_ods(&mut wb, &mut buf)?;
+    let wb2 = read_ods_buf(buf.get_ref())?;
+    assert_eq!(wb2.metadata().generator, "my-app 1.0");
+    assert_eq!(wb2.metadata().document_statistics.cell_count, 99);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default().write_ods(&mut wb, &mut buf)?;
+    let wb2 = read_ods_buf(buf.get_ref())?;
+    assert!(wb2.metadata().generator.starts_with("spreadsheet-ods"));
+    assert_eq!(wb2.metadata().document_statistics.cell_count, 1);
+
+    Ok(())
+}
+
+#[test]
+fn read_only_sheets() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    for name in ["Data", "Other", "Prices"] {
+        let mut sh = Sheet::new(name);
+        sh.set_value(0, 0, name);
+        wb.push_sheet(sh);
+    }
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let wb = OdsOptions::default()
+        .only_sheets(&["Data", "Prices"])
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.num_sheets(), 2);
+    assert_eq!(wb.sheet(0).name(), "Data");
+    assert_eq!(wb.sheet(1).name(), "Prices");
+    assert_eq!(wb.sheet(1).value(0, 0).as_str_or(""), "Prices");
+
+    let wb = OdsOptions::default()
+        .only_sheet_indices(&[1])
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.num_sheets(), 1);
+    assert_eq!(wb.sheet(0).name(), "Other");
+
+    Ok(())
+}
+
+// Rewrites the zip with modified entries.
+fn patch_ods(buf: Vec<u8>, patch: impl Fn(&str, String) -> String) -> Result<Vec<u8>, OdsError> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(buf))?;
+    let mut patched = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let name = entry.name().to_string();
+        if entry.is_dir() {
+            patched.add_directory(name, zip::write::SimpleFileOptions::default())?;
+            continue;
+        }
+        let mut data = String::new();
+        entry.read_to_string(&mut data)?;
+        let data = patch(name.as_str(), data);
+        patched.start_file(name, zip::write::SimpleFileOptions::default())?;
+        patched.write_all(data.as_bytes())?;
+    }
+    Ok(patched.finish()?.into_inner())
+}
+
+#[test]
+fn read_lenient() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 42);
+    sh.set_value(0, 1, "ok");
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    // break settings.xml and one cell value.
+    let buf = patch_ods(buf, |name, data| match name {
+        "settings.xml" => data.replacen("config:type=\"int\">", "config:type=\"int\">x", 1),
+        "content.xml" => data.replace("office:value=\"42\"", "office:value=\"x42\""),
+        _ => data,
+    })?;
+
+    assert!(read_ods_buf(&buf).is_err());
+
+    let wb = OdsOptions::default()
+        .lenient()
+        .read_ods(Cursor::new(&buf))?;
+    let mut recovered: Vec<_> = wb
+        .warnings()
+        .iter()
+        .filter_map(|v| match v {
+            OdsWarning::Recovered { context, .. } => Some(context.as_str()),
+            _ => None,
+        })
+        .collect();
+    recovered.sort();
+    assert_eq!(recovered, vec!["one.A1", "settings.xml"]);
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "42");
+    assert_eq!(wb.sheet(0).value(0, 1).as_str_or(""), "ok");
+
+    Ok(())
+}
+
+#[test]
+fn read_error_location() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "ok");
+    sh.set_value(1, 2, 42);
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let buf = patch_ods(buf, |name, data| match name {
+        "content.xml" => data.replace("office:value=\"42\"", "office:value=\"x42\""),
+        _ => data,
+    })?;
+
+    let err = read_ods_buf(&buf).unwrap_err();
+    match &err {
+        OdsError::Located {
+            part,
+            offset,
+            sheet,
+            row,
+            col,
+            ..
+        } => {
+            assert_eq!(part, "content.xml");
+            assert!(*offset > 0);
+            assert_eq!(sheet.as_deref(), Some("one"));
+            assert_eq!(*row, Some(1));
+            assert_eq!(*col, Some(2));
+        }
+        _ => panic!("no location {}", err),
+    }
+    assert!(matches!(
+        err.innermost(),
+        OdsError::Parse(_, Some(v)) if v == "x42"
+    ));
+    assert!(err.source().is_some());
+    assert!(err.to_string().starts_with("content.xml at byte"));
+
+    Ok(())
+}
+
+#[test]
+fn read_malformed() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_value(0, 1, 2);
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let broken = patch_ods(buf.clone(), |name, data| match name {
+        "content.xml" => data.replacen(
+            "<table:table-row",
+            "<table:table-row table:number-rows-repeated=\"0\"",
+            1,
+        ),
+        _ => data,
+    })?;
+    assert!(read_ods_buf(&broken).is_err());
+
+    let broken = patch_ods(buf.clone(), |name, data| match name {
+        "content.xml" => data.replacen(
+            "<table:table-cell",
+            "<table:table-cell table:number-columns-repeated=\"4294967295\"",
+            1,
+        ),
+        _ => data,
+    })?;
+    assert!(read_ods_buf(&broken).is_err());
+
+    let broken = patch_ods(buf, |name, data| match name {
+        "content.xml" => data.replacen(
+            "<table:table-row",
+            "<table:table-header-rows></table:table-header-rows><table:table-row",
+            1,
+        ),
+        _ => data,
+    })?;
+    let wb = read_ods_buf(&broken)?;
+    assert!(wb.sheet(0).header_rows().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn read_limits() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_row_repeat(0, 100);
+    sh.set_value(200, 0, "text");
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let is_limit = |res: Result<WorkBook, OdsError>| {
+        matches!(
+            res.map_err(|e| e.innermost().to_string()),
+            Err(e) if e.starts_with("Limit exceeded")
+        )
+    };
+
+    assert!(OdsOptions::default()
+        .max_rows(300)
+        .max_repeat_expansion(100)
+        .max_xml_depth(5)
+        .max_inflate_ratio(1000)
+        .read_ods(Cursor::new(&buf))
+        .is_ok());
+
+    assert!(is_limit(
+        OdsOptions::default()
+            .max_rows(200)
+            .read_ods(Cursor::new(&buf))
+    ));
+    assert!(is_limit(
+        OdsOptions::default()
+            .max_repeat_expansion(10)
+            .read_ods(Cursor::new(&buf))
+    ));
+
+    // The repeated rows count, not only the first one.
+    let mut wb_repeat = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_row_repeat(0, 100);
+    wb_repeat.push_sheet(sh);
+    let buf_repeat = write_ods_buf(&mut wb_repeat, Vec::new())?;
+    assert!(is_limit(
+        OdsOptions::default()
+            .max_rows(50)
+            .read_ods(Cursor::new(&buf_repeat))
+    ));
+    assert!(OdsOptions::default()
+        .use_repeat_for_cells()
+        .max_repeat_expansion(10)
+        .read_ods(Cursor::new(&buf))
+        .is_ok());
+    assert!(is_limit(
+        OdsOptions::default()
+            .max_inflate_ratio(1)
+            .read_ods(Cursor::new(&buf))
+    ));
+
+    let nested = patch_ods(buf, |name, data| match name {
+        "content.xml" => data.replacen(
+            "<text:p>text</text:p>",
+            &format!(
+                "<text:p>{}text{}</text:p>",
+                "<text:span>".repeat(10),
+                "</text:span>".repeat(10)
+            ),
+            1,
+        ),
+        _ => data,
+    })?;
+    assert!(read_ods_buf(&nested).is_ok());
+    assert!(is_limit(
+        OdsOptions::default()
+            .max_xml_depth(5)
+            .read_ods(Cursor::new(&nested))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn read_report_unused() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("one"));
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let buf = patch_ods(buf, |name, data| match name {
+        "content.xml" => data.replace("table:name=\"one\"", "table:name=\"one\" x:unknown=\"1\""),
+        _ => data,
+    })?;
+
+    let wb = OdsOptions::default()
+        .report_unused()
+        .read_ods(Cursor::new(&buf))?;
+    assert!(wb.warnings().contains(&OdsWarning::UnusedAttr {
+        context: "read_table_attr".to_string(),
+        tag: "table:table".to_string(),
+        attr: "x:unknown".to_string(),
+        value: "1".to_string(),
+    }));
+
+    Ok(())
+}
+
+#[test]
+fn read_preserve_unknown() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, 1);
+    sh.set_value(2, 0, 3);
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let buf = patch_ods(buf, |name, data| match name {
+        "content.xml" => {
+            let data = data.replacen("<table:table-column", "<x:prelude/><table:table-column", 1);
+            let (second_row, _) = data
+                .match_indices("<table:table-row")
+                .nth(1)
+                .expect("second row");
+            let data = format!("{}<x:between/>{}", &data[..second_row], &data[second_row..]);
+            data.replace(
+                "</office:automatic-styles>",
+                "<style:style style:name=\"ch1\" style:family=\"chart\">\
+                <style:chart-properties/></style:style></office:automatic-styles>",
+            )
+            .replace("</table:table>", "<x:in-table/></table:table>")
+            .replace(
+                "</office:spreadsheet>",
+                "<x:in-body x:a=\"1\"><x:child/></x:in-body></office:spreadsheet>",
+            )
+        }
+        _ => data,
+    })?;
+
+    assert!(read_ods_buf(&buf).is_err());
+
+    let mut wb = OdsOptions::default()
+        .preserve_unknown()
+        .read_ods(Cursor::new(&buf))?;
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(buf))?;
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+    assert!(content.contains("style:name=\"ch1\""));
+    assert!(content.contains("<style:chart-properties"));
+    assert!(content.contains("<x:in-table"));
+    assert!(content.contains("<x:in-body x:a=\"1\">"));
+    assert!(content.contains("<x:child"));
+
+    // the tags keep their position in the table.
+    let pos = |pat: &str| content.find(pat).expect(pat);
+    assert!(pos("<x:prelude") < pos("<table:table-column"));
+    let first_row_end = pos("</table:table-row>");
+    assert!(first_row_end < pos("<x:between"));
+    assert!(
+        pos("<x:between")
+            < content[first_row_end..].find("<table:table-row").unwrap() + first_row_end
+    );
+    assert!(content.rfind("</table:table-row>").expect("row") < pos("<x:in-table"));
+
+    Ok(())
+}
+
+#[test]
+fn read_write_timezone() -> Result<(), OdsError> {
+    let dt = DateTime::parse_from_rfc3339("2024-03-01T12:00:00+02:00")?;
+    let local = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .and_then(|v| v.and_hms_opt(12, 0, 0))
+        .expect("datetime");
+    let utc = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .and_then(|v| v.and_hms_opt(10, 0, 0))
+        .expect("datetime");
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, dt);
+    sh.set_value(1, 0, local);
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::DateTime(local));
+
+    let wb = OdsOptions::default()
+        .timezone(TimezonePolicy::Utc)
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::DateTime(utc));
+    assert_eq!(wb.sheet(0).value(1, 0), &Value::DateTime(local));
+
+    let mut wb = OdsOptions::default()
+        .timezone(TimezonePolicy::Keep)
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::DateTimeTz(dt));
+    assert_eq!(wb.sheet(0).value(1, 0), &Value::DateTime(local));
+    assert_eq!(wb.sheet(0).value(0, 0).as_datetime_opt(), Some(local));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = OdsOptions::default()
+        .timezone(TimezonePolicy::Keep)
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::DateTimeTz(dt));
+
+    Ok(())
+}